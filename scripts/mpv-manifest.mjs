@@ -0,0 +1,47 @@
+// Build the bundled MPV manifest published with each release.
+//
+// `packaging/mpv/sources.json` pins one zip archive per platform:
+//   { "builds": [{ "target": "windows-x86_64", "version": "0.40.0",
+//                  "url": "https://…/mpv.zip", "executable": "mpv.exe" }] }
+// `target` is `<os>-<arch>` as in Rust's `std::env::consts`. Each archive is
+// downloaded and its SHA-256 recorded; the release workflow signs the result,
+// and JellyPilot only installs builds from a manifest whose signature checks
+// out against the key compiled into it.
+import { createHash } from 'node:crypto';
+import { mkdir, readFile, writeFile } from 'node:fs/promises';
+import { dirname } from 'node:path';
+
+const SOURCES_PATH = 'packaging/mpv/sources.json';
+const ZIP_MAGIC = [0x50, 0x4b, 0x03, 0x04];
+
+const outputPath = process.argv[2];
+if (!outputPath) {
+  console.error('Usage: bun scripts/mpv-manifest.mjs <output.json>');
+  process.exit(1);
+}
+
+const sources = JSON.parse(await readFile(SOURCES_PATH, 'utf8'));
+const builds = [];
+for (const source of sources.builds) {
+  for (const field of ['target', 'version', 'url', 'executable']) {
+    if (typeof source[field] !== 'string' || source[field] === '') {
+      throw new Error(`${SOURCES_PATH}: build is missing ${field}`);
+    }
+  }
+  const response = await fetch(source.url, {
+    headers: { 'User-Agent': 'jellypilot-mpv-manifest' },
+  });
+  if (!response.ok) {
+    throw new Error(`Failed to download ${source.url}: ${response.status}`);
+  }
+  const archive = new Uint8Array(await response.arrayBuffer());
+  if (!ZIP_MAGIC.every((byte, index) => archive[index] === byte)) {
+    throw new Error(`${source.url} is not a zip archive`);
+  }
+  const sha256 = createHash('sha256').update(archive).digest('hex');
+  console.log(`${source.target}: ${source.version} ${sha256}`);
+  builds.push({ ...source, sha256 });
+}
+
+await mkdir(dirname(outputPath), { recursive: true });
+await writeFile(outputPath, `${JSON.stringify({ builds }, null, 2)}\n`);
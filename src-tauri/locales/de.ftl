@@ -0,0 +1,101 @@
+# Deutsche Texte des JellyPilot-Backends.
+# Fehlende Meldungen werden auf Englisch angezeigt.
+
+## Notifications
+
+notify-mpv-too-old = MPV { $version } ist zu alt, um gesteuert zu werden. Aktualisiere MPV auf { $minimum } oder neuer.
+notify-mpv-outdated = MPV { $version } ist veraltet; einige Funktionen sind deaktiviert. Aktualisiere MPV auf { $minimum } oder neuer.
+notify-mpv-restart-to-apply = MPV neu starten, um zu übernehmen: { $args }
+notify-mpv-args-removed = MPV wurde ohne { $args } gestartet; diese Optionen würden die Steuerung durch JellyPilot verhindern.
+notify-library-needs-session = Die Wiedergabe aus der Bibliothek erfordert eine aktive Sitzung
+notify-resume-failed = Wiedergabe konnte nicht fortgesetzt werden: { $error }
+notify-switched-address = Serveradresse gewechselt. Verbinde erneut...
+notify-connection-lost = Verbindung verloren. Verbinde erneut...
+notify-reconnected = Wieder mit Jellyfin verbunden
+notify-command-failed = Befehl fehlgeschlagen: { $error }
+notify-unreadable-message = Eine unlesbare { $message_type }-Nachricht des Servers wurde ignoriert.
+notify-duration-mismatch = { $title } dauert in MPV { $mpv_minutes } Min., in Jellyfin aber { $server_minutes } Min. Möglicherweise läuft die falsche Datei oder eine abgebrochene Transkodierung.
+notify-playback-stalled = { $title } hält zum Puffern an. Das Netzwerk liefert derzeit { $speed } Mbit/s.
+notify-bitrate-lowered = { $title } puffert immer wieder und wird jetzt mit { $speed } Mbit/s gestreamt.
+notify-device-id-conflict = Ein anderes JellyPilot unter { $address } verwendet diese Geräte-ID, daher können Übertragungen bei beiden landen. Erzeuge die Geräte-ID auf einem der beiden in den Einstellungen neu.
+notify-transcode-interrupted = Der Server hat das Transkodieren von { $title } bei { $position } abgebrochen. Die Wiedergabe wird dort fortgesetzt...
+notify-mpv-slow-load = MPV öffnet den Stream nach { $seconds } Sekunden immer noch. Stoppe die Wiedergabe, um abzubrechen.
+notify-mpv-slow = MPV hat seit { $seconds } Sekunden nicht reagiert. Stoppe die Wiedergabe, um die wartenden Befehle zu überspringen.
+notify-control-api-failed = Die Steuerungs-API konnte nicht auf Port { $port } lauschen: { $error }
+
+## Connection status
+
+connection-signed-out = Abgemeldet
+connection-session-expired = Der Server akzeptiert diese Sitzung nicht mehr. Melde dich erneut an.
+
+## OS notifications
+
+os-playback-started = Jetzt läuft
+os-auto-next = Als Nächstes
+os-connection-lost = Verbindung verloren
+os-connection-restored = Verbindung wiederhergestellt
+os-mpv-crashed = MPV wurde unerwartet beendet
+os-mpv-crashed-body = Die Wiedergabe wurde unterbrochen. Streame erneut, um fortzufahren.
+
+## Playback errors
+
+playback-error-auth = Der Server hat diese Sitzung abgelehnt. Melde dich erneut an.
+playback-error-playback-info = Der Server konnte dieses Element nicht für die Wiedergabe vorbereiten. Prüfe, ob die Medien auf dem Server verfügbar sind.
+playback-error-stream-url = Es konnte keine Stream-Adresse erstellt werden. Verbinde dich erneut mit dem Server und versuche es noch einmal.
+playback-error-mpv-spawn = MPV konnte nicht gestartet werden. Lege den MPV-Pfad in den Einstellungen fest.
+playback-error-mpv-load = MPV konnte den Stream nicht öffnen. Prüfe die Netzwerkverbindung oder exportiere die Diagnose für das MPV-Protokoll.
+playback-error-ipc-lost = MPV wurde während der Wiedergabe beendet oder reagiert nicht mehr.
+playback-error-ended-early = Die Wiedergabe endete deutlich vor dem Ende, etwa wegen eines Netzwerkausfalls oder einer beschädigten Datei. Versuche es erneut, um dort fortzusetzen.
+
+## MPV on-screen messages
+
+osd-image-subtitles-unavailable = { $name }-Untertitel können bei diesem Stream nicht angezeigt werden
+osd-intro-available = Intro verfügbar - { $key } zum Überspringen drücken
+osd-credits-available = Abspann verfügbar - { $key } zum Überspringen drücken
+osd-skipped-intro = Intro übersprungen
+osd-skipped-credits = Abspann übersprungen
+osd-recap-available = Rückblick verfügbar - { $key } zum Überspringen drücken
+osd-commercial-available = Werbung verfügbar - { $key } zum Überspringen drücken
+osd-segment-available = Markierter Abschnitt verfügbar - { $key } zum Überspringen drücken
+osd-skipped-recap = Rückblick übersprungen
+osd-skipped-commercial = Werbung übersprungen
+osd-skipped-segment = Markierten Abschnitt übersprungen
+osd-nothing-to-skip = Kein Intro oder Abspann zum Überspringen
+osd-skip-start-marked = Sprungbeginn bei { $position } markiert
+osd-skip-range-saved = { $start }-{ $end } wird in dieser Serie ab jetzt übersprungen
+osd-skip-mark-start-first = Zuerst den Sprungbeginn markieren
+osd-up-next = Als Nächstes in { $seconds }
+osd-nothing-up-next = Nichts als Nächstes
+osd-buffering = Puffern - Netzwerk bei { $speed } Mbit/s
+osd-intro-skip-on = Automatisches Überspringen des Intros an
+osd-intro-skip-off = Automatisches Überspringen des Intros aus
+osd-marked-watched = Als gesehen markiert
+osd-marked-unwatched = Als ungesehen markiert
+osd-watched-toggle-failed = Gesehen-Status konnte nicht geändert werden
+osd-favorited = ♥ Zu Favoriten hinzugefügt
+osd-unfavorited = ♡ Aus Favoriten entfernt
+osd-favorite-toggle-failed = Favoriten konnten nicht geändert werden
+osd-speed = Geschwindigkeit { $speed }x
+osd-secondary-subtitle = Zweiter Untertitel: { $name }
+osd-secondary-subtitle-off = Zweiter Untertitel aus
+osd-secondary-subtitle-unavailable = Kein zweiter Untertitel verfügbar
+osd-ab-loop-start = Schleifenanfang bei { $position }
+osd-ab-loop-set = Schleife { $start }-{ $end }
+osd-ab-loop-cleared = Schleife aufgehoben
+osd-screenshot-saved = Screenshot gespeichert: { $name }
+osd-screenshot-failed = Screenshot konnte nicht gespeichert werden
+osd-press-key-to-bind = Drücke die Taste für das Tastenkürzel (Esc bricht ab)
+
+## Tray menu
+
+tray-play-pause = Wiedergabe/Pause
+tray-next = Weiter
+tray-previous = Zurück
+tray-mute = Stumm
+tray-toggle-watched = Als gesehen/ungesehen markieren
+tray-toggle-favorite = Favorit
+tray-continue-watching = Weiterschauen
+tray-not-connected = Nicht verbunden
+tray-nothing-to-resume = Nichts zum Fortsetzen
+tray-show-console = Konsole anzeigen
+tray-quit = Beenden
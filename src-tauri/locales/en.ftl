@@ -0,0 +1,101 @@
+# User-facing strings produced by the JellyPilot backend.
+# English is the fallback for messages a translation does not have.
+
+## Notifications
+
+notify-mpv-too-old = MPV { $version } is too old to be controlled. Update MPV to { $minimum } or newer.
+notify-mpv-outdated = MPV { $version } is outdated; some features are turned off. Update MPV to { $minimum } or newer.
+notify-mpv-restart-to-apply = Restart MPV to apply: { $args }
+notify-mpv-args-removed = MPV was started without { $args }; these options would break control from JellyPilot.
+notify-library-needs-session = Library playback requires an active session
+notify-resume-failed = Failed to resume playback: { $error }
+notify-switched-address = Switched server address. Reconnecting...
+notify-connection-lost = Connection lost. Reconnecting...
+notify-reconnected = Reconnected to Jellyfin
+notify-command-failed = Command failed: { $error }
+notify-unreadable-message = Ignored a { $message_type } message from the server that could not be read.
+notify-duration-mismatch = { $title } is { $mpv_minutes } min long in MPV but { $server_minutes } min in Jellyfin. The wrong file or a truncated transcode may be playing.
+notify-playback-stalled = { $title } stopped to buffer. The network currently delivers { $speed } Mbit/s.
+notify-bitrate-lowered = { $title } keeps buffering, so it now streams at { $speed } Mbit/s.
+notify-device-id-conflict = Another JellyPilot at { $address } uses this device ID, so casts may go to either one. Regenerate the device ID in Settings on one of them.
+notify-transcode-interrupted = The server stopped transcoding { $title } at { $position }. Resuming from there...
+notify-mpv-slow-load = MPV is still opening the stream after { $seconds } seconds. Stop playback to cancel it.
+notify-mpv-slow = MPV has not responded for { $seconds } seconds. Stop playback to skip the waiting commands.
+notify-control-api-failed = The control API could not listen on port { $port }: { $error }
+
+## Connection status
+
+connection-signed-out = Signed out
+connection-session-expired = The server no longer accepts this session. Sign in again.
+
+## OS notifications
+
+os-playback-started = Now playing
+os-auto-next = Up next
+os-connection-lost = Connection lost
+os-connection-restored = Connection restored
+os-mpv-crashed = MPV stopped unexpectedly
+os-mpv-crashed-body = Playback was interrupted. Cast again to continue.
+
+## Playback errors
+
+playback-error-auth = The server rejected this session. Sign in again.
+playback-error-playback-info = The server could not prepare this item for playback. Check that the media is available on the server.
+playback-error-stream-url = No stream address could be built. Reconnect to the server and try again.
+playback-error-mpv-spawn = MPV could not be started. Configure the MPV path in Settings.
+playback-error-mpv-load = MPV could not open the stream. Check the network connection or export diagnostics for the MPV log.
+playback-error-ipc-lost = MPV closed or stopped responding during playback.
+playback-error-ended-early = Playback stopped well before the end, e.g. because the network dropped or the file is damaged. Retry to continue where it stopped.
+
+## MPV on-screen messages
+
+osd-image-subtitles-unavailable = { $name } subtitles can't be shown on this stream
+osd-intro-available = Intro available - press { $key } to skip
+osd-credits-available = Credits available - press { $key } to skip
+osd-skipped-intro = Skipped intro
+osd-skipped-credits = Skipped credits
+osd-recap-available = Recap available - press { $key } to skip
+osd-commercial-available = Commercial available - press { $key } to skip
+osd-segment-available = Marked segment available - press { $key } to skip
+osd-skipped-recap = Skipped recap
+osd-skipped-commercial = Skipped commercial
+osd-skipped-segment = Skipped marked segment
+osd-nothing-to-skip = No intro or credits to skip
+osd-skip-start-marked = Skip start marked at { $position }
+osd-skip-range-saved = Skipping { $start }-{ $end } in this series from now on
+osd-skip-mark-start-first = Mark the skip start first
+osd-up-next = Up next in { $seconds }
+osd-nothing-up-next = Nothing up next
+osd-buffering = Buffering - network at { $speed } Mbit/s
+osd-intro-skip-on = Automatic intro skipping on
+osd-intro-skip-off = Automatic intro skipping off
+osd-marked-watched = Marked as watched
+osd-marked-unwatched = Marked as unwatched
+osd-watched-toggle-failed = Could not change the watched state
+osd-favorited = ♥ Added to favorites
+osd-unfavorited = ♡ Removed from favorites
+osd-favorite-toggle-failed = Could not change the favorites
+osd-speed = Speed { $speed }x
+osd-secondary-subtitle = Secondary subtitle: { $name }
+osd-secondary-subtitle-off = Secondary subtitle off
+osd-secondary-subtitle-unavailable = No secondary subtitle available
+osd-ab-loop-start = Loop start set at { $position }
+osd-ab-loop-set = Looping { $start }-{ $end }
+osd-ab-loop-cleared = Loop cleared
+osd-screenshot-saved = Screenshot saved: { $name }
+osd-screenshot-failed = Could not save the screenshot
+osd-press-key-to-bind = Press the key to use as the shortcut (Esc cancels)
+
+## Tray menu
+
+tray-play-pause = Play/Pause
+tray-next = Next
+tray-previous = Previous
+tray-mute = Mute
+tray-toggle-watched = Mark Watched/Unwatched
+tray-toggle-favorite = Favorite
+tray-continue-watching = Continue Watching
+tray-not-connected = Not connected
+tray-nothing-to-resume = Nothing to resume
+tray-show-console = Show Operations Console
+tray-quit = Quit
@@ -180,6 +180,7 @@ pub(crate) fn save_profiles(
 #[cfg(test)]
 mod tests {
   use super::*;
+  use crate::jellyfin::TlsTrust;
 
   fn session(
     provider: MediaServerProvider,
@@ -189,10 +190,14 @@ mod tests {
   ) -> SavedSession {
     SavedSession {
       access_token: token.to_string(),
+      custom_headers: Default::default(),
       device_id: Some("device-1".to_string()),
+      local_url: None,
       provider,
       server_name: Some("Media Home".to_string()),
+      server_id: None,
       server_url: server_url.to_string(),
+      tls: TlsTrust::default(),
       user_id: format!("user-{user_name}"),
       user_name: user_name.to_string(),
     }
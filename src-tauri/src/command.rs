@@ -3,21 +3,40 @@ use serde::{Deserialize, Serialize};
 use specta::specta;
 #[cfg(debug_assertions)]
 use specta_typescript::Typescript;
+use std::collections::BTreeMap;
 use std::sync::Arc;
+use std::time::Instant;
 use tauri::State;
 use tauri_specta::{collect_commands, collect_events, Builder, Event};
 
 use crate::auth_profiles::{load_profiles, save_profiles, SavedServiceProfiles};
-use crate::config::AppConfig;
+use crate::config::{AppConfig, ConfigFieldError, TrackPreferenceScope};
+use crate::control_api;
+use crate::diagnostics;
+use crate::i18n::{self, tr};
 use crate::jellyfin::{
-  ConnectionState, Credentials, JellyfinClient, JellyfinError, QuickConnectRequest,
-  QuickConnectStatus, SavedSession, SessionManager, VideoHome, VideoItemDetail, VideoLibraryPage,
-  VideoLibraryPageRequest, VideoLibraryPlayRequest, VideoLibraryShortcut, VideoSearchPage,
-  VideoSearchRequest, VideoSeasonEpisodes, VideoSeasonEpisodesRequest, VideoShowDetail,
-  VideoUserDataUpdate, VideoUserDataUpdateRequest,
+  scope_of_key, seconds_to_ticks, BandwidthMeter, BandwidthStats, CastCapabilities,
+  ConnectionState, Credentials, JellyfinClient, JellyfinError, MediaSource, PlaybackChapter,
+  PlaybackErrorStage, QuickConnectRequest, QuickConnectStatus, RemotePlaystateCommand,
+  RemoteSession, SavedSession, ScreenshotMode, SeekTarget, SessionManager, SessionPhase, TlsTrust,
+  TrackPreference, VideoHome, VideoItemDetail, VideoLibraryPage, VideoLibraryPageRequest,
+  VideoLibraryPlayRequest, VideoLibraryShortcut, VideoSearchPage, VideoSearchRequest,
+  VideoSeasonEpisodes, VideoSeasonEpisodesRequest, VideoShowDetail, VideoUserDataUpdate,
+  VideoUserDataUpdateRequest,
+};
+use crate::metrics;
+use crate::mpv::{
+  bundled_mpv_status, changed_bindings, install_bundled_mpv, session_logs, set_log_retention,
+  write_input_conf, BundledMpvStatus, MpvClient, MpvVersion, PropertyValue, MIN_IPC_MPV_VERSION,
+  MIN_MPV_VERSION,
 };
-use crate::mpv::{write_input_conf, MpvClient, PropertyValue};
 use crate::playback_control;
+use crate::retention::BufferUsage;
+use crate::self_check::{self, SelfCheck};
+use crate::settings_lock::{SettingsLock, SettingsLockStatus, SettingsPin, UnlockError};
+use crate::telemetry::{self, TelemetryReport};
+use crate::tray;
+use crate::webhooks;
 
 // ============================================================================
 // Events
@@ -119,22 +138,26 @@ pub enum CommandErrorCode {
 pub struct CommandError {
   pub code: CommandErrorCode,
   pub message: String,
+  /// Settings that failed validation, for `config_set`.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  #[specta(optional)]
+  pub field_errors: Option<Vec<ConfigFieldError>>,
 }
 
 impl CommandError {
-  #[allow(dead_code)]
   pub fn not_connected(message: impl Into<String>) -> Self {
     Self {
       code: CommandErrorCode::NotConnected,
       message: message.into(),
+      field_errors: None,
     }
   }
 
-  #[allow(dead_code)]
   pub fn not_found(message: impl Into<String>) -> Self {
     Self {
       code: CommandErrorCode::NotFound,
       message: message.into(),
+      field_errors: None,
     }
   }
 
@@ -142,6 +165,7 @@ impl CommandError {
     Self {
       code: CommandErrorCode::InvalidInput,
       message: message.into(),
+      field_errors: None,
     }
   }
 
@@ -149,6 +173,7 @@ impl CommandError {
     Self {
       code: CommandErrorCode::Network,
       message: message.into(),
+      field_errors: None,
     }
   }
 
@@ -156,6 +181,21 @@ impl CommandError {
     Self {
       code: CommandErrorCode::AuthFailed,
       message: message.into(),
+      field_errors: None,
+    }
+  }
+
+  /// Invalid settings, one entry per problem.
+  pub fn invalid_config(errors: Vec<ConfigFieldError>) -> Self {
+    let message = errors
+      .iter()
+      .map(|error| error.message.as_str())
+      .collect::<Vec<_>>()
+      .join("\n");
+    Self {
+      code: CommandErrorCode::InvalidInput,
+      message,
+      field_errors: Some(errors),
     }
   }
 
@@ -163,6 +203,7 @@ impl CommandError {
     Self {
       code: CommandErrorCode::Internal,
       message: message.into(),
+      field_errors: None,
     }
   }
 }
@@ -182,16 +223,21 @@ fn internal_err(e: impl std::fmt::Display) -> CommandError {
 
 fn jellyfin_err(e: JellyfinError) -> CommandError {
   match e {
-    JellyfinError::InvalidUrl(message) => CommandError::invalid_input(message),
+    JellyfinError::InvalidUrl(message) | JellyfinError::Tls(message) => {
+      CommandError::invalid_input(message)
+    }
     JellyfinError::QuickConnectUnavailable => {
       CommandError::auth_failed("Quick Connect is not enabled on this server")
     }
-    JellyfinError::AuthFailed(message) => CommandError::auth_failed(message),
+    JellyfinError::AuthFailed(message)
+    | JellyfinError::CredentialsExpired(message)
+    | JellyfinError::InvalidCredentials(message) => CommandError::auth_failed(message),
     JellyfinError::Http(_) | JellyfinError::HttpError(_) => CommandError::network(e.to_string()),
     JellyfinError::NotConnected | JellyfinError::SessionNotFound => {
       CommandError::not_connected(e.to_string())
     }
     JellyfinError::WebSocket(_) | JellyfinError::Json(_) => internal_err(e),
+    JellyfinError::Playback { source, .. } => jellyfin_err(*source),
   }
 }
 
@@ -204,6 +250,7 @@ async fn start_remote_control_session_if_supported(
     state.client.clone(),
     state.mpv.clone(),
     config_state.0.clone(),
+    state.bandwidth.clone(),
     app.clone(),
   ));
 
@@ -292,6 +339,28 @@ pub struct NowPlayingMedia {
   pub series_name: Option<String>,
   pub season_number: Option<i32>,
   pub episode_number: Option<i32>,
+  /// None until the server has sent it.
+  pub user_data: Option<NowPlayingUserData>,
+}
+
+/// The signed-in user's watched, favorite and rating state of the current media.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct NowPlayingUserData {
+  pub played: bool,
+  pub favorite: bool,
+  /// Thumbs up (true) or down (false); None when not rated.
+  pub likes: Option<bool>,
+}
+
+impl From<&VideoUserDataUpdate> for NowPlayingUserData {
+  fn from(update: &VideoUserDataUpdate) -> Self {
+    Self {
+      played: update.played,
+      favorite: update.favorite,
+      likes: update.likes,
+    }
+  }
 }
 
 /// User-facing playback state for the Operations Console.
@@ -314,6 +383,106 @@ pub struct NowPlayingChanged {
   pub state: NowPlayingState,
 }
 
+/// Download totals emitted periodically while playing.
+#[derive(Debug, Clone, Serialize, specta::Type, Event)]
+#[serde(rename_all = "camelCase")]
+pub struct BandwidthUpdated {
+  pub stats: BandwidthStats,
+}
+
+/// Playback failure event emitted to frontend.
+#[derive(Debug, Clone, Serialize, specta::Type, Event)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaybackError {
+  pub stage: PlaybackErrorStage,
+  /// What the user can do about the failure.
+  pub message: String,
+  /// The underlying error, for details and bug reports.
+  pub detail: String,
+}
+
+impl PlaybackError {
+  /// Emit a playback failure to the frontend.
+  pub fn report(app: &tauri::AppHandle, stage: PlaybackErrorStage, detail: impl Into<String>) {
+    let error = Self {
+      stage,
+      message: stage.user_message(),
+      detail: detail.into(),
+    };
+    if let Err(e) = error.emit(app) {
+      log::error!("Failed to emit playback error: {}", e);
+    }
+  }
+}
+
+/// Event emitted when the server stops accepting the saved session, e.g.
+/// after a password change. The session stays idle until the user signs in
+/// again.
+#[derive(Debug, Clone, Serialize, specta::Type, Event)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionExpired {
+  /// What the user can do about it.
+  pub message: String,
+  /// The server's rejection, for details and bug reports.
+  pub detail: String,
+}
+
+impl SessionExpired {
+  /// Emit a session expiry to the frontend.
+  pub fn report(app: &tauri::AppHandle, detail: impl Into<String>) {
+    let event = Self {
+      message: "The server no longer accepts this session. Sign in again.".to_string(),
+      detail: detail.into(),
+    };
+    if let Err(e) = event.emit(app) {
+      log::error!("Failed to emit session expiry: {}", e);
+    }
+  }
+}
+
+/// Server connection changes, for a status banner that replaces toasts
+/// about every lost and restored connection.
+#[derive(Debug, Clone, Serialize, specta::Type, Event)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum ConnectionEvent {
+  Connecting,
+  Connected {
+    /// Server name, or its address when the name is unknown.
+    server: String,
+  },
+  /// The connection was lost; attempt `attempt` follows in `delay_seconds`.
+  Reconnecting {
+    attempt: u32,
+    #[serde(rename = "delaySeconds")]
+    delay_seconds: f64,
+  },
+  /// Not connected and not retrying, e.g. after signing out.
+  Disconnected {
+    reason: String,
+  },
+}
+
+impl ConnectionEvent {
+  /// Emit a connection change to the frontend.
+  pub fn report(self, app: &tauri::AppHandle) {
+    if let Err(e) = self.emit(app) {
+      log::error!("Failed to emit connection event: {}", e);
+    }
+  }
+}
+
+/// Event emitted when a newer JellyPilot release is published.
+#[derive(Debug, Clone, Serialize, specta::Type, Event)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateAvailable {
+  /// The release's version, e.g. `1.5.0`.
+  pub version: String,
+  /// Release notes in Markdown.
+  pub release_notes: String,
+  /// The release page with the downloads.
+  pub download_url: String,
+}
+
 /// MPV client state managed by Tauri.
 pub struct MpvState(pub Arc<MpvClient>);
 
@@ -322,6 +491,8 @@ pub struct JellyfinState {
   pub client: Arc<JellyfinClient>,
   pub mpv: Arc<MpvClient>,
   pub session: RwLock<Option<Arc<SessionManager>>>,
+  /// Download totals, kept across sessions.
+  pub bandwidth: Arc<RwLock<BandwidthMeter>>,
 }
 
 impl JellyfinState {
@@ -330,6 +501,7 @@ impl JellyfinState {
       client,
       mpv,
       session: RwLock::new(None),
+      bandwidth: Arc::new(RwLock::new(BandwidthMeter::default())),
     }
   }
 }
@@ -347,6 +519,7 @@ pub async fn mpv_start(
   jellyfin_state: State<'_, JellyfinState>,
 ) -> Result<(), CommandError> {
   state.0.start().await.map_err(internal_err)?;
+  notify_removed_mpv_args(&app, &state.0);
   playback_control::emit_now_playing_changed(&app, &jellyfin_state).await;
   Ok(())
 }
@@ -394,6 +567,39 @@ pub async fn mpv_seek(
   Ok(())
 }
 
+/// Seek the current item to a percentage (0-100) of its runtime.
+#[tauri::command]
+#[specta]
+pub async fn playback_seek_percent(
+  app: tauri::AppHandle,
+  jellyfin_state: State<'_, JellyfinState>,
+  percent: f64,
+) -> Result<(), CommandError> {
+  playback_control::seek(&app, &jellyfin_state, SeekTarget::Percent(percent)).await
+}
+
+/// Seek the current item to an absolute position in seconds.
+#[tauri::command]
+#[specta]
+pub async fn playback_seek_absolute(
+  app: tauri::AppHandle,
+  jellyfin_state: State<'_, JellyfinState>,
+  seconds: f64,
+) -> Result<(), CommandError> {
+  playback_control::seek(&app, &jellyfin_state, SeekTarget::Seconds(seconds)).await
+}
+
+/// Rate the current item thumbs up (`likes`) or thumbs down.
+#[tauri::command]
+#[specta]
+pub async fn playback_set_user_rating(
+  app: tauri::AppHandle,
+  jellyfin_state: State<'_, JellyfinState>,
+  likes: bool,
+) -> Result<NowPlayingUserData, CommandError> {
+  playback_control::set_user_rating(&app, &jellyfin_state, likes).await
+}
+
 /// Set pause state.
 #[tauri::command]
 #[specta]
@@ -415,14 +621,7 @@ pub async fn mpv_set_volume(
   jellyfin_state: State<'_, JellyfinState>,
   volume: f64,
 ) -> Result<(), CommandError> {
-  if !(0.0..=100.0).contains(&volume) {
-    return Err(CommandError::invalid_input(
-      "Volume must be between 0 and 100",
-    ));
-  }
-  state.0.set_volume(volume).await.map_err(internal_err)?;
-  playback_control::emit_now_playing_changed(&app, &jellyfin_state).await;
-  Ok(())
+  playback_control::set_volume(&app, &state.0, &jellyfin_state, volume).await
 }
 
 /// Set audio track by ID.
@@ -474,6 +673,233 @@ pub async fn mpv_toggle_mute(
   playback_control::toggle_mute(&app, &state.0, &jellyfin_state).await
 }
 
+/// Enter or leave fullscreen in the running player.
+#[tauri::command]
+#[specta]
+pub async fn playback_set_fullscreen(
+  state: State<'_, MpvState>,
+  fullscreen: bool,
+) -> Result<(), CommandError> {
+  state
+    .0
+    .set_fullscreen(fullscreen)
+    .await
+    .map_err(internal_err)
+}
+
+/// Version (media source) of the current item.
+#[derive(Debug, Clone, Serialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct MediaVersion {
+  /// Id passed to `playback_switch_version`.
+  pub id: String,
+  /// Version name from the server, or the resolution when it has none.
+  pub name: String,
+  pub container: Option<String>,
+  /// Video height in pixels.
+  pub height: Option<i32>,
+  /// Total bitrate in bits per second.
+  pub bitrate: Option<i32>,
+  /// Whether this version is playing.
+  pub is_current: bool,
+}
+
+impl MediaVersion {
+  fn new(position: usize, source: &MediaSource, current_id: Option<&str>) -> Self {
+    let height = source.video_height();
+    Self {
+      id: source.id.clone(),
+      name: source
+        .name
+        .clone()
+        .or_else(|| height.map(|height| format!("{}p", height)))
+        .unwrap_or_else(|| format!("Version {}", position + 1)),
+      container: source.container.clone(),
+      height,
+      bitrate: source.bitrate,
+      is_current: current_id == Some(source.id.as_str()),
+    }
+  }
+}
+
+/// List the versions of the current item.
+#[tauri::command]
+#[specta]
+pub async fn playback_list_versions(
+  state: State<'_, JellyfinState>,
+) -> Result<Vec<MediaVersion>, CommandError> {
+  let session = state
+    .session
+    .read()
+    .clone()
+    .ok_or_else(|| CommandError::invalid_input("Versions are available during playback"))?;
+  let (versions, current_id) = session
+    .media_versions()
+    .await
+    .map_err(CommandError::invalid_input)?;
+  Ok(
+    versions
+      .iter()
+      .enumerate()
+      .map(|(position, source)| MediaVersion::new(position, source, current_id.as_deref()))
+      .collect(),
+  )
+}
+
+/// Reload the current item from another version at the current position.
+#[tauri::command]
+#[specta]
+pub async fn playback_switch_version(
+  app: tauri::AppHandle,
+  state: State<'_, JellyfinState>,
+  media_source_id: String,
+) -> Result<(), CommandError> {
+  let session = state
+    .session
+    .read()
+    .clone()
+    .ok_or_else(|| CommandError::invalid_input("Versions are available during playback"))?;
+  session
+    .switch_media_version(&media_source_id)
+    .await
+    .map_err(CommandError::invalid_input)?;
+  playback_control::emit_now_playing_changed(&app, &state).await;
+  Ok(())
+}
+
+/// Show a second subtitle stream (Jellyfin index) under the selected one,
+/// or hide it with `None`.
+#[tauri::command]
+#[specta]
+pub async fn playback_set_secondary_subtitle(
+  state: State<'_, JellyfinState>,
+  index: Option<i32>,
+) -> Result<(), CommandError> {
+  let session = state.session.read().clone().ok_or_else(|| {
+    CommandError::invalid_input("Secondary subtitles are available during playback")
+  })?;
+  session
+    .set_secondary_subtitle(index)
+    .await
+    .map_err(CommandError::invalid_input)
+}
+
+/// Loop the current item between `start` and `end` seconds.
+#[tauri::command]
+#[specta]
+pub async fn playback_set_ab_loop(
+  state: State<'_, JellyfinState>,
+  start: f64,
+  end: f64,
+) -> Result<(), CommandError> {
+  let session = state
+    .session
+    .read()
+    .clone()
+    .ok_or_else(|| CommandError::invalid_input("Looping is available during playback"))?;
+  session
+    .set_ab_loop(Some((start, end)))
+    .await
+    .map_err(CommandError::invalid_input)
+}
+
+/// Stop looping part of the current item.
+#[tauri::command]
+#[specta]
+pub async fn playback_clear_ab_loop(state: State<'_, JellyfinState>) -> Result<(), CommandError> {
+  let session = state
+    .session
+    .read()
+    .clone()
+    .ok_or_else(|| CommandError::invalid_input("Looping is available during playback"))?;
+  session
+    .set_ab_loop(None)
+    .await
+    .map_err(CommandError::invalid_input)
+}
+
+/// Save a screenshot named after the playing item to the screenshot folder.
+/// Returns the file's path.
+#[tauri::command]
+#[specta]
+pub async fn playback_screenshot(
+  state: State<'_, JellyfinState>,
+  mode: ScreenshotMode,
+) -> Result<String, CommandError> {
+  let session = state
+    .session
+    .read()
+    .clone()
+    .ok_or_else(|| CommandError::invalid_input("Screenshots are available during playback"))?;
+  session
+    .screenshot(mode)
+    .await
+    .map(|path| path.to_string_lossy().into_owned())
+    .map_err(CommandError::invalid_input)
+}
+
+/// Chapters of the playing item, titled from the server even when the
+/// stream leaves them unnamed.
+#[tauri::command]
+#[specta]
+pub async fn playback_get_chapters(
+  state: State<'_, JellyfinState>,
+) -> Result<Vec<PlaybackChapter>, CommandError> {
+  let session = state
+    .session
+    .read()
+    .clone()
+    .ok_or_else(|| CommandError::invalid_input("Chapters are available during playback"))?;
+  session
+    .chapters()
+    .await
+    .map_err(CommandError::invalid_input)
+}
+
+/// Jump to a chapter by its index in `playback_get_chapters`.
+#[tauri::command]
+#[specta]
+pub async fn playback_goto_chapter(
+  state: State<'_, JellyfinState>,
+  index: u32,
+) -> Result<(), CommandError> {
+  let session = state
+    .session
+    .read()
+    .clone()
+    .ok_or_else(|| CommandError::invalid_input("Chapters are available during playback"))?;
+  session
+    .goto_chapter(index)
+    .await
+    .map_err(CommandError::invalid_input)
+}
+
+/// Play the item that ended early again from where it stopped.
+#[tauri::command]
+#[specta]
+pub async fn playback_retry(state: State<'_, JellyfinState>) -> Result<(), CommandError> {
+  let session = state
+    .session
+    .read()
+    .clone()
+    .ok_or_else(|| CommandError::invalid_input("No playback ended early"))?;
+  session
+    .retry_early_end()
+    .await
+    .map_err(CommandError::invalid_input)
+}
+
+/// What the playback session is doing, e.g. loading, playing or stalling.
+#[tauri::command]
+#[specta]
+pub fn session_get_state(state: State<'_, JellyfinState>) -> SessionPhase {
+  state
+    .session
+    .read()
+    .as_ref()
+    .map_or(SessionPhase::Disconnected, |session| session.phase())
+}
+
 /// Get current player state.
 #[tauri::command]
 #[specta]
@@ -497,6 +923,88 @@ pub fn mpv_is_connected(state: State<'_, MpvState>) -> bool {
   state.0.is_connected()
 }
 
+/// Version of the configured MPV, or null when it could not be detected.
+#[tauri::command]
+#[specta]
+pub fn mpv_get_version(state: State<'_, MpvState>) -> Option<MpvVersion> {
+  state.0.version()
+}
+
+/// Detect the configured MPV's version for feature gating and warn when it
+/// is too old, instead of letting playback fail with IPC errors later.
+pub async fn refresh_mpv_version(app: &tauri::AppHandle, mpv: &MpvClient) {
+  let Some(version) = mpv.refresh_version().await else {
+    return;
+  };
+  if version < MIN_IPC_MPV_VERSION {
+    AppNotification::error(
+      app,
+      tr!(
+        "notify-mpv-too-old",
+        version = version.to_string(),
+        minimum = MIN_MPV_VERSION.to_string()
+      ),
+    );
+  } else if version < MIN_MPV_VERSION {
+    AppNotification::warning(
+      app,
+      tr!(
+        "notify-mpv-outdated",
+        version = version.to_string(),
+        minimum = MIN_MPV_VERSION.to_string()
+      ),
+    );
+  }
+}
+
+/// Tell the user which extra MPV args the last start left out.
+pub fn notify_removed_mpv_args(app: &tauri::AppHandle, mpv: &MpvClient) {
+  let removed = mpv.take_removed_args();
+  if !removed.is_empty() {
+    AppNotification::warning(
+      app,
+      tr!("notify-mpv-args-removed", args = removed.join(" ")),
+    );
+  }
+}
+
+/// Installed bundled MPV build.
+#[tauri::command]
+#[specta]
+pub fn mpv_bundled_status() -> BundledMpvStatus {
+  bundled_mpv_status()
+}
+
+/// Download the bundled MPV build for this platform, or update it, and use it
+/// when no MPV path is configured.
+#[tauri::command]
+#[specta]
+pub async fn mpv_bundled_install(
+  app: tauri::AppHandle,
+  config_state: State<'_, ConfigState>,
+  mpv_state: State<'_, MpvState>,
+  lock_state: State<'_, SettingsLockState>,
+) -> Result<BundledMpvStatus, CommandError> {
+  require_settings_unlocked(&config_state, &lock_state)?;
+  update_bundled_mpv(&app, &mpv_state.0, &config_state.0).await?;
+  Ok(bundled_mpv_status())
+}
+
+/// Install or update the bundled MPV, then re-resolve the MPV to spawn.
+pub async fn update_bundled_mpv(
+  app: &tauri::AppHandle,
+  mpv: &MpvClient,
+  config: &RwLock<AppConfig>,
+) -> Result<(), CommandError> {
+  install_bundled_mpv()
+    .await
+    .map_err(|e| CommandError::network(format!("Failed to install MPV: {}", e)))?;
+  let mpv_path = config.read().resolved_mpv_path();
+  mpv.set_mpv_path(mpv_path);
+  refresh_mpv_version(app, mpv).await;
+  Ok(())
+}
+
 // ============================================================================
 // Jellyfin Commands
 // ============================================================================
@@ -589,6 +1097,10 @@ pub async fn jellyfin_disconnect(
 
   // Disconnect client
   state.client.login().disconnect();
+  ConnectionEvent::Disconnected {
+    reason: tr!("connection-signed-out"),
+  }
+  .report(&app);
   playback_control::emit_now_playing_changed(&app, &state).await;
 
   Ok(())
@@ -782,7 +1294,11 @@ pub async fn jellyfin_restore_session(
 pub async fn jellyfin_clear_session(
   app: tauri::AppHandle,
   state: State<'_, JellyfinState>,
+  config_state: State<'_, ConfigState>,
+  lock_state: State<'_, SettingsLockState>,
 ) -> Result<(), CommandError> {
+  require_settings_unlocked(&config_state, &lock_state)?;
+
   // Take session without holding lock across await
   let session = state.session.write().take();
 
@@ -799,6 +1315,50 @@ pub async fn jellyfin_clear_session(
   Ok(())
 }
 
+/// Register as a cast target again, retrying until the server lists this device.
+#[tauri::command]
+#[specta]
+pub async fn jellyfin_refresh_cast_registration(
+  state: State<'_, JellyfinState>,
+) -> Result<(), CommandError> {
+  let session = state
+    .session
+    .read()
+    .clone()
+    .ok_or_else(|| CommandError::invalid_input("Connect to a server first"))?;
+  session
+    .refresh_cast_registration()
+    .await
+    .map_err(jellyfin_err)
+}
+
+/// Give this install a new device ID when another one uses the same, then
+/// register as a cast target under it.
+#[tauri::command]
+#[specta]
+pub async fn jellyfin_regenerate_device_id(
+  app: tauri::AppHandle,
+  state: State<'_, JellyfinState>,
+  config_state: State<'_, ConfigState>,
+  lock_state: State<'_, SettingsLockState>,
+) -> Result<(), CommandError> {
+  require_settings_unlocked(&config_state, &lock_state)?;
+  let device_id = state.client.login().regenerate_device_id();
+  log::info!("Regenerated device ID: {}", device_id);
+
+  // Saved profiles restore the device ID, so keep them on the new one.
+  if let Some(session) = state.client.login().get_saved_session() {
+    let mut profiles = load_profiles(&app).map_err(internal_err)?;
+    profiles.upsert_active(session);
+    save_profiles(&app, &profiles).map_err(internal_err)?;
+  }
+
+  if state.client.login().is_connected() {
+    start_remote_control_session_if_supported(&app, &state, &config_state).await?;
+  }
+  Ok(())
+}
+
 /// Play the next episode from the active Jellyfin session.
 #[tauri::command]
 #[specta]
@@ -890,21 +1450,175 @@ pub async fn server_restore_session(
 pub async fn server_clear_session(
   app: tauri::AppHandle,
   state: State<'_, JellyfinState>,
+  config_state: State<'_, ConfigState>,
+  lock_state: State<'_, SettingsLockState>,
 ) -> Result<(), CommandError> {
-  jellyfin_clear_session(app, state).await
+  jellyfin_clear_session(app, state, config_state, lock_state).await
 }
 
-/// List saved media server profiles.
+/// Set the certificate trust used for the next connection, e.g. before
+/// starting Quick Connect with a self-signed server.
 #[tauri::command]
 #[specta]
-pub fn server_profiles_get(app: tauri::AppHandle) -> Result<SavedServiceProfiles, CommandError> {
-  load_profiles(&app)
-    .map(|profiles| profiles.summary())
-    .map_err(internal_err)
-}
-
-/// Import a legacy single saved session into the saved service profile store.
-#[tauri::command]
+pub fn server_set_tls_trust(
+  state: State<'_, JellyfinState>,
+  config_state: State<'_, ConfigState>,
+  lock_state: State<'_, SettingsLockState>,
+  trust: TlsTrust,
+) -> Result<(), CommandError> {
+  require_settings_unlocked(&config_state, &lock_state)?;
+  state
+    .client
+    .login()
+    .set_tls_trust(&trust)
+    .map_err(jellyfin_err)
+}
+
+/// Set the headers a reverse proxy requires for the next connection, e.g.
+/// before starting Quick Connect behind Cloudflare Access.
+#[tauri::command]
+#[specta]
+pub fn server_set_custom_headers(
+  state: State<'_, JellyfinState>,
+  config_state: State<'_, ConfigState>,
+  lock_state: State<'_, SettingsLockState>,
+  headers: BTreeMap<String, String>,
+) -> Result<(), CommandError> {
+  require_settings_unlocked(&config_state, &lock_state)?;
+  state
+    .client
+    .login()
+    .set_custom_headers(&headers)
+    .map_err(jellyfin_err)
+}
+
+/// SHA-256 fingerprint of the certificate a server presents, for the user to
+/// accept before it is pinned.
+#[tauri::command]
+#[specta]
+pub async fn server_certificate_fingerprint(
+  state: State<'_, JellyfinState>,
+  server_url: String,
+) -> Result<String, CommandError> {
+  state
+    .client
+    .login()
+    .fetch_certificate_fingerprint(&server_url)
+    .await
+    .map_err(jellyfin_err)
+}
+
+/// Other active sessions of the signed-in user, e.g. a phone or TV that may
+/// be playing what gets cast here.
+#[tauri::command]
+#[specta]
+pub async fn server_list_other_sessions(
+  state: State<'_, JellyfinState>,
+) -> Result<Vec<RemoteSession>, CommandError> {
+  state
+    .client
+    .playback()
+    .list_other_sessions()
+    .await
+    .map_err(jellyfin_err)
+}
+
+/// Sessions on the server that the signed-in user may control, e.g. TVs and
+/// other clients.
+#[tauri::command]
+#[specta]
+pub async fn server_list_sessions(
+  state: State<'_, JellyfinState>,
+) -> Result<Vec<RemoteSession>, CommandError> {
+  state
+    .client
+    .playback()
+    .list_sessions()
+    .await
+    .map_err(jellyfin_err)
+}
+
+/// Start playing items on another session.
+#[tauri::command]
+#[specta]
+pub async fn server_remote_play(
+  state: State<'_, JellyfinState>,
+  session_id: String,
+  item_ids: Vec<String>,
+  start_position_seconds: Option<f64>,
+) -> Result<(), CommandError> {
+  if item_ids.is_empty() {
+    return Err(CommandError::invalid_input("No items to play"));
+  }
+  state
+    .client
+    .playback()
+    .send_remote_play(
+      &session_id,
+      &item_ids,
+      start_position_seconds.map(seconds_to_ticks),
+    )
+    .await
+    .map_err(jellyfin_err)
+}
+
+/// Pause, resume, stop, seek or skip on another session. `seek_position_seconds`
+/// is required for `seek`.
+#[tauri::command]
+#[specta]
+pub async fn server_remote_playstate(
+  state: State<'_, JellyfinState>,
+  session_id: String,
+  command: RemotePlaystateCommand,
+  seek_position_seconds: Option<f64>,
+) -> Result<(), CommandError> {
+  if command == RemotePlaystateCommand::Seek && seek_position_seconds.is_none() {
+    return Err(CommandError::invalid_input("Seek needs a position"));
+  }
+  state
+    .client
+    .playback()
+    .send_remote_playstate(
+      &session_id,
+      command,
+      seek_position_seconds.map(seconds_to_ticks),
+    )
+    .await
+    .map_err(jellyfin_err)
+}
+
+/// Send a general command such as `VolumeUp`, `ToggleMute` or `SetVolume`
+/// (with a `Volume` argument) to another session.
+#[tauri::command]
+#[specta]
+pub async fn server_remote_command(
+  state: State<'_, JellyfinState>,
+  session_id: String,
+  name: String,
+  args: BTreeMap<String, String>,
+) -> Result<(), CommandError> {
+  if name.trim().is_empty() {
+    return Err(CommandError::invalid_input("Command name cannot be empty"));
+  }
+  state
+    .client
+    .playback()
+    .send_remote_command(&session_id, &name, &args)
+    .await
+    .map_err(jellyfin_err)
+}
+
+/// List saved media server profiles.
+#[tauri::command]
+#[specta]
+pub fn server_profiles_get(app: tauri::AppHandle) -> Result<SavedServiceProfiles, CommandError> {
+  load_profiles(&app)
+    .map(|profiles| profiles.summary())
+    .map_err(internal_err)
+}
+
+/// Import a legacy single saved session into the saved service profile store.
+#[tauri::command]
 #[specta]
 pub fn server_profiles_import_legacy(
   app: tauri::AppHandle,
@@ -981,8 +1695,11 @@ pub async fn server_profiles_activate(
 pub async fn server_profiles_remove(
   app: tauri::AppHandle,
   state: State<'_, JellyfinState>,
+  config_state: State<'_, ConfigState>,
+  lock_state: State<'_, SettingsLockState>,
   key: String,
 ) -> Result<SavedServiceProfiles, CommandError> {
+  require_settings_unlocked(&config_state, &lock_state)?;
   let mut profiles = load_profiles(&app).map_err(internal_err)?;
   if profiles.active_profile_key() == Some(key.as_str()) {
     stop_active_media_server_session(&app, &state).await?;
@@ -1022,11 +1739,14 @@ pub struct ConfigState(pub Arc<RwLock<AppConfig>>);
 const CONFIG_STORE_FILE: &str = "config.json";
 const CONFIG_STORE_KEY: &str = "app_config";
 
-/// Get the current app configuration.
+/// Get the current app configuration. The settings PIN hash stays in the
+/// backend; `settings_lock_status` tells whether one is set.
 #[tauri::command]
 #[specta]
 pub fn config_get(state: State<'_, ConfigState>) -> AppConfig {
-  state.0.read().clone()
+  let mut config = state.0.read().clone();
+  config.settings_pin = None;
+  config
 }
 
 /// Update the app configuration, apply changes live, and persist to disk.
@@ -1037,32 +1757,86 @@ pub async fn config_set(
   state: State<'_, ConfigState>,
   mpv_state: State<'_, MpvState>,
   jellyfin_state: State<'_, JellyfinState>,
-  config: AppConfig,
+  lock_state: State<'_, SettingsLockState>,
+  mut config: AppConfig,
 ) -> Result<(), CommandError> {
-  use std::path::PathBuf;
-  use tauri_plugin_store::StoreExt;
-
-  config.validate().map_err(CommandError::invalid_input)?;
+  require_settings_unlocked(&state, &lock_state)?;
+  config.validate().map_err(CommandError::invalid_config)?;
+  config.settings_pin = state.0.read().settings_pin.clone();
+  if config.control_api_port.is_some() && config.control_api_token.is_empty() {
+    config.control_api_token = uuid::Uuid::new_v4().simple().to_string();
+  }
 
   // Update in-memory state
-  *state.0.write() = config.clone();
+  let previous = std::mem::replace(&mut *state.0.write(), config.clone());
+  let previous_bindings = previous.input_bindings();
+  let previous_mpv_args = previous.mpv_args;
+
+  if config.language != previous.language {
+    i18n::set_language(config.language);
+    tray::refresh_labels(&app);
+  }
+  if let Err(e) = metrics::apply_port(config.metrics_port) {
+    log::warn!("Failed to serve metrics: {}", e);
+  }
+  webhooks::configure(config.webhooks.clone());
+  control_api::apply(&app, config.control_api_port, &config.control_api_token);
 
   // Apply MPV config changes (takes effect on next MPV spawn)
-  let mpv_path = config
-    .mpv_path
-    .as_ref()
-    .filter(|s| !s.is_empty())
-    .map(PathBuf::from);
-  mpv_state.0.set_mpv_path(mpv_path);
+  mpv_state.0.set_mpv_path(config.resolved_mpv_path());
   mpv_state.0.set_extra_args(config.mpv_args.clone());
-  log::info!("MPV config updated (applies on next spawn)");
+  mpv_state.0.set_managed_config(config.managed_mpv_config());
+  mpv_state
+    .0
+    .set_fullscreen_options(config.fullscreen_options());
+  mpv_state.0.set_display_server(config.mpv_display_server);
+  mpv_state.0.set_command_timeouts(config.command_timeouts());
+  mpv_state.0.set_adopt_orphans(config.adopt_orphaned_mpv);
+  mpv_state.0.set_outlive_app(config.mpv_outlives_app);
+  mpv_state
+    .0
+    .set_audit_retention(config.command_audit_retention as usize);
+  set_log_retention(config.mpv_log_retention as usize);
+  if config.mpv_args != previous_mpv_args && mpv_state.0.is_connected() {
+    let restart_required = mpv_state
+      .0
+      .apply_extra_args_live(&previous_mpv_args, &config.mpv_args)
+      .await;
+    if !restart_required.is_empty() {
+      AppNotification::warning(
+        &app,
+        tr!(
+          "notify-mpv-restart-to-apply",
+          args = restart_required.join(" ")
+        ),
+      );
+    }
+  } else {
+    log::info!("MPV config updated (applies on next spawn)");
+  }
+  if config.bundled_mpv && !previous.bundled_mpv {
+    // Downloading can take a while; the bundled MPV is used once it is in place.
+    let app = app.clone();
+    let mpv = mpv_state.0.clone();
+    let config = state.0.clone();
+    tauri::async_runtime::spawn(async move {
+      if let Err(e) = update_bundled_mpv(&app, &mpv, &config).await {
+        AppNotification::error(&app, e.message);
+      }
+    });
+  } else if config.mpv_path != previous.mpv_path || config.bundled_mpv != previous.bundled_mpv {
+    refresh_mpv_version(&app, &mpv_state.0).await;
+  }
 
+  jellyfin_state
+    .client
+    .set_cast_capabilities(CastCapabilities::from_config(&config));
   // Apply Jellyfin device name change if connected
   if jellyfin_state.client.login().is_connected() {
     jellyfin_state
       .client
       .set_device_name(config.device_name.clone());
-    // Re-register capabilities with new device name
+    // Re-register capabilities with new device name and cast capabilities
     if let Err(e) = jellyfin_state.client.playback().report_capabilities().await {
       log::warn!("Failed to re-register capabilities: {}", e);
     } else {
@@ -1071,27 +1845,44 @@ pub async fn config_set(
   }
 
   // Update MPV keybindings file (blocking I/O, run in spawn_blocking)
-  let keybind_next = config.keybind_next.clone();
-  let keybind_prev = config.keybind_prev.clone();
-  let keybind_intro_skip = config.keybind_intro_skip.clone();
+  let input_bindings = config.input_bindings();
+  let changed = changed_bindings(&previous_bindings, &input_bindings);
+  if !changed.is_empty() && mpv_state.0.is_connected() {
+    let restart_required = mpv_state.0.apply_input_bindings_live(&changed).await;
+    if !restart_required.is_empty() {
+      AppNotification::warning(
+        &app,
+        tr!(
+          "notify-mpv-restart-to-apply",
+          args = restart_required.join(" ")
+        ),
+      );
+    }
+  }
   tauri::async_runtime::spawn_blocking(move || {
-    write_input_conf(&keybind_next, &keybind_prev, &keybind_intro_skip);
+    write_input_conf(&input_bindings);
   })
   .await
   .map_err(|e| CommandError::internal(format!("Failed to write input.conf: {}", e)))?;
 
   // Persist to disk
+  save_config(&app, &config)?;
+
+  log::info!("Config saved to disk");
+  Ok(())
+}
+
+fn save_config(app: &tauri::AppHandle, config: &AppConfig) -> Result<(), CommandError> {
+  use tauri_plugin_store::StoreExt;
+
   let store = app.store(CONFIG_STORE_FILE).map_err(internal_err)?;
   store.set(
     CONFIG_STORE_KEY.to_string(),
-    serde_json::to_value(&config).map_err(internal_err)?,
+    serde_json::to_value(config).map_err(internal_err)?,
   );
   // Note: store.save() is synchronous but typically fast for small configs.
   // For larger data, consider spawn_blocking.
-  store.save().map_err(internal_err)?;
-
-  log::info!("Config saved to disk");
-  Ok(())
+  store.save().map_err(internal_err)
 }
 
 /// Get the default configuration.
@@ -1112,6 +1903,380 @@ pub fn config_detect_mpv() -> Option<String> {
   })
 }
 
+/// How long `config_capture_keybind` waits for a key.
+const KEYBIND_CAPTURE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(15);
+
+/// Record the next key pressed in the MPV window, in MPV's key syntax for
+/// shortcut settings. MPV is started for it when none runs, and quit again
+/// afterwards. Returns null when Escape was pressed or no key came.
+#[tauri::command]
+#[specta]
+pub async fn config_capture_keybind(
+  state: State<'_, MpvState>,
+) -> Result<Option<String>, CommandError> {
+  let started = !state.0.is_connected();
+  if started {
+    state.0.start().await.map_err(internal_err)?;
+  }
+  if let Err(e) = state
+    .0
+    .show_text(
+      &tr!("osd-press-key-to-bind"),
+      KEYBIND_CAPTURE_TIMEOUT.as_millis() as i64,
+    )
+    .await
+  {
+    log::warn!("Failed to show the key capture prompt: {}", e);
+  }
+  let captured = state.0.capture_key(KEYBIND_CAPTURE_TIMEOUT).await;
+  let _ = state.0.show_text("", 1).await;
+  if started {
+    state.0.stop().await;
+  }
+  captured.map_err(internal_err)
+}
+
+// ============================================================================
+// Settings Lock
+// ============================================================================
+
+/// Settings unlock state managed by Tauri.
+#[derive(Default)]
+pub struct SettingsLockState(pub parking_lot::Mutex<SettingsLock>);
+
+/// Reject the call while a settings PIN is set and was not entered recently.
+fn require_settings_unlocked(
+  config_state: &ConfigState,
+  lock_state: &SettingsLockState,
+) -> Result<(), CommandError> {
+  let pin = config_state.0.read().settings_pin.clone();
+  if lock_state
+    .0
+    .lock()
+    .is_unlocked(pin.as_ref(), Instant::now())
+  {
+    Ok(())
+  } else {
+    Err(CommandError::auth_failed(
+      "Settings are locked. Enter the PIN to continue.",
+    ))
+  }
+}
+
+/// Whether a settings PIN is set and whether settings are currently unlocked.
+#[tauri::command]
+#[specta]
+pub fn settings_lock_status(
+  config_state: State<'_, ConfigState>,
+  lock_state: State<'_, SettingsLockState>,
+) -> SettingsLockStatus {
+  let pin = config_state.0.read().settings_pin.clone();
+  lock_state.0.lock().status(pin.as_ref(), Instant::now())
+}
+
+/// Unlock settings and destructive actions for a few minutes.
+#[tauri::command]
+#[specta]
+pub async fn settings_unlock(
+  config_state: State<'_, ConfigState>,
+  lock_state: State<'_, SettingsLockState>,
+  pin: String,
+) -> Result<(), CommandError> {
+  let stored = config_state.0.read().settings_pin.clone();
+  let begun = lock_state.0.lock().begin_unlock(Instant::now());
+  let result = match (begun, stored) {
+    (Ok(()), Some(stored)) => {
+      // Hashing the entered PIN takes a moment; keep it off the runtime.
+      let matches = tauri::async_runtime::spawn_blocking(move || stored.verify(&pin))
+        .await
+        .map_err(internal_err)?;
+      if matches {
+        Ok(())
+      } else {
+        Err(UnlockError::WrongPin)
+      }
+    }
+    (begun, _) => begun,
+  };
+  match result {
+    Ok(()) => {
+      lock_state.0.lock().unlocked(Instant::now());
+      Ok(())
+    }
+    Err(UnlockError::WrongPin) => {
+      log::warn!("Settings unlock failed: wrong PIN");
+      Err(CommandError::auth_failed("Incorrect PIN"))
+    }
+    Err(UnlockError::Throttled { retry_in }) => {
+      log::warn!("Settings unlock refused: too many wrong PINs");
+      Err(CommandError::auth_failed(format!(
+        "Too many incorrect PINs. Try again in {} seconds.",
+        retry_in.as_secs().max(1)
+      )))
+    }
+  }
+}
+
+/// Lock settings again before the unlock expires.
+#[tauri::command]
+#[specta]
+pub fn settings_lock(lock_state: State<'_, SettingsLockState>) {
+  lock_state.0.lock().lock();
+}
+
+/// Set a new settings PIN, or remove the lock with `None`. Requires settings
+/// to be unlocked when a PIN is already set.
+#[tauri::command]
+#[specta]
+pub async fn settings_set_pin(
+  app: tauri::AppHandle,
+  config_state: State<'_, ConfigState>,
+  lock_state: State<'_, SettingsLockState>,
+  pin: Option<String>,
+) -> Result<(), CommandError> {
+  require_settings_unlocked(&config_state, &lock_state)?;
+  let settings_pin = match pin {
+    Some(pin) => Some(
+      tauri::async_runtime::spawn_blocking(move || SettingsPin::new(&pin))
+        .await
+        .map_err(internal_err)?
+        .map_err(CommandError::invalid_input)?,
+    ),
+    None => None,
+  };
+
+  let config = {
+    let mut config = config_state.0.write();
+    config.settings_pin = settings_pin;
+    config.clone()
+  };
+  save_config(&app, &config)?;
+  log::info!(
+    "Settings PIN {}",
+    if config.settings_pin.is_some() {
+      "set"
+    } else {
+      "removed"
+    }
+  );
+  Ok(())
+}
+
+// ============================================================================
+// Track Preferences
+// ============================================================================
+
+/// Audio and subtitle choice remembered from track changes during playback.
+///
+/// Sent back unchanged apart from the track fields to edit an entry; `scope`
+/// and `label` are ignored then.
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedTrackPreference {
+  /// Key passed to `preferences_delete`.
+  pub key: String,
+  pub scope: TrackPreferenceScope,
+  /// Series or library name, when known.
+  pub label: Option<String>,
+  pub audio_language: Option<String>,
+  pub audio_title: Option<String>,
+  pub subtitle_language: Option<String>,
+  pub subtitle_title: Option<String>,
+  /// Whether subtitles are turned on; None when no subtitle choice was saved.
+  pub subtitles_enabled: Option<bool>,
+}
+
+impl SavedTrackPreference {
+  fn new(key: String, preference: TrackPreference) -> Self {
+    Self {
+      scope: scope_of_key(&key),
+      key,
+      label: preference.label,
+      audio_language: preference.audio_language,
+      audio_title: preference.audio_title,
+      subtitle_language: preference.subtitle_language,
+      subtitle_title: preference.subtitle_title,
+      subtitles_enabled: preference
+        .subtitle_preference_set
+        .then_some(preference.is_subtitle_enabled),
+    }
+  }
+
+  fn into_track_preference(self) -> TrackPreference {
+    TrackPreference {
+      audio_language: self.audio_language,
+      audio_title: self.audio_title,
+      subtitle_language: self.subtitle_language,
+      subtitle_title: self.subtitle_title,
+      subtitle_preference_set: self.subtitles_enabled.is_some(),
+      is_subtitle_enabled: self.subtitles_enabled.unwrap_or(false),
+      label: None,
+    }
+  }
+}
+
+fn preferences_session(state: &JellyfinState) -> Result<Arc<SessionManager>, CommandError> {
+  state
+    .session
+    .read()
+    .clone()
+    .ok_or_else(|| CommandError::not_connected("Track preferences require an active session"))
+}
+
+/// List saved track preferences, naming series that were saved without a name.
+#[tauri::command]
+#[specta]
+pub async fn preferences_list(
+  state: State<'_, JellyfinState>,
+) -> Result<Vec<SavedTrackPreference>, CommandError> {
+  let session = preferences_session(&state)?;
+  session.resolve_track_preference_labels().await;
+  Ok(
+    session
+      .track_preferences()
+      .into_iter()
+      .map(|(key, preference)| SavedTrackPreference::new(key, preference))
+      .collect(),
+  )
+}
+
+/// Replace the audio and subtitle choice of a saved track preference.
+#[tauri::command]
+#[specta]
+pub async fn preferences_update(
+  state: State<'_, JellyfinState>,
+  config_state: State<'_, ConfigState>,
+  lock_state: State<'_, SettingsLockState>,
+  preference: SavedTrackPreference,
+) -> Result<(), CommandError> {
+  require_settings_unlocked(&config_state, &lock_state)?;
+  let session = preferences_session(&state)?;
+  let key = preference.key.clone();
+  if !session.update_track_preference(&key, preference.into_track_preference()) {
+    return Err(CommandError::not_found(format!(
+      "No saved track preference for {}",
+      key
+    )));
+  }
+  Ok(())
+}
+
+/// Delete a saved track preference by key.
+#[tauri::command]
+#[specta]
+pub async fn preferences_delete(
+  state: State<'_, JellyfinState>,
+  config_state: State<'_, ConfigState>,
+  lock_state: State<'_, SettingsLockState>,
+  key: String,
+) -> Result<(), CommandError> {
+  require_settings_unlocked(&config_state, &lock_state)?;
+  let session = preferences_session(&state)?;
+  if !session.delete_track_preference(&key) {
+    return Err(CommandError::not_found(format!(
+      "No saved track preference for {}",
+      key
+    )));
+  }
+  Ok(())
+}
+
+// ============================================================================
+// Diagnostics
+// ============================================================================
+
+/// Check MPV, the IPC socket location and the server connection, returning
+/// a checklist with a fix-it hint for every problem.
+#[tauri::command]
+#[specta]
+pub async fn diagnostics_run_checks(
+  config_state: State<'_, ConfigState>,
+  jellyfin_state: State<'_, JellyfinState>,
+) -> Result<Vec<SelfCheck>, CommandError> {
+  let config = config_state.0.read().clone();
+  let session = jellyfin_state.session.read().clone();
+  Ok(self_check::run_checks(&config, &jellyfin_state.client, session.as_deref()).await)
+}
+
+/// Bundle app logs, MPV logs, redacted config and recent MPV commands into a zip.
+///
+/// Writes to `destination` when given, otherwise to the downloads folder.
+/// Returns the path of the written file.
+#[tauri::command]
+#[specta]
+pub async fn diagnostics_export(
+  app: tauri::AppHandle,
+  config_state: State<'_, ConfigState>,
+  mpv_state: State<'_, MpvState>,
+  jellyfin_state: State<'_, JellyfinState>,
+  destination: Option<String>,
+) -> Result<String, CommandError> {
+  use std::path::PathBuf;
+  use tauri::Manager;
+
+  let destination = match destination.filter(|s| !s.is_empty()) {
+    Some(path) => PathBuf::from(path),
+    None => app
+      .path()
+      .download_dir()
+      .or_else(|_| app.path().app_data_dir())
+      .map_err(internal_err)?
+      .join(diagnostics::bundle_file_name()),
+  };
+  let sources = diagnostics::DiagnosticsSources {
+    app_log_dir: app.path().app_log_dir().ok(),
+    mpv_logs: session_logs(),
+    config: config_state.0.read().clone(),
+    command_audit: mpv_state.0.command_audit(),
+    playback_duration: jellyfin_state
+      .session
+      .read()
+      .as_ref()
+      .and_then(|session| session.duration_report()),
+  };
+
+  let bundle_path = destination.clone();
+  tauri::async_runtime::spawn_blocking(move || diagnostics::write_bundle(&bundle_path, &sources))
+    .await
+    .map_err(internal_err)?
+    .map_err(|e| CommandError::internal(format!("Failed to write diagnostics bundle: {}", e)))?;
+
+  log::info!("Diagnostics bundle written to {:?}", destination);
+  Ok(destination.to_string_lossy().to_string())
+}
+
+/// Entries and estimated size of every bounded record JellyPilot keeps
+/// while running, such as the MPV command audit and session logs.
+#[tauri::command]
+#[specta]
+pub fn diagnostics_memory_report(
+  mpv_state: State<'_, MpvState>,
+  jellyfin_state: State<'_, JellyfinState>,
+) -> Vec<BufferUsage> {
+  let mut report = mpv_state.0.memory_usage();
+  report.push(jellyfin_state.client.response_cache_usage());
+  report
+}
+
+// ============================================================================
+// Statistics
+// ============================================================================
+
+/// Bytes downloaded in the current or last playback session and since startup.
+#[tauri::command]
+#[specta]
+pub fn stats_get_bandwidth(state: State<'_, JellyfinState>) -> BandwidthStats {
+  state.bandwidth.read().stats(std::time::Instant::now())
+}
+
+/// The anonymous usage statistics the next report would send, so users can
+/// see them before opting in.
+#[tauri::command]
+#[specta]
+pub fn telemetry_preview(state: State<'_, MpvState>) -> TelemetryReport {
+  telemetry::report(&state.0)
+}
+
 /// Load config from disk. Called internally during app setup.
 pub fn load_config_from_store(app: &tauri::AppHandle) -> AppConfig {
   use tauri_plugin_store::StoreExt;
@@ -1148,14 +2313,31 @@ pub fn specta_builder() -> Builder<tauri::Wry> {
       mpv_stop,
       mpv_loadfile,
       mpv_seek,
+      playback_seek_percent,
+      playback_seek_absolute,
+      playback_set_user_rating,
       mpv_set_pause,
       mpv_set_volume,
       mpv_toggle_mute,
+      playback_set_fullscreen,
+      playback_list_versions,
+      playback_switch_version,
+      playback_set_secondary_subtitle,
+      playback_set_ab_loop,
+      playback_clear_ab_loop,
+      playback_screenshot,
+      playback_get_chapters,
+      playback_goto_chapter,
+      playback_retry,
+      session_get_state,
       mpv_set_audio_track,
       mpv_set_subtitle_track,
       mpv_get_property,
       mpv_get_state,
       mpv_is_connected,
+      mpv_get_version,
+      mpv_bundled_status,
+      mpv_bundled_install,
       now_playing_get_state,
       library_video_home,
       library_video_shortcuts,
@@ -1174,6 +2356,8 @@ pub fn specta_builder() -> Builder<tauri::Wry> {
       jellyfin_get_session,
       jellyfin_restore_session,
       jellyfin_clear_session,
+      jellyfin_refresh_cast_registration,
+      jellyfin_regenerate_device_id,
       jellyfin_play_next_episode,
       jellyfin_play_previous_episode,
       jellyfin_quick_connect_start,
@@ -1187,6 +2371,14 @@ pub fn specta_builder() -> Builder<tauri::Wry> {
       server_get_session,
       server_restore_session,
       server_clear_session,
+      server_set_tls_trust,
+      server_set_custom_headers,
+      server_certificate_fingerprint,
+      server_list_other_sessions,
+      server_list_sessions,
+      server_remote_play,
+      server_remote_playstate,
+      server_remote_command,
       server_profiles_get,
       server_profiles_import_legacy,
       server_profiles_save_current,
@@ -1197,8 +2389,33 @@ pub fn specta_builder() -> Builder<tauri::Wry> {
       config_set,
       config_default,
       config_detect_mpv,
+      config_capture_keybind,
+      // Settings lock commands
+      settings_lock_status,
+      settings_unlock,
+      settings_lock,
+      settings_set_pin,
+      // Track preference commands
+      preferences_list,
+      preferences_update,
+      preferences_delete,
+      // Diagnostics commands
+      diagnostics_export,
+      diagnostics_run_checks,
+      diagnostics_memory_report,
+      // Statistics commands
+      stats_get_bandwidth,
+      telemetry_preview,
     ])
-    .events(collect_events![AppNotification, NowPlayingChanged]);
+    .events(collect_events![
+      AppNotification,
+      BandwidthUpdated,
+      ConnectionEvent,
+      NowPlayingChanged,
+      PlaybackError,
+      SessionExpired,
+      UpdateAvailable
+    ]);
 
   #[cfg(debug_assertions)] // <- Only export on non-release builds
   {
@@ -1222,6 +2439,12 @@ mod tests {
     let err = jellyfin_err(JellyfinError::AuthFailed("revoked".to_string()));
 
     assert!(matches!(err.code, CommandErrorCode::AuthFailed));
+
+    let err = jellyfin_err(JellyfinError::CredentialsExpired("revoked".to_string()));
+    assert!(matches!(err.code, CommandErrorCode::AuthFailed));
+
+    let err = jellyfin_err(JellyfinError::InvalidCredentials("wrong".to_string()));
+    assert!(matches!(err.code, CommandErrorCode::AuthFailed));
   }
 
   #[test]
@@ -1242,6 +2465,35 @@ mod tests {
     assert!(err.message.contains("Unable to discover Emby API base URL"));
   }
 
+  #[test]
+  fn jellyfin_err_maps_playback_failures_by_their_cause() {
+    let err =
+      jellyfin_err(JellyfinError::NotConnected.at_playback_stage(PlaybackErrorStage::StreamUrl));
+
+    assert!(matches!(err.code, CommandErrorCode::NotConnected));
+  }
+
+  #[test]
+  fn saved_track_preference_round_trips_subtitle_choice() {
+    let preference = TrackPreference {
+      audio_language: Some("jpn".to_string()),
+      subtitle_language: Some("eng".to_string()),
+      subtitle_preference_set: true,
+      is_subtitle_enabled: false,
+      label: Some("Show".to_string()),
+      ..Default::default()
+    };
+
+    let saved = SavedTrackPreference::new("series-1".to_string(), preference);
+    assert!(matches!(saved.scope, TrackPreferenceScope::Series));
+    assert_eq!(saved.subtitles_enabled, Some(false));
+
+    let restored = saved.into_track_preference();
+    assert!(restored.subtitle_preference_set);
+    assert!(!restored.is_subtitle_enabled);
+    assert_eq!(restored.audio_language.as_deref(), Some("jpn"));
+  }
+
   #[test]
   fn export_bindings() {
     // This test triggers binding generation
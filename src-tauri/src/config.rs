@@ -1,8 +1,22 @@
 //! Application configuration with persistence.
 
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
 use serde::{Deserialize, Deserializer, Serialize};
 use specta::Type;
 
+use crate::i18n::Language;
+use crate::jellyfin::{CastMediaType, RemoteCommand};
+use crate::mpv::{
+  builtin_input_bindings, bundled_mpv_path, default_command_timeouts, is_valid_key,
+  overridden_required_option, CommandTimeouts, DisplayServerMode, FullscreenOptions, InputBinding,
+  ManagedMpvConfig, ShortcutKeys, DEFAULT_AUDIT_RETENTION, DEFAULT_COMMAND_TIMEOUT_SECONDS,
+  DEFAULT_MPV_LOG_RETENTION,
+};
+use crate::settings_lock::SettingsPin;
+use crate::webhooks::Webhook;
+
 /// Intro Skipper behavior mode.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
 #[serde(rename_all = "camelCase")]
@@ -12,6 +26,89 @@ pub enum IntroSkipperMode {
   Off,
 }
 
+/// How far playback continues on its own when an episode ends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum AutoNextScope {
+  Off,
+  SameSeason,
+  SameSeries,
+}
+
+/// Which items share remembered audio and subtitle choices.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum TrackPreferenceScope {
+  /// Each series keeps its own choices.
+  #[default]
+  Series,
+  /// All items in the same library share choices.
+  Library,
+  /// One set of choices for everything.
+  Global,
+}
+
+/// How remote volume (0-100) is spread over MPV's volume range.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum VolumeCurve {
+  /// Volume scales proportionally.
+  #[default]
+  Linear,
+  /// Quadratic: finer steps at low volume, closer to perceived loudness.
+  Soft,
+}
+
+/// What happens to MPV when playback is stopped from Jellyfin.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum MpvStopBehavior {
+  /// Quit MPV; the next cast starts a new one.
+  #[default]
+  Quit,
+  /// Stop playback and leave the idle MPV window open, so the next cast starts faster.
+  KeepIdle,
+  /// Stop playback and close the window while MPV keeps running in the background.
+  HideWindow,
+}
+
+/// Whether an idle MPV is kept ready for the first cast.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum MpvWarmStart {
+  /// Start MPV on the first cast.
+  #[default]
+  Off,
+  /// Start MPV at launch without a window.
+  Hidden,
+  /// Start MPV at launch with its window minimized.
+  Minimized,
+}
+
+/// Which version to play when an item has several media sources.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum MediaVersionPreference {
+  /// The first version the server lists.
+  #[default]
+  ServerDefault,
+  HighestBitrate,
+  LowestBitrate,
+  /// The version closest to the preferred video height without exceeding it.
+  PreferredResolution,
+}
+
+/// What happens on the device that was playing an item when it is cast to JellyPilot.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum CastTakeover {
+  /// Leave the other device alone.
+  #[default]
+  Off,
+  Pause,
+  Stop,
+}
+
 /// Application configuration.
 #[derive(Debug, Clone, Serialize, Type)]
 #[serde(rename_all = "camelCase")]
@@ -24,10 +121,18 @@ pub struct AppConfig {
   #[serde(default)]
   pub mpv_args: Vec<String>,
 
+  /// Download and use a JellyPilot-managed MPV when no MPV path is set.
+  #[serde(default)]
+  pub bundled_mpv: bool,
+
   /// Device name shown in Jellyfin cast menu.
   #[serde(default = "default_device_name")]
   pub device_name: String,
 
+  /// Language of notifications, MPV on-screen messages and the tray menu.
+  #[serde(default)]
+  pub language: Language,
+
   /// Progress reporting interval in seconds.
   #[serde(default = "default_progress_interval")]
   pub progress_interval: u32,
@@ -40,6 +145,16 @@ pub struct AppConfig {
   #[serde(default = "default_intro_skipper_mode")]
   pub intro_skipper_mode: IntroSkipperMode,
 
+  /// Treat a "Credits"/"Ending" chapter as the credits of episodes that have
+  /// no Intro Skipper credits range.
+  #[serde(default)]
+  pub credits_fallback: bool,
+
+  /// With `credits_fallback`, treat this many final seconds as credits when
+  /// no chapter is named like them (None = chapters only).
+  #[serde(default)]
+  pub credits_fallback_seconds: Option<u32>,
+
   /// Ordered subtitle language codes to prefer when Jellyfin does not request a track.
   #[serde(default)]
   pub preferred_subtitle_languages: Vec<String>,
@@ -59,6 +174,253 @@ pub struct AppConfig {
   /// Keybinding for manual Intro Skipper seek in MPV.
   #[serde(default = "default_keybind_intro_skip")]
   pub keybind_intro_skip: String,
+
+  /// Keybinding that turns automatic intro skipping on or off for this MPV session.
+  #[serde(default = "default_keybind_toggle_intro_skip")]
+  pub keybind_toggle_intro_skip: String,
+
+  /// Keybinding that marks the playing item watched or unwatched.
+  #[serde(default = "default_keybind_toggle_watched")]
+  pub keybind_toggle_watched: String,
+
+  /// Keybinding that adds the playing item to the favorites or removes it.
+  #[serde(default = "default_keybind_toggle_favorite")]
+  pub keybind_toggle_favorite: String,
+
+  /// Keybinding that raises the playback speed.
+  #[serde(default = "default_keybind_speed_up")]
+  pub keybind_speed_up: String,
+
+  /// Keybinding that lowers the playback speed.
+  #[serde(default = "default_keybind_speed_down")]
+  pub keybind_speed_down: String,
+
+  /// Keybinding that toggles MPV's stats overlay.
+  #[serde(default = "default_keybind_stats")]
+  pub keybind_stats: String,
+
+  /// Keybinding that stops playback and leaves MPV idle.
+  #[serde(default = "default_keybind_stop_idle")]
+  pub keybind_stop_idle: String,
+
+  /// Keybinding that cycles the secondary subtitle through the item's streams.
+  #[serde(default = "default_keybind_secondary_subtitle")]
+  pub keybind_secondary_subtitle: String,
+
+  /// Keybinding that sets the A-B loop start, then its end, then clears it.
+  #[serde(default = "default_keybind_ab_loop")]
+  pub keybind_ab_loop: String,
+
+  /// Keybinding that saves a screenshot named after the playing item.
+  #[serde(default = "default_keybind_screenshot")]
+  pub keybind_screenshot: String,
+
+  /// Extra MPV keybindings, mapping a key to the MPV input command it runs
+  /// (e.g. `"Ctrl+n": "script-message jellypilot-next"`).
+  #[serde(default)]
+  pub custom_bindings: BTreeMap<String, String>,
+
+  /// Start MPV with a JellyPilot-generated mpv.conf instead of the user's own config.
+  #[serde(default)]
+  pub managed_mpv_config: bool,
+
+  /// Demuxer cache size in MiB for the managed mpv.conf (None = MPV default).
+  #[serde(default)]
+  pub mpv_cache_size_mib: Option<u32>,
+
+  /// Screen index MPV opens on for the managed mpv.conf (None = MPV default).
+  #[serde(default)]
+  pub mpv_screen: Option<u32>,
+
+  /// Open MPV fullscreen with the managed mpv.conf.
+  #[serde(default)]
+  pub mpv_fullscreen: bool,
+
+  /// Show MPV's on-screen controller with the managed mpv.conf.
+  #[serde(default = "default_mpv_osc")]
+  pub mpv_osc: bool,
+
+  /// Switch MPV to fullscreen whenever new playback starts.
+  #[serde(default)]
+  pub fullscreen_on_playback: bool,
+
+  /// Monitor index used for fullscreen (None = MPV default).
+  #[serde(default)]
+  pub fullscreen_screen: Option<u32>,
+
+  /// Switch to fullscreen when playback is cast from another device, such as a phone.
+  #[serde(default)]
+  pub fullscreen_remote_casts: bool,
+
+  /// Wayland/X11 window options for MPV on Linux (auto-detected by default).
+  #[serde(default)]
+  pub mpv_display_server: DisplayServerMode,
+
+  /// What happens to MPV when playback is stopped from Jellyfin.
+  #[serde(default)]
+  pub mpv_stop_behavior: MpvStopBehavior,
+
+  /// Start MPV at launch and again whenever none runs between playbacks.
+  #[serde(default)]
+  pub mpv_warm_start: MpvWarmStart,
+
+  /// Seconds MPV gets to answer an IPC command.
+  #[serde(default = "default_mpv_command_timeout_seconds")]
+  pub mpv_command_timeout_seconds: u32,
+
+  /// Timeouts in seconds for single IPC commands by name (e.g. `loadfile`),
+  /// replacing the default for them.
+  #[serde(default = "default_command_timeouts")]
+  pub mpv_command_timeouts: BTreeMap<String, u32>,
+
+  /// Take over an MPV a crashed JellyPilot left running instead of quitting it.
+  #[serde(default)]
+  pub adopt_orphaned_mpv: bool,
+
+  /// Let MPV keep playing when JellyPilot crashes instead of ending with it.
+  #[serde(default)]
+  pub mpv_outlives_app: bool,
+
+  /// Recent MPV commands kept for diagnostics bundles.
+  #[serde(default = "default_command_audit_retention")]
+  pub command_audit_retention: u32,
+
+  /// MPV session logs kept on disk, including the current one.
+  #[serde(default = "default_mpv_log_retention")]
+  pub mpv_log_retention: u32,
+
+  /// Step over specials (season 0) when moving to the next or previous episode.
+  #[serde(default)]
+  pub skip_specials: bool,
+
+  /// Where automatic playback of the next episode may continue.
+  #[serde(default = "default_auto_next_scope")]
+  pub auto_next_scope: AutoNextScope,
+
+  /// Let automatic playback continue into specials (season 0).
+  #[serde(default = "default_auto_next_specials")]
+  pub auto_next_specials: bool,
+
+  /// Start the next item when the credits begin instead of at the end of the file.
+  #[serde(default)]
+  pub auto_next_at_credits: bool,
+
+  /// Seconds of "Up next" countdown shown over the credits first (None = no countdown).
+  #[serde(default)]
+  pub auto_next_countdown_seconds: Option<u32>,
+
+  /// Record the raw payload of WebSocket messages that fail to parse and warn about them.
+  #[serde(default)]
+  pub websocket_parse_diagnostics: bool,
+
+  /// OS notification when playback starts, with the item's artwork.
+  #[serde(default)]
+  pub os_notify_playback_started: bool,
+
+  /// OS notification when the next episode or queue item starts on its own.
+  #[serde(default)]
+  pub os_notify_auto_next: bool,
+
+  /// OS notifications when the server connection is lost and restored.
+  #[serde(default)]
+  pub os_notify_connection: bool,
+
+  /// OS notification when MPV exits unexpectedly during playback.
+  #[serde(default)]
+  pub os_notify_mpv_crash: bool,
+
+  /// Serve Prometheus metrics on 127.0.0.1 at this port (None = off).
+  #[serde(default)]
+  pub metrics_port: Option<u16>,
+
+  /// Receivers of playback start/pause/resume/stop/auto-next events.
+  #[serde(default)]
+  pub webhooks: Vec<Webhook>,
+
+  /// Serve the local control API on 127.0.0.1 at this port (None = off).
+  #[serde(default)]
+  pub control_api_port: Option<u16>,
+
+  /// Bearer token the control API requires; generated when the API is enabled.
+  #[serde(default)]
+  pub control_api_token: String,
+
+  /// MPV volume reached at remote volume 100; MPV is never allowed above it.
+  #[serde(default = "default_max_volume")]
+  pub max_volume: u32,
+
+  /// How remote volume maps onto MPV volume below the cap.
+  #[serde(default)]
+  pub volume_curve: VolumeCurve,
+
+  /// Which items share track changes made during playback.
+  #[serde(default)]
+  pub track_preference_scope: TrackPreferenceScope,
+
+  /// Which version plays when a Play request doesn't name one.
+  #[serde(default)]
+  pub media_version_preference: MediaVersionPreference,
+
+  /// Video height in pixels used by the preferred-resolution version choice.
+  #[serde(default = "default_preferred_video_height")]
+  pub preferred_video_height: u32,
+
+  /// Seconds MPV may wait for the network before a stall warning (None = no warnings).
+  #[serde(default = "default_stall_warning_seconds")]
+  pub stall_warning_seconds: Option<u32>,
+
+  /// Reload as a lower-bitrate transcode when stall warnings keep recurring.
+  #[serde(default)]
+  pub lower_bitrate_on_stalls: bool,
+
+  /// Pause or stop the user's other session that was playing an item cast here.
+  #[serde(default)]
+  pub cast_takeover: CastTakeover,
+
+  /// PIN guarding settings and destructive actions (None = no lock). Only
+  /// changed through `settings_set_pin`; `config_set` keeps the stored value
+  /// and `config_get` leaves it out.
+  #[serde(default)]
+  pub settings_pin: Option<SettingsPin>,
+
+  /// Look for newer releases on GitHub. Off for distribution packages,
+  /// which update through the package manager.
+  #[serde(default = "default_check_for_updates")]
+  pub check_for_updates: bool,
+
+  /// The user agreed to send anonymous usage statistics.
+  #[serde(default)]
+  pub telemetry_consent: bool,
+
+  /// http(s) URL the usage statistics are posted to.
+  #[serde(default)]
+  pub telemetry_endpoint: String,
+
+  /// Log the usage statistics instead of sending them.
+  #[serde(default)]
+  pub telemetry_dry_run: bool,
+
+  /// Seconds to go back when resuming a saved position or after a long pause
+  /// (None = resume where playback stopped).
+  #[serde(default)]
+  pub resume_rewind_seconds: Option<u32>,
+
+  /// Pauses at least this many seconds long rewind on resume.
+  #[serde(default = "default_resume_rewind_after_pause_seconds")]
+  pub resume_rewind_after_pause_seconds: u32,
+
+  /// Folder screenshots are saved to (None = `JellyPilot` in the pictures
+  /// folder).
+  #[serde(default)]
+  pub screenshot_dir: Option<String>,
+
+  /// Media types the server may cast to JellyPilot.
+  #[serde(default = "default_cast_media_types")]
+  pub cast_media_types: Vec<CastMediaType>,
+
+  /// Remote controls offered to other clients while JellyPilot plays.
+  #[serde(default = "default_cast_commands")]
+  pub cast_commands: Vec<RemoteCommand>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -68,8 +430,12 @@ struct AppConfigWire {
   mpv_path: Option<String>,
   #[serde(default)]
   mpv_args: Vec<String>,
+  #[serde(default)]
+  bundled_mpv: bool,
   #[serde(default = "default_device_name")]
   device_name: String,
+  #[serde(default)]
+  language: Language,
   #[serde(default = "default_progress_interval")]
   progress_interval: u32,
   #[serde(default)]
@@ -79,6 +445,10 @@ struct AppConfigWire {
   #[serde(default)]
   intro_skipper_enabled: Option<bool>,
   #[serde(default)]
+  credits_fallback: bool,
+  #[serde(default)]
+  credits_fallback_seconds: Option<u32>,
+  #[serde(default)]
   preferred_subtitle_languages: Vec<String>,
   #[serde(default = "default_image_disk_cache_enabled")]
   image_disk_cache_enabled: bool,
@@ -88,6 +458,126 @@ struct AppConfigWire {
   keybind_prev: String,
   #[serde(default = "default_keybind_intro_skip")]
   keybind_intro_skip: String,
+  #[serde(default = "default_keybind_toggle_intro_skip")]
+  keybind_toggle_intro_skip: String,
+  #[serde(default = "default_keybind_toggle_watched")]
+  keybind_toggle_watched: String,
+  #[serde(default = "default_keybind_toggle_favorite")]
+  keybind_toggle_favorite: String,
+  #[serde(default = "default_keybind_speed_up")]
+  keybind_speed_up: String,
+  #[serde(default = "default_keybind_speed_down")]
+  keybind_speed_down: String,
+  #[serde(default = "default_keybind_stats")]
+  keybind_stats: String,
+  #[serde(default = "default_keybind_stop_idle")]
+  keybind_stop_idle: String,
+  #[serde(default = "default_keybind_secondary_subtitle")]
+  keybind_secondary_subtitle: String,
+  #[serde(default = "default_keybind_ab_loop")]
+  keybind_ab_loop: String,
+  #[serde(default = "default_keybind_screenshot")]
+  keybind_screenshot: String,
+  #[serde(default)]
+  custom_bindings: BTreeMap<String, String>,
+  #[serde(default)]
+  managed_mpv_config: bool,
+  #[serde(default)]
+  mpv_cache_size_mib: Option<u32>,
+  #[serde(default)]
+  mpv_screen: Option<u32>,
+  #[serde(default)]
+  mpv_fullscreen: bool,
+  #[serde(default = "default_mpv_osc")]
+  mpv_osc: bool,
+  #[serde(default)]
+  fullscreen_on_playback: bool,
+  #[serde(default)]
+  fullscreen_screen: Option<u32>,
+  #[serde(default)]
+  fullscreen_remote_casts: bool,
+  #[serde(default)]
+  mpv_display_server: DisplayServerMode,
+  #[serde(default)]
+  mpv_stop_behavior: MpvStopBehavior,
+  #[serde(default)]
+  mpv_warm_start: MpvWarmStart,
+  #[serde(default = "default_mpv_command_timeout_seconds")]
+  mpv_command_timeout_seconds: u32,
+  #[serde(default = "default_command_timeouts")]
+  mpv_command_timeouts: BTreeMap<String, u32>,
+  #[serde(default)]
+  adopt_orphaned_mpv: bool,
+  #[serde(default)]
+  mpv_outlives_app: bool,
+  #[serde(default = "default_command_audit_retention")]
+  command_audit_retention: u32,
+  #[serde(default = "default_mpv_log_retention")]
+  mpv_log_retention: u32,
+  #[serde(default)]
+  skip_specials: bool,
+  #[serde(default = "default_auto_next_scope")]
+  auto_next_scope: AutoNextScope,
+  #[serde(default = "default_auto_next_specials")]
+  auto_next_specials: bool,
+  #[serde(default)]
+  auto_next_at_credits: bool,
+  #[serde(default)]
+  auto_next_countdown_seconds: Option<u32>,
+  #[serde(default)]
+  websocket_parse_diagnostics: bool,
+  #[serde(default)]
+  os_notify_playback_started: bool,
+  #[serde(default)]
+  os_notify_auto_next: bool,
+  #[serde(default)]
+  os_notify_connection: bool,
+  #[serde(default)]
+  os_notify_mpv_crash: bool,
+  #[serde(default)]
+  metrics_port: Option<u16>,
+  #[serde(default)]
+  webhooks: Vec<Webhook>,
+  #[serde(default)]
+  control_api_port: Option<u16>,
+  #[serde(default)]
+  control_api_token: String,
+  #[serde(default = "default_max_volume")]
+  max_volume: u32,
+  #[serde(default)]
+  volume_curve: VolumeCurve,
+  #[serde(default)]
+  track_preference_scope: TrackPreferenceScope,
+  #[serde(default)]
+  media_version_preference: MediaVersionPreference,
+  #[serde(default = "default_preferred_video_height")]
+  preferred_video_height: u32,
+  #[serde(default = "default_stall_warning_seconds")]
+  stall_warning_seconds: Option<u32>,
+  #[serde(default)]
+  lower_bitrate_on_stalls: bool,
+  #[serde(default)]
+  cast_takeover: CastTakeover,
+  #[serde(default)]
+  settings_pin: Option<SettingsPin>,
+  #[serde(default = "default_check_for_updates")]
+  check_for_updates: bool,
+  #[serde(default)]
+  telemetry_consent: bool,
+  #[serde(default)]
+  telemetry_endpoint: String,
+  #[serde(default)]
+  telemetry_dry_run: bool,
+  #[serde(default)]
+  resume_rewind_seconds: Option<u32>,
+  #[serde(default = "default_resume_rewind_after_pause_seconds")]
+  resume_rewind_after_pause_seconds: u32,
+  #[serde(default)]
+  screenshot_dir: Option<String>,
+  #[serde(default = "default_cast_media_types")]
+  cast_media_types: Vec<CastMediaType>,
+  #[serde(default = "default_cast_commands")]
+  cast_commands: Vec<RemoteCommand>,
 }
 
 impl<'de> Deserialize<'de> for AppConfig {
@@ -107,15 +597,79 @@ impl<'de> Deserialize<'de> for AppConfig {
     Ok(Self {
       mpv_path: wire.mpv_path,
       mpv_args: wire.mpv_args,
+      bundled_mpv: wire.bundled_mpv,
       device_name: wire.device_name,
+      language: wire.language,
       progress_interval: wire.progress_interval,
       start_minimized: wire.start_minimized,
       intro_skipper_mode,
+      credits_fallback: wire.credits_fallback,
+      credits_fallback_seconds: wire.credits_fallback_seconds,
       preferred_subtitle_languages: wire.preferred_subtitle_languages,
       image_disk_cache_enabled: wire.image_disk_cache_enabled,
       keybind_next: wire.keybind_next,
       keybind_prev: wire.keybind_prev,
       keybind_intro_skip: wire.keybind_intro_skip,
+      keybind_toggle_intro_skip: wire.keybind_toggle_intro_skip,
+      keybind_toggle_watched: wire.keybind_toggle_watched,
+      keybind_toggle_favorite: wire.keybind_toggle_favorite,
+      keybind_speed_up: wire.keybind_speed_up,
+      keybind_speed_down: wire.keybind_speed_down,
+      keybind_stats: wire.keybind_stats,
+      keybind_stop_idle: wire.keybind_stop_idle,
+      keybind_secondary_subtitle: wire.keybind_secondary_subtitle,
+      keybind_ab_loop: wire.keybind_ab_loop,
+      keybind_screenshot: wire.keybind_screenshot,
+      custom_bindings: wire.custom_bindings,
+      managed_mpv_config: wire.managed_mpv_config,
+      mpv_cache_size_mib: wire.mpv_cache_size_mib,
+      mpv_screen: wire.mpv_screen,
+      mpv_fullscreen: wire.mpv_fullscreen,
+      mpv_osc: wire.mpv_osc,
+      fullscreen_on_playback: wire.fullscreen_on_playback,
+      fullscreen_screen: wire.fullscreen_screen,
+      fullscreen_remote_casts: wire.fullscreen_remote_casts,
+      mpv_display_server: wire.mpv_display_server,
+      mpv_stop_behavior: wire.mpv_stop_behavior,
+      mpv_warm_start: wire.mpv_warm_start,
+      mpv_command_timeout_seconds: wire.mpv_command_timeout_seconds,
+      mpv_command_timeouts: wire.mpv_command_timeouts,
+      adopt_orphaned_mpv: wire.adopt_orphaned_mpv,
+      mpv_outlives_app: wire.mpv_outlives_app,
+      command_audit_retention: wire.command_audit_retention,
+      mpv_log_retention: wire.mpv_log_retention,
+      skip_specials: wire.skip_specials,
+      auto_next_scope: wire.auto_next_scope,
+      auto_next_specials: wire.auto_next_specials,
+      auto_next_at_credits: wire.auto_next_at_credits,
+      auto_next_countdown_seconds: wire.auto_next_countdown_seconds,
+      websocket_parse_diagnostics: wire.websocket_parse_diagnostics,
+      os_notify_playback_started: wire.os_notify_playback_started,
+      os_notify_auto_next: wire.os_notify_auto_next,
+      os_notify_connection: wire.os_notify_connection,
+      os_notify_mpv_crash: wire.os_notify_mpv_crash,
+      metrics_port: wire.metrics_port,
+      webhooks: wire.webhooks,
+      control_api_port: wire.control_api_port,
+      control_api_token: wire.control_api_token,
+      max_volume: wire.max_volume,
+      volume_curve: wire.volume_curve,
+      track_preference_scope: wire.track_preference_scope,
+      media_version_preference: wire.media_version_preference,
+      preferred_video_height: wire.preferred_video_height,
+      stall_warning_seconds: wire.stall_warning_seconds,
+      lower_bitrate_on_stalls: wire.lower_bitrate_on_stalls,
+      cast_takeover: wire.cast_takeover,
+      settings_pin: wire.settings_pin,
+      check_for_updates: wire.check_for_updates,
+      telemetry_consent: wire.telemetry_consent,
+      telemetry_endpoint: wire.telemetry_endpoint,
+      telemetry_dry_run: wire.telemetry_dry_run,
+      resume_rewind_seconds: wire.resume_rewind_seconds,
+      resume_rewind_after_pause_seconds: wire.resume_rewind_after_pause_seconds,
+      screenshot_dir: wire.screenshot_dir,
+      cast_media_types: wire.cast_media_types,
+      cast_commands: wire.cast_commands,
     })
   }
 }
@@ -140,6 +694,46 @@ fn default_keybind_intro_skip() -> String {
   "g".to_string()
 }
 
+fn default_keybind_toggle_intro_skip() -> String {
+  "Alt+g".to_string()
+}
+
+fn default_keybind_toggle_watched() -> String {
+  "Alt+w".to_string()
+}
+
+fn default_keybind_toggle_favorite() -> String {
+  "Alt+f".to_string()
+}
+
+fn default_keybind_speed_up() -> String {
+  "Alt+]".to_string()
+}
+
+fn default_keybind_speed_down() -> String {
+  "Alt+[".to_string()
+}
+
+fn default_keybind_stats() -> String {
+  "Alt+i".to_string()
+}
+
+fn default_keybind_secondary_subtitle() -> String {
+  "Alt+j".to_string()
+}
+
+fn default_keybind_ab_loop() -> String {
+  "Alt+l".to_string()
+}
+
+fn default_keybind_screenshot() -> String {
+  "Alt+s".to_string()
+}
+
+fn default_keybind_stop_idle() -> String {
+  "Alt+q".to_string()
+}
+
 fn default_intro_skipper_mode() -> IntroSkipperMode {
   IntroSkipperMode::Automatic
 }
@@ -148,53 +742,508 @@ fn default_image_disk_cache_enabled() -> bool {
   true
 }
 
+fn default_mpv_osc() -> bool {
+  true
+}
+
+fn default_mpv_command_timeout_seconds() -> u32 {
+  DEFAULT_COMMAND_TIMEOUT_SECONDS
+}
+
+fn default_command_audit_retention() -> u32 {
+  DEFAULT_AUDIT_RETENTION as u32
+}
+
+fn default_mpv_log_retention() -> u32 {
+  DEFAULT_MPV_LOG_RETENTION as u32
+}
+
+fn default_auto_next_scope() -> AutoNextScope {
+  AutoNextScope::SameSeries
+}
+
+fn default_max_volume() -> u32 {
+  100
+}
+
+fn default_preferred_video_height() -> u32 {
+  1080
+}
+
+fn default_stall_warning_seconds() -> Option<u32> {
+  Some(5)
+}
+
+fn default_auto_next_specials() -> bool {
+  true
+}
+
+fn default_check_for_updates() -> bool {
+  true
+}
+
+fn default_resume_rewind_after_pause_seconds() -> u32 {
+  60
+}
+
+fn default_cast_media_types() -> Vec<CastMediaType> {
+  CastMediaType::ALL.to_vec()
+}
+
+fn default_cast_commands() -> Vec<RemoteCommand> {
+  RemoteCommand::ALL.to_vec()
+}
+
 impl Default for AppConfig {
   fn default() -> Self {
     Self {
       mpv_path: None,
       mpv_args: Vec::new(),
+      bundled_mpv: false,
       device_name: default_device_name(),
+      language: Language::default(),
       progress_interval: default_progress_interval(),
       start_minimized: false,
       intro_skipper_mode: default_intro_skipper_mode(),
+      credits_fallback: false,
+      credits_fallback_seconds: None,
       preferred_subtitle_languages: Vec::new(),
       image_disk_cache_enabled: default_image_disk_cache_enabled(),
       keybind_next: default_keybind_next(),
       keybind_prev: default_keybind_prev(),
       keybind_intro_skip: default_keybind_intro_skip(),
+      keybind_toggle_intro_skip: default_keybind_toggle_intro_skip(),
+      keybind_toggle_watched: default_keybind_toggle_watched(),
+      keybind_toggle_favorite: default_keybind_toggle_favorite(),
+      keybind_speed_up: default_keybind_speed_up(),
+      keybind_speed_down: default_keybind_speed_down(),
+      keybind_stats: default_keybind_stats(),
+      keybind_stop_idle: default_keybind_stop_idle(),
+      keybind_secondary_subtitle: default_keybind_secondary_subtitle(),
+      keybind_ab_loop: default_keybind_ab_loop(),
+      keybind_screenshot: default_keybind_screenshot(),
+      custom_bindings: BTreeMap::new(),
+      managed_mpv_config: false,
+      mpv_cache_size_mib: None,
+      mpv_screen: None,
+      mpv_fullscreen: false,
+      mpv_osc: default_mpv_osc(),
+      fullscreen_on_playback: false,
+      fullscreen_screen: None,
+      fullscreen_remote_casts: false,
+      mpv_display_server: DisplayServerMode::default(),
+      mpv_stop_behavior: MpvStopBehavior::default(),
+      mpv_warm_start: MpvWarmStart::default(),
+      mpv_command_timeout_seconds: default_mpv_command_timeout_seconds(),
+      mpv_command_timeouts: default_command_timeouts(),
+      adopt_orphaned_mpv: false,
+      mpv_outlives_app: false,
+      command_audit_retention: default_command_audit_retention(),
+      mpv_log_retention: default_mpv_log_retention(),
+      skip_specials: false,
+      auto_next_scope: default_auto_next_scope(),
+      auto_next_specials: default_auto_next_specials(),
+      auto_next_at_credits: false,
+      auto_next_countdown_seconds: None,
+      websocket_parse_diagnostics: false,
+      os_notify_playback_started: false,
+      os_notify_auto_next: false,
+      os_notify_connection: false,
+      os_notify_mpv_crash: false,
+      metrics_port: None,
+      webhooks: Vec::new(),
+      control_api_port: None,
+      control_api_token: String::new(),
+      max_volume: default_max_volume(),
+      volume_curve: VolumeCurve::default(),
+      track_preference_scope: TrackPreferenceScope::default(),
+      media_version_preference: MediaVersionPreference::default(),
+      preferred_video_height: default_preferred_video_height(),
+      stall_warning_seconds: default_stall_warning_seconds(),
+      lower_bitrate_on_stalls: false,
+      cast_takeover: CastTakeover::default(),
+      settings_pin: None,
+      check_for_updates: default_check_for_updates(),
+      telemetry_consent: false,
+      telemetry_endpoint: String::new(),
+      telemetry_dry_run: false,
+      resume_rewind_seconds: None,
+      resume_rewind_after_pause_seconds: default_resume_rewind_after_pause_seconds(),
+      screenshot_dir: None,
+      cast_media_types: default_cast_media_types(),
+      cast_commands: default_cast_commands(),
+    }
+  }
+}
+
+/// A setting that failed validation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigFieldError {
+  /// The setting's name as serialized, e.g. `deviceName`.
+  pub field: String,
+  pub message: String,
+}
+
+impl ConfigFieldError {
+  fn new(field: &str, message: impl Into<String>) -> Self {
+    Self {
+      field: field.to_string(),
+      message: message.into(),
     }
   }
 }
 
 impl AppConfig {
-  /// Validate configuration values.
-  pub fn validate(&self) -> Result<(), String> {
-    if self.device_name.trim().is_empty() {
-      return Err("Device name cannot be empty".to_string());
+  /// Validate configuration values, collecting every problem.
+  pub fn validate(&self) -> Result<(), Vec<ConfigFieldError>> {
+    let mut errors = Vec::new();
+    let mut check = |failed: bool, field: &str, message: &str| {
+      if failed {
+        errors.push(ConfigFieldError::new(field, message));
+      }
+    };
+    check(
+      self.device_name.trim().is_empty(),
+      "deviceName",
+      "Device name cannot be empty",
+    );
+    check(
+      self.progress_interval < 1 || self.progress_interval > 60,
+      "progressInterval",
+      "Progress interval must be between 1 and 60 seconds",
+    );
+    check(
+      self
+        .preferred_subtitle_languages
+        .iter()
+        .any(|language| language.trim().is_empty()),
+      "preferredSubtitleLanguages",
+      "Preferred subtitle languages cannot contain empty entries",
+    );
+    check(
+      self
+        .mpv_cache_size_mib
+        .is_some_and(|size| !(1..=8192).contains(&size)),
+      "mpvCacheSizeMib",
+      "MPV cache size must be between 1 and 8192 MiB",
+    );
+    check(
+      self
+        .credits_fallback_seconds
+        .is_some_and(|seconds| !(10..=600).contains(&seconds)),
+      "creditsFallbackSeconds",
+      "Credits length must be between 10 and 600 seconds",
+    );
+    check(
+      self
+        .auto_next_countdown_seconds
+        .is_some_and(|seconds| !(1..=30).contains(&seconds)),
+      "autoNextCountdownSeconds",
+      "Auto-next countdown must be between 1 and 30 seconds",
+    );
+    check(
+      !(1..=100).contains(&self.max_volume),
+      "maxVolume",
+      "Maximum volume must be between 1 and 100",
+    );
+    check(
+      !(144..=4320).contains(&self.preferred_video_height),
+      "preferredVideoHeight",
+      "Preferred video height must be between 144 and 4320 pixels",
+    );
+    check(
+      self
+        .stall_warning_seconds
+        .is_some_and(|seconds| !(1..=60).contains(&seconds)),
+      "stallWarningSeconds",
+      "Stall warning delay must be between 1 and 60 seconds",
+    );
+    check(
+      self
+        .resume_rewind_seconds
+        .is_some_and(|seconds| !(1..=60).contains(&seconds)),
+      "resumeRewindSeconds",
+      "Resume rewind must be between 1 and 60 seconds",
+    );
+    check(
+      !(1..=3600).contains(&self.resume_rewind_after_pause_seconds),
+      "resumeRewindAfterPauseSeconds",
+      "Pause length before rewinding must be between 1 and 3600 seconds",
+    );
+    check(
+      self
+        .screenshot_dir
+        .as_deref()
+        .is_some_and(|dir| !dir.is_empty() && !Path::new(dir).is_absolute()),
+      "screenshotDir",
+      "Screenshot folder must be an absolute path",
+    );
+    check(
+      !(1..=120).contains(&self.mpv_command_timeout_seconds),
+      "mpvCommandTimeoutSeconds",
+      "MPV command timeout must be between 1 and 120 seconds",
+    );
+    check(
+      self
+        .mpv_command_timeouts
+        .iter()
+        .any(|(name, seconds)| name.trim().is_empty() || !(1..=120).contains(seconds)),
+      "mpvCommandTimeouts",
+      "MPV command timeouts need a command name and between 1 and 120 seconds",
+    );
+    check(
+      !(10..=5000).contains(&self.command_audit_retention),
+      "commandAuditRetention",
+      "Keep between 10 and 5000 MPV commands",
+    );
+    check(
+      !(1..=50).contains(&self.mpv_log_retention),
+      "mpvLogRetention",
+      "Keep between 1 and 50 MPV session logs",
+    );
+    check(
+      self.cast_media_types.is_empty(),
+      "castMediaTypes",
+      "Accept at least one media type, or nothing can be cast to JellyPilot",
+    );
+    check(
+      self.metrics_port == Some(0),
+      "metricsPort",
+      "Metrics port must be between 1 and 65535",
+    );
+    check(
+      self.control_api_port == Some(0),
+      "controlApiPort",
+      "Control API port must be between 1 and 65535",
+    );
+    check(
+      self.control_api_port.is_some() && self.control_api_port == self.metrics_port,
+      "controlApiPort",
+      "Control API and metrics need different ports",
+    );
+    let telemetry_endpoint = self.telemetry_endpoint.trim();
+    check(
+      !telemetry_endpoint.is_empty()
+        && !(telemetry_endpoint.starts_with("http://")
+          || telemetry_endpoint.starts_with("https://")),
+      "telemetryEndpoint",
+      "Telemetry endpoint must start with http:// or https://",
+    );
+
+    for (field, label, key) in [
+      ("keybindNext", "Next episode", &self.keybind_next),
+      ("keybindPrev", "Previous episode", &self.keybind_prev),
+      ("keybindIntroSkip", "Intro skip", &self.keybind_intro_skip),
+      (
+        "keybindToggleIntroSkip",
+        "Automatic intro skipping toggle",
+        &self.keybind_toggle_intro_skip,
+      ),
+      (
+        "keybindToggleWatched",
+        "Watched toggle",
+        &self.keybind_toggle_watched,
+      ),
+      (
+        "keybindToggleFavorite",
+        "Favorite toggle",
+        &self.keybind_toggle_favorite,
+      ),
+      ("keybindSpeedUp", "Speed up", &self.keybind_speed_up),
+      ("keybindSpeedDown", "Slow down", &self.keybind_speed_down),
+      ("keybindStats", "Stats overlay", &self.keybind_stats),
+      (
+        "keybindStopIdle",
+        "Stop and go idle",
+        &self.keybind_stop_idle,
+      ),
+      (
+        "keybindSecondarySubtitle",
+        "Secondary subtitle",
+        &self.keybind_secondary_subtitle,
+      ),
+      ("keybindAbLoop", "A-B loop", &self.keybind_ab_loop),
+      ("keybindScreenshot", "Screenshot", &self.keybind_screenshot),
+    ] {
+      if key.trim().is_empty() {
+        errors.push(ConfigFieldError::new(
+          field,
+          format!("{} keybinding cannot be empty", label),
+        ));
+      } else if !is_valid_key(key) {
+        errors.push(ConfigFieldError::new(
+          field,
+          format!("{} keybinding \"{}\" is not a valid MPV key", label, key),
+        ));
+      }
     }
-    if self.progress_interval < 1 || self.progress_interval > 60 {
-      return Err("Progress interval must be between 1 and 60 seconds".to_string());
+    let builtin_keys = [
+      self.keybind_next.trim(),
+      self.keybind_prev.trim(),
+      self.keybind_intro_skip.trim(),
+      self.keybind_toggle_intro_skip.trim(),
+      self.keybind_toggle_watched.trim(),
+      self.keybind_toggle_favorite.trim(),
+      self.keybind_speed_up.trim(),
+      self.keybind_speed_down.trim(),
+      self.keybind_stats.trim(),
+      self.keybind_stop_idle.trim(),
+      self.keybind_secondary_subtitle.trim(),
+      self.keybind_ab_loop.trim(),
+      self.keybind_screenshot.trim(),
+    ];
+    for (key, command) in &self.custom_bindings {
+      if !is_valid_key(key) || key.chars().any(char::is_whitespace) {
+        errors.push(ConfigFieldError::new(
+          "customBindings",
+          format!("Custom keybinding key \"{}\" is not a valid MPV key", key),
+        ));
+      }
+      if command.trim().is_empty() || command.contains(['\n', '\r']) {
+        errors.push(ConfigFieldError::new(
+          "customBindings",
+          format!("Custom keybinding for {} must be a single MPV command", key),
+        ));
+      }
+      if builtin_keys.contains(&key.as_str()) {
+        errors.push(ConfigFieldError::new(
+          "customBindings",
+          format!(
+            "Custom keybinding {} conflicts with a JellyPilot shortcut",
+            key
+          ),
+        ));
+      }
     }
-    if self.keybind_next.trim().is_empty() {
-      return Err("Next episode keybinding cannot be empty".to_string());
+
+    if let Some(path) = self.mpv_path.as_deref().filter(|path| !path.is_empty()) {
+      if let Err(message) = check_executable(Path::new(path)) {
+        errors.push(ConfigFieldError::new("mpvPath", message));
+      }
     }
-    if self.keybind_prev.trim().is_empty() {
-      return Err("Previous episode keybinding cannot be empty".to_string());
+    for arg in &self.mpv_args {
+      if let Some(option) = overridden_required_option(arg) {
+        errors.push(ConfigFieldError::new(
+          "mpvArgs",
+          format!(
+            "MPV argument {} conflicts with {}, which JellyPilot needs to control MPV",
+            arg.trim(),
+            option
+          ),
+        ));
+      }
     }
-    if self.keybind_intro_skip.trim().is_empty() {
-      return Err("Intro skip keybinding cannot be empty".to_string());
+    for webhook in &self.webhooks {
+      if let Err(message) = webhook.validate() {
+        errors.push(ConfigFieldError::new("webhooks", message));
+      }
     }
-    if self
-      .preferred_subtitle_languages
-      .iter()
-      .any(|language| language.trim().is_empty())
-    {
-      return Err("Preferred subtitle languages cannot contain empty entries".to_string());
+
+    if errors.is_empty() {
+      Ok(())
+    } else {
+      Err(errors)
     }
-    Ok(())
+  }
+
+  /// MPV executable to spawn: the configured path, else the bundled MPV when
+  /// enabled and installed. None detects a system MPV.
+  pub fn resolved_mpv_path(&self) -> Option<PathBuf> {
+    self
+      .mpv_path
+      .as_ref()
+      .filter(|path| !path.is_empty())
+      .map(PathBuf::from)
+      .or_else(|| self.bundled_mpv.then(bundled_mpv_path).flatten())
+  }
+
+  /// Folder screenshots are saved to: the configured one, else `JellyPilot`
+  /// in the pictures folder.
+  pub fn resolved_screenshot_dir(&self) -> Option<PathBuf> {
+    self
+      .screenshot_dir
+      .as_ref()
+      .filter(|dir| !dir.is_empty())
+      .map(PathBuf::from)
+      .or_else(|| dirs::picture_dir().map(|p| p.join("JellyPilot")))
+  }
+
+  /// Settings for the managed mpv.conf, or None to use the user's config untouched.
+  pub fn managed_mpv_config(&self) -> Option<ManagedMpvConfig> {
+    self.managed_mpv_config.then(|| ManagedMpvConfig {
+      cache_size_mib: self.mpv_cache_size_mib,
+      screen: self.mpv_screen,
+      fullscreen: self.mpv_fullscreen,
+      osc: self.mpv_osc,
+    })
+  }
+
+  /// How long MPV gets to answer each IPC command.
+  pub fn command_timeouts(&self) -> CommandTimeouts {
+    CommandTimeouts::from_seconds(self.mpv_command_timeout_seconds, &self.mpv_command_timeouts)
+  }
+
+  /// Fullscreen options MPV is spawned with.
+  pub fn fullscreen_options(&self) -> FullscreenOptions {
+    FullscreenOptions {
+      on_start: self.fullscreen_on_playback,
+      screen: self.fullscreen_screen,
+    }
+  }
+
+  /// Keys of JellyPilot's own MPV shortcuts.
+  pub fn shortcut_keys(&self) -> ShortcutKeys {
+    ShortcutKeys {
+      next: self.keybind_next.clone(),
+      prev: self.keybind_prev.clone(),
+      intro_skip: self.keybind_intro_skip.clone(),
+      toggle_intro_skip: self.keybind_toggle_intro_skip.clone(),
+      toggle_watched: self.keybind_toggle_watched.clone(),
+      toggle_favorite: self.keybind_toggle_favorite.clone(),
+      speed_up: self.keybind_speed_up.clone(),
+      speed_down: self.keybind_speed_down.clone(),
+      stats: self.keybind_stats.clone(),
+      stop_idle: self.keybind_stop_idle.clone(),
+      secondary_subtitle: self.keybind_secondary_subtitle.clone(),
+      ab_loop: self.keybind_ab_loop.clone(),
+      screenshot: self.keybind_screenshot.clone(),
+    }
+  }
+
+  /// All bindings JellyPilot manages in its MPV input.conf.
+  pub fn input_bindings(&self) -> Vec<InputBinding> {
+    let mut bindings = builtin_input_bindings(&self.shortcut_keys());
+    bindings.extend(
+      self
+        .custom_bindings
+        .iter()
+        .map(|(key, command)| InputBinding::new(key.trim(), command.trim())),
+    );
+    bindings
   }
 }
 
+/// Whether `path` is a file MPV can be started from.
+fn check_executable(path: &Path) -> Result<(), String> {
+  let metadata = std::fs::metadata(path)
+    .map_err(|_| format!("MPV executable {} does not exist", path.display()))?;
+  if !metadata.is_file() {
+    return Err(format!("MPV path {} is not a file", path.display()));
+  }
+  #[cfg(unix)]
+  {
+    use std::os::unix::fs::PermissionsExt;
+    if metadata.permissions().mode() & 0o111 == 0 {
+      return Err(format!(
+        "MPV executable {} is not executable",
+        path.display()
+      ));
+    }
+  }
+  Ok(())
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -241,16 +1290,101 @@ mod tests {
     assert_eq!(config.intro_skipper_mode, IntroSkipperMode::Off);
   }
 
+  #[test]
+  fn config_rejects_volume_cap_outside_percent_range() {
+    let mut config = AppConfig::default();
+    assert_eq!(config.max_volume, 100);
+
+    config.max_volume = 0;
+    assert!(config.validate().is_err());
+    config.max_volume = 130;
+    assert!(config.validate().is_err());
+    config.max_volume = 70;
+    assert!(config.validate().is_ok());
+  }
+
   #[test]
   fn config_rejects_empty_preferred_subtitle_language() {
     let mut config = AppConfig::default();
     config.preferred_subtitle_languages.push(" ".to_string());
 
-    let err = config.validate().expect_err("empty language should fail");
+    let errors = config.validate().expect_err("empty language should fail");
+
+    assert_eq!(
+      errors,
+      vec![ConfigFieldError::new(
+        "preferredSubtitleLanguages",
+        "Preferred subtitle languages cannot contain empty entries"
+      )]
+    );
+  }
+
+  #[test]
+  fn managed_mpv_config_is_off_by_default_and_keeps_osc() {
+    let mut config = AppConfig::default();
+    assert!(config.managed_mpv_config().is_none());
+
+    config.managed_mpv_config = true;
+    config.mpv_screen = Some(1);
+
+    assert_eq!(
+      config.managed_mpv_config(),
+      Some(ManagedMpvConfig {
+        cache_size_mib: None,
+        screen: Some(1),
+        fullscreen: false,
+        osc: true,
+      })
+    );
+  }
+
+  #[test]
+  fn custom_bindings_are_written_after_builtin_bindings() {
+    let mut config = AppConfig::default();
+    config
+      .custom_bindings
+      .insert("Ctrl+w".to_string(), " quit ".to_string());
+
+    let bindings = config.input_bindings();
+
+    assert_eq!(bindings.len(), 4);
+    assert_eq!(bindings[3], InputBinding::new("Ctrl+w", "quit"));
+  }
+
+  #[test]
+  fn config_rejects_custom_binding_that_shadows_builtin_key() {
+    let mut config = AppConfig::default();
+    config
+      .custom_bindings
+      .insert("g".to_string(), "cycle sub".to_string());
+
+    let errors = config.validate().expect_err("shadowing key should fail");
+
+    assert_eq!(
+      errors,
+      vec![ConfigFieldError::new(
+        "customBindings",
+        "Custom keybinding g conflicts with a JellyPilot shortcut"
+      )]
+    );
+  }
+
+  #[test]
+  fn config_reports_every_invalid_field() {
+    let config = AppConfig {
+      keybind_next: "Hyper+n".to_string(),
+      mpv_path: Some("/nonexistent/mpv".to_string()),
+      mpv_args: vec!["--no-idle".to_string(), "--volume-max=150".to_string()],
+      ..Default::default()
+    };
+
+    let errors = config.validate().expect_err("invalid fields should fail");
+    let fields: Vec<&str> = errors.iter().map(|error| error.field.as_str()).collect();
 
+    assert_eq!(fields, vec!["keybindNext", "mpvPath", "mpvArgs"]);
     assert_eq!(
-      err,
-      "Preferred subtitle languages cannot contain empty entries"
+      errors[2].message,
+      "MPV argument --no-idle conflicts with --idle, which JellyPilot needs to control MPV"
     );
   }
 }
@@ -0,0 +1,616 @@
+//! Local HTTP control API.
+//!
+//! An opt-in REST API on `127.0.0.1:<control_api_port>` for scripts, Stream
+//! Deck plugins and other local tools. It mirrors the playback commands of
+//! the JellyPilot window and goes through the same `playback_control`
+//! functions, so server reports and the window stay in sync. Every request
+//! needs `Authorization: Bearer <control_api_token>`.
+//!
+//! Routes, all answering with the Now Playing state after the change:
+//! - `GET  /v1/now-playing`
+//! - `POST /v1/pause`, `/v1/resume`, `/v1/toggle-pause`
+//! - `POST /v1/seek` with `{"seconds": 90}` or `{"percent": 50}`
+//! - `POST /v1/next`, `/v1/previous`
+//! - `POST /v1/volume` with `{"volume": 40}`
+//! - `GET  /v1/events`: Server-Sent Events, see below
+//!
+//! Errors are a `CommandError` JSON body with a matching HTTP status.
+//!
+//! `/v1/events` pushes a `nowPlaying` event with the current state right
+//! away and again whenever it changes, including position ticks during
+//! playback, so Stream Deck keys can show the title and play/pause state
+//! without polling. Message shapes are documented in `docs/control-api.md`.
+
+use std::convert::Infallible;
+use std::future::Future;
+use std::sync::{Arc, OnceLock};
+
+use axum::extract::{Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use futures_util::{stream, Stream, StreamExt};
+use parking_lot::Mutex;
+use serde::Deserialize;
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Manager};
+use tokio::net::TcpListener;
+use tokio::sync::broadcast;
+
+use crate::command::{
+  AppNotification, CommandError, CommandErrorCode, JellyfinState, NowPlayingState,
+};
+use crate::i18n::tr;
+use crate::jellyfin::SeekTarget;
+use crate::playback_control::{self, AdjacentDirection};
+
+/// Playback operations exposed by the API.
+pub trait PlaybackControl: Send + Sync + 'static {
+  fn now_playing(&self) -> impl Future<Output = NowPlayingState> + Send;
+  /// Pause or resume; `None` toggles.
+  fn set_pause(
+    &self,
+    paused: Option<bool>,
+  ) -> impl Future<Output = Result<(), CommandError>> + Send;
+  fn seek(&self, target: SeekTarget) -> impl Future<Output = Result<(), CommandError>> + Send;
+  fn play_adjacent(
+    &self,
+    direction: AdjacentDirection,
+  ) -> impl Future<Output = Result<(), CommandError>> + Send;
+  fn set_volume(&self, volume: f64) -> impl Future<Output = Result<(), CommandError>> + Send;
+  /// Now Playing states published from now on.
+  fn subscribe(&self) -> broadcast::Receiver<NowPlayingState>;
+}
+
+impl PlaybackControl for AppHandle {
+  async fn now_playing(&self) -> NowPlayingState {
+    let state = self.state::<JellyfinState>();
+    playback_control::collect_now_playing_state(&state).await
+  }
+
+  async fn set_pause(&self, paused: Option<bool>) -> Result<(), CommandError> {
+    let state = self.state::<JellyfinState>();
+    match paused {
+      Some(paused) => playback_control::set_pause(self, &state.mpv, &state, paused).await,
+      None => playback_control::toggle_pause(self, &state.mpv, &state).await,
+    }
+  }
+
+  async fn seek(&self, target: SeekTarget) -> Result<(), CommandError> {
+    playback_control::seek(self, &self.state::<JellyfinState>(), target).await
+  }
+
+  async fn play_adjacent(&self, direction: AdjacentDirection) -> Result<(), CommandError> {
+    playback_control::play_adjacent_episode(self, &self.state::<JellyfinState>(), direction).await
+  }
+
+  async fn set_volume(&self, volume: f64) -> Result<(), CommandError> {
+    let state = self.state::<JellyfinState>();
+    playback_control::set_volume(self, &state.mpv, &state, volume).await
+  }
+
+  fn subscribe(&self) -> broadcast::Receiver<NowPlayingState> {
+    updates().subscribe()
+  }
+}
+
+/// Buffered states per event stream; a slow client skips to newer ones.
+const UPDATE_CAPACITY: usize = 16;
+
+static UPDATES: OnceLock<broadcast::Sender<NowPlayingState>> = OnceLock::new();
+
+fn updates() -> &'static broadcast::Sender<NowPlayingState> {
+  UPDATES.get_or_init(|| broadcast::channel(UPDATE_CAPACITY).0)
+}
+
+/// Push a Now Playing change to connected event streams.
+pub fn publish(state: &NowPlayingState) {
+  let updates = updates();
+  if updates.receiver_count() > 0 {
+    let _ = updates.send(state.clone());
+  }
+}
+
+static SERVER: Mutex<Option<(u16, String, JoinHandle<()>)>> = parking_lot::const_mutex(None);
+
+/// Serve the API on `port` with `token`, or stop serving when `port` is None.
+///
+/// Restarts the listener only when the port or token changes. A port that
+/// can't be bound is reported as a notification and left unserved, so the
+/// next call with the same settings tries again.
+pub fn apply(app: &AppHandle, port: Option<u16>, token: &str) {
+  let mut server = SERVER.lock();
+  if server
+    .as_ref()
+    .map(|(current_port, current_token, _)| (*current_port, current_token.as_str()))
+    == port.map(|port| (port, token))
+  {
+    return;
+  }
+  if let Some((old_port, _, handle)) = server.take() {
+    log::info!("Stopping control API on port {}", old_port);
+    handle.abort();
+  }
+  let Some(port) = port else {
+    return;
+  };
+  if token.is_empty() {
+    log::error!("Control API not started: no token is configured");
+    return;
+  }
+  let listener = match bind(port) {
+    Ok(listener) => listener,
+    Err(e) => {
+      log::error!("Failed to start control API on port {}: {}", port, e);
+      AppNotification::error(
+        app,
+        tr!(
+          "notify-control-api-failed",
+          port = port.to_string(),
+          error = e.to_string()
+        ),
+      );
+      return;
+    }
+  };
+  let router = router(Arc::new(app.clone()), token);
+  *server = Some((
+    port,
+    token.to_string(),
+    tauri::async_runtime::spawn(serve(listener, router)),
+  ));
+}
+
+/// Bind `port` on loopback up front, so a taken port fails in [`apply`].
+fn bind(port: u16) -> std::io::Result<std::net::TcpListener> {
+  let listener = std::net::TcpListener::bind(("127.0.0.1", port))?;
+  listener.set_nonblocking(true)?;
+  Ok(listener)
+}
+
+async fn serve(listener: std::net::TcpListener, router: Router) {
+  let listener = match TcpListener::from_std(listener) {
+    Ok(listener) => listener,
+    Err(e) => {
+      log::error!("Failed to start control API: {}", e);
+      return;
+    }
+  };
+  if let Ok(address) = listener.local_addr() {
+    log::info!("Serving control API on http://{}", address);
+  }
+  if let Err(e) = axum::serve(listener, router).await {
+    log::error!("Control API stopped: {}", e);
+  }
+}
+
+/// The API routes for `control`, guarded by `token`.
+pub fn router<C: PlaybackControl>(control: Arc<C>, token: &str) -> Router {
+  Router::new()
+    .route("/v1/now-playing", get(now_playing::<C>))
+    .route("/v1/pause", post(pause::<C>))
+    .route("/v1/resume", post(resume::<C>))
+    .route("/v1/toggle-pause", post(toggle_pause::<C>))
+    .route("/v1/seek", post(seek::<C>))
+    .route("/v1/next", post(next::<C>))
+    .route("/v1/previous", post(previous::<C>))
+    .route("/v1/volume", post(volume::<C>))
+    .route("/v1/events", get(events::<C>))
+    .with_state(control)
+    .layer(middleware::from_fn_with_state(
+      Arc::<str>::from(token),
+      require_token,
+    ))
+}
+
+async fn require_token(State(token): State<Arc<str>>, request: Request, next: Next) -> Response {
+  let provided = request
+    .headers()
+    .get(header::AUTHORIZATION)
+    .and_then(|value| value.to_str().ok())
+    .and_then(|value| value.strip_prefix("Bearer "));
+  if provided.is_some_and(|provided| tokens_match(provided, &token)) {
+    next.run(request).await
+  } else {
+    ApiError(CommandError::auth_failed("Missing or invalid API token")).into_response()
+  }
+}
+
+/// Compare without returning early on the first differing byte.
+fn tokens_match(provided: &str, expected: &str) -> bool {
+  provided.len() == expected.len()
+    && provided
+      .bytes()
+      .zip(expected.bytes())
+      .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+      == 0
+}
+
+struct ApiError(CommandError);
+
+impl From<CommandError> for ApiError {
+  fn from(error: CommandError) -> Self {
+    Self(error)
+  }
+}
+
+impl IntoResponse for ApiError {
+  fn into_response(self) -> Response {
+    (status_for(&self.0.code), Json(self.0)).into_response()
+  }
+}
+
+fn status_for(code: &CommandErrorCode) -> StatusCode {
+  match code {
+    CommandErrorCode::NotConnected => StatusCode::CONFLICT,
+    CommandErrorCode::NotFound => StatusCode::NOT_FOUND,
+    CommandErrorCode::InvalidInput => StatusCode::BAD_REQUEST,
+    CommandErrorCode::Network => StatusCode::BAD_GATEWAY,
+    CommandErrorCode::AuthFailed => StatusCode::UNAUTHORIZED,
+    CommandErrorCode::Internal => StatusCode::INTERNAL_SERVER_ERROR,
+  }
+}
+
+type ApiResult = Result<Json<NowPlayingState>, ApiError>;
+
+async fn now_playing<C: PlaybackControl>(State(control): State<Arc<C>>) -> Json<NowPlayingState> {
+  Json(control.now_playing().await)
+}
+
+async fn pause<C: PlaybackControl>(State(control): State<Arc<C>>) -> ApiResult {
+  control.set_pause(Some(true)).await?;
+  Ok(Json(control.now_playing().await))
+}
+
+async fn resume<C: PlaybackControl>(State(control): State<Arc<C>>) -> ApiResult {
+  control.set_pause(Some(false)).await?;
+  Ok(Json(control.now_playing().await))
+}
+
+async fn toggle_pause<C: PlaybackControl>(State(control): State<Arc<C>>) -> ApiResult {
+  control.set_pause(None).await?;
+  Ok(Json(control.now_playing().await))
+}
+
+#[derive(Debug, Deserialize)]
+struct SeekRequest {
+  seconds: Option<f64>,
+  percent: Option<f64>,
+}
+
+impl SeekRequest {
+  fn target(&self) -> Result<SeekTarget, CommandError> {
+    match (self.seconds, self.percent) {
+      (Some(seconds), None) => Ok(SeekTarget::Seconds(seconds)),
+      (None, Some(percent)) => Ok(SeekTarget::Percent(percent)),
+      _ => Err(CommandError::invalid_input(
+        "Seek needs either seconds or percent",
+      )),
+    }
+  }
+}
+
+async fn seek<C: PlaybackControl>(
+  State(control): State<Arc<C>>,
+  Json(request): Json<SeekRequest>,
+) -> ApiResult {
+  control.seek(request.target()?).await?;
+  Ok(Json(control.now_playing().await))
+}
+
+async fn next<C: PlaybackControl>(State(control): State<Arc<C>>) -> ApiResult {
+  control.play_adjacent(AdjacentDirection::Next).await?;
+  Ok(Json(control.now_playing().await))
+}
+
+async fn previous<C: PlaybackControl>(State(control): State<Arc<C>>) -> ApiResult {
+  control.play_adjacent(AdjacentDirection::Previous).await?;
+  Ok(Json(control.now_playing().await))
+}
+
+#[derive(Debug, Deserialize)]
+struct VolumeRequest {
+  volume: f64,
+}
+
+async fn volume<C: PlaybackControl>(
+  State(control): State<Arc<C>>,
+  Json(request): Json<VolumeRequest>,
+) -> ApiResult {
+  control.set_volume(request.volume).await?;
+  Ok(Json(control.now_playing().await))
+}
+
+async fn events<C: PlaybackControl>(
+  State(control): State<Arc<C>>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+  // Subscribe before taking the snapshot so no change in between is lost.
+  let updates = control.subscribe();
+  let current = control.now_playing().await;
+  let changes = stream::unfold(updates, |mut updates| async move {
+    loop {
+      match updates.recv().await {
+        Ok(state) => return Some((state, updates)),
+        Err(broadcast::error::RecvError::Lagged(skipped)) => {
+          log::debug!("Control API event stream skipped {} states", skipped);
+        }
+        Err(broadcast::error::RecvError::Closed) => return None,
+      }
+    }
+  });
+  let states = stream::once(async move { current }).chain(changes);
+  Sse::new(states.map(|state| Ok(now_playing_event(&state)))).keep_alive(KeepAlive::default())
+}
+
+fn now_playing_event(state: &NowPlayingState) -> Event {
+  Event::default()
+    .event("nowPlaying")
+    .json_data(state)
+    .unwrap_or_else(|e| {
+      log::error!("Failed to serialize Now Playing event: {}", e);
+      Event::default().comment("serialization failed")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::command::PlayerState;
+  use crate::now_playing::{build_now_playing_state, PlaybackContext};
+
+  const TOKEN: &str = "test-token";
+
+  /// Records calls and plays a 100 s item without touching MPV.
+  struct FakeControl {
+    calls: Mutex<Vec<String>>,
+    paused: Mutex<bool>,
+    updates: broadcast::Sender<NowPlayingState>,
+  }
+
+  impl FakeControl {
+    fn new() -> Arc<Self> {
+      Arc::new(Self {
+        calls: Mutex::new(Vec::new()),
+        paused: Mutex::new(false),
+        updates: broadcast::channel(UPDATE_CAPACITY).0,
+      })
+    }
+
+    fn state(&self) -> NowPlayingState {
+      build_now_playing_state(
+        PlayerState {
+          connected: true,
+          paused: *self.paused.lock(),
+          duration: 100.0,
+          ..PlayerState::default()
+        },
+        PlaybackContext {
+          has_active_session: true,
+          current_item: None,
+          user_data: None,
+        },
+      )
+    }
+
+    fn record(&self, call: String) {
+      self.calls.lock().push(call);
+    }
+  }
+
+  impl PlaybackControl for FakeControl {
+    async fn now_playing(&self) -> NowPlayingState {
+      self.state()
+    }
+
+    async fn set_pause(&self, paused: Option<bool>) -> Result<(), CommandError> {
+      self.record(format!("set_pause({:?})", paused));
+      {
+        let mut current = self.paused.lock();
+        *current = paused.unwrap_or(!*current);
+      }
+      let _ = self.updates.send(self.state());
+      Ok(())
+    }
+
+    async fn seek(&self, target: SeekTarget) -> Result<(), CommandError> {
+      self.record(format!("seek({:?})", target));
+      Ok(())
+    }
+
+    async fn play_adjacent(&self, direction: AdjacentDirection) -> Result<(), CommandError> {
+      self.record(format!("play_adjacent({:?})", direction));
+      Err(CommandError::invalid_input(
+        "Next episode is available during episode playback",
+      ))
+    }
+
+    async fn set_volume(&self, volume: f64) -> Result<(), CommandError> {
+      self.record(format!("set_volume({})", volume));
+      Ok(())
+    }
+
+    fn subscribe(&self) -> broadcast::Receiver<NowPlayingState> {
+      self.updates.subscribe()
+    }
+  }
+
+  async fn spawn_server(control: Arc<FakeControl>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+    let address = listener.local_addr().expect("local addr");
+    tokio::spawn(async move {
+      axum::serve(listener, router(control, TOKEN)).await.unwrap();
+    });
+    format!("http://{}", address)
+  }
+
+  fn post(base: &str, path: &str) -> reqwest::RequestBuilder {
+    reqwest::Client::new()
+      .post(format!("{}{}", base, path))
+      .bearer_auth(TOKEN)
+  }
+
+  /// Data of the next `nowPlaying` event, skipping keep-alive comments.
+  async fn next_event(response: &mut reqwest::Response, buffer: &mut String) -> serde_json::Value {
+    loop {
+      if let Some(end) = buffer.find("\n\n") {
+        let block: String = buffer.drain(..end + 2).collect();
+        if block.lines().any(|line| line == "event: nowPlaying") {
+          let data = block
+            .lines()
+            .find_map(|line| line.strip_prefix("data: "))
+            .expect("event has data");
+          return serde_json::from_str(data).expect("event data is JSON");
+        }
+        continue;
+      }
+      let chunk = tokio::time::timeout(std::time::Duration::from_secs(5), response.chunk())
+        .await
+        .expect("event within timeout")
+        .expect("stream readable")
+        .expect("stream still open");
+      buffer.push_str(&String::from_utf8_lossy(&chunk));
+    }
+  }
+
+  #[tokio::test]
+  async fn requests_without_the_token_are_rejected() {
+    let control = FakeControl::new();
+    let base = spawn_server(control.clone()).await;
+
+    let missing = reqwest::get(format!("{}/v1/now-playing", base))
+      .await
+      .unwrap();
+    assert_eq!(missing.status(), reqwest::StatusCode::UNAUTHORIZED);
+    let body: serde_json::Value = missing.json().await.unwrap();
+    assert_eq!(body["code"], "authFailed");
+
+    let wrong = reqwest::Client::new()
+      .post(format!("{}/v1/pause", base))
+      .bearer_auth("not-the-token")
+      .send()
+      .await
+      .unwrap();
+    assert_eq!(wrong.status(), reqwest::StatusCode::UNAUTHORIZED);
+    assert!(control.calls.lock().is_empty());
+  }
+
+  #[tokio::test]
+  async fn controls_return_the_new_state() {
+    let control = FakeControl::new();
+    let base = spawn_server(control.clone()).await;
+
+    let response = post(&base, "/v1/pause").send().await.unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["status"], "paused");
+
+    post(&base, "/v1/toggle-pause").send().await.unwrap();
+    let seek = post(&base, "/v1/seek")
+      .json(&serde_json::json!({ "percent": 50 }))
+      .send()
+      .await
+      .unwrap();
+    assert_eq!(seek.status(), reqwest::StatusCode::OK);
+    post(&base, "/v1/volume")
+      .json(&serde_json::json!({ "volume": 40 }))
+      .send()
+      .await
+      .unwrap();
+
+    assert_eq!(
+      *control.calls.lock(),
+      vec![
+        "set_pause(Some(true))",
+        "set_pause(None)",
+        "seek(Percent(50.0))",
+        "set_volume(40)",
+      ]
+    );
+  }
+
+  #[tokio::test]
+  async fn failures_carry_the_command_error() {
+    let control = FakeControl::new();
+    let base = spawn_server(control).await;
+
+    let next = post(&base, "/v1/next").send().await.unwrap();
+    assert_eq!(next.status(), reqwest::StatusCode::BAD_REQUEST);
+    let body: serde_json::Value = next.json().await.unwrap();
+    assert_eq!(body["code"], "invalidInput");
+    assert_eq!(
+      body["message"],
+      "Next episode is available during episode playback"
+    );
+
+    let seek = post(&base, "/v1/seek")
+      .json(&serde_json::json!({}))
+      .send()
+      .await
+      .unwrap();
+    assert_eq!(seek.status(), reqwest::StatusCode::BAD_REQUEST);
+  }
+
+  #[tokio::test]
+  async fn events_push_the_current_state_and_changes() {
+    let control = FakeControl::new();
+    let base = spawn_server(control).await;
+
+    let mut events = reqwest::Client::new()
+      .get(format!("{}/v1/events", base))
+      .bearer_auth(TOKEN)
+      .send()
+      .await
+      .unwrap();
+    assert_eq!(events.status(), reqwest::StatusCode::OK);
+    assert_eq!(
+      events.headers()[reqwest::header::CONTENT_TYPE],
+      "text/event-stream"
+    );
+    let mut buffer = String::new();
+    let current = next_event(&mut events, &mut buffer).await;
+    assert_eq!(current["status"], "playing");
+    assert_eq!(current["player"]["duration"], 100.0);
+
+    post(&base, "/v1/pause").send().await.unwrap();
+    let changed = next_event(&mut events, &mut buffer).await;
+    assert_eq!(changed["status"], "paused");
+  }
+
+  #[test]
+  fn tokens_must_match_exactly() {
+    assert!(tokens_match("secret-token", "secret-token"));
+    assert!(!tokens_match("secret-tokex", "secret-token"));
+    assert!(!tokens_match("secret", "secret-token"));
+    assert!(!tokens_match("", "secret-token"));
+  }
+
+  #[test]
+  fn seek_takes_exactly_one_target() {
+    let request = |seconds, percent| SeekRequest { seconds, percent };
+    assert_eq!(
+      request(Some(90.0), None).target().unwrap(),
+      SeekTarget::Seconds(90.0)
+    );
+    assert_eq!(
+      request(None, Some(50.0)).target().unwrap(),
+      SeekTarget::Percent(50.0)
+    );
+    assert!(request(None, None).target().is_err());
+    assert!(request(Some(1.0), Some(1.0)).target().is_err());
+  }
+
+  #[test]
+  fn error_codes_map_to_http_statuses() {
+    assert_eq!(
+      status_for(&CommandErrorCode::InvalidInput),
+      StatusCode::BAD_REQUEST
+    );
+    assert_eq!(
+      status_for(&CommandErrorCode::AuthFailed),
+      StatusCode::UNAUTHORIZED
+    );
+  }
+}
@@ -0,0 +1,300 @@
+//! Diagnostics bundle for bug reports.
+//!
+//! Collects app logs, MPV session logs, the (redacted) config, the recent
+//! MPV command audit and the current item's durations into a single zip file.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+use thiserror::Error;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::config::AppConfig;
+use crate::jellyfin::DurationReport;
+use crate::mpv::AuditEntry;
+use crate::redact::{redact, REDACTED};
+
+/// Substrings of an MPV option name that mark its value as sensitive.
+const SENSITIVE_MPV_OPTIONS: [&str; 6] = ["header", "cookie", "password", "token", "key", "auth"];
+
+#[derive(Error, Debug)]
+pub enum DiagnosticsError {
+  #[error("I/O error: {0}")]
+  Io(#[from] std::io::Error),
+  #[error("Zip error: {0}")]
+  Zip(#[from] zip::result::ZipError),
+  #[error("JSON error: {0}")]
+  Json(#[from] serde_json::Error),
+}
+
+/// Everything that goes into a diagnostics bundle.
+pub struct DiagnosticsSources {
+  pub app_log_dir: Option<PathBuf>,
+  pub mpv_logs: Vec<PathBuf>,
+  pub config: AppConfig,
+  pub command_audit: Vec<AuditEntry>,
+  /// Jellyfin runtime vs MPV duration of the item playing at export time.
+  pub playback_duration: Option<DurationReport>,
+}
+
+/// Write the diagnostics zip to `destination`.
+pub fn write_bundle(
+  destination: &Path,
+  sources: &DiagnosticsSources,
+) -> Result<(), DiagnosticsError> {
+  let file = std::fs::File::create(destination)?;
+  let mut zip = ZipWriter::new(file);
+  let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+  zip.start_file("about.txt", options)?;
+  zip.write_all(about_text().as_bytes())?;
+
+  let config = redacted_config(&sources.config)?;
+  zip.start_file("config.json", options)?;
+  zip.write_all(&serde_json::to_vec_pretty(&config)?)?;
+
+  // Rejected WebSocket payloads are stored raw, so they go through redaction too.
+  let command_audit = serde_json::to_string_pretty(&sources.command_audit)?;
+  zip.start_file("mpv-command-audit.json", options)?;
+  zip.write_all(redact(&command_audit).as_bytes())?;
+
+  if let Some(duration) = &sources.playback_duration {
+    zip.start_file("playback-duration.json", options)?;
+    zip.write_all(&serde_json::to_vec_pretty(duration)?)?;
+  }
+
+  let app_logs = sources
+    .app_log_dir
+    .as_deref()
+    .map(log_files_in)
+    .unwrap_or_default();
+  for (folder, paths) in [("app-logs", &app_logs), ("mpv-logs", &sources.mpv_logs)] {
+    for path in paths {
+      let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+        continue;
+      };
+      let contents = match std::fs::read(path) {
+        Ok(contents) => contents,
+        Err(e) => {
+          log::warn!("Skipping {:?} in diagnostics bundle: {}", path, e);
+          continue;
+        }
+      };
+      zip.start_file(format!("{}/{}", folder, name), options)?;
+      zip.write_all(redact(&String::from_utf8_lossy(&contents)).as_bytes())?;
+    }
+  }
+
+  zip.finish()?;
+  Ok(())
+}
+
+/// Default bundle file name, unique per export.
+pub fn bundle_file_name() -> String {
+  let secs = std::time::SystemTime::now()
+    .duration_since(std::time::UNIX_EPOCH)
+    .map(|d| d.as_secs())
+    .unwrap_or_default();
+  format!("jellypilot-diagnostics-{}.zip", secs)
+}
+
+fn about_text() -> String {
+  format!(
+    "JellyPilot {}\nOS: {} ({})\n",
+    env!("CARGO_PKG_VERSION"),
+    std::env::consts::OS,
+    std::env::consts::ARCH
+  )
+}
+
+fn log_files_in(dir: &Path) -> Vec<PathBuf> {
+  let Ok(entries) = std::fs::read_dir(dir) else {
+    return Vec::new();
+  };
+  let mut files: Vec<PathBuf> = entries
+    .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+    .filter(|path| path.extension().is_some_and(|ext| ext == "log"))
+    .collect();
+  files.sort();
+  files
+}
+
+/// Config as JSON with sensitive MPV option values, webhook secrets, the
+/// control API token and the settings PIN hash masked.
+fn redacted_config(config: &AppConfig) -> Result<Value, serde_json::Error> {
+  let mut value = serde_json::to_value(config)?;
+  if let Some(Value::Array(args)) = value.get_mut("mpvArgs") {
+    for arg in args.iter_mut() {
+      if let Value::String(text) = arg {
+        *text = redact_mpv_arg(text);
+      }
+    }
+  }
+  if let Some(Value::Array(webhooks)) = value.get_mut("webhooks") {
+    for webhook in webhooks.iter_mut() {
+      if let Some(secret) = webhook
+        .get_mut("secret")
+        .filter(|secret| secret.as_str().is_some_and(|s| !s.is_empty()))
+      {
+        *secret = Value::String(REDACTED.to_string());
+      }
+    }
+  }
+  if let Some(token) = value
+    .get_mut("controlApiToken")
+    .filter(|token| token.as_str().is_some_and(|s| !s.is_empty()))
+  {
+    *token = Value::String(REDACTED.to_string());
+  }
+  // The PIN is short enough to brute-force from its hash.
+  if let Some(pin) = value.get_mut("settingsPin").filter(|pin| !pin.is_null()) {
+    *pin = Value::String(REDACTED.to_string());
+  }
+  Ok(value)
+}
+
+fn redact_mpv_arg(arg: &str) -> String {
+  let Some((name, _)) = arg.split_once('=') else {
+    return redact(arg);
+  };
+  let lower = name.to_ascii_lowercase();
+  if SENSITIVE_MPV_OPTIONS
+    .iter()
+    .any(|sensitive| lower.contains(sensitive))
+  {
+    format!("{}={}", name, REDACTED)
+  } else {
+    redact(arg)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::settings_lock::SettingsPin;
+  use crate::webhooks::Webhook;
+
+  #[test]
+  fn redact_masks_tokens_in_urls_and_headers() {
+    let line = r#"Playing: https://server/Videos/1/stream?Static=true&api_key=abc123&x=1
+Authorization: MediaBrowser Client="JellyPilot", Token="tok-456""#;
+
+    let redacted = redact(line);
+
+    assert!(redacted.contains("api_key=[REDACTED]&x=1"));
+    assert!(redacted.contains(r#"Token="[REDACTED]""#));
+    assert!(!redacted.contains("abc123"));
+    assert!(!redacted.contains("tok-456"));
+  }
+
+  #[test]
+  fn redacted_config_masks_sensitive_mpv_options_only() {
+    let config = AppConfig {
+      mpv_args: vec![
+        "--http-header-fields=Authorization: Bearer secret".to_string(),
+        "--hwdec=auto".to_string(),
+        "--fullscreen".to_string(),
+      ],
+      ..AppConfig::default()
+    };
+
+    let value = redacted_config(&config).unwrap();
+
+    assert_eq!(
+      value["mpvArgs"],
+      serde_json::json!([
+        "--http-header-fields=[REDACTED]",
+        "--hwdec=auto",
+        "--fullscreen"
+      ])
+    );
+  }
+
+  #[test]
+  fn redacted_config_masks_webhook_secrets_and_api_token() {
+    let config = AppConfig {
+      webhooks: vec![Webhook {
+        url: "https://ha.local/api/webhook/lights".to_string(),
+        secret: "hunter2".to_string(),
+      }],
+      control_api_token: "0123456789abcdef".to_string(),
+      ..AppConfig::default()
+    };
+
+    let value = redacted_config(&config).unwrap();
+
+    assert_eq!(
+      value["webhooks"][0]["url"],
+      "https://ha.local/api/webhook/lights"
+    );
+    assert_eq!(value["webhooks"][0]["secret"], REDACTED);
+    assert_eq!(value["controlApiToken"], REDACTED);
+  }
+
+  #[test]
+  fn redacted_config_masks_the_settings_pin_hash() {
+    let pin = SettingsPin::new("2468").unwrap();
+    let config = AppConfig {
+      settings_pin: Some(pin.clone()),
+      ..AppConfig::default()
+    };
+
+    let value = redacted_config(&config).unwrap();
+    let text = value.to_string();
+
+    assert_eq!(value["settingsPin"], REDACTED);
+    assert!(!text.contains(&pin.hash));
+    assert!(!text.contains(&pin.salt));
+    assert!(redacted_config(&AppConfig::default()).unwrap()["settingsPin"].is_null());
+  }
+
+  #[test]
+  fn write_bundle_includes_config_audit_and_logs() {
+    let dir = std::env::temp_dir().join(format!("jellypilot-diag-test-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let mpv_log = dir.join("mpv-000000000001-1.log");
+    std::fs::write(&mpv_log, "loading ?api_key=secret\n").unwrap();
+    let destination = dir.join("bundle.zip");
+
+    write_bundle(
+      &destination,
+      &DiagnosticsSources {
+        app_log_dir: None,
+        mpv_logs: vec![mpv_log],
+        config: AppConfig::default(),
+        command_audit: Vec::new(),
+        playback_duration: Some(DurationReport {
+          item_id: "item-1".to_string(),
+          jellyfin_runtime_ticks: Some(36_000_000_000),
+          mpv_duration_ticks: Some(18_000_000_000),
+        }),
+      },
+    )
+    .unwrap();
+
+    let mut archive = zip::ZipArchive::new(std::fs::File::open(&destination).unwrap()).unwrap();
+    let mut names: Vec<String> = archive.file_names().map(str::to_string).collect();
+    names.sort();
+    assert_eq!(
+      names,
+      vec![
+        "about.txt",
+        "config.json",
+        "mpv-command-audit.json",
+        "mpv-logs/mpv-000000000001-1.log",
+        "playback-duration.json"
+      ]
+    );
+    let mut log = String::new();
+    std::io::Read::read_to_string(
+      &mut archive.by_name("mpv-logs/mpv-000000000001-1.log").unwrap(),
+      &mut log,
+    )
+    .unwrap();
+    assert_eq!(log, "loading ?api_key=[REDACTED]\n");
+
+    let _ = std::fs::remove_dir_all(dir);
+  }
+}
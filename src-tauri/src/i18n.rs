@@ -0,0 +1,174 @@
+//! Localized user-facing strings produced by the backend.
+//!
+//! Notifications, MPV on-screen messages, tray labels and playback error
+//! messages are looked up here by message id instead of being written
+//! inline. Messages live in Fluent files under `locales/`, embedded at build
+//! time. A message missing from a translation falls back to English. The
+//! language comes from `AppConfig` and applies process-wide.
+
+use std::sync::OnceLock;
+
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use unic_langid::LanguageIdentifier;
+
+/// Language of backend-originated strings.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum Language {
+  #[default]
+  English,
+  German,
+}
+
+impl Language {
+  const ALL: [Language; 2] = [Language::English, Language::German];
+
+  fn locale(self) -> &'static str {
+    match self {
+      Self::English => "en",
+      Self::German => "de",
+    }
+  }
+
+  fn messages(self) -> &'static str {
+    match self {
+      Self::English => include_str!("../locales/en.ftl"),
+      Self::German => include_str!("../locales/de.ftl"),
+    }
+  }
+}
+
+static LANGUAGE: RwLock<Language> = parking_lot::const_rwlock(Language::English);
+static BUNDLES: OnceLock<Vec<(Language, FluentBundle<FluentResource>)>> = OnceLock::new();
+
+/// Switch the language of strings produced from now on.
+pub fn set_language(language: Language) {
+  *LANGUAGE.write() = language;
+}
+
+/// Message `id` in the current language. Prefer `tr!`.
+pub fn translate(id: &str, args: Option<&FluentArgs>) -> String {
+  translate_in(*LANGUAGE.read(), id, args)
+}
+
+/// Look up a message by id, with optional `name = value` arguments:
+/// `tr!("notify-command-failed", error = e.to_string())`.
+macro_rules! tr {
+  ($id:expr) => {
+    $crate::i18n::translate($id, None)
+  };
+  ($id:expr, $($name:ident = $value:expr),+ $(,)?) => {{
+    let mut args = fluent_bundle::FluentArgs::new();
+    $(args.set(stringify!($name), $value);)+
+    $crate::i18n::translate($id, Some(&args))
+  }};
+}
+pub(crate) use tr;
+
+fn translate_in(language: Language, id: &str, args: Option<&FluentArgs>) -> String {
+  for language in [language, Language::English] {
+    let bundle = bundle(language);
+    let Some(pattern) = bundle.get_message(id).and_then(|message| message.value()) else {
+      continue;
+    };
+    let mut errors = Vec::new();
+    let text = bundle.format_pattern(pattern, args, &mut errors);
+    if !errors.is_empty() {
+      log::warn!("Failed to format message {}: {:?}", id, errors);
+    }
+    return text.into_owned();
+  }
+  log::warn!("Missing message {}", id);
+  id.to_string()
+}
+
+fn bundle(language: Language) -> &'static FluentBundle<FluentResource> {
+  let bundles = BUNDLES.get_or_init(|| {
+    Language::ALL
+      .iter()
+      .map(|language| (*language, build_bundle(*language)))
+      .collect()
+  });
+  bundles
+    .iter()
+    .find(|(candidate, _)| *candidate == language)
+    .map(|(_, bundle)| bundle)
+    .expect("every language has a bundle")
+}
+
+fn build_bundle(language: Language) -> FluentBundle<FluentResource> {
+  let locale: LanguageIdentifier = language.locale().parse().expect("valid locale");
+  let mut bundle = FluentBundle::new_concurrent(vec![locale]);
+  // Bidi isolation marks would show up as stray characters in MPV's OSD.
+  bundle.set_use_isolating(false);
+
+  let resource = FluentResource::try_new(language.messages().to_string()).unwrap_or_else(
+    |(resource, errors)| {
+      log::error!("Invalid {} messages: {:?}", language.locale(), errors);
+      resource
+    },
+  );
+  if let Err(errors) = bundle.add_resource(resource) {
+    log::error!("Duplicate {} messages: {:?}", language.locale(), errors);
+  }
+  bundle
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn message_ids(language: Language) -> Vec<&'static str> {
+    if let Err((_, errors)) = FluentResource::try_new(language.messages().to_string()) {
+      panic!("{:?} messages do not parse: {:?}", language, errors);
+    }
+    language
+      .messages()
+      .lines()
+      .filter(|line| line.starts_with(|c: char| c.is_ascii_lowercase()))
+      .filter_map(|line| line.split_once(" = ").map(|(id, _)| id))
+      .collect()
+  }
+
+  #[test]
+  fn translations_only_contain_english_messages() {
+    let english = message_ids(Language::English);
+    for language in Language::ALL {
+      for id in message_ids(language) {
+        assert!(
+          english.contains(&id),
+          "{:?} has unknown message {}",
+          language,
+          id
+        );
+      }
+    }
+  }
+
+  #[test]
+  fn messages_are_formatted_without_isolation_marks() {
+    let mut args = FluentArgs::new();
+    args.set("error", "timed out");
+
+    assert_eq!(
+      translate_in(Language::English, "notify-command-failed", Some(&args)),
+      "Command failed: timed out"
+    );
+    assert_eq!(
+      translate_in(Language::German, "notify-command-failed", Some(&args)),
+      "Befehl fehlgeschlagen: timed out"
+    );
+  }
+
+  #[test]
+  fn unknown_messages_fall_back_to_the_id() {
+    assert_eq!(
+      translate_in(Language::German, "no-such-message", None),
+      "no-such-message"
+    );
+  }
+}
@@ -0,0 +1,312 @@
+//! Time limits for MPV actions.
+//!
+//! MPV actions run one at a time, so one that hangs (a spawn that never
+//! connects, a stream MPV never finishes opening) used to hold up everything
+//! queued behind it, including the stop meant to end it. An action that
+//! outlasts its slow threshold is reported, a stop that arrives meanwhile
+//! cancels it, and after its limit it is given up.
+//!
+//! A stop also supersedes the plays and seeks queued before it: playing a
+//! burst of casts only to stop right after would keep MPV busy for nothing.
+
+use std::collections::VecDeque;
+use std::future::Future;
+use std::time::Duration;
+
+use tokio::sync::mpsc;
+
+use super::session::MpvAction;
+use crate::mpv::CommandTimeouts;
+
+/// Actions the queue holds before senders wait, counting those taken off the
+/// channel while a slow action runs.
+pub const ACTION_QUEUE_CAPACITY: usize = 32;
+
+/// When an action counts as slow and when it is given up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ActionLimits {
+  pub slow_after: Duration,
+  pub give_up_after: Duration,
+}
+
+impl ActionLimits {
+  /// Limits for `action`. Loading may include starting MPV and waiting for
+  /// its IPC socket; everything else is a few short IPC commands. An action
+  /// is not given up before its slowest command has had what the IPC
+  /// `timeouts` allow it, with the slow threshold to spare for the rest.
+  pub fn of(action: &MpvAction, timeouts: &CommandTimeouts) -> Self {
+    let (slow_after, give_up_after, slowest_command) = match action {
+      MpvAction::Play { .. } => (
+        Duration::from_secs(15),
+        Duration::from_secs(60),
+        timeouts.longest(),
+      ),
+      // Only a play loads a file.
+      _ => (
+        Duration::from_secs(5),
+        Duration::from_secs(20),
+        timeouts.longest_except("loadfile"),
+      ),
+    };
+    Self {
+      slow_after,
+      give_up_after: give_up_after.max(slowest_command + slow_after),
+    }
+  }
+}
+
+/// The next action to run: queued actions come first, and plays and seeks
+/// queued before a stop are dropped. None once the channel is closed.
+pub async fn next_action(
+  rx: &mut mpsc::Receiver<MpvAction>,
+  backlog: &mut VecDeque<MpvAction>,
+) -> Option<MpvAction> {
+  while backlog.len() < ACTION_QUEUE_CAPACITY {
+    match rx.try_recv() {
+      Ok(action) => backlog.push_back(action),
+      Err(_) => break,
+    }
+  }
+  let dropped = drop_superseded(backlog);
+  if dropped > 0 {
+    log::info!("Dropped {} queued MPV actions for a stop", dropped);
+  }
+  match backlog.pop_front() {
+    Some(action) => Some(action),
+    None => rx.recv().await,
+  }
+}
+
+/// Remove the plays and seeks queued before the last stop, returning how
+/// many were removed.
+fn drop_superseded(backlog: &mut VecDeque<MpvAction>) -> usize {
+  let Some(last_stop) = backlog.iter().rposition(MpvAction::is_stop) else {
+    return 0;
+  };
+  let before = backlog.len();
+  let mut index = 0;
+  backlog.retain(|action| {
+    let superseded =
+      index < last_stop && matches!(action, MpvAction::Play { .. } | MpvAction::Seek(_));
+    index += 1;
+    !superseded
+  });
+  before - backlog.len()
+}
+
+/// How a watched action ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActionOutcome {
+  Finished,
+  /// Still running at its limit and dropped.
+  TimedOut,
+  /// Slow and dropped for a stop that arrived meanwhile.
+  Preempted,
+}
+
+/// Drive `run` within `limits`. Actions sent meanwhile are moved from `rx`
+/// to the end of `backlog`; `on_slow` is called once the action is slow.
+pub async fn watch(
+  run: impl Future<Output = ()>,
+  limits: ActionLimits,
+  rx: &mut mpsc::Receiver<MpvAction>,
+  backlog: &mut VecDeque<MpvAction>,
+  mut on_slow: impl FnMut(),
+) -> ActionOutcome {
+  let slow = tokio::time::sleep(limits.slow_after);
+  let give_up = tokio::time::sleep(limits.give_up_after);
+  tokio::pin!(run, slow, give_up);
+  let mut is_slow = false;
+
+  loop {
+    tokio::select! {
+      () = &mut run => return ActionOutcome::Finished,
+      () = &mut give_up => return ActionOutcome::TimedOut,
+      () = &mut slow, if !is_slow => {
+        is_slow = true;
+        on_slow();
+        if backlog.iter().any(MpvAction::is_stop) {
+          return ActionOutcome::Preempted;
+        }
+      }
+      Some(next) = rx.recv(), if backlog.len() < ACTION_QUEUE_CAPACITY => {
+        let preempts = is_slow && next.is_stop();
+        backlog.push_back(next);
+        if preempts {
+          return ActionOutcome::Preempted;
+        }
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::config::AppConfig;
+  use std::collections::BTreeMap;
+
+  const LIMITS: ActionLimits = ActionLimits {
+    slow_after: Duration::from_millis(20),
+    give_up_after: Duration::from_millis(200),
+  };
+
+  fn play(title: &str) -> MpvAction {
+    MpvAction::Play {
+      url: format!("https://jellyfin.example/{title}"),
+      start_position: 0.0,
+      title: title.to_string(),
+      audio_index: None,
+      subtitle_index: None,
+      fonts_dir: None,
+    }
+  }
+
+  fn titles(backlog: &VecDeque<MpvAction>) -> Vec<String> {
+    backlog
+      .iter()
+      .map(|action| match action {
+        MpvAction::Play { title, .. } => title.clone(),
+        other => format!("{other:?}"),
+      })
+      .collect()
+  }
+
+  #[test]
+  fn actions_outlast_the_longest_command_timeout_settings_allow() {
+    let config = AppConfig {
+      mpv_command_timeout_seconds: 120,
+      mpv_command_timeouts: BTreeMap::from([("loadfile".to_string(), 120)]),
+      ..Default::default()
+    };
+    assert!(config.validate().is_ok());
+    let timeouts = config.command_timeouts();
+
+    for action in [play("a"), MpvAction::ToggleMute] {
+      let limits = ActionLimits::of(&action, &timeouts);
+      assert!(
+        limits.give_up_after > Duration::from_secs(120),
+        "{action:?}"
+      );
+    }
+  }
+
+  #[test]
+  fn default_timeouts_keep_the_usual_limits() {
+    let timeouts = CommandTimeouts::default();
+
+    assert_eq!(
+      ActionLimits::of(&play("a"), &timeouts).give_up_after,
+      Duration::from_secs(60)
+    );
+    assert_eq!(
+      ActionLimits::of(&MpvAction::Seek(10.0), &timeouts).give_up_after,
+      Duration::from_secs(20)
+    );
+  }
+
+  #[tokio::test]
+  async fn a_stop_runs_before_the_plays_queued_ahead_of_it() {
+    let (tx, mut rx) = mpsc::channel(ACTION_QUEUE_CAPACITY);
+    let mut backlog = VecDeque::new();
+    for title in ["a", "b", "c"] {
+      tx.send(play(title)).await.unwrap();
+      tx.send(MpvAction::Seek(10.0)).await.unwrap();
+    }
+    tx.send(MpvAction::Stop).await.unwrap();
+
+    let next = next_action(&mut rx, &mut backlog).await;
+
+    assert!(matches!(next, Some(MpvAction::Stop)));
+    assert!(backlog.is_empty());
+  }
+
+  #[tokio::test]
+  async fn plays_after_the_last_stop_are_kept() {
+    let (tx, mut rx) = mpsc::channel(ACTION_QUEUE_CAPACITY);
+    let mut backlog = VecDeque::new();
+    tx.send(play("a")).await.unwrap();
+    tx.send(MpvAction::ToggleMute).await.unwrap();
+    tx.send(MpvAction::Stop).await.unwrap();
+    tx.send(play("b")).await.unwrap();
+    tx.send(MpvAction::StopIdle).await.unwrap();
+    tx.send(play("c")).await.unwrap();
+    tx.send(MpvAction::Seek(10.0)).await.unwrap();
+
+    let next = next_action(&mut rx, &mut backlog).await;
+
+    assert!(matches!(next, Some(MpvAction::ToggleMute)));
+    assert_eq!(titles(&backlog), ["Stop", "StopIdle", "c", "Seek(10.0)"]);
+  }
+
+  #[tokio::test]
+  async fn queues_without_a_stop_keep_their_order() {
+    let (tx, mut rx) = mpsc::channel(ACTION_QUEUE_CAPACITY);
+    let mut backlog = VecDeque::from([play("a")]);
+    tx.send(MpvAction::Seek(10.0)).await.unwrap();
+    tx.send(play("b")).await.unwrap();
+
+    let next = next_action(&mut rx, &mut backlog).await;
+
+    assert!(matches!(next, Some(MpvAction::Play { title, .. }) if title == "a"));
+    assert_eq!(titles(&backlog), ["Seek(10.0)", "b"]);
+  }
+
+  #[tokio::test]
+  async fn quick_actions_finish_without_a_warning() {
+    let (_tx, mut rx) = mpsc::channel(4);
+    let mut backlog = VecDeque::new();
+    let mut warnings = 0;
+
+    let outcome = watch(async {}, LIMITS, &mut rx, &mut backlog, || warnings += 1).await;
+
+    assert_eq!(outcome, ActionOutcome::Finished);
+    assert_eq!(warnings, 0);
+  }
+
+  #[tokio::test]
+  async fn a_stop_cancels_a_slow_action() {
+    let (tx, mut rx) = mpsc::channel(4);
+    let mut backlog = VecDeque::new();
+    let mut warnings = 0;
+    tx.send(MpvAction::Seek(30.0)).await.unwrap();
+    tx.send(MpvAction::Stop).await.unwrap();
+
+    let outcome = watch(
+      std::future::pending(),
+      LIMITS,
+      &mut rx,
+      &mut backlog,
+      || warnings += 1,
+    )
+    .await;
+
+    assert_eq!(outcome, ActionOutcome::Preempted);
+    assert_eq!(warnings, 1);
+    assert!(matches!(
+      backlog.make_contiguous(),
+      [MpvAction::Seek(_), MpvAction::Stop]
+    ));
+  }
+
+  #[tokio::test]
+  async fn stuck_actions_are_given_up_at_their_limit() {
+    let (tx, mut rx) = mpsc::channel(4);
+    let mut backlog = VecDeque::new();
+    let mut warnings = 0;
+    tx.send(MpvAction::ToggleMute).await.unwrap();
+
+    let outcome = watch(
+      std::future::pending(),
+      LIMITS,
+      &mut rx,
+      &mut backlog,
+      || warnings += 1,
+    )
+    .await;
+
+    assert_eq!(outcome, ActionOutcome::TimedOut);
+    assert_eq!(warnings, 1);
+    assert_eq!(backlog.len(), 1);
+  }
+}
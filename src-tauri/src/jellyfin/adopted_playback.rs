@@ -0,0 +1,128 @@
+//! Picking up a playback an adopted MPV kept running across a restart.
+//!
+//! Every file loaded into MPV is recorded together with what the server was
+//! told about it. When JellyPilot restarts and adopts the MPV an earlier run
+//! left behind (`adopt_orphaned_mpv`), the record of the file MPV still
+//! plays rebuilds the playback, so progress reporting carries on instead of
+//! the file being stopped and cast again.
+
+use serde::{Deserialize, Serialize};
+
+use super::types::{seconds_to_ticks, MediaItem, PlaybackSession};
+use crate::command::PlayerState;
+
+/// The file last loaded into MPV and the playback it belongs to.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoredPlayback {
+  /// URL MPV was asked to load, compared with the `path` it reports.
+  pub url: String,
+  pub item: MediaItem,
+  pub media_source_id: Option<String>,
+  pub play_session_id: Option<String>,
+  pub audio_stream_index: Option<i32>,
+  pub subtitle_stream_index: Option<i32>,
+  pub play_method: String,
+  pub is_growing: bool,
+  pub subtitles_burned_in: bool,
+  pub live_stream_id: Option<String>,
+}
+
+impl StoredPlayback {
+  /// Record `session` of `item` playing from `url`.
+  pub fn new(url: &str, item: &MediaItem, session: &PlaybackSession) -> Self {
+    Self {
+      url: url.to_string(),
+      item: item.clone(),
+      media_source_id: session.media_source_id.clone(),
+      play_session_id: session.play_session_id.clone(),
+      audio_stream_index: session.audio_stream_index,
+      subtitle_stream_index: session.subtitle_stream_index,
+      play_method: session.play_method.clone(),
+      is_growing: session.is_growing,
+      subtitles_burned_in: session.subtitles_burned_in,
+      live_stream_id: session.live_stream_id.clone(),
+    }
+  }
+
+  /// Whether MPV, playing `mpv_path`, still plays the recorded file.
+  pub fn matches(&self, mpv_path: &str) -> bool {
+    !mpv_path.is_empty() && self.url == mpv_path
+  }
+
+  /// The playback as MPV reports it now; `volume` is already on the remote's scale.
+  pub fn into_session(self, player: &PlayerState, volume: i32) -> PlaybackSession {
+    PlaybackSession {
+      item_id: self.item.id,
+      media_source_id: self.media_source_id,
+      play_session_id: self.play_session_id,
+      skip_ranges: Vec::new(),
+      position_ticks: seconds_to_ticks(player.time_pos),
+      is_paused: player.paused,
+      is_muted: player.muted,
+      volume,
+      audio_stream_index: self.audio_stream_index,
+      subtitle_stream_index: self.subtitle_stream_index,
+      play_method: self.play_method,
+      mpv_duration_ticks: (player.duration > 0.0).then(|| seconds_to_ticks(player.duration)),
+      can_seek: !self.is_growing,
+      is_growing: self.is_growing,
+      subtitles_burned_in: self.subtitles_burned_in,
+      live_stream_id: self.live_stream_id,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn stored() -> StoredPlayback {
+    let item: MediaItem = serde_json::from_value(serde_json::json!({
+      "Id": "item-1",
+      "Name": "Episode",
+      "Type": "Episode",
+    }))
+    .unwrap();
+    StoredPlayback {
+      url: "http://server/Videos/item-1/stream?static=true".to_string(),
+      item,
+      media_source_id: Some("source-1".to_string()),
+      play_session_id: Some("play-1".to_string()),
+      audio_stream_index: Some(1),
+      subtitle_stream_index: None,
+      play_method: "DirectStream".to_string(),
+      is_growing: false,
+      subtitles_burned_in: false,
+      live_stream_id: None,
+    }
+  }
+
+  #[test]
+  fn only_the_recorded_url_matches() {
+    let stored = stored();
+    assert!(stored.matches("http://server/Videos/item-1/stream?static=true"));
+    assert!(!stored.matches("http://server/Videos/item-2/stream?static=true"));
+    assert!(!stored.matches(""));
+  }
+
+  #[test]
+  fn the_session_takes_position_and_pause_from_mpv() {
+    let player = PlayerState {
+      connected: true,
+      paused: true,
+      muted: false,
+      time_pos: 612.5,
+      duration: 1440.0,
+      volume: 80.0,
+    };
+    let session = stored().into_session(&player, 80);
+    assert_eq!(session.item_id, "item-1");
+    assert_eq!(session.play_session_id.as_deref(), Some("play-1"));
+    assert_eq!(session.position_ticks, seconds_to_ticks(612.5));
+    assert!(session.is_paused);
+    assert_eq!(session.volume, 80);
+    assert_eq!(session.mpv_duration_ticks, Some(seconds_to_ticks(1440.0)));
+    assert!(session.can_seek);
+  }
+}
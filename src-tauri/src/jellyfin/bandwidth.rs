@@ -0,0 +1,164 @@
+//! Bytes downloaded by MPV, per playback session and since JellyPilot started.
+//!
+//! MPV has no running byte counter for network reads, so bytes are integrated
+//! from its `cache-speed` property: each sample gives the read speed over the
+//! last second, and the speed holds until the next sample arrives.
+
+use std::time::Instant;
+
+use serde::Serialize;
+use specta::Type;
+
+/// Download totals for users on metered connections.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct BandwidthStats {
+  /// Item of the current or last playback session.
+  pub item_id: Option<String>,
+  /// Bytes downloaded while playing that item.
+  pub session_bytes: f64,
+  /// Seconds since that session started.
+  pub session_seconds: f64,
+  /// Bytes downloaded since JellyPilot started.
+  pub total_bytes: f64,
+  /// Current download speed in bytes per second.
+  pub bytes_per_second: f64,
+}
+
+impl BandwidthStats {
+  /// Average speed of the session, or the current speed before it has any duration.
+  pub fn session_bytes_per_second(&self) -> f64 {
+    if self.session_seconds > 0.0 {
+      self.session_bytes / self.session_seconds
+    } else {
+      self.bytes_per_second
+    }
+  }
+}
+
+#[derive(Debug)]
+struct MeteredSession {
+  item_id: String,
+  bytes: f64,
+  started: Instant,
+}
+
+/// Accumulates MPV read speed samples into byte counts.
+#[derive(Debug, Default)]
+pub struct BandwidthMeter {
+  total_bytes: f64,
+  session: Option<MeteredSession>,
+  bytes_per_second: f64,
+  last_sample: Option<Instant>,
+}
+
+impl BandwidthMeter {
+  /// Record a `cache-speed` sample while `item_id` is playing.
+  ///
+  /// A different item starts a new session; the previous one stays visible
+  /// in the stats until then.
+  pub fn record_speed(&mut self, item_id: Option<&str>, bytes_per_second: f64, now: Instant) {
+    let bytes = self.pending_bytes(now);
+    self.total_bytes += bytes;
+    if let Some(item_id) = item_id {
+      let current = self
+        .session
+        .as_ref()
+        .map(|session| session.item_id.as_str());
+      if current != Some(item_id) {
+        self.session = Some(MeteredSession {
+          item_id: item_id.to_string(),
+          bytes: 0.0,
+          started: now,
+        });
+      }
+      if let Some(session) = &mut self.session {
+        session.bytes += bytes;
+      }
+    }
+
+    self.bytes_per_second = if bytes_per_second.is_finite() {
+      bytes_per_second.max(0.0)
+    } else {
+      0.0
+    };
+    self.last_sample = Some(now);
+  }
+
+  /// Current totals, including bytes read since the last sample.
+  pub fn stats(&self, now: Instant) -> BandwidthStats {
+    let pending = self.pending_bytes(now);
+    BandwidthStats {
+      item_id: self.session.as_ref().map(|session| session.item_id.clone()),
+      session_bytes: self
+        .session
+        .as_ref()
+        .map_or(0.0, |session| session.bytes + pending),
+      session_seconds: self.session.as_ref().map_or(0.0, |session| {
+        now.saturating_duration_since(session.started).as_secs_f64()
+      }),
+      total_bytes: self.total_bytes + pending,
+      bytes_per_second: self.bytes_per_second,
+    }
+  }
+
+  fn pending_bytes(&self, now: Instant) -> f64 {
+    self.last_sample.map_or(0.0, |last| {
+      self.bytes_per_second * now.saturating_duration_since(last).as_secs_f64()
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::time::Duration;
+
+  #[test]
+  fn speed_samples_integrate_into_session_and_total_bytes() {
+    let start = Instant::now();
+    let mut meter = BandwidthMeter::default();
+
+    meter.record_speed(Some("item-1"), 1_000.0, start);
+    meter.record_speed(Some("item-1"), 4_000.0, start + Duration::from_secs(2));
+    let stats = meter.stats(start + Duration::from_secs(3));
+
+    assert_eq!(stats.item_id.as_deref(), Some("item-1"));
+    assert_eq!(stats.session_bytes, 6_000.0);
+    assert_eq!(stats.total_bytes, 6_000.0);
+    assert_eq!(stats.session_seconds, 3.0);
+    assert_eq!(stats.bytes_per_second, 4_000.0);
+    assert_eq!(stats.session_bytes_per_second(), 2_000.0);
+  }
+
+  #[test]
+  fn a_new_item_starts_a_new_session_but_keeps_the_total() {
+    let start = Instant::now();
+    let mut meter = BandwidthMeter::default();
+
+    meter.record_speed(Some("item-1"), 1_000.0, start);
+    meter.record_speed(None, 0.0, start + Duration::from_secs(5));
+    assert_eq!(
+      meter.stats(start + Duration::from_secs(6)).session_bytes,
+      5_000.0
+    );
+
+    meter.record_speed(Some("item-2"), 2_000.0, start + Duration::from_secs(10));
+    let stats = meter.stats(start + Duration::from_secs(11));
+
+    assert_eq!(stats.item_id.as_deref(), Some("item-2"));
+    assert_eq!(stats.session_bytes, 2_000.0);
+    assert_eq!(stats.total_bytes, 7_000.0);
+  }
+
+  #[test]
+  fn invalid_speeds_count_as_idle() {
+    let start = Instant::now();
+    let mut meter = BandwidthMeter::default();
+
+    meter.record_speed(Some("item-1"), f64::NAN, start);
+    meter.record_speed(Some("item-1"), -5.0, start + Duration::from_secs(1));
+
+    assert_eq!(meter.stats(start + Duration::from_secs(2)).total_bytes, 0.0);
+  }
+}
@@ -0,0 +1,167 @@
+//! What JellyPilot tells the server it can do as a cast target.
+//!
+//! The server only offers a cast target for the media types it accepts, and
+//! clients only show the remote controls it supports. Both lists come from
+//! the settings, so e.g. music can be kept off JellyPilot by not accepting
+//! audio.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use crate::config::AppConfig;
+
+/// Media a cast to JellyPilot may play.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum CastMediaType {
+  Video,
+  Audio,
+}
+
+impl CastMediaType {
+  pub const ALL: [Self; 2] = [Self::Video, Self::Audio];
+
+  /// The name the server uses in `PlayableMediaTypes`.
+  pub fn server_name(self) -> &'static str {
+    match self {
+      Self::Video => "Video",
+      Self::Audio => "Audio",
+    }
+  }
+}
+
+/// Remote control command JellyPilot can handle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum RemoteCommand {
+  Play,
+  Playstate,
+  SetVolume,
+  VolumeUp,
+  VolumeDown,
+  ToggleMute,
+  ToggleFullscreen,
+  SetAudioStreamIndex,
+  SetSubtitleStreamIndex,
+  SetRepeatMode,
+  SetShuffleQueue,
+}
+
+impl RemoteCommand {
+  pub const ALL: [Self; 11] = [
+    Self::Play,
+    Self::Playstate,
+    Self::SetVolume,
+    Self::VolumeUp,
+    Self::VolumeDown,
+    Self::ToggleMute,
+    Self::ToggleFullscreen,
+    Self::SetAudioStreamIndex,
+    Self::SetSubtitleStreamIndex,
+    Self::SetRepeatMode,
+    Self::SetShuffleQueue,
+  ];
+
+  /// The name the server uses in `SupportedCommands`.
+  pub fn server_name(self) -> &'static str {
+    match self {
+      Self::Play => "Play",
+      Self::Playstate => "Playstate",
+      Self::SetVolume => "SetVolume",
+      Self::VolumeUp => "VolumeUp",
+      Self::VolumeDown => "VolumeDown",
+      Self::ToggleMute => "ToggleMute",
+      Self::ToggleFullscreen => "ToggleFullscreen",
+      Self::SetAudioStreamIndex => "SetAudioStreamIndex",
+      Self::SetSubtitleStreamIndex => "SetSubtitleStreamIndex",
+      Self::SetRepeatMode => "SetRepeatMode",
+      Self::SetShuffleQueue => "SetShuffleQueue",
+    }
+  }
+}
+
+/// Media types and commands advertised to the server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CastCapabilities {
+  media_types: Vec<CastMediaType>,
+  commands: Vec<RemoteCommand>,
+}
+
+impl Default for CastCapabilities {
+  fn default() -> Self {
+    Self {
+      media_types: CastMediaType::ALL.to_vec(),
+      commands: RemoteCommand::ALL.to_vec(),
+    }
+  }
+}
+
+impl CastCapabilities {
+  /// The capabilities chosen in the settings.
+  pub fn from_config(config: &AppConfig) -> Self {
+    Self {
+      media_types: config.cast_media_types.clone(),
+      commands: config.cast_commands.clone(),
+    }
+  }
+
+  /// Body for `/Sessions/Capabilities/Full`, listing each entry once in the
+  /// server's usual order.
+  pub fn payload(&self) -> serde_json::Value {
+    let media_types: Vec<&str> = CastMediaType::ALL
+      .into_iter()
+      .filter(|media_type| self.media_types.contains(media_type))
+      .map(CastMediaType::server_name)
+      .collect();
+    let commands: Vec<&str> = RemoteCommand::ALL
+      .into_iter()
+      .filter(|command| self.commands.contains(command))
+      .map(RemoteCommand::server_name)
+      .collect();
+    serde_json::json!({
+      "PlayableMediaTypes": media_types,
+      "SupportedCommands": commands,
+      "SupportsMediaControl": true,
+      "SupportsPersistentIdentifier": true,
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn defaults_advertise_everything_jellypilot_handles() {
+    let payload = CastCapabilities::from_config(&AppConfig::default()).payload();
+
+    assert_eq!(
+      payload["PlayableMediaTypes"],
+      serde_json::json!(["Video", "Audio"])
+    );
+    assert_eq!(
+      payload["SupportedCommands"].as_array().map(Vec::len),
+      Some(RemoteCommand::ALL.len())
+    );
+  }
+
+  #[test]
+  fn unchecked_entries_are_left_out() {
+    let payload = CastCapabilities::from_config(&AppConfig {
+      cast_media_types: vec![CastMediaType::Video],
+      cast_commands: vec![
+        RemoteCommand::Playstate,
+        RemoteCommand::Play,
+        RemoteCommand::Play,
+      ],
+      ..Default::default()
+    })
+    .payload();
+
+    assert_eq!(payload["PlayableMediaTypes"], serde_json::json!(["Video"]));
+    assert_eq!(
+      payload["SupportedCommands"],
+      serde_json::json!(["Play", "Playstate"])
+    );
+  }
+}
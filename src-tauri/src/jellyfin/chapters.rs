@@ -0,0 +1,127 @@
+//! Chapter list of the playing item.
+//!
+//! MPV knows where the stream's chapters start, but remuxed files often name
+//! them "Chapter 1" or not at all, and transcodes drop them entirely. The
+//! server keeps the names from the library scan. Chapters are taken from
+//! MPV and named from the server chapter starting at the same time, or
+//! taken from the server alone when the stream has none.
+
+use serde::Serialize;
+use specta::Type;
+
+use super::types::{ticks_to_seconds, ChapterInfo};
+use crate::mpv::Chapter;
+
+/// Server and MPV chapters this close together, in seconds, are the same.
+const SAME_CHAPTER_SECONDS: f64 = 1.0;
+
+/// Chapter of the playing item.
+#[derive(Debug, Clone, PartialEq, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaybackChapter {
+  /// Position in the list, as `playback_goto_chapter` takes it.
+  pub index: u32,
+  /// None when neither the stream nor the server names the chapter.
+  pub title: Option<String>,
+  pub start_seconds: f64,
+}
+
+/// Merge MPV's `chapter-list` with the server's chapters.
+pub fn merge_chapters(mpv: &[Chapter], server: &[ChapterInfo]) -> Vec<PlaybackChapter> {
+  let server: Vec<(f64, Option<&str>)> = server
+    .iter()
+    .map(|chapter| {
+      (
+        ticks_to_seconds(chapter.start_position_ticks),
+        named(chapter.name.as_deref()),
+      )
+    })
+    .collect();
+
+  let chapters: Vec<(f64, Option<&str>)> = if mpv.is_empty() {
+    server
+  } else {
+    mpv
+      .iter()
+      .map(|chapter| {
+        let server_name = server
+          .iter()
+          .find(|(start, _)| (start - chapter.time).abs() <= SAME_CHAPTER_SECONDS)
+          .and_then(|(_, name)| *name);
+        (
+          chapter.time,
+          server_name.or_else(|| named(chapter.title.as_deref())),
+        )
+      })
+      .collect()
+  };
+
+  chapters
+    .into_iter()
+    .zip(0..)
+    .map(|((start_seconds, title), index)| PlaybackChapter {
+      index,
+      title: title.map(str::to_string),
+      start_seconds,
+    })
+    .collect()
+}
+
+fn named(title: Option<&str>) -> Option<&str> {
+  title.map(str::trim).filter(|title| !title.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn mpv(title: Option<&str>, time: f64) -> Chapter {
+    Chapter {
+      title: title.map(str::to_string),
+      time,
+    }
+  }
+
+  fn server(name: &str, seconds: i64) -> ChapterInfo {
+    ChapterInfo {
+      start_position_ticks: seconds * 10_000_000,
+      name: Some(name.to_string()),
+    }
+  }
+
+  fn titles(chapters: &[PlaybackChapter]) -> Vec<Option<&str>> {
+    chapters
+      .iter()
+      .map(|chapter| chapter.title.as_deref())
+      .collect()
+  }
+
+  #[test]
+  fn stream_chapters_take_the_server_names() {
+    let chapters = merge_chapters(
+      &[
+        mpv(None, 0.0),
+        mpv(Some("Chapter 2"), 90.4),
+        mpv(Some(""), 600.0),
+      ],
+      &[
+        server("Cold Open", 0),
+        server("Titles", 90),
+        server("", 600),
+      ],
+    );
+
+    assert_eq!(titles(&chapters), [Some("Cold Open"), Some("Titles"), None]);
+    assert_eq!(chapters[1].index, 1);
+    assert_eq!(chapters[1].start_seconds, 90.4);
+  }
+
+  #[test]
+  fn server_chapters_stand_in_for_a_stream_without_any() {
+    let chapters = merge_chapters(&[], &[server("Intro", 0), server("Act One", 75)]);
+
+    assert_eq!(titles(&chapters), [Some("Intro"), Some("Act One")]);
+    assert_eq!(chapters[1].start_seconds, 75.0);
+    assert!(merge_chapters(&[], &[]).is_empty());
+  }
+}
@@ -2,37 +2,47 @@
 
 use parking_lot::RwLock;
 use reqwest::{header, Client, Method};
+use std::collections::BTreeMap;
+use std::path::Path;
 use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::image_cache::ImageDownload;
 use crate::image_ref::{image_id_for_url, ImageRefKind};
+use crate::redact::redact;
+use crate::retention::BufferUsage;
 
+use super::capabilities::CastCapabilities;
+use super::custom_headers;
 use super::error::JellyfinError;
-use super::intro_skipper::{
-  parse_intro_skipper_ranges, IntroSkipRange, IntroSkipperPluginResponse,
-};
+use super::intro_skipper::{parse_intro_skipper_ranges, IntroSkipperPluginResponse};
+use super::response_cache::ResponseCache;
+use super::skip_range::SkipRange;
+use super::tls::{self, TlsTrust};
 use super::types::*;
+use super::websocket::JellyfinWebSocket;
 
 /// Device info for Jellyfin client identification.
 const DEFAULT_DEVICE_NAME: &str = "JellyPilot";
 const DEVICE_ID_PREFIX: &str = "jellypilot-";
 const CLIENT_NAME: &str = "JellyPilot";
 const CLIENT_VERSION: &str = env!("CARGO_PKG_VERSION");
-const SUPPORTED_REMOTE_COMMANDS: &[&str] = &[
-  "Play",
-  "Playstate",
-  "SetVolume",
-  "ToggleMute",
-  "ToggleFullscreen",
-  "SetAudioStreamIndex",
-  "SetSubtitleStreamIndex",
-];
+/// Most consecutive specials stepped over when skipping specials.
+const MAX_SKIPPED_SPECIALS: usize = 10;
+/// Most collections searched for the one holding the current item.
+const MAX_COLLECTIONS_SCANNED: usize = 100;
+/// How long a server address may take to answer before another one is preferred.
+const ADDRESS_PROBE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(3);
+/// How recently another session must have been active to be listed.
+const OTHER_SESSIONS_ACTIVE_WITHIN_SECONDS: u32 = 960;
+/// Lowest Emby user rating that counts as a thumbs up.
+const EMBY_LIKES_MIN_RATING: f64 = 6.5;
 
 /// Jellyfin HTTP API client.
 pub struct JellyfinClient {
-  http: Client,
+  http: RwLock<Client>,
   state: Arc<RwLock<ClientState>>,
+  cache: RwLock<ResponseCache>,
 }
 /// Login/session lifecycle interface for the Jellyfin HTTP adapter.
 pub struct JellyfinLogin<'a> {
@@ -49,6 +59,22 @@ pub struct JellyfinLibrary<'a> {
   client: &'a JellyfinClient,
 }
 
+/// Addresses a server profile can be reached at.
+#[derive(Debug, Clone)]
+struct ServerAddresses {
+  /// The profile's server URL, usually the external one.
+  remote: String,
+  /// LAN address of the same server.
+  local: Option<String>,
+}
+
+/// The part of `/System/Info/Public` an address probe checks.
+#[derive(serde::Deserialize)]
+struct PublicServerId {
+  #[serde(rename = "Id")]
+  id: Option<String>,
+}
+
 /// Internal connection state.
 struct ClientState {
   provider: MediaServerProvider,
@@ -59,8 +85,16 @@ struct ClientState {
   user_id: Option<String>,
   user_name: Option<String>,
   server_name: Option<String>,
+  server_id: Option<String>,
   device_id: String,
   device_name: String,
+  tls: TlsTrust,
+  /// Headers a reverse proxy in front of the server requires.
+  custom_headers: BTreeMap<String, String>,
+  /// Media types and commands advertised as a cast target.
+  capabilities: CastCapabilities,
+  /// Addresses `server_url` is chosen from; `None` when only one is known.
+  addresses: Option<ServerAddresses>,
 }
 
 impl JellyfinClient {
@@ -69,10 +103,12 @@ impl JellyfinClient {
     let device_id = format!("{}{}", DEVICE_ID_PREFIX, Uuid::new_v4());
 
     Self {
-      http: Client::builder()
-        .timeout(std::time::Duration::from_secs(30))
-        .build()
-        .expect("Failed to create HTTP client"),
+      http: RwLock::new(
+        Client::builder()
+          .timeout(std::time::Duration::from_secs(30))
+          .build()
+          .expect("Failed to create HTTP client"),
+      ),
       state: Arc::new(RwLock::new(ClientState {
         provider: MediaServerProvider::Jellyfin,
         remote_control_available: false,
@@ -82,9 +118,15 @@ impl JellyfinClient {
         user_id: None,
         user_name: None,
         server_name: None,
+        server_id: None,
         device_id,
         device_name: DEFAULT_DEVICE_NAME.to_string(),
+        tls: TlsTrust::default(),
+        custom_headers: BTreeMap::new(),
+        capabilities: CastCapabilities::default(),
+        addresses: None,
       })),
+      cache: RwLock::new(ResponseCache::default()),
     }
   }
   /// Login/session lifecycle operations.
@@ -102,20 +144,102 @@ impl JellyfinClient {
     JellyfinLibrary { client: self }
   }
 
+  fn http(&self) -> Client {
+    self.http.read().clone()
+  }
+
+  /// HTTP client builder with the shared timeout, the given certificate
+  /// trust and the profile's custom headers.
+  fn http_builder(
+    tls: &TlsTrust,
+    custom_headers: &BTreeMap<String, String>,
+  ) -> Result<reqwest::ClientBuilder, JellyfinError> {
+    let builder = Client::builder()
+      .timeout(std::time::Duration::from_secs(30))
+      .default_headers(custom_headers::header_map(custom_headers)?);
+    Ok(match tls.client_config()? {
+      Some(config) => builder.tls_backend_preconfigured(config),
+      None => builder,
+    })
+  }
+
+  /// HTTP client builder for the current connection settings.
+  fn current_http_builder(&self) -> Result<reqwest::ClientBuilder, JellyfinError> {
+    let (tls, custom_headers) = {
+      let state = self.state.read();
+      (state.tls.clone(), state.custom_headers.clone())
+    };
+    Self::http_builder(&tls, &custom_headers)
+  }
+
+  /// Trust certificates for the server as configured in its profile.
+  pub fn set_tls_trust(&self, tls: &TlsTrust) -> Result<(), JellyfinError> {
+    let custom_headers = self.state.read().custom_headers.clone();
+    let http = Self::http_builder(tls, &custom_headers)?.build()?;
+    *self.http.write() = http;
+    self.state.write().tls = tls.clone();
+    Ok(())
+  }
+
+  /// Send the headers from the server's profile with every request.
+  pub fn set_custom_headers(
+    &self,
+    custom_headers: &BTreeMap<String, String>,
+  ) -> Result<(), JellyfinError> {
+    let tls = self.state.read().tls.clone();
+    let http = Self::http_builder(&tls, custom_headers)?.build()?;
+    *self.http.write() = http;
+    self.state.write().custom_headers = custom_headers.clone();
+    Ok(())
+  }
+
+  /// Custom headers for the WebSocket upgrade request.
+  pub fn websocket_headers(&self) -> Result<header::HeaderMap, JellyfinError> {
+    custom_headers::header_map(&self.state.read().custom_headers)
+  }
+
+  /// TLS configuration for the WebSocket, or `None` for the defaults.
+  pub fn websocket_tls_config(&self) -> Result<Option<Arc<rustls::ClientConfig>>, JellyfinError> {
+    let tls = self.state.read().tls.clone();
+    Ok(tls.client_config()?.map(Arc::new))
+  }
+
+  /// Fingerprint of the certificate a server presents, for the user to accept.
+  pub async fn fetch_certificate_fingerprint(
+    &self,
+    server_url: &str,
+  ) -> Result<String, JellyfinError> {
+    let server_url = Self::normalize_server_url(server_url)?;
+    tls::fetch_certificate_fingerprint(&server_url).await
+  }
+
   /// Set the device name (shown in Jellyfin cast menu).
   pub fn set_device_name(&self, name: String) {
     self.state.write().device_name = name;
   }
 
+  /// Set what the next capability registration advertises.
+  pub fn set_cast_capabilities(&self, capabilities: CastCapabilities) {
+    self.state.write().capabilities = capabilities;
+  }
+
   /// Get the device ID.
   pub fn device_id(&self) -> String {
     self.state.read().device_id.clone()
   }
 
+  /// Replace the device ID with a new random one, e.g. when another install
+  /// uses the same. Returns the new ID.
+  pub fn regenerate_device_id(&self) -> String {
+    let device_id = format!("{}{}", DEVICE_ID_PREFIX, Uuid::new_v4());
+    self.state.write().device_id = device_id.clone();
+    device_id
+  }
+
   pub async fn download_image(&self, url: &str) -> Result<ImageDownload, JellyfinError> {
     let token = self.state.read().access_token.clone();
     let response = self
-      .http
+      .http()
       .get(url)
       .header(header::AUTHORIZATION, self.auth_header(token.as_deref()))
       .header(header::USER_AGENT, self.request_user_agent())
@@ -186,8 +310,8 @@ impl JellyfinClient {
     let mut configuration = jellyfin_api::apis::configuration::Configuration::new();
     configuration.base_path = server_url.to_string();
     configuration.user_agent = Some(Self::app_user_agent());
-    configuration.client = Client::builder()
-      .timeout(std::time::Duration::from_secs(30))
+    configuration.client = self
+      .current_http_builder()?
       .default_headers(headers)
       .build()?;
 
@@ -208,8 +332,8 @@ impl JellyfinClient {
     let mut configuration = emby_api::apis::configuration::Configuration::new();
     configuration.base_path = server_url.to_string();
     configuration.user_agent = Some(Self::emby_chrome_user_agent());
-    configuration.client = Client::builder()
-      .timeout(std::time::Duration::from_secs(30))
+    configuration.client = self
+      .current_http_builder()?
       .default_headers(headers)
       .build()?;
 
@@ -233,11 +357,20 @@ impl JellyfinClient {
     }
   }
 
+  /// Like `openapi_error`, but HTTP failures are authentication failures and
+  /// a 401 is built with `unauthorized`.
   fn openapi_auth_error<T: std::fmt::Debug>(
     context: &str,
     err: jellyfin_api::apis::Error<T>,
+    unauthorized: fn(String) -> JellyfinError,
   ) -> JellyfinError {
+    let rejected = matches!(
+      &err,
+      jellyfin_api::apis::Error::ResponseError(response)
+        if response.status == reqwest::StatusCode::UNAUTHORIZED
+    );
     match Self::openapi_error(context, err) {
+      JellyfinError::HttpError(message) if rejected => unauthorized(message),
       JellyfinError::HttpError(message) => JellyfinError::AuthFailed(message),
       err => err,
     }
@@ -265,13 +398,30 @@ impl JellyfinClient {
   fn emby_openapi_auth_error<T: std::fmt::Debug>(
     context: &str,
     err: emby_api::apis::Error<T>,
+    unauthorized: fn(String) -> JellyfinError,
   ) -> JellyfinError {
+    let rejected = matches!(
+      &err,
+      emby_api::apis::Error::ResponseError(response)
+        if response.status == reqwest::StatusCode::UNAUTHORIZED
+    );
     match Self::emby_openapi_error(context, err) {
+      JellyfinError::HttpError(message) if rejected => unauthorized(message),
       JellyfinError::HttpError(message) => JellyfinError::AuthFailed(message),
       err => err,
     }
   }
 
+  /// Error for a failed authenticated request; a 401 means the token was
+  /// revoked, e.g. because the password changed.
+  fn request_error(status: reqwest::StatusCode, message: String) -> JellyfinError {
+    if status == reqwest::StatusCode::UNAUTHORIZED {
+      JellyfinError::CredentialsExpired(message)
+    } else {
+      JellyfinError::HttpError(message)
+    }
+  }
+
   fn missing_openapi_field(context: &str, field: &str) -> JellyfinError {
     JellyfinError::HttpError(format!("{context} response missing {field}"))
   }
@@ -400,10 +550,134 @@ impl JellyfinClient {
 
   /// Authenticate with Jellyfin server.
   pub async fn authenticate(&self, creds: &Credentials) -> Result<AuthResponse, JellyfinError> {
-    match creds.provider {
-      MediaServerProvider::Jellyfin => self.authenticate_jellyfin(creds).await,
-      MediaServerProvider::Emby => self.authenticate_emby(creds).await,
+    self.set_tls_trust(&creds.tls)?;
+    self.set_custom_headers(&creds.custom_headers)?;
+    let remote = Self::normalize_server_url(&creds.server_url)?;
+    let local = creds
+      .local_url
+      .as_deref()
+      .map(Self::normalize_server_url)
+      .transpose()?;
+
+    let mut chosen = creds.clone();
+    if let Some(local) = &local {
+      let candidates = [local.clone(), remote.clone()];
+      if let Some(fastest) = self.fastest_address(&candidates, None).await {
+        chosen.server_url = fastest;
+      }
+    }
+
+    let auth = match chosen.provider {
+      MediaServerProvider::Jellyfin => self.authenticate_jellyfin(&chosen).await,
+      MediaServerProvider::Emby => self.authenticate_emby(&chosen).await,
+    }?;
+
+    // Emby discovery may append an API base; apply it to every address.
+    let chosen_url = Self::normalize_server_url(&chosen.server_url)?;
+    let mut state = self.state.write();
+    let api_base = state
+      .server_url
+      .as_deref()
+      .and_then(|resolved| resolved.strip_prefix(chosen_url.as_str()))
+      .unwrap_or_default()
+      .to_string();
+    state.addresses = Some(ServerAddresses {
+      remote: format!("{remote}{api_base}"),
+      local: local.map(|local| format!("{local}{api_base}")),
+    });
+
+    Ok(auth)
+  }
+
+  /// Probe the addresses and return the one whose public info answered first.
+  ///
+  /// With `server_id`, an address only counts when the server there reports
+  /// that ID, so another server on the LAN address is never picked.
+  async fn fastest_address(
+    &self,
+    candidates: &[String],
+    server_id: Option<&str>,
+  ) -> Option<String> {
+    if candidates.len() < 2 {
+      return candidates.first().cloned();
+    }
+
+    let http = self.http();
+    let probes = candidates.iter().map(|url| {
+      let request = http
+        .get(format!("{url}/System/Info/Public"))
+        .header(header::USER_AGENT, self.request_user_agent())
+        .timeout(ADDRESS_PROBE_TIMEOUT);
+      Box::pin(async move {
+        let response = request
+          .send()
+          .await
+          .map_err(|err| format!("{url}: {err}"))?;
+        if !response.status().is_success() {
+          return Err(format!("{url}: HTTP {}", response.status()));
+        }
+        let Some(expected) = server_id else {
+          return Ok(url.clone());
+        };
+        let info: PublicServerId = response
+          .json()
+          .await
+          .map_err(|err| format!("{url}: invalid public info: {err}"))?;
+        match info.id {
+          Some(id) if same_server_id(&id, expected) => Ok(url.clone()),
+          Some(id) => Err(format!("{url}: another server answered ({id})")),
+          None => Err(format!("{url}: public info has no server ID")),
+        }
+      })
+    });
+
+    match futures_util::future::select_ok(probes).await {
+      Ok((url, _)) => Some(url),
+      Err(err) => {
+        log::warn!("No server address answered, last failure: {}", err);
+        None
+      }
+    }
+  }
+
+  /// Switch `server_url` to the fastest reachable address, e.g. after moving
+  /// between home and away. Returns true when the address changed.
+  ///
+  /// Only addresses where the saved server answers count; without a saved
+  /// server ID the address is left alone.
+  pub async fn select_server_address(&self) -> bool {
+    let (addresses, current, server_id) = {
+      let state = self.state.read();
+      (
+        state.addresses.clone(),
+        state.server_url.clone(),
+        state.server_id.clone(),
+      )
+    };
+    let (
+      Some(ServerAddresses {
+        remote,
+        local: Some(local),
+      }),
+      Some(server_id),
+    ) = (addresses, server_id)
+    else {
+      return false;
+    };
+
+    let Some(fastest) = self
+      .fastest_address(&[local, remote], Some(&server_id))
+      .await
+    else {
+      return false;
+    };
+    if current.as_deref() == Some(fastest.as_str()) {
+      return false;
     }
+
+    log::info!("Switching server address to {}", fastest);
+    self.state.write().server_url = Some(fastest);
+    true
   }
 
   async fn authenticate_jellyfin(
@@ -423,7 +697,13 @@ impl JellyfinClient {
       },
     )
     .await
-    .map_err(|err| Self::openapi_auth_error("Password authentication", err))
+    .map_err(|err| {
+      Self::openapi_auth_error(
+        "Password authentication",
+        err,
+        JellyfinError::InvalidCredentials,
+      )
+    })
     .and_then(Self::auth_response_from_openapi)?;
 
     // Store connection state
@@ -436,6 +716,7 @@ impl JellyfinClient {
       state.access_token = Some(auth.access_token.clone());
       state.user_id = Some(auth.user.id.clone());
       state.user_name = Some(auth.user.name.clone());
+      state.server_id = Some(auth.server_id.clone());
     }
 
     // Fetch server info
@@ -457,6 +738,7 @@ impl JellyfinClient {
       state.user_id = Some(auth.user.id.clone());
       state.user_name = Some(auth.user.name.clone());
       state.server_name = info.map(|info| info.server_name);
+      state.server_id = Some(auth.server_id.clone());
     }
 
     Ok(auth)
@@ -495,7 +777,7 @@ impl JellyfinClient {
             .ok();
           return Ok((candidate, auth, info));
         }
-        Err(JellyfinError::AuthFailed(message)) => {
+        Err(JellyfinError::AuthFailed(message) | JellyfinError::InvalidCredentials(message)) => {
           auth_failures.push(format!("{candidate}: {message}"));
         }
         Err(err) => auth_failures.push(format!("{candidate}: {err}")),
@@ -506,7 +788,7 @@ impl JellyfinClient {
       .iter()
       .any(|failure| failure.contains("HTTP 401 Unauthorized"))
     {
-      return Err(JellyfinError::AuthFailed(format!(
+      return Err(JellyfinError::InvalidCredentials(format!(
         "Password authentication failed. {}",
         auth_failures.join("; ")
       )));
@@ -537,7 +819,13 @@ impl JellyfinClient {
       },
     )
     .await
-    .map_err(|err| Self::emby_openapi_auth_error("Password authentication", err))
+    .map_err(|err| {
+      Self::emby_openapi_auth_error(
+        "Password authentication",
+        err,
+        JellyfinError::InvalidCredentials,
+      )
+    })
     .and_then(Self::emby_auth_response_from_openapi)
   }
 
@@ -626,7 +914,13 @@ impl JellyfinClient {
       },
     )
     .await
-    .map_err(|err| Self::openapi_auth_error("Quick Connect authentication", err))
+    .map_err(|err| {
+      Self::openapi_auth_error(
+        "Quick Connect authentication",
+        err,
+        JellyfinError::AuthFailed,
+      )
+    })
     .and_then(Self::auth_response_from_openapi)?;
 
     {
@@ -669,6 +963,7 @@ impl JellyfinClient {
     {
       let mut state = self.state.write();
       state.server_name = Some(info.server_name.clone());
+      state.server_id = Some(info.id.clone());
     }
 
     Ok(info)
@@ -685,7 +980,13 @@ impl JellyfinClient {
 
         jellyfin_api::apis::user_api::get_current_user(&configuration)
           .await
-          .map_err(|err| Self::openapi_auth_error("Saved session validation", err))?;
+          .map_err(|err| {
+            Self::openapi_auth_error(
+              "Saved session validation",
+              err,
+              JellyfinError::CredentialsExpired,
+            )
+          })?;
       }
       MediaServerProvider::Emby => {
         let user_id = self.user_id()?;
@@ -696,7 +997,13 @@ impl JellyfinClient {
           emby_api::apis::user_service_api::GetUsersByIdParams { id: user_id },
         )
         .await
-        .map_err(|err| Self::emby_openapi_auth_error("Saved session validation", err))?;
+        .map_err(|err| {
+          Self::emby_openapi_auth_error(
+            "Saved session validation",
+            err,
+            JellyfinError::CredentialsExpired,
+          )
+        })?;
       }
     }
 
@@ -705,6 +1012,7 @@ impl JellyfinClient {
 
   /// Disconnect from server.
   pub fn disconnect(&self) {
+    self.invalidate_cache();
     let mut state = self.state.write();
     state.provider = MediaServerProvider::Jellyfin;
     state.remote_control_available = false;
@@ -714,12 +1022,24 @@ impl JellyfinClient {
     state.user_id = None;
     state.user_name = None;
     state.server_name = None;
+    state.server_id = None;
+    state.addresses = None;
+    drop(state);
+    if let Err(e) = self.set_tls_trust(&TlsTrust::default()) {
+      log::warn!("Failed to reset TLS trust: {}", e);
+    }
+    if let Err(e) = self.set_custom_headers(&BTreeMap::new()) {
+      log::warn!("Failed to reset custom headers: {}", e);
+    }
   }
 
   /// Restore a session from saved data.
   ///
   /// Validates the token by making a test API call.
   pub async fn restore_session(&self, session: &SavedSession) -> Result<(), JellyfinError> {
+    self.set_tls_trust(&session.tls)?;
+    self.set_custom_headers(&session.custom_headers)?;
+
     // Set the state first
     {
       let mut state = self.state.write();
@@ -731,11 +1051,17 @@ impl JellyfinClient {
       state.user_id = Some(session.user_id.clone());
       state.user_name = Some(session.user_name.clone());
       state.server_name = session.server_name.clone();
+      state.server_id = session.server_id.clone();
       // Restore device_id if present, otherwise keep the generated one
       if let Some(saved_device_id) = &session.device_id {
         state.device_id = saved_device_id.clone();
       }
+      state.addresses = Some(ServerAddresses {
+        remote: session.server_url.clone(),
+        local: session.local_url.clone(),
+      });
     }
+    self.select_server_address().await;
 
     // Validate the token with an authenticated endpoint, then refresh public
     // server info for connection state.
@@ -752,10 +1078,11 @@ impl JellyfinClient {
       Ok(_) => Ok(()),
       Err(e) => {
         self.disconnect();
-        Err(JellyfinError::AuthFailed(format!(
-          "Session validation failed: {}",
-          e
-        )))
+        let message = format!("Session validation failed: {}", e);
+        Err(match e {
+          JellyfinError::CredentialsExpired(_) => JellyfinError::CredentialsExpired(message),
+          _ => JellyfinError::AuthFailed(message),
+        })
       }
     }
   }
@@ -769,14 +1096,19 @@ impl JellyfinClient {
       state.user_id.clone(),
       state.user_name.clone(),
     ) {
+      let addresses = state.addresses.as_ref();
       Some(SavedSession {
         provider: state.provider,
-        server_url,
+        server_url: addresses.map_or(server_url, |addresses| addresses.remote.clone()),
         access_token,
         user_id,
         user_name,
         server_name: state.server_name.clone(),
         device_id: Some(state.device_id.clone()),
+        tls: state.tls.clone(),
+        local_url: addresses.and_then(|addresses| addresses.local.clone()),
+        custom_headers: state.custom_headers.clone(),
+        server_id: state.server_id.clone(),
       })
     } else {
       None
@@ -815,6 +1147,11 @@ impl JellyfinClient {
 
   fn normalize_server_url(server_url: &str) -> Result<String, JellyfinError> {
     let server_url = server_url.trim_end_matches('/').to_string();
+    if has_unbracketed_ipv6_host(&server_url) {
+      return Err(JellyfinError::InvalidUrl(
+        "IPv6 addresses must be enclosed in brackets, e.g. http://[fd00::10]:8096".to_string(),
+      ));
+    }
     let parsed = reqwest::Url::parse(&server_url)
       .map_err(|err| JellyfinError::InvalidUrl(format!("URL could not be parsed: {err}")))?;
     if parsed.scheme() != "http" && parsed.scheme() != "https" {
@@ -902,7 +1239,7 @@ impl JellyfinClient {
     let url = format!("{}{}", server_url, path);
 
     let response = self
-      .http
+      .http()
       .get(&url)
       .header(header::USER_AGENT, self.request_user_agent())
       .header("X-Emby-Authorization", self.auth_header(Some(&token)))
@@ -912,15 +1249,45 @@ impl JellyfinClient {
     let status = response.status();
     if !status.is_success() {
       let body = response.text().await.unwrap_or_default();
-      return Err(JellyfinError::HttpError(format!(
-        "GET {} failed: HTTP {} - {}",
-        path, status, body
-      )));
+      return Err(Self::request_error(
+        status,
+        format!("GET {} failed: HTTP {} - {}", path, status, body),
+      ));
     }
 
     Ok(response.json().await?)
   }
 
+  /// Authenticated GET answered from the response cache while it is fresh.
+  async fn get_cached<T: serde::de::DeserializeOwned>(
+    &self,
+    path: &str,
+  ) -> Result<T, JellyfinError> {
+    let key = format!("{}{}", self.server_url()?, path);
+    let cached = self.cache.read().get(&key, std::time::Instant::now());
+    if let Some(body) = cached {
+      log::debug!("Response cache hit: {}", path);
+      return Ok(serde_json::from_value(body)?);
+    }
+
+    let body: serde_json::Value = self.get(path).await?;
+    self
+      .cache
+      .write()
+      .insert(key, body.clone(), std::time::Instant::now());
+    Ok(serde_json::from_value(body)?)
+  }
+
+  /// Drop all cached responses, e.g. after the server reports changed user data.
+  pub fn invalidate_cache(&self) {
+    self.cache.write().clear();
+  }
+
+  /// Cached responses and their size, for the memory report.
+  pub fn response_cache_usage(&self) -> BufferUsage {
+    self.cache.read().usage()
+  }
+
   async fn get_with_query<T: serde::de::DeserializeOwned>(
     &self,
     path: &str,
@@ -931,7 +1298,7 @@ impl JellyfinClient {
     let url = format!("{}{}", server_url, path);
 
     let response = self
-      .http
+      .http()
       .get(&url)
       .header(header::USER_AGENT, self.request_user_agent())
       .header("X-Emby-Authorization", self.auth_header(Some(&token)))
@@ -942,10 +1309,10 @@ impl JellyfinClient {
     let status = response.status();
     if !status.is_success() {
       let body = response.text().await.unwrap_or_default();
-      return Err(JellyfinError::HttpError(format!(
-        "GET {} failed: HTTP {} - {}",
-        path, status, body
-      )));
+      return Err(Self::request_error(
+        status,
+        format!("GET {} failed: HTTP {} - {}", path, status, body),
+      ));
     }
 
     Ok(response.json().await?)
@@ -961,7 +1328,7 @@ impl JellyfinClient {
     let url = format!("{}{}", server_url, path);
 
     let response = self
-      .http
+      .http()
       .request(method.clone(), &url)
       .header(header::USER_AGENT, self.request_user_agent())
       .header("X-Emby-Authorization", self.auth_header(Some(&token)))
@@ -971,10 +1338,10 @@ impl JellyfinClient {
     let status = response.status();
     if !status.is_success() {
       let body = response.text().await.unwrap_or_default();
-      return Err(JellyfinError::HttpError(format!(
-        "{} {} failed: HTTP {} - {}",
-        method, path, status, body
-      )));
+      return Err(Self::request_error(
+        status,
+        format!("{} {} failed: HTTP {} - {}", method, path, status, body),
+      ));
     }
 
     Ok(response.json().await?)
@@ -991,7 +1358,7 @@ impl JellyfinClient {
     let url = format!("{}{}", server_url, path);
 
     let response = self
-      .http
+      .http()
       .post(&url)
       .header(header::USER_AGENT, self.request_user_agent())
       .header(header::CONTENT_TYPE, "application/json")
@@ -1003,10 +1370,10 @@ impl JellyfinClient {
     let status = response.status();
     if !status.is_success() {
       let body = response.text().await.unwrap_or_default();
-      return Err(JellyfinError::HttpError(format!(
-        "POST {} failed: HTTP {} - {}",
-        path, status, body
-      )));
+      return Err(Self::request_error(
+        status,
+        format!("POST {} failed: HTTP {} - {}", path, status, body),
+      ));
     }
 
     Ok(response.json().await?)
@@ -1022,10 +1389,14 @@ impl JellyfinClient {
     let token = self.access_token()?;
     let url = format!("{}{}", server_url, path);
 
-    log::debug!("POST {} with body: {:?}", path, body);
+    log::debug!(
+      "POST {} with body: {}",
+      path,
+      redact(&format!("{:?}", body))
+    );
 
     let response = self
-      .http
+      .http()
       .post(&url)
       .header(header::USER_AGENT, self.request_user_agent())
       .header(header::CONTENT_TYPE, "application/json")
@@ -1038,10 +1409,10 @@ impl JellyfinClient {
     if !status.is_success() {
       let body = response.text().await.unwrap_or_default();
       log::error!("POST {} failed with status {}: {}", path, status, body);
-      return Err(JellyfinError::HttpError(format!(
-        "HTTP {} - {}",
-        status, body
-      )));
+      return Err(Self::request_error(
+        status,
+        format!("HTTP {} - {}", status, body),
+      ));
     }
 
     Ok(())
@@ -1051,16 +1422,60 @@ impl JellyfinClient {
   pub async fn get_item(&self, item_id: &str) -> Result<MediaItem, JellyfinError> {
     let user_id = self.user_id()?;
     self
-      .get(&format!("/Users/{}/Items/{}", user_id, item_id))
+      .get_media_item(&format!("/Users/{}/Items/{}", user_id, item_id))
       .await
   }
 
+  /// Get the chapters the server knows for a media item.
+  pub async fn get_item_chapters(&self, item_id: &str) -> Result<Vec<ChapterInfo>, JellyfinError> {
+    let user_id = self.user_id()?;
+    let item: ItemChapters = self
+      .get_cached(&format!("/Users/{}/Items/{}", user_id, item_id))
+      .await?;
+    Ok(item.chapters)
+  }
+
+  async fn get_media_item(&self, path: &str) -> Result<MediaItem, JellyfinError> {
+    #[cfg(feature = "openapi-models")]
+    if self.provider() == MediaServerProvider::Jellyfin {
+      let dto: jellyfin_api::models::BaseItemDto = self.get_cached(path).await?;
+      return super::openapi_models::media_item(dto);
+    }
+    self.get_cached(path).await
+  }
+
+  /// Get media item by ID, bypassing the response cache.
+  ///
+  /// Used to follow fields that change during playback, such as the status
+  /// and runtime of an in-progress recording.
+  pub async fn refresh_item(&self, item_id: &str) -> Result<MediaItem, JellyfinError> {
+    let user_id = self.user_id()?;
+    let path = format!("/Users/{}/Items/{}", user_id, item_id);
+    let key = format!("{}{}", self.server_url()?, path);
+    self.cache.write().remove(&key);
+    self.get_media_item(&path).await
+  }
+
   /// Get playback info for a media item.
   pub async fn get_playback_info(
     &self,
     item_id: &str,
     audio_stream_index: Option<i32>,
     subtitle_stream_index: Option<i32>,
+  ) -> Result<PlaybackInfoResponse, JellyfinError> {
+    self
+      .get_playback_info_at_bitrate(item_id, audio_stream_index, subtitle_stream_index, None)
+      .await
+  }
+
+  /// Get playback info for a media item streamed at most at `max_streaming_bitrate`
+  /// bits per second (None = 140 Mbps); the server transcodes sources above it.
+  pub async fn get_playback_info_at_bitrate(
+    &self,
+    item_id: &str,
+    audio_stream_index: Option<i32>,
+    subtitle_stream_index: Option<i32>,
+    max_streaming_bitrate: Option<i64>,
   ) -> Result<PlaybackInfoResponse, JellyfinError> {
     let user_id = self.user_id()?;
     let path = format!("/Items/{}/PlaybackInfo", item_id);
@@ -1068,7 +1483,7 @@ impl JellyfinClient {
     let request = PlaybackInfoRequest {
       user_id,
       device_id: self.device_id(),
-      max_streaming_bitrate: Some(140_000_000), // 140 Mbps
+      max_streaming_bitrate: Some(max_streaming_bitrate.unwrap_or(140_000_000)),
       start_time_ticks: None,
       audio_stream_index,
       subtitle_stream_index,
@@ -1078,6 +1493,11 @@ impl JellyfinClient {
       auto_open_live_stream: true,
     };
 
+    #[cfg(feature = "openapi-models")]
+    if self.provider() == MediaServerProvider::Jellyfin {
+      let response: jellyfin_api::models::PlaybackInfoResponse = self.post(&path, &request).await?;
+      return super::openapi_models::playback_info(response);
+    }
     self.post(&path, &request).await
   }
 
@@ -1088,33 +1508,50 @@ impl JellyfinClient {
   pub async fn get_intro_skipper_ranges(
     &self,
     item_id: &str,
-  ) -> Result<Vec<IntroSkipRange>, JellyfinError> {
+  ) -> Result<Vec<SkipRange>, JellyfinError> {
     let path = format!("/Episode/{}/IntroSkipperSegments", item_id);
     let response = self.get::<IntroSkipperPluginResponse>(&path).await?;
 
     Ok(parse_intro_skipper_ranges(response))
   }
 
+  /// Header fields MPV sends with the stream and subtitle requests of a
+  /// file from this server: the profile's custom headers, for a proxy in
+  /// front of it, then the token.
+  ///
+  /// Stream URLs carry no token, so it stays out of MPV's command line,
+  /// window title and logs; MPV authenticates with this header instead.
+  pub fn stream_headers(&self) -> Vec<String> {
+    let state = self.state.read();
+    let mut headers: Vec<String> = state
+      .custom_headers
+      .iter()
+      .map(|(name, value)| format!("{}: {}", name.trim(), value.trim()))
+      .collect();
+    if let Some(token) = &state.access_token {
+      headers.push(format!("X-Emby-Token: {token}"));
+    }
+    headers
+  }
+
   /// Build the direct play URL for a media source.
   /// Always uses HTTP streaming URL - even for "File" protocol sources,
   /// since the file path is on the server, not accessible locally.
   pub fn build_stream_url(&self, item_id: &str, media_source: &MediaSource) -> Option<String> {
     let state = self.state.read();
     let server_url = state.server_url.as_ref()?;
-    let token = state.access_token.as_ref()?;
+    state.access_token.as_ref()?;
 
     if !media_source.supports_direct_play {
       if media_source.supports_direct_stream {
         if let Some(url) = media_source.direct_stream_url.as_deref() {
-          let url = absolute_server_url(server_url, url);
-          return Some(append_api_key_if_missing(&url, token));
+          return Some(without_api_key(&absolute_server_url(server_url, url)));
         }
       }
 
       if media_source.supports_transcoding {
         if let Some(url) = media_source.transcoding_url.as_deref() {
-          let url = absolute_server_url(server_url, url);
-          return Some(append_api_key_if_missing(&url, token));
+          return Some(without_api_key(&absolute_server_url(server_url, url)));
         }
       }
     }
@@ -1122,10 +1559,41 @@ impl JellyfinClient {
     // Build streaming URL - always use HTTP, never raw file paths.
     // The file path in media_source.path is on the server, not locally accessible.
     let container = media_source.container.as_deref().unwrap_or("mkv");
-    Some(format!(
-      "{}/Videos/{}/stream.{}?Static=true&MediaSourceId={}&api_key={}",
-      server_url, item_id, container, media_source.id, token
-    ))
+    let mut url = format!(
+      "{}/Videos/{}/stream.{}?Static=true&MediaSourceId={}",
+      server_url, item_id, container, media_source.id
+    );
+    // In-progress recordings are served from a live stream the server opened.
+    if let Some(live_stream_id) = media_source.live_stream_id.as_deref() {
+      url.push_str(&format!("&LiveStreamId={}", live_stream_id));
+    }
+    Some(url)
+  }
+
+  /// Build a transcoding URL that burns `subtitle_index` into the video.
+  ///
+  /// Used for image subtitles, which remuxed and transcoded streams drop.
+  pub fn build_burn_in_stream_url(
+    &self,
+    media_source: &MediaSource,
+    subtitle_index: i32,
+  ) -> Option<String> {
+    let state = self.state.read();
+    let server_url = state.server_url.as_ref()?;
+    state.access_token.as_ref()?;
+
+    if !media_source.supports_transcoding {
+      return None;
+    }
+    let url = absolute_server_url(server_url, media_source.transcoding_url.as_deref()?);
+    let url = with_query_params(
+      &url,
+      &[
+        ("SubtitleStreamIndex", subtitle_index.to_string()),
+        ("SubtitleMethod", "Encode".to_string()),
+      ],
+    );
+    Some(without_api_key(&url))
   }
 
   /// Build external subtitle URL with correct format extension.
@@ -1143,7 +1611,7 @@ impl JellyfinClient {
   ) -> Option<String> {
     let state = self.state.read();
     let server_url = state.server_url.as_ref()?;
-    let token = state.access_token.as_ref()?;
+    state.access_token.as_ref()?;
 
     // Normalize codec to lowercase for case-insensitive matching.
     // Jellyfin can report codecs in various cases (e.g., "PGSSUB", "ass", "subrip").
@@ -1168,11 +1636,63 @@ impl JellyfinClient {
     // Jellyfin subtitle endpoint format:
     // /Videos/{itemId}/{mediaSourceId}/Subtitles/{streamIndex}/Stream.{format}
     Some(format!(
-      "{}/Videos/{}/{}/Subtitles/{}/Stream.{}?api_key={}",
-      server_url, item_id, media_source_id, stream.index, ext, token
+      "{}/Videos/{}/{}/Subtitles/{}/Stream.{}",
+      server_url, item_id, media_source_id, stream.index, ext
     ))
   }
 
+  /// Download attachment `index` of a media source, such as an embedded font,
+  /// to `path` and return its size. An attachment of more than `limit` bytes
+  /// is refused, by its announced length or once the download outgrows it.
+  ///
+  /// Emby doesn't serve attachments.
+  pub async fn download_attachment(
+    &self,
+    item_id: &str,
+    media_source_id: &str,
+    index: i32,
+    path: &Path,
+    limit: u64,
+  ) -> Result<u64, JellyfinError> {
+    if self.provider() == MediaServerProvider::Emby {
+      return Err(JellyfinError::HttpError(
+        "Emby doesn't serve media attachments".to_string(),
+      ));
+    }
+    let server_url = self.server_url()?;
+    let token = self.access_token()?;
+    // /Videos/{itemId}/{mediaSourceId}/Attachments/{index}
+    let url = format!(
+      "{}/Videos/{}/{}/Attachments/{}",
+      server_url, item_id, media_source_id, index
+    );
+    let mut response = self
+      .http()
+      .get(&url)
+      .header(header::USER_AGENT, self.request_user_agent())
+      .header("X-Emby-Authorization", self.auth_header(Some(&token)))
+      .send()
+      .await?;
+    let status = response.status();
+    if !status.is_success() {
+      return Err(Self::request_error(
+        status,
+        format!("Attachment download failed with HTTP {}", status),
+      ));
+    }
+    if let Some(len) = response.content_length().filter(|len| *len > limit) {
+      return Err(JellyfinError::HttpError(format!(
+        "Attachment of {} bytes exceeds the {} bytes left",
+        len, limit
+      )));
+    }
+    let written = write_limited(&mut response, path, limit).await;
+    if written.is_err() {
+      let _ = tokio::fs::remove_file(path).await;
+    }
+    written
+  }
+
   /// Get WebSocket URL for session.
   pub fn websocket_url(&self) -> Result<String, JellyfinError> {
     let state = self.state.read();
@@ -1185,11 +1705,13 @@ impl JellyfinClient {
       .as_ref()
       .ok_or(JellyfinError::NotConnected)?;
 
-    // Convert http(s) to ws(s)
-    let ws_url = if server_url.starts_with("https://") {
-      server_url.replace("https://", "wss://")
-    } else {
-      server_url.replace("http://", "ws://")
+    // Convert http(s) to ws(s), keeping the host (bracketed for IPv6) and base path
+    let ws_url = match server_url.strip_prefix("https://") {
+      Some(rest) => format!("wss://{rest}"),
+      None => format!(
+        "ws://{}",
+        server_url.strip_prefix("http://").unwrap_or(server_url)
+      ),
     };
 
     Ok(format!(
@@ -1220,19 +1742,14 @@ impl JellyfinClient {
   ///
   /// This makes the client appear as a controllable cast target.
   pub async fn report_capabilities(&self) -> Result<(), JellyfinError> {
-    let capabilities = serde_json::json!({
-      "PlayableMediaTypes": ["Video", "Audio"],
-      "SupportedCommands": SUPPORTED_REMOTE_COMMANDS,
-      "SupportsMediaControl": true,
-      "SupportsPersistentIdentifier": true,
-    });
+    let capabilities = self.state.read().capabilities.payload();
 
     let server_url = self.server_url()?;
     let token = self.access_token()?;
     let url = format!("{}/Sessions/Capabilities/Full", server_url);
 
     let response = self
-      .http
+      .http()
       .post(&url)
       .header(header::USER_AGENT, self.request_user_agent())
       .header(reqwest::header::CONTENT_TYPE, "application/json")
@@ -1251,61 +1768,231 @@ impl JellyfinClient {
     Ok(())
   }
 
-  /// Get the next episode in a series after the given episode.
+  /// Tell the server this session is in use, so its dashboard shows it as
+  /// active rather than idle.
   ///
-  /// Uses the /Shows/{seriesId}/Episodes endpoint with StartItemId to get adjacent episodes.
-  /// Returns None if there's no next episode or if the item is not an episode.
-  pub async fn get_next_episode(
-    &self,
-    current_item: &MediaItem,
-  ) -> Result<Option<MediaItem>, JellyfinError> {
-    // Only works for episodes
-    if current_item.item_type != "Episode" {
-      log::debug!("get_next_episode: not an episode, skipping");
-      return Ok(None);
-    }
-
-    let series_id = match &current_item.series_id {
-      Some(id) => id,
-      None => {
-        log::debug!("get_next_episode: no series_id, skipping");
-        return Ok(None);
+  /// During playback this pings the playback session. Otherwise capabilities
+  /// are posted again, the cheapest request the server logs as activity.
+  pub async fn ping_session(&self, play_session_id: Option<&str>) -> Result<(), JellyfinError> {
+    match play_session_id {
+      Some(id) => {
+        self
+          .post_empty(
+            &format!("/Sessions/Playing/Ping?playSessionId={}", id),
+            &serde_json::json!({}),
+          )
+          .await
       }
-    };
-
-    let user_id = self.user_id()?;
-
-    // Get episodes starting from current, limit 2 (current + next)
-    let path = format!(
-      "/Shows/{}/Episodes?UserId={}&StartItemId={}&Limit=2&Fields=MediaSources,MediaStreams",
-      series_id, user_id, current_item.id
-    );
+      None => self.report_capabilities().await,
+    }
+  }
 
-    let response: EpisodesResponse = self.get(&path).await?;
+  /// Check that the server answers its public info endpoint.
+  pub async fn probe_server(&self) -> Result<(), JellyfinError> {
+    let server_url = self.server_url()?;
+    let response = self
+      .http()
+      .get(format!("{server_url}/System/Info/Public"))
+      .header(header::USER_AGENT, self.request_user_agent())
+      .timeout(ADDRESS_PROBE_TIMEOUT)
+      .send()
+      .await?;
 
-    // The response includes the current episode and the next one (if exists)
-    // We want the second item (index 1) which is the next episode
-    if response.items.len() >= 2 {
-      let next_ep = response.items.into_iter().nth(1);
-      if let Some(ref ep) = next_ep {
-        log::info!(
-          "Found next episode: {} - S{:02}E{:02} - {}",
-          ep.series_name.as_deref().unwrap_or("Unknown"),
-          ep.parent_index_number.unwrap_or(0),
-          ep.index_number.unwrap_or(0),
-          ep.name
-        );
-      }
-      Ok(next_ep)
-    } else {
-      log::info!("No next episode available (end of series or season)");
-      Ok(None)
+    let status = response.status();
+    if !status.is_success() {
+      return Err(Self::request_error(
+        status,
+        format!("GET /System/Info/Public failed: HTTP {}", status),
+      ));
     }
+    Ok(())
   }
 
-  /// Get the previous episode in a series before the given episode.
+  /// Check that the server accepts a WebSocket connection for this device.
+  pub async fn probe_websocket(&self) -> Result<(), JellyfinError> {
+    let url = self.websocket_url()?;
+    let tls = self.websocket_tls_config()?;
+    let headers = self.websocket_headers()?;
+    JellyfinWebSocket::probe(&url, Some(&self.request_user_agent()), tls, headers).await
+  }
+
+  /// The server's session for this device, if it has one.
+  pub async fn own_session(&self) -> Result<Option<SessionInfo>, JellyfinError> {
+    Ok(self.device_sessions().await?.into_iter().next())
+  }
+
+  /// Every session the server has for this device's ID.
+  pub async fn device_sessions(&self) -> Result<Vec<SessionInfo>, JellyfinError> {
+    let device_id = self.device_id();
+    let sessions: Vec<SessionInfo> = self
+      .get_with_query("/Sessions", &[("DeviceId", device_id.clone())])
+      .await?;
+    Ok(
+      sessions
+        .into_iter()
+        .filter(|session| session.device_id.as_deref() == Some(device_id.as_str()))
+        .collect(),
+    )
+  }
+
+  /// Recently active sessions the signed-in user may control, excluding this one.
+  pub async fn list_sessions(&self) -> Result<Vec<RemoteSession>, JellyfinError> {
+    Ok(
+      self
+        .fetch_controllable_sessions()
+        .await?
+        .into_iter()
+        .map(RemoteSession::from)
+        .collect(),
+    )
+  }
+
+  /// Other recently active sessions of the signed-in user, excluding this one.
+  pub async fn list_other_sessions(&self) -> Result<Vec<RemoteSession>, JellyfinError> {
+    let user_id = self.user_id()?;
+    Ok(
+      self
+        .fetch_controllable_sessions()
+        .await?
+        .into_iter()
+        .filter(|session| {
+          session
+            .user_id
+            .as_deref()
+            .is_some_and(|id| same_server_id(id, &user_id))
+        })
+        .map(RemoteSession::from)
+        .collect(),
+    )
+  }
+
+  async fn fetch_controllable_sessions(&self) -> Result<Vec<SessionInfo>, JellyfinError> {
+    let user_id = self.user_id()?;
+    let device_id = self.device_id();
+    let sessions: Vec<SessionInfo> = self
+      .get_with_query(
+        "/Sessions",
+        &[
+          ("ControllableByUserId", user_id),
+          (
+            "ActiveWithinSeconds",
+            OTHER_SESSIONS_ACTIVE_WITHIN_SECONDS.to_string(),
+          ),
+        ],
+      )
+      .await?;
+
+    Ok(
+      sessions
+        .into_iter()
+        .filter(|session| session.device_id.as_deref() != Some(device_id.as_str()))
+        .collect(),
+    )
+  }
+
+  /// Send a playstate command, e.g. pause or seek, to another session.
+  pub async fn send_remote_playstate(
+    &self,
+    session_id: &str,
+    command: RemotePlaystateCommand,
+    seek_position_ticks: Option<i64>,
+  ) -> Result<(), JellyfinError> {
+    let mut path = format!("/Sessions/{}/Playing/{}", session_id, command.as_str());
+    if let Some(ticks) = seek_position_ticks {
+      path.push_str(&format!("?SeekPositionTicks={}", ticks));
+    }
+    self.post_empty(&path, &serde_json::json!({})).await
+  }
+
+  /// Start playing items on another session.
+  pub async fn send_remote_play(
+    &self,
+    session_id: &str,
+    item_ids: &[String],
+    start_position_ticks: Option<i64>,
+  ) -> Result<(), JellyfinError> {
+    let mut path = format!(
+      "/Sessions/{}/Playing?PlayCommand=PlayNow&ItemIds={}",
+      session_id,
+      item_ids.join(",")
+    );
+    if let Some(ticks) = start_position_ticks {
+      path.push_str(&format!("&StartPositionTicks={}", ticks));
+    }
+    self.post_empty(&path, &serde_json::json!({})).await
+  }
+
+  /// Send a general command, e.g. `VolumeUp` or `SetVolume`, to another session.
+  pub async fn send_remote_command(
+    &self,
+    session_id: &str,
+    name: &str,
+    arguments: &BTreeMap<String, String>,
+  ) -> Result<(), JellyfinError> {
+    self
+      .post_empty(
+        &format!("/Sessions/{}/Command", session_id),
+        &serde_json::json!({ "Name": name, "Arguments": arguments }),
+      )
+      .await
+  }
+
+  /// Get the next episode in a series after the given episode.
+  ///
+  /// Uses the /Shows/{seriesId}/Episodes endpoint with StartItemId to get adjacent episodes.
+  /// Returns None if there's no next episode or if the item is not an episode.
+  pub async fn get_next_episode(
+    &self,
+    current_item: &MediaItem,
+  ) -> Result<Option<MediaItem>, JellyfinError> {
+    // Only works for episodes
+    if current_item.item_type != "Episode" {
+      log::debug!("get_next_episode: not an episode, skipping");
+      return Ok(None);
+    }
+
+    let series_id = match &current_item.series_id {
+      Some(id) => id,
+      None => {
+        log::debug!("get_next_episode: no series_id, skipping");
+        return Ok(None);
+      }
+    };
+
+    let user_id = self.user_id()?;
+
+    // Get episodes starting from current, limit 2 (current + next)
+    let path = format!(
+      "/Shows/{}/Episodes?UserId={}&StartItemId={}&Limit=2&Fields=MediaSources,MediaStreams",
+      series_id, user_id, current_item.id
+    );
+
+    let response: EpisodesResponse = self.get_cached(&path).await?;
+
+    // The response includes the current episode and the next one (if exists)
+    // We want the second item (index 1) which is the next episode
+    if response.items.len() >= 2 {
+      let next_ep = response.items.into_iter().nth(1);
+      if let Some(ref ep) = next_ep {
+        log::info!(
+          "Found next episode: {} - S{:02}E{:02} - {}",
+          ep.series_name.as_deref().unwrap_or("Unknown"),
+          ep.parent_index_number.unwrap_or(0),
+          ep.index_number.unwrap_or(0),
+          ep.name
+        );
+      }
+      Ok(next_ep)
+    } else {
+      log::info!("No next episode available (end of series or season)");
+      Ok(None)
+    }
+  }
+
+  /// Get the previous episode in a series before the given episode.
   ///
-  /// Uses the /Shows/{seriesId}/Episodes endpoint to find adjacent episodes.
+  /// Uses the /Shows/{seriesId}/Episodes endpoint with AdjacentTo, which returns only the
+  /// episodes around the current one in series order, across season boundaries.
   /// Returns None if there's no previous episode or if the item is not an episode.
   pub async fn get_previous_episode(
     &self,
@@ -1327,38 +2014,114 @@ impl JellyfinClient {
 
     let user_id = self.user_id()?;
 
-    // Get all episodes for the series to find the previous one
-    // We need to fetch episodes and find the one before current
+    // Response holds the previous episode (if any), the current one and the next one (if any)
     let path = format!(
-      "/Shows/{}/Episodes?UserId={}&Fields=MediaSources,MediaStreams",
-      series_id, user_id
+      "/Shows/{}/Episodes?UserId={}&AdjacentTo={}&Fields=MediaSources,MediaStreams",
+      series_id, user_id, current_item.id
     );
 
-    let response: EpisodesResponse = self.get(&path).await?;
+    let response: EpisodesResponse = self.get_cached(&path).await?;
 
-    // Find the current episode index and return the previous one
-    let mut prev_ep: Option<MediaItem> = None;
-    for ep in response.items {
-      if ep.id == current_item.id {
-        // Found current, return the previous one (if any)
-        if let Some(ref prev) = prev_ep {
-          log::info!(
-            "Found previous episode: {} - S{:02}E{:02} - {}",
-            prev.series_name.as_deref().unwrap_or("Unknown"),
-            prev.parent_index_number.unwrap_or(0),
-            prev.index_number.unwrap_or(0),
-            prev.name
-          );
+    match episode_before(response.items, &current_item.id) {
+      Some(prev) => {
+        log::info!(
+          "Found previous episode: {} - S{:02}E{:02} - {}",
+          prev.series_name.as_deref().unwrap_or("Unknown"),
+          prev.parent_index_number.unwrap_or(0),
+          prev.index_number.unwrap_or(0),
+          prev.name
+        );
+        Ok(Some(prev))
+      }
+      None => {
+        log::info!("No previous episode available (start of series)");
+        Ok(None)
+      }
+    }
+  }
+
+  /// Get the next or previous episode, optionally stepping over specials (season 0).
+  ///
+  /// Ordering across seasons and specials placed between episodes follows the server.
+  /// Specials are never skipped while a special is playing.
+  pub async fn get_adjacent_episode(
+    &self,
+    current_item: &MediaItem,
+    next: bool,
+    skip_specials: bool,
+  ) -> Result<Option<MediaItem>, JellyfinError> {
+    let skip_specials = skip_specials && !is_special(current_item);
+    let mut from = current_item.clone();
+
+    for _ in 0..=MAX_SKIPPED_SPECIALS {
+      let adjacent = if next {
+        self.get_next_episode(&from).await?
+      } else {
+        self.get_previous_episode(&from).await?
+      };
+      match adjacent {
+        Some(episode) if skip_specials && is_special(&episode) => {
+          log::info!("Skipping special: {}", episode.name);
+          from = episode;
         }
-        return Ok(prev_ep);
+        adjacent => return Ok(adjacent),
+      }
+    }
+
+    log::info!(
+      "Only specials found after skipping {} items",
+      MAX_SKIPPED_SPECIALS
+    );
+    Ok(None)
+  }
+
+  /// Item IDs of the first collection (BoxSet) containing `item_id`, in collection order.
+  ///
+  /// Collections don't appear as parents of their items, so the user's collections are
+  /// searched one by one; responses are cached, so repeated next/previous stays cheap.
+  pub async fn get_collection_item_ids(
+    &self,
+    item_id: &str,
+  ) -> Result<Option<Vec<String>>, JellyfinError> {
+    let user_id = self.user_id()?;
+    let collections: ItemsResponse = self
+      .get_cached(&format!(
+        "/Users/{}/Items?IncludeItemTypes=BoxSet&Recursive=true&SortBy=SortName",
+        user_id
+      ))
+      .await?;
+
+    for collection in collections.items.iter().take(MAX_COLLECTIONS_SCANNED) {
+      let children: ItemsResponse = self
+        .get_cached(&format!(
+          "/Items?ParentId={}&UserId={}&SortBy=SortName",
+          collection.id, user_id
+        ))
+        .await?;
+      if children.items.iter().any(|child| child.id == item_id) {
+        log::info!("Item {} belongs to collection {}", item_id, collection.name);
+        return Ok(Some(
+          children.items.into_iter().map(|child| child.id).collect(),
+        ));
       }
-      prev_ep = Some(ep);
     }
 
-    log::info!("No previous episode available (start of series)");
     Ok(None)
   }
 
+  /// Library (collection folder) holding an item, if the server exposes one.
+  pub async fn get_library(&self, item_id: &str) -> Result<Option<MediaItem>, JellyfinError> {
+    let user_id = self.user_id()?;
+    let ancestors: Vec<MediaItem> = self
+      .get_cached(&format!("/Items/{}/Ancestors?UserId={}", item_id, user_id))
+      .await?;
+    Ok(
+      ancestors
+        .into_iter()
+        .find(|ancestor| ancestor.item_type == "CollectionFolder"),
+    )
+  }
+
   /// Validate that our session appears in the Jellyfin session list.
   /// This checks if we're visible as a cast target.
   pub async fn validate_session(&self) -> Result<(), JellyfinError> {
@@ -1577,6 +2340,24 @@ impl<'a> JellyfinLogin<'a> {
     self.client.restore_session(session).await
   }
 
+  pub fn set_tls_trust(&self, tls: &TlsTrust) -> Result<(), JellyfinError> {
+    self.client.set_tls_trust(tls)
+  }
+
+  pub fn set_custom_headers(
+    &self,
+    custom_headers: &BTreeMap<String, String>,
+  ) -> Result<(), JellyfinError> {
+    self.client.set_custom_headers(custom_headers)
+  }
+
+  pub async fn fetch_certificate_fingerprint(
+    &self,
+    server_url: &str,
+  ) -> Result<String, JellyfinError> {
+    self.client.fetch_certificate_fingerprint(server_url).await
+  }
+
   pub fn disconnect(&self) {
     self.client.disconnect();
   }
@@ -1585,6 +2366,10 @@ impl<'a> JellyfinLogin<'a> {
     self.client.get_saved_session()
   }
 
+  pub fn regenerate_device_id(&self) -> String {
+    self.client.regenerate_device_id()
+  }
+
   pub fn is_connected(&self) -> bool {
     self.client.is_connected()
   }
@@ -1603,6 +2388,14 @@ impl<'a> JellyfinPlayback<'a> {
     self.client.get_item(item_id).await
   }
 
+  pub async fn refresh_item(&self, item_id: &str) -> Result<MediaItem, JellyfinError> {
+    self.client.refresh_item(item_id).await
+  }
+
+  pub async fn get_item_chapters(&self, item_id: &str) -> Result<Vec<ChapterInfo>, JellyfinError> {
+    self.client.get_item_chapters(item_id).await
+  }
+
   pub async fn get_playback_info(
     &self,
     item_id: &str,
@@ -1615,10 +2408,28 @@ impl<'a> JellyfinPlayback<'a> {
       .await
   }
 
+  pub async fn get_playback_info_at_bitrate(
+    &self,
+    item_id: &str,
+    audio_stream_index: Option<i32>,
+    subtitle_stream_index: Option<i32>,
+    max_streaming_bitrate: Option<i64>,
+  ) -> Result<PlaybackInfoResponse, JellyfinError> {
+    self
+      .client
+      .get_playback_info_at_bitrate(
+        item_id,
+        audio_stream_index,
+        subtitle_stream_index,
+        max_streaming_bitrate,
+      )
+      .await
+  }
+
   pub async fn get_intro_skipper_ranges(
     &self,
     item_id: &str,
-  ) -> Result<Vec<IntroSkipRange>, JellyfinError> {
+  ) -> Result<Vec<SkipRange>, JellyfinError> {
     self.client.get_intro_skipper_ranges(item_id).await
   }
 
@@ -1626,6 +2437,16 @@ impl<'a> JellyfinPlayback<'a> {
     self.client.build_stream_url(item_id, media_source)
   }
 
+  pub fn build_burn_in_stream_url(
+    &self,
+    media_source: &MediaSource,
+    subtitle_index: i32,
+  ) -> Option<String> {
+    self
+      .client
+      .build_burn_in_stream_url(media_source, subtitle_index)
+  }
+
   pub fn build_subtitle_url(
     &self,
     item_id: &str,
@@ -1637,6 +2458,24 @@ impl<'a> JellyfinPlayback<'a> {
       .build_subtitle_url(item_id, media_source_id, stream)
   }
 
+  pub fn stream_headers(&self) -> Vec<String> {
+    self.client.stream_headers()
+  }
+
+  pub async fn download_attachment(
+    &self,
+    item_id: &str,
+    media_source_id: &str,
+    index: i32,
+    path: &Path,
+    limit: u64,
+  ) -> Result<u64, JellyfinError> {
+    self
+      .client
+      .download_attachment(item_id, media_source_id, index, path, limit)
+      .await
+  }
+
   pub fn websocket_url(&self) -> Result<String, JellyfinError> {
     self.client.websocket_url()
   }
@@ -1645,6 +2484,18 @@ impl<'a> JellyfinPlayback<'a> {
     self.client.request_user_agent()
   }
 
+  pub fn websocket_tls_config(&self) -> Result<Option<Arc<rustls::ClientConfig>>, JellyfinError> {
+    self.client.websocket_tls_config()
+  }
+
+  pub fn websocket_headers(&self) -> Result<header::HeaderMap, JellyfinError> {
+    self.client.websocket_headers()
+  }
+
+  pub async fn select_server_address(&self) -> bool {
+    self.client.select_server_address().await
+  }
+
   pub async fn report_playback_start(&self, info: &PlaybackStartInfo) -> Result<(), JellyfinError> {
     self.client.report_playback_start(info).await
   }
@@ -1664,23 +2515,100 @@ impl<'a> JellyfinPlayback<'a> {
     self.client.report_capabilities().await
   }
 
-  pub async fn get_next_episode(
+  pub async fn ping_session(&self, play_session_id: Option<&str>) -> Result<(), JellyfinError> {
+    self.client.ping_session(play_session_id).await
+  }
+
+  pub async fn probe_server(&self) -> Result<(), JellyfinError> {
+    self.client.probe_server().await
+  }
+
+  pub async fn probe_websocket(&self) -> Result<(), JellyfinError> {
+    self.client.probe_websocket().await
+  }
+
+  pub async fn own_session(&self) -> Result<Option<SessionInfo>, JellyfinError> {
+    self.client.own_session().await
+  }
+
+  pub async fn device_sessions(&self) -> Result<Vec<SessionInfo>, JellyfinError> {
+    self.client.device_sessions().await
+  }
+
+  pub async fn list_sessions(&self) -> Result<Vec<RemoteSession>, JellyfinError> {
+    self.client.list_sessions().await
+  }
+
+  pub async fn list_other_sessions(&self) -> Result<Vec<RemoteSession>, JellyfinError> {
+    self.client.list_other_sessions().await
+  }
+
+  pub async fn send_remote_playstate(
     &self,
-    current_item: &MediaItem,
-  ) -> Result<Option<MediaItem>, JellyfinError> {
-    self.client.get_next_episode(current_item).await
+    session_id: &str,
+    command: RemotePlaystateCommand,
+    seek_position_ticks: Option<i64>,
+  ) -> Result<(), JellyfinError> {
+    self
+      .client
+      .send_remote_playstate(session_id, command, seek_position_ticks)
+      .await
   }
 
-  pub async fn get_previous_episode(
+  pub async fn send_remote_play(
+    &self,
+    session_id: &str,
+    item_ids: &[String],
+    start_position_ticks: Option<i64>,
+  ) -> Result<(), JellyfinError> {
+    self
+      .client
+      .send_remote_play(session_id, item_ids, start_position_ticks)
+      .await
+  }
+
+  pub async fn send_remote_command(
+    &self,
+    session_id: &str,
+    name: &str,
+    arguments: &BTreeMap<String, String>,
+  ) -> Result<(), JellyfinError> {
+    self
+      .client
+      .send_remote_command(session_id, name, arguments)
+      .await
+  }
+
+  pub async fn get_adjacent_episode(
     &self,
     current_item: &MediaItem,
+    next: bool,
+    skip_specials: bool,
   ) -> Result<Option<MediaItem>, JellyfinError> {
-    self.client.get_previous_episode(current_item).await
+    self
+      .client
+      .get_adjacent_episode(current_item, next, skip_specials)
+      .await
+  }
+
+  pub async fn get_collection_item_ids(
+    &self,
+    item_id: &str,
+  ) -> Result<Option<Vec<String>>, JellyfinError> {
+    self.client.get_collection_item_ids(item_id).await
+  }
+
+  pub async fn get_library(&self, item_id: &str) -> Result<Option<MediaItem>, JellyfinError> {
+    self.client.get_library(item_id).await
   }
 
   pub async fn validate_session(&self) -> Result<(), JellyfinError> {
     self.client.validate_session().await
   }
+
+  pub fn invalidate_cache(&self) {
+    self.client.invalidate_cache();
+  }
 }
 
 impl<'a> JellyfinLibrary<'a> {
@@ -1723,6 +2651,23 @@ impl<'a> JellyfinLibrary<'a> {
     })
   }
 
+  /// Resume rows only, for surfaces that do not need the full Video Home.
+  pub async fn continue_watching(&self) -> Result<Vec<VideoHomeItem>, JellyfinError> {
+    let server_url = self.client.server_url()?;
+    let user_id = self.client.user_id()?;
+
+    if self.client.provider() == MediaServerProvider::Emby {
+      return emby_continue_watching_items(self.client, &server_url, &user_id).await;
+    }
+
+    let token = self.client.access_token()?;
+    let configuration = self
+      .client
+      .openapi_configuration(&server_url, Some(&token))?;
+
+    continue_watching_items(&configuration, &server_url, &user_id).await
+  }
+
   pub async fn library_shortcuts(&self) -> Result<Vec<VideoLibraryShortcut>, JellyfinError> {
     if self.client.provider() == MediaServerProvider::Emby {
       return self.emby_library_shortcuts().await;
@@ -2157,7 +3102,91 @@ impl<'a> JellyfinLibrary<'a> {
 
     Ok(map_video_user_data_update(item_id, user_data))
   }
-}
+
+  /// Watched, favorite and rating state of one item for the signed-in user.
+  pub async fn user_data(&self, item_id: &str) -> Result<VideoUserDataUpdate, JellyfinError> {
+    let item_id = item_id.trim().to_string();
+    if item_id.is_empty() {
+      return Err(JellyfinError::HttpError(
+        "Item id is required for user data".to_string(),
+      ));
+    }
+
+    let user_id = self.client.user_id()?;
+    if self.client.provider() == MediaServerProvider::Emby {
+      let item = self
+        .client
+        .request_without_body::<emby_api::models::BaseItemDto>(
+          Method::GET,
+          &format!("/Users/{user_id}/Items/{item_id}"),
+        )
+        .await?;
+      let user_data = item.user_data.map(|data| *data).unwrap_or_default();
+      return Ok(map_emby_video_user_data_update(item_id, user_data));
+    }
+
+    let server_url = self.client.server_url()?;
+    let token = self.client.access_token()?;
+    let configuration = self
+      .client
+      .openapi_configuration(&server_url, Some(&token))?;
+    let user_data = jellyfin_api::apis::items_api::get_item_user_data(
+      &configuration,
+      jellyfin_api::apis::items_api::GetItemUserDataParams {
+        item_id: item_id.clone(),
+        user_id: Some(user_id),
+      },
+    )
+    .await
+    .map_err(|err| JellyfinClient::openapi_error("Get user data", err))?;
+
+    Ok(map_video_user_data_update(item_id, user_data))
+  }
+
+  /// Rate an item thumbs up (`likes`) or thumbs down for the signed-in user.
+  pub async fn set_rating(
+    &self,
+    item_id: &str,
+    likes: bool,
+  ) -> Result<VideoUserDataUpdate, JellyfinError> {
+    let item_id = item_id.trim().to_string();
+    if item_id.is_empty() {
+      return Err(JellyfinError::HttpError(
+        "Item id is required for ratings".to_string(),
+      ));
+    }
+
+    let user_id = self.client.user_id()?;
+    if self.client.provider() == MediaServerProvider::Emby {
+      let user_data = self
+        .client
+        .request_without_body::<emby_api::models::UserItemDataDto>(
+          Method::POST,
+          &format!("/Users/{user_id}/Items/{item_id}/Rating?Likes={likes}"),
+        )
+        .await?;
+      return Ok(map_emby_video_user_data_update(item_id, user_data));
+    }
+
+    let server_url = self.client.server_url()?;
+    let token = self.client.access_token()?;
+    let configuration = self
+      .client
+      .openapi_configuration(&server_url, Some(&token))?;
+    let user_data = jellyfin_api::apis::user_library_api::update_user_item_rating(
+      &configuration,
+      jellyfin_api::apis::user_library_api::UpdateUserItemRatingParams {
+        item_id: item_id.clone(),
+        user_id: Some(user_id),
+        likes: Some(likes),
+      },
+    )
+    .await
+    .map_err(|err| JellyfinClient::openapi_error("Update rating", err))?;
+
+    Ok(map_video_user_data_update(item_id, user_data))
+  }
+}
 
 impl<'a> JellyfinLibrary<'a> {
   async fn emby_video_home(&self) -> Result<VideoHome, JellyfinError> {
@@ -2453,6 +3482,33 @@ impl<'a> JellyfinLibrary<'a> {
   }
 }
 
+/// Write the body of `response` to a new file at `path`, failing once it
+/// grows past `limit` bytes. Returns the bytes written.
+async fn write_limited(
+  response: &mut reqwest::Response,
+  path: &Path,
+  limit: u64,
+) -> Result<u64, JellyfinError> {
+  use tokio::io::AsyncWriteExt;
+
+  let save_error =
+    |e: std::io::Error| JellyfinError::HttpError(format!("Failed to save attachment: {}", e));
+  let mut file = tokio::fs::File::create(path).await.map_err(save_error)?;
+  let mut written: u64 = 0;
+  while let Some(chunk) = response.chunk().await? {
+    written += chunk.len() as u64;
+    if written > limit {
+      return Err(JellyfinError::HttpError(format!(
+        "Attachment exceeds the {} bytes left",
+        limit
+      )));
+    }
+    file.write_all(&chunk).await.map_err(save_error)?;
+  }
+  file.flush().await.map_err(save_error)?;
+  Ok(written)
+}
+
 async fn continue_watching_items(
   configuration: &jellyfin_api::apis::configuration::Configuration,
   server_url: &str,
@@ -3007,6 +4063,7 @@ fn map_video_user_data_update(
     item_id,
     played: user_data.played.unwrap_or(false),
     favorite: user_data.is_favorite.unwrap_or(false),
+    likes: user_data.likes.flatten(),
   }
 }
 
@@ -3253,13 +4310,62 @@ fn absolute_server_url(server_url: &str, path_or_url: &str) -> String {
   }
 }
 
-fn append_api_key_if_missing(url: &str, token: &str) -> String {
-  if url.contains("api_key=") {
+/// True when the host of `url` is an IPv6 literal without brackets, e.g.
+/// `http://fd00::10:8096`, where the port can't be told apart from the address.
+fn has_unbracketed_ipv6_host(url: &str) -> bool {
+  let Some((_, rest)) = url.split_once("://") else {
+    return false;
+  };
+  let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+  let host = authority.rsplit('@').next().unwrap_or_default();
+  !host.starts_with('[') && host.matches(':').count() > 1
+}
+
+/// Set query parameters on `url`, replacing any existing values.
+fn with_query_params(url: &str, params: &[(&str, String)]) -> String {
+  let Ok(mut parsed) = reqwest::Url::parse(url) else {
+    return url.to_string();
+  };
+  let kept: Vec<(String, String)> = parsed
+    .query_pairs()
+    .filter(|(key, _)| {
+      !params
+        .iter()
+        .any(|(name, _)| key.eq_ignore_ascii_case(name))
+    })
+    .map(|(key, value)| (key.into_owned(), value.into_owned()))
+    .collect();
+  parsed
+    .query_pairs_mut()
+    .clear()
+    .extend_pairs(kept)
+    .extend_pairs(params.iter().map(|(key, value)| (*key, value.as_str())));
+  parsed.to_string()
+}
+
+/// Drop any token the server put into a stream URL; MPV sends
+/// [`JellyfinClient::stream_headers`] instead.
+fn without_api_key(url: &str) -> String {
+  let is_api_key =
+    |key: &str| key.eq_ignore_ascii_case("api_key") || key.eq_ignore_ascii_case("ApiKey");
+  let Ok(mut parsed) = reqwest::Url::parse(url) else {
+    return url.to_string();
+  };
+  if !parsed.query_pairs().any(|(key, _)| is_api_key(&key)) {
     return url.to_string();
   }
 
-  let separator = if url.contains('?') { '&' } else { '?' };
-  format!("{url}{separator}api_key={token}")
+  let kept: Vec<(String, String)> = parsed
+    .query_pairs()
+    .filter(|(key, _)| !is_api_key(key))
+    .map(|(key, value)| (key.into_owned(), value.into_owned()))
+    .collect();
+  if kept.is_empty() {
+    parsed.set_query(None);
+  } else {
+    parsed.query_pairs_mut().clear().extend_pairs(kept);
+  }
+  parsed.to_string()
 }
 
 struct EmbyBrowseItemsQuery {
@@ -3847,6 +4953,11 @@ fn map_emby_video_user_data_update(
     item_id,
     played: user_data.played.unwrap_or(false),
     favorite: user_data.is_favorite.unwrap_or(false),
+    // Emby only returns the numeric rating behind a like.
+    likes: user_data
+      .rating
+      .flatten()
+      .map(|rating| rating >= EMBY_LIKES_MIN_RATING),
   }
 }
 
@@ -3896,6 +5007,31 @@ fn video_home_item_to_library_item(item: VideoHomeItem) -> VideoLibraryItem {
     played_percentage: item.played_percentage,
   }
 }
+/// Specials live in season 0.
+fn is_special(item: &MediaItem) -> bool {
+  item.parent_index_number == Some(0)
+}
+
+/// Episode immediately before `current_id` in an ordered episode list.
+fn episode_before(episodes: Vec<MediaItem>, current_id: &str) -> Option<MediaItem> {
+  let position = episodes
+    .iter()
+    .position(|episode| episode.id == current_id)?;
+  episodes.into_iter().nth(position.checked_sub(1)?)
+}
+
+/// Compare server GUIDs, which may or may not be hyphenated.
+fn same_server_id(a: &str, b: &str) -> bool {
+  a.chars()
+    .filter(|c| *c != '-')
+    .map(|c| c.to_ascii_lowercase())
+    .eq(
+      b.chars()
+        .filter(|c| *c != '-')
+        .map(|c| c.to_ascii_lowercase()),
+    )
+}
+
 impl Default for JellyfinClient {
   fn default() -> Self {
     Self::new()
@@ -4056,6 +5192,9 @@ mod tests {
         server_url: server_url.clone(),
         username: "Ada".to_string(),
         password: "correct horse battery staple".to_string(),
+        tls: TlsTrust::default(),
+        local_url: None,
+        custom_headers: BTreeMap::new(),
       })
       .await
       .expect("password authentication should succeed");
@@ -4106,6 +5245,9 @@ mod tests {
         server_url: format!("{server_url}/proxy"),
         username: "Ada".to_string(),
         password: "correct horse battery staple".to_string(),
+        tls: TlsTrust::default(),
+        local_url: None,
+        custom_headers: BTreeMap::new(),
       })
       .await
       .expect("emby password authentication should succeed");
@@ -4165,6 +5307,9 @@ mod tests {
         server_url,
         username: "Ada".to_string(),
         password: "correct horse battery staple".to_string(),
+        tls: TlsTrust::default(),
+        local_url: None,
+        custom_headers: BTreeMap::new(),
       })
       .await
       .expect("emby password authentication should succeed after public info is blocked");
@@ -4210,6 +5355,10 @@ mod tests {
         user_name: "Ada".to_string(),
         server_name: Some("Emby Home".to_string()),
         device_id: Some("jellypilot-saved-emby-device".to_string()),
+        tls: TlsTrust::default(),
+        local_url: None,
+        custom_headers: BTreeMap::new(),
+        server_id: None,
       })
       .await
       .expect("emby restore should validate token");
@@ -4255,6 +5404,9 @@ mod tests {
         server_url,
         username: "Ada".to_string(),
         password: "wrong".to_string(),
+        tls: TlsTrust::default(),
+        local_url: None,
+        custom_headers: BTreeMap::new(),
       })
       .await
       .expect_err("missing Emby base should fail before authentication");
@@ -4266,7 +5418,7 @@ mod tests {
   }
 
   #[tokio::test]
-  async fn emby_authentication_maps_unauthorized_to_auth_failed() {
+  async fn emby_authentication_maps_unauthorized_to_invalid_credentials() {
     let (server_url, _requests) = serve_route_responses_with_requests(vec![
       (
         "GET /System/Info/Public ",
@@ -4288,13 +5440,16 @@ mod tests {
         server_url,
         username: "Ada".to_string(),
         password: "wrong".to_string(),
+        tls: TlsTrust::default(),
+        local_url: None,
+        custom_headers: BTreeMap::new(),
       })
       .await
       .expect_err("bad Emby credentials should fail");
 
     assert!(
-      matches!(err, JellyfinError::AuthFailed(ref message) if message.contains("Password authentication failed")),
-      "expected auth failure, got {err:?}"
+      matches!(err, JellyfinError::InvalidCredentials(ref message) if message.contains("Password authentication failed")),
+      "expected invalid credentials, got {err:?}"
     );
   }
 
@@ -4321,6 +5476,9 @@ mod tests {
         server_url,
         username: "Ada".to_string(),
         password: "wrong".to_string(),
+        tls: TlsTrust::default(),
+        local_url: None,
+        custom_headers: BTreeMap::new(),
       })
       .await
       .expect_err("missing token should fail");
@@ -4331,6 +5489,60 @@ mod tests {
     );
   }
 
+  #[tokio::test]
+  async fn attachments_larger_than_the_limit_are_not_saved() {
+    let (server_url, _) = serve_route_responses_with_requests(vec![
+      (
+        "GET /Users/",
+        "200 OK",
+        r#"{"Id":"00000000-0000-0000-0000-000000000001","Name":"Ada"}"#,
+      ),
+      (
+        "GET /System/Info",
+        "200 OK",
+        r#"{"ServerName":"Jellyfin Home","Version":"10.10.0","Id":"server-1"}"#,
+      ),
+      ("/Attachments/0 ", "200 OK", "font"),
+      ("/Attachments/1 ", "200 OK", "a much larger font"),
+    ])
+    .await;
+    let client = JellyfinClient::new();
+    client
+      .restore_session(&SavedSession {
+        provider: MediaServerProvider::Jellyfin,
+        server_url,
+        access_token: "token-1".to_string(),
+        user_id: "00000000-0000-0000-0000-000000000001".to_string(),
+        user_name: "Ada".to_string(),
+        server_name: None,
+        device_id: None,
+        tls: TlsTrust::default(),
+        local_url: None,
+        custom_headers: BTreeMap::new(),
+        server_id: None,
+      })
+      .await
+      .expect("restore should succeed");
+    let dir = std::env::temp_dir().join(format!("jellypilot-attachments-{}", Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let small = dir.join("small.ttf");
+    let written = client
+      .download_attachment("item-1", "source-1", 0, &small, 8)
+      .await
+      .expect("attachment within the limit should be saved");
+    assert_eq!(written, 4);
+    assert_eq!(std::fs::read(&small).unwrap(), b"font");
+
+    let large = dir.join("large.ttf");
+    client
+      .download_attachment("item-1", "source-1", 1, &large, 8)
+      .await
+      .expect_err("attachment beyond the limit should be refused");
+    assert!(!large.exists());
+    let _ = std::fs::remove_dir_all(&dir);
+  }
+
   #[tokio::test]
   async fn restore_session_validates_token_and_refreshes_server_name() {
     let (server_url, requests) = serve_responses_with_requests(vec![
@@ -4355,167 +5567,681 @@ mod tests {
         user_name: "Ada".to_string(),
         server_name: None,
         device_id: Some("jellypilot-saved-device".to_string()),
+        tls: TlsTrust::default(),
+        local_url: None,
+        custom_headers: BTreeMap::new(),
+        server_id: None,
+      })
+      .await
+      .expect("restore should validate token and refresh server info");
+
+    let session = client
+      .get_saved_session()
+      .expect("restore should keep saved session");
+    assert_eq!(session.server_name.as_deref(), Some("Jellyfin Home"));
+
+    let captured = requests.lock();
+    let validation_request = captured
+      .first()
+      .expect("token validation request should be captured");
+    assert!(validation_request.starts_with("GET /Users/Me "));
+    assert!(validation_request.contains("Token=\"token-1\""));
+  }
+
+  #[tokio::test]
+  async fn restore_session_prefers_the_reachable_local_address() {
+    let unreachable = TcpListener::bind("127.0.0.1:0")
+      .await
+      .expect("test server should bind");
+    let remote_url = format!(
+      "http://{}",
+      unreachable
+        .local_addr()
+        .expect("test server should have addr")
+    );
+    drop(unreachable);
+    let (local_url, requests) = serve_route_responses_with_requests(vec![
+      (
+        "GET /System/Info/Public ",
+        "200 OK",
+        r#"{"ServerName":"Jellyfin Home","Version":"10.10.0","Id":"server-1"}"#,
+      ),
+      (
+        "GET /Users/Me ",
+        "200 OK",
+        r#"{"Id":"00000000-0000-0000-0000-000000000001","Name":"Ada"}"#,
+      ),
+      (
+        "GET /System/Info/Public ",
+        "200 OK",
+        r#"{"ServerName":"Jellyfin Home","Version":"10.10.0","Id":"server-1"}"#,
+      ),
+    ])
+    .await;
+    let client = JellyfinClient::new();
+
+    client
+      .restore_session(&SavedSession {
+        provider: MediaServerProvider::Jellyfin,
+        server_url: remote_url.clone(),
+        access_token: "token-1".to_string(),
+        user_id: "00000000-0000-0000-0000-000000000001".to_string(),
+        user_name: "Ada".to_string(),
+        server_name: None,
+        device_id: None,
+        tls: TlsTrust::default(),
+        local_url: Some(local_url.clone()),
+        custom_headers: BTreeMap::new(),
+        server_id: Some("server-1".to_string()),
+      })
+      .await
+      .expect("restore should use the local address");
+
+    assert_eq!(client.server_url().unwrap(), local_url);
+    let session = client
+      .get_saved_session()
+      .expect("restore should keep saved session");
+    assert_eq!(session.server_url, remote_url);
+    assert_eq!(session.local_url.as_deref(), Some(local_url.as_str()));
+    assert_eq!(requests.lock().len(), 3);
+  }
+
+  #[tokio::test]
+  async fn restore_session_ignores_another_server_at_the_local_address() {
+    let (remote_url, _) = serve_route_responses_with_requests(vec![
+      (
+        "GET /System/Info/Public ",
+        "200 OK",
+        r#"{"ServerName":"Jellyfin Home","Version":"10.10.0","Id":"server-1"}"#,
+      ),
+      (
+        "GET /Users/Me ",
+        "200 OK",
+        r#"{"Id":"00000000-0000-0000-0000-000000000001","Name":"Ada"}"#,
+      ),
+      (
+        "GET /System/Info/Public ",
+        "200 OK",
+        r#"{"ServerName":"Jellyfin Home","Version":"10.10.0","Id":"server-1"}"#,
+      ),
+    ])
+    .await;
+    let (local_url, _) = serve_route_responses_with_requests(vec![(
+      "GET /System/Info/Public ",
+      "200 OK",
+      r#"{"ServerName":"Someone Else","Version":"10.10.0","Id":"server-2"}"#,
+    )])
+    .await;
+    let client = JellyfinClient::new();
+
+    client
+      .restore_session(&SavedSession {
+        provider: MediaServerProvider::Jellyfin,
+        server_url: remote_url.clone(),
+        access_token: "token-1".to_string(),
+        user_id: "00000000-0000-0000-0000-000000000001".to_string(),
+        user_name: "Ada".to_string(),
+        server_name: None,
+        device_id: None,
+        tls: TlsTrust::default(),
+        local_url: Some(local_url),
+        custom_headers: BTreeMap::new(),
+        server_id: Some("server-1".to_string()),
+      })
+      .await
+      .expect("restore should stay on the remote address");
+
+    assert_eq!(client.server_url().unwrap(), remote_url);
+  }
+
+  #[tokio::test]
+  async fn restore_session_clears_state_when_token_validation_fails() {
+    let server_url = serve_once("401 Unauthorized", r#"{"Message":"revoked"}"#).await;
+    let client = JellyfinClient::new();
+
+    let err = client
+      .restore_session(&SavedSession {
+        provider: MediaServerProvider::Jellyfin,
+        server_url,
+        access_token: "token-1".to_string(),
+        user_id: "00000000-0000-0000-0000-000000000001".to_string(),
+        user_name: "Ada".to_string(),
+        server_name: Some("Jellyfin Home".to_string()),
+        device_id: Some("jellypilot-saved-device".to_string()),
+        tls: TlsTrust::default(),
+        local_url: None,
+        custom_headers: BTreeMap::new(),
+        server_id: None,
       })
       .await
-      .expect("restore should validate token and refresh server info");
-
-    let session = client
-      .get_saved_session()
-      .expect("restore should keep saved session");
-    assert_eq!(session.server_name.as_deref(), Some("Jellyfin Home"));
+      .expect_err("restore should report validation failure");
+
+    assert!(
+      matches!(err, JellyfinError::CredentialsExpired(_)),
+      "expected expired credentials, got {err:?}"
+    );
+    assert!(!client.is_connected());
+  }
+
+  #[tokio::test]
+  async fn quick_connect_start_returns_code_and_secret_from_server() {
+    let (server_url, requests) = serve_responses_with_requests(vec![(
+      "200 OK",
+      r#"{"Code":"ABCD12","Secret":"secret-123"}"#,
+    )])
+    .await;
+    let client = JellyfinClient::new();
+
+    let request = client
+      .quick_connect_start(&server_url)
+      .await
+      .expect("quick connect request should start");
+
+    assert_eq!(request.code, "ABCD12");
+    assert_eq!(request.secret, "secret-123");
+
+    let captured = requests.lock();
+    let request = captured
+      .first()
+      .expect("quick connect start request should be captured");
+    assert!(request.starts_with("POST /QuickConnect/Initiate "));
+    assert!(request.contains("Client=\"JellyPilot\""));
+  }
+
+  #[tokio::test]
+  async fn quick_connect_start_returns_unavailable_when_server_rejects_request() {
+    let server_url = serve_once(
+      "401 Unauthorized",
+      r#"{"Message":"Quick Connect is disabled"}"#,
+    )
+    .await;
+    let client = JellyfinClient::new();
+
+    let err = client
+      .quick_connect_start(&server_url)
+      .await
+      .expect_err("quick connect should report unavailable");
+
+    assert!(
+      matches!(err, JellyfinError::QuickConnectUnavailable),
+      "expected quick connect unavailable, got {err:?}"
+    );
+  }
+
+  #[tokio::test]
+  async fn quick_connect_check_returns_approved_when_server_authenticated_request() {
+    let (server_url, requests) = serve_responses_with_requests(vec![(
+      "200 OK",
+      r#"{"Authenticated":true,"Code":"ABCD12","Secret":"secret-123"}"#,
+    )])
+    .await;
+    let client = JellyfinClient::new();
+
+    let status = client
+      .quick_connect_check(&server_url, "secret-123")
+      .await
+      .expect("quick connect state should load");
+
+    assert!(matches!(status, QuickConnectStatus::Approved));
+
+    let captured = requests.lock();
+    let request = captured
+      .first()
+      .expect("quick connect check request should be captured");
+    assert!(request.starts_with("GET /QuickConnect/Connect?secret=secret-123 "));
+  }
+
+  #[tokio::test]
+  async fn quick_connect_authenticate_creates_saved_session() {
+    let (server_url, requests) = serve_responses_with_requests(vec![
+      (
+        "200 OK",
+        r#"{"User":{"Id":"00000000-0000-0000-0000-000000000001","Name":"Ada"},"AccessToken":"token-1","ServerId":"server-1"}"#,
+      ),
+      (
+        "200 OK",
+        r#"{"ServerName":"Jellyfin Home","Version":"10.10.0","Id":"server-1"}"#,
+      ),
+    ])
+    .await;
+    let client = JellyfinClient::new();
+
+    client
+      .quick_connect_authenticate(&server_url, "secret-123")
+      .await
+      .expect("quick connect authentication should succeed");
+
+    let session = client
+      .get_saved_session()
+      .expect("quick connect should create saved session");
+
+    assert_eq!(session.access_token, "token-1");
+
+    let captured = requests.lock();
+    let auth_request = captured
+      .first()
+      .expect("quick connect auth request should be captured");
+    assert!(auth_request.starts_with("POST /Users/AuthenticateWithQuickConnect "));
+    assert!(auth_request.contains(r#""Secret":"secret-123""#));
+    let info_request = captured
+      .get(1)
+      .expect("public info request should be captured");
+    assert!(info_request.starts_with("GET /System/Info/Public "));
+  }
+
+  fn connect_test_client(client: &JellyfinClient, server_url: String) {
+    let mut state = client.state.write();
+    state.server_url = Some(server_url);
+    state.access_token = Some("token-1".to_string());
+    state.user_id = Some("00000000-0000-0000-0000-000000000001".to_string());
+  }
+
+  fn connect_test_client_as_emby(client: &JellyfinClient, server_url: String) {
+    let mut state = client.state.write();
+    state.provider = MediaServerProvider::Emby;
+    state.server_url = Some(server_url);
+    state.access_token = Some("emby-token".to_string());
+    state.user_id = Some("00000000-0000-0000-0000-000000000001".to_string());
+  }
+
+  fn test_episode(id: &str, season: i32, episode: i32) -> MediaItem {
+    MediaItem {
+      id: id.to_string(),
+      name: format!("Episode {}", episode),
+      item_type: "Episode".to_string(),
+      series_id: Some("series-1".to_string()),
+      series_name: Some("Show".to_string()),
+      season_name: None,
+      index_number: Some(episode),
+      parent_index_number: Some(season),
+      run_time_ticks: None,
+      overview: None,
+      status: None,
+    }
+  }
+
+  #[tokio::test]
+  async fn previous_episode_uses_adjacent_query_across_season_boundary() {
+    let client = JellyfinClient::new();
+    let (server_url, requests) = serve_responses_with_requests(vec![(
+      "200 OK",
+      r#"{"Items":[
+        {"Id":"s1e10","Name":"Finale","Type":"Episode","SeriesId":"series-1","ParentIndexNumber":1,"IndexNumber":10},
+        {"Id":"s2e1","Name":"Premiere","Type":"Episode","SeriesId":"series-1","ParentIndexNumber":2,"IndexNumber":1},
+        {"Id":"s2e2","Name":"Second","Type":"Episode","SeriesId":"series-1","ParentIndexNumber":2,"IndexNumber":2}
+      ],"TotalRecordCount":3}"#,
+    )])
+    .await;
+    connect_test_client(&client, server_url);
+
+    let previous = client
+      .get_previous_episode(&test_episode("s2e1", 2, 1))
+      .await
+      .expect("previous episode lookup should succeed")
+      .expect("previous season finale should be found");
+
+    assert_eq!(previous.id, "s1e10");
+    let captured = requests.lock();
+    let request = captured
+      .first()
+      .expect("episodes request should be captured");
+    assert!(request.starts_with("GET /Shows/series-1/Episodes?"));
+    assert!(request.contains("AdjacentTo=s2e1"));
+  }
+
+  #[tokio::test]
+  async fn previous_episode_is_none_at_start_of_series() {
+    let client = JellyfinClient::new();
+    let server_url = serve_once(
+      "200 OK",
+      r#"{"Items":[
+        {"Id":"s1e1","Name":"Pilot","Type":"Episode","SeriesId":"series-1","ParentIndexNumber":1,"IndexNumber":1},
+        {"Id":"s1e2","Name":"Second","Type":"Episode","SeriesId":"series-1","ParentIndexNumber":1,"IndexNumber":2}
+      ],"TotalRecordCount":2}"#,
+    )
+    .await;
+    connect_test_client(&client, server_url);
+
+    let previous = client
+      .get_previous_episode(&test_episode("s1e1", 1, 1))
+      .await
+      .expect("previous episode lookup should succeed");
+
+    assert!(previous.is_none());
+  }
+
+  #[tokio::test]
+  async fn other_sessions_exclude_this_device_and_other_users() {
+    let client = JellyfinClient::new();
+    let device_id = client.device_id();
+    let sessions = format!(
+      r#"[
+        {{"Id":"self","UserId":"00000000000000000000000000000001","DeviceId":"{}"}},
+        {{"Id":"phone","UserId":"00000000000000000000000000000001","DeviceId":"phone-1","DeviceName":"Pixel","Client":"Jellyfin Android",
+          "NowPlayingItem":{{"Id":"movie-1","Name":"Movie"}},"PlayState":{{"IsPaused":false}}}},
+        {{"Id":"guest","UserId":"00000000000000000000000000000002","DeviceId":"tv-1"}}
+      ]"#,
+      device_id
+    );
+    let (server_url, requests) =
+      serve_owned_responses_with_requests(vec![("200 OK".to_string(), sessions)]).await;
+    connect_test_client(&client, server_url);
+
+    let others = client
+      .list_other_sessions()
+      .await
+      .expect("sessions should be listed");
+
+    assert_eq!(others.len(), 1);
+    assert_eq!(others[0].id, "phone");
+    assert_eq!(others[0].device_name.as_deref(), Some("Pixel"));
+    assert_eq!(others[0].now_playing_item_id.as_deref(), Some("movie-1"));
+    assert!(!others[0].is_paused);
+    let captured = requests.lock();
+    assert!(captured[0].starts_with("GET /Sessions?"));
+    assert!(captured[0].contains("ControllableByUserId=00000000-0000-0000-0000-000000000001"));
+  }
+
+  #[tokio::test]
+  async fn session_ping_uses_the_playback_session_when_playing() {
+    let client = JellyfinClient::new();
+    let (server_url, requests) =
+      serve_responses_with_requests(vec![("204 No Content", ""), ("204 No Content", "")]).await;
+    connect_test_client(&client, server_url);
+
+    client
+      .ping_session(Some("play-1"))
+      .await
+      .expect("playback ping should succeed");
+    client
+      .ping_session(None)
+      .await
+      .expect("idle ping should succeed");
+
+    let captured = requests.lock();
+    assert!(captured[0].starts_with("POST /Sessions/Playing/Ping?playSessionId=play-1 "));
+    assert!(captured[1].starts_with("POST /Sessions/Capabilities/Full "));
+  }
+
+  #[tokio::test]
+  async fn own_session_reports_whether_capabilities_were_registered() {
+    let client = JellyfinClient::new();
+    let body = format!(
+      r#"[{{"Id":"self","DeviceId":"{}","SupportsRemoteControl":true}}]"#,
+      client.device_id()
+    );
+    let (server_url, requests) =
+      serve_owned_responses_with_requests(vec![("200 OK".to_string(), body)]).await;
+    connect_test_client(&client, server_url);
+
+    let session = client
+      .own_session()
+      .await
+      .expect("sessions should load")
+      .expect("own session should be found");
+
+    assert!(session.supports_remote_control);
+    assert!(requests.lock()[0].contains(&format!("DeviceId={}", client.device_id())));
+  }
+
+  #[tokio::test]
+  async fn remote_playstate_posts_to_the_session() {
+    let client = JellyfinClient::new();
+    let (server_url, requests) = serve_responses_with_requests(vec![("204 No Content", "")]).await;
+    connect_test_client(&client, server_url);
+
+    client
+      .send_remote_playstate("phone", RemotePlaystateCommand::Stop, None)
+      .await
+      .expect("stop should be sent");
+
+    assert!(requests.lock()[0].starts_with("POST /Sessions/phone/Playing/Stop "));
+  }
+
+  #[tokio::test]
+  async fn remote_play_seek_and_command_target_the_session() {
+    let client = JellyfinClient::new();
+    let (server_url, requests) = serve_responses_with_requests(vec![
+      ("204 No Content", ""),
+      ("204 No Content", ""),
+      ("204 No Content", ""),
+    ])
+    .await;
+    connect_test_client(&client, server_url);
+
+    client
+      .send_remote_play(
+        "tv",
+        &["movie-1".to_string(), "movie-2".to_string()],
+        Some(50),
+      )
+      .await
+      .expect("play should be sent");
+    client
+      .send_remote_playstate("tv", RemotePlaystateCommand::Seek, Some(600))
+      .await
+      .expect("seek should be sent");
+    let arguments = BTreeMap::from([("Volume".to_string(), "40".to_string())]);
+    client
+      .send_remote_command("tv", "SetVolume", &arguments)
+      .await
+      .expect("command should be sent");
 
     let captured = requests.lock();
-    let validation_request = captured
-      .first()
-      .expect("token validation request should be captured");
-    assert!(validation_request.starts_with("GET /Users/Me "));
-    assert!(validation_request.contains("Token=\"token-1\""));
+    assert!(captured[0].starts_with(
+      "POST /Sessions/tv/Playing?PlayCommand=PlayNow&ItemIds=movie-1,movie-2&StartPositionTicks=50 "
+    ));
+    assert!(captured[1].starts_with("POST /Sessions/tv/Playing/Seek?SeekPositionTicks=600 "));
+    assert!(captured[2].starts_with("POST /Sessions/tv/Command "));
+    assert!(captured[2].contains(r#"{"Arguments":{"Volume":"40"},"Name":"SetVolume"}"#));
   }
 
   #[tokio::test]
-  async fn restore_session_clears_state_when_token_validation_fails() {
-    let server_url = serve_once("401 Unauthorized", r#"{"Message":"revoked"}"#).await;
+  async fn revoked_token_reports_expired_credentials() {
     let client = JellyfinClient::new();
+    let server_url = serve_once("401 Unauthorized", r#"{"Message":"revoked"}"#).await;
+    connect_test_client(&client, server_url);
 
     let err = client
-      .restore_session(&SavedSession {
-        provider: MediaServerProvider::Jellyfin,
-        server_url,
-        access_token: "token-1".to_string(),
-        user_id: "00000000-0000-0000-0000-000000000001".to_string(),
-        user_name: "Ada".to_string(),
-        server_name: Some("Jellyfin Home".to_string()),
-        device_id: Some("jellypilot-saved-device".to_string()),
-      })
+      .get_previous_episode(&test_episode("s1e2", 1, 2))
       .await
-      .expect_err("restore should report validation failure");
+      .expect_err("revoked token should fail");
 
     assert!(
-      matches!(err, JellyfinError::AuthFailed(_)),
-      "expected auth failure, got {err:?}"
+      matches!(err, JellyfinError::CredentialsExpired(_)),
+      "expected expired credentials, got {err:?}"
     );
-    assert!(!client.is_connected());
   }
 
   #[tokio::test]
-  async fn quick_connect_start_returns_code_and_secret_from_server() {
-    let (server_url, requests) = serve_responses_with_requests(vec![(
+  async fn next_episode_crosses_season_boundary() {
+    let client = JellyfinClient::new();
+    let server_url = serve_once(
       "200 OK",
-      r#"{"Code":"ABCD12","Secret":"secret-123"}"#,
-    )])
+      r#"{"Items":[
+        {"Id":"s1e10","Name":"Finale","Type":"Episode","SeriesId":"series-1","ParentIndexNumber":1,"IndexNumber":10},
+        {"Id":"s2e1","Name":"Premiere","Type":"Episode","SeriesId":"series-1","ParentIndexNumber":2,"IndexNumber":1}
+      ],"TotalRecordCount":2}"#,
+    )
     .await;
-    let client = JellyfinClient::new();
+    connect_test_client(&client, server_url);
 
-    let request = client
-      .quick_connect_start(&server_url)
+    let next = client
+      .get_next_episode(&test_episode("s1e10", 1, 10))
       .await
-      .expect("quick connect request should start");
+      .expect("next episode lookup should succeed")
+      .expect("next season premiere should be found");
 
-    assert_eq!(request.code, "ABCD12");
-    assert_eq!(request.secret, "secret-123");
+    assert_eq!(next.id, "s2e1");
+  }
+
+  #[tokio::test]
+  async fn next_episode_skips_interleaved_specials_when_configured() {
+    let client = JellyfinClient::new();
+    let (server_url, requests) = serve_responses_with_requests(vec![
+      (
+        "200 OK",
+        r#"{"Items":[
+          {"Id":"s1e5","Name":"Five","Type":"Episode","SeriesId":"series-1","ParentIndexNumber":1,"IndexNumber":5},
+          {"Id":"s0e1","Name":"Special","Type":"Episode","SeriesId":"series-1","ParentIndexNumber":0,"IndexNumber":1}
+        ],"TotalRecordCount":2}"#,
+      ),
+      (
+        "200 OK",
+        r#"{"Items":[
+          {"Id":"s0e1","Name":"Special","Type":"Episode","SeriesId":"series-1","ParentIndexNumber":0,"IndexNumber":1},
+          {"Id":"s1e6","Name":"Six","Type":"Episode","SeriesId":"series-1","ParentIndexNumber":1,"IndexNumber":6}
+        ],"TotalRecordCount":2}"#,
+      ),
+    ])
+    .await;
+    connect_test_client(&client, server_url);
+
+    let next = client
+      .get_adjacent_episode(&test_episode("s1e5", 1, 5), true, true)
+      .await
+      .expect("next episode lookup should succeed")
+      .expect("episode after the special should be found");
 
+    assert_eq!(next.id, "s1e6");
     let captured = requests.lock();
-    let request = captured
-      .first()
-      .expect("quick connect start request should be captured");
-    assert!(request.starts_with("POST /QuickConnect/Initiate "));
-    assert!(request.contains("Client=\"JellyPilot\""));
+    assert_eq!(captured.len(), 2);
+    assert!(captured[1].contains("StartItemId=s0e1"));
   }
 
   #[tokio::test]
-  async fn quick_connect_start_returns_unavailable_when_server_rejects_request() {
+  async fn adjacent_episode_keeps_specials_unless_skipping() {
+    let client = JellyfinClient::new();
     let server_url = serve_once(
-      "401 Unauthorized",
-      r#"{"Message":"Quick Connect is disabled"}"#,
+      "200 OK",
+      r#"{"Items":[
+        {"Id":"s0e1","Name":"Special","Type":"Episode","SeriesId":"series-1","ParentIndexNumber":0,"IndexNumber":1},
+        {"Id":"s1e1","Name":"Pilot","Type":"Episode","SeriesId":"series-1","ParentIndexNumber":1,"IndexNumber":1}
+      ],"TotalRecordCount":2}"#,
     )
     .await;
-    let client = JellyfinClient::new();
+    connect_test_client(&client, server_url);
 
-    let err = client
-      .quick_connect_start(&server_url)
+    let previous = client
+      .get_adjacent_episode(&test_episode("s1e1", 1, 1), false, false)
       .await
-      .expect_err("quick connect should report unavailable");
+      .expect("previous episode lookup should succeed");
 
-    assert!(
-      matches!(err, JellyfinError::QuickConnectUnavailable),
-      "expected quick connect unavailable, got {err:?}"
-    );
+    assert_eq!(previous.map(|episode| episode.id), Some("s0e1".to_string()));
   }
 
   #[tokio::test]
-  async fn quick_connect_check_returns_approved_when_server_authenticated_request() {
-    let (server_url, requests) = serve_responses_with_requests(vec![(
-      "200 OK",
-      r#"{"Authenticated":true,"Code":"ABCD12","Secret":"secret-123"}"#,
-    )])
-    .await;
+  async fn previous_episode_skipping_specials_stops_at_start_of_series() {
     let client = JellyfinClient::new();
+    let server_url = serve_responses(vec![
+      (
+        "200 OK",
+        r#"{"Items":[
+          {"Id":"s0e1","Name":"Special","Type":"Episode","SeriesId":"series-1","ParentIndexNumber":0,"IndexNumber":1},
+          {"Id":"s1e1","Name":"Pilot","Type":"Episode","SeriesId":"series-1","ParentIndexNumber":1,"IndexNumber":1}
+        ],"TotalRecordCount":2}"#,
+      ),
+      (
+        "200 OK",
+        r#"{"Items":[
+          {"Id":"s0e1","Name":"Special","Type":"Episode","SeriesId":"series-1","ParentIndexNumber":0,"IndexNumber":1},
+          {"Id":"s1e1","Name":"Pilot","Type":"Episode","SeriesId":"series-1","ParentIndexNumber":1,"IndexNumber":1}
+        ],"TotalRecordCount":2}"#,
+      ),
+    ])
+    .await;
+    connect_test_client(&client, server_url);
 
-    let status = client
-      .quick_connect_check(&server_url, "secret-123")
+    let previous = client
+      .get_adjacent_episode(&test_episode("s1e1", 1, 1), false, true)
       .await
-      .expect("quick connect state should load");
-
-    assert!(matches!(status, QuickConnectStatus::Approved));
+      .expect("previous episode lookup should succeed");
 
-    let captured = requests.lock();
-    let request = captured
-      .first()
-      .expect("quick connect check request should be captured");
-    assert!(request.starts_with("GET /QuickConnect/Connect?secret=secret-123 "));
+    assert!(previous.is_none());
   }
 
   #[tokio::test]
-  async fn quick_connect_authenticate_creates_saved_session() {
+  async fn collection_item_ids_come_from_the_collection_holding_the_item() {
+    let client = JellyfinClient::new();
     let (server_url, requests) = serve_responses_with_requests(vec![
       (
         "200 OK",
-        r#"{"User":{"Id":"00000000-0000-0000-0000-000000000001","Name":"Ada"},"AccessToken":"token-1","ServerId":"server-1"}"#,
+        r#"{"Items":[
+          {"Id":"boxset-a","Name":"Alien Collection","Type":"BoxSet"},
+          {"Id":"boxset-b","Name":"Back to the Future Collection","Type":"BoxSet"}
+        ]}"#,
       ),
       (
         "200 OK",
-        r#"{"ServerName":"Jellyfin Home","Version":"10.10.0","Id":"server-1"}"#,
+        r#"{"Items":[{"Id":"alien","Name":"Alien","Type":"Movie"}]}"#,
+      ),
+      (
+        "200 OK",
+        r#"{"Items":[
+          {"Id":"bttf1","Name":"Back to the Future","Type":"Movie"},
+          {"Id":"bttf2","Name":"Back to the Future Part II","Type":"Movie"},
+          {"Id":"bttf3","Name":"Back to the Future Part III","Type":"Movie"}
+        ]}"#,
       ),
     ])
     .await;
-    let client = JellyfinClient::new();
+    connect_test_client(&client, server_url);
 
-    client
-      .quick_connect_authenticate(&server_url, "secret-123")
+    let ids = client
+      .get_collection_item_ids("bttf2")
       .await
-      .expect("quick connect authentication should succeed");
-
-    let session = client
-      .get_saved_session()
-      .expect("quick connect should create saved session");
-
-    assert_eq!(session.access_token, "token-1");
+      .expect("collection lookup should succeed");
 
+    assert_eq!(
+      ids,
+      Some(vec![
+        "bttf1".to_string(),
+        "bttf2".to_string(),
+        "bttf3".to_string()
+      ])
+    );
     let captured = requests.lock();
-    let auth_request = captured
-      .first()
-      .expect("quick connect auth request should be captured");
-    assert!(auth_request.starts_with("POST /Users/AuthenticateWithQuickConnect "));
-    assert!(auth_request.contains(r#""Secret":"secret-123""#));
-    let info_request = captured
-      .get(1)
-      .expect("public info request should be captured");
-    assert!(info_request.starts_with("GET /System/Info/Public "));
+    assert!(captured[0].contains("IncludeItemTypes=BoxSet"));
+    assert!(captured[2].starts_with("GET /Items?ParentId=boxset-b&"));
+    assert!(captured[2].contains("SortBy=SortName"));
   }
 
-  fn connect_test_client(client: &JellyfinClient, server_url: String) {
-    let mut state = client.state.write();
-    state.server_url = Some(server_url);
-    state.access_token = Some("token-1".to_string());
-    state.user_id = Some("00000000-0000-0000-0000-000000000001".to_string());
+  #[tokio::test]
+  async fn library_is_the_collection_folder_among_the_ancestors() {
+    let client = JellyfinClient::new();
+    let (server_url, requests) = serve_responses_with_requests(vec![(
+      "200 OK",
+      r#"[
+        {"Id":"season-1","Name":"Season 1","Type":"Season"},
+        {"Id":"series-1","Name":"Show","Type":"Series"},
+        {"Id":"anime","Name":"Anime","Type":"CollectionFolder"},
+        {"Id":"root","Name":"Media Folders","Type":"UserRootFolder"}
+      ]"#,
+    )])
+    .await;
+    connect_test_client(&client, server_url);
+
+    let library = client
+      .get_library("episode-1")
+      .await
+      .expect("ancestor lookup should succeed");
+
+    assert_eq!(
+      library.map(|library| library.name),
+      Some("Anime".to_string())
+    );
+    assert!(requests.lock()[0].starts_with("GET /Items/episode-1/Ancestors?UserId="));
   }
 
-  fn connect_test_client_as_emby(client: &JellyfinClient, server_url: String) {
-    let mut state = client.state.write();
-    state.provider = MediaServerProvider::Emby;
-    state.server_url = Some(server_url);
-    state.access_token = Some("emby-token".to_string());
-    state.user_id = Some("00000000-0000-0000-0000-000000000001".to_string());
+  #[test]
+  fn episode_before_ignores_lists_without_the_current_episode() {
+    let episodes = vec![test_episode("a", 1, 1), test_episode("b", 1, 2)];
+
+    assert_eq!(
+      episode_before(episodes.clone(), "b").map(|e| e.id),
+      Some("a".to_string())
+    );
+    assert!(episode_before(episodes, "missing").is_none());
   }
 
   #[tokio::test]
@@ -4603,6 +6329,42 @@ mod tests {
     );
   }
 
+  #[test]
+  fn websocket_url_keeps_bracketed_ipv6_hosts() {
+    let client = JellyfinClient::new();
+    let device_id = client.device_id();
+    {
+      let mut state = client.state.write();
+      state.server_url = Some(
+        JellyfinClient::normalize_server_url("http://[fd00::10]:8096/jellyfin/")
+          .expect("bracketed IPv6 URL should be accepted"),
+      );
+      state.access_token = Some("token-1".to_string());
+    }
+
+    let url = client
+      .websocket_url()
+      .expect("websocket URL should be built from connection state");
+
+    assert_eq!(
+      url,
+      format!("ws://[fd00::10]:8096/jellyfin/socket?api_key=token-1&deviceId={device_id}")
+    );
+  }
+
+  #[test]
+  fn unbracketed_ipv6_hosts_are_rejected_with_a_hint() {
+    let err = JellyfinClient::normalize_server_url("http://fd00::10:8096")
+      .expect_err("unbracketed IPv6 host should be rejected");
+
+    assert!(
+      matches!(err, JellyfinError::InvalidUrl(ref message) if message.contains("brackets")),
+      "expected bracket hint, got {err:?}"
+    );
+    assert!(JellyfinClient::normalize_server_url("https://[::1]").is_ok());
+    assert!(JellyfinClient::normalize_server_url("http://media.local:8096").is_ok());
+  }
+
   #[tokio::test]
   async fn emby_validate_session_failure_keeps_connection_alive_with_warning() {
     let client = JellyfinClient::new();
@@ -4651,7 +6413,7 @@ mod tests {
       .first()
       .expect("capability registration request should be captured");
     assert!(request.starts_with("POST /Sessions/Capabilities/Full "));
-    assert!(request.contains(r#""SupportedCommands":["Play","Playstate","SetVolume","ToggleMute","ToggleFullscreen","SetAudioStreamIndex","SetSubtitleStreamIndex"]"#));
+    assert!(request.contains(r#""SupportedCommands":["Play","Playstate","SetVolume","VolumeUp","VolumeDown","ToggleMute","ToggleFullscreen","SetAudioStreamIndex","SetSubtitleStreamIndex","SetRepeatMode","SetShuffleQueue"]"#));
     assert!(!request.contains("MoveUp"));
     assert!(!request.contains("PlayNext"));
     assert!(!request.contains("PlayMediaSource"));
@@ -5344,6 +7106,49 @@ mod tests {
     );
   }
 
+  #[tokio::test]
+  async fn ratings_post_likes_and_map_them_back_per_provider() {
+    let item_id = "00000000-0000-0000-0000-000000000080";
+    let (server_url, requests) = serve_responses_with_requests(vec![(
+      "200 OK",
+      r#"{"IsFavorite":false,"Played":true,"Likes":true}"#,
+    )])
+    .await;
+    let client = JellyfinClient::new();
+    connect_test_client(&client, server_url);
+
+    let liked = client
+      .library()
+      .set_rating(item_id, true)
+      .await
+      .expect("rating should update user data");
+
+    assert_eq!(liked.likes, Some(true));
+    assert!(liked.played);
+    let captured = requests.lock();
+    assert!(captured[0].starts_with("POST /UserItems/00000000-0000-0000-0000-000000000080/Rating?"));
+    assert!(captured[0].contains("likes=true"));
+
+    let (server_url, requests) = serve_responses_with_requests(vec![(
+      "200 OK",
+      r#"{"IsFavorite":false,"Played":false,"Rating":1}"#,
+    )])
+    .await;
+    let emby = JellyfinClient::new();
+    connect_test_client_as_emby(&emby, server_url);
+
+    let disliked = emby
+      .library()
+      .set_rating(item_id, false)
+      .await
+      .expect("Emby rating should update user data");
+
+    assert_eq!(disliked.likes, Some(false));
+    assert!(requests.lock()[0].starts_with(
+      "POST /Users/00000000-0000-0000-0000-000000000001/Items/00000000-0000-0000-0000-000000000080/Rating?Likes=false"
+    ));
+  }
+
   #[tokio::test]
   async fn emby_video_home_and_shortcuts_load_shared_browser_rows() {
     let movie_library_id = "00000000-0000-0000-0000-000000000220";
@@ -5648,7 +7453,12 @@ mod tests {
       supports_transcoding: true,
       direct_stream_url: Some("/videos/direct-stream.mp4?MediaSourceId=source-1".to_string()),
       add_api_key_to_direct_stream_url: Some(true),
-      transcoding_url: Some("/videos/transcoded.m3u8".to_string()),
+      transcoding_url: Some("/videos/transcoded.m3u8?ApiKey=server-token".to_string()),
+      live_stream_id: None,
+      is_infinite_stream: false,
+      name: None,
+      bitrate: None,
+      media_attachments: Vec::new(),
     };
     let direct_stream = MediaSource {
       supports_direct_play: false,
@@ -5666,20 +7476,110 @@ mod tests {
       client
         .build_stream_url("movie-1", &direct_play)
         .expect("direct play URL"),
-      "http://media.example.test/emby/Videos/movie-1/stream.mkv?Static=true&MediaSourceId=source-1&api_key=emby-token"
+      "http://media.example.test/emby/Videos/movie-1/stream.mkv?Static=true&MediaSourceId=source-1"
     );
     assert_eq!(
       client
         .build_stream_url("movie-1", &direct_stream)
         .expect("direct stream URL"),
-      "http://media.example.test/emby/videos/direct-stream.mp4?MediaSourceId=source-1&api_key=emby-token"
+      "http://media.example.test/emby/videos/direct-stream.mp4?MediaSourceId=source-1"
     );
     assert_eq!(
       client
         .build_stream_url("movie-1", &transcode)
         .expect("transcoding URL"),
-      "http://media.example.test/emby/videos/transcoded.m3u8?api_key=emby-token"
+      "http://media.example.test/emby/videos/transcoded.m3u8"
+    );
+    assert_eq!(client.stream_headers(), ["X-Emby-Token: emby-token"]);
+  }
+
+  #[test]
+  fn stream_headers_carry_the_proxy_headers_of_the_profile() {
+    let client = JellyfinClient::new();
+    connect_test_client_as_emby(&client, "http://media.example.test/emby".to_string());
+    client
+      .set_custom_headers(&BTreeMap::from([(
+        "CF-Access-Client-Id".to_string(),
+        "client-1".to_string(),
+      )]))
+      .unwrap();
+
+    assert_eq!(
+      client.stream_headers(),
+      ["CF-Access-Client-Id: client-1", "X-Emby-Token: emby-token"]
+    );
+  }
+
+  #[test]
+  fn static_stream_url_carries_the_live_stream_id_of_a_recording() {
+    let client = JellyfinClient::new();
+    connect_test_client_as_emby(&client, "http://media.example.test/emby".to_string());
+    let recording = MediaSource {
+      id: "source-1".to_string(),
+      path: None,
+      protocol: "File".to_string(),
+      container: Some("ts".to_string()),
+      run_time_ticks: None,
+      media_streams: Vec::new(),
+      supports_direct_play: true,
+      supports_direct_stream: false,
+      supports_transcoding: false,
+      direct_stream_url: None,
+      add_api_key_to_direct_stream_url: None,
+      transcoding_url: None,
+      live_stream_id: Some("live-7".to_string()),
+      is_infinite_stream: false,
+      name: None,
+      bitrate: None,
+      media_attachments: Vec::new(),
+    };
+
+    assert_eq!(
+      client
+        .build_stream_url("recording-1", &recording)
+        .expect("recording URL"),
+      "http://media.example.test/emby/Videos/recording-1/stream.ts?Static=true&MediaSourceId=source-1&LiveStreamId=live-7"
+    );
+  }
+
+  #[test]
+  fn burn_in_stream_url_requests_encoded_subtitles_from_the_transcode() {
+    let client = JellyfinClient::new();
+    connect_test_client(&client, "http://media.example.test".to_string());
+    let transcode = MediaSource {
+      id: "source-1".to_string(),
+      path: None,
+      protocol: "File".to_string(),
+      container: Some("mkv".to_string()),
+      run_time_ticks: None,
+      media_streams: Vec::new(),
+      supports_direct_play: false,
+      supports_direct_stream: false,
+      supports_transcoding: true,
+      direct_stream_url: None,
+      add_api_key_to_direct_stream_url: None,
+      transcoding_url: Some(
+        "/videos/movie-1/master.m3u8?MediaSourceId=source-1&SubtitleMethod=Hls&SubtitleStreamIndex=2"
+          .to_string(),
+      ),
+      live_stream_id: None,
+      is_infinite_stream: false,
+      name: None,
+      bitrate: None,
+      media_attachments: Vec::new(),
+    };
+    let direct_only = MediaSource {
+      supports_transcoding: false,
+      ..transcode.clone()
+    };
+
+    assert_eq!(
+      client
+        .build_burn_in_stream_url(&transcode, 4)
+        .expect("burn-in URL"),
+      "http://media.example.test/videos/movie-1/master.m3u8?MediaSourceId=source-1&SubtitleStreamIndex=4&SubtitleMethod=Encode"
     );
+    assert!(client.build_burn_in_stream_url(&direct_only, 4).is_none());
   }
 
   #[test]
@@ -0,0 +1,125 @@
+//! Credits ranges inferred from the file when no server data describes them.
+//!
+//! Kept conservative: only chapters named like end credits count, and only
+//! in the second half of the file. The tail fallback is opt-in on top.
+
+use super::skip_range::{SkipKind, SkipRange};
+use crate::mpv::Chapter;
+
+/// Chapter titles, compared case-insensitively, that mark the credits.
+const CREDITS_CHAPTER_TITLES: [&str; 4] = ["credits", "end credits", "closing credits", "ending"];
+
+/// Credits range from a credits chapter, else from the last `tail_seconds`.
+pub fn credits_fallback_range(
+  chapters: &[Chapter],
+  duration_seconds: f64,
+  tail_seconds: Option<u32>,
+) -> Option<SkipRange> {
+  if !duration_seconds.is_finite() || duration_seconds <= 0.0 {
+    return None;
+  }
+
+  let chapter_range = chapters.iter().enumerate().find_map(|(index, chapter)| {
+    let title = chapter.title.as_deref()?.trim().to_lowercase();
+    if !CREDITS_CHAPTER_TITLES.contains(&title.as_str()) || chapter.time < duration_seconds / 2.0 {
+      return None;
+    }
+    let end_seconds = chapters
+      .get(index + 1)
+      .map_or(duration_seconds, |next| next.time.min(duration_seconds));
+    SkipRange::new(SkipKind::Credits, chapter.time, end_seconds)
+  });
+
+  chapter_range.or_else(|| {
+    let tail_seconds = f64::from(tail_seconds?);
+    // Short files would lose most of their content to the tail.
+    if tail_seconds * 2.0 > duration_seconds {
+      return None;
+    }
+    SkipRange::new(
+      SkipKind::Credits,
+      duration_seconds - tail_seconds,
+      duration_seconds,
+    )
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn chapter(title: &str, time: f64) -> Chapter {
+    Chapter {
+      title: Some(title.to_string()),
+      time,
+    }
+  }
+
+  fn credits(start_seconds: f64, end_seconds: f64) -> Option<SkipRange> {
+    SkipRange::new(SkipKind::Credits, start_seconds, end_seconds)
+  }
+
+  #[test]
+  fn credits_chapter_runs_to_the_next_chapter_or_the_end() {
+    let chapters = [
+      chapter("Opening", 0.0),
+      chapter("Episode", 90.0),
+      chapter("Ending", 1290.0),
+      chapter("Preview", 1380.0),
+    ];
+
+    assert_eq!(
+      credits_fallback_range(&chapters, 1420.0, None),
+      credits(1290.0, 1380.0)
+    );
+    assert_eq!(
+      credits_fallback_range(&chapters[..3], 1420.0, None),
+      credits(1290.0, 1420.0)
+    );
+  }
+
+  #[test]
+  fn chapter_titles_match_case_insensitively() {
+    let chapters = [chapter("Episode", 0.0), chapter(" END CREDITS ", 1300.0)];
+
+    assert_eq!(
+      credits_fallback_range(&chapters, 1400.0, None),
+      credits(1300.0, 1400.0)
+    );
+  }
+
+  #[test]
+  fn opening_credits_and_early_chapters_are_not_credits() {
+    let chapters = [
+      chapter("Opening Credits", 0.0),
+      chapter("Credits", 60.0),
+      chapter("Episode", 120.0),
+    ];
+
+    assert_eq!(credits_fallback_range(&chapters, 1400.0, None), None);
+  }
+
+  #[test]
+  fn tail_is_used_only_without_a_credits_chapter() {
+    let untitled = [Chapter {
+      title: None,
+      time: 0.0,
+    }];
+
+    assert_eq!(
+      credits_fallback_range(&untitled, 1400.0, Some(60)),
+      credits(1340.0, 1400.0)
+    );
+    assert_eq!(
+      credits_fallback_range(&[chapter("Credits", 1350.0)], 1400.0, Some(60)),
+      credits(1350.0, 1400.0)
+    );
+    assert_eq!(credits_fallback_range(&[], 1400.0, None), None);
+  }
+
+  #[test]
+  fn tail_longer_than_half_the_file_is_ignored() {
+    assert_eq!(credits_fallback_range(&[], 100.0, Some(60)), None);
+    assert_eq!(credits_fallback_range(&[], f64::NAN, Some(60)), None);
+  }
+}
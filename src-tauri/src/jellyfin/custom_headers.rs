@@ -0,0 +1,81 @@
+//! Extra request headers for servers behind an authenticating reverse proxy.
+//!
+//! Proxies such as Cloudflare Access only let a request through when it
+//! carries a service token, e.g. `CF-Access-Client-Id` and
+//! `CF-Access-Client-Secret`. A server profile can list such headers; they
+//! are sent with every HTTP request and with the WebSocket upgrade.
+
+use std::collections::BTreeMap;
+
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+
+use super::error::JellyfinError;
+
+/// Headers the client sets itself, which a profile may not replace.
+const RESERVED: &[&str] = &[
+  "authorization",
+  "connection",
+  "content-length",
+  "host",
+  "upgrade",
+  "user-agent",
+  "x-emby-authorization",
+  "x-emby-token",
+];
+
+/// Header map for the headers of a server profile.
+pub fn header_map(headers: &BTreeMap<String, String>) -> Result<HeaderMap, JellyfinError> {
+  let mut map = HeaderMap::new();
+  for (name, value) in headers {
+    let name = name.trim();
+    let header_name = HeaderName::from_bytes(name.as_bytes())
+      .map_err(|_| JellyfinError::HttpError(format!("Invalid header name: {name}")))?;
+    if RESERVED.contains(&header_name.as_str())
+      || header_name.as_str().starts_with("sec-websocket-")
+    {
+      return Err(JellyfinError::HttpError(format!(
+        "The {name} header is set by JellyPilot and can't be replaced"
+      )));
+    }
+    let mut header_value = HeaderValue::from_str(value.trim())
+      .map_err(|_| JellyfinError::HttpError(format!("Invalid value for header {name}")))?;
+    header_value.set_sensitive(true);
+    map.insert(header_name, header_value);
+  }
+  Ok(map)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn headers(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+    pairs
+      .iter()
+      .map(|(name, value)| (name.to_string(), value.to_string()))
+      .collect()
+  }
+
+  #[test]
+  fn proxy_tokens_become_sensitive_headers() {
+    let map = header_map(&headers(&[
+      ("CF-Access-Client-Id", "client.access"),
+      (" CF-Access-Client-Secret ", " secret "),
+    ]))
+    .unwrap();
+
+    assert_eq!(map.len(), 2);
+    assert_eq!(map["cf-access-client-id"], "client.access");
+    assert_eq!(map["cf-access-client-secret"], "secret");
+    assert!(map["cf-access-client-secret"].is_sensitive());
+  }
+
+  #[test]
+  fn headers_the_client_sets_are_rejected() {
+    assert!(header_map(&headers(&[("Host", "jellyfin.example")])).is_err());
+    assert!(header_map(&headers(&[("X-Emby-Authorization", "MediaBrowser")])).is_err());
+    assert!(header_map(&headers(&[("Sec-WebSocket-Key", "abc")])).is_err());
+    assert!(header_map(&headers(&[("Bad Name", "value")])).is_err());
+    assert!(header_map(&headers(&[("X-Token", "line\nbreak")])).is_err());
+  }
+}
@@ -0,0 +1,100 @@
+//! Another install signed in with this device's id.
+//!
+//! The server keeps one session per device id, so a second JellyPilot with a
+//! copied config takes over the same session and casts reach whichever one
+//! spoke last. The session list gives it away: the session's remote address
+//! changes while this install stays connected, or several sessions with the
+//! id come from different addresses.
+
+use super::types::SessionInfo;
+
+/// Where the server sees this install connect from.
+#[derive(Debug, Default)]
+pub struct DeviceEndpoint {
+  known: Option<String>,
+  reported: bool,
+}
+
+impl DeviceEndpoint {
+  /// Forget the address after a reconnect, which may come from a new one.
+  pub fn forget(&mut self) {
+    self.known = None;
+  }
+
+  /// Check the server's sessions for this device id. Returns the other
+  /// install's address the first time a conflict shows up.
+  pub fn check(&mut self, sessions: &[SessionInfo]) -> Option<String> {
+    let mut endpoints: Vec<&str> = sessions
+      .iter()
+      .filter_map(|session| session.remote_end_point.as_deref())
+      .map(str::trim)
+      .filter(|endpoint| !endpoint.is_empty())
+      .collect();
+    endpoints.sort_unstable();
+    endpoints.dedup();
+
+    if self.known.is_none() {
+      match endpoints.as_slice() {
+        [] => return None,
+        [only] => {
+          self.known = Some(only.to_string());
+          return None;
+        }
+        _ => {}
+      }
+    }
+    let known = self.known.as_deref().unwrap_or(endpoints[0]);
+    let other = endpoints.iter().find(|endpoint| **endpoint != known)?;
+    if std::mem::replace(&mut self.reported, true) {
+      return None;
+    }
+    Some(other.to_string())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn sessions(endpoints: &[&str]) -> Vec<SessionInfo> {
+    endpoints
+      .iter()
+      .map(|endpoint| {
+        serde_json::from_value(serde_json::json!({
+          "Id": format!("session-{endpoint}"),
+          "DeviceId": "jellypilot-device",
+          "RemoteEndPoint": endpoint,
+        }))
+        .unwrap()
+      })
+      .collect()
+  }
+
+  #[test]
+  fn a_moved_session_is_reported_once() {
+    let mut endpoint = DeviceEndpoint::default();
+
+    assert_eq!(endpoint.check(&sessions(&["192.168.1.20"])), None);
+    assert_eq!(endpoint.check(&sessions(&["192.168.1.20"])), None);
+    assert_eq!(
+      endpoint.check(&sessions(&["192.168.1.31"])),
+      Some("192.168.1.31".to_string())
+    );
+    assert_eq!(endpoint.check(&sessions(&["192.168.1.31"])), None);
+  }
+
+  #[test]
+  fn sessions_from_two_addresses_conflict_and_reconnects_start_over() {
+    let mut endpoint = DeviceEndpoint::default();
+    assert_eq!(
+      endpoint.check(&sessions(&["10.0.0.2", "10.0.0.9"])),
+      Some("10.0.0.9".to_string())
+    );
+
+    let mut endpoint = DeviceEndpoint::default();
+    assert_eq!(endpoint.check(&sessions(&["10.0.0.2"])), None);
+    endpoint.forget();
+    assert_eq!(endpoint.check(&sessions(&["10.0.0.7"])), None);
+    assert_eq!(endpoint.check(&sessions(&[])), None);
+  }
+}
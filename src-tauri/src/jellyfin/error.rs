@@ -1,7 +1,46 @@
 //! Jellyfin error types.
 
+use serde::Serialize;
+use specta::Type;
 use thiserror::Error;
 
+use crate::i18n::tr;
+
+/// Stage of starting or running playback that failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Type)]
+#[serde(rename_all = "kebab-case")]
+pub enum PlaybackErrorStage {
+  /// The server rejected the session token.
+  Auth,
+  /// Item metadata or playback info could not be fetched.
+  PlaybackInfo,
+  /// No stream URL could be built for the media source.
+  StreamUrl,
+  /// MPV could not be started.
+  MpvSpawn,
+  /// MPV failed to open the stream.
+  MpvLoad,
+  /// The IPC connection to MPV dropped during playback.
+  IpcLost,
+  /// The file ended well before the end of the item.
+  EndedEarly,
+}
+
+impl PlaybackErrorStage {
+  /// What the user can do about a failure at this stage.
+  pub fn user_message(self) -> String {
+    match self {
+      Self::Auth => tr!("playback-error-auth"),
+      Self::PlaybackInfo => tr!("playback-error-playback-info"),
+      Self::StreamUrl => tr!("playback-error-stream-url"),
+      Self::MpvSpawn => tr!("playback-error-mpv-spawn"),
+      Self::MpvLoad => tr!("playback-error-mpv-load"),
+      Self::IpcLost => tr!("playback-error-ipc-lost"),
+      Self::EndedEarly => tr!("playback-error-ended-early"),
+    }
+  }
+}
+
 /// Errors that can occur when interacting with Jellyfin.
 #[derive(Debug, Error)]
 pub enum JellyfinError {
@@ -20,6 +59,15 @@ pub enum JellyfinError {
   #[error("Authentication failed: {0}")]
   AuthFailed(String),
 
+  /// The server no longer accepts the saved access token, e.g. after a
+  /// password change or when the session was revoked.
+  #[error("Session expired: {0}")]
+  CredentialsExpired(String),
+
+  /// The server rejected the username or password.
+  #[error("Invalid username or password: {0}")]
+  InvalidCredentials(String),
+
   #[error("Quick Connect is not enabled on this server")]
   QuickConnectUnavailable,
 
@@ -29,6 +77,81 @@ pub enum JellyfinError {
   #[error("Invalid server URL: {0}")]
   InvalidUrl(String),
 
+  #[error("TLS configuration error: {0}")]
+  Tls(String),
+
   #[error("Session not found")]
   SessionNotFound,
+
+  #[error("{source}")]
+  Playback {
+    stage: PlaybackErrorStage,
+    source: Box<JellyfinError>,
+  },
+}
+
+impl JellyfinError {
+  /// Tag an error with the playback stage it happened in. Authentication
+  /// failures are always tagged as [`PlaybackErrorStage::Auth`].
+  pub fn at_playback_stage(self, stage: PlaybackErrorStage) -> Self {
+    match self {
+      Self::Playback { .. } => self,
+      Self::AuthFailed(_) | Self::CredentialsExpired(_) | Self::InvalidCredentials(_) => {
+        Self::Playback {
+          stage: PlaybackErrorStage::Auth,
+          source: Box::new(self),
+        }
+      }
+      _ => Self::Playback {
+        stage,
+        source: Box::new(self),
+      },
+    }
+  }
+
+  /// True when the saved token was rejected and the user has to sign in again.
+  pub fn is_credentials_expired(&self) -> bool {
+    match self {
+      Self::CredentialsExpired(_) => true,
+      Self::Playback { source, .. } => source.is_credentials_expired(),
+      _ => false,
+    }
+  }
+
+  /// Playback stage this error was tagged with, if any.
+  pub fn playback_stage(&self) -> Option<PlaybackErrorStage> {
+    match self {
+      Self::Playback { stage, .. } => Some(*stage),
+      _ => None,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn playback_stage_keeps_the_first_stage_and_flags_auth_failures() {
+    let err = JellyfinError::NotConnected.at_playback_stage(PlaybackErrorStage::StreamUrl);
+    assert_eq!(err.playback_stage(), Some(PlaybackErrorStage::StreamUrl));
+    assert_eq!(err.to_string(), "Not connected to server");
+
+    let err = err.at_playback_stage(PlaybackErrorStage::PlaybackInfo);
+    assert_eq!(err.playback_stage(), Some(PlaybackErrorStage::StreamUrl));
+
+    let err = JellyfinError::AuthFailed("expired".to_string())
+      .at_playback_stage(PlaybackErrorStage::PlaybackInfo);
+    assert_eq!(err.playback_stage(), Some(PlaybackErrorStage::Auth));
+  }
+
+  #[test]
+  fn expired_credentials_are_detected_through_playback_stages() {
+    let err = JellyfinError::CredentialsExpired("revoked".to_string())
+      .at_playback_stage(PlaybackErrorStage::PlaybackInfo);
+    assert_eq!(err.playback_stage(), Some(PlaybackErrorStage::Auth));
+    assert!(err.is_credentials_expired());
+
+    assert!(!JellyfinError::InvalidCredentials("wrong".to_string()).is_credentials_expired());
+  }
 }
@@ -0,0 +1,200 @@
+//! Skip ranges the user marks from MPV.
+//!
+//! Marks are stored per series, or per item outside a series, so an intro
+//! marked once is skipped in every episode without any server plugin.
+
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+
+use super::session::{MpvAction, SessionState};
+use super::skip_range::{SkipKind, SkipRange};
+use super::types::{ticks_to_seconds, MediaItem};
+use crate::i18n::tr;
+
+/// Marks starting this close to a stored range replace it.
+///
+/// Intros rarely start at exactly the same second in every episode, so
+/// re-marking one adjusts the stored range instead of adding a near-duplicate.
+const REMARK_TOLERANCE_SECONDS: f64 = 10.0;
+
+/// A stored user skip range.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarkedSkipRange {
+  pub start_seconds: f64,
+  pub end_seconds: f64,
+}
+
+impl MarkedSkipRange {
+  /// A mark, or None when the end is not after the start.
+  pub fn new(start_seconds: f64, end_seconds: f64) -> Option<Self> {
+    SkipRange::new(SkipKind::Custom, start_seconds, end_seconds).map(|_| Self {
+      start_seconds,
+      end_seconds,
+    })
+  }
+
+  fn replaces(&self, other: &MarkedSkipRange) -> bool {
+    let overlaps = self.start_seconds < other.end_seconds && other.start_seconds < self.end_seconds;
+    overlaps || (self.start_seconds - other.start_seconds).abs() <= REMARK_TOLERANCE_SECONDS
+  }
+}
+
+/// Key marks for `item` are stored under: its series, or the item itself.
+pub fn marked_skip_key(item: &MediaItem) -> &str {
+  item.series_id.as_deref().unwrap_or(&item.id)
+}
+
+/// Store `mark`, replacing stored ranges it overlaps or nearly shares a start with.
+pub fn insert_marked_range(ranges: &mut Vec<MarkedSkipRange>, mark: MarkedSkipRange) {
+  ranges.retain(|range| !mark.replaces(range));
+  ranges.push(mark);
+  ranges.sort_by(|a, b| a.start_seconds.total_cmp(&b.start_seconds));
+}
+
+/// Skip engine ranges for stored marks; stored values that no longer validate are dropped.
+pub fn marked_skip_ranges(ranges: &[MarkedSkipRange]) -> Vec<SkipRange> {
+  ranges
+    .iter()
+    .filter_map(|range| SkipRange::new(SkipKind::Custom, range.start_seconds, range.end_seconds))
+    .collect()
+}
+
+/// Remember the current position as the start of a user skip range.
+pub(super) async fn handle_skip_mark_start(
+  state: &RwLock<SessionState>,
+  action_tx: &mpsc::Sender<MpvAction>,
+) {
+  let position_seconds = {
+    let mut s = state.write();
+    let Some(position_ticks) = s.playback.as_ref().map(|playback| playback.position_ticks) else {
+      log::warn!("Skip start marked without playback");
+      return;
+    };
+    let position_seconds = ticks_to_seconds(position_ticks);
+    s.skip_mark_start = Some(position_seconds);
+    position_seconds
+  };
+
+  let _ = action_tx
+    .send(MpvAction::ShowText {
+      text: tr!(
+        "osd-skip-start-marked",
+        position = crate::tray::format_clock(position_seconds)
+      ),
+      duration_ms: 1500,
+    })
+    .await;
+}
+
+/// Close the user skip range started by the last mark and store it for the
+/// series. Returns whether the stored ranges changed.
+pub(super) async fn handle_skip_mark_end(
+  state: &RwLock<SessionState>,
+  action_tx: &mpsc::Sender<MpvAction>,
+) -> bool {
+  let mark = {
+    let mut s = state.write();
+    let Some(end_seconds) = s
+      .playback
+      .as_ref()
+      .map(|playback| ticks_to_seconds(playback.position_ticks))
+    else {
+      log::warn!("Skip end marked without playback");
+      return false;
+    };
+    let Some(key) = s
+      .current_item
+      .as_ref()
+      .map(|item| marked_skip_key(item).to_string())
+    else {
+      return false;
+    };
+    let mark = s
+      .skip_mark_start
+      .and_then(|start_seconds| MarkedSkipRange::new(start_seconds, end_seconds));
+    if let Some(mark) = mark {
+      s.skip_mark_start = None;
+      insert_marked_range(s.marked_skip_ranges.entry(key).or_default(), mark);
+      if let Some(playback) = s.playback.as_mut() {
+        // Already watched through in this session; applies from the next playback.
+        if let Some(mut range) = marked_skip_ranges(&[mark]).pop() {
+          range.notified = true;
+          range.skipped = true;
+          playback.skip_ranges.push(range);
+        }
+      }
+    }
+    mark
+  };
+
+  let text = match mark {
+    Some(mark) => tr!(
+      "osd-skip-range-saved",
+      start = crate::tray::format_clock(mark.start_seconds),
+      end = crate::tray::format_clock(mark.end_seconds)
+    ),
+    None => tr!("osd-skip-mark-start-first"),
+  };
+  let _ = action_tx
+    .send(MpvAction::ShowText {
+      text,
+      duration_ms: 2000,
+    })
+    .await;
+  mark.is_some()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn mark(start_seconds: f64, end_seconds: f64) -> MarkedSkipRange {
+    MarkedSkipRange::new(start_seconds, end_seconds).unwrap()
+  }
+
+  #[test]
+  fn marks_must_end_after_they_start() {
+    assert!(MarkedSkipRange::new(80.0, 10.0).is_none());
+    assert!(MarkedSkipRange::new(10.0, 10.0).is_none());
+    assert!(MarkedSkipRange::new(f64::NAN, 10.0).is_none());
+  }
+
+  #[test]
+  fn remarking_near_the_same_start_replaces_the_stored_range() {
+    let mut ranges = vec![mark(60.0, 150.0)];
+
+    insert_marked_range(&mut ranges, mark(52.0, 141.0));
+
+    assert_eq!(ranges, vec![mark(52.0, 141.0)]);
+  }
+
+  #[test]
+  fn overlapping_marks_replace_and_separate_marks_accumulate() {
+    let mut ranges = vec![mark(60.0, 150.0)];
+
+    insert_marked_range(&mut ranges, mark(1300.0, 1380.0));
+    insert_marked_range(&mut ranges, mark(120.0, 170.0));
+
+    assert_eq!(ranges, vec![mark(120.0, 170.0), mark(1300.0, 1380.0)]);
+  }
+
+  #[test]
+  fn stored_marks_become_custom_skip_ranges() {
+    let stored = vec![
+      mark(60.0, 150.0),
+      MarkedSkipRange {
+        start_seconds: 90.0,
+        end_seconds: 30.0,
+      },
+    ];
+
+    let ranges = marked_skip_ranges(&stored);
+
+    assert_eq!(
+      ranges,
+      vec![SkipRange::new(SkipKind::Custom, 60.0, 150.0).unwrap()]
+    );
+  }
+}
@@ -0,0 +1,153 @@
+//! Choosing between the versions (media sources) of an item.
+//!
+//! Items with several versions, e.g. a 4K and a 1080p file or different cuts,
+//! list one media source per version in their playback info.
+
+use super::types::MediaSource;
+use crate::config::MediaVersionPreference;
+
+/// Pick the version to play.
+///
+/// A version named by the Play request wins; otherwise `preference` decides.
+/// Versions without the compared bitrate or height sort last, and ties go to
+/// the version the server lists first.
+pub fn select_media_source<'a>(
+  sources: &'a [MediaSource],
+  requested_id: Option<&str>,
+  preference: MediaVersionPreference,
+  preferred_height: u32,
+) -> Option<&'a MediaSource> {
+  if let Some(source) = requested_id.and_then(|id| sources.iter().find(|source| source.id == id)) {
+    return Some(source);
+  }
+  if let Some(id) = requested_id {
+    log::warn!(
+      "Requested media source {} not found; choosing by preference",
+      id
+    );
+  }
+
+  match preference {
+    MediaVersionPreference::ServerDefault => sources.first(),
+    MediaVersionPreference::HighestBitrate => sources
+      .iter()
+      .rev()
+      .max_by_key(|source| source.bitrate.unwrap_or(i32::MIN)),
+    MediaVersionPreference::LowestBitrate => sources
+      .iter()
+      .min_by_key(|source| source.bitrate.unwrap_or(i32::MAX)),
+    MediaVersionPreference::PreferredResolution => {
+      let preferred = i32::try_from(preferred_height).unwrap_or(i32::MAX);
+      sources
+        .iter()
+        .rev()
+        .filter(|source| {
+          source
+            .video_height()
+            .is_some_and(|height| height <= preferred)
+        })
+        .max_by_key(|source| (source.video_height(), source.bitrate))
+        .or_else(|| {
+          sources
+            .iter()
+            .filter(|source| source.video_height().is_some())
+            .min_by_key(|source| source.video_height())
+        })
+        .or_else(|| sources.first())
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::jellyfin::MediaStream;
+
+  fn version(id: &str, height: Option<i32>, bitrate: Option<i32>) -> MediaSource {
+    MediaSource {
+      id: id.to_string(),
+      path: None,
+      protocol: "File".to_string(),
+      container: Some("mkv".to_string()),
+      run_time_ticks: None,
+      media_streams: vec![MediaStream {
+        index: 0,
+        stream_type: "Video".to_string(),
+        codec: None,
+        language: None,
+        display_title: None,
+        is_default: true,
+        is_external: false,
+        height,
+      }],
+      supports_direct_play: true,
+      supports_direct_stream: false,
+      supports_transcoding: false,
+      direct_stream_url: None,
+      add_api_key_to_direct_stream_url: None,
+      transcoding_url: None,
+      live_stream_id: None,
+      is_infinite_stream: false,
+      name: None,
+      bitrate,
+    }
+  }
+
+  fn versions() -> Vec<MediaSource> {
+    vec![
+      version("1080p", Some(1080), Some(10_000_000)),
+      version("4k", Some(2160), Some(60_000_000)),
+      version("720p", Some(720), Some(4_000_000)),
+    ]
+  }
+
+  fn selected(
+    requested_id: Option<&str>,
+    preference: MediaVersionPreference,
+    preferred_height: u32,
+  ) -> Option<String> {
+    let sources = versions();
+    select_media_source(&sources, requested_id, preference, preferred_height)
+      .map(|source| source.id.clone())
+  }
+
+  #[test]
+  fn requested_version_wins_over_preference() {
+    assert_eq!(
+      selected(Some("720p"), MediaVersionPreference::HighestBitrate, 1080).as_deref(),
+      Some("720p")
+    );
+    assert_eq!(
+      selected(Some("missing"), MediaVersionPreference::ServerDefault, 1080).as_deref(),
+      Some("1080p")
+    );
+  }
+
+  #[test]
+  fn bitrate_preferences_pick_the_extremes() {
+    assert_eq!(
+      selected(None, MediaVersionPreference::HighestBitrate, 1080).as_deref(),
+      Some("4k")
+    );
+    assert_eq!(
+      selected(None, MediaVersionPreference::LowestBitrate, 1080).as_deref(),
+      Some("720p")
+    );
+  }
+
+  #[test]
+  fn preferred_resolution_never_exceeds_the_height_when_possible() {
+    assert_eq!(
+      selected(None, MediaVersionPreference::PreferredResolution, 1440).as_deref(),
+      Some("1080p")
+    );
+    assert_eq!(
+      selected(None, MediaVersionPreference::PreferredResolution, 2160).as_deref(),
+      Some("4k")
+    );
+    assert_eq!(
+      selected(None, MediaVersionPreference::PreferredResolution, 480).as_deref(),
+      Some("720p")
+    );
+  }
+}
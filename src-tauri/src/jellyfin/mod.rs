@@ -2,18 +2,50 @@
 //!
 //! Handles authentication, WebSocket remote control, and playback reporting.
 
+mod action_watchdog;
+mod adopted_playback;
+mod bandwidth;
+mod capabilities;
+mod chapters;
 mod client;
 #[cfg(test)]
 mod client_facade;
+mod credits_fallback;
+mod custom_headers;
+mod device_conflict;
 mod error;
 mod intro_skipper;
+mod marked_skip;
+mod media_version;
 mod mpv_event;
+#[cfg(feature = "openapi-models")]
+mod openapi_models;
 mod play_resolution;
+mod preference_scope;
+mod queue;
+mod response_cache;
+mod resume_rewind;
+mod screenshot;
 mod session;
+mod session_files;
+mod session_phase;
+mod skip_range;
+mod stall;
+mod subtitle_tracks;
+mod tls;
 mod types;
+mod volume;
 mod websocket;
 
+pub use bandwidth::{BandwidthMeter, BandwidthStats};
+pub use capabilities::{CastCapabilities, CastMediaType, RemoteCommand};
+pub use chapters::PlaybackChapter;
 pub use client::JellyfinClient;
-pub use error::JellyfinError;
-pub use session::SessionManager;
+pub use error::{JellyfinError, PlaybackErrorStage};
+pub use preference_scope::scope_of_key;
+pub use screenshot::ScreenshotMode;
+pub use session::{SeekTarget, SessionManager};
+pub use session_files::remove_stale_session_files;
+pub use session_phase::SessionPhase;
+pub use tls::TlsTrust;
 pub use types::*;
@@ -2,9 +2,22 @@
 
 use std::time::{Duration, Instant};
 
-use super::types::{seconds_to_ticks, PlaybackSession};
+use super::types::{seconds_to_ticks, MediaItem, PlaybackSession, TICKS_PER_SECOND};
+use crate::config::AutoNextScope;
 use crate::playback_control::AdjacentDirection;
 
+/// Seeking back this far before the credits cancels the auto-next countdown.
+const CREDITS_COUNTDOWN_SEEK_BACK_SECONDS: f64 = 2.0;
+/// Smallest duration difference treated as a mismatch, so short intros or
+/// container rounding in transcodes don't trigger it.
+const DURATION_MISMATCH_MIN_TICKS: i64 = 60 * TICKS_PER_SECOND;
+/// Relative duration difference treated as a mismatch.
+const DURATION_MISMATCH_RATIO: f64 = 0.05;
+/// How long the position must move normally after a seek before it is reported.
+pub const SEEK_SETTLE_TIME: Duration = Duration::from_millis(500);
+/// Position change beyond the elapsed time that counts as a seek, in seconds.
+const SEEK_JUMP_SECONDS: f64 = 1.0;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PropertyReportDecision {
   Ignore,
@@ -14,7 +27,7 @@ pub enum PropertyReportDecision {
 
 pub fn property_report_decision(property_name: &str) -> PropertyReportDecision {
   match property_name {
-    "pause" | "volume" | "mute" => PropertyReportDecision::ReportNow,
+    "pause" | "volume" | "mute" | "seekable" => PropertyReportDecision::ReportNow,
     "time-pos" => PropertyReportDecision::ReportWhenThrottleElapsed,
     _ => PropertyReportDecision::Ignore,
   }
@@ -61,14 +74,239 @@ pub fn apply_property_update(
         playback.position_ticks = seconds_to_ticks(position);
       }
     }
+    "duration" => {
+      playback.mpv_duration_ticks = data.as_f64().map(seconds_to_ticks);
+    }
+    "seekable" => {
+      // A growing recording stays unseekable for Jellyfin until it finishes.
+      if let Some(seekable) = data.as_bool() {
+        playback.can_seek = seekable && !playback.is_growing;
+      }
+    }
     _ => {}
   }
 }
 
+/// True when MPV's duration differs from the Jellyfin runtime by enough to
+/// suggest the wrong file or a truncated transcode.
+pub fn is_duration_mismatch(runtime_ticks: i64, mpv_duration_ticks: i64) -> bool {
+  if runtime_ticks <= 0 || mpv_duration_ticks <= 0 {
+    return false;
+  }
+  (runtime_ticks - mpv_duration_ticks).abs() > duration_tolerance_ticks(runtime_ticks)
+}
+
+/// True when a file that ended at `position_ticks` stopped well short of the
+/// runtime, e.g. because the server's transcode died mid-stream.
+pub fn is_premature_end(position_ticks: i64, runtime_ticks: i64) -> bool {
+  runtime_ticks > 0
+    && runtime_ticks - position_ticks.max(0) > duration_tolerance_ticks(runtime_ticks)
+}
+
+/// How a file MPV played to its end finished.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EndOfFile {
+  /// Played to about its end; auto-next may continue.
+  Finished,
+  /// Ended well short of its length, e.g. after a network failure or in a
+  /// corrupt file.
+  Early,
+}
+
+/// Judge an end of file at `position_ticks` against the item's runtime, or
+/// MPV's duration when the server has no runtime.
+pub fn end_of_file(
+  position_ticks: i64,
+  runtime_ticks: Option<i64>,
+  mpv_duration_ticks: Option<i64>,
+) -> EndOfFile {
+  match runtime_ticks
+    .filter(|ticks| *ticks > 0)
+    .or(mpv_duration_ticks)
+  {
+    Some(length_ticks) if is_premature_end(position_ticks, length_ticks) => EndOfFile::Early,
+    _ => EndOfFile::Finished,
+  }
+}
+
+fn duration_tolerance_ticks(runtime_ticks: i64) -> i64 {
+  DURATION_MISMATCH_MIN_TICKS.max((runtime_ticks as f64 * DURATION_MISMATCH_RATIO) as i64)
+}
+
+/// Whether auto-next at the end of `current` may continue with `next`.
+pub fn auto_next_allowed(scope: AutoNextScope, current: &MediaItem, next: &MediaItem) -> bool {
+  match scope {
+    AutoNextScope::Off => false,
+    AutoNextScope::SameSeason => {
+      current.series_id == next.series_id && current.parent_index_number == next.parent_index_number
+    }
+    AutoNextScope::SameSeries => current.series_id == next.series_id,
+  }
+}
+
 pub fn is_natural_end(reason: Option<&str>) -> bool {
   reason == Some("eof")
 }
 
+/// Countdown to the next item that started when playback reached the credits.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CreditsCountdown {
+  start_seconds: f64,
+  shown_seconds: Option<u32>,
+}
+
+/// What a position update means for a running credits countdown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CountdownStep {
+  Wait,
+  /// Whole seconds left, shown when it changes.
+  Show(u32),
+  PlayNext,
+  /// Playback moved back before the credits.
+  Cancel,
+}
+
+impl CreditsCountdown {
+  pub fn new(start_seconds: f64) -> Self {
+    Self {
+      start_seconds,
+      shown_seconds: None,
+    }
+  }
+
+  /// Advance with playback; the countdown pauses with it.
+  pub fn step(&mut self, position_seconds: f64, countdown_seconds: u32) -> CountdownStep {
+    if position_seconds < self.start_seconds - CREDITS_COUNTDOWN_SEEK_BACK_SECONDS {
+      return CountdownStep::Cancel;
+    }
+    let remaining = self.start_seconds + f64::from(countdown_seconds) - position_seconds;
+    if remaining <= 0.0 {
+      return CountdownStep::PlayNext;
+    }
+    let remaining = remaining.ceil() as u32;
+    if self.shown_seconds == Some(remaining) {
+      return CountdownStep::Wait;
+    }
+    self.shown_seconds = Some(remaining);
+    CountdownStep::Show(remaining)
+  }
+}
+
+/// What a `time-pos` update means for progress reporting while seeking.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeekReportStep {
+  /// No seek in progress; the usual throttle applies.
+  Throttle,
+  /// A seek is still settling; don't report yet.
+  Hold,
+  /// The position has been stable long enough; report it now.
+  Settled,
+}
+
+/// MPV's A-B loop points in seconds, from its `ab-loop-a` and `ab-loop-b`
+/// properties.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct AbLoop {
+  pub start: Option<f64>,
+  pub end: Option<f64>,
+}
+
+impl AbLoop {
+  /// Take a changed `ab-loop-a` or `ab-loop-b`; MPV reports "no" when unset.
+  pub fn update(&mut self, property_name: &str, data: Option<&serde_json::Value>) {
+    let point = data.and_then(|data| data.as_f64());
+    match property_name {
+      "ab-loop-a" => self.start = point,
+      "ab-loop-b" => self.end = point,
+      _ => {}
+    }
+  }
+
+  pub fn is_set(&self) -> bool {
+    self.start.is_some() || self.end.is_some()
+  }
+
+  /// The loop after the loop shortcut at `position`: the first press sets
+  /// the start, the second the end, the third clears it.
+  pub fn step(&self, position: f64) -> Self {
+    match (self.start, self.end) {
+      (None, _) => Self {
+        start: Some(position),
+        end: None,
+      },
+      (Some(start), None) if position > start => Self {
+        start: Some(start),
+        end: Some(position),
+      },
+      (Some(start), None) if position < start => Self {
+        start: Some(position),
+        end: Some(start),
+      },
+      (Some(_), None) => *self,
+      (Some(_), Some(_)) => Self::default(),
+    }
+  }
+
+  /// Whether moving from `from` to `to` is MPV going from the end of the
+  /// loop back to its start.
+  fn wraps(&self, from: f64, to: f64) -> bool {
+    let (Some(start), Some(end)) = (self.start, self.end) else {
+      return false;
+    };
+    from >= end - SEEK_JUMP_SECONDS && (to - start).abs() <= SEEK_JUMP_SECONDS
+  }
+}
+
+/// Coalesces the burst of position jumps from scrubbing into one report of
+/// the position playback settled at. An A-B loop going around is playback,
+/// not a seek, so it keeps the usual throttle.
+#[derive(Debug, Default)]
+pub struct SeekCoalescer {
+  last_position: Option<(f64, Instant)>,
+  last_jump: Option<Instant>,
+}
+
+impl SeekCoalescer {
+  pub fn position(
+    &mut self,
+    position_seconds: f64,
+    ab_loop: &AbLoop,
+    now: Instant,
+  ) -> SeekReportStep {
+    if let Some((last, at)) = self.last_position {
+      let elapsed = now.saturating_duration_since(at).as_secs_f64();
+      let moved = position_seconds - last;
+      let jumped = moved < -SEEK_JUMP_SECONDS || moved > elapsed + SEEK_JUMP_SECONDS;
+      if jumped && !ab_loop.wraps(last, position_seconds) {
+        self.last_jump = Some(now);
+      }
+    }
+    self.last_position = Some((position_seconds, now));
+    if self.settle(now) {
+      SeekReportStep::Settled
+    } else if self.last_jump.is_some() {
+      SeekReportStep::Hold
+    } else {
+      SeekReportStep::Throttle
+    }
+  }
+
+  /// When the pending seek settles if the position stops changing, e.g.
+  /// when scrubbing while paused.
+  pub fn deadline(&self) -> Option<Instant> {
+    self.last_jump.map(|jump| jump + SEEK_SETTLE_TIME)
+  }
+
+  /// Finish a pending seek whose deadline passed. True when it should be reported.
+  pub fn settle(&mut self, now: Instant) -> bool {
+    if self.deadline().is_some_and(|deadline| now >= deadline) {
+      self.last_jump = None;
+      return true;
+    }
+    false
+  }
+}
+
 pub fn client_message_direction(args: &[String]) -> Option<AdjacentDirection> {
   match args.first().map(String::as_str) {
     Some("jellypilot-next") => Some(AdjacentDirection::Next),
@@ -80,14 +318,14 @@ pub fn client_message_direction(args: &[String]) -> Option<AdjacentDirection> {
 #[cfg(test)]
 mod tests {
   use super::*;
-  use crate::jellyfin::intro_skipper::IntroSkipRange;
+  use crate::jellyfin::skip_range::SkipRange;
 
   fn playback() -> PlaybackSession {
     PlaybackSession {
       item_id: "item-1".into(),
       media_source_id: Some("source-1".into()),
       play_session_id: Some("play-1".into()),
-      intro_skipper_ranges: Vec::<IntroSkipRange>::new(),
+      skip_ranges: Vec::<SkipRange>::new(),
       position_ticks: 0,
       is_paused: false,
       is_muted: false,
@@ -95,6 +333,11 @@ mod tests {
       audio_stream_index: None,
       subtitle_stream_index: None,
       play_method: "DirectPlay".into(),
+      mpv_duration_ticks: None,
+      can_seek: true,
+      is_growing: false,
+      subtitles_burned_in: false,
+      live_stream_id: None,
     }
   }
 
@@ -113,6 +356,89 @@ mod tests {
     assert_eq!(playback.position_ticks, 125_000_000);
   }
 
+  #[test]
+  fn duration_updates_are_stored_and_cleared_when_unavailable() {
+    let mut playback = playback();
+
+    apply_property_update(&mut playback, "duration", &serde_json::json!(1500.0));
+    assert_eq!(playback.mpv_duration_ticks, Some(15_000_000_000));
+
+    apply_property_update(&mut playback, "duration", &serde_json::Value::Null);
+    assert_eq!(playback.mpv_duration_ticks, None);
+  }
+
+  #[test]
+  fn seekable_updates_can_seek_except_for_growing_files() {
+    let mut playback = playback();
+
+    apply_property_update(&mut playback, "seekable", &serde_json::json!(false));
+    assert!(!playback.can_seek);
+    apply_property_update(&mut playback, "seekable", &serde_json::json!(true));
+    assert!(playback.can_seek);
+
+    playback.is_growing = true;
+    apply_property_update(&mut playback, "seekable", &serde_json::json!(true));
+    assert!(!playback.can_seek);
+  }
+
+  #[test]
+  fn duration_mismatch_tolerates_small_differences() {
+    let hour = 3600 * TICKS_PER_SECOND;
+
+    assert!(!is_duration_mismatch(hour, hour - 30 * TICKS_PER_SECOND));
+    assert!(!is_duration_mismatch(hour, hour + 150 * TICKS_PER_SECOND));
+    assert!(is_duration_mismatch(hour, hour / 2));
+    assert!(is_duration_mismatch(
+      10 * 60 * TICKS_PER_SECOND,
+      20 * 60 * TICKS_PER_SECOND
+    ));
+    assert!(!is_duration_mismatch(0, hour));
+  }
+
+  #[test]
+  fn an_end_well_before_the_runtime_is_premature() {
+    let hour = 3600 * TICKS_PER_SECOND;
+
+    assert!(is_premature_end(hour / 2, hour));
+    assert!(is_premature_end(0, hour));
+    assert!(!is_premature_end(hour - 150 * TICKS_PER_SECOND, hour));
+    assert!(!is_premature_end(hour + TICKS_PER_SECOND, hour));
+    assert!(!is_premature_end(hour / 2, 0));
+  }
+
+  #[test]
+  fn end_of_file_boundaries_follow_the_runtime_tolerance() {
+    let seconds = |seconds: i64| seconds * TICKS_PER_SECOND;
+    let hour = seconds(3600);
+    let ten_minutes = seconds(600);
+
+    // 5% of an hour is three minutes
+    assert_eq!(
+      end_of_file(hour - seconds(180), Some(hour), None),
+      EndOfFile::Finished
+    );
+    assert_eq!(
+      end_of_file(hour - seconds(181), Some(hour), None),
+      EndOfFile::Early
+    );
+    // Short items still get a minute
+    assert_eq!(
+      end_of_file(ten_minutes - seconds(60), Some(ten_minutes), None),
+      EndOfFile::Finished
+    );
+    assert_eq!(
+      end_of_file(ten_minutes - seconds(61), Some(ten_minutes), None),
+      EndOfFile::Early
+    );
+    // The server's runtime wins over a truncated file's duration
+    assert_eq!(
+      end_of_file(hour / 2, Some(hour), Some(hour / 2)),
+      EndOfFile::Early
+    );
+    assert_eq!(end_of_file(hour / 2, Some(0), Some(hour)), EndOfFile::Early);
+    assert_eq!(end_of_file(hour / 2, None, None), EndOfFile::Finished);
+  }
+
   #[test]
   fn progress_reporting_is_immediate_for_state_changes_and_throttled_for_time_position() {
     let now = Instant::now();
@@ -138,6 +464,188 @@ mod tests {
     ));
   }
 
+  fn episode(id: &str, season: i32) -> MediaItem {
+    MediaItem {
+      id: id.into(),
+      name: id.into(),
+      item_type: "Episode".into(),
+      series_id: Some("series-1".into()),
+      series_name: None,
+      season_name: None,
+      index_number: Some(1),
+      parent_index_number: Some(season),
+      run_time_ticks: None,
+      overview: None,
+      status: None,
+    }
+  }
+
+  #[test]
+  fn credits_countdown_shows_each_second_then_plays_next() {
+    let mut countdown = CreditsCountdown::new(1200.0);
+
+    assert_eq!(countdown.step(1200.0, 5), CountdownStep::Show(5));
+    assert_eq!(countdown.step(1200.4, 5), CountdownStep::Wait);
+    assert_eq!(countdown.step(1201.2, 5), CountdownStep::Show(4));
+    assert_eq!(countdown.step(1205.0, 5), CountdownStep::PlayNext);
+  }
+
+  #[test]
+  fn credits_countdown_without_seconds_plays_next_right_away() {
+    assert_eq!(
+      CreditsCountdown::new(1200.0).step(1200.0, 0),
+      CountdownStep::PlayNext
+    );
+  }
+
+  #[test]
+  fn seeking_back_before_the_credits_cancels_the_countdown() {
+    let mut countdown = CreditsCountdown::new(1200.0);
+
+    assert_eq!(countdown.step(1199.0, 5), CountdownStep::Show(6));
+    assert_eq!(countdown.step(900.0, 5), CountdownStep::Cancel);
+  }
+
+  #[test]
+  fn auto_next_scope_limits_continuation_across_seasons() {
+    let finale = episode("s1e10", 1);
+    let next_in_season = episode("s1e11", 1);
+    let premiere = episode("s2e1", 2);
+    let special = episode("s0e1", 0);
+
+    assert!(auto_next_allowed(
+      AutoNextScope::SameSeason,
+      &finale,
+      &next_in_season
+    ));
+    assert!(!auto_next_allowed(
+      AutoNextScope::SameSeason,
+      &finale,
+      &premiere
+    ));
+    assert!(!auto_next_allowed(
+      AutoNextScope::SameSeason,
+      &finale,
+      &special
+    ));
+    assert!(auto_next_allowed(
+      AutoNextScope::SameSeries,
+      &finale,
+      &premiere
+    ));
+    assert!(!auto_next_allowed(
+      AutoNextScope::Off,
+      &finale,
+      &next_in_season
+    ));
+  }
+
+  /// Feed `(milliseconds, position)` updates and collect the steps.
+  fn seek_steps(updates: &[(u64, f64)]) -> Vec<SeekReportStep> {
+    seek_steps_in_loop(updates, AbLoop::default())
+  }
+
+  fn seek_steps_in_loop(updates: &[(u64, f64)], ab_loop: AbLoop) -> Vec<SeekReportStep> {
+    let start = Instant::now();
+    let mut coalescer = SeekCoalescer::default();
+    updates
+      .iter()
+      .map(|(ms, position)| {
+        coalescer.position(*position, &ab_loop, start + Duration::from_millis(*ms))
+      })
+      .collect()
+  }
+
+  #[test]
+  fn normal_playback_keeps_the_throttle() {
+    use SeekReportStep::Throttle;
+
+    assert_eq!(
+      seek_steps(&[(0, 10.0), (250, 10.25), (500, 10.5), (5000, 15.0)]),
+      vec![Throttle; 4]
+    );
+  }
+
+  #[test]
+  fn scrubbing_burst_reports_once_after_the_position_settles() {
+    use SeekReportStep::{Hold, Settled, Throttle};
+
+    assert_eq!(
+      seek_steps(&[
+        (0, 10.0),
+        (100, 60.0),
+        (200, 120.0),
+        (300, 90.0),
+        (500, 90.2),
+        (800, 90.5),
+        (900, 90.6),
+      ]),
+      vec![Throttle, Hold, Hold, Hold, Hold, Settled, Throttle]
+    );
+  }
+
+  #[test]
+  fn resuming_after_a_long_pause_is_not_a_seek() {
+    assert_eq!(
+      seek_steps(&[(0, 10.0), (60_000, 10.04)]),
+      vec![SeekReportStep::Throttle; 2]
+    );
+  }
+
+  #[test]
+  fn an_ab_loop_going_around_is_not_a_seek() {
+    use SeekReportStep::{Hold, Throttle};
+    let ab_loop = AbLoop {
+      start: Some(60.0),
+      end: Some(70.0),
+    };
+
+    assert_eq!(
+      seek_steps_in_loop(&[(0, 69.5), (250, 69.9), (500, 60.1), (750, 60.3)], ab_loop),
+      vec![Throttle; 4]
+    );
+    // Seeking elsewhere while looping still coalesces.
+    assert_eq!(
+      seek_steps_in_loop(&[(0, 65.0), (100, 20.0)], ab_loop),
+      vec![Throttle, Hold]
+    );
+  }
+
+  #[test]
+  fn the_loop_shortcut_sets_start_then_end_then_clears() {
+    let mut ab_loop = AbLoop::default();
+    ab_loop.update("ab-loop-a", Some(&serde_json::json!(12.5)));
+    assert_eq!(ab_loop.start, Some(12.5));
+    ab_loop.update("ab-loop-a", Some(&serde_json::json!("no")));
+    assert!(!ab_loop.is_set());
+
+    let started = AbLoop::default().step(30.0);
+    assert_eq!((started.start, started.end), (Some(30.0), None));
+    let set = started.step(20.0);
+    assert_eq!((set.start, set.end), (Some(20.0), Some(30.0)));
+    assert_eq!(started.step(30.0), started);
+    assert_eq!(set.step(40.0), AbLoop::default());
+  }
+
+  #[test]
+  fn a_seek_while_paused_settles_at_its_deadline() {
+    let start = Instant::now();
+    let mut coalescer = SeekCoalescer::default();
+    let ab_loop = AbLoop::default();
+    coalescer.position(10.0, &ab_loop, start);
+    coalescer.position(300.0, &ab_loop, start + Duration::from_millis(100));
+
+    let deadline = coalescer.deadline().expect("seek should be pending");
+    assert_eq!(
+      deadline,
+      start + Duration::from_millis(100) + SEEK_SETTLE_TIME
+    );
+    assert!(!coalescer.settle(deadline - Duration::from_millis(1)));
+    assert!(coalescer.settle(deadline));
+    assert_eq!(coalescer.deadline(), None);
+    assert!(!coalescer.settle(deadline));
+  }
+
   #[test]
   fn natural_end_and_keyboard_shortcuts_map_to_adjacent_playback_decisions() {
     assert!(is_natural_end(Some("eof")));
@@ -0,0 +1,181 @@
+//! Domain types built from the OpenAPI-generated Jellyfin models.
+//!
+//! Enabled by the `openapi-models` feature. Item and playback-info responses
+//! are deserialized with the generated `jellyfin_api` models, which cover the
+//! full schema, and converted into the small domain types the rest of the
+//! crate uses. Without the feature the hand-written types in `types.rs` parse
+//! responses directly.
+
+use jellyfin_api::models;
+
+use super::error::JellyfinError;
+use super::types::{MediaAttachment, MediaItem, MediaSource, MediaStream, PlaybackInfoResponse};
+
+fn missing_field(context: &str, field: &str) -> JellyfinError {
+  JellyfinError::HttpError(format!("{context} response missing {field}"))
+}
+
+/// Jellyfin formats ids without hyphens; keep that so ids compare equal to
+/// the ones received over the WebSocket.
+fn jellyfin_id(id: uuid::Uuid) -> String {
+  id.simple().to_string()
+}
+
+pub fn media_item(dto: models::BaseItemDto) -> Result<MediaItem, JellyfinError> {
+  let id = dto.id.ok_or_else(|| missing_field("Item", "Id"))?;
+  let item_type = dto.r#type.ok_or_else(|| missing_field("Item", "Type"))?;
+
+  Ok(MediaItem {
+    id: jellyfin_id(id),
+    name: dto.name.flatten().unwrap_or_default(),
+    item_type: item_type.to_string(),
+    series_id: dto.series_id.flatten().map(jellyfin_id),
+    series_name: dto.series_name.flatten(),
+    season_name: dto.season_name.flatten(),
+    index_number: dto.index_number.flatten(),
+    parent_index_number: dto.parent_index_number.flatten(),
+    run_time_ticks: dto.run_time_ticks.flatten(),
+    overview: dto.overview.flatten(),
+    status: dto.status.flatten(),
+  })
+}
+
+pub fn playback_info(
+  response: models::PlaybackInfoResponse,
+) -> Result<PlaybackInfoResponse, JellyfinError> {
+  let media_sources = response
+    .media_sources
+    .unwrap_or_default()
+    .into_iter()
+    .map(media_source)
+    .collect::<Result<_, _>>()?;
+
+  Ok(PlaybackInfoResponse {
+    media_sources,
+    play_session_id: response.play_session_id.flatten(),
+  })
+}
+
+fn media_source(info: models::MediaSourceInfo) -> Result<MediaSource, JellyfinError> {
+  let id = info
+    .id
+    .flatten()
+    .ok_or_else(|| missing_field("Playback info", "MediaSources.Id"))?;
+
+  Ok(MediaSource {
+    id,
+    path: info.path.flatten(),
+    protocol: info
+      .protocol
+      .map(|protocol| protocol.to_string())
+      .unwrap_or_default(),
+    container: info.container.flatten(),
+    run_time_ticks: info.run_time_ticks.flatten(),
+    media_streams: info
+      .media_streams
+      .flatten()
+      .unwrap_or_default()
+      .into_iter()
+      .filter_map(media_stream)
+      .collect(),
+    supports_direct_play: info.supports_direct_play.unwrap_or_default(),
+    supports_direct_stream: info.supports_direct_stream.unwrap_or_default(),
+    supports_transcoding: info.supports_transcoding.unwrap_or_default(),
+    // Jellyfin has no direct stream URL; the stream URL is built client-side.
+    direct_stream_url: None,
+    add_api_key_to_direct_stream_url: None,
+    transcoding_url: info.transcoding_url.flatten(),
+    live_stream_id: info.live_stream_id.flatten(),
+    is_infinite_stream: info.is_infinite_stream.unwrap_or_default(),
+    name: info.name.flatten(),
+    bitrate: info.bitrate.flatten(),
+    media_attachments: info
+      .media_attachments
+      .flatten()
+      .unwrap_or_default()
+      .into_iter()
+      .filter_map(media_attachment)
+      .collect(),
+  })
+}
+
+/// Attachments without an index can't be downloaded, so they are dropped.
+fn media_attachment(attachment: models::MediaAttachment) -> Option<MediaAttachment> {
+  Some(MediaAttachment {
+    index: attachment.index?,
+    codec: attachment.codec.flatten(),
+    file_name: attachment.file_name.flatten(),
+    mime_type: attachment.mime_type.flatten(),
+  })
+}
+
+/// Streams without an index or type can't be selected, so they are dropped.
+fn media_stream(stream: models::MediaStream) -> Option<MediaStream> {
+  Some(MediaStream {
+    index: stream.index?,
+    stream_type: stream.r#type?.to_string(),
+    codec: stream.codec.flatten(),
+    language: stream.language.flatten(),
+    display_title: stream.display_title.flatten(),
+    is_default: stream.is_default.unwrap_or_default(),
+    is_external: stream.is_external.unwrap_or_default(),
+    height: stream.height.flatten(),
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn media_item_keeps_jellyfin_id_format() {
+    let dto: models::BaseItemDto = serde_json::from_value(serde_json::json!({
+      "Id": "00000000000000000000000000000101",
+      "Name": "Pilot",
+      "Type": "Episode",
+      "SeriesId": "00000000000000000000000000000100",
+      "IndexNumber": 1,
+      "ParentIndexNumber": 1,
+      "RunTimeTicks": 12_000_000_000i64
+    }))
+    .unwrap();
+
+    let item = media_item(dto).unwrap();
+
+    assert_eq!(item.id, "00000000000000000000000000000101");
+    assert_eq!(item.item_type, "Episode");
+    assert_eq!(
+      item.series_id.as_deref(),
+      Some("00000000000000000000000000000100")
+    );
+    assert_eq!(item.run_time_ticks, Some(12_000_000_000));
+  }
+
+  #[test]
+  fn playback_info_converts_sources_and_streams() {
+    let response: models::PlaybackInfoResponse = serde_json::from_value(serde_json::json!({
+      "PlaySessionId": "play-1",
+      "MediaSources": [{
+        "Id": "source-1",
+        "Protocol": "File",
+        "Container": "mkv",
+        "SupportsDirectPlay": true,
+        "MediaStreams": [
+          {"Index": 0, "Type": "Video", "Codec": "h264"},
+          {"Index": 1, "Type": "Audio", "Language": "eng", "IsDefault": true}
+        ]
+      }]
+    }))
+    .unwrap();
+
+    let info = playback_info(response).unwrap();
+
+    assert_eq!(info.play_session_id.as_deref(), Some("play-1"));
+    let source = &info.media_sources[0];
+    assert_eq!(source.protocol, "File");
+    assert!(source.supports_direct_play);
+    assert_eq!(source.media_streams.len(), 2);
+    assert_eq!(source.media_streams[1].stream_type, "Audio");
+    assert!(source.media_streams[1].is_default);
+  }
+}
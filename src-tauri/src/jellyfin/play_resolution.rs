@@ -15,12 +15,23 @@ pub struct PlayResolution<'a> {
   pub mpv_audio_index: Option<i32>,
   pub mpv_subtitle_index: Option<i32>,
   pub external_subtitle_stream: Option<&'a MediaStream>,
+  /// Set when the selected subtitle is an image subtitle the stream would drop.
+  pub image_subtitle: Option<ImageSubtitle<'a>>,
   pub start_position: f64,
   pub position_ticks: i64,
   pub play_method: &'static str,
   pub should_fetch_intro_skipper_ranges: bool,
 }
 
+/// How a selected image subtitle is shown on a remuxed or transcoded stream.
+#[derive(Debug, Clone, Copy)]
+pub enum ImageSubtitle<'a> {
+  /// Request a transcode with the subtitle burned into the video.
+  BurnIn(&'a MediaStream),
+  /// The server can't transcode this source; the subtitle can't be shown.
+  Unavailable(&'a MediaStream),
+}
+
 /// Resolve the local playback choices for a Jellyfin Play request.
 pub fn resolve_play_request<'a>(
   request: &PlayRequest,
@@ -71,7 +82,11 @@ pub fn resolve_play_request<'a>(
     }
   });
 
-  let mpv_subtitle_index = if external_subtitle_stream.is_some() {
+  let image_subtitle = image_subtitle(media_source, subtitle_index);
+
+  let mpv_subtitle_index = if external_subtitle_stream.is_some()
+    || matches!(image_subtitle, Some(ImageSubtitle::BurnIn(_)))
+  {
     None
   } else {
     subtitle_index.map(|idx| {
@@ -89,12 +104,16 @@ pub fn resolve_play_request<'a>(
     mpv_audio_index,
     mpv_subtitle_index,
     external_subtitle_stream,
+    image_subtitle,
     start_position: request
       .start_position_ticks
       .map(ticks_to_seconds)
       .unwrap_or(0.0),
     position_ticks: request.start_position_ticks.unwrap_or(0),
-    play_method: play_method(media_source),
+    play_method: match image_subtitle {
+      Some(ImageSubtitle::BurnIn(_)) => "Transcode",
+      _ => play_method(media_source),
+    },
     should_fetch_intro_skipper_ranges: config.intro_skipper_enabled
       && item.item_type == "Episode"
       && playback_info.play_session_id.is_some(),
@@ -103,6 +122,7 @@ pub fn resolve_play_request<'a>(
 
 /// Convert Jellyfin stream index to MPV track index.
 /// Jellyfin uses absolute indices across all streams; MPV uses 1-based indices per track type.
+/// External streams aren't part of the file and don't count.
 pub fn jellyfin_to_mpv_track_index(
   streams: &[MediaStream],
   stream_type: &str,
@@ -110,7 +130,7 @@ pub fn jellyfin_to_mpv_track_index(
 ) -> i32 {
   let mut mpv_index = 0;
   for stream in streams {
-    if stream.stream_type == stream_type {
+    if stream.stream_type == stream_type && !stream.is_external {
       mpv_index += 1;
       if stream.index == jellyfin_index {
         return mpv_index;
@@ -120,7 +140,31 @@ pub fn jellyfin_to_mpv_track_index(
   1
 }
 
-fn play_method(media_source: &MediaSource) -> &'static str {
+/// Image subtitle handling needed for `subtitle_index` on `media_source`.
+///
+/// Only embedded subtitles matter: external ones are loaded by MPV from their
+/// own URL, and the original file keeps every embedded track.
+pub fn image_subtitle(
+  media_source: &MediaSource,
+  subtitle_index: Option<i32>,
+) -> Option<ImageSubtitle<'_>> {
+  let index = subtitle_index.filter(|index| *index >= 0)?;
+  if !media_source.is_server_processed() {
+    return None;
+  }
+  let stream = media_source
+    .media_streams
+    .iter()
+    .find(|stream| stream.index == index && !stream.is_external && stream.is_image_subtitle())?;
+
+  Some(if media_source.can_burn_in_subtitles() {
+    ImageSubtitle::BurnIn(stream)
+  } else {
+    ImageSubtitle::Unavailable(stream)
+  })
+}
+
+pub fn play_method(media_source: &MediaSource) -> &'static str {
   if media_source.supports_direct_play {
     "DirectPlay"
   } else if media_source.supports_direct_stream {
@@ -142,6 +186,7 @@ mod tests {
       media_source_id: None,
       audio_stream_index: audio,
       subtitle_stream_index: subtitle,
+      start_index: None,
     }
   }
 
@@ -157,6 +202,7 @@ mod tests {
       parent_index_number: Some(1),
       run_time_ticks: None,
       overview: None,
+      status: None,
     }
   }
 
@@ -169,6 +215,7 @@ mod tests {
       display_title: None,
       is_default: false,
       is_external: false,
+      height: None,
     }
   }
 
@@ -194,6 +241,11 @@ mod tests {
       direct_stream_url: None,
       add_api_key_to_direct_stream_url: None,
       transcoding_url: None,
+      live_stream_id: None,
+      is_infinite_stream: false,
+      name: None,
+      bitrate: None,
+      media_attachments: Vec::new(),
     }
   }
 
@@ -212,6 +264,7 @@ mod tests {
       subtitle_title: None,
       subtitle_preference_set: subtitle_language.is_some(),
       is_subtitle_enabled: subtitle_language.is_some(),
+      label: None,
     }
   }
 
@@ -378,6 +431,54 @@ mod tests {
     assert!(!non_episode.should_fetch_intro_skipper_ranges);
   }
 
+  #[test]
+  fn image_subtitles_on_transcoded_sources_are_burned_in() {
+    let pgs = MediaStream {
+      codec: Some("PGSSUB".into()),
+      ..stream(3, "Subtitle", Some("eng"))
+    };
+    let source = MediaSource {
+      supports_direct_play: false,
+      supports_transcoding: true,
+      transcoding_url: Some("/videos/item-1/master.m3u8".into()),
+      ..media_source(vec![stream(1, "Audio", Some("eng")), pgs])
+    };
+    let request = request(None, Some(3));
+    let item = item("Episode");
+    let playback_info = playback_info();
+
+    let resolution = resolve(&request, &item, &playback_info, &source, None, &[], true);
+
+    assert!(matches!(
+      resolution.image_subtitle,
+      Some(ImageSubtitle::BurnIn(stream)) if stream.index == 3
+    ));
+    assert_eq!(resolution.mpv_subtitle_index, None);
+    assert_eq!(resolution.play_method, "Transcode");
+  }
+
+  #[test]
+  fn image_subtitles_need_no_handling_when_the_original_file_plays() {
+    let pgs = MediaStream {
+      codec: Some("hdmv_pgs_subtitle".into()),
+      ..stream(3, "Subtitle", Some("eng"))
+    };
+    let direct_play = media_source(vec![pgs.clone()]);
+    let remux_only = MediaSource {
+      supports_direct_play: false,
+      supports_direct_stream: true,
+      direct_stream_url: Some("/videos/item-1/stream.mp4".into()),
+      ..media_source(vec![pgs])
+    };
+
+    assert!(image_subtitle(&direct_play, Some(3)).is_none());
+    assert!(matches!(
+      image_subtitle(&remux_only, Some(3)),
+      Some(ImageSubtitle::Unavailable(_))
+    ));
+    assert!(image_subtitle(&remux_only, Some(-1)).is_none());
+  }
+
   #[test]
   fn jellyfin_track_selection_conversion_still_uses_type_local_mpv_indices() {
     let streams = vec![
@@ -389,6 +490,7 @@ mod tests {
         display_title: None,
         is_default: false,
         is_external: false,
+        height: None,
       },
       MediaStream {
         index: 1,
@@ -398,6 +500,7 @@ mod tests {
         display_title: None,
         is_default: true,
         is_external: false,
+        height: None,
       },
       MediaStream {
         index: 2,
@@ -407,6 +510,7 @@ mod tests {
         display_title: None,
         is_default: false,
         is_external: false,
+        height: None,
       },
       MediaStream {
         index: 3,
@@ -416,6 +520,7 @@ mod tests {
         display_title: None,
         is_default: false,
         is_external: false,
+        height: None,
       },
     ];
 
@@ -423,4 +528,18 @@ mod tests {
     assert_eq!(jellyfin_to_mpv_track_index(&streams, "Subtitle", 3), 1);
     assert_eq!(jellyfin_to_mpv_track_index(&streams, "Audio", 99), 1);
   }
+
+  #[test]
+  fn mpv_track_index_skips_external_subtitles() {
+    let streams = vec![
+      external_subtitle(0, "eng"),
+      stream(1, "Video", None),
+      stream(2, "Subtitle", Some("ger")),
+      external_subtitle(3, "fre"),
+      stream(4, "Subtitle", Some("jpn")),
+    ];
+
+    assert_eq!(jellyfin_to_mpv_track_index(&streams, "Subtitle", 2), 1);
+    assert_eq!(jellyfin_to_mpv_track_index(&streams, "Subtitle", 4), 2);
+  }
 }
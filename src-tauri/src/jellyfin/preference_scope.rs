@@ -0,0 +1,106 @@
+//! Keys under which track preferences are remembered.
+//!
+//! Series keys are the bare series id, as stored before scopes existed, so
+//! saved series preferences keep working. Library keys carry a prefix and the
+//! global preference uses a fixed key.
+
+use super::types::MediaItem;
+use crate::config::TrackPreferenceScope;
+
+const LIBRARY_KEY_PREFIX: &str = "library:";
+const GLOBAL_KEY: &str = "global";
+
+/// Where track changes for the current item are remembered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreferenceTarget {
+  pub key: String,
+  /// Series or library name shown when listing saved preferences.
+  pub label: Option<String>,
+}
+
+/// Preference target for `item`; `library` is its library, when known.
+pub fn preference_target(
+  scope: TrackPreferenceScope,
+  item: &MediaItem,
+  library: Option<&MediaItem>,
+) -> Option<PreferenceTarget> {
+  match scope {
+    TrackPreferenceScope::Series => item.series_id.as_ref().map(|series_id| PreferenceTarget {
+      key: series_id.clone(),
+      label: item.series_name.clone(),
+    }),
+    TrackPreferenceScope::Library => library.map(|library| PreferenceTarget {
+      key: format!("{}{}", LIBRARY_KEY_PREFIX, library.id),
+      label: Some(library.name.clone()),
+    }),
+    TrackPreferenceScope::Global => Some(PreferenceTarget {
+      key: GLOBAL_KEY.to_string(),
+      label: None,
+    }),
+  }
+}
+
+/// Scope a stored preference key belongs to.
+pub fn scope_of_key(key: &str) -> TrackPreferenceScope {
+  if key == GLOBAL_KEY {
+    TrackPreferenceScope::Global
+  } else if key.starts_with(LIBRARY_KEY_PREFIX) {
+    TrackPreferenceScope::Library
+  } else {
+    TrackPreferenceScope::Series
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn item(id: &str, name: &str, item_type: &str, series_id: Option<&str>) -> MediaItem {
+    MediaItem {
+      id: id.to_string(),
+      name: name.to_string(),
+      item_type: item_type.to_string(),
+      series_id: series_id.map(str::to_string),
+      series_name: series_id.map(|_| "Show".to_string()),
+      season_name: None,
+      index_number: None,
+      parent_index_number: None,
+      run_time_ticks: None,
+      overview: None,
+      status: None,
+    }
+  }
+
+  #[test]
+  fn targets_follow_the_configured_scope() {
+    let episode = item("episode-1", "Pilot", "Episode", Some("series-1"));
+    let library = item("library-1", "Anime", "CollectionFolder", None);
+
+    let series = preference_target(TrackPreferenceScope::Series, &episode, Some(&library)).unwrap();
+    assert_eq!(series.key, "series-1");
+    assert_eq!(series.label.as_deref(), Some("Show"));
+
+    let by_library =
+      preference_target(TrackPreferenceScope::Library, &episode, Some(&library)).unwrap();
+    assert_eq!(by_library.key, "library:library-1");
+    assert_eq!(by_library.label.as_deref(), Some("Anime"));
+
+    let global = preference_target(TrackPreferenceScope::Global, &episode, None).unwrap();
+    assert_eq!(global.key, "global");
+  }
+
+  #[test]
+  fn items_without_series_or_library_have_no_scoped_target() {
+    let movie = item("movie-1", "Movie", "Movie", None);
+
+    assert!(preference_target(TrackPreferenceScope::Series, &movie, None).is_none());
+    assert!(preference_target(TrackPreferenceScope::Library, &movie, None).is_none());
+  }
+
+  #[test]
+  fn stored_keys_map_back_to_their_scope() {
+    assert_eq!(scope_of_key("series-1"), TrackPreferenceScope::Series);
+    assert_eq!(scope_of_key("library:abc"), TrackPreferenceScope::Library);
+    assert_eq!(scope_of_key("global"), TrackPreferenceScope::Global);
+  }
+}
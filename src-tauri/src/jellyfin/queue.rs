@@ -0,0 +1,286 @@
+//! Playback queue for multi-item Play requests.
+
+use uuid::Uuid;
+
+use super::types::{PlaybackOrder, QueueItem, RepeatMode};
+
+/// Direction of a queue move.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueueStep {
+  Next,
+  Previous,
+  /// The current item finished on its own; `RepeatOne` replays it.
+  NaturalEnd,
+}
+
+/// Ordered list of items the playback target will play, with the current position.
+#[derive(Debug, Clone, Default)]
+pub struct PlaybackQueue {
+  items: Vec<QueueItem>,
+  current_index: Option<usize>,
+  next_playlist_item_id: u64,
+  repeat_mode: RepeatMode,
+  /// Unshuffled order, kept while shuffle is on so it can be restored.
+  sorted_items: Option<Vec<QueueItem>>,
+}
+
+impl PlaybackQueue {
+  /// Replace the queue and select the item at `start_index`.
+  pub fn replace(&mut self, item_ids: &[String], start_index: usize) -> Option<&QueueItem> {
+    let shuffle = self.playback_order();
+    self.items = self.new_items(item_ids);
+    self.current_index = None;
+    self.sorted_items = None;
+    if !self.items.is_empty() {
+      self.current_index = Some(start_index.min(self.items.len() - 1));
+    }
+
+    self.set_playback_order(shuffle);
+    self.current()
+  }
+
+  /// Insert items directly after the current item.
+  pub fn play_next(&mut self, item_ids: &[String]) {
+    let items = self.new_items(item_ids);
+    if let Some(sorted_items) = self.sorted_items.as_mut() {
+      let current_id = self
+        .current_index
+        .and_then(|index| self.items.get(index))
+        .map(|item| item.playlist_item_id.as_str());
+      let insert_at = current_id
+        .and_then(|id| {
+          sorted_items
+            .iter()
+            .position(|item| item.playlist_item_id == id)
+        })
+        .map(|index| index + 1)
+        .unwrap_or(0);
+      sorted_items.splice(insert_at..insert_at, items.iter().cloned());
+    }
+    let insert_at = self.current_index.map(|index| index + 1).unwrap_or(0);
+    self.items.splice(insert_at..insert_at, items);
+  }
+
+  /// Append items to the end of the queue.
+  pub fn play_last(&mut self, item_ids: &[String]) {
+    let items = self.new_items(item_ids);
+    if let Some(sorted_items) = self.sorted_items.as_mut() {
+      sorted_items.extend(items.iter().cloned());
+    }
+    self.items.extend(items);
+  }
+
+  /// Move through the queue, honoring the repeat mode.
+  pub fn step(&mut self, step: QueueStep) -> Option<&QueueItem> {
+    let current = self.current_index?;
+    let last = self.items.len().checked_sub(1)?;
+    let wrap = self.repeat_mode == RepeatMode::RepeatAll;
+
+    let target = match step {
+      QueueStep::NaturalEnd if self.repeat_mode == RepeatMode::RepeatOne => current,
+      QueueStep::Next | QueueStep::NaturalEnd if current < last => current + 1,
+      QueueStep::Next | QueueStep::NaturalEnd if wrap => 0,
+      QueueStep::Previous if current > 0 => current - 1,
+      QueueStep::Previous if wrap => last,
+      _ => return None,
+    };
+    self.current_index = Some(target);
+    self.current()
+  }
+
+  pub fn repeat_mode(&self) -> RepeatMode {
+    self.repeat_mode
+  }
+
+  pub fn set_repeat_mode(&mut self, repeat_mode: RepeatMode) {
+    self.repeat_mode = repeat_mode;
+  }
+
+  pub fn playback_order(&self) -> PlaybackOrder {
+    if self.sorted_items.is_some() {
+      PlaybackOrder::Shuffle
+    } else {
+      PlaybackOrder::Default
+    }
+  }
+
+  /// Shuffle keeps the current item first and randomizes the rest; sorting
+  /// restores the original order with the current item still selected.
+  pub fn set_playback_order(&mut self, order: PlaybackOrder) {
+    if order == self.playback_order() {
+      return;
+    }
+
+    let current_id = self.current().map(|item| item.playlist_item_id.clone());
+    match order {
+      PlaybackOrder::Shuffle => {
+        self.sorted_items = Some(self.items.clone());
+        let current = self.current_index.map(|index| self.items.remove(index));
+        self.items.sort_by_cached_key(|_| Uuid::new_v4());
+        if let Some(current) = current {
+          self.items.insert(0, current);
+          self.current_index = Some(0);
+        }
+      }
+      PlaybackOrder::Default => {
+        if let Some(sorted_items) = self.sorted_items.take() {
+          self.items = sorted_items;
+        }
+        self.current_index = current_id.and_then(|id| {
+          self
+            .items
+            .iter()
+            .position(|item| item.playlist_item_id == id)
+        });
+      }
+    }
+  }
+
+  pub fn current(&self) -> Option<&QueueItem> {
+    self.items.get(self.current_index?)
+  }
+
+  pub fn items(&self) -> &[QueueItem] {
+    &self.items
+  }
+
+  /// Drop all items; repeat and shuffle modes stay as the user set them.
+  pub fn clear(&mut self) {
+    self.items.clear();
+    self.current_index = None;
+    if self.sorted_items.is_some() {
+      self.sorted_items = Some(Vec::new());
+    }
+  }
+
+  fn new_items(&mut self, item_ids: &[String]) -> Vec<QueueItem> {
+    item_ids
+      .iter()
+      .map(|item_id| {
+        self.next_playlist_item_id += 1;
+        QueueItem {
+          id: item_id.clone(),
+          playlist_item_id: format!("playlistItem{}", self.next_playlist_item_id),
+        }
+      })
+      .collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn ids(ids: &[&str]) -> Vec<String> {
+    ids.iter().map(|id| id.to_string()).collect()
+  }
+
+  fn queued_ids(queue: &PlaybackQueue) -> Vec<&str> {
+    queue.items().iter().map(|item| item.id.as_str()).collect()
+  }
+
+  #[test]
+  fn replace_selects_start_index_and_assigns_unique_playlist_ids() {
+    let mut queue = PlaybackQueue::default();
+
+    let current = queue.replace(&ids(&["a", "b", "c"]), 1).cloned();
+
+    assert_eq!(current.map(|item| item.id), Some("b".to_string()));
+    let playlist_ids: Vec<&str> = queue
+      .items()
+      .iter()
+      .map(|item| item.playlist_item_id.as_str())
+      .collect();
+    assert_eq!(
+      playlist_ids,
+      vec!["playlistItem1", "playlistItem2", "playlistItem3"]
+    );
+  }
+
+  #[test]
+  fn replace_clamps_out_of_range_start_index() {
+    let mut queue = PlaybackQueue::default();
+
+    let current = queue.replace(&ids(&["a", "b"]), 9).cloned();
+
+    assert_eq!(current.map(|item| item.id), Some("b".to_string()));
+  }
+
+  #[test]
+  fn replace_with_no_items_clears_current() {
+    let mut queue = PlaybackQueue::default();
+    queue.replace(&ids(&["a"]), 0);
+
+    assert!(queue.replace(&[], 0).is_none());
+    assert!(queue.current().is_none());
+  }
+
+  #[test]
+  fn play_next_inserts_after_current_and_play_last_appends() {
+    let mut queue = PlaybackQueue::default();
+    queue.replace(&ids(&["a", "b"]), 0);
+
+    queue.play_next(&ids(&["x"]));
+    queue.play_last(&ids(&["z"]));
+
+    assert_eq!(queued_ids(&queue), vec!["a", "x", "b", "z"]);
+    assert_eq!(queue.current().map(|item| item.id.as_str()), Some("a"));
+  }
+
+  #[test]
+  fn steps_stop_at_queue_bounds_without_repeat() {
+    let mut queue = PlaybackQueue::default();
+    queue.replace(&ids(&["a", "b"]), 0);
+
+    assert!(queue.step(QueueStep::Previous).is_none());
+    assert_eq!(step_id(&mut queue, QueueStep::Next), Some("b"));
+    assert!(queue.step(QueueStep::NaturalEnd).is_none());
+    assert_eq!(queue.current().map(|item| item.id.as_str()), Some("b"));
+    assert_eq!(step_id(&mut queue, QueueStep::Previous), Some("a"));
+  }
+
+  #[test]
+  fn repeat_all_wraps_in_both_directions() {
+    let mut queue = PlaybackQueue::default();
+    queue.replace(&ids(&["a", "b"]), 1);
+    queue.set_repeat_mode(RepeatMode::RepeatAll);
+
+    assert_eq!(step_id(&mut queue, QueueStep::NaturalEnd), Some("a"));
+    assert_eq!(step_id(&mut queue, QueueStep::Previous), Some("b"));
+  }
+
+  #[test]
+  fn repeat_one_replays_on_natural_end_but_explicit_next_moves_on() {
+    let mut queue = PlaybackQueue::default();
+    queue.replace(&ids(&["a", "b"]), 0);
+    queue.set_repeat_mode(RepeatMode::RepeatOne);
+
+    assert_eq!(step_id(&mut queue, QueueStep::NaturalEnd), Some("a"));
+    assert_eq!(step_id(&mut queue, QueueStep::Next), Some("b"));
+  }
+
+  #[test]
+  fn shuffle_keeps_current_first_and_sorted_restores_original_order() {
+    let mut queue = PlaybackQueue::default();
+    queue.replace(&ids(&["a", "b", "c", "d"]), 2);
+
+    queue.set_playback_order(PlaybackOrder::Shuffle);
+
+    assert_eq!(queue.playback_order(), PlaybackOrder::Shuffle);
+    assert_eq!(queued_ids(&queue)[0], "c");
+    assert_eq!(queue.current().map(|item| item.id.as_str()), Some("c"));
+    let mut shuffled = queued_ids(&queue);
+    shuffled.sort();
+    assert_eq!(shuffled, vec!["a", "b", "c", "d"]);
+
+    queue.play_last(&ids(&["e"]));
+    queue.set_playback_order(PlaybackOrder::Default);
+
+    assert_eq!(queued_ids(&queue), vec!["a", "b", "c", "d", "e"]);
+    assert_eq!(queue.current().map(|item| item.id.as_str()), Some("c"));
+  }
+
+  fn step_id(queue: &mut PlaybackQueue, step: QueueStep) -> Option<&str> {
+    queue.step(step).map(|item| item.id.as_str())
+  }
+}
@@ -0,0 +1,150 @@
+//! Short-lived cache for idempotent Jellyfin GET responses.
+//!
+//! Next/previous navigation fetches the same item and episode list several
+//! times in a row. Responses are kept as raw JSON for a few minutes and
+//! dropped wholesale when the server reports changed user data.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use serde_json::Value;
+
+use crate::retention::BufferUsage;
+
+/// How long a cached response is served.
+pub const RESPONSE_CACHE_TTL: Duration = Duration::from_secs(300);
+/// Upper bound on cached responses; the oldest entry is evicted beyond it.
+const RESPONSE_CACHE_CAPACITY: usize = 128;
+
+#[derive(Debug)]
+struct CachedResponse {
+  stored_at: Instant,
+  body: Value,
+}
+
+/// Cached GET response bodies keyed by request URL.
+#[derive(Debug, Default)]
+pub struct ResponseCache {
+  entries: HashMap<String, CachedResponse>,
+}
+
+impl ResponseCache {
+  /// Fresh body for `key`, if any.
+  pub fn get(&self, key: &str, now: Instant) -> Option<Value> {
+    self
+      .entries
+      .get(key)
+      .filter(|entry| now.duration_since(entry.stored_at) < RESPONSE_CACHE_TTL)
+      .map(|entry| entry.body.clone())
+  }
+
+  pub fn insert(&mut self, key: String, body: Value, now: Instant) {
+    self
+      .entries
+      .retain(|_, entry| now.duration_since(entry.stored_at) < RESPONSE_CACHE_TTL);
+    if self.entries.len() >= RESPONSE_CACHE_CAPACITY && !self.entries.contains_key(&key) {
+      let oldest = self
+        .entries
+        .iter()
+        .min_by_key(|(_, entry)| entry.stored_at)
+        .map(|(key, _)| key.clone());
+      if let Some(oldest) = oldest {
+        self.entries.remove(&oldest);
+      }
+    }
+    self.entries.insert(
+      key,
+      CachedResponse {
+        stored_at: now,
+        body,
+      },
+    );
+  }
+
+  /// Drop the cached response for `key`.
+  pub fn remove(&mut self, key: &str) {
+    self.entries.remove(key);
+  }
+
+  /// Drop every cached response.
+  pub fn clear(&mut self) {
+    self.entries.clear();
+  }
+
+  pub fn usage(&self) -> BufferUsage {
+    let bytes: usize = self
+      .entries
+      .iter()
+      .map(|(key, entry)| key.len() + entry.body.to_string().len())
+      .sum();
+    BufferUsage::new(
+      "Jellyfin response cache",
+      self.entries.len(),
+      RESPONSE_CACHE_CAPACITY,
+      bytes as u64,
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn entries_expire_after_the_ttl() {
+    let now = Instant::now();
+    let mut cache = ResponseCache::default();
+
+    cache.insert("/Items/1".to_string(), serde_json::json!({"Id": "1"}), now);
+
+    assert_eq!(
+      cache.get("/Items/1", now + Duration::from_secs(10)),
+      Some(serde_json::json!({"Id": "1"}))
+    );
+    assert_eq!(cache.get("/Items/1", now + RESPONSE_CACHE_TTL), None);
+    assert_eq!(cache.get("/Items/2", now), None);
+  }
+
+  #[test]
+  fn oldest_entry_is_evicted_at_capacity() {
+    let now = Instant::now();
+    let mut cache = ResponseCache::default();
+
+    for index in 0..=RESPONSE_CACHE_CAPACITY {
+      cache.insert(
+        format!("/Items/{}", index),
+        Value::Null,
+        now + Duration::from_millis(index as u64),
+      );
+    }
+
+    let later = now + Duration::from_secs(1);
+    assert_eq!(cache.entries.len(), RESPONSE_CACHE_CAPACITY);
+    assert!(cache.get("/Items/0", later).is_none());
+    assert!(cache.get("/Items/1", later).is_some());
+  }
+
+  #[test]
+  fn clear_drops_everything() {
+    let now = Instant::now();
+    let mut cache = ResponseCache::default();
+    cache.insert("/Items/1".to_string(), Value::Null, now);
+
+    cache.clear();
+
+    assert!(cache.get("/Items/1", now).is_none());
+  }
+
+  #[test]
+  fn remove_drops_only_that_entry() {
+    let now = Instant::now();
+    let mut cache = ResponseCache::default();
+    cache.insert("/Items/1".to_string(), Value::Null, now);
+    cache.insert("/Items/2".to_string(), Value::Null, now);
+
+    cache.remove("/Items/1");
+
+    assert!(cache.get("/Items/1", now).is_none());
+    assert!(cache.get("/Items/2", now).is_some());
+  }
+}
@@ -0,0 +1,82 @@
+//! Going back a few seconds when playback picks up again.
+//!
+//! After a long pause, or when a saved position is resumed, the viewer has
+//! lost the thread of the scene. With `resume_rewind_seconds` set, playback
+//! goes back that far first. Pauses shorter than
+//! `resume_rewind_after_pause_seconds` are left alone, so toggling pause
+//! doesn't keep stepping backwards.
+
+use std::time::Duration;
+
+use crate::config::AppConfig;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ResumeRewind {
+  seconds: f64,
+  after_pause: Duration,
+}
+
+impl ResumeRewind {
+  /// The configured rewind, or None when it is turned off.
+  pub fn from_config(config: &AppConfig) -> Option<Self> {
+    let seconds = config
+      .resume_rewind_seconds
+      .filter(|seconds| *seconds > 0)?;
+    Some(Self {
+      seconds: f64::from(seconds),
+      after_pause: Duration::from_secs(u64::from(config.resume_rewind_after_pause_seconds)),
+    })
+  }
+
+  /// Where to start an item saved at `position`; playing from the beginning
+  /// stays there.
+  pub fn start_position(&self, position: f64) -> f64 {
+    if position <= 0.0 {
+      return position;
+    }
+    (position - self.seconds).max(0.0)
+  }
+
+  /// Where to seek when playback at `position` resumes after being paused
+  /// for `paused_for`, if the pause was long enough.
+  pub fn after_pause(&self, paused_for: Duration, position: f64) -> Option<f64> {
+    (paused_for >= self.after_pause && position > 0.0).then(|| (position - self.seconds).max(0.0))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn rewind(seconds: Option<u32>, after_pause_seconds: u32) -> Option<ResumeRewind> {
+    ResumeRewind::from_config(&AppConfig {
+      resume_rewind_seconds: seconds,
+      resume_rewind_after_pause_seconds: after_pause_seconds,
+      ..Default::default()
+    })
+  }
+
+  #[test]
+  fn saved_positions_start_a_little_earlier() {
+    assert_eq!(rewind(None, 60), None);
+    assert_eq!(rewind(Some(0), 60), None);
+
+    let rewind = rewind(Some(5), 60).unwrap();
+    assert_eq!(rewind.start_position(120.0), 115.0);
+    assert_eq!(rewind.start_position(3.0), 0.0);
+    assert_eq!(rewind.start_position(0.0), 0.0);
+  }
+
+  #[test]
+  fn only_long_pauses_rewind() {
+    let rewind = rewind(Some(5), 60).unwrap();
+
+    assert_eq!(rewind.after_pause(Duration::from_secs(59), 300.0), None);
+    assert_eq!(
+      rewind.after_pause(Duration::from_secs(60), 300.0),
+      Some(295.0)
+    );
+    assert_eq!(rewind.after_pause(Duration::from_secs(600), 2.0), Some(0.0));
+    assert_eq!(rewind.after_pause(Duration::from_secs(600), 0.0), None);
+  }
+}
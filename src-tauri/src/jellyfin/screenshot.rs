@@ -0,0 +1,141 @@
+//! Screenshots named after what is playing.
+//!
+//! MPV names its screenshots `mpv-shot0001.png`, which says nothing once the
+//! stream is gone. These are named after the Jellyfin item instead, e.g.
+//! `Show - S01E02 - Pilot - 00-12-34.png` for an episode at 12:34.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use super::types::MediaItem;
+
+/// What a screenshot shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum ScreenshotMode {
+  /// The video with subtitles, at the video's resolution.
+  Subtitles,
+  /// The video alone.
+  Video,
+  /// The window as shown, with the OSD and at the window's size.
+  Window,
+}
+
+impl ScreenshotMode {
+  /// The flag MPV's `screenshot-to-file` takes for this mode.
+  pub fn mpv_flag(self) -> &'static str {
+    match self {
+      Self::Subtitles => "subtitles",
+      Self::Video => "video",
+      Self::Window => "window",
+    }
+  }
+}
+
+/// File name for a screenshot of `item` at `position` seconds.
+pub fn file_name(item: Option<&MediaItem>, position: f64) -> String {
+  let mut parts = Vec::new();
+  if let Some(item) = item {
+    if let Some(series) = item.series_name.as_deref().filter(|name| !name.is_empty()) {
+      parts.push(series.to_string());
+    }
+    if let (Some(season), Some(episode)) = (item.parent_index_number, item.index_number) {
+      parts.push(format!("S{:02}E{:02}", season, episode));
+    }
+    if !item.name.is_empty() {
+      parts.push(item.name.clone());
+    }
+  }
+  if parts.is_empty() {
+    parts.push("JellyPilot".to_string());
+  }
+  let total = position.max(0.0) as u64;
+  parts.push(format!(
+    "{:02}-{:02}-{:02}",
+    total / 3600,
+    total / 60 % 60,
+    total % 60
+  ));
+  format!("{}.png", sanitize(&parts.join(" - ")))
+}
+
+/// `name` in `dir`, numbered when a screenshot of that moment already exists.
+pub fn unused_path(dir: &Path, name: &str) -> PathBuf {
+  let path = dir.join(name);
+  if !path.exists() {
+    return path;
+  }
+  let stem = name.strip_suffix(".png").unwrap_or(name);
+  (2..)
+    .map(|n| dir.join(format!("{} ({}).png", stem, n)))
+    .find(|path| !path.exists())
+    .unwrap_or(path)
+}
+
+/// Replace characters Windows or macOS don't allow in file names.
+fn sanitize(name: &str) -> String {
+  name
+    .chars()
+    .map(|c| match c {
+      '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*' => '_',
+      c if c.is_control() => '_',
+      c => c,
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn item(json: serde_json::Value) -> MediaItem {
+    serde_json::from_value(json).unwrap()
+  }
+
+  #[test]
+  fn names_carry_the_series_episode_and_position() {
+    let episode = item(serde_json::json!({
+      "Id": "episode-1",
+      "Name": "Pilot: Part 1",
+      "Type": "Episode",
+      "SeriesName": "Show",
+      "ParentIndexNumber": 1,
+      "IndexNumber": 2,
+    }));
+    assert_eq!(
+      file_name(Some(&episode), 754.6),
+      "Show - S01E02 - Pilot_ Part 1 - 00-12-34.png"
+    );
+
+    let movie = item(serde_json::json!({
+      "Id": "movie-1",
+      "Name": "AC/DC Live",
+      "Type": "Movie",
+    }));
+    assert_eq!(file_name(Some(&movie), 3725.0), "AC_DC Live - 01-02-05.png");
+    assert_eq!(file_name(None, 0.0), "JellyPilot - 00-00-00.png");
+  }
+
+  #[test]
+  fn existing_screenshots_are_not_overwritten() {
+    let dir = std::env::temp_dir().join(format!(
+      "jellypilot-screenshot-test-{}",
+      uuid::Uuid::new_v4()
+    ));
+    std::fs::create_dir_all(&dir).unwrap();
+    assert_eq!(
+      unused_path(&dir, "Show - 00-00-01.png"),
+      dir.join("Show - 00-00-01.png")
+    );
+
+    std::fs::write(dir.join("Show - 00-00-01.png"), b"").unwrap();
+    std::fs::write(dir.join("Show - 00-00-01 (2).png"), b"").unwrap();
+    assert_eq!(
+      unused_path(&dir, "Show - 00-00-01.png"),
+      dir.join("Show - 00-00-01 (3).png")
+    );
+    std::fs::remove_dir_all(&dir).unwrap();
+  }
+}
@@ -1,34 +1,92 @@
 //! Session manager - coordinates Jellyfin commands with MPV player.
 
 use parking_lot::RwLock;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
 use tauri::AppHandle;
 use tauri_plugin_store::StoreExt;
 use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
 
+use super::action_watchdog::{self, ActionLimits, ActionOutcome, ACTION_QUEUE_CAPACITY};
+use super::adopted_playback::StoredPlayback;
+use super::bandwidth::BandwidthMeter;
+use super::chapters::{merge_chapters, PlaybackChapter};
 use super::client::JellyfinClient;
-use super::error::JellyfinError;
-use super::intro_skipper::{
-  evaluate_manual_skip, evaluate_skip, evaluate_skip_prompt, IntroSkipKind,
-};
+use super::credits_fallback::credits_fallback_range;
+use super::device_conflict::DeviceEndpoint;
+use super::error::{JellyfinError, PlaybackErrorStage};
+use super::marked_skip::{self, marked_skip_key, marked_skip_ranges, MarkedSkipRange};
+use super::media_version::select_media_source;
 use super::mpv_event::{
-  apply_property_update, client_message_direction, is_natural_end, property_report_decision,
-  should_report_progress, PropertyReportDecision,
+  apply_property_update, auto_next_allowed, client_message_direction, end_of_file,
+  is_duration_mismatch, is_natural_end, property_report_decision, should_report_progress, AbLoop,
+  CountdownStep, CreditsCountdown, EndOfFile, PropertyReportDecision, SeekCoalescer,
+  SeekReportStep,
 };
 use super::play_resolution::{
-  jellyfin_to_mpv_track_index, resolve_play_request, PlayResolutionConfig,
+  image_subtitle, jellyfin_to_mpv_track_index, play_method, resolve_play_request, ImageSubtitle,
+  PlayResolutionConfig,
+};
+use super::preference_scope::{preference_target, scope_of_key, PreferenceTarget};
+use super::queue::{PlaybackQueue, QueueStep};
+use super::resume_rewind::ResumeRewind;
+use super::screenshot::{self, ScreenshotMode};
+use super::session_files::{self, SessionFiles};
+use super::session_phase::SessionPhase;
+use super::skip_range::{
+  evaluate_manual_skip, evaluate_skip_ranges, SkipAction, SkipKind, SkipPolicies, SkipPolicy,
 };
+use super::stall::{self, StallTracker};
+use super::subtitle_tracks::{SubtitleSelection, SubtitleTracks};
 use super::types::*;
-use super::websocket::{JellyfinCommand, JellyfinWebSocket, JellyfinWebSocketEvent};
-use crate::command::{AppNotification, NowPlayingChanged};
-use crate::config::{AppConfig, IntroSkipperMode};
-use crate::mpv::MpvClient;
+use super::volume::{VolumeMapping, VOLUME_STEP};
+use super::websocket::{
+  JellyfinCommand, JellyfinWebSocket, JellyfinWebSocketEvent, ParseFailure, ReconnectAttempt,
+};
+use crate::command::{
+  notify_removed_mpv_args, AppNotification, BandwidthUpdated, ConnectionEvent, NowPlayingChanged,
+  NowPlayingUserData, PlaybackError, SessionExpired,
+};
+use crate::config::{
+  AppConfig, AutoNextScope, CastTakeover, IntroSkipperMode, MpvStopBehavior, TrackPreferenceScope,
+};
+use crate::i18n::tr;
+use crate::metrics::{self, Counter};
+use crate::mpv::{Chapter, ChapterList, EventQueue, MpvClient};
 use crate::now_playing::{build_now_playing_state, collect_player_state, PlaybackContext};
+use crate::os_notification::{self, OsNotificationKind};
+use crate::redact::redact;
+use crate::webhooks::{self, WebhookEvent};
 use tauri_specta::Event;
 
 const PREFERENCES_STORE_FILE: &str = "preferences.json";
 const SERIES_PREFERENCES_KEY: &str = "series_track_preferences";
+const MARKED_SKIP_RANGES_KEY: &str = "series_skip_ranges";
+const LOADED_PLAYBACK_KEY: &str = "loaded_playback";
+/// How often an in-progress recording is re-fetched for its status and runtime.
+const RECORDING_REFRESH_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+/// Waits before each repeated cast registration; some servers list a new
+/// session only after a moment.
+#[cfg(not(test))]
+const CAST_REGISTRATION_RETRY_DELAYS: [std::time::Duration; 4] = [
+  std::time::Duration::from_secs(1),
+  std::time::Duration::from_secs(2),
+  std::time::Duration::from_secs(5),
+  std::time::Duration::from_secs(10),
+];
+#[cfg(test)]
+const CAST_REGISTRATION_RETRY_DELAYS: [std::time::Duration; 4] = [std::time::Duration::ZERO; 4];
+/// How often the session's pause state is compared with MPV's.
+const PAUSE_DRIFT_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
+/// How often the server's sessions are checked for another install with
+/// this device ID.
+const DEVICE_CONFLICT_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5 * 60);
+/// Playback speed change per speed shortcut press.
+const SPEED_STEP: f64 = 0.25;
+/// Slowest and fastest playback speed the speed shortcuts reach.
+const MIN_SPEED: f64 = 0.25;
+const MAX_SPEED: f64 = 4.0;
 
 /// Actions to perform on MPV.
 #[derive(Debug, Clone)]
@@ -40,51 +98,338 @@ pub enum MpvAction {
     title: String,
     audio_index: Option<i32>,
     subtitle_index: Option<i32>,
+    /// Folder of embedded fonts the stream leaves out, for styled subtitles.
+    fonts_dir: Option<std::path::PathBuf>,
   },
   /// Add an external subtitle file.
   AddExternalSubtitle(String),
-  /// Pause playback.
-  Pause,
-  /// Resume playback.
-  Resume,
   /// Seek to position (seconds).
   Seek(f64),
   /// Show text on MPV's on-screen display.
   ShowText { text: String, duration_ms: i64 },
   /// Stop playback.
   Stop,
-  /// Set volume (0-100).
+  /// Set remote volume (0-100), mapped onto MPV through the volume cap and curve.
   SetVolume(i32),
   /// Toggle mute.
   ToggleMute,
   /// Toggle fullscreen.
   ToggleFullscreen,
+  /// Enter or leave fullscreen.
+  SetFullscreen(bool),
   /// Set audio track by stream index.
   SetAudioTrack(i32),
   /// Set subtitle track by stream index (-1 to disable).
   SetSubtitleTrack(i32),
+  /// Set the secondary subtitle track by MPV track id (-1 to disable).
+  SetSecondarySubtitleTrack(i32),
+  /// Add an external subtitle file without selecting it and show it as the
+  /// secondary subtitle, which MPV will give `track_id`.
+  AddSecondarySubtitle { url: String, track_id: i32 },
+  /// Set or clear MPV's A-B loop points.
+  SetAbLoop(AbLoop),
+  /// Save a screenshot to `path` and confirm it on the OSD.
+  Screenshot {
+    path: std::path::PathBuf,
+    mode: ScreenshotMode,
+  },
+  /// Step the playback speed up or down.
+  StepSpeed { faster: bool },
+  /// Toggle MPV's stats overlay.
+  ToggleStats,
+  /// Stop playback and leave MPV idle with its window open.
+  StopIdle,
+}
+
+impl MpvAction {
+  /// True for the actions that end playback.
+  pub fn is_stop(&self) -> bool {
+    matches!(self, Self::Stop | Self::StopIdle)
+  }
+}
+
+/// Seek destination requested from the JellyPilot window.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SeekTarget {
+  /// Absolute position in seconds.
+  Seconds(f64),
+  /// Percentage (0-100) of the current item's runtime.
+  Percent(f64),
+}
+
+/// Item user data the player shortcuts and tray flip.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum UserDataToggle {
+  Watched,
+  Favorite,
+}
+
+impl UserDataToggle {
+  fn label(self) -> &'static str {
+    match self {
+      Self::Watched => "watched state",
+      Self::Favorite => "favorite state",
+    }
+  }
+
+  /// OSD text once the item is (`set`) or is no longer watched or a favorite.
+  fn confirmation(self, set: bool) -> String {
+    match (self, set) {
+      (Self::Watched, true) => tr!("osd-marked-watched"),
+      (Self::Watched, false) => tr!("osd-marked-unwatched"),
+      (Self::Favorite, true) => tr!("osd-favorited"),
+      (Self::Favorite, false) => tr!("osd-unfavorited"),
+    }
+  }
+
+  fn failure(self) -> String {
+    match self {
+      Self::Watched => tr!("osd-watched-toggle-failed"),
+      Self::Favorite => tr!("osd-favorite-toggle-failed"),
+    }
+  }
 }
 
+/// Where a Play request came from, for fullscreen-on-start decisions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PlayOrigin {
+  /// Cast from another client, such as a phone.
+  RemoteCast,
+  /// Started from JellyPilot's own library browser or tray.
+  Local,
+  /// Next/previous item while already playing; keeps the window as it is.
+  Continuation,
+}
+
+/// What asked for the next or previous episode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AdjacentTrigger {
+  /// Explicit next/previous from a remote, shortcut, tray or the UI.
+  User,
+  /// The current item finished; subject to the auto-next settings.
+  NaturalEnd,
+  /// The credits of the still-playing item began; subject to the auto-next settings.
+  Credits,
+}
+
+/// Identifies one playback, so writes made after an await can tell whether
+/// the playback they started on is still the current one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(super) struct PlaybackGeneration(u64);
+
 /// Session manager state.
-struct SessionState {
-  playback: Option<PlaybackSession>,
+///
+/// Guards are never held across an await. Flows that read state, await and
+/// then write it back capture the `playback_generation` first and drop the
+/// write when a new playback began in between, rather than clobbering it.
+pub(super) struct SessionState {
+  pub(super) playback: Option<PlaybackSession>,
+  /// Advanced whenever a play starts loading.
+  pub(super) playback_generation: PlaybackGeneration,
   last_report_time: std::time::Instant,
   /// Intro Skipper settings captured when the current MPV process started.
   effective_intro_skipper_config: IntroSkipperRuntimeConfig,
-  /// Current series ID being played (for track preference saving).
-  current_series_id: Option<String>,
+  /// Where track changes for the current item are remembered.
+  preference_target: Option<PreferenceTarget>,
   /// Current item being played (for next episode lookup).
-  current_item: Option<MediaItem>,
+  pub(super) current_item: Option<MediaItem>,
   /// Current media streams (for looking up track languages).
   current_media_streams: Vec<MediaStream>,
-  /// Track preferences per series (key: series_id).
-  series_preferences: HashMap<String, TrackPreference>,
+  /// Track preferences keyed by series id, library key or the global key.
+  track_preferences: HashMap<String, TrackPreference>,
+  /// Items queued by multi-item Play requests.
+  queue: PlaybackQueue,
+  /// The next Play was started by the previous item ending, not by a request.
+  auto_advanced: bool,
+  /// User skip ranges keyed by series id, or item id outside a series.
+  pub(super) marked_skip_ranges: HashMap<String, Vec<MarkedSkipRange>>,
+  /// Position of a skip start marked in the current item, waiting for its end.
+  pub(super) skip_mark_start: Option<f64>,
+  /// Running countdown to the next item over the current item's credits.
+  credits_countdown: Option<CreditsCountdown>,
+  /// Buffering stalls and bitrate cap of the current playback.
+  pub(super) stall: StallTracker,
+  /// External subtitles added to the file MPV plays.
+  subtitle_tracks: SubtitleTracks,
+  /// Files downloaded for the current playback.
+  pub(super) session_files: SessionFiles,
+  /// Watched, favorite and rating state of the current item, once fetched.
+  user_data: Option<NowPlayingUserData>,
+  /// When MPV paused the current playback, to rewind after a long pause.
+  paused_at: Option<std::time::Instant>,
+  /// MPV's A-B loop points, as it last reported them.
+  ab_loop: AbLoop,
+  /// Times the current item's transcode was restarted after ending early.
+  transcode_restarts: u32,
+  /// The last playback that ended well before the end of its item, to retry.
+  early_end: Option<EarlyEnd>,
+  /// What the session is doing; only changed through `enter`.
+  pub(super) phase: SessionPhase,
+}
+
+/// Where a playback that ended early stopped.
+#[derive(Debug, Clone, PartialEq)]
+struct EarlyEnd {
+  item_id: String,
+  position_ticks: i64,
+  /// Runtime of the item, or MPV's duration when the server has none.
+  length_ticks: i64,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct IntroSkipperRuntimeConfig {
   mode: IntroSkipperMode,
   keybind_intro_skip: String,
+  auto_next_at_credits: bool,
+}
+
+impl SessionState {
+  /// Start a new playback generation; in-flight writes for the previous one are dropped.
+  fn begin_playback(&mut self) -> PlaybackGeneration {
+    self.playback_generation = PlaybackGeneration(self.playback_generation.0 + 1);
+    self.playback_generation
+  }
+
+  /// The current playback, if it still belongs to `generation`.
+  fn playback_of(&mut self, generation: PlaybackGeneration) -> Option<&mut PlaybackSession> {
+    if self.playback_generation != generation {
+      return None;
+    }
+    self.playback.as_mut()
+  }
+
+  /// The current playback, if it still belongs to `generation`, for reading.
+  fn playback_in(&self, generation: PlaybackGeneration) -> Option<&PlaybackSession> {
+    if self.playback_generation != generation {
+      return None;
+    }
+    self.playback.as_ref()
+  }
+
+  /// Select audio stream `index` in the playback of `generation` and remember
+  /// its language for the current scope. Returns whether a preference
+  /// changed, or None when a newer playback began.
+  fn remember_audio_track(&mut self, generation: PlaybackGeneration, index: i32) -> Option<bool> {
+    if self.playback_generation != generation {
+      return None;
+    }
+    if let Some(playback) = self.playback.as_mut() {
+      playback.audio_stream_index = Some(index);
+    }
+    let Some(target) = self.preference_target.clone() else {
+      return Some(false);
+    };
+    let track_info = self
+      .current_media_streams
+      .iter()
+      .find(|stream| stream.stream_type == "Audio" && stream.index == index)
+      .map(|stream| (stream.language.clone(), stream.display_title.clone()));
+    let Some((lang, title)) = track_info else {
+      return Some(false);
+    };
+    log::info!(
+      "Saving audio preference for {}: lang={:?}, title={:?}",
+      target.key,
+      lang,
+      title
+    );
+    let pref = self.track_preferences.entry(target.key).or_default();
+    pref.label = target.label;
+    pref.audio_language = lang;
+    pref.audio_title = title;
+    Some(true)
+  }
+
+  /// Select subtitle stream `index` (-1 = off) in the playback of `generation`
+  /// and remember the choice for the current scope. Returns whether a
+  /// preference changed, or None when a newer playback began.
+  fn remember_subtitle_track(
+    &mut self,
+    generation: PlaybackGeneration,
+    index: i32,
+  ) -> Option<bool> {
+    if self.playback_generation != generation {
+      return None;
+    }
+    if let Some(playback) = self.playback.as_mut() {
+      playback.subtitle_stream_index = Some(index);
+    }
+    let Some(target) = self.preference_target.clone() else {
+      return Some(false);
+    };
+    if index == -1 {
+      log::info!("Saving subtitle disabled preference for {}", target.key);
+      let pref = self.track_preferences.entry(target.key).or_default();
+      pref.label = target.label;
+      pref.is_subtitle_enabled = false;
+      pref.subtitle_preference_set = true;
+      pref.subtitle_language = None;
+      pref.subtitle_title = None;
+      return Some(true);
+    }
+    let track_info = self
+      .current_media_streams
+      .iter()
+      .find(|stream| stream.stream_type == "Subtitle" && stream.index == index)
+      .map(|stream| (stream.language.clone(), stream.display_title.clone()));
+    let pref = self
+      .track_preferences
+      .entry(target.key.clone())
+      .or_default();
+    pref.label = target.label;
+    pref.is_subtitle_enabled = true;
+    pref.subtitle_preference_set = true;
+    if let Some((lang, title)) = track_info {
+      log::info!(
+        "Saving subtitle preference for {}: lang={:?}, title={:?}",
+        target.key,
+        lang,
+        title
+      );
+      pref.subtitle_language = lang;
+      pref.subtitle_title = title;
+    }
+    Some(true)
+  }
+
+  /// Move to the `next` phase, unless the current one can't lead there.
+  /// Returns whether the session is now in `next`.
+  pub(super) fn enter(&mut self, next: SessionPhase) -> bool {
+    if self.phase == next {
+      return true;
+    }
+    if !self.phase.can_become(next) {
+      log::warn!(
+        "Ignoring illegal session phase change {:?} -> {:?}",
+        self.phase,
+        next
+      );
+      return false;
+    }
+    log::debug!("Session phase {:?} -> {:?}", self.phase, next);
+    self.phase = next;
+    true
+  }
+
+  /// MPV was told to stop; only a file that is open or loading waits for it
+  /// to confirm.
+  fn stopping(&mut self) {
+    if !matches!(self.phase, SessionPhase::Idle | SessionPhase::Disconnected) {
+      self.enter(SessionPhase::Stopping);
+    }
+  }
+
+  /// Playing or paused, as the current playback last reported.
+  pub(super) fn open_phase(&self) -> SessionPhase {
+    SessionPhase::open(self.playback.as_ref().is_some_and(|p| p.is_paused))
+  }
+
+  /// Nothing is open in MPV any more; a stopped session stays disconnected.
+  fn playback_ended(&mut self) {
+    if self.phase != SessionPhase::Disconnected {
+      self.enter(SessionPhase::Idle);
+    }
+  }
 }
 
 impl From<&AppConfig> for IntroSkipperRuntimeConfig {
@@ -92,6 +437,180 @@ impl From<&AppConfig> for IntroSkipperRuntimeConfig {
     Self {
       mode: config.intro_skipper_mode,
       keybind_intro_skip: config.keybind_intro_skip.clone(),
+      auto_next_at_credits: config.auto_next_at_credits,
+    }
+  }
+}
+
+impl IntroSkipperRuntimeConfig {
+  /// Skip policy per range kind for the configured mode.
+  fn policies(&self) -> SkipPolicies {
+    let mut policies = SkipPolicies::uniform(match self.mode {
+      IntroSkipperMode::Automatic => SkipPolicy::Skip,
+      IntroSkipperMode::Manual => SkipPolicy::Prompt,
+      IntroSkipperMode::Off => SkipPolicy::Ignore,
+    });
+    if self.auto_next_at_credits {
+      policies.credits = SkipPolicy::PlayNext;
+    }
+    policies
+  }
+}
+
+/// Properties observed on every MPV connection.
+const OBSERVED_PROPERTIES: [&str; 10] = [
+  "pause",
+  "volume",
+  "mute",
+  "time-pos",
+  "duration",
+  "seekable",
+  "cache-speed",
+  "paused-for-cache",
+  "ab-loop-a",
+  "ab-loop-b",
+];
+/// Restarts of a transcode that keeps ending early before playback stops.
+const MAX_TRANSCODE_RESTARTS: u32 = 3;
+/// Minimum time between throttled progress reports.
+const PROGRESS_REPORT_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Registers and releases property observers on an MPV connection.
+trait PropertyObserver {
+  async fn observe_property(&self, property: &str) -> Result<(), String>;
+  async fn unobserve_property(&self, property: &str) -> Result<(), String>;
+}
+
+impl PropertyObserver for MpvClient {
+  async fn observe_property(&self, property: &str) -> Result<(), String> {
+    MpvClient::observe_property(self, property)
+      .await
+      .map_err(|e| e.to_string())
+  }
+
+  async fn unobserve_property(&self, property: &str) -> Result<(), String> {
+    MpvClient::unobserve_property(self, property)
+      .await
+      .map_err(|e| e.to_string())
+  }
+}
+
+/// Pause state of the MPV playing the session.
+trait PauseControl {
+  async fn get_pause(&self) -> Result<bool, String>;
+  async fn set_pause(&self, paused: bool) -> Result<(), String>;
+}
+
+impl PauseControl for MpvClient {
+  async fn get_pause(&self) -> Result<bool, String> {
+    MpvClient::get_pause(self).await.map_err(|e| e.to_string())
+  }
+
+  async fn set_pause(&self, paused: bool) -> Result<(), String> {
+    MpvClient::set_pause(self, paused)
+      .await
+      .map_err(|e| e.to_string())
+  }
+}
+
+/// Event listener state for one MPV connection.
+///
+/// MPV can restart between items while the playback it serves lives on in
+/// `SessionState`, so each connection registers its observers again and
+/// takes its progress throttle from the session instead of starting over.
+struct MpvConnection {
+  last_recording_refresh: std::time::Instant,
+  seeks: SeekCoalescer,
+  /// Properties this listener observes, released when it stops.
+  observed: Vec<&'static str>,
+}
+
+impl MpvConnection {
+  async fn open(observer: &impl PropertyObserver, state: &RwLock<SessionState>) -> Self {
+    let mut observed = Vec::with_capacity(OBSERVED_PROPERTIES.len());
+    for property in OBSERVED_PROPERTIES {
+      match observer.observe_property(property).await {
+        Ok(()) => observed.push(property),
+        Err(e) => log::warn!("Failed to observe {}: {}", property, e),
+      }
+    }
+    if let Some(playback) = state.read().playback.as_ref() {
+      log::info!(
+        "MPV connected during playback of {} with {} skip ranges",
+        playback.item_id,
+        playback.skip_ranges.len()
+      );
+    }
+    log::info!("Property observations set up, listening for events...");
+
+    Self {
+      last_recording_refresh: std::time::Instant::now(),
+      seeks: SeekCoalescer::default(),
+      observed,
+    }
+  }
+
+  /// Release the observations of a listener that stops while MPV keeps
+  /// running, so the next listener does not get its events twice.
+  async fn close(self, observer: &impl PropertyObserver) {
+    for property in self.observed {
+      if let Err(e) = observer.unobserve_property(property).await {
+        log::warn!("Failed to unobserve {}: {}", property, e);
+      }
+    }
+  }
+
+  /// Hold back position reports while a seek settles, then report the
+  /// settled position right away.
+  fn seek_report_decision(
+    &mut self,
+    state: &RwLock<SessionState>,
+    event: &crate::mpv::MpvEvent,
+    decision: PropertyReportDecision,
+  ) -> PropertyReportDecision {
+    let Some(position) = event.data.as_ref().and_then(|data| data.as_f64()) else {
+      return decision;
+    };
+    let ab_loop = state.read().ab_loop;
+    match self
+      .seeks
+      .position(position, &ab_loop, std::time::Instant::now())
+    {
+      SeekReportStep::Throttle => decision,
+      SeekReportStep::Hold => PropertyReportDecision::Ignore,
+      SeekReportStep::Settled => PropertyReportDecision::ReportNow,
+    }
+  }
+
+  /// Whether a property change should be reported now; throttled reports
+  /// restart the session's report clock.
+  fn should_report(&self, state: &RwLock<SessionState>, decision: PropertyReportDecision) -> bool {
+    let mut s = state.write();
+    let now = std::time::Instant::now();
+    let should_report =
+      should_report_progress(decision, now, s.last_report_time, PROGRESS_REPORT_INTERVAL);
+    if should_report && decision == PropertyReportDecision::ReportWhenThrottleElapsed {
+      s.last_report_time = now;
+    }
+    should_report
+  }
+}
+
+/// Queue fields for playback start and progress reports.
+struct QueueReport {
+  now_playing_queue: Vec<QueueItem>,
+  playlist_item_id: Option<String>,
+  repeat_mode: RepeatMode,
+  playback_order: PlaybackOrder,
+}
+
+impl From<&PlaybackQueue> for QueueReport {
+  fn from(queue: &PlaybackQueue) -> Self {
+    Self {
+      now_playing_queue: queue.items().to_vec(),
+      playlist_item_id: queue.current().map(|item| item.playlist_item_id.clone()),
+      repeat_mode: queue.repeat_mode(),
+      playback_order: queue.playback_order(),
     }
   }
 }
@@ -102,10 +621,16 @@ pub struct SessionManager {
   websocket: Arc<JellyfinWebSocket>,
   mpv: Arc<MpvClient>,
   config: Arc<RwLock<AppConfig>>,
+  /// Download totals, shared with the app so they outlive the session.
+  bandwidth: Arc<RwLock<BandwidthMeter>>,
   app_handle: AppHandle,
   state: Arc<RwLock<SessionState>>,
   action_tx: mpsc::Sender<MpvAction>,
   action_rx: Arc<RwLock<Option<mpsc::Receiver<MpvAction>>>>,
+  /// Where the server sees this device, to notice a second install with its ID.
+  device_endpoint: Arc<RwLock<DeviceEndpoint>>,
+  /// Cancelled when the session stops, ending its MPV event listener.
+  shutdown: CancellationToken,
 }
 
 impl SessionManager {
@@ -114,53 +639,94 @@ impl SessionManager {
     client: Arc<JellyfinClient>,
     mpv: Arc<MpvClient>,
     config: Arc<RwLock<AppConfig>>,
+    bandwidth: Arc<RwLock<BandwidthMeter>>,
     app_handle: AppHandle,
   ) -> Self {
-    let (action_tx, action_rx) = mpsc::channel(32);
+    let (action_tx, action_rx) = mpsc::channel(ACTION_QUEUE_CAPACITY);
 
     // Load series preferences from disk
-    let series_preferences = Self::load_preferences_from_store(&app_handle);
+    let track_preferences = Self::load_preferences_from_store(&app_handle);
+    let marked_skip_ranges = Self::load_marked_skip_ranges_from_store(&app_handle);
 
     Self {
       client,
       websocket: Arc::new(JellyfinWebSocket::new()),
       mpv,
       config: config.clone(),
+      bandwidth,
       app_handle,
       state: Arc::new(RwLock::new(SessionState {
         playback: None,
+        playback_generation: PlaybackGeneration::default(),
         last_report_time: std::time::Instant::now(),
         effective_intro_skipper_config: IntroSkipperRuntimeConfig::from(&*config.read()),
-        current_series_id: None,
+        preference_target: None,
         current_item: None,
         current_media_streams: Vec::new(),
-        series_preferences,
+        track_preferences,
+        queue: PlaybackQueue::default(),
+        auto_advanced: false,
+        marked_skip_ranges,
+        skip_mark_start: None,
+        credits_countdown: None,
+        stall: StallTracker::default(),
+        subtitle_tracks: SubtitleTracks::default(),
+        user_data: None,
+        paused_at: None,
+        ab_loop: AbLoop::default(),
+        transcode_restarts: 0,
+        early_end: None,
+        session_files: SessionFiles::default(),
+        phase: SessionPhase::Disconnected,
       })),
       action_tx,
       action_rx: Arc::new(RwLock::new(Some(action_rx))),
+      device_endpoint: Arc::new(RwLock::new(DeviceEndpoint::default())),
+      shutdown: CancellationToken::new(),
     }
   }
 
+  /// What the session is doing right now.
+  pub fn phase(&self) -> SessionPhase {
+    self.state.read().phase
+  }
+
   /// Return current media item metadata for user-facing Now Playing state.
   pub fn current_item(&self) -> Option<MediaItem> {
     self.state.read().current_item.clone()
   }
 
+  /// The user's watched, favorite and rating state of the current item.
+  pub fn current_user_data(&self) -> Option<NowPlayingUserData> {
+    self.state.read().user_data
+  }
+
   async fn emit_now_playing_changed(
     app_handle: &AppHandle,
     mpv: &MpvClient,
     state: &RwLock<SessionState>,
   ) {
     let player = collect_player_state(mpv).await;
-    let state = state.read();
-    let now_playing = build_now_playing_state(
-      player,
-      PlaybackContext {
-        has_active_session: true,
-        current_item: state.current_item.as_ref(),
-      },
-    );
+    let now_playing = {
+      let state = state.read();
+      build_now_playing_state(
+        player,
+        PlaybackContext {
+          has_active_session: true,
+          current_item: state.current_item.as_ref(),
+          user_data: state.user_data,
+        },
+      )
+    };
 
+    crate::tray::show_playback_progress(app_handle, &now_playing);
+    crate::control_api::publish(&now_playing);
+    metrics::set_position(
+      now_playing
+        .media
+        .is_some()
+        .then_some(now_playing.player.time_pos),
+    );
     let event = NowPlayingChanged { state: now_playing };
 
     if let Err(e) = event.emit(app_handle) {
@@ -202,6 +768,32 @@ impl SessionManager {
     HashMap::new()
   }
 
+  /// Load user skip ranges from disk.
+  fn load_marked_skip_ranges_from_store(
+    app_handle: &AppHandle,
+  ) -> HashMap<String, Vec<MarkedSkipRange>> {
+    let store = match app_handle.store(PREFERENCES_STORE_FILE) {
+      Ok(store) => store,
+      Err(e) => {
+        log::warn!("Failed to open preferences store: {}", e);
+        return HashMap::new();
+      }
+    };
+    let Some(value) = store.get(MARKED_SKIP_RANGES_KEY) else {
+      return HashMap::new();
+    };
+    match serde_json::from_value::<HashMap<String, Vec<MarkedSkipRange>>>(value) {
+      Ok(ranges) => {
+        log::info!("Loaded marked skip ranges for {} series", ranges.len());
+        ranges
+      }
+      Err(e) => {
+        log::warn!("Failed to parse stored skip ranges: {}", e);
+        HashMap::new()
+      }
+    }
+  }
+
   /// Start the session (connect WebSocket and begin listening).
   pub async fn start(&self) -> Result<(), JellyfinError> {
     log::info!(
@@ -210,28 +802,83 @@ impl SessionManager {
     );
 
     // Connect WebSocket first
+    ConnectionEvent::Connecting.report(&self.app_handle);
     let ws_url = self.client.playback().websocket_url()?;
     let ws_user_agent = self.client.playback().websocket_user_agent();
+    let ws_tls = self.client.playback().websocket_tls_config()?;
+    let ws_headers = self.client.playback().websocket_headers()?;
     self
       .websocket
-      .connect_with_user_agent(&ws_url, Some(&ws_user_agent))
-      .await?;
+      .connect_with_options(&ws_url, Some(&ws_user_agent), ws_tls, ws_headers)
+      .await
+      .inspect_err(|e| {
+        ConnectionEvent::Disconnected {
+          reason: e.to_string(),
+        }
+        .report(&self.app_handle)
+      })?;
 
     // Then report capabilities via HTTP (must be after WebSocket is established)
     self.client.playback().report_capabilities().await?;
 
     if let Err(e) = self.client.playback().validate_session().await {
-      log::warn!("Session validation failed: {} - cast may not work", e);
+      log::warn!(
+        "Session validation failed: {} - retrying in the background",
+        e
+      );
+      let client = self.client.clone();
+      tokio::spawn(async move {
+        if let Err(e) = Self::register_cast_target(&client).await {
+          log::warn!("Cast registration failed: {} - cast may not work", e);
+        }
+      });
     } else {
       log::info!("Session validated - we should appear as cast target");
     }
 
     // Start WebSocket command consumer with auto-reconnect
     self.start_websocket_consumer();
+    self.start_device_conflict_check();
 
     self.start_local().await
   }
 
+  /// Report capabilities again and wait until the server lists this device
+  /// as a cast target, for when it went missing from the cast menu.
+  pub async fn refresh_cast_registration(&self) -> Result<(), JellyfinError> {
+    Self::register_cast_target(&self.client).await
+  }
+
+  /// Report capabilities and validate the session, retrying both with
+  /// backoff until the server lists this device. A rejected token ends the
+  /// retries right away.
+  async fn register_cast_target(client: &JellyfinClient) -> Result<(), JellyfinError> {
+    let mut delays = CAST_REGISTRATION_RETRY_DELAYS.iter();
+    loop {
+      let result = match client.playback().report_capabilities().await {
+        Ok(()) => client.playback().validate_session().await,
+        Err(e) => Err(e),
+      };
+      let e = match result {
+        Ok(()) => {
+          log::info!("Session validated - we should appear as cast target");
+          return Ok(());
+        }
+        Err(e) if e.is_credentials_expired() || !client.is_connected() => return Err(e),
+        Err(e) => e,
+      };
+      let Some(delay) = delays.next() else {
+        return Err(e);
+      };
+      log::info!(
+        "Cast registration not confirmed: {}; retrying in {:?}",
+        e,
+        delay
+      );
+      tokio::time::sleep(*delay).await;
+    }
+  }
+
   /// Start local MPV consumers without registering as a remote-control target.
   pub async fn start_local(&self) -> Result<(), JellyfinError> {
     // Start MPV action consumer
@@ -239,10 +886,69 @@ impl SessionManager {
 
     // Start MPV event listener for end-of-file detection
     self.start_mpv_event_listener();
+    self.start_pause_drift_check();
 
+    self.state.write().enter(SessionPhase::Idle);
+    self.resume_adopted_playback().await;
     Ok(())
   }
 
+  /// Carry on reporting the playback of an MPV adopted from a crashed
+  /// JellyPilot, or stop its file when it isn't the one last loaded.
+  async fn resume_adopted_playback(&self) {
+    self.mpv.handle_orphans().await;
+    if !self.mpv.is_adopted() || !self.mpv.is_connected() || self.state.read().playback.is_some() {
+      return;
+    }
+    let path = match self.mpv.get_property_as::<Option<String>>("path").await {
+      Ok(path) => path.unwrap_or_default(),
+      Err(e) => {
+        log::warn!("Failed to get the file of the adopted MPV: {}", e);
+        return;
+      }
+    };
+    if path.is_empty() {
+      return;
+    }
+    let Some(stored) = Self::load_loaded_playback(&self.app_handle).filter(|s| s.matches(&path))
+    else {
+      log::info!("The adopted MPV plays a file JellyPilot didn't load; stopping it");
+      if let Err(e) = self.mpv.stop_playback(true).await {
+        log::warn!("Failed to stop the adopted MPV's file: {}", e);
+      }
+      return;
+    };
+
+    let player = collect_player_state(&self.mpv).await;
+    let volume = VolumeMapping::from_config(&self.config.read()).to_remote(player.volume);
+    let item = stored.item.clone();
+    log::info!(
+      "Resuming playback of {} from the adopted MPV at {:.1}s",
+      item.id,
+      player.time_pos
+    );
+    let generation = {
+      let mut s = self.state.write();
+      let generation = s.begin_playback();
+      let mut session = stored.into_session(&player, volume);
+      if let Some(marks) = s.marked_skip_ranges.get(marked_skip_key(&item)) {
+        session.skip_ranges = marked_skip_ranges(marks);
+      }
+      s.effective_intro_skipper_config = IntroSkipperRuntimeConfig::from(&*self.config.read());
+      s.current_item = Some(item.clone());
+      s.playback = Some(session);
+      s.last_report_time = std::time::Instant::now();
+      s.enter(SessionPhase::Loading);
+      let phase = s.open_phase();
+      s.enter(phase);
+      generation
+    };
+
+    Self::reannounce_playback(&self.client, &self.state).await;
+    Self::emit_now_playing_changed(&self.app_handle, &self.mpv, &self.state).await;
+    Self::refresh_user_data(&self.client, &self.state, generation, &item.id).await;
+  }
+
   /// Start WebSocket command stream consumer.
   fn start_websocket_consumer(&self) {
     let client = self.client.clone();
@@ -252,6 +958,7 @@ impl SessionManager {
     let app_handle = self.app_handle.clone();
     let mpv = self.mpv.clone();
     let config = self.config.clone();
+    let device_endpoint = self.device_endpoint.clone();
 
     tokio::spawn(async move {
       let Some(mut event_rx) = websocket.take_event_receiver() else {
@@ -264,37 +971,182 @@ impl SessionManager {
         match event {
           JellyfinWebSocketEvent::Connected => {
             log::info!("Jellyfin WebSocket connected");
+            Self::report_connected(&client, &app_handle);
           }
-          JellyfinWebSocketEvent::ConnectionLost => {
+          JellyfinWebSocketEvent::ConnectionLost(next) => {
             log::warn!("Jellyfin WebSocket connection lost");
-            Self::clear_playback_context(&client, &state).await;
-            AppNotification::warning(&app_handle, "Connection lost. Reconnecting...");
+            Self::report_reconnecting(&app_handle, next);
+            if Self::fail_over_address(&client, &state, &action_tx, &websocket).await {
+              AppNotification::info(&app_handle, tr!("notify-switched-address"));
+            } else {
+              // MPV keeps playing; the session is announced again on reconnect.
+              if state.read().playback.is_some() {
+                log::info!("Keeping the local playback session until the server is back");
+              }
+              // The connection banner shows this; only the opt-in OS
+              // notification is sent on top.
+              os_notification::notify(
+                &app_handle,
+                OsNotificationKind::ConnectionLost,
+                &tr!("notify-connection-lost"),
+              );
+            }
+          }
+          JellyfinWebSocketEvent::ReconnectFailed(next) => {
+            Self::report_reconnecting(&app_handle, next);
+            if Self::fail_over_address(&client, &state, &action_tx, &websocket).await {
+              AppNotification::info(&app_handle, tr!("notify-switched-address"));
+            }
           }
           JellyfinWebSocketEvent::Reconnected => {
             log::info!("WebSocket reconnected successfully");
-            AppNotification::info(&app_handle, "Reconnected to Jellyfin");
+            metrics::increment(Counter::Reconnects);
+            Self::report_connected(&client, &app_handle);
+            // The network may have changed, and the server sees a new address.
+            device_endpoint.write().forget();
+            os_notification::notify(
+              &app_handle,
+              OsNotificationKind::ConnectionRestored,
+              &tr!("notify-reconnected"),
+            );
 
             if let Err(e) = client.playback().report_capabilities().await {
               log::error!("Failed to report capabilities after reconnect: {}", e);
+              if e.is_credentials_expired() {
+                Self::handle_credentials_expired(
+                  &client,
+                  &state,
+                  &websocket,
+                  &app_handle,
+                  e.to_string(),
+                )
+                .await;
+                break;
+              }
             }
+            Self::reannounce_playback(&client, &state).await;
+          }
+          JellyfinWebSocketEvent::AuthRejected => {
+            Self::handle_credentials_expired(
+              &client,
+              &state,
+              &websocket,
+              &app_handle,
+              "WebSocket reconnection was rejected with HTTP 401".to_string(),
+            )
+            .await;
+            break;
           }
           JellyfinWebSocketEvent::Command(cmd) => {
+            metrics::increment(Counter::CommandsHandled);
             if let Err(e) =
               Self::handle_command(&client, &state, &action_tx, &app_handle, &mpv, &config, cmd)
                 .await
             {
               log::error!("Failed to handle Jellyfin command: {}", e);
-              AppNotification::error(&app_handle, format!("Command failed: {}", e));
+              if e.is_credentials_expired() {
+                Self::handle_credentials_expired(
+                  &client,
+                  &state,
+                  &websocket,
+                  &app_handle,
+                  e.to_string(),
+                )
+                .await;
+                break;
+              }
+              match e.playback_stage() {
+                Some(stage) => PlaybackError::report(&app_handle, stage, e.to_string()),
+                None => AppNotification::error(
+                  &app_handle,
+                  tr!("notify-command-failed", error = e.to_string()),
+                ),
+              }
             }
           }
+          JellyfinWebSocketEvent::ParseFailed(failure) => {
+            Self::handle_parse_failure(&app_handle, &mpv, &config, failure);
+          }
         }
       }
     });
   }
 
+  /// Move to another address of the server when it answers faster than the
+  /// current one, e.g. after a laptop left the home network. The WebSocket
+  /// reconnects there and a playing stream reloads from it. Returns true when
+  /// the address changed.
+  async fn fail_over_address(
+    client: &JellyfinClient,
+    state: &RwLock<SessionState>,
+    action_tx: &mpsc::Sender<MpvAction>,
+    websocket: &JellyfinWebSocket,
+  ) -> bool {
+    if !client.playback().select_server_address().await {
+      return false;
+    }
+
+    match client.playback().websocket_url() {
+      Ok(url) => websocket.set_url(&url),
+      Err(e) => log::warn!("No WebSocket URL for the new server address: {}", e),
+    }
+
+    let subtitle_index = state
+      .read()
+      .playback
+      .as_ref()
+      .map(|playback| playback.subtitle_stream_index.unwrap_or(-1));
+    if let Some(subtitle_index) = subtitle_index {
+      if !Self::reload_stream(client, state, action_tx, subtitle_index, true).await {
+        log::warn!("Failed to reload the stream from the new server address");
+      }
+    }
+    true
+  }
+
+  /// The server rejected the saved token, e.g. after a password change. Stop
+  /// the WebSocket instead of reconnecting with a dead token and ask the user
+  /// to sign in again.
+  async fn handle_credentials_expired(
+    client: &JellyfinClient,
+    state: &RwLock<SessionState>,
+    websocket: &JellyfinWebSocket,
+    app_handle: &AppHandle,
+    detail: String,
+  ) {
+    log::warn!("Server rejected the saved session: {}", detail);
+    Self::clear_playback_context(client, state).await;
+    websocket.disconnect().await;
+    ConnectionEvent::Disconnected {
+      reason: tr!("connection-session-expired"),
+    }
+    .report(app_handle);
+    SessionExpired::report(app_handle, detail);
+  }
+
+  fn report_connected(client: &JellyfinClient, app_handle: &AppHandle) {
+    let connection = client.login().connection_state();
+    ConnectionEvent::Connected {
+      server: connection
+        .server_name
+        .or(connection.server_url)
+        .unwrap_or_default(),
+    }
+    .report(app_handle);
+  }
+
+  fn report_reconnecting(app_handle: &AppHandle, next: ReconnectAttempt) {
+    ConnectionEvent::Reconnecting {
+      attempt: next.attempt,
+      delay_seconds: next.delay.as_secs_f64(),
+    }
+    .report(app_handle);
+  }
+
   /// Start the MPV action consumer task.
   fn start_action_consumer(&self) {
     if let Some(mut action_rx) = self.action_rx.write().take() {
+      let client = self.client.clone();
       let mpv = self.mpv.clone();
       let app_handle = self.app_handle.clone();
       let config = self.config.clone();
@@ -302,137 +1154,308 @@ impl SessionManager {
 
       tokio::spawn(async move {
         log::info!("MPV action consumer started, waiting for actions...");
-        while let Some(action) = action_rx.recv().await {
-          log::info!("Processing MPV action: {:?}", action);
-
-          match action {
-            MpvAction::Play {
-              url,
-              start_position,
-              title,
-              audio_index,
-              subtitle_index,
-            } => {
-              log::info!(
-                "MpvAction::Play received, url={}, title={}",
-                redact_url(&url),
-                title
-              );
-              // Start MPV if not already running
-              if !mpv.is_connected() {
-                log::info!("MPV not connected, starting...");
-                if let Err(e) = mpv.start().await {
-                  log::error!("Failed to start MPV: {}", e);
-                  AppNotification::error(&app_handle, format!("Failed to start MPV: {}", e));
-                  continue;
-                }
-                state.write().effective_intro_skipper_config =
-                  IntroSkipperRuntimeConfig::from(&*config.read());
-                log::info!("MPV started successfully");
-              }
-
-              // Load the file with all options (start position, audio/subtitle tracks)
-              // This ensures tracks are set atomically with the file load, avoiding race conditions
-              log::info!(
-                "Loading file into MPV: {} (start={}, aid={:?}, sid={:?})",
-                redact_url(&url),
+        // Whether a stop closed the window of the still running MPV.
+        let mut window_hidden = false;
+        // Actions taken off the channel but not run yet, oldest first.
+        let mut backlog = VecDeque::new();
+        while let Some(action) = action_watchdog::next_action(&mut action_rx, &mut backlog).await {
+          log::info!(
+            "Processing MPV action: {}",
+            redact(&format!("{:?}", action))
+          );
+          let limits = ActionLimits::of(&action, &mpv.command_timeouts());
+          let loads = matches!(action, MpvAction::Play { .. });
+          let stops = action.is_stop();
+          let started = std::time::Instant::now();
+
+          let run = async {
+            match action {
+              MpvAction::Play {
+                url,
                 start_position,
+                title,
                 audio_index,
-                subtitle_index
-              );
-              if let Err(e) = mpv
-                .loadfile_with_options(
-                  &url,
-                  Some(start_position),
-                  audio_index.map(|i| i as i64),
-                  subtitle_index.map(|i| i as i64),
-                )
-                .await
-              {
-                log::error!("Failed to load file: {}", e);
-                AppNotification::error(&app_handle, format!("Failed to load media: {}", e));
-                continue;
-              }
-              log::info!("File loaded successfully");
+                subtitle_index,
+                fonts_dir,
+              } => {
+                log::info!(
+                  "MpvAction::Play received, url={}, title={}",
+                  redact(&url),
+                  title
+                );
+                state.write().enter(SessionPhase::Loading);
+                // Start MPV if not already running
+                if !mpv.is_connected() {
+                  log::info!("MPV not connected, starting...");
+                  if let Err(e) = mpv.start().await {
+                    log::error!("Failed to start MPV: {}", e);
+                    PlaybackError::report(&app_handle, PlaybackErrorStage::MpvSpawn, e.to_string());
+                    state.write().playback_ended();
+                    return;
+                  }
+                  state.write().effective_intro_skipper_config =
+                    IntroSkipperRuntimeConfig::from(&*config.read());
+                  log::info!("MPV started successfully");
+                  notify_removed_mpv_args(&app_handle, &mpv);
+                } else {
+                  // A window hidden by a stop or a warm start comes back.
+                  let warm = mpv.take_warm();
+                  if std::mem::take(&mut window_hidden) || warm {
+                    if let Err(e) = mpv.show_window().await {
+                      log::warn!("Failed to reopen the MPV window: {}", e);
+                    }
+                  }
+                }
 
-              // Set the media title (shown in MPV window)
-              if let Err(e) = mpv.set_property_string("force-media-title", &title).await {
-                log::warn!("Failed to set media title: {}", e);
-              }
+                // Load the file with all options (start position, audio/subtitle tracks)
+                // This ensures tracks are set atomically with the file load, avoiding race conditions
+                log::info!(
+                  "Loading file into MPV: {} (start={}, aid={:?}, sid={:?})",
+                  redact(&url),
+                  start_position,
+                  audio_index,
+                  subtitle_index
+                );
+                if let Err(e) = mpv
+                  .loadfile_with_options(
+                    &url,
+                    Some(start_position),
+                    audio_index.map(|i| i as i64),
+                    subtitle_index.map(|i| i as i64),
+                    fonts_dir.as_deref(),
+                    &client.playback().stream_headers(),
+                  )
+                  .await
+                {
+                  log::error!("Failed to load file: {}", e);
+                  PlaybackError::report(&app_handle, PlaybackErrorStage::MpvLoad, e.to_string());
+                  state.write().playback_ended();
+                  return;
+                }
+                log::info!("File loaded successfully");
+                Self::save_loaded_playback(&state, &app_handle, &url);
 
-              log::info!("Started playback: {} - {}", title, redact_url(&url));
-            }
-            MpvAction::Pause => {
-              log::info!("MpvAction::Pause - setting pause=true");
-              if let Err(e) = mpv.set_pause(true).await {
-                log::error!("Failed to pause: {}", e);
-              } else {
-                log::info!("MPV paused successfully");
+                // Set the media title (shown in MPV window)
+                if let Err(e) = mpv.set_property_string("force-media-title", &title).await {
+                  log::warn!("Failed to set media title: {}", e);
+                }
+
+                log::info!("Started playback: {} - {}", title, redact(&url));
+                metrics::increment(Counter::PlaybacksStarted);
+                let (auto_advanced, item) = {
+                  let mut s = state.write();
+                  (std::mem::take(&mut s.auto_advanced), s.current_item.clone())
+                };
+                if let Some(item) = item {
+                  let (kind, event) = if auto_advanced {
+                    (OsNotificationKind::AutoNext, WebhookEvent::AutoNext)
+                  } else {
+                    (
+                      OsNotificationKind::PlaybackStarted,
+                      WebhookEvent::PlaybackStarted,
+                    )
+                  };
+                  webhooks::fire(event, &item, seconds_to_ticks(start_position));
+                  let (app_handle, client) = (app_handle.clone(), client.clone());
+                  tokio::spawn(async move {
+                    os_notification::notify_playback(&app_handle, &client, kind, &item.id, &title)
+                      .await;
+                  });
+                }
               }
-            }
-            MpvAction::Resume => {
-              log::info!("MpvAction::Resume - setting pause=false");
-              if let Err(e) = mpv.set_pause(false).await {
-                log::error!("Failed to resume: {}", e);
-              } else {
-                log::info!("MPV resumed successfully");
+              MpvAction::Seek(position) => {
+                if let Err(e) = mpv.seek(position).await {
+                  log::error!("Failed to seek: {}", e);
+                }
               }
-            }
-            MpvAction::Seek(position) => {
-              if let Err(e) = mpv.seek(position).await {
-                log::error!("Failed to seek: {}", e);
+              MpvAction::ShowText { text, duration_ms } => {
+                if let Err(e) = mpv.show_text(&text, duration_ms).await {
+                  log::warn!("Failed to show MPV text: {}", e);
+                }
               }
-            }
-            MpvAction::ShowText { text, duration_ms } => {
-              if let Err(e) = mpv.show_text(&text, duration_ms).await {
-                log::warn!("Failed to show MPV text: {}", e);
+              MpvAction::Stop => {
+                let behavior = config.read().mpv_stop_behavior;
+                log::info!("MpvAction::Stop - {:?}", behavior);
+                state.write().stopping();
+                let keep_window = match behavior {
+                  MpvStopBehavior::Quit => None,
+                  MpvStopBehavior::KeepIdle => Some(true),
+                  MpvStopBehavior::HideWindow => Some(false),
+                };
+                let stopped = match keep_window {
+                  Some(keep_window) if mpv.is_connected() => {
+                    match mpv.stop_playback(keep_window).await {
+                      Ok(()) => {
+                        window_hidden = !keep_window;
+                        true
+                      }
+                      Err(e) => {
+                        log::warn!("Failed to stop playback in MPV: {}, quitting", e);
+                        false
+                      }
+                    }
+                  }
+                  _ => false,
+                };
+                if !stopped {
+                  if let Err(e) = mpv.quit().await {
+                    log::warn!("Failed to quit MPV gracefully: {}, forcing stop", e);
+                    mpv.stop().await;
+                  }
+                }
               }
-            }
-            MpvAction::Stop => {
-              log::info!("MpvAction::Stop - quitting MPV gracefully");
-              if let Err(e) = mpv.quit().await {
-                log::warn!("Failed to quit MPV gracefully: {}, forcing stop", e);
-                mpv.stop().await;
+              MpvAction::SetVolume(volume) => {
+                let mpv_volume = VolumeMapping::from_config(&config.read()).to_mpv(volume);
+                if let Err(e) = mpv.set_volume(mpv_volume).await {
+                  log::error!("Failed to set volume: {}", e);
+                }
               }
-            }
-            MpvAction::SetVolume(volume) => {
-              if let Err(e) = mpv.set_volume(volume as f64).await {
-                log::error!("Failed to set volume: {}", e);
+              MpvAction::ToggleMute => {
+                if let Err(e) = mpv.toggle_mute().await {
+                  log::error!("Failed to toggle mute: {}", e);
+                }
               }
-            }
-            MpvAction::ToggleMute => {
-              if let Err(e) = mpv.toggle_mute().await {
-                log::error!("Failed to toggle mute: {}", e);
+              MpvAction::ToggleFullscreen => {
+                if let Err(e) = mpv.toggle_fullscreen().await {
+                  log::error!("Failed to toggle fullscreen: {}", e);
+                }
               }
-            }
-            MpvAction::ToggleFullscreen => {
-              if let Err(e) = mpv.toggle_fullscreen().await {
-                log::error!("Failed to toggle fullscreen: {}", e);
+              MpvAction::SetFullscreen(fullscreen) => {
+                if let Err(e) = mpv.set_fullscreen(fullscreen).await {
+                  log::error!("Failed to set fullscreen: {}", e);
+                }
               }
-            }
-            MpvAction::SetAudioTrack(index) => {
-              // index is already MPV's 1-based track ID
-              if let Err(e) = mpv.set_audio_track(index as i64).await {
-                log::error!("Failed to set audio track: {}", e);
+              MpvAction::SetAudioTrack(index) => {
+                // index is already MPV's 1-based track ID
+                if let Err(e) = mpv.set_audio_track(index as i64).await {
+                  log::error!("Failed to set audio track: {}", e);
+                }
               }
-            }
-            MpvAction::SetSubtitleTrack(index) => {
-              if index == -1 {
-                // Disable subtitles
-                if let Err(e) = mpv.disable_track("sid").await {
-                  log::error!("Failed to disable subtitles: {}", e);
+              MpvAction::SetSubtitleTrack(index) => {
+                if index == -1 {
+                  // Disable subtitles
+                  if let Err(e) = mpv.disable_track("sid").await {
+                    log::error!("Failed to disable subtitles: {}", e);
+                  }
+                } else {
+                  // index is already MPV's 1-based track ID
+                  if let Err(e) = mpv.set_subtitle_track(index as i64).await {
+                    log::error!("Failed to set subtitle track: {}", e);
+                  }
                 }
-              } else {
-                // index is already MPV's 1-based track ID
-                if let Err(e) = mpv.set_subtitle_track(index as i64).await {
-                  log::error!("Failed to set subtitle track: {}", e);
+              }
+              MpvAction::SetSecondarySubtitleTrack(track_id) => {
+                let result = if track_id == -1 {
+                  mpv.disable_track("secondary-sid").await
+                } else {
+                  mpv.set_secondary_subtitle_track(track_id as i64).await
+                };
+                if let Err(e) = result {
+                  log::error!("Failed to set secondary subtitle track: {}", e);
+                }
+              }
+              MpvAction::AddSecondarySubtitle { url, track_id } => {
+                if let Err(e) = mpv.sub_add(&url, false).await {
+                  log::error!("Failed to add secondary subtitle: {}", e);
+                } else if let Err(e) = mpv.set_secondary_subtitle_track(track_id as i64).await {
+                  log::error!("Failed to set secondary subtitle track: {}", e);
+                }
+              }
+              MpvAction::SetAbLoop(ab_loop) => {
+                if let Err(e) = mpv.set_ab_loop(ab_loop.start, ab_loop.end).await {
+                  log::error!("Failed to set A-B loop: {}", e);
+                }
+              }
+              MpvAction::Screenshot { path, mode } => {
+                let text = match mpv.screenshot_to_file(&path, mode.mpv_flag()).await {
+                  Ok(()) => {
+                    log::info!("Saved screenshot {}", path.display());
+                    let name = path
+                      .file_name()
+                      .map(|name| name.to_string_lossy().into_owned())
+                      .unwrap_or_default();
+                    tr!("osd-screenshot-saved", name = name)
+                  }
+                  Err(e) => {
+                    log::error!("Failed to save screenshot: {}", e);
+                    tr!("osd-screenshot-failed")
+                  }
+                };
+                if let Err(e) = mpv.show_text(&text, 2000).await {
+                  log::warn!("Failed to show MPV text: {}", e);
+                }
+              }
+              MpvAction::AddExternalSubtitle(url) => {
+                log::info!("MpvAction::AddExternalSubtitle: {}", redact(&url));
+                if let Err(e) = mpv.sub_add(&url, true).await {
+                  log::error!("Failed to add external subtitle: {}", e);
+                }
+              }
+              MpvAction::StepSpeed { faster } => {
+                let speed = match mpv.get_speed().await {
+                  Ok(speed) => stepped_speed(speed, faster),
+                  Err(e) => {
+                    log::warn!("Failed to read playback speed: {}", e);
+                    return;
+                  }
+                };
+                if let Err(e) = mpv.set_speed(speed).await {
+                  log::warn!("Failed to set playback speed: {}", e);
+                  return;
                 }
+                let text = tr!("osd-speed", speed = speed.to_string());
+                if let Err(e) = mpv.show_text(&text, 1000).await {
+                  log::warn!("Failed to show MPV text: {}", e);
+                }
+              }
+              MpvAction::ToggleStats => {
+                if let Err(e) = mpv.toggle_stats().await {
+                  log::warn!("Failed to toggle stats overlay: {}", e);
+                }
+              }
+              MpvAction::StopIdle => {
+                log::info!("MpvAction::StopIdle");
+                state.write().stopping();
+                match mpv.stop_playback(true).await {
+                  Ok(()) => window_hidden = false,
+                  Err(e) => log::warn!("Failed to stop playback in MPV: {}", e),
+                }
+              }
+            }
+          };
+          let outcome = action_watchdog::watch(run, limits, &mut action_rx, &mut backlog, || {
+            log::warn!("MPV action still running after {:?}", limits.slow_after);
+            metrics::increment(Counter::SlowMpvActions);
+            let seconds = limits.slow_after.as_secs().to_string();
+            let message = if loads {
+              tr!("notify-mpv-slow-load", seconds = seconds)
+            } else {
+              tr!("notify-mpv-slow", seconds = seconds)
+            };
+            AppNotification::warning(&app_handle, message);
+          })
+          .await;
+
+          let elapsed = started.elapsed();
+          match outcome {
+            ActionOutcome::Finished => log::debug!("MPV action took {:?}", elapsed),
+            ActionOutcome::Preempted => {
+              log::warn!("Cancelled MPV action after {:?} for a stop", elapsed);
+              if loads {
+                Self::abandon_load(&mpv, &state).await;
               }
             }
-            MpvAction::AddExternalSubtitle(url) => {
-              log::info!("MpvAction::AddExternalSubtitle: {}", redact_url(&url));
-              if let Err(e) = mpv.sub_add(&url, true).await {
-                log::error!("Failed to add external subtitle: {}", e);
+            ActionOutcome::TimedOut => {
+              log::error!("Gave up on MPV action after {:?}", elapsed);
+              if loads {
+                PlaybackError::report(
+                  &app_handle,
+                  PlaybackErrorStage::MpvLoad,
+                  format!("MPV did not open the stream within {:?}", elapsed),
+                );
+                Self::abandon_load(&mpv, &state).await;
+              } else if stops {
+                mpv.stop().await;
               }
             }
           }
@@ -441,6 +1464,53 @@ impl SessionManager {
     }
   }
 
+  /// Clean up after a play the watchdog cut short: an MPV it was still
+  /// starting is killed, and the session stops waiting for the file. An MPV
+  /// that was already running is left to the stop that follows.
+  async fn abandon_load(mpv: &MpvClient, state: &RwLock<SessionState>) {
+    if !mpv.is_connected() {
+      mpv.stop().await;
+    }
+    state.write().playback_ended();
+  }
+
+  /// Pause or stop the user's other sessions that were playing one of the
+  /// items just cast here.
+  async fn take_over_playback(
+    client: &JellyfinClient,
+    command: RemotePlaystateCommand,
+    item_ids: &[String],
+  ) {
+    let sessions = match client.playback().list_other_sessions().await {
+      Ok(sessions) => sessions,
+      Err(e) => {
+        log::warn!("Failed to list other sessions for takeover: {}", e);
+        return;
+      }
+    };
+
+    for session in sessions.iter().filter(|session| {
+      session
+        .now_playing_item_id
+        .as_ref()
+        .is_some_and(|id| item_ids.contains(id))
+    }) {
+      log::info!(
+        "Sending {} to session {} ({})",
+        command.as_str(),
+        session.id,
+        session.device_name.as_deref().unwrap_or("unknown device")
+      );
+      if let Err(e) = client
+        .playback()
+        .send_remote_playstate(&session.id, command, None)
+        .await
+      {
+        log::warn!("Failed to take over session {}: {}", session.id, e);
+      }
+    }
+  }
+
   /// Handle a Jellyfin command.
   async fn handle_command(
     client: &JellyfinClient,
@@ -453,15 +1523,27 @@ impl SessionManager {
   ) -> Result<(), JellyfinError> {
     match cmd {
       JellyfinCommand::Play(request) => {
+        let takeover = match config.read().cast_takeover {
+          CastTakeover::Off => None,
+          CastTakeover::Pause => Some(RemotePlaystateCommand::Pause),
+          CastTakeover::Stop => Some(RemotePlaystateCommand::Stop),
+        };
+        let taken_over_items = takeover
+          .filter(|_| !matches!(request.play_command.as_str(), "PlayNext" | "PlayLast"))
+          .map(|command| (command, request.item_ids.clone()));
         Self::handle_play(
           client,
           state,
           action_tx,
           mpv.is_connected(),
           config,
+          PlayOrigin::RemoteCast,
           request,
         )
         .await?;
+        if let Some((command, item_ids)) = taken_over_items {
+          Self::take_over_playback(client, command, &item_ids).await;
+        }
       }
       JellyfinCommand::Playstate(request) => {
         Self::handle_playstate(client, state, action_tx, mpv, config, request).await?;
@@ -469,10 +1551,40 @@ impl SessionManager {
       JellyfinCommand::GeneralCommand(request) => {
         Self::handle_general_command(client, state, action_tx, app_handle, request).await?;
       }
+      JellyfinCommand::UserDataChanged => {
+        log::debug!("User data changed on server, dropping cached responses");
+        client.playback().invalidate_cache();
+      }
     }
     Ok(())
   }
 
+  /// Record a dropped WebSocket message when parse diagnostics are enabled.
+  fn handle_parse_failure(
+    app_handle: &AppHandle,
+    mpv: &MpvClient,
+    config: &RwLock<AppConfig>,
+    failure: ParseFailure,
+  ) {
+    if !config.read().websocket_parse_diagnostics {
+      return;
+    }
+    log::warn!(
+      "Dropped {} message that failed to parse: {}; payload: {}",
+      failure.message_type,
+      failure.error,
+      failure.payload
+    );
+    mpv.audit_rejected_message(&failure.message_type, &failure.error, failure.payload);
+    AppNotification::warning(
+      app_handle,
+      tr!(
+        "notify-unreadable-message",
+        message_type = failure.message_type.clone()
+      ),
+    );
+  }
+
   /// Handle Play command.
   async fn handle_play(
     client: &JellyfinClient,
@@ -480,19 +1592,93 @@ impl SessionManager {
     action_tx: &mpsc::Sender<MpvAction>,
     mpv_connected: bool,
     config: &RwLock<AppConfig>,
+    origin: PlayOrigin,
     request: PlayRequest,
   ) -> Result<(), JellyfinError> {
-    log::info!("handle_play called with request: {:?}", request);
+    log::info!(
+      "handle_play called from {:?} with request: {:?}",
+      origin,
+      request
+    );
+
+    // PlayNext/PlayLast only extend the queue while something is already playing
+    let enqueue_only = matches!(request.play_command.as_str(), "PlayNext" | "PlayLast")
+      && state.read().playback.is_some();
+    if enqueue_only {
+      {
+        let mut s = state.write();
+        if request.play_command == "PlayNext" {
+          s.queue.play_next(&request.item_ids);
+        } else {
+          s.queue.play_last(&request.item_ids);
+        }
+        log::info!(
+          "{}: queued {} item(s), queue length {}",
+          request.play_command,
+          request.item_ids.len(),
+          s.queue.items().len()
+        );
+      }
+      Self::report_progress(client, state).await;
+      return Ok(());
+    }
 
-    // Get the first item ID
-    let item_id = request
-      .item_ids
-      .first()
+    let start_index = request.start_index.unwrap_or(0).max(0) as usize;
+    let item_id = state
+      .write()
+      .queue
+      .replace(&request.item_ids, start_index)
+      .map(|item| item.id.clone())
       .ok_or(JellyfinError::SessionNotFound)?;
+
+    Self::play_item(
+      client,
+      state,
+      action_tx,
+      mpv_connected,
+      config,
+      &request,
+      &item_id,
+    )
+    .await?;
+
+    let fullscreen = Self::fullscreen_on_start(&config.read(), origin);
+    if fullscreen {
+      let _ = action_tx.send(MpvAction::SetFullscreen(true)).await;
+    }
+    Ok(())
+  }
+
+  /// Whether a new playback from `origin` should switch MPV to fullscreen.
+  fn fullscreen_on_start(config: &AppConfig, origin: PlayOrigin) -> bool {
+    match origin {
+      PlayOrigin::RemoteCast => config.fullscreen_on_playback || config.fullscreen_remote_casts,
+      PlayOrigin::Local => config.fullscreen_on_playback,
+      PlayOrigin::Continuation => false,
+    }
+  }
+
+  /// Load one item into MPV and report playback start, keeping the current queue.
+  async fn play_item(
+    client: &JellyfinClient,
+    state: &RwLock<SessionState>,
+    action_tx: &mpsc::Sender<MpvAction>,
+    mpv_connected: bool,
+    config: &RwLock<AppConfig>,
+    request: &PlayRequest,
+    item_id: &str,
+  ) -> Result<(), JellyfinError> {
     log::info!("Playing item_id: {}", item_id);
+    let generation = state.write().begin_playback();
+
+    let at_playback_info = |e: JellyfinError| e.at_playback_stage(PlaybackErrorStage::PlaybackInfo);
 
     // Fetch media item metadata for title
-    let item = client.playback().get_item(item_id).await?;
+    let item = client
+      .playback()
+      .get_item(item_id)
+      .await
+      .map_err(at_playback_info)?;
     let title = Self::format_title(&item);
     log::info!("Media title: {}", title);
 
@@ -504,37 +1690,65 @@ impl SessionManager {
         request.audio_stream_index,
         request.subtitle_stream_index,
       )
-      .await?;
+      .await
+      .map_err(at_playback_info)?;
     log::info!(
       "Got playback info, media_sources count: {}",
       playback_info.media_sources.len()
     );
 
-    // Get the best media source
-    let media_source = playback_info
-      .media_sources
-      .first()
-      .ok_or(JellyfinError::SessionNotFound)?;
+    // Pick the requested version, or the one the config prefers
+    let (version_preference, preferred_video_height) = {
+      let config = config.read();
+      (
+        config.media_version_preference,
+        config.preferred_video_height,
+      )
+    };
+    let media_source = select_media_source(
+      &playback_info.media_sources,
+      request.media_source_id.as_deref(),
+      version_preference,
+      preferred_video_height,
+    )
+    .ok_or_else(|| at_playback_info(JellyfinError::SessionNotFound))?;
     log::info!(
       "Using media_source: id={}, protocol={:?}",
       media_source.id,
       media_source.protocol
     );
 
-    let series_preference = item.series_id.as_ref().and_then(|series_id| {
+    let preference_scope = config.read().track_preference_scope;
+    let library = if preference_scope == TrackPreferenceScope::Library {
+      match client.playback().get_library(item_id).await {
+        Ok(library) => library,
+        Err(e) => {
+          log::warn!(
+            "Library of {} unavailable for track preferences: {}",
+            item_id,
+            e
+          );
+          None
+        }
+      }
+    } else {
+      None
+    };
+    let preference_target = preference_target(preference_scope, &item, library.as_ref());
+    let series_preference = preference_target.as_ref().and_then(|target| {
       let s = state.read();
       log::info!(
-        "Looking up preferences for series_id={}, preference_count={}, has_preference={}",
-        series_id,
-        s.series_preferences.len(),
-        s.series_preferences.contains_key(series_id)
+        "Looking up preferences for key={}, preference_count={}, has_preference={}",
+        target.key,
+        s.track_preferences.len(),
+        s.track_preferences.contains_key(&target.key)
       );
-      s.series_preferences.get(series_id).cloned()
+      s.track_preferences.get(&target.key).cloned()
     });
     if let Some(ref pref) = series_preference {
       log::info!(
-        "Found track preference for series {:?}: {:?}",
-        item.series_id,
+        "Found track preference for {:?}: {:?}",
+        preference_target.as_ref().map(|target| &target.key),
         pref
       );
     }
@@ -551,8 +1765,8 @@ impl SessionManager {
         intro_skipper_config.mode != IntroSkipperMode::Off,
       )
     };
-    let resolution = resolve_play_request(
-      &request,
+    let mut resolution = resolve_play_request(
+      request,
       &item,
       &playback_info,
       media_source,
@@ -563,14 +1777,30 @@ impl SessionManager {
       },
     );
 
-    // Build stream URL
-    let url = client
-      .playback()
-      .build_stream_url(item_id, media_source)
-      .ok_or(JellyfinError::NotConnected)?;
-    log::info!("Built stream URL: {}", redact_url(&url));
+    // Go back a little into a saved position so the scene is familiar again
+    if let Some(rewind) = ResumeRewind::from_config(&config.read()) {
+      resolution.start_position = rewind.start_position(resolution.start_position);
+      resolution.position_ticks = seconds_to_ticks(resolution.start_position);
+    }
+
+    // Build stream URL; image subtitles on a transcode are burned into the video
+    let url = match resolution.image_subtitle {
+      Some(ImageSubtitle::BurnIn(stream)) => {
+        log::info!(
+          "Burning in image subtitle {} ({:?})",
+          stream.index,
+          stream.codec
+        );
+        client
+          .playback()
+          .build_burn_in_stream_url(media_source, stream.index)
+      }
+      _ => client.playback().build_stream_url(item_id, media_source),
+    }
+    .ok_or_else(|| JellyfinError::NotConnected.at_playback_stage(PlaybackErrorStage::StreamUrl))?;
+    log::info!("Built stream URL: {}", redact(&url));
 
-    let intro_skipper_ranges = if resolution.should_fetch_intro_skipper_ranges {
+    let mut skip_ranges = if resolution.should_fetch_intro_skipper_ranges {
       match client.playback().get_intro_skipper_ranges(item_id).await {
         Ok(ranges) => {
           log::info!("Loaded {} Intro Skipper ranges", ranges.len());
@@ -585,18 +1815,45 @@ impl SessionManager {
       log::debug!("Intro Skipper disabled or inapplicable; skipping range fetch");
       Vec::new()
     };
+    if let Some(marks) = state.read().marked_skip_ranges.get(marked_skip_key(&item)) {
+      log::info!("Loaded {} marked skip ranges", marks.len());
+      skip_ranges.extend(marked_skip_ranges(marks));
+    }
+
+    // In-progress recordings keep growing; MPV reports when they become seekable.
+    let is_growing = item.is_recording_in_progress() || media_source.is_infinite_stream;
+    if is_growing {
+      log::info!("{} is an in-progress recording; seeking disabled", item_id);
+    }
 
     // Store playback session and current series
     {
       let mut s = state.write();
-      s.current_series_id = item.series_id.clone();
+      if s.playback_generation != generation {
+        log::info!("Play of {} superseded while loading", item_id);
+        return Ok(());
+      }
+      s.preference_target = preference_target;
       s.current_item = Some(item.clone());
       s.current_media_streams = media_source.media_streams.clone();
+      s.skip_mark_start = None;
+      s.credits_countdown = None;
+      s.stall = StallTracker::default();
+      s.user_data = None;
+      s.paused_at = None;
+      s.transcode_restarts = 0;
+      s.early_end = None;
+      // A new file drops added subtitles; the external one is added below.
+      s.subtitle_tracks = SubtitleTracks::default();
+      s.session_files.clear();
+      if let Some(stream) = resolution.external_subtitle_stream {
+        s.subtitle_tracks.loaded(stream.index);
+      }
       s.playback = Some(PlaybackSession {
-        item_id: item_id.clone(),
+        item_id: item_id.to_string(),
         media_source_id: Some(media_source.id.clone()),
         play_session_id: playback_info.play_session_id.clone(),
-        intro_skipper_ranges,
+        skip_ranges,
         position_ticks: resolution.position_ticks,
         is_paused: false,
         is_muted: false,
@@ -604,13 +1861,23 @@ impl SessionManager {
         audio_stream_index: resolution.audio_stream_index,
         subtitle_stream_index: resolution.subtitle_stream_index,
         play_method: resolution.play_method.to_string(),
+        mpv_duration_ticks: None,
+        can_seek: !is_growing,
+        is_growing,
+        subtitles_burned_in: matches!(resolution.image_subtitle, Some(ImageSubtitle::BurnIn(_))),
+        live_stream_id: media_source.live_stream_id.clone(),
       });
       s.last_report_time = std::time::Instant::now();
     }
 
+    // Remuxes and transcodes leave out the fonts of styled subtitles
+    let fonts_dir =
+      session_files::fetch_subtitle_fonts(client, state, generation, item_id, media_source).await;
+
     // Report playback started
+    let queue_report = QueueReport::from(&state.read().queue);
     let start_info = PlaybackStartInfo {
-      item_id: item_id.clone(),
+      item_id: item_id.to_string(),
       media_source_id: Some(media_source.id.clone()),
       play_session_id: playback_info.play_session_id.clone(),
       position_ticks: request.start_position_ticks,
@@ -620,7 +1887,14 @@ impl SessionManager {
       audio_stream_index: resolution.audio_stream_index,
       subtitle_stream_index: resolution.subtitle_stream_index,
       play_method: resolution.play_method.to_string(),
-      can_seek: true,
+      can_seek: !is_growing,
+      live_stream_id: media_source.live_stream_id.clone(),
+      run_time_ticks: item.run_time_ticks,
+      item: Some(NowPlayingItem::new(&item, item.run_time_ticks)),
+      now_playing_queue: queue_report.now_playing_queue,
+      playlist_item_id: queue_report.playlist_item_id,
+      repeat_mode: queue_report.repeat_mode,
+      playback_order: queue_report.playback_order,
     };
     client.playback().report_playback_start(&start_info).await?;
 
@@ -632,6 +1906,12 @@ impl SessionManager {
       resolution.subtitle_stream_index,
       resolution.mpv_subtitle_index
     );
+    // MPV keeps loop points from one file to the next
+    if std::mem::take(&mut state.write().ab_loop).is_set() {
+      let _ = action_tx
+        .send(MpvAction::SetAbLoop(AbLoop::default()))
+        .await;
+    }
     let _ = action_tx
       .send(MpvAction::Play {
         url,
@@ -639,10 +1919,15 @@ impl SessionManager {
         title,
         audio_index: resolution.mpv_audio_index,
         subtitle_index: resolution.mpv_subtitle_index,
+        fonts_dir,
       })
       .await;
     log::info!("MpvAction::Play sent successfully");
 
+    if let Some(ImageSubtitle::Unavailable(stream)) = resolution.image_subtitle {
+      Self::notify_image_subtitle_unavailable(action_tx, stream).await;
+    }
+
     // Load external subtitle if the selected subtitle is external
     if let Some(ext_sub_stream) = resolution.external_subtitle_stream {
       if let Some(sub_url) =
@@ -653,7 +1938,7 @@ impl SessionManager {
         log::info!(
           "Loading external subtitle: codec={:?}, url={}",
           ext_sub_stream.codec,
-          redact_url(&sub_url)
+          redact(&sub_url)
         );
         let _ = action_tx
           .send(MpvAction::AddExternalSubtitle(sub_url))
@@ -663,11 +1948,214 @@ impl SessionManager {
       }
     }
 
+    Self::refresh_user_data(client, state, generation, item_id).await;
     Ok(())
   }
 
+  /// Fetch the user's watched, favorite and rating state of the item that
+  /// just started, unless another playback took over meanwhile.
+  async fn refresh_user_data(
+    client: &JellyfinClient,
+    state: &RwLock<SessionState>,
+    generation: PlaybackGeneration,
+    item_id: &str,
+  ) {
+    match client.library().user_data(item_id).await {
+      Ok(update) => {
+        let mut s = state.write();
+        if s.playback_generation == generation {
+          s.user_data = Some(NowPlayingUserData::from(&update));
+        }
+      }
+      Err(e) => log::warn!("User data for {} unavailable: {}", item_id, e),
+    }
+  }
+
+  /// Reload the stream at the current position when a subtitle change needs an
+  /// image subtitle burned in, or a burned-in subtitle taken out again. With
+  /// `always`, the stream is reloaded regardless, e.g. after the server
+  /// address changed.
+  ///
+  /// Returns false when no reload was needed or possible; the caller then
+  /// selects the track in MPV as usual. A playback replaced meanwhile counts
+  /// as handled.
+  pub(super) async fn reload_stream(
+    client: &JellyfinClient,
+    state: &RwLock<SessionState>,
+    action_tx: &mpsc::Sender<MpvAction>,
+    subtitle_index: i32,
+    always: bool,
+  ) -> bool {
+    let (playback, title, generation, bitrate_cap) = {
+      let s = state.read();
+      let title = s
+        .current_item
+        .as_ref()
+        .map(Self::format_title)
+        .unwrap_or_default();
+      (
+        s.playback.clone(),
+        title,
+        s.playback_generation,
+        s.stall.bitrate_cap(),
+      )
+    };
+    let Some(playback) = playback else {
+      return false;
+    };
+
+    let subtitle = (subtitle_index >= 0).then_some(subtitle_index);
+    let playback_info = match client
+      .playback()
+      .get_playback_info_at_bitrate(
+        &playback.item_id,
+        playback.audio_stream_index,
+        subtitle,
+        bitrate_cap,
+      )
+      .await
+    {
+      Ok(info) => info,
+      Err(e) => {
+        log::warn!("Playback info for subtitle change unavailable: {}", e);
+        return false;
+      }
+    };
+    let Some(media_source) = playback_info
+      .media_sources
+      .iter()
+      .find(|source| playback.media_source_id.as_deref() == Some(source.id.as_str()))
+      .or_else(|| playback_info.media_sources.first())
+    else {
+      return false;
+    };
+
+    let handling = image_subtitle(media_source, subtitle);
+    let url = match handling {
+      Some(ImageSubtitle::BurnIn(stream)) => client
+        .playback()
+        .build_burn_in_stream_url(media_source, stream.index),
+      Some(ImageSubtitle::Unavailable(stream)) => {
+        Self::notify_image_subtitle_unavailable(action_tx, stream).await;
+        return false;
+      }
+      None if playback.subtitles_burned_in || always => client
+        .playback()
+        .build_stream_url(&playback.item_id, media_source),
+      None => return false,
+    };
+    let Some(url) = url else {
+      log::warn!("Failed to build stream URL for subtitle change");
+      return false;
+    };
+    let burn_in = matches!(handling, Some(ImageSubtitle::BurnIn(_)));
+
+    let streams = &media_source.media_streams;
+    let external_subtitle = subtitle.and_then(|index| {
+      streams.iter().find(|stream| {
+        stream.stream_type == "Subtitle" && stream.index == index && stream.is_external
+      })
+    });
+    let mpv_subtitle_index = if burn_in || external_subtitle.is_some() {
+      None
+    } else {
+      Some(subtitle.map_or(-1, |index| {
+        jellyfin_to_mpv_track_index(streams, "Subtitle", index)
+      }))
+    };
+    let mpv_audio_index = playback
+      .audio_stream_index
+      .filter(|index| *index >= 0)
+      .map(|index| jellyfin_to_mpv_track_index(streams, "Audio", index));
+
+    {
+      let mut s = state.write();
+      let Some(current) = s.playback_of(generation) else {
+        log::info!("Playback changed while preparing a stream reload");
+        return true;
+      };
+      current.media_source_id = Some(media_source.id.clone());
+      current.play_session_id = playback_info.play_session_id.clone();
+      current.play_method = if burn_in {
+        "Transcode"
+      } else {
+        play_method(media_source)
+      }
+      .to_string();
+      current.subtitles_burned_in = burn_in;
+      current.live_stream_id = media_source.live_stream_id.clone();
+      s.current_media_streams = media_source.media_streams.clone();
+      s.subtitle_tracks = SubtitleTracks::default();
+      if let Some(stream) = external_subtitle {
+        s.subtitle_tracks.loaded(stream.index);
+      }
+    }
+
+    let fonts_dir = session_files::fetch_subtitle_fonts(
+      client,
+      state,
+      generation,
+      &playback.item_id,
+      media_source,
+    )
+    .await;
+
+    log::info!(
+      "Reloading stream with subtitle {} (burn-in={}): {}",
+      subtitle_index,
+      burn_in,
+      redact(&url)
+    );
+    let _ = action_tx
+      .send(MpvAction::Play {
+        url,
+        start_position: ticks_to_seconds(playback.position_ticks),
+        title,
+        audio_index: mpv_audio_index,
+        subtitle_index: mpv_subtitle_index,
+        fonts_dir,
+      })
+      .await;
+
+    if let Some(stream) = external_subtitle {
+      if let Some(sub_url) =
+        client
+          .playback()
+          .build_subtitle_url(&playback.item_id, &media_source.id, stream)
+      {
+        let _ = action_tx
+          .send(MpvAction::AddExternalSubtitle(sub_url))
+          .await;
+      }
+    }
+    true
+  }
+
+  /// Tell the viewer an image subtitle can't be shown on the current stream.
+  async fn notify_image_subtitle_unavailable(
+    action_tx: &mpsc::Sender<MpvAction>,
+    stream: &MediaStream,
+  ) {
+    let name = stream
+      .display_title
+      .as_deref()
+      .or(stream.codec.as_deref())
+      .unwrap_or("Image");
+    log::warn!(
+      "Subtitle {} ({}) is image-based and the server can't burn it in",
+      stream.index,
+      name
+    );
+    let _ = action_tx
+      .send(MpvAction::ShowText {
+        text: tr!("osd-image-subtitles-unavailable", name = name),
+        duration_ms: 4000,
+      })
+      .await;
+  }
+
   /// Format media title for display in MPV.
-  fn format_title(item: &MediaItem) -> String {
+  pub(super) fn format_title(item: &MediaItem) -> String {
     match item.item_type.as_str() {
       "Episode" => {
         let series = item.series_name.as_deref().unwrap_or("Unknown");
@@ -690,79 +2178,38 @@ impl SessionManager {
   ) -> Result<(), JellyfinError> {
     log::info!("handle_playstate: command={}", request.command);
     match request.command.as_str() {
-      "Pause" => {
-        log::info!("Processing Pause command");
-        {
-          let mut s = state.write();
-          if let Some(ref mut playback) = s.playback {
-            playback.is_paused = true;
-          }
+      "Pause" | "Unpause" | "PlayPause" => {
+        log::info!("Processing {} command", request.command);
+        let paused = match request.command.as_str() {
+          "Pause" => Some(true),
+          "Unpause" => Some(false),
+          _ => None,
+        };
+        if let Err(e) = Self::apply_pause(client, state, mpv, paused).await {
+          log::error!("Failed to apply {}: {}", request.command, e);
         }
-        let _ = action_tx.send(MpvAction::Pause).await;
       }
-      "Unpause" => {
-        log::info!("Processing Unpause command");
-        {
-          let mut s = state.write();
-          if let Some(ref mut playback) = s.playback {
-            playback.is_paused = false;
-          }
-        }
-        let _ = action_tx.send(MpvAction::Resume).await;
-      }
-      "PlayPause" => {
-        // Query actual MPV state to handle cases where user paused via MPV keyboard
-        let is_paused = match mpv.get_pause().await {
-          Ok(paused) => paused,
-          Err(e) => {
-            log::warn!(
-              "Failed to get pause state from MPV: {}, using internal state",
-              e
-            );
-            let s = state.read();
-            s.playback.as_ref().map(|p| p.is_paused).unwrap_or(false)
-          }
-        };
-        log::info!("Processing PlayPause command, MPV paused={}", is_paused);
-        if is_paused {
-          {
-            let mut s = state.write();
-            if let Some(ref mut playback) = s.playback {
-              playback.is_paused = false;
-            }
-          }
-          let _ = action_tx.send(MpvAction::Resume).await;
-        } else {
-          {
-            let mut s = state.write();
-            if let Some(ref mut playback) = s.playback {
-              playback.is_paused = true;
-            }
-          }
-          let _ = action_tx.send(MpvAction::Pause).await;
-        }
-      }
-      "Seek" => {
-        if let Some(ticks) = request.seek_position_ticks {
-          let position = ticks_to_seconds(ticks);
-          {
-            let mut s = state.write();
-            if let Some(ref mut playback) = s.playback {
-              playback.position_ticks = ticks;
-            }
-          }
-          let _ = action_tx.send(MpvAction::Seek(position)).await;
+      "Seek" => {
+        let phase = state.read().phase;
+        if !phase.can_seek() {
+          log::info!("Ignoring Seek while the session is {:?}", phase);
+        } else if let Some(ticks) = request.seek_position_ticks {
+          Self::apply_seek(state, action_tx, ticks).await;
         }
       }
       "Stop" => {
         log::info!("Processing Stop command");
         // Take the playback session and report stop to Jellyfin
-        let session = {
+        let (session, item) = {
           let mut s = state.write();
-          s.playback.take()
+          s.queue.clear();
+          (s.playback.take(), s.current_item.clone())
         };
 
         if let Some(session) = session {
+          if let Some(item) = &item {
+            webhooks::fire(WebhookEvent::Stopped, item, session.position_ticks);
+          }
           let stop_info = PlaybackStopInfo {
             item_id: session.item_id,
             media_source_id: session.media_source_id,
@@ -784,8 +2231,16 @@ impl SessionManager {
         };
 
         if let Some(item) = current_item {
-          if let Err(e) =
-            Self::play_adjacent_episode(client, state, action_tx, config, &item, true, true).await
+          if let Err(e) = Self::play_adjacent_episode(
+            client,
+            state,
+            action_tx,
+            config,
+            &item,
+            true,
+            AdjacentTrigger::User,
+          )
+          .await
           {
             log::warn!("NextTrack unavailable: {}", e);
           }
@@ -801,8 +2256,16 @@ impl SessionManager {
         };
 
         if let Some(item) = current_item {
-          if let Err(e) =
-            Self::play_adjacent_episode(client, state, action_tx, config, &item, false, true).await
+          if let Err(e) = Self::play_adjacent_episode(
+            client,
+            state,
+            action_tx,
+            config,
+            &item,
+            false,
+            AdjacentTrigger::User,
+          )
+          .await
           {
             log::warn!("PreviousTrack unavailable: {}", e);
           }
@@ -826,30 +2289,77 @@ impl SessionManager {
     request: GeneralCommand,
   ) -> Result<(), JellyfinError> {
     let mut should_save_prefs = false;
+    // Track changes belong to the playback the command was sent for.
+    let generation = state.read().playback_generation;
 
     match request.name.as_str() {
       "SetVolume" => {
         if let Some(args) = request.arguments {
           if let Some(volume) = parse_command_int(args.get("Volume")) {
             // Clamp to valid player range (0-100)
-            let volume = volume.clamp(0, 100) as i32;
-            // Update session state
-            {
-              let mut s = state.write();
-              if let Some(ref mut playback) = s.playback {
-                playback.volume = volume;
-              }
-            }
-            let _ = action_tx.send(MpvAction::SetVolume(volume)).await;
+            Self::set_remote_volume(state, action_tx, volume.clamp(0, 100) as i32).await;
           }
         }
       }
+      "VolumeUp" | "VolumeDown" => {
+        let step = if request.name == "VolumeUp" {
+          VOLUME_STEP
+        } else {
+          -VOLUME_STEP
+        };
+        let current = state.read().playback.as_ref().map(|p| p.volume);
+        if let Some(current) = current {
+          Self::set_remote_volume(state, action_tx, (current + step).clamp(0, 100)).await;
+        }
+      }
       "ToggleMute" => {
         let _ = action_tx.send(MpvAction::ToggleMute).await;
       }
       "ToggleFullscreen" => {
         let _ = action_tx.send(MpvAction::ToggleFullscreen).await;
       }
+      "SetRepeatMode" => {
+        let repeat_mode = request
+          .arguments
+          .as_ref()
+          .and_then(|args| args.get("RepeatMode"))
+          .and_then(|value| serde_json::from_value::<RepeatMode>(value.clone()).ok());
+        if let Some(repeat_mode) = repeat_mode {
+          log::info!("SetRepeatMode: {:?}", repeat_mode);
+          state.write().queue.set_repeat_mode(repeat_mode);
+          Self::report_progress(client, state).await;
+        } else {
+          log::warn!("SetRepeatMode without a valid RepeatMode argument");
+        }
+      }
+      "SetShuffleQueue" | "ToggleShuffle" => {
+        let order = {
+          let mut s = state.write();
+          let order = if request.name == "ToggleShuffle" {
+            Some(match s.queue.playback_order() {
+              PlaybackOrder::Default => PlaybackOrder::Shuffle,
+              PlaybackOrder::Shuffle => PlaybackOrder::Default,
+            })
+          } else {
+            request
+              .arguments
+              .as_ref()
+              .and_then(|args| args.get("ShuffleMode"))
+              .and_then(|value| value.as_str())
+              .and_then(parse_shuffle_mode)
+          };
+          if let Some(order) = order {
+            s.queue.set_playback_order(order);
+          }
+          order
+        };
+        if let Some(order) = order {
+          log::info!("{}: playback order {:?}", request.name, order);
+          Self::report_progress(client, state).await;
+        } else {
+          log::warn!("SetShuffleQueue without a valid ShuffleMode argument");
+        }
+      }
       "SetAudioStreamIndex" => {
         if let Some(args) = &request.arguments {
           let index = parse_command_int(args.get("Index"));
@@ -858,32 +2368,11 @@ impl SessionManager {
             // Update playback state and save series preference
             let mpv_index = {
               let mut s = state.write();
-              if let Some(ref mut playback) = s.playback {
-                playback.audio_stream_index = Some(index as i32);
-              }
-              // Save preference for series (clone to avoid borrow issues)
-              let series_id = s.current_series_id.clone();
-              if let Some(series_id) = series_id {
-                // Find the language and title of the selected track
-                let track_info = s
-                  .current_media_streams
-                  .iter()
-                  .find(|stream| stream.stream_type == "Audio" && stream.index == index as i32)
-                  .map(|stream| (stream.language.clone(), stream.display_title.clone()));
-
-                if let Some((lang, title)) = track_info {
-                  log::info!(
-                    "Saving audio preference for series {}: lang={:?}, title={:?}",
-                    series_id,
-                    lang,
-                    title
-                  );
-                  let pref = s.series_preferences.entry(series_id).or_default();
-                  pref.audio_language = lang;
-                  pref.audio_title = title;
-                  should_save_prefs = true;
-                }
-              }
+              let Some(changed) = s.remember_audio_track(generation, index as i32) else {
+                log::info!("SetAudioStreamIndex: playback changed; ignoring");
+                return Ok(());
+              };
+              should_save_prefs = changed;
               // Convert Jellyfin stream index to MPV track index
               jellyfin_to_mpv_track_index(&s.current_media_streams, "Audio", index as i32)
             };
@@ -899,113 +2388,77 @@ impl SessionManager {
           if let Some(index) = index {
             log::info!("SetSubtitleStreamIndex: {} (Jellyfin index)", index);
 
+            // Image subtitles on a remux or transcode need the stream reloaded
+            let needs_stream_reload = {
+              let s = state.read();
+              s.playback.as_ref().is_some_and(|playback| {
+                playback.subtitles_burned_in
+                  || (playback.play_method != "DirectPlay"
+                    && s.current_media_streams.iter().any(|stream| {
+                      stream.index == index as i32
+                        && !stream.is_external
+                        && stream.is_image_subtitle()
+                    }))
+              })
+            };
+
             // Collect data we need while holding the lock
-            let (mpv_action, item_id, media_source_id) = {
+            let (mpv_action, external) = {
               let mut s = state.write();
+              let Some(changed) = s.remember_subtitle_track(generation, index as i32) else {
+                log::info!("SetSubtitleStreamIndex: playback changed; ignoring");
+                return Ok(());
+              };
+              should_save_prefs = changed;
 
-              // Update playback state
-              if let Some(ref mut playback) = s.playback {
-                playback.subtitle_stream_index = Some(index as i32);
-              }
-
-              // Save preference for series
-              let series_id = s.current_series_id.clone();
-              if let Some(series_id) = series_id {
-                if index == -1 {
-                  log::info!(
-                    "Saving subtitle disabled preference for series {}",
-                    series_id
-                  );
-                  let pref = s.series_preferences.entry(series_id).or_default();
-                  pref.is_subtitle_enabled = false;
-                  pref.subtitle_preference_set = true;
-                  pref.subtitle_language = None;
-                  pref.subtitle_title = None;
-                  should_save_prefs = true;
-                } else {
-                  let track_info = s
-                    .current_media_streams
-                    .iter()
-                    .find(|stream| stream.stream_type == "Subtitle" && stream.index == index as i32)
-                    .map(|stream| (stream.language.clone(), stream.display_title.clone()));
-
-                  let pref = s.series_preferences.entry(series_id.clone()).or_default();
-                  if let Some((lang, title)) = track_info {
-                    log::info!(
-                      "Saving subtitle preference for series {}: lang={:?}, title={:?}",
-                      series_id,
-                      lang,
-                      title
-                    );
-                    pref.is_subtitle_enabled = true;
-                    pref.subtitle_preference_set = true;
-                    pref.subtitle_language = lang;
-                    pref.subtitle_title = title;
-                  } else {
-                    pref.is_subtitle_enabled = true;
-                    pref.subtitle_preference_set = true;
-                  }
-                  should_save_prefs = true;
+              // MPV's own and already added tracks are selected by id;
+              // an external stream MPV doesn't have yet is added first.
+              match s
+                .subtitle_tracks
+                .select(&s.current_media_streams, index as i32)
+              {
+                SubtitleSelection::Disable => (Some(MpvAction::SetSubtitleTrack(-1)), None),
+                SubtitleSelection::Select(mpv_index) => {
+                  (Some(MpvAction::SetSubtitleTrack(mpv_index)), None)
                 }
-              }
-
-              // Determine action: external subtitle via sub-add or internal via sid
-              if index == -1 {
-                // Disable subtitles
-                (MpvAction::SetSubtitleTrack(-1), None, None)
-              } else {
-                // Find the subtitle stream
-                let external_stream = s
-                  .current_media_streams
-                  .iter()
-                  .find(|stream| {
-                    stream.stream_type == "Subtitle"
-                      && stream.index == index as i32
-                      && stream.is_external
-                  })
-                  .cloned();
-
-                if let Some(ext_stream) = external_stream {
-                  // External subtitle - need to use sub-add
-                  let item_id = s.playback.as_ref().map(|p| p.item_id.clone());
-                  let media_source_id = s.playback.as_ref().and_then(|p| p.media_source_id.clone());
-                  // Return placeholder action - we'll build the URL outside the lock
+                SubtitleSelection::Load(stream) => {
+                  let source = s.playback.as_ref().and_then(|playback| {
+                    Some((playback.item_id.clone(), playback.media_source_id.clone()?))
+                  });
                   (
-                    MpvAction::SetSubtitleTrack(-1),
-                    item_id,
-                    media_source_id.map(|id| (id, ext_stream)),
+                    None,
+                    source
+                      .map(|(item_id, media_source_id)| (item_id, media_source_id, stream.clone())),
                   )
-                } else {
-                  // Internal subtitle - convert index and use sid
-                  let mpv_idx =
-                    jellyfin_to_mpv_track_index(&s.current_media_streams, "Subtitle", index as i32);
-                  (MpvAction::SetSubtitleTrack(mpv_idx), None, None)
                 }
               }
             };
 
             // Handle the action
-            match (item_id, media_source_id) {
-              (Some(item_id), Some((ms_id, ext_stream))) => {
-                // External subtitle - build URL and use sub-add
-                if let Some(sub_url) =
-                  client
-                    .playback()
-                    .build_subtitle_url(&item_id, &ms_id, &ext_stream)
-                {
-                  log::info!("SetSubtitleStreamIndex: loading external subtitle via sub-add");
-                  let _ = action_tx
-                    .send(MpvAction::AddExternalSubtitle(sub_url))
-                    .await;
-                } else {
-                  log::warn!("Failed to build external subtitle URL");
-                }
-              }
-              _ => {
-                // Internal subtitle or disable
-                log::info!("SetSubtitleStreamIndex: sending {:?}", mpv_action);
-                let _ = action_tx.send(mpv_action).await;
+            if needs_stream_reload
+              && Self::reload_stream(client, state, action_tx, index as i32, false).await
+            {
+              log::info!(
+                "SetSubtitleStreamIndex: reloaded stream for subtitle {}",
+                index
+              );
+            } else if let Some((item_id, media_source_id, stream)) = external {
+              if let Some(sub_url) =
+                client
+                  .playback()
+                  .build_subtitle_url(&item_id, &media_source_id, &stream)
+              {
+                log::info!("SetSubtitleStreamIndex: loading external subtitle via sub-add");
+                state.write().subtitle_tracks.loaded(stream.index);
+                let _ = action_tx
+                  .send(MpvAction::AddExternalSubtitle(sub_url))
+                  .await;
+              } else {
+                log::warn!("Failed to build external subtitle URL");
               }
+            } else if let Some(mpv_action) = mpv_action {
+              log::info!("SetSubtitleStreamIndex: sending {:?}", mpv_action);
+              let _ = action_tx.send(mpv_action).await;
             }
           }
         }
@@ -1027,7 +2480,7 @@ impl SessionManager {
   fn save_preferences_static(state: &RwLock<SessionState>, app_handle: &AppHandle) {
     let prefs = {
       let s = state.read();
-      s.series_preferences.clone()
+      s.track_preferences.clone()
     };
 
     match app_handle.store(PREFERENCES_STORE_FILE) {
@@ -1056,13 +2509,17 @@ impl SessionManager {
   /// - Periodic time-pos reporting (every 10s) for progress bar
   /// - End-file events for auto-play next episode
   /// - Client-message events for keyboard shortcuts
+  /// - Cache-speed samples for bandwidth metering
+  /// - Buffering stalls, which are checked on every cache-speed sample
   fn start_mpv_event_listener(&self) {
     let mpv = self.mpv.clone();
     let client = self.client.clone();
     let state = self.state.clone();
     let action_tx = self.action_tx.clone();
     let config = self.config.clone();
+    let bandwidth = self.bandwidth.clone();
     let app_handle = self.app_handle.clone();
+    let shutdown = self.shutdown.clone();
 
     tokio::spawn(async move {
       log::info!("MPV event listener started");
@@ -1070,7 +2527,7 @@ impl SessionManager {
       // Wait a bit for MPV to connect before trying to get events
       tokio::time::sleep(std::time::Duration::from_secs(1)).await;
 
-      loop {
+      while !shutdown.is_cancelled() {
         // Try to get the event receiver
         let event_rx = match mpv.events() {
           Some(rx) => rx,
@@ -1082,71 +2539,138 @@ impl SessionManager {
         };
 
         log::info!("Got MPV event receiver, setting up property observations...");
-
-        // Observer IDs for different properties
-        const OBS_PAUSE: i64 = 1;
-        const OBS_VOLUME: i64 = 2;
-        const OBS_MUTE: i64 = 3;
-        const OBS_TIME_POS: i64 = 4;
-
-        // Set up property observations
-        if let Err(e) = mpv.observe_property(OBS_PAUSE, "pause").await {
-          log::warn!("Failed to observe pause: {}", e);
-        }
-        if let Err(e) = mpv.observe_property(OBS_VOLUME, "volume").await {
-          log::warn!("Failed to observe volume: {}", e);
-        }
-        if let Err(e) = mpv.observe_property(OBS_MUTE, "mute").await {
-          log::warn!("Failed to observe mute: {}", e);
-        }
-        if let Err(e) = mpv.observe_property(OBS_TIME_POS, "time-pos").await {
-          log::warn!("Failed to observe time-pos: {}", e);
-        }
-
-        log::info!("Property observations set up, listening for events...");
-
-        // Track last progress report time to throttle time-pos updates
-        let mut last_progress_report = std::time::Instant::now();
-        let progress_report_interval = std::time::Duration::from_secs(5);
+        let mut connection = MpvConnection::open(&*mpv, &state).await;
+        Self::report_activity(&client, &state, "MPV started").await;
 
         // Process events
-        while let Ok(event) = event_rx.recv().await {
+        loop {
+          let event = match connection.seeks.deadline() {
+            Some(deadline) => {
+              let deadline = tokio::time::Instant::from_std(deadline);
+              match tokio::time::timeout_at(deadline, Self::next_mpv_event(&event_rx, &shutdown))
+                .await
+              {
+                Ok(event) => event,
+                // The position stopped changing after a seek, e.g. while paused.
+                Err(_) => {
+                  if connection.seeks.settle(std::time::Instant::now()) {
+                    Self::report_progress(&client, &state).await;
+                    Self::emit_now_playing_changed(&app_handle, &mpv, &state).await;
+                  }
+                  continue;
+                }
+              }
+            }
+            None => Self::next_mpv_event(&event_rx, &shutdown).await,
+          };
+          let Some(event) = event else {
+            break;
+          };
           match event.event.as_str() {
             "property-change" => {
               let property_name = event.name.as_deref().unwrap_or("");
+              if property_name == "duration" {
+                Self::handle_duration_change(&app_handle, &state, &event);
+                Self::add_credits_fallback(&mpv, &state, &config).await;
+                continue;
+              }
+              if property_name == "ab-loop-a" || property_name == "ab-loop-b" {
+                state
+                  .write()
+                  .ab_loop
+                  .update(property_name, event.data.as_ref());
+                continue;
+              }
+              if property_name == "cache-speed" || property_name == "paused-for-cache" {
+                if property_name == "cache-speed" {
+                  Self::record_cache_speed(&bandwidth, &state, &event);
+                }
+                stall::handle_buffering(
+                  &client,
+                  &state,
+                  &action_tx,
+                  &config,
+                  &bandwidth,
+                  &app_handle,
+                  &event,
+                )
+                .await;
+                continue;
+              }
               let decision = property_report_decision(property_name);
               let should_report = if decision == PropertyReportDecision::Ignore {
                 false
               } else {
-                Self::update_state_from_property(&state, &event);
+                let volume = VolumeMapping::from_config(&config.read());
+                let was_paused = Self::is_paused(&state);
+                Self::update_state_from_property(&state, &event, &volume);
+                if property_name == "pause" {
+                  Self::track_pause_phase(&state);
+                  Self::fire_pause_webhook(&state, was_paused);
+                  Self::rewind_after_pause(&state, &action_tx, &config, &event).await;
+                }
+                if property_name == "volume" {
+                  Self::enforce_volume_cap(&action_tx, &event, &volume).await;
+                }
                 if property_name == "time-pos" {
-                  Self::apply_intro_skipper(&state, &action_tx, &event).await;
+                  Self::apply_skip_ranges(&state, &action_tx, &event).await;
+                  Self::advance_credits_countdown(&client, &state, &action_tx, &config, &event)
+                    .await;
+                  Self::refresh_growing_recording(
+                    &client,
+                    &state,
+                    &mut connection.last_recording_refresh,
+                  )
+                  .await;
                 }
 
-                let now = std::time::Instant::now();
-                let should_report = should_report_progress(
-                  decision,
-                  now,
-                  last_progress_report,
-                  progress_report_interval,
-                );
-                if should_report && decision == PropertyReportDecision::ReportWhenThrottleElapsed {
-                  last_progress_report = now;
-                }
-                should_report
+                let decision = if property_name == "time-pos" {
+                  connection.seek_report_decision(&state, &event, decision)
+                } else {
+                  decision
+                };
+                connection.should_report(&state, decision)
               };
 
               if should_report {
                 Self::report_progress(&client, &state).await;
                 Self::emit_now_playing_changed(&app_handle, &mpv, &state).await;
+                Self::emit_bandwidth_updated(&app_handle, &bandwidth);
+              }
+            }
+            "playback-restart" => {
+              // The file MPV was opening is ready, or a seek finished.
+              let mut s = state.write();
+              if s.phase == SessionPhase::Loading {
+                let phase = s.open_phase();
+                s.enter(phase);
               }
             }
             "end-file" => {
-              Self::handle_end_file_event(&event, &client, &state, &action_tx, &config).await;
+              Self::track_end_file_phase(&state, &event);
+              Self::handle_end_file_event(
+                &event,
+                &client,
+                &state,
+                &action_tx,
+                &config,
+                &app_handle,
+              )
+              .await;
+              Self::report_activity(&client, &state, "playback ended").await;
               Self::emit_now_playing_changed(&app_handle, &mpv, &state).await;
             }
             "client-message" => {
-              Self::handle_client_message_event(&event, &client, &state, &action_tx, &config).await;
+              Self::report_activity(&client, &state, "keyboard shortcut").await;
+              Self::handle_client_message_event(
+                &event,
+                &client,
+                &state,
+                &action_tx,
+                &config,
+                &app_handle,
+              )
+              .await;
               Self::emit_now_playing_changed(&app_handle, &mpv, &state).await;
             }
             _ => {
@@ -1155,18 +2679,167 @@ impl SessionManager {
           }
         }
 
+        if shutdown.is_cancelled() {
+          // Observations registered by a newer MPV connection are not ours.
+          if mpv
+            .events()
+            .is_some_and(|current| Arc::ptr_eq(&current, &event_rx))
+          {
+            connection.close(&*mpv).await;
+          }
+          break;
+        }
+
         // MPV event receiver closed - this means MPV died or disconnected
+        if mpv.is_connected() {
+          // A new MPV already took over; its playback and skip ranges stay.
+          log::info!("MPV event receiver closed after a new MPV connected");
+          continue;
+        }
         // Clear playback context and notify Jellyfin
         log::warn!("MPV event receiver closed, clearing playback context...");
+        let was_playing = state.read().playback.is_some();
         Self::clear_playback_context(&client, &state).await;
+        if was_playing {
+          metrics::increment(Counter::MpvCrashes);
+          PlaybackError::report(
+            &app_handle,
+            PlaybackErrorStage::IpcLost,
+            "MPV event stream closed",
+          );
+          os_notification::notify(
+            &app_handle,
+            OsNotificationKind::MpvCrashed,
+            &tr!("os-mpv-crashed-body"),
+          );
+        }
+        Self::report_activity(&client, &state, "MPV stopped").await;
         Self::emit_now_playing_changed(&app_handle, &mpv, &state).await;
         tokio::time::sleep(std::time::Duration::from_secs(2)).await;
       }
+      log::info!("MPV event listener stopped");
     });
   }
 
+  /// The next MPV event; None once MPV disconnected or the session stopped.
+  async fn next_mpv_event(
+    events: &EventQueue,
+    shutdown: &CancellationToken,
+  ) -> Option<crate::mpv::MpvEvent> {
+    tokio::select! {
+      event = events.recv() => event,
+      () = shutdown.cancelled() => None,
+    }
+  }
+
+  /// Ping the server so the dashboard shows this session as active instead
+  /// of going stale between playbacks.
+  async fn report_activity(client: &JellyfinClient, state: &RwLock<SessionState>, reason: &str) {
+    if !client.is_connected() || !client.supports_remote_control() {
+      return;
+    }
+    let play_session_id = state
+      .read()
+      .playback
+      .as_ref()
+      .and_then(|playback| playback.play_session_id.clone());
+    log::debug!("Reporting session activity: {}", reason);
+    if let Err(e) = client
+      .playback()
+      .ping_session(play_session_id.as_deref())
+      .await
+    {
+      log::warn!("Failed to report session activity ({}): {}", reason, e);
+    }
+  }
+
+  fn is_paused(state: &RwLock<SessionState>) -> Option<bool> {
+    state
+      .read()
+      .playback
+      .as_ref()
+      .map(|playback| playback.is_paused)
+  }
+
+  /// Follow MPV's pause state while a file is open; a stall or a load in
+  /// progress settles on it once it ends.
+  fn track_pause_phase(state: &RwLock<SessionState>) {
+    let mut s = state.write();
+    if matches!(s.phase, SessionPhase::Playing | SessionPhase::Paused) {
+      let phase = s.open_phase();
+      s.enter(phase);
+    }
+  }
+
+  /// Return to idle when MPV closes a file. While a new file is loading, the
+  /// end of the one it replaces is ignored unless the new one failed.
+  fn track_end_file_phase(state: &RwLock<SessionState>, event: &crate::mpv::MpvEvent) {
+    let mut s = state.write();
+    if s.phase == SessionPhase::Loading && event.reason.as_deref() != Some("error") {
+      return;
+    }
+    s.playback_ended();
+  }
+
+  /// Tell webhooks about a pause or resume, ignoring repeated values.
+  fn fire_pause_webhook(state: &RwLock<SessionState>, was_paused: Option<bool>) {
+    let s = state.read();
+    let (Some(playback), Some(item)) = (s.playback.as_ref(), s.current_item.as_ref()) else {
+      return;
+    };
+    if was_paused == Some(playback.is_paused) {
+      return;
+    }
+    let event = if playback.is_paused {
+      WebhookEvent::Paused
+    } else {
+      WebhookEvent::Resumed
+    };
+    webhooks::fire(event, item, playback.position_ticks);
+  }
+
+  /// Remember when MPV paused, and when it resumes after a long enough pause
+  /// seek back a little so the viewer picks the scene up again. Every pause
+  /// change reaches MPV, so its events see all of them.
+  async fn rewind_after_pause(
+    state: &RwLock<SessionState>,
+    action_tx: &mpsc::Sender<MpvAction>,
+    config: &RwLock<AppConfig>,
+    event: &crate::mpv::MpvEvent,
+  ) {
+    let Some(paused) = event.data.as_ref().and_then(|data| data.as_bool()) else {
+      return;
+    };
+    let rewind = ResumeRewind::from_config(&config.read());
+    let target = {
+      let mut s = state.write();
+      if paused {
+        s.paused_at.get_or_insert_with(std::time::Instant::now);
+        return;
+      }
+      let paused_at = s.paused_at.take();
+      match (rewind, paused_at, s.playback.as_ref()) {
+        (Some(rewind), Some(paused_at), Some(playback)) if playback.can_seek => rewind.after_pause(
+          paused_at.elapsed(),
+          ticks_to_seconds(playback.position_ticks),
+        ),
+        _ => None,
+      }
+    };
+    if let Some(position) = target {
+      log::info!("Resumed after a long pause; rewinding to {:.1}s", position);
+      let _ = action_tx.send(MpvAction::Seek(position)).await;
+    }
+  }
+
   /// Update session state from a property-change event.
-  fn update_state_from_property(state: &RwLock<SessionState>, event: &crate::mpv::MpvEvent) {
+  ///
+  /// MPV volume is stored in the remote 0-100 domain reported to the server.
+  fn update_state_from_property(
+    state: &RwLock<SessionState>,
+    event: &crate::mpv::MpvEvent,
+    volume: &VolumeMapping,
+  ) {
     let property_name = event.name.as_deref().unwrap_or("");
     let data = match &event.data {
       Some(d) => d,
@@ -1179,88 +2852,436 @@ impl SessionManager {
       None => return,
     };
 
+    if property_name == "volume" {
+      if let Some(mpv_volume) = data.as_f64() {
+        let remote = serde_json::json!(volume.to_remote(mpv_volume));
+        apply_property_update(playback, property_name, &remote);
+      }
+      return;
+    }
     apply_property_update(playback, property_name, data);
   }
 
-  /// Apply Intro Skipper seek decisions for a time-position update.
-  async fn apply_intro_skipper(
+  /// Credit bytes MPV read at its last reported speed to the playing item.
+  fn record_cache_speed(
+    bandwidth: &RwLock<BandwidthMeter>,
     state: &RwLock<SessionState>,
+    event: &crate::mpv::MpvEvent,
+  ) {
+    let speed = event
+      .data
+      .as_ref()
+      .and_then(|data| data.as_f64())
+      .unwrap_or(0.0);
+    let item_id = state
+      .read()
+      .playback
+      .as_ref()
+      .map(|playback| playback.item_id.clone());
+    bandwidth
+      .write()
+      .record_speed(item_id.as_deref(), speed, std::time::Instant::now());
+  }
+
+  fn emit_bandwidth_updated(app_handle: &AppHandle, bandwidth: &RwLock<BandwidthMeter>) {
+    let event = BandwidthUpdated {
+      stats: bandwidth.read().stats(std::time::Instant::now()),
+    };
+    if let Err(e) = event.emit(app_handle) {
+      log::error!("Failed to emit bandwidth stats: {}", e);
+    }
+  }
+
+  /// Pull MPV back under the configured cap when something raised it past it.
+  async fn enforce_volume_cap(
     action_tx: &mpsc::Sender<MpvAction>,
     event: &crate::mpv::MpvEvent,
+    volume: &VolumeMapping,
   ) {
-    let intro_skipper_config = {
-      let state = state.read();
-      state.effective_intro_skipper_config.clone()
+    let Some(mpv_volume) = event.data.as_ref().and_then(|data| data.as_f64()) else {
+      return;
     };
+    if volume.exceeds_cap(mpv_volume) {
+      log::info!("MPV volume {} is above the configured cap", mpv_volume);
+      let _ = action_tx.send(MpvAction::SetVolume(100)).await;
+    }
+  }
+
+  /// Store a remote volume and apply it to MPV.
+  async fn set_remote_volume(
+    state: &RwLock<SessionState>,
+    action_tx: &mpsc::Sender<MpvAction>,
+    volume: i32,
+  ) {
+    if let Some(ref mut playback) = state.write().playback {
+      playback.volume = volume;
+    }
+    let _ = action_tx.send(MpvAction::SetVolume(volume)).await;
+  }
 
-    if intro_skipper_config.mode == IntroSkipperMode::Off {
+  /// Add a credits range from the file's chapters or tail to an episode the
+  /// server has no credits range for, once its duration is known.
+  async fn add_credits_fallback(
+    mpv: &MpvClient,
+    state: &RwLock<SessionState>,
+    config: &RwLock<AppConfig>,
+  ) {
+    let (enabled, tail_seconds) = {
+      let config = config.read();
+      (config.credits_fallback, config.credits_fallback_seconds)
+    };
+    if !enabled {
       return;
     }
 
-    if event.name.as_deref() != Some("time-pos") {
+    let (duration_seconds, generation) = {
+      let s = state.read();
+      let is_episode = s
+        .current_item
+        .as_ref()
+        .is_some_and(|item| item.item_type == "Episode");
+      let Some(playback) = s.playback.as_ref() else {
+        return;
+      };
+      if !is_episode || playback.is_growing || has_credits_range(playback) {
+        return;
+      }
+      let Some(duration_ticks) = playback.mpv_duration_ticks else {
+        return;
+      };
+      (ticks_to_seconds(duration_ticks), s.playback_generation)
+    };
+
+    let chapters = Self::mpv_chapters(mpv).await;
+    let Some(range) = credits_fallback_range(&chapters, duration_seconds, tail_seconds) else {
+      log::debug!("No credits chapter or fallback tail for the current item");
       return;
+    };
+    let mut s = state.write();
+    if let Some(playback) = s
+      .playback_of(generation)
+      .filter(|playback| !has_credits_range(playback))
+    {
+      log::info!(
+        "Using fallback credits range {:.1}s-{:.1}s",
+        range.start_seconds,
+        range.end_seconds
+      );
+      playback.skip_ranges.push(range);
     }
+  }
 
-    let Some(position_seconds) = event.data.as_ref().and_then(|data| data.as_f64()) else {
+  /// Store MPV's duration and warn once per item when it disagrees with Jellyfin.
+  fn handle_duration_change(
+    app_handle: &AppHandle,
+    state: &RwLock<SessionState>,
+    event: &crate::mpv::MpvEvent,
+  ) {
+    let Some(data) = &event.data else {
       return;
     };
 
-    match intro_skipper_config.mode {
-      IntroSkipperMode::Automatic => {
-        let seek_target = {
-          let mut s = state.write();
-          s.playback.as_mut().and_then(|playback| {
-            evaluate_skip(position_seconds, &mut playback.intro_skipper_ranges)
-          })
-        };
+    let mismatch = {
+      let mut s = state.write();
+      let runtime_ticks = s.current_item.as_ref().and_then(|item| item.run_time_ticks);
+      let title = s.current_item.as_ref().map(Self::format_title);
+      let Some(playback) = s.playback.as_mut() else {
+        return;
+      };
+      let first_report = playback.mpv_duration_ticks.is_none();
+      apply_property_update(playback, "duration", data);
 
-        if let Some(seek_target) = seek_target {
-          log::info!(
-            "Intro Skipper seeking from {:.3}s to {:.3}s",
-            position_seconds,
-            seek_target
-          );
-          let _ = action_tx.send(MpvAction::Seek(seek_target)).await;
+      match (first_report, runtime_ticks, playback.mpv_duration_ticks) {
+        // A growing recording's duration trails its scheduled runtime by design.
+        (true, Some(runtime), Some(duration))
+          if !playback.is_growing && is_duration_mismatch(runtime, duration) =>
+        {
+          Some((title.unwrap_or_default(), runtime, duration))
         }
+        _ => None,
       }
-      IntroSkipperMode::Manual => {
-        let prompt_kind = {
-          let mut s = state.write();
-          s.playback.as_mut().and_then(|playback| {
-            evaluate_skip_prompt(position_seconds, &mut playback.intro_skipper_ranges)
-          })
-        };
+    };
 
-        if let Some(kind) = prompt_kind {
-          let _ = action_tx
-            .send(MpvAction::ShowText {
-              text: format!(
-                "{} available - press {} to skip",
-                intro_skipper_label(kind),
-                intro_skipper_config.keybind_intro_skip
-              ),
-              duration_ms: 3000,
-            })
-            .await;
-        }
-      }
-      IntroSkipperMode::Off => {}
+    if let Some((title, runtime, duration)) = mismatch {
+      log::warn!(
+        "Duration mismatch for {}: MPV reports {} ticks, Jellyfin runtime is {} ticks",
+        title,
+        duration,
+        runtime
+      );
+      AppNotification::warning(
+        app_handle,
+        tr!(
+          "notify-duration-mismatch",
+          title = title,
+          mpv_minutes = (ticks_to_seconds(duration) / 60.0).round(),
+          server_minutes = (ticks_to_seconds(runtime) / 60.0).round()
+        ),
+      );
     }
   }
 
-  /// Report current playback progress to Jellyfin.
-  async fn report_progress(client: &JellyfinClient, state: &RwLock<SessionState>) {
-    let session = {
+  /// Re-fetch an in-progress recording so its runtime stays current, and
+  /// allow seeking again once the recording has finished.
+  async fn refresh_growing_recording(
+    client: &JellyfinClient,
+    state: &RwLock<SessionState>,
+    last_refresh: &mut std::time::Instant,
+  ) {
+    let (item_id, generation) = {
       let s = state.read();
-      s.playback.clone()
+      match s.playback.as_ref() {
+        Some(playback) if playback.is_growing => (playback.item_id.clone(), s.playback_generation),
+        _ => return,
+      }
     };
-
-    let Some(session) = session else {
+    if last_refresh.elapsed() < RECORDING_REFRESH_INTERVAL {
       return;
-    };
+    }
+    *last_refresh = std::time::Instant::now();
 
-    let progress = PlaybackProgressInfo {
-      item_id: session.item_id.clone(),
+    let item = match client.playback().refresh_item(&item_id).await {
+      Ok(item) => item,
+      Err(e) => {
+        log::warn!("Failed to refresh recording {}: {}", item_id, e);
+        return;
+      }
+    };
+
+    let mut s = state.write();
+    let Some(playback) = s.playback_of(generation) else {
+      return;
+    };
+    if !item.is_recording_in_progress() {
+      log::info!("Recording {} finished; seeking enabled", item_id);
+      playback.is_growing = false;
+      playback.can_seek = true;
+    }
+    s.current_item = Some(item);
+  }
+
+  /// Saved track preferences, sorted by key.
+  pub fn track_preferences(&self) -> Vec<(String, TrackPreference)> {
+    let mut preferences: Vec<(String, TrackPreference)> = self
+      .state
+      .read()
+      .track_preferences
+      .iter()
+      .map(|(key, preference)| (key.clone(), preference.clone()))
+      .collect();
+    preferences.sort_by(|(a, _), (b, _)| a.cmp(b));
+    preferences
+  }
+
+  /// Fill in missing series names of saved preferences from the server.
+  ///
+  /// Preferences saved before labels existed only carry the series id.
+  pub async fn resolve_track_preference_labels(&self) {
+    let unlabeled: Vec<String> = self
+      .state
+      .read()
+      .track_preferences
+      .iter()
+      .filter(|(key, preference)| {
+        preference.label.is_none() && scope_of_key(key) == TrackPreferenceScope::Series
+      })
+      .map(|(key, _)| key.clone())
+      .collect();
+    if unlabeled.is_empty() {
+      return;
+    }
+
+    let mut resolved = Vec::new();
+    for series_id in unlabeled {
+      match self.client.playback().get_item(&series_id).await {
+        Ok(series) => resolved.push((series_id, series.name)),
+        Err(e) => log::warn!("Could not resolve series {}: {}", series_id, e),
+      }
+    }
+    if resolved.is_empty() {
+      return;
+    }
+
+    {
+      let mut s = self.state.write();
+      for (series_id, name) in resolved {
+        if let Some(preference) = s.track_preferences.get_mut(&series_id) {
+          preference.label = Some(name);
+        }
+      }
+    }
+    Self::save_preferences_static(&self.state, &self.app_handle);
+  }
+
+  /// Replace a saved track preference, keeping its label; false when `key` wasn't saved.
+  pub fn update_track_preference(&self, key: &str, mut preference: TrackPreference) -> bool {
+    {
+      let mut s = self.state.write();
+      let Some(existing) = s.track_preferences.get_mut(key) else {
+        return false;
+      };
+      preference.label = existing.label.take();
+      *existing = preference;
+    }
+    Self::save_preferences_static(&self.state, &self.app_handle);
+    true
+  }
+
+  /// Forget a saved track preference; false when `key` wasn't saved.
+  pub fn delete_track_preference(&self, key: &str) -> bool {
+    let removed = self.state.write().track_preferences.remove(key).is_some();
+    if removed {
+      Self::save_preferences_static(&self.state, &self.app_handle);
+    }
+    removed
+  }
+
+  /// Runtime and MPV duration of the current item, for diagnostics.
+  pub fn duration_report(&self) -> Option<DurationReport> {
+    let s = self.state.read();
+    let playback = s.playback.as_ref()?;
+    Some(DurationReport {
+      item_id: playback.item_id.clone(),
+      jellyfin_runtime_ticks: s.current_item.as_ref().and_then(|item| item.run_time_ticks),
+      mpv_duration_ticks: playback.mpv_duration_ticks,
+    })
+  }
+
+  /// Apply skip range decisions for a time-position update.
+  async fn apply_skip_ranges(
+    state: &RwLock<SessionState>,
+    action_tx: &mpsc::Sender<MpvAction>,
+    event: &crate::mpv::MpvEvent,
+  ) {
+    if event.name.as_deref() != Some("time-pos") {
+      return;
+    }
+
+    let Some(position_seconds) = event.data.as_ref().and_then(|data| data.as_f64()) else {
+      return;
+    };
+
+    let (action, keybind) = {
+      let mut s = state.write();
+      let policies = s.effective_intro_skipper_config.policies();
+      let keybind = s.effective_intro_skipper_config.keybind_intro_skip.clone();
+      let action = s.playback.as_mut().and_then(|playback| {
+        evaluate_skip_ranges(position_seconds, &mut playback.skip_ranges, &policies)
+      });
+      (action, keybind)
+    };
+
+    match action {
+      Some(SkipAction::Seek(decision)) => {
+        log::info!(
+          "Skipping {:?} from {:.3}s to {:.3}s",
+          decision.kind,
+          position_seconds,
+          decision.seek_target
+        );
+        let _ = action_tx.send(MpvAction::Seek(decision.seek_target)).await;
+      }
+      Some(SkipAction::Prompt(kind)) => {
+        let _ = action_tx
+          .send(MpvAction::ShowText {
+            text: skip_available_text(kind, &keybind),
+            duration_ms: 3000,
+          })
+          .await;
+      }
+      Some(SkipAction::PlayNext(kind)) => {
+        log::info!(
+          "{:?} started at {:.3}s; starting auto-next",
+          kind,
+          position_seconds
+        );
+        state.write().credits_countdown = Some(CreditsCountdown::new(position_seconds));
+      }
+      None => {}
+    }
+  }
+
+  /// Run the auto-next countdown started by the credits, then continue playback.
+  async fn advance_credits_countdown(
+    client: &JellyfinClient,
+    state: &RwLock<SessionState>,
+    action_tx: &mpsc::Sender<MpvAction>,
+    config: &RwLock<AppConfig>,
+    event: &crate::mpv::MpvEvent,
+  ) {
+    let Some(position_seconds) = event.data.as_ref().and_then(|data| data.as_f64()) else {
+      return;
+    };
+    let countdown_seconds = config.read().auto_next_countdown_seconds.unwrap_or(0);
+
+    let (step, item) = {
+      let mut s = state.write();
+      let item = s.current_item.clone();
+      let Some(countdown) = s.credits_countdown.as_mut() else {
+        return;
+      };
+      let step = countdown.step(position_seconds, countdown_seconds);
+      if matches!(step, CountdownStep::PlayNext | CountdownStep::Cancel) {
+        s.credits_countdown = None;
+      }
+      (step, item)
+    };
+
+    match step {
+      CountdownStep::Wait => {}
+      CountdownStep::Show(remaining) => {
+        let _ = action_tx
+          .send(MpvAction::ShowText {
+            text: tr!("osd-up-next", seconds = remaining.to_string()),
+            duration_ms: 1100,
+          })
+          .await;
+      }
+      CountdownStep::Cancel => log::info!("Seeked back before the credits; auto-next cancelled"),
+      CountdownStep::PlayNext => {
+        let Some(item) = item else {
+          return;
+        };
+        if !Self::continue_playback(
+          client,
+          state,
+          action_tx,
+          config,
+          &item,
+          AdjacentTrigger::Credits,
+        )
+        .await
+          && countdown_seconds > 0
+        {
+          let _ = action_tx
+            .send(MpvAction::ShowText {
+              text: tr!("osd-nothing-up-next"),
+              duration_ms: 2000,
+            })
+            .await;
+        }
+      }
+    }
+  }
+
+  /// Report current playback progress to Jellyfin.
+  async fn report_progress(client: &JellyfinClient, state: &RwLock<SessionState>) {
+    let (session, current_item, queue_report) = {
+      let s = state.read();
+      (
+        s.playback.clone(),
+        s.current_item.clone(),
+        QueueReport::from(&s.queue),
+      )
+    };
+
+    let Some(session) = session else {
+      return;
+    };
+    let run_time_ticks = reported_run_time_ticks(current_item.as_ref(), &session);
+
+    let progress = PlaybackProgressInfo {
+      item_id: session.item_id.clone(),
       media_source_id: session.media_source_id.clone(),
       play_session_id: session.play_session_id.clone(),
       position_ticks: Some(session.position_ticks),
@@ -1270,13 +3291,71 @@ impl SessionManager {
       audio_stream_index: session.audio_stream_index,
       subtitle_stream_index: session.subtitle_stream_index,
       play_method: session.play_method,
-      can_seek: true,
+      can_seek: session.can_seek,
+      live_stream_id: session.live_stream_id,
+      run_time_ticks,
+      item: current_item
+        .as_ref()
+        .map(|item| NowPlayingItem::new(item, run_time_ticks)),
+      now_playing_queue: queue_report.now_playing_queue,
+      playlist_item_id: queue_report.playlist_item_id,
+      repeat_mode: queue_report.repeat_mode,
+      playback_order: queue_report.playback_order,
     };
 
     log::debug!("Progress payload: {:?}", progress);
 
     if let Err(e) = client.playback().report_playback_progress(&progress).await {
       log::error!("Failed to report playback progress: {}", e);
+      metrics::increment(Counter::ProgressReportFailures);
+    }
+  }
+
+  /// Report the playback that continued through a lost connection as started
+  /// again, so the server tracks it and accepts its progress and stop reports.
+  async fn reannounce_playback(client: &JellyfinClient, state: &RwLock<SessionState>) {
+    let (session, current_item, queue_report) = {
+      let s = state.read();
+      (
+        s.playback.clone(),
+        s.current_item.clone(),
+        QueueReport::from(&s.queue),
+      )
+    };
+
+    let Some(session) = session else {
+      return;
+    };
+    let run_time_ticks = reported_run_time_ticks(current_item.as_ref(), &session);
+    log::info!(
+      "Re-announcing playback of {} after reconnect",
+      session.item_id
+    );
+
+    let start_info = PlaybackStartInfo {
+      item_id: session.item_id,
+      media_source_id: session.media_source_id,
+      play_session_id: session.play_session_id,
+      position_ticks: Some(session.position_ticks),
+      is_paused: session.is_paused,
+      is_muted: session.is_muted,
+      volume_level: session.volume,
+      audio_stream_index: session.audio_stream_index,
+      subtitle_stream_index: session.subtitle_stream_index,
+      play_method: session.play_method,
+      can_seek: session.can_seek,
+      live_stream_id: session.live_stream_id,
+      run_time_ticks,
+      item: current_item
+        .as_ref()
+        .map(|item| NowPlayingItem::new(item, run_time_ticks)),
+      now_playing_queue: queue_report.now_playing_queue,
+      playlist_item_id: queue_report.playlist_item_id,
+      repeat_mode: queue_report.repeat_mode,
+      playback_order: queue_report.playback_order,
+    };
+    if let Err(e) = client.playback().report_playback_start(&start_info).await {
+      log::error!("Failed to re-announce playback after reconnect: {}", e);
     }
   }
 
@@ -1287,6 +3366,7 @@ impl SessionManager {
     state: &RwLock<SessionState>,
     action_tx: &mpsc::Sender<MpvAction>,
     config: &RwLock<AppConfig>,
+    app_handle: &AppHandle,
   ) {
     let reason = event.reason.as_deref().unwrap_or("");
     log::info!("MPV end-file event, reason: {}", reason);
@@ -1295,7 +3375,33 @@ impl SessionManager {
     if !is_natural_end(event.reason.as_deref()) {
       return;
     }
+    let Some(early_end) = Self::early_end(state) else {
+      Self::continue_after_end(client, state, action_tx, config).await;
+      return;
+    };
+    if Self::resume_interrupted_transcode(client, state, action_tx, app_handle, &early_end).await {
+      return;
+    }
+
+    // Moving on would skip whatever the viewer missed; offer a retry instead
+    let detail = format!(
+      "Ended at {} of {}",
+      crate::tray::format_clock(ticks_to_seconds(early_end.position_ticks)),
+      crate::tray::format_clock(ticks_to_seconds(early_end.length_ticks))
+    );
+    log::warn!("Playback of {} {}", early_end.item_id, detail);
+    Self::report_playback_stopped(client, state).await;
+    state.write().early_end = Some(early_end);
+    PlaybackError::report(app_handle, PlaybackErrorStage::EndedEarly, detail);
+  }
 
+  /// Report the finished item and continue with the next one.
+  async fn continue_after_end(
+    client: &JellyfinClient,
+    state: &RwLock<SessionState>,
+    action_tx: &mpsc::Sender<MpvAction>,
+    config: &RwLock<AppConfig>,
+  ) {
     // Get current item for next episode lookup
     let current_item = {
       let s = state.read();
@@ -1311,11 +3417,179 @@ impl SessionManager {
     // Report playback stopped to Jellyfin
     Self::report_playback_stopped(client, state).await;
 
-    // Try to get next episode
-    if let Err(e) =
-      Self::play_adjacent_episode(client, state, action_tx, config, &item, true, false).await
+    Self::continue_playback(
+      client,
+      state,
+      action_tx,
+      config,
+      &item,
+      AdjacentTrigger::NaturalEnd,
+    )
+    .await;
+  }
+
+  /// Where the current playback stopped, when MPV reached the end of the
+  /// file well before the end of the item.
+  fn early_end(state: &RwLock<SessionState>) -> Option<EarlyEnd> {
+    let s = state.read();
+    let playback = s.playback.as_ref()?;
+    // A growing recording's runtime is the schedule, not the file
+    if playback.is_growing {
+      return None;
+    }
+    let runtime_ticks = s.current_item.as_ref().and_then(|item| item.run_time_ticks);
+    match end_of_file(
+      playback.position_ticks,
+      runtime_ticks,
+      playback.mpv_duration_ticks,
+    ) {
+      EndOfFile::Finished => None,
+      EndOfFile::Early => Some(EarlyEnd {
+        item_id: playback.item_id.clone(),
+        position_ticks: playback.position_ticks,
+        length_ticks: runtime_ticks
+          .filter(|ticks| *ticks > 0)
+          .or(playback.mpv_duration_ticks)
+          .unwrap_or_default(),
+      }),
+    }
+  }
+
+  /// A transcode the server ended early looks like the end of the file to
+  /// MPV. Restart it with fresh playback info where it broke off instead of
+  /// moving on, up to a few times. Returns whether it was restarted.
+  async fn resume_interrupted_transcode(
+    client: &JellyfinClient,
+    state: &RwLock<SessionState>,
+    action_tx: &mpsc::Sender<MpvAction>,
+    app_handle: &AppHandle,
+    early_end: &EarlyEnd,
+  ) -> bool {
+    let (subtitle_index, title, restarts) = {
+      let mut s = state.write();
+      let title = s
+        .current_item
+        .as_ref()
+        .map(Self::format_title)
+        .unwrap_or_default();
+      let Some(playback) = s.playback.as_ref() else {
+        return false;
+      };
+      if playback.play_method != "Transcode" {
+        return false;
+      }
+      let subtitle_index = playback.subtitle_stream_index.unwrap_or(-1);
+      s.transcode_restarts += 1;
+      (subtitle_index, title, s.transcode_restarts)
+    };
+
+    let position = crate::tray::format_clock(ticks_to_seconds(early_end.position_ticks));
+    log::warn!(
+      "Transcode of {} ended early at {} (restart {})",
+      title,
+      position,
+      restarts
+    );
+    metrics::increment(Counter::InterruptedTranscodes);
+    if restarts > MAX_TRANSCODE_RESTARTS
+      || !Self::reload_stream(client, state, action_tx, subtitle_index, true).await
+    {
+      return false;
+    }
+    AppNotification::warning(
+      app_handle,
+      tr!(
+        "notify-transcode-interrupted",
+        title = title,
+        position = position
+      ),
+    );
+    true
+  }
+
+  /// Play the item that last ended early again from where it stopped.
+  pub async fn retry_early_end(&self) -> Result<(), String> {
+    let early_end = self
+      .state
+      .write()
+      .early_end
+      .take()
+      .ok_or("No playback ended early")?;
+    log::info!(
+      "Retrying {} at {} ticks",
+      early_end.item_id,
+      early_end.position_ticks
+    );
+    let request = PlayRequest {
+      item_ids: vec![early_end.item_id.clone()],
+      start_position_ticks: Some(early_end.position_ticks),
+      play_command: "PlayNow".to_string(),
+      media_source_id: None,
+      audio_stream_index: None,
+      subtitle_stream_index: None,
+      start_index: None,
+    };
+    Self::play_item(
+      &self.client,
+      &self.state,
+      &self.action_tx,
+      self.mpv.is_connected(),
+      &self.config,
+      &request,
+      &early_end.item_id,
+    )
+    .await
+    .map_err(|e| e.to_string())
+  }
+
+  /// Start the next queue item or episode after `item` without a user request.
+  /// Returns whether playback continued.
+  async fn continue_playback(
+    client: &JellyfinClient,
+    state: &RwLock<SessionState>,
+    action_tx: &mpsc::Sender<MpvAction>,
+    config: &RwLock<AppConfig>,
+    item: &MediaItem,
+    trigger: AdjacentTrigger,
+  ) -> bool {
+    // Queue repeat modes decide first; RepeatOne replays the finished item
+    if let Some(result) = Self::play_adjacent_queue_item(
+      client,
+      state,
+      action_tx,
+      config,
+      QueueStep::NaturalEnd,
+      trigger != AdjacentTrigger::NaturalEnd,
+    )
+    .await
     {
-      log::info!("Natural end did not start an adjacent episode: {}", e);
+      return match result {
+        Ok(()) => {
+          state.write().auto_advanced = true;
+          true
+        }
+        Err(e) => {
+          log::error!("Failed to continue playback queue: {}", e);
+          false
+        }
+      };
+    }
+
+    if config.read().auto_next_scope == AutoNextScope::Off {
+      log::info!("Auto-next is off; not starting the next episode");
+      return false;
+    }
+
+    // Try to get next episode
+    match Self::play_adjacent_episode(client, state, action_tx, config, item, true, trigger).await {
+      Ok(()) => {
+        state.write().auto_advanced = true;
+        true
+      }
+      Err(e) => {
+        log::info!("{:?} did not start an adjacent episode: {}", trigger, e);
+        false
+      }
     }
   }
 
@@ -1324,45 +3598,123 @@ impl SessionManager {
   /// Users can add to their input.conf:
   ///   Shift+> script-message jellypilot-next
   ///   Shift+< script-message jellypilot-prev
+  ///   [ script-message jellypilot-mark-skip-start
+  ///   ] script-message jellypilot-mark-skip-end
+  ///
+  /// The other shortcuts are bound in the managed input.conf.
   async fn handle_client_message_event(
     event: &crate::mpv::MpvEvent,
     client: &JellyfinClient,
     state: &RwLock<SessionState>,
     action_tx: &mpsc::Sender<MpvAction>,
     config: &RwLock<AppConfig>,
+    app_handle: &AppHandle,
   ) {
     let args = match &event.args {
       Some(args) if !args.is_empty() => args,
       _ => return,
     };
 
-    if args[0] == "jellypilot-skip-intro" {
-      Self::handle_manual_intro_skip(state, action_tx).await;
-      return;
-    }
-
-    let Some(direction) = client_message_direction(args) else {
-      log::debug!("Unknown client-message command: {}", args[0]);
-      return;
-    };
-
-    let current_item = {
-      let s = state.read();
-      s.current_item.clone()
-    };
-
-    let Some(item) = current_item else {
-      log::warn!("{}: No current item", args[0]);
-      return;
-    };
-
-    let next = direction == crate::playback_control::AdjacentDirection::Next;
-    log::info!(
-      "Keyboard shortcut: playing {} episode",
-      if next { "next" } else { "previous" }
+    match args[0].as_str() {
+      "jellypilot-skip-intro" => {
+        Self::handle_manual_intro_skip(state, action_tx).await;
+        return;
+      }
+      "jellypilot-mark-skip-start" => {
+        marked_skip::handle_skip_mark_start(state, action_tx).await;
+        return;
+      }
+      "jellypilot-mark-skip-end" => {
+        if marked_skip::handle_skip_mark_end(state, action_tx).await {
+          Self::save_marked_skip_ranges(state, app_handle);
+        }
+        return;
+      }
+      "jellypilot-toggle-intro-skip" => {
+        Self::toggle_intro_skip(state, action_tx).await;
+        return;
+      }
+      "jellypilot-toggle-watched" => {
+        let _ =
+          Self::toggle_current_user_data(client, state, action_tx, UserDataToggle::Watched).await;
+        return;
+      }
+      "jellypilot-toggle-favorite" => {
+        let _ =
+          Self::toggle_current_user_data(client, state, action_tx, UserDataToggle::Favorite).await;
+        return;
+      }
+      "jellypilot-speed-up" | "jellypilot-speed-down" => {
+        let faster = args[0] == "jellypilot-speed-up";
+        let _ = action_tx.send(MpvAction::StepSpeed { faster }).await;
+        return;
+      }
+      "jellypilot-toggle-stats" => {
+        let _ = action_tx.send(MpvAction::ToggleStats).await;
+        return;
+      }
+      "jellypilot-ab-loop" => {
+        Self::step_ab_loop(state, action_tx).await;
+        return;
+      }
+      "jellypilot-screenshot" => {
+        match Self::screenshot_path(state, config) {
+          Ok(path) => {
+            let _ = action_tx
+              .send(MpvAction::Screenshot {
+                path,
+                mode: ScreenshotMode::Subtitles,
+              })
+              .await;
+          }
+          Err(e) => log::warn!("Screenshot shortcut: {}", e),
+        }
+        return;
+      }
+      "jellypilot-cycle-secondary-subtitle" => {
+        Self::cycle_secondary_subtitle(client, state, action_tx).await;
+        return;
+      }
+      "jellypilot-stop-idle" => {
+        log::info!("Keyboard shortcut: stopping playback");
+        state.write().queue.clear();
+        Self::report_playback_stopped(client, state).await;
+        let _ = action_tx.send(MpvAction::StopIdle).await;
+        return;
+      }
+      _ => {}
+    }
+
+    let Some(direction) = client_message_direction(args) else {
+      log::debug!("Unknown client-message command: {}", args[0]);
+      return;
+    };
+
+    let current_item = {
+      let s = state.read();
+      s.current_item.clone()
+    };
+
+    let Some(item) = current_item else {
+      log::warn!("{}: No current item", args[0]);
+      return;
+    };
+
+    let next = direction == crate::playback_control::AdjacentDirection::Next;
+    log::info!(
+      "Keyboard shortcut: playing {} episode",
+      if next { "next" } else { "previous" }
     );
-    if let Err(e) =
-      Self::play_adjacent_episode(client, state, action_tx, config, &item, next, true).await
+    if let Err(e) = Self::play_adjacent_episode(
+      client,
+      state,
+      action_tx,
+      config,
+      &item,
+      next,
+      AdjacentTrigger::User,
+    )
+    .await
     {
       log::warn!("Keyboard shortcut {} unavailable: {}", args[0], e);
     }
@@ -1372,22 +3724,14 @@ impl SessionManager {
     state: &RwLock<SessionState>,
     action_tx: &mpsc::Sender<MpvAction>,
   ) {
-    if state.read().effective_intro_skipper_config.mode != IntroSkipperMode::Manual {
-      let _ = action_tx
-        .send(MpvAction::ShowText {
-          text: "No intro or credits to skip".to_string(),
-          duration_ms: 1200,
-        })
-        .await;
-      return;
-    }
-
     let decision = {
       let mut s = state.write();
+      let policies = s.effective_intro_skipper_config.policies();
       s.playback.as_mut().and_then(|playback| {
         evaluate_manual_skip(
           ticks_to_seconds(playback.position_ticks),
-          &mut playback.intro_skipper_ranges,
+          &mut playback.skip_ranges,
+          &policies,
         )
       })
     };
@@ -1396,28 +3740,430 @@ impl SessionManager {
       let _ = action_tx.send(MpvAction::Seek(decision.seek_target)).await;
       let _ = action_tx
         .send(MpvAction::ShowText {
-          text: format!("Skipped {}", intro_skipper_label_lower(decision.kind)),
+          text: skipped_text(decision.kind),
           duration_ms: 1500,
         })
         .await;
     } else {
       let _ = action_tx
         .send(MpvAction::ShowText {
-          text: "No intro or credits to skip".to_string(),
+          text: tr!("osd-nothing-to-skip"),
           duration_ms: 1200,
         })
         .await;
     }
   }
 
+  /// Switch automatic intro skipping on or off until MPV restarts.
+  async fn toggle_intro_skip(state: &RwLock<SessionState>, action_tx: &mpsc::Sender<MpvAction>) {
+    let enabled = {
+      let mut s = state.write();
+      let config = &mut s.effective_intro_skipper_config;
+      config.mode = match config.mode {
+        IntroSkipperMode::Automatic => IntroSkipperMode::Manual,
+        IntroSkipperMode::Manual | IntroSkipperMode::Off => IntroSkipperMode::Automatic,
+      };
+      config.mode == IntroSkipperMode::Automatic
+    };
+    log::info!(
+      "Keyboard shortcut: automatic intro skipping {}",
+      if enabled { "on" } else { "off" }
+    );
+    let text = if enabled {
+      tr!("osd-intro-skip-on")
+    } else {
+      tr!("osd-intro-skip-off")
+    };
+    let _ = action_tx
+      .send(MpvAction::ShowText {
+        text,
+        duration_ms: 1500,
+      })
+      .await;
+  }
+
+  /// Show the next subtitle stream as the secondary subtitle, or none after
+  /// the last, and name it on MPV's OSD.
+  async fn cycle_secondary_subtitle(
+    client: &JellyfinClient,
+    state: &RwLock<SessionState>,
+    action_tx: &mpsc::Sender<MpvAction>,
+  ) {
+    let (next, name) = {
+      let s = state.read();
+      let primary = s
+        .playback
+        .as_ref()
+        .and_then(|playback| playback.subtitle_stream_index);
+      let next = s
+        .subtitle_tracks
+        .next_secondary(&s.current_media_streams, primary);
+      let name = next.map(|index| {
+        s.current_media_streams
+          .iter()
+          .find(|stream| stream.stream_type == "Subtitle" && stream.index == index)
+          .and_then(|stream| stream.display_title.clone().or(stream.language.clone()))
+          .unwrap_or_else(|| format!("#{}", index))
+      });
+      (next, name)
+    };
+    let text = match Self::apply_secondary_subtitle(client, state, action_tx, next).await {
+      Ok(()) => match name {
+        Some(name) => tr!("osd-secondary-subtitle", name = name),
+        None => tr!("osd-secondary-subtitle-off"),
+      },
+      Err(e) => {
+        log::warn!("Keyboard shortcut: secondary subtitle unavailable: {}", e);
+        tr!("osd-secondary-subtitle-unavailable")
+      }
+    };
+    let _ = action_tx
+      .send(MpvAction::ShowText {
+        text,
+        duration_ms: 2000,
+      })
+      .await;
+  }
+
+  /// Show the subtitle stream `index` under the primary one, or hide the
+  /// secondary subtitle with `None`. Streams are mapped to MPV tracks like
+  /// the primary selection, and external ones are added on first use.
+  async fn apply_secondary_subtitle(
+    client: &JellyfinClient,
+    state: &RwLock<SessionState>,
+    action_tx: &mpsc::Sender<MpvAction>,
+    index: Option<i32>,
+  ) -> Result<(), String> {
+    let action = {
+      let mut s = state.write();
+      let Some(playback) = s.playback.as_ref() else {
+        return Err("Secondary subtitles are available during playback".to_string());
+      };
+      let primary = playback.subtitle_stream_index;
+      let source = playback
+        .media_source_id
+        .clone()
+        .map(|media_source_id| (playback.item_id.clone(), media_source_id));
+      match index {
+        None => {
+          s.subtitle_tracks.set_secondary(None);
+          MpvAction::SetSecondarySubtitleTrack(-1)
+        }
+        Some(index) => {
+          if primary == Some(index) {
+            return Err("This subtitle is already shown".to_string());
+          }
+          let action = Self::secondary_subtitle_action(client, &mut s, source, index)?;
+          s.subtitle_tracks.set_secondary(Some(index));
+          action
+        }
+      }
+    };
+    log::info!("Secondary subtitle: {}", redact(&format!("{:?}", action)));
+    let _ = action_tx.send(action).await;
+    Ok(())
+  }
+
+  /// The MPV action that shows subtitle stream `index` as the secondary one.
+  fn secondary_subtitle_action(
+    client: &JellyfinClient,
+    s: &mut SessionState,
+    source: Option<(String, String)>,
+    index: i32,
+  ) -> Result<MpvAction, String> {
+    let known = s
+      .current_media_streams
+      .iter()
+      .any(|stream| stream.stream_type == "Subtitle" && stream.index == index);
+    if !known {
+      return Err(format!("Subtitle stream {} not found", index));
+    }
+    match s.subtitle_tracks.select(&s.current_media_streams, index) {
+      SubtitleSelection::Disable => Err(format!("Subtitle stream {} not found", index)),
+      SubtitleSelection::Select(track_id) => Ok(MpvAction::SetSecondarySubtitleTrack(track_id)),
+      SubtitleSelection::Load(stream) => {
+        let stream = stream.clone();
+        let url = source
+          .and_then(|(item_id, media_source_id)| {
+            client
+              .playback()
+              .build_subtitle_url(&item_id, &media_source_id, &stream)
+          })
+          .ok_or_else(|| "No address for the external subtitle".to_string())?;
+        s.subtitle_tracks.loaded(stream.index);
+        match s.subtitle_tracks.select(&s.current_media_streams, index) {
+          SubtitleSelection::Select(track_id) => {
+            Ok(MpvAction::AddSecondarySubtitle { url, track_id })
+          }
+          _ => Err(format!("Subtitle stream {} not found", index)),
+        }
+      }
+    }
+  }
+
+  /// Show a second subtitle stream under the selected one, e.g. a language
+  /// being learned, or hide it with `None`. Takes a Jellyfin stream index.
+  pub async fn set_secondary_subtitle(&self, index: Option<i32>) -> Result<(), String> {
+    Self::apply_secondary_subtitle(&self.client, &self.state, &self.action_tx, index).await
+  }
+
+  /// Flip the playing item's watched or favorite state on the server and
+  /// confirm it on MPV's OSD. Returns whether it is set afterwards.
+  async fn toggle_current_user_data(
+    client: &JellyfinClient,
+    state: &RwLock<SessionState>,
+    action_tx: &mpsc::Sender<MpvAction>,
+    toggle: UserDataToggle,
+  ) -> Result<bool, String> {
+    let item_id = state
+      .read()
+      .current_item
+      .as_ref()
+      .map(|item| item.id.clone())
+      .ok_or_else(|| format!("The {} can be changed during playback", toggle.label()))?;
+    let result = Self::toggle_user_data(client, state, item_id, toggle).await;
+    let text = match &result {
+      Ok(set) => toggle.confirmation(*set),
+      Err(e) => {
+        log::warn!(
+          "Failed to change the current item's {}: {}",
+          toggle.label(),
+          e
+        );
+        toggle.failure()
+      }
+    };
+    let _ = action_tx
+      .send(MpvAction::ShowText {
+        text,
+        duration_ms: 1500,
+      })
+      .await;
+    result
+  }
+
+  async fn toggle_user_data(
+    client: &JellyfinClient,
+    state: &RwLock<SessionState>,
+    item_id: String,
+    toggle: UserDataToggle,
+  ) -> Result<bool, String> {
+    let detail = client
+      .library()
+      .item_detail(item_id.clone())
+      .await
+      .map_err(|e| e.to_string())?;
+    let action = match toggle {
+      UserDataToggle::Watched if detail.played => VideoUserDataAction::MarkUnplayed,
+      UserDataToggle::Watched => VideoUserDataAction::MarkPlayed,
+      UserDataToggle::Favorite if detail.favorite => VideoUserDataAction::Unfavorite,
+      UserDataToggle::Favorite => VideoUserDataAction::Favorite,
+    };
+    let update = client
+      .library()
+      .update_user_data(VideoUserDataUpdateRequest { item_id, action })
+      .await
+      .map_err(|e| e.to_string())?;
+    Self::store_user_data(state, &update);
+    Ok(match toggle {
+      UserDataToggle::Watched => update.played,
+      UserDataToggle::Favorite => update.favorite,
+    })
+  }
+
+  /// Take user data the server returned for the current item into Now Playing.
+  fn store_user_data(state: &RwLock<SessionState>, update: &VideoUserDataUpdate) {
+    let mut s = state.write();
+    if s
+      .current_item
+      .as_ref()
+      .is_some_and(|item| item.id == update.item_id)
+    {
+      s.user_data = Some(NowPlayingUserData::from(update));
+    }
+  }
+
+  /// Set the loop start at the current position, then its end, then clear
+  /// the loop, and confirm each step on MPV's OSD.
+  async fn step_ab_loop(state: &RwLock<SessionState>, action_tx: &mpsc::Sender<MpvAction>) {
+    let ab_loop = {
+      let mut s = state.write();
+      let Some(position) = s
+        .playback
+        .as_ref()
+        .map(|playback| ticks_to_seconds(playback.position_ticks))
+      else {
+        log::warn!("A-B loop shortcut without playback");
+        return;
+      };
+      s.ab_loop = s.ab_loop.step(position);
+      s.ab_loop
+    };
+
+    let text = match (ab_loop.start, ab_loop.end) {
+      (Some(start), Some(end)) => tr!(
+        "osd-ab-loop-set",
+        start = crate::tray::format_clock(start),
+        end = crate::tray::format_clock(end)
+      ),
+      (Some(start), None) => tr!(
+        "osd-ab-loop-start",
+        position = crate::tray::format_clock(start)
+      ),
+      _ => tr!("osd-ab-loop-cleared"),
+    };
+    let _ = action_tx.send(MpvAction::SetAbLoop(ab_loop)).await;
+    let _ = action_tx
+      .send(MpvAction::ShowText {
+        text,
+        duration_ms: 2000,
+      })
+      .await;
+  }
+
+  /// Loop the current item between `start` and `end` seconds, or stop
+  /// looping with `None`.
+  pub async fn set_ab_loop(&self, range: Option<(f64, f64)>) -> Result<(), String> {
+    let ab_loop = match range {
+      Some((start, end)) => {
+        if !(start.is_finite() && end.is_finite() && start >= 0.0 && end > start) {
+          return Err("The loop must end after it starts".to_string());
+        }
+        AbLoop {
+          start: Some(start),
+          end: Some(end),
+        }
+      }
+      None => AbLoop::default(),
+    };
+    {
+      let mut s = self.state.write();
+      if s.playback.is_none() {
+        return Err("Looping is available during playback".to_string());
+      }
+      s.ab_loop = ab_loop;
+    }
+    log::info!("A-B loop: {:?}", ab_loop);
+    let _ = self.action_tx.send(MpvAction::SetAbLoop(ab_loop)).await;
+    Ok(())
+  }
+
+  /// Where a screenshot of the current moment goes, creating the folder.
+  fn screenshot_path(
+    state: &RwLock<SessionState>,
+    config: &RwLock<AppConfig>,
+  ) -> Result<std::path::PathBuf, String> {
+    let (item, position) = {
+      let s = state.read();
+      let playback = s
+        .playback
+        .as_ref()
+        .ok_or("Screenshots are available during playback")?;
+      (
+        s.current_item.clone(),
+        ticks_to_seconds(playback.position_ticks),
+      )
+    };
+    let dir = config
+      .read()
+      .resolved_screenshot_dir()
+      .ok_or("No screenshot folder is set")?;
+    std::fs::create_dir_all(&dir)
+      .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    Ok(screenshot::unused_path(
+      &dir,
+      &screenshot::file_name(item.as_ref(), position),
+    ))
+  }
+
+  /// Save a screenshot named after the playing item. Returns its path.
+  pub async fn screenshot(&self, mode: ScreenshotMode) -> Result<std::path::PathBuf, String> {
+    let path = Self::screenshot_path(&self.state, &self.config)?;
+    let _ = self
+      .action_tx
+      .send(MpvAction::Screenshot {
+        path: path.clone(),
+        mode,
+      })
+      .await;
+    Ok(path)
+  }
+
+  /// Save user skip ranges to disk.
+  fn save_marked_skip_ranges(state: &RwLock<SessionState>, app_handle: &AppHandle) {
+    let ranges = state.read().marked_skip_ranges.clone();
+
+    let store = match app_handle.store(PREFERENCES_STORE_FILE) {
+      Ok(store) => store,
+      Err(e) => {
+        log::error!("Failed to open preferences store for writing: {}", e);
+        return;
+      }
+    };
+    match serde_json::to_value(&ranges) {
+      Ok(value) => {
+        store.set(MARKED_SKIP_RANGES_KEY.to_string(), value);
+        if let Err(e) = store.save() {
+          log::error!("Failed to save skip ranges to disk: {}", e);
+        }
+      }
+      Err(e) => log::error!("Failed to serialize skip ranges: {}", e),
+    }
+  }
+
+  /// Remember the file just loaded into MPV, so the playback can be picked
+  /// up again if JellyPilot restarts while MPV keeps playing.
+  fn save_loaded_playback(state: &RwLock<SessionState>, app_handle: &AppHandle, url: &str) {
+    let stored = {
+      let s = state.read();
+      let (Some(item), Some(session)) = (&s.current_item, &s.playback) else {
+        return;
+      };
+      StoredPlayback::new(url, item, session)
+    };
+
+    let store = match app_handle.store(PREFERENCES_STORE_FILE) {
+      Ok(store) => store,
+      Err(e) => {
+        log::error!("Failed to open preferences store for writing: {}", e);
+        return;
+      }
+    };
+    match serde_json::to_value(&stored) {
+      Ok(value) => {
+        store.set(LOADED_PLAYBACK_KEY.to_string(), value);
+        if let Err(e) = store.save() {
+          log::error!("Failed to save the loaded playback to disk: {}", e);
+        }
+      }
+      Err(e) => log::error!("Failed to serialize the loaded playback: {}", e),
+    }
+  }
+
+  /// The file last loaded into MPV, if one was recorded.
+  fn load_loaded_playback(app_handle: &AppHandle) -> Option<StoredPlayback> {
+    let store = app_handle
+      .store(PREFERENCES_STORE_FILE)
+      .inspect_err(|e| log::warn!("Failed to open preferences store: {}", e))
+      .ok()?;
+    let value = store.get(LOADED_PLAYBACK_KEY)?;
+    serde_json::from_value(value)
+      .inspect_err(|e| log::warn!("Failed to parse the loaded playback: {}", e))
+      .ok()
+  }
+
   /// Report playback stopped to Jellyfin and clear session.
   async fn report_playback_stopped(client: &JellyfinClient, state: &RwLock<SessionState>) {
-    let session = {
+    let (session, item) = {
       let mut s = state.write();
-      s.playback.take()
+      s.session_files.clear();
+      (s.playback.take(), s.current_item.clone())
     };
 
     if let Some(session) = session {
+      if let Some(item) = &item {
+        webhooks::fire(WebhookEvent::Stopped, item, session.position_ticks);
+      }
       let stop_info = PlaybackStopInfo {
         item_id: session.item_id,
         media_source_id: session.media_source_id,
@@ -1431,20 +4177,28 @@ impl SessionManager {
   }
 
   /// Clear all playback context - reports stop to Jellyfin and clears all state.
-  /// Call this when MPV dies unexpectedly or WebSocket disconnects during playback.
+  /// Call this when MPV dies unexpectedly or the saved session is rejected.
   async fn clear_playback_context(client: &JellyfinClient, state: &RwLock<SessionState>) {
+    let generation = state.read().playback_generation;
     // First report stopped to Jellyfin
     Self::report_playback_stopped(client, state).await;
 
-    // Then clear all related state
+    // Then clear all related state, unless a new playback took it over
     let mut s = state.write();
+    if s.playback_generation != generation {
+      log::info!("Playback started while clearing; keeping its context");
+      return;
+    }
     s.current_item = None;
-    s.current_series_id = None;
+    s.user_data = None;
+    s.preference_target = None;
     s.current_media_streams.clear();
+    s.queue.clear();
+    s.playback_ended();
     log::info!("Playback context cleared");
   }
 
-  /// Play the next or previous episode.
+  /// Play the next or previous queue item, falling back to the adjacent episode.
   async fn play_adjacent_episode(
     client: &JellyfinClient,
     state: &RwLock<SessionState>,
@@ -1452,15 +4206,78 @@ impl SessionManager {
     config: &RwLock<AppConfig>,
     current_item: &MediaItem,
     next: bool,
-    report_current_stopped: bool,
+    trigger: AdjacentTrigger,
   ) -> Result<(), String> {
-    let result = if next {
-      client.playback().get_next_episode(current_item).await
+    // The natural-end path has already reported the finished item as stopped
+    let report_current_stopped = trigger != AdjacentTrigger::NaturalEnd;
+    let step = if next {
+      QueueStep::Next
     } else {
-      client.playback().get_previous_episode(current_item).await
+      QueueStep::Previous
+    };
+    if let Some(result) = Self::play_adjacent_queue_item(
+      client,
+      state,
+      action_tx,
+      config,
+      step,
+      report_current_stopped,
+    )
+    .await
+    {
+      return result.map_err(|e| {
+        log::error!(
+          "Failed to play {} queue item: {}",
+          if next { "next" } else { "previous" },
+          e
+        );
+        format!(
+          "Failed to play {} queue item",
+          if next { "next" } else { "previous" }
+        )
+      });
+    }
+
+    if current_item.item_type != "Episode" {
+      return Self::play_adjacent_collection_item(
+        client,
+        state,
+        action_tx,
+        config,
+        current_item,
+        step,
+        trigger,
+      )
+      .await;
+    }
+
+    let (skip_specials, auto_next_scope) = {
+      let config = config.read();
+      let skip_specials = match trigger {
+        AdjacentTrigger::User => config.skip_specials,
+        AdjacentTrigger::NaturalEnd | AdjacentTrigger::Credits => {
+          config.skip_specials || !config.auto_next_specials
+        }
+      };
+      (skip_specials, config.auto_next_scope)
     };
+    let result = client
+      .playback()
+      .get_adjacent_episode(current_item, next, skip_specials)
+      .await;
 
     match result {
+      Ok(Some(adjacent_item))
+        if trigger != AdjacentTrigger::User
+          && !auto_next_allowed(auto_next_scope, current_item, &adjacent_item) =>
+      {
+        log::info!(
+          "Next episode {} is outside the auto-next scope {:?}",
+          adjacent_item.id,
+          auto_next_scope
+        );
+        Err("Next episode is outside the auto-next scope".to_string())
+      }
       Ok(Some(adjacent_item)) => {
         log::info!(
           "Playing {} episode: {} - S{:02}E{:02}",
@@ -1481,21 +4298,30 @@ impl SessionManager {
           media_source_id: None,
           audio_stream_index: None,
           subtitle_stream_index: None,
+          start_index: None,
         };
 
-        Self::handle_play(client, state, action_tx, true, config, play_request)
-          .await
-          .map_err(|e| {
-            log::error!(
-              "Failed to play {} episode: {}",
-              if next { "next" } else { "previous" },
-              e
-            );
-            format!(
-              "Failed to play {} episode",
-              if next { "next" } else { "previous" }
-            )
-          })
+        Self::handle_play(
+          client,
+          state,
+          action_tx,
+          true,
+          config,
+          PlayOrigin::Continuation,
+          play_request,
+        )
+        .await
+        .map_err(|e| {
+          log::error!(
+            "Failed to play {} episode: {}",
+            if next { "next" } else { "previous" },
+            e
+          );
+          format!(
+            "Failed to play {} episode",
+            if next { "next" } else { "previous" }
+          )
+        })
       }
       Ok(None) => {
         log::info!(
@@ -1521,6 +4347,115 @@ impl SessionManager {
     }
   }
 
+  /// Continue through the collection (BoxSet) holding a non-episode item by
+  /// loading the collection into the playback queue.
+  async fn play_adjacent_collection_item(
+    client: &JellyfinClient,
+    state: &RwLock<SessionState>,
+    action_tx: &mpsc::Sender<MpvAction>,
+    config: &RwLock<AppConfig>,
+    current_item: &MediaItem,
+    step: QueueStep,
+    trigger: AdjacentTrigger,
+  ) -> Result<(), String> {
+    if trigger != AdjacentTrigger::User
+      && config.read().auto_next_scope != AutoNextScope::SameSeries
+    {
+      return Err("Collection continuation is outside the auto-next scope".to_string());
+    }
+
+    let item_ids = match client
+      .playback()
+      .get_collection_item_ids(&current_item.id)
+      .await
+    {
+      Ok(Some(item_ids)) => item_ids,
+      Ok(None) => {
+        return Err("Next and previous are available for episodes and collection items".to_string())
+      }
+      Err(e) => {
+        log::error!(
+          "Failed to look up collection for {}: {}",
+          current_item.id,
+          e
+        );
+        return Err("Failed to look up the collection".to_string());
+      }
+    };
+    let Some(position) = item_ids.iter().position(|id| *id == current_item.id) else {
+      return Err("Current item is no longer in its collection".to_string());
+    };
+    state.write().queue.replace(&item_ids, position);
+
+    let direction = if step == QueueStep::Previous {
+      "previous"
+    } else {
+      "next"
+    };
+    match Self::play_adjacent_queue_item(
+      client,
+      state,
+      action_tx,
+      config,
+      step,
+      trigger != AdjacentTrigger::NaturalEnd,
+    )
+    .await
+    {
+      Some(result) => result.map_err(|e| {
+        log::error!("Failed to play {} collection item: {}", direction, e);
+        format!("Failed to play {} collection item", direction)
+      }),
+      None => Err(format!("No {} item in the collection", direction)),
+    }
+  }
+
+  /// Move within the playback queue; `None` when the queue has no item for that step.
+  async fn play_adjacent_queue_item(
+    client: &JellyfinClient,
+    state: &RwLock<SessionState>,
+    action_tx: &mpsc::Sender<MpvAction>,
+    config: &RwLock<AppConfig>,
+    step: QueueStep,
+    report_current_stopped: bool,
+  ) -> Option<Result<(), JellyfinError>> {
+    let queue_item = state.write().queue.step(step).cloned()?;
+
+    log::info!(
+      "Playing queue item after {:?}: {} ({})",
+      step,
+      queue_item.id,
+      queue_item.playlist_item_id
+    );
+
+    if report_current_stopped {
+      Self::report_playback_stopped(client, state).await;
+    }
+
+    let play_request = PlayRequest {
+      item_ids: vec![queue_item.id.clone()],
+      start_position_ticks: None,
+      play_command: "PlayNow".to_string(),
+      media_source_id: None,
+      audio_stream_index: None,
+      subtitle_stream_index: None,
+      start_index: None,
+    };
+
+    Some(
+      Self::play_item(
+        client,
+        state,
+        action_tx,
+        true,
+        config,
+        &play_request,
+        &queue_item.id,
+      )
+      .await,
+    )
+  }
+
   async fn play_library_request(
     client: &JellyfinClient,
     state: &RwLock<SessionState>,
@@ -1538,6 +4473,7 @@ impl SessionManager {
       action_tx,
       mpv_connected,
       config,
+      PlayOrigin::Local,
       play_request,
     )
     .await
@@ -1590,6 +4526,7 @@ impl SessionManager {
       media_source_id: None,
       audio_stream_index: request.audio_stream_index,
       subtitle_stream_index: request.subtitle_stream_index,
+      start_index: None,
     })
   }
 
@@ -1606,6 +4543,290 @@ impl SessionManager {
     .await
   }
 
+  /// Seek the current item and report the new position to the server.
+  pub async fn seek(&self, target: SeekTarget) -> Result<(), String> {
+    Self::seek_and_report(&self.client, &self.state, &self.action_tx, target).await
+  }
+
+  /// Chapters of the playing item, named from the server where it can.
+  pub async fn chapters(&self) -> Result<Vec<PlaybackChapter>, String> {
+    let item_id = self
+      .state
+      .read()
+      .playback
+      .as_ref()
+      .map(|playback| playback.item_id.clone())
+      .ok_or("Chapters are available during playback")?;
+    let mpv_chapters = Self::mpv_chapters(&self.mpv).await;
+    let server_chapters = self
+      .client
+      .playback()
+      .get_item_chapters(&item_id)
+      .await
+      .unwrap_or_else(|e| {
+        log::warn!("Failed to fetch chapters of {}: {}", item_id, e);
+        Vec::new()
+      });
+    Ok(merge_chapters(&mpv_chapters, &server_chapters))
+  }
+
+  /// Seek to the start of the chapter at `index` in [`Self::chapters`].
+  pub async fn goto_chapter(&self, index: u32) -> Result<(), String> {
+    let chapters = self.chapters().await?;
+    let chapter = chapters
+      .get(index as usize)
+      .ok_or_else(|| format!("The current item has no chapter {}", index))?;
+    self.seek(SeekTarget::Seconds(chapter.start_seconds)).await
+  }
+
+  /// MPV's `chapter-list`; empty when the file has none or it can't be read.
+  async fn mpv_chapters(mpv: &MpvClient) -> Vec<Chapter> {
+    match mpv
+      .get_property_as::<Option<ChapterList>>("chapter-list")
+      .await
+    {
+      Ok(chapters) => chapters.unwrap_or_default(),
+      Err(e) => {
+        log::warn!("Failed to read MPV chapter list: {}", e);
+        Vec::new()
+      }
+    }
+  }
+
+  async fn seek_and_report(
+    client: &JellyfinClient,
+    state: &RwLock<SessionState>,
+    action_tx: &mpsc::Sender<MpvAction>,
+    target: SeekTarget,
+  ) -> Result<(), String> {
+    let ticks = {
+      let s = state.read();
+      if s.playback.is_none() {
+        return Err("Seeking is available during playback".to_string());
+      }
+      if !s.phase.can_seek() {
+        return Err("Seeking is available once the item has loaded".to_string());
+      }
+      let runtime_ticks = s
+        .current_item
+        .as_ref()
+        .and_then(|item| item.run_time_ticks)
+        .filter(|ticks| *ticks > 0);
+      seek_target_ticks(target, runtime_ticks)?
+    };
+
+    Self::apply_seek(state, action_tx, ticks).await;
+    Self::report_progress(client, state).await;
+    Ok(())
+  }
+
+  /// Record the new position and move MPV there.
+  async fn apply_seek(
+    state: &RwLock<SessionState>,
+    action_tx: &mpsc::Sender<MpvAction>,
+    ticks: i64,
+  ) {
+    if let Some(playback) = state.write().playback.as_mut() {
+      playback.position_ticks = ticks;
+    }
+    let _ = action_tx
+      .send(MpvAction::Seek(ticks_to_seconds(ticks)))
+      .await;
+  }
+
+  /// Pause for a screen lock or system sleep and report the position right away.
+  /// Resuming is left to the user.
+  pub async fn pause_for_system_event(&self) {
+    Self::pause_and_report(&self.client, &self.state, &*self.mpv).await;
+  }
+
+  /// Check that the WebSocket survived a system sleep; reconnects if it did not.
+  pub fn revalidate_connection(&self) {
+    self.websocket.revalidate();
+  }
+
+  /// True while the remote-control WebSocket is connected.
+  pub fn is_websocket_connected(&self) -> bool {
+    self.websocket.is_connected()
+  }
+
+  async fn pause_and_report(
+    client: &JellyfinClient,
+    state: &RwLock<SessionState>,
+    mpv: &impl PauseControl,
+  ) {
+    if Self::is_paused(state) != Some(false) {
+      return;
+    }
+    log::info!("Pausing playback for system lock/sleep");
+    if let Err(e) = Self::apply_pause(client, state, mpv, Some(true)).await {
+      log::warn!("Failed to pause for system lock/sleep: {}", e);
+    }
+  }
+
+  /// Pause, resume or, with `None`, toggle playback from the window, tray or
+  /// control API. Returns whether MPV is paused afterwards.
+  pub async fn set_pause(&self, paused: Option<bool>) -> Result<bool, String> {
+    Self::apply_pause(&self.client, &self.state, &*self.mpv, paused).await
+  }
+
+  /// Every pause change goes through here, whoever asked for it: MPV is
+  /// changed, its resulting state is taken into the session, and the server
+  /// is told right away. Returns whether MPV is paused afterwards.
+  async fn apply_pause(
+    client: &JellyfinClient,
+    state: &RwLock<SessionState>,
+    mpv: &impl PauseControl,
+    paused: Option<bool>,
+  ) -> Result<bool, String> {
+    let paused = match paused {
+      Some(paused) => paused,
+      // MPV knows about pauses made in its own window; the session may not yet.
+      None => match mpv.get_pause().await {
+        Ok(paused) => !paused,
+        Err(e) => {
+          log::warn!(
+            "Failed to get pause state from MPV: {}, using session state",
+            e
+          );
+          !Self::is_paused(state).unwrap_or(false)
+        }
+      },
+    };
+    mpv.set_pause(paused).await?;
+    if let Err(e) = Self::sync_pause_from_mpv(state, mpv).await {
+      log::warn!("Failed to confirm pause state with MPV: {}", e);
+      let was_paused = Self::is_paused(state);
+      if let Some(playback) = state.write().playback.as_mut() {
+        playback.is_paused = paused;
+      }
+      Self::fire_pause_webhook(state, was_paused);
+    }
+    let paused = Self::is_paused(state).unwrap_or(paused);
+    Self::report_progress(client, state).await;
+    Ok(paused)
+  }
+
+  /// Take MPV's pause state into the session. Returns true when the session
+  /// disagreed and was corrected.
+  async fn sync_pause_from_mpv(
+    state: &RwLock<SessionState>,
+    mpv: &impl PauseControl,
+  ) -> Result<bool, String> {
+    let paused = mpv.get_pause().await?;
+    let was_paused = Self::is_paused(state);
+    if was_paused.is_none_or(|was_paused| was_paused == paused) {
+      return Ok(false);
+    }
+    if let Some(playback) = state.write().playback.as_mut() {
+      playback.is_paused = paused;
+    }
+    Self::fire_pause_webhook(state, was_paused);
+    Ok(true)
+  }
+
+  /// Warn when another install uses this device ID, checking the server's
+  /// sessions until the session is replaced.
+  fn start_device_conflict_check(&self) {
+    let client = self.client.clone();
+    let websocket = Arc::downgrade(&self.websocket);
+    let device_endpoint = self.device_endpoint.clone();
+    let app_handle = self.app_handle.clone();
+
+    tokio::spawn(async move {
+      let mut interval = tokio::time::interval(DEVICE_CONFLICT_CHECK_INTERVAL);
+      loop {
+        interval.tick().await;
+        let Some(websocket) = websocket.upgrade() else {
+          break;
+        };
+        if !websocket.is_connected() {
+          continue;
+        }
+        let sessions = match client.playback().device_sessions().await {
+          Ok(sessions) => sessions,
+          Err(e) => {
+            log::debug!("Device conflict check skipped: {}", e);
+            continue;
+          }
+        };
+        if let Some(address) = device_endpoint.write().check(&sessions) {
+          log::warn!("Another client at {} uses this device ID", redact(&address));
+          AppNotification::warning(
+            &app_handle,
+            tr!("notify-device-id-conflict", address = address),
+          );
+        }
+      }
+    });
+  }
+
+  /// Correct pause state that drifted from MPV, e.g. when a property change
+  /// was lost during an IPC reconnect.
+  fn start_pause_drift_check(&self) {
+    let mpv = self.mpv.clone();
+    let client = self.client.clone();
+    let state = self.state.clone();
+
+    tokio::spawn(async move {
+      let mut interval = tokio::time::interval(PAUSE_DRIFT_CHECK_INTERVAL);
+      loop {
+        interval.tick().await;
+        if state.read().playback.is_none() || !mpv.is_connected() {
+          continue;
+        }
+        match Self::sync_pause_from_mpv(&state, &*mpv).await {
+          Ok(true) => {
+            log::warn!("Session pause state drifted from MPV; corrected");
+            Self::report_progress(&client, &state).await;
+          }
+          Ok(false) => {}
+          Err(e) => log::debug!("Pause drift check skipped: {}", e),
+        }
+      }
+    });
+  }
+
+  /// Mark the playing item watched or unwatched, whichever it is not.
+  /// Called from the system tray. Returns whether it is watched afterwards.
+  pub async fn toggle_watched(&self) -> Result<bool, String> {
+    Self::toggle_current_user_data(
+      &self.client,
+      &self.state,
+      &self.action_tx,
+      UserDataToggle::Watched,
+    )
+    .await
+  }
+
+  /// Rate the playing item thumbs up (`likes`) or thumbs down.
+  pub async fn set_user_rating(&self, likes: bool) -> Result<NowPlayingUserData, String> {
+    let item_id = self
+      .current_item()
+      .map(|item| item.id)
+      .ok_or_else(|| "Rating is available during playback".to_string())?;
+    let update = self
+      .client
+      .library()
+      .set_rating(&item_id, likes)
+      .await
+      .map_err(|e| e.to_string())?;
+    Self::store_user_data(&self.state, &update);
+    Ok(NowPlayingUserData::from(&update))
+  }
+
+  /// Add the playing item to the favorites or remove it. Called from the
+  /// system tray. Returns whether it is a favorite afterwards.
+  pub async fn toggle_favorite(&self) -> Result<bool, String> {
+    Self::toggle_current_user_data(
+      &self.client,
+      &self.state,
+      &self.action_tx,
+      UserDataToggle::Favorite,
+    )
+    .await
+  }
+
   /// Play the next episode. Called from system tray or UI.
   pub async fn play_next_episode(&self) -> Result<(), String> {
     let current_item = {
@@ -1622,7 +4843,7 @@ impl SessionManager {
         &self.config,
         &item,
         true,
-        true,
+        AdjacentTrigger::User,
       )
       .await
     } else {
@@ -1647,7 +4868,7 @@ impl SessionManager {
         &self.config,
         &item,
         false,
-        true,
+        AdjacentTrigger::User,
       )
       .await
     } else {
@@ -1656,15 +4877,89 @@ impl SessionManager {
     }
   }
 
+  /// Versions of the current item, with the id of the one playing.
+  pub async fn media_versions(&self) -> Result<(Vec<MediaSource>, Option<String>), String> {
+    let (item_id, current_id) = {
+      let s = self.state.read();
+      let playback = s
+        .playback
+        .as_ref()
+        .ok_or_else(|| "Versions are available during playback".to_string())?;
+      (playback.item_id.clone(), playback.media_source_id.clone())
+    };
+    let playback_info = self
+      .client
+      .playback()
+      .get_playback_info(&item_id, None, None)
+      .await
+      .map_err(|e| e.to_string())?;
+    Ok((playback_info.media_sources, current_id))
+  }
+
+  /// Reload the current item from another version at the current position.
+  pub async fn switch_media_version(&self, media_source_id: &str) -> Result<(), String> {
+    let generation = self.state.read().playback_generation;
+    let (versions, current_id) = self.media_versions().await?;
+    if current_id.as_deref() == Some(media_source_id) {
+      return Ok(());
+    }
+    if !versions.iter().any(|version| version.id == media_source_id) {
+      return Err(format!("Version {} not found", media_source_id));
+    }
+
+    let (item_id, position_ticks) = {
+      let s = self.state.read();
+      let playback = s
+        .playback_in(generation)
+        .ok_or_else(|| "Playback changed while loading versions".to_string())?;
+      (playback.item_id.clone(), playback.position_ticks)
+    };
+    log::info!(
+      "Switching {} to version {} at {} ticks",
+      item_id,
+      media_source_id,
+      position_ticks
+    );
+
+    // Stream indices differ between versions; preferences pick the new tracks.
+    let request = PlayRequest {
+      item_ids: vec![item_id.clone()],
+      start_position_ticks: Some(position_ticks),
+      play_command: "PlayNow".to_string(),
+      media_source_id: Some(media_source_id.to_string()),
+      audio_stream_index: None,
+      subtitle_stream_index: None,
+      start_index: None,
+    };
+    Self::report_playback_stopped(&self.client, &self.state).await;
+    Self::play_item(
+      &self.client,
+      &self.state,
+      &self.action_tx,
+      self.mpv.is_connected(),
+      &self.config,
+      &request,
+      &item_id,
+    )
+    .await
+    .map_err(|e| e.to_string())
+  }
+
   /// Stop the session.
   pub async fn stop(&self) -> Result<(), JellyfinError> {
+    self.shutdown.cancel();
     // Report playback stopped if there's an active session
-    let session = {
+    let (session, item) = {
       let mut s = self.state.write();
-      s.playback.take()
+      s.queue.clear();
+      s.session_files.clear();
+      (s.playback.take(), s.current_item.clone())
     };
 
     if let Some(session) = session {
+      if let Some(item) = &item {
+        webhooks::fire(WebhookEvent::Stopped, item, session.position_ticks);
+      }
       let stop_info = PlaybackStopInfo {
         item_id: session.item_id,
         media_source_id: session.media_source_id,
@@ -1679,6 +4974,7 @@ impl SessionManager {
     }
 
     self.websocket.disconnect().await;
+    self.state.write().enter(SessionPhase::Disconnected);
     Ok(())
   }
 }
@@ -1693,131 +4989,84 @@ fn parse_command_int(value: Option<&serde_json::Value>) -> Option<i64> {
   })
 }
 
-fn intro_skipper_label(kind: IntroSkipKind) -> &'static str {
-  match kind {
-    IntroSkipKind::Introduction => "Intro",
-    IntroSkipKind::Credits => "Credits",
+/// Map a Jellyfin `ShuffleMode` argument to the queue playback order.
+fn parse_shuffle_mode(mode: &str) -> Option<PlaybackOrder> {
+  match mode {
+    "Shuffle" => Some(PlaybackOrder::Shuffle),
+    "Sorted" => Some(PlaybackOrder::Default),
+    _ => None,
   }
 }
 
-fn intro_skipper_label_lower(kind: IntroSkipKind) -> &'static str {
-  match kind {
-    IntroSkipKind::Introduction => "intro",
-    IntroSkipKind::Credits => "credits",
-  }
+/// Runtime to report for the current item. Items without a known runtime, and
+/// recordings that keep growing, use the duration MPV reports.
+fn reported_run_time_ticks(item: Option<&MediaItem>, playback: &PlaybackSession) -> Option<i64> {
+  item
+    .and_then(|item| item.run_time_ticks)
+    .filter(|ticks| *ticks > 0 && !playback.is_growing)
+    .or(playback.mpv_duration_ticks)
 }
 
-/// Redact sensitive URL/header fragments from log text.
-fn redact_url(url: &str) -> String {
-  const SENSITIVE_KEYS: &[&str] = &[
-    "api_key",
-    "access_token",
-    "accesstoken",
-    "token",
-    "password",
-    "pw",
-  ];
-
-  let mut output = String::with_capacity(url.len());
-  let mut cursor = 0;
-
-  while cursor < url.len() {
-    let Some((_, key_end)) = find_sensitive_assignment(&url[cursor..], SENSITIVE_KEYS) else {
-      output.push_str(&url[cursor..]);
-      break;
-    };
-
-    let key_end = cursor + key_end;
-    let value_start = key_end + 1;
-    let quote = url[value_start..]
-      .chars()
-      .next()
-      .filter(|ch| matches!(ch, '"' | '\''));
-    let value_start = value_start + quote.map(char::len_utf8).unwrap_or(0);
-    let value_end = find_assignment_value_end(url, value_start, quote);
-
-    output.push_str(&url[cursor..value_start]);
-    output.push_str("[REDACTED]");
-    if let Some(quote) = quote {
-      if value_end < url.len() && url[value_end..].starts_with(quote) {
-        output.push(quote);
-        cursor = value_end + quote.len_utf8();
-        continue;
-      }
+/// Resolve a seek target to ticks, clamped to the item's runtime when known.
+fn seek_target_ticks(target: SeekTarget, runtime_ticks: Option<i64>) -> Result<i64, String> {
+  let ticks = match target {
+    SeekTarget::Seconds(seconds) if seconds.is_finite() && seconds >= 0.0 => {
+      seconds_to_ticks(seconds)
     }
-    cursor = value_end;
-  }
-
-  output
-}
-
-fn find_sensitive_assignment(text: &str, sensitive_keys: &[&str]) -> Option<(usize, usize)> {
-  let bytes = text.as_bytes();
-  let mut index = 0;
-
-  while index < bytes.len() {
-    if is_key_boundary(text, index) {
-      let key_start = index + boundary_len(text, index);
-      let mut key_end = key_start;
-      while key_end < bytes.len() && is_assignment_key_byte(bytes[key_end]) {
-        key_end += 1;
-      }
-
-      if key_end < bytes.len()
-        && bytes[key_end] == b'='
-        && sensitive_keys
-          .iter()
-          .any(|key| text[key_start..key_end].eq_ignore_ascii_case(key))
-      {
-        return Some((key_start, key_end));
-      }
-
-      index = key_end.saturating_add(1);
-    } else {
-      index += 1;
+    SeekTarget::Seconds(_) => {
+      return Err("Seek position must be a non-negative number".to_string())
     }
-  }
-
-  None
+    SeekTarget::Percent(percent) if percent.is_finite() && (0.0..=100.0).contains(&percent) => {
+      let runtime_ticks =
+        runtime_ticks.ok_or_else(|| "The current item has no known runtime".to_string())?;
+      (runtime_ticks as f64 * percent / 100.0) as i64
+    }
+    SeekTarget::Percent(_) => return Err("Seek percent must be between 0 and 100".to_string()),
+  };
+  Ok(match runtime_ticks {
+    Some(runtime_ticks) => ticks.min(runtime_ticks),
+    None => ticks,
+  })
 }
 
-fn is_key_boundary(text: &str, index: usize) -> bool {
-  index == 0
-    || matches!(
-      text.as_bytes()[index],
-      b'?' | b'&' | b',' | b' ' | b'\t' | b'\n'
-    )
+fn has_credits_range(playback: &PlaybackSession) -> bool {
+  playback
+    .skip_ranges
+    .iter()
+    .any(|range| range.kind == SkipKind::Credits)
 }
 
-fn boundary_len(text: &str, index: usize) -> usize {
-  if matches!(text.as_bytes()[index], b'?' | b'&') {
-    1
-  } else {
-    0
+fn skip_available_text(kind: SkipKind, key: &str) -> String {
+  let key = key.to_string();
+  match kind {
+    SkipKind::Introduction => tr!("osd-intro-available", key = key),
+    SkipKind::Credits => tr!("osd-credits-available", key = key),
+    SkipKind::Recap => tr!("osd-recap-available", key = key),
+    SkipKind::Commercial => tr!("osd-commercial-available", key = key),
+    SkipKind::Custom => tr!("osd-segment-available", key = key),
   }
 }
 
-fn is_assignment_key_byte(byte: u8) -> bool {
-  byte.is_ascii_alphanumeric() || matches!(byte, b'_' | b'-')
+/// Playback speed one shortcut step away from `current`, on the step grid.
+fn stepped_speed(current: f64, faster: bool) -> f64 {
+  let step = if faster { 1.0 } else { -1.0 };
+  (((current / SPEED_STEP).round() + step) * SPEED_STEP).clamp(MIN_SPEED, MAX_SPEED)
 }
 
-fn find_assignment_value_end(text: &str, value_start: usize, quote: Option<char>) -> usize {
-  if let Some(quote) = quote {
-    text[value_start..]
-      .find(quote)
-      .map(|offset| value_start + offset)
-      .unwrap_or(text.len())
-  } else {
-    text[value_start..]
-      .find(['&', ' ', '\t', '\n', '\r', '"', '\''])
-      .map(|offset| value_start + offset)
-      .unwrap_or(text.len())
+fn skipped_text(kind: SkipKind) -> String {
+  match kind {
+    SkipKind::Introduction => tr!("osd-skipped-intro"),
+    SkipKind::Credits => tr!("osd-skipped-credits"),
+    SkipKind::Recap => tr!("osd-skipped-recap"),
+    SkipKind::Commercial => tr!("osd-skipped-commercial"),
+    SkipKind::Custom => tr!("osd-skipped-segment"),
   }
 }
 
 #[cfg(test)]
 mod tests {
-  use super::super::intro_skipper::{IntroSkipKind, IntroSkipRange};
+  use super::super::skip_range::{SkipKind, SkipRange};
+  use super::super::tls::TlsTrust;
   use super::*;
   use std::sync::Arc;
   use tokio::io::{AsyncReadExt, AsyncWriteExt};
@@ -1880,6 +5129,10 @@ mod tests {
         user_name: "Ada".to_string(),
         server_name: Some("Jellyfin Home".to_string()),
         device_id: Some("device-1".to_string()),
+        tls: TlsTrust::default(),
+        local_url: None,
+        custom_headers: Default::default(),
+        server_id: None,
       })
       .await
       .expect("test client should restore saved session");
@@ -1906,6 +5159,10 @@ mod tests {
         user_name: "Ada".to_string(),
         server_name: Some("Emby Home".to_string()),
         device_id: Some("device-1".to_string()),
+        tls: TlsTrust::default(),
+        local_url: None,
+        custom_headers: Default::default(),
+        server_id: None,
       })
       .await
       .expect("test Emby client should restore saved session");
@@ -1920,16 +5177,38 @@ mod tests {
     })
   }
 
+  impl SessionState {
+    /// Idle state without playback; tests set the fields they need.
+    pub(super) fn for_test() -> Self {
+      SessionState {
+        playback: None,
+        playback_generation: PlaybackGeneration::default(),
+        last_report_time: std::time::Instant::now(),
+        effective_intro_skipper_config: IntroSkipperRuntimeConfig::from(&AppConfig::default()),
+        preference_target: None,
+        current_item: None,
+        current_media_streams: Vec::new(),
+        track_preferences: HashMap::new(),
+        queue: PlaybackQueue::default(),
+        auto_advanced: false,
+        marked_skip_ranges: HashMap::new(),
+        skip_mark_start: None,
+        credits_countdown: None,
+        stall: StallTracker::default(),
+        subtitle_tracks: SubtitleTracks::default(),
+        user_data: None,
+        paused_at: None,
+        ab_loop: AbLoop::default(),
+        transcode_restarts: 0,
+        early_end: None,
+        session_files: SessionFiles::default(),
+        phase: SessionPhase::Idle,
+      }
+    }
+  }
+
   fn empty_test_state() -> RwLock<SessionState> {
-    RwLock::new(SessionState {
-      playback: None,
-      last_report_time: std::time::Instant::now(),
-      effective_intro_skipper_config: IntroSkipperRuntimeConfig::from(&AppConfig::default()),
-      current_series_id: None,
-      current_item: None,
-      current_media_streams: Vec::new(),
-      series_preferences: HashMap::new(),
-    })
+    RwLock::new(SessionState::for_test())
   }
 
   fn test_state_with_active_playback() -> RwLock<SessionState> {
@@ -1938,7 +5217,7 @@ mod tests {
         item_id: "old-movie".to_string(),
         media_source_id: Some("old-source".to_string()),
         play_session_id: Some("old-play".to_string()),
-        intro_skipper_ranges: Vec::new(),
+        skip_ranges: Vec::new(),
         position_ticks: 420_000_000,
         is_paused: false,
         is_muted: false,
@@ -1946,22 +5225,23 @@ mod tests {
         audio_stream_index: None,
         subtitle_stream_index: None,
         play_method: "DirectPlay".to_string(),
+        mpv_duration_ticks: None,
+        can_seek: true,
+        is_growing: false,
+        subtitles_burned_in: false,
+        live_stream_id: None,
       }),
-      last_report_time: std::time::Instant::now(),
-      effective_intro_skipper_config: IntroSkipperRuntimeConfig::from(&AppConfig::default()),
-      current_series_id: None,
-      current_item: None,
-      current_media_streams: Vec::new(),
-      series_preferences: HashMap::new(),
+      phase: SessionPhase::Playing,
+      ..SessionState::for_test()
     })
   }
 
   pub(super) fn test_state_with_intro_range() -> RwLock<SessionState> {
-    test_state_with_range(IntroSkipKind::Introduction, 10.0, 80.0)
+    test_state_with_range(SkipKind::Introduction, 10.0, 80.0)
   }
 
   fn test_state_with_range(
-    kind: IntroSkipKind,
+    kind: SkipKind,
     start_seconds: f64,
     end_seconds: f64,
   ) -> RwLock<SessionState> {
@@ -1970,7 +5250,7 @@ mod tests {
         item_id: "item-1".to_string(),
         media_source_id: Some("source-1".to_string()),
         play_session_id: Some("play-1".to_string()),
-        intro_skipper_ranges: vec![IntroSkipRange {
+        skip_ranges: vec![SkipRange {
           kind,
           start_seconds,
           end_seconds,
@@ -1984,16 +5264,56 @@ mod tests {
         audio_stream_index: None,
         subtitle_stream_index: None,
         play_method: "DirectPlay".to_string(),
+        mpv_duration_ticks: None,
+        can_seek: true,
+        is_growing: false,
+        subtitles_burned_in: false,
+        live_stream_id: None,
       }),
-      last_report_time: std::time::Instant::now(),
-      effective_intro_skipper_config: IntroSkipperRuntimeConfig::from(&AppConfig::default()),
-      current_series_id: None,
-      current_item: None,
-      current_media_streams: Vec::new(),
-      series_preferences: HashMap::new(),
+      phase: SessionPhase::Playing,
+      ..SessionState::for_test()
     })
   }
 
+  #[test]
+  fn track_change_for_a_superseded_playback_leaves_the_new_item_alone() {
+    let state = test_state_with_active_playback();
+    let audio: MediaStream =
+      serde_json::from_value(serde_json::json!({"Index": 1, "Type": "Audio", "Language": "jpn"}))
+        .unwrap();
+    let generation = {
+      let mut s = state.write();
+      s.preference_target = Some(PreferenceTarget {
+        key: "series-1".to_string(),
+        label: None,
+      });
+      s.current_media_streams = vec![audio];
+      s.playback_generation
+    };
+    // A play of another series starts before the track change is applied.
+    {
+      let mut s = state.write();
+      s.begin_playback();
+      s.preference_target = Some(PreferenceTarget {
+        key: "series-2".to_string(),
+        label: None,
+      });
+    }
+
+    let mut s = state.write();
+    assert_eq!(s.remember_audio_track(generation, 1), None);
+    assert_eq!(s.remember_subtitle_track(generation, -1), None);
+    assert!(s.track_preferences.is_empty());
+    assert_eq!(s.playback.as_ref().unwrap().audio_stream_index, None);
+
+    let current = s.playback_generation;
+    assert_eq!(s.remember_audio_track(current, 1), Some(true));
+    assert_eq!(
+      s.track_preferences["series-2"].audio_language.as_deref(),
+      Some("jpn")
+    );
+  }
+
   #[tokio::test]
   async fn library_play_replaces_active_playback_and_resumes_from_saved_position() {
     let (client, requests) = connected_test_client(vec![
@@ -2054,19 +5374,487 @@ mod tests {
       other => panic!("expected play action, got {other:?}"),
     }
 
-    let playback = state.read().playback.clone().expect("new playback state");
-    assert_eq!(playback.item_id, "movie-1");
-    assert_eq!(playback.position_ticks, 1_200_000_000);
-    assert_eq!(playback.audio_stream_index, Some(1));
-    assert_eq!(playback.subtitle_stream_index, Some(2));
+    let playback = state.read().playback.clone().expect("new playback state");
+    assert_eq!(playback.item_id, "movie-1");
+    assert_eq!(playback.position_ticks, 1_200_000_000);
+    assert_eq!(playback.audio_stream_index, Some(1));
+    assert_eq!(playback.subtitle_stream_index, Some(2));
+
+    let captured = requests.lock();
+    assert!(captured[2].starts_with("POST /Sessions/Playing/Stopped "));
+    assert!(captured[2].contains(r#""ItemId":"old-movie""#));
+    assert!(captured[2].contains(r#""PositionTicks":420000000"#));
+    assert!(captured[5].starts_with("POST /Sessions/Playing "));
+    assert!(captured[5].contains(r#""ItemId":"movie-1""#));
+    assert!(captured[5].contains(r#""PositionTicks":1200000000"#));
+  }
+
+  #[test]
+  fn seek_target_ticks_resolves_percent_and_clamps_to_runtime() {
+    let runtime = Some(seconds_to_ticks(200.0));
+
+    assert_eq!(
+      seek_target_ticks(SeekTarget::Percent(25.0), runtime),
+      Ok(seconds_to_ticks(50.0))
+    );
+    assert_eq!(
+      seek_target_ticks(SeekTarget::Seconds(500.0), runtime),
+      Ok(seconds_to_ticks(200.0))
+    );
+    assert!(seek_target_ticks(SeekTarget::Percent(50.0), None).is_err());
+    assert!(seek_target_ticks(SeekTarget::Percent(101.0), runtime).is_err());
+    assert!(seek_target_ticks(SeekTarget::Seconds(-1.0), runtime).is_err());
+  }
+
+  #[tokio::test]
+  async fn window_seek_updates_session_and_reports_new_position() {
+    let (client, requests) = connected_test_client(vec![
+      (
+        "200 OK",
+        r#"{"Id":"00000000-0000-0000-0000-000000000001","Name":"Ada"}"#,
+      ),
+      (
+        "200 OK",
+        r#"{"ServerName":"Jellyfin Home","Version":"10.10.0","Id":"server-1"}"#,
+      ),
+      ("204 No Content", ""),
+    ])
+    .await;
+    let state = test_state_with_active_playback();
+    let (action_tx, mut action_rx) = mpsc::channel(4);
+
+    SessionManager::seek_and_report(&client, &state, &action_tx, SeekTarget::Seconds(30.0))
+      .await
+      .expect("seek during playback should succeed");
+
+    assert!(matches!(action_rx.try_recv(), Ok(MpvAction::Seek(position)) if position == 30.0));
+    let playback = state.read().playback.clone().expect("playback continues");
+    assert_eq!(playback.position_ticks, 300_000_000);
+    let captured = requests.lock();
+    assert!(captured[2].starts_with("POST /Sessions/Playing/Progress "));
+    assert!(captured[2].contains(r#""PositionTicks":300000000"#));
+  }
+
+  #[tokio::test]
+  async fn window_seek_waits_until_the_item_has_loaded() {
+    let client = JellyfinClient::new();
+    let state = test_state_with_active_playback();
+    state.write().enter(SessionPhase::Loading);
+    let (action_tx, mut action_rx) = mpsc::channel(4);
+
+    let result =
+      SessionManager::seek_and_report(&client, &state, &action_tx, SeekTarget::Seconds(30.0)).await;
+
+    assert!(result.is_err());
+    assert!(action_rx.try_recv().is_err());
+    let playback = state.read().playback.clone().expect("playback continues");
+    assert_eq!(playback.position_ticks, 420_000_000);
+  }
+
+  #[tokio::test]
+  async fn a_play_preempted_by_a_stop_leaves_the_session_idle() {
+    let state = test_state_with_active_playback();
+    assert!(state.write().enter(SessionPhase::Loading));
+    let mpv = MpvClient::new(None);
+    let (action_tx, mut action_rx) = mpsc::channel(4);
+    let mut backlog = VecDeque::new();
+    let limits = ActionLimits {
+      slow_after: std::time::Duration::from_millis(20),
+      give_up_after: std::time::Duration::from_secs(5),
+    };
+    action_tx.send(MpvAction::Stop).await.unwrap();
+
+    let outcome = action_watchdog::watch(
+      std::future::pending(),
+      limits,
+      &mut action_rx,
+      &mut backlog,
+      || {},
+    )
+    .await;
+    assert_eq!(outcome, ActionOutcome::Preempted);
+    SessionManager::abandon_load(&mpv, &state).await;
+
+    assert_eq!(state.read().phase, SessionPhase::Idle);
+    // The stop that follows has nothing left to wait for.
+    state.write().stopping();
+    assert_eq!(state.read().phase, SessionPhase::Idle);
+    assert!(!state.write().enter(SessionPhase::Stopping));
+    assert!(state.write().enter(SessionPhase::Loading));
+  }
+
+  #[tokio::test]
+  async fn clearing_playback_keeps_the_context_of_a_play_started_meanwhile() {
+    let (client, requests) = connected_test_client(vec![
+      (
+        "200 OK",
+        r#"{"Id":"00000000-0000-0000-0000-000000000001","Name":"Ada"}"#,
+      ),
+      (
+        "200 OK",
+        r#"{"ServerName":"Jellyfin Home","Version":"10.10.0","Id":"server-1"}"#,
+      ),
+      ("204 No Content", ""),
+    ])
+    .await;
+    let state = test_state_with_active_playback();
+    let next_item: MediaItem =
+      serde_json::from_str(r#"{"Id":"movie-2","Name":"Next Movie","Type":"Movie"}"#).unwrap();
+
+    // The play lands while the stop report is in flight.
+    tokio::join!(
+      SessionManager::clear_playback_context(&client, &state),
+      async {
+        let mut s = state.write();
+        s.begin_playback();
+        s.current_item = Some(next_item);
+      }
+    );
+
+    let s = state.read();
+    assert!(s.playback.is_none());
+    assert_eq!(
+      s.current_item.as_ref().map(|item| item.id.as_str()),
+      Some("movie-2")
+    );
+    assert!(requests.lock()[2].starts_with("POST /Sessions/Playing/Stopped "));
+  }
+
+  #[tokio::test]
+  async fn recording_refresh_does_not_update_a_replay_started_meanwhile() {
+    let (client, _requests) = connected_test_client(vec![
+      (
+        "200 OK",
+        r#"{"Id":"00000000-0000-0000-0000-000000000001","Name":"Ada"}"#,
+      ),
+      (
+        "200 OK",
+        r#"{"ServerName":"Jellyfin Home","Version":"10.10.0","Id":"server-1"}"#,
+      ),
+      (
+        "200 OK",
+        r#"{"Id":"old-movie","Name":"Evening News","Type":"Recording","Status":"Completed"}"#,
+      ),
+    ])
+    .await;
+    let state = test_state_with_active_playback();
+    state.write().playback.as_mut().unwrap().is_growing = true;
+    let mut last_refresh = std::time::Instant::now() - RECORDING_REFRESH_INTERVAL;
+
+    // The same recording is played again while the refresh is in flight.
+    tokio::join!(
+      SessionManager::refresh_growing_recording(&client, &state, &mut last_refresh),
+      async {
+        let mut s = state.write();
+        s.begin_playback();
+        s.playback.as_mut().unwrap().position_ticks = 0;
+      }
+    );
+
+    let s = state.read();
+    assert!(s.playback.as_ref().unwrap().is_growing);
+    assert!(s.current_item.is_none());
+  }
+
+  #[tokio::test]
+  async fn reconnect_reannounces_playback_at_current_position() {
+    let (client, requests) = connected_test_client(vec![
+      (
+        "200 OK",
+        r#"{"Id":"00000000-0000-0000-0000-000000000001","Name":"Ada"}"#,
+      ),
+      (
+        "200 OK",
+        r#"{"ServerName":"Jellyfin Home","Version":"10.10.0","Id":"server-1"}"#,
+      ),
+      ("204 No Content", ""),
+    ])
+    .await;
+    let state = test_state_with_active_playback();
+
+    SessionManager::reannounce_playback(&client, &state).await;
+
+    assert!(state.read().playback.is_some());
+    let captured = requests.lock();
+    assert_eq!(captured.len(), 3);
+    assert!(captured[2].starts_with("POST /Sessions/Playing "));
+    assert!(captured[2].contains(r#""PositionTicks":420000000"#));
+  }
+
+  #[tokio::test]
+  async fn cast_registration_retries_until_the_server_lists_the_device() {
+    let (client, requests) = connected_test_client(vec![
+      (
+        "200 OK",
+        r#"{"Id":"00000000-0000-0000-0000-000000000001","Name":"Ada"}"#,
+      ),
+      (
+        "200 OK",
+        r#"{"ServerName":"Jellyfin Home","Version":"10.10.0","Id":"server-1"}"#,
+      ),
+      ("204 No Content", ""),
+      ("200 OK", "[]"),
+      ("204 No Content", ""),
+      (
+        "200 OK",
+        r#"[{"DeviceId":"device-1","SupportsMediaControl":true,"SupportsRemoteControl":true}]"#,
+      ),
+    ])
+    .await;
+
+    SessionManager::register_cast_target(&client)
+      .await
+      .expect("second attempt should find the session");
+
+    let captured = requests.lock();
+    assert_eq!(captured.len(), 6);
+    assert!(captured[2].starts_with("POST /Sessions/Capabilities/Full "));
+    assert!(captured[3].starts_with("GET /Sessions"));
+    assert!(captured[4].starts_with("POST /Sessions/Capabilities/Full "));
+    assert!(
+      client
+        .connection_state()
+        .capabilities
+        .remote_control_available
+    );
+  }
+
+  #[tokio::test]
+  async fn system_pause_pauses_once_and_reports_paused_position() {
+    let (client, requests) = connected_test_client(vec![
+      (
+        "200 OK",
+        r#"{"Id":"00000000-0000-0000-0000-000000000001","Name":"Ada"}"#,
+      ),
+      (
+        "200 OK",
+        r#"{"ServerName":"Jellyfin Home","Version":"10.10.0","Id":"server-1"}"#,
+      ),
+      ("204 No Content", ""),
+    ])
+    .await;
+    let state = test_state_with_active_playback();
+    let mpv = FakePause::default();
+
+    SessionManager::pause_and_report(&client, &state, &mpv).await;
+    SessionManager::pause_and_report(&client, &state, &mpv).await;
+
+    assert_eq!(*mpv.set_calls.lock(), vec![true]);
+    let captured = requests.lock();
+    assert_eq!(captured.len(), 3);
+    assert!(captured[2].starts_with("POST /Sessions/Playing/Progress "));
+    assert!(captured[2].contains(r#""IsPaused":true"#));
+    assert!(captured[2].contains(r#""PositionTicks":420000000"#));
+  }
+
+  /// MPV pause state; `stuck` ignores every change, like a refused pause.
+  #[derive(Default)]
+  struct FakePause {
+    paused: parking_lot::Mutex<bool>,
+    stuck: bool,
+    set_calls: parking_lot::Mutex<Vec<bool>>,
+  }
+
+  impl PauseControl for FakePause {
+    async fn get_pause(&self) -> Result<bool, String> {
+      Ok(*self.paused.lock())
+    }
+
+    async fn set_pause(&self, paused: bool) -> Result<(), String> {
+      self.set_calls.lock().push(paused);
+      if !self.stuck {
+        *self.paused.lock() = paused;
+      }
+      Ok(())
+    }
+  }
+
+  #[tokio::test]
+  async fn toggling_pause_follows_mpv_and_session_keeps_what_mpv_did() {
+    let (client, requests) = connected_test_client(vec![
+      (
+        "200 OK",
+        r#"{"Id":"00000000-0000-0000-0000-000000000001","Name":"Ada"}"#,
+      ),
+      (
+        "200 OK",
+        r#"{"ServerName":"Jellyfin Home","Version":"10.10.0","Id":"server-1"}"#,
+      ),
+      ("204 No Content", ""),
+    ])
+    .await;
+    let state = test_state_with_active_playback();
+    // Paused in the MPV window; the session never heard about it.
+    let mpv = FakePause {
+      paused: parking_lot::Mutex::new(true),
+      stuck: true,
+      ..Default::default()
+    };
+
+    let paused = SessionManager::apply_pause(&client, &state, &mpv, None)
+      .await
+      .unwrap();
+
+    assert_eq!(*mpv.set_calls.lock(), vec![false]);
+    assert!(paused);
+    assert_eq!(SessionManager::is_paused(&state), Some(true));
+    let captured = requests.lock();
+    assert_eq!(captured.len(), 3);
+    assert!(captured[2].contains(r#""IsPaused":true"#));
+  }
+
+  #[tokio::test]
+  async fn pause_drift_is_corrected_from_mpv() {
+    let state = test_state_with_active_playback();
+    let mpv = FakePause {
+      paused: parking_lot::Mutex::new(true),
+      ..Default::default()
+    };
+
+    assert!(SessionManager::sync_pause_from_mpv(&state, &mpv)
+      .await
+      .unwrap());
+    assert_eq!(SessionManager::is_paused(&state), Some(true));
+    assert!(!SessionManager::sync_pause_from_mpv(&state, &mpv)
+      .await
+      .unwrap());
+  }
+
+  #[test]
+  fn fullscreen_on_start_follows_origin_and_config() {
+    let mut config = AppConfig {
+      fullscreen_remote_casts: true,
+      ..Default::default()
+    };
+
+    assert!(SessionManager::fullscreen_on_start(
+      &config,
+      PlayOrigin::RemoteCast
+    ));
+    assert!(!SessionManager::fullscreen_on_start(
+      &config,
+      PlayOrigin::Local
+    ));
+
+    config.fullscreen_on_playback = true;
+    assert!(SessionManager::fullscreen_on_start(
+      &config,
+      PlayOrigin::Local
+    ));
+    assert!(!SessionManager::fullscreen_on_start(
+      &config,
+      PlayOrigin::Continuation
+    ));
+  }
+
+  #[tokio::test]
+  async fn multi_item_play_reports_now_playing_queue_and_playlist_item_id() {
+    let (client, requests) = connected_test_client(vec![
+      (
+        "200 OK",
+        r#"{"Id":"00000000-0000-0000-0000-000000000001","Name":"Ada"}"#,
+      ),
+      (
+        "200 OK",
+        r#"{"ServerName":"Jellyfin Home","Version":"10.10.0","Id":"server-1"}"#,
+      ),
+      (
+        "200 OK",
+        r#"{"Id":"movie-2","Name":"Second Movie","Type":"Movie"}"#,
+      ),
+      (
+        "200 OK",
+        r#"{"MediaSources":[{"Id":"source-2","Protocol":"Http","Container":"mkv","MediaStreams":[]}],"PlaySessionId":"play-2"}"#,
+      ),
+      ("204 No Content", ""),
+    ])
+    .await;
+    let state = empty_test_state();
+    let config = test_config();
+    let (action_tx, _action_rx) = mpsc::channel(4);
+
+    SessionManager::handle_play(
+      &client,
+      &state,
+      &action_tx,
+      true,
+      &config,
+      PlayOrigin::RemoteCast,
+      PlayRequest {
+        item_ids: vec![
+          "movie-1".to_string(),
+          "movie-2".to_string(),
+          "movie-3".to_string(),
+        ],
+        start_position_ticks: None,
+        play_command: "PlayNow".to_string(),
+        media_source_id: None,
+        audio_stream_index: None,
+        subtitle_stream_index: None,
+        start_index: Some(1),
+      },
+    )
+    .await
+    .expect("multi-item play should start the selected item");
+
+    let playback = state.read().playback.clone().expect("new playback state");
+    assert_eq!(playback.item_id, "movie-2");
+
+    let captured = requests.lock();
+    assert!(captured[4].starts_with("POST /Sessions/Playing "));
+    assert!(captured[4].contains(
+      r#""NowPlayingQueue":[{"Id":"movie-1","PlaylistItemId":"playlistItem1"},{"Id":"movie-2","PlaylistItemId":"playlistItem2"},{"Id":"movie-3","PlaylistItemId":"playlistItem3"}]"#
+    ));
+    assert!(captured[4].contains(r#""PlaylistItemId":"playlistItem2""#));
+  }
+
+  #[tokio::test]
+  async fn play_next_during_playback_extends_queue_without_interrupting() {
+    let (client, requests) = connected_test_client(vec![
+      (
+        "200 OK",
+        r#"{"Id":"00000000-0000-0000-0000-000000000001","Name":"Ada"}"#,
+      ),
+      (
+        "200 OK",
+        r#"{"ServerName":"Jellyfin Home","Version":"10.10.0","Id":"server-1"}"#,
+      ),
+      ("204 No Content", ""),
+    ])
+    .await;
+    let state = test_state_with_active_playback();
+    state.write().queue.replace(&["old-movie".to_string()], 0);
+    let config = test_config();
+    let (action_tx, mut action_rx) = mpsc::channel(4);
+
+    SessionManager::handle_play(
+      &client,
+      &state,
+      &action_tx,
+      true,
+      &config,
+      PlayOrigin::RemoteCast,
+      PlayRequest {
+        item_ids: vec!["movie-next".to_string()],
+        start_position_ticks: None,
+        play_command: "PlayNext".to_string(),
+        media_source_id: None,
+        audio_stream_index: None,
+        subtitle_stream_index: None,
+        start_index: None,
+      },
+    )
+    .await
+    .expect("PlayNext should queue the item");
+
+    assert!(action_rx.try_recv().is_err());
+    let playback = state.read().playback.clone().expect("playback continues");
+    assert_eq!(playback.item_id, "old-movie");
 
     let captured = requests.lock();
-    assert!(captured[2].starts_with("POST /Sessions/Playing/Stopped "));
-    assert!(captured[2].contains(r#""ItemId":"old-movie""#));
-    assert!(captured[2].contains(r#""PositionTicks":420000000"#));
-    assert!(captured[5].starts_with("POST /Sessions/Playing "));
-    assert!(captured[5].contains(r#""ItemId":"movie-1""#));
-    assert!(captured[5].contains(r#""PositionTicks":1200000000"#));
+    assert!(captured[2].starts_with("POST /Sessions/Playing/Progress "));
+    assert!(captured[2].contains(r#""Id":"movie-next","PlaylistItemId":"playlistItem2""#));
+    assert!(captured[2].contains(r#""PlaylistItemId":"playlistItem1""#));
   }
 
   #[tokio::test]
@@ -2204,9 +5992,7 @@ mod tests {
         assert_eq!(title, "Emby Movie");
         assert_eq!(audio_index, Some(1));
         assert_eq!(subtitle_index, None);
-        assert!(
-          url.ends_with("/videos/direct-stream.mp4?MediaSourceId=source-emby&api_key=emby-token")
-        );
+        assert!(url.ends_with("/videos/direct-stream.mp4?MediaSourceId=source-emby"));
       }
       other => panic!("expected play action, got {other:?}"),
     }
@@ -2217,9 +6003,7 @@ mod tests {
       .expect("external Emby subtitle should be loaded separately");
     match subtitle_action {
       MpvAction::AddExternalSubtitle(url) => {
-        assert!(
-          url.ends_with("/Videos/movie-emby/source-emby/Subtitles/2/Stream.srt?api_key=emby-token")
-        );
+        assert!(url.ends_with("/Videos/movie-emby/source-emby/Subtitles/2/Stream.srt"));
       }
       other => panic!("expected external subtitle action, got {other:?}"),
     }
@@ -2257,7 +6041,7 @@ mod tests {
         item_id: "movie-emby".to_string(),
         media_source_id: Some("source-emby".to_string()),
         play_session_id: Some("play-emby".to_string()),
-        intro_skipper_ranges: Vec::new(),
+        skip_ranges: Vec::new(),
         position_ticks: 900_000_000,
         is_paused: true,
         is_muted: true,
@@ -2265,13 +6049,14 @@ mod tests {
         audio_stream_index: Some(1),
         subtitle_stream_index: Some(2),
         play_method: "DirectStream".to_string(),
+        mpv_duration_ticks: None,
+        can_seek: true,
+        is_growing: false,
+        subtitles_burned_in: false,
+        live_stream_id: None,
       }),
-      last_report_time: std::time::Instant::now(),
-      effective_intro_skipper_config: IntroSkipperRuntimeConfig::from(&AppConfig::default()),
-      current_series_id: None,
-      current_item: None,
-      current_media_streams: Vec::new(),
-      series_preferences: HashMap::new(),
+      phase: SessionPhase::Paused,
+      ..SessionState::for_test()
     });
 
     SessionManager::report_progress(&client, &state).await;
@@ -2306,7 +6091,7 @@ mod tests {
         item_id: "movie-emby".to_string(),
         media_source_id: Some("source-emby".to_string()),
         play_session_id: Some("play-emby".to_string()),
-        intro_skipper_ranges: Vec::new(),
+        skip_ranges: Vec::new(),
         position_ticks: 1_230_000_000,
         is_paused: false,
         is_muted: false,
@@ -2314,13 +6099,14 @@ mod tests {
         audio_stream_index: Some(1),
         subtitle_stream_index: Some(2),
         play_method: "DirectStream".to_string(),
+        mpv_duration_ticks: None,
+        can_seek: true,
+        is_growing: false,
+        subtitles_burned_in: false,
+        live_stream_id: None,
       }),
-      last_report_time: std::time::Instant::now(),
-      effective_intro_skipper_config: IntroSkipperRuntimeConfig::from(&AppConfig::default()),
-      current_series_id: None,
-      current_item: None,
-      current_media_streams: Vec::new(),
-      series_preferences: HashMap::new(),
+      phase: SessionPhase::Playing,
+      ..SessionState::for_test()
     });
 
     SessionManager::report_playback_stopped(&client, &state).await;
@@ -2347,7 +6133,7 @@ mod tests {
       args: None,
     };
 
-    SessionManager::apply_intro_skipper(&state, &action_tx, &event).await;
+    SessionManager::apply_skip_ranges(&state, &action_tx, &event).await;
 
     assert!(matches!(
       action_rx.recv().await,
@@ -2355,6 +6141,100 @@ mod tests {
     ));
   }
 
+  #[tokio::test]
+  async fn secondary_subtitles_map_to_mpv_tracks_and_add_external_ones() {
+    let (client, _requests) = connected_test_client(vec![
+      (
+        "200 OK",
+        r#"{"Id":"00000000-0000-0000-0000-000000000001","Name":"Ada"}"#,
+      ),
+      (
+        "200 OK",
+        r#"{"ServerName":"Jellyfin Home","Version":"10.10.0","Id":"server-1"}"#,
+      ),
+    ])
+    .await;
+    let state = test_state_with_active_playback();
+    let subtitle = |index: i32, is_external: bool| MediaStream {
+      index,
+      stream_type: "Subtitle".to_string(),
+      codec: Some("srt".to_string()),
+      language: None,
+      display_title: None,
+      is_default: false,
+      is_external,
+      height: None,
+    };
+    state.write().current_media_streams = vec![subtitle(2, false), subtitle(3, true)];
+    let (action_tx, mut action_rx) = mpsc::channel(1);
+
+    SessionManager::apply_secondary_subtitle(&client, &state, &action_tx, Some(2))
+      .await
+      .unwrap();
+    assert!(matches!(
+      action_rx.recv().await,
+      Some(MpvAction::SetSecondarySubtitleTrack(1))
+    ));
+
+    SessionManager::apply_secondary_subtitle(&client, &state, &action_tx, Some(3))
+      .await
+      .unwrap();
+    assert!(matches!(
+      action_rx.recv().await,
+      Some(MpvAction::AddSecondarySubtitle { track_id: 2, .. })
+    ));
+    assert_eq!(state.read().subtitle_tracks.secondary(), Some(3));
+
+    SessionManager::apply_secondary_subtitle(&client, &state, &action_tx, None)
+      .await
+      .unwrap();
+    assert!(matches!(
+      action_rx.recv().await,
+      Some(MpvAction::SetSecondarySubtitleTrack(-1))
+    ));
+    assert!(
+      SessionManager::apply_secondary_subtitle(&client, &state, &action_tx, Some(9))
+        .await
+        .is_err()
+    );
+  }
+
+  #[tokio::test]
+  async fn resuming_after_a_long_pause_rewinds() {
+    let state = test_state_with_active_playback();
+    let config = RwLock::new(AppConfig {
+      resume_rewind_seconds: Some(5),
+      resume_rewind_after_pause_seconds: 60,
+      ..Default::default()
+    });
+    let (action_tx, mut action_rx) = mpsc::channel(1);
+    let pause_event = |paused: bool| crate::mpv::MpvEvent {
+      event: "property-change".to_string(),
+      id: Some(1),
+      name: Some("pause".to_string()),
+      data: Some(serde_json::json!(paused)),
+      reason: None,
+      args: None,
+    };
+
+    SessionManager::rewind_after_pause(&state, &action_tx, &config, &pause_event(true)).await;
+    SessionManager::rewind_after_pause(&state, &action_tx, &config, &pause_event(false)).await;
+    assert!(
+      action_rx.try_recv().is_err(),
+      "a short pause resumes in place"
+    );
+
+    SessionManager::rewind_after_pause(&state, &action_tx, &config, &pause_event(true)).await;
+    state.write().paused_at =
+      std::time::Instant::now().checked_sub(std::time::Duration::from_secs(120));
+    SessionManager::rewind_after_pause(&state, &action_tx, &config, &pause_event(false)).await;
+    assert!(matches!(
+      action_rx.recv().await,
+      Some(MpvAction::Seek(position)) if position == 37.0
+    ));
+    assert_eq!(state.read().paused_at, None);
+  }
+
   #[tokio::test]
   async fn time_pos_update_inside_already_skipped_range_emits_no_second_seek() {
     let state = test_state_with_intro_range();
@@ -2368,20 +6248,20 @@ mod tests {
       args: None,
     };
 
-    SessionManager::apply_intro_skipper(&state, &action_tx, &event).await;
+    SessionManager::apply_skip_ranges(&state, &action_tx, &event).await;
     assert!(matches!(
       action_rx.recv().await,
       Some(MpvAction::Seek(80.0))
     ));
 
-    SessionManager::apply_intro_skipper(&state, &action_tx, &event).await;
+    SessionManager::apply_skip_ranges(&state, &action_tx, &event).await;
 
     assert!(action_rx.try_recv().is_err());
   }
 
   #[tokio::test]
   async fn time_pos_update_inside_credit_range_emits_seek_not_next_episode_action() {
-    let state = test_state_with_range(IntroSkipKind::Credits, 1200.0, 1260.0);
+    let state = test_state_with_range(SkipKind::Credits, 1200.0, 1260.0);
     let (action_tx, mut action_rx) = mpsc::channel(1);
     let event = crate::mpv::MpvEvent {
       event: "property-change".to_string(),
@@ -2392,7 +6272,7 @@ mod tests {
       args: None,
     };
 
-    SessionManager::apply_intro_skipper(&state, &action_tx, &event).await;
+    SessionManager::apply_skip_ranges(&state, &action_tx, &event).await;
 
     assert!(matches!(
       action_rx.recv().await,
@@ -2402,15 +6282,7 @@ mod tests {
 
   #[tokio::test]
   async fn time_pos_update_without_active_ranges_emits_no_seek_action() {
-    let state = RwLock::new(SessionState {
-      playback: None,
-      last_report_time: std::time::Instant::now(),
-      effective_intro_skipper_config: IntroSkipperRuntimeConfig::from(&AppConfig::default()),
-      current_series_id: None,
-      current_item: None,
-      current_media_streams: Vec::new(),
-      series_preferences: HashMap::new(),
-    });
+    let state = RwLock::new(SessionState::for_test());
     let (action_tx, mut action_rx) = mpsc::channel(1);
     let event = crate::mpv::MpvEvent {
       event: "property-change".to_string(),
@@ -2421,7 +6293,7 @@ mod tests {
       args: None,
     };
 
-    SessionManager::apply_intro_skipper(&state, &action_tx, &event).await;
+    SessionManager::apply_skip_ranges(&state, &action_tx, &event).await;
 
     assert!(action_rx.try_recv().is_err());
   }
@@ -2444,7 +6316,7 @@ mod tests {
       args: None,
     };
 
-    SessionManager::apply_intro_skipper(&state, &action_tx, &event).await;
+    SessionManager::apply_skip_ranges(&state, &action_tx, &event).await;
 
     assert!(action_rx.try_recv().is_err());
   }
@@ -2468,7 +6340,7 @@ mod tests {
       args: None,
     };
 
-    SessionManager::apply_intro_skipper(&state, &action_tx, &event).await;
+    SessionManager::apply_skip_ranges(&state, &action_tx, &event).await;
 
     assert!(matches!(
       action_rx.recv().await,
@@ -2525,9 +6397,359 @@ mod tests {
     ));
   }
 
+  #[tokio::test]
+  async fn intro_skip_shortcut_toggles_automatic_skipping() {
+    let state = test_state_with_intro_range();
+    let (action_tx, mut action_rx) = mpsc::channel(2);
+
+    SessionManager::toggle_intro_skip(&state, &action_tx).await;
+    assert_eq!(
+      state.read().effective_intro_skipper_config.mode,
+      IntroSkipperMode::Manual
+    );
+    SessionManager::toggle_intro_skip(&state, &action_tx).await;
+
+    assert_eq!(
+      state.read().effective_intro_skipper_config.mode,
+      IntroSkipperMode::Automatic
+    );
+    assert!(matches!(
+      action_rx.recv().await,
+      Some(MpvAction::ShowText { text, .. }) if text == "Automatic intro skipping off"
+    ));
+    assert!(matches!(
+      action_rx.recv().await,
+      Some(MpvAction::ShowText { text, .. }) if text == "Automatic intro skipping on"
+    ));
+  }
+
+  #[test]
+  fn speed_steps_snap_to_the_grid_and_stay_in_range() {
+    assert_eq!(stepped_speed(1.0, true), 1.25);
+    assert_eq!(stepped_speed(1.1, true), 1.25);
+    assert_eq!(stepped_speed(1.0, false), 0.75);
+    assert_eq!(stepped_speed(MIN_SPEED, false), MIN_SPEED);
+    assert_eq!(stepped_speed(MAX_SPEED, true), MAX_SPEED);
+  }
+
+  /// Playback of a library episode whose detail and user data update are the next responses.
+  async fn user_data_toggle_fixture(
+    detail: &'static str,
+    update: &'static str,
+  ) -> (JellyfinClient, RequestLog, RwLock<SessionState>) {
+    let (client, requests) = connected_test_client(vec![
+      (
+        "200 OK",
+        r#"{"Id":"00000000-0000-0000-0000-000000000001","Name":"Ada"}"#,
+      ),
+      (
+        "200 OK",
+        r#"{"ServerName":"Jellyfin Home","Version":"10.10.0","Id":"server-1"}"#,
+      ),
+      ("200 OK", detail),
+      ("200 OK", update),
+    ])
+    .await;
+    let state = test_state_with_active_playback();
+    state.write().current_item = Some(MediaItem {
+      id: "00000000-0000-0000-0000-000000000080".to_string(),
+      name: "Pilot".to_string(),
+      item_type: "Episode".to_string(),
+      series_id: None,
+      series_name: None,
+      season_name: None,
+      index_number: Some(1),
+      parent_index_number: Some(1),
+      run_time_ticks: None,
+      overview: None,
+      status: None,
+    });
+    (client, requests, state)
+  }
+
+  #[tokio::test]
+  async fn watched_toggle_flips_the_played_state_and_confirms_it() {
+    let (client, requests, state) = user_data_toggle_fixture(
+      r#"{"Id":"00000000-0000-0000-0000-000000000080","Name":"Pilot","Type":"Episode","UserData":{"Played":true}}"#,
+      r#"{"IsFavorite":false,"Played":false}"#,
+    )
+    .await;
+    let (action_tx, mut action_rx) = mpsc::channel(1);
+
+    let watched = SessionManager::toggle_current_user_data(
+      &client,
+      &state,
+      &action_tx,
+      UserDataToggle::Watched,
+    )
+    .await;
+
+    assert_eq!(watched, Ok(false));
+    assert!(matches!(
+      action_rx.try_recv(),
+      Ok(MpvAction::ShowText { text, .. }) if text == "Marked as unwatched"
+    ));
+    let captured = requests.lock();
+    assert!(
+      captured[3].starts_with("DELETE /UserPlayedItems/00000000-0000-0000-0000-000000000080?")
+    );
+  }
+
+  #[tokio::test]
+  async fn favorite_toggle_adds_the_item_and_shows_a_heart() {
+    let (client, requests, state) = user_data_toggle_fixture(
+      r#"{"Id":"00000000-0000-0000-0000-000000000080","Name":"Pilot","Type":"Episode","UserData":{"IsFavorite":false}}"#,
+      r#"{"IsFavorite":true,"Played":false}"#,
+    )
+    .await;
+    let (action_tx, mut action_rx) = mpsc::channel(1);
+
+    let favorite = SessionManager::toggle_current_user_data(
+      &client,
+      &state,
+      &action_tx,
+      UserDataToggle::Favorite,
+    )
+    .await;
+
+    assert_eq!(favorite, Ok(true));
+    assert!(matches!(
+      action_rx.try_recv(),
+      Ok(MpvAction::ShowText { text, .. }) if text == "♥ Added to favorites"
+    ));
+    assert_eq!(
+      state.read().user_data.map(|user_data| user_data.favorite),
+      Some(true)
+    );
+    let captured = requests.lock();
+    assert!(
+      captured[3].starts_with("POST /UserFavoriteItems/00000000-0000-0000-0000-000000000080?")
+    );
+  }
+
+  fn set_position(state: &RwLock<SessionState>, position_seconds: f64) {
+    state.write().playback.as_mut().unwrap().position_ticks = seconds_to_ticks(position_seconds);
+  }
+
+  #[tokio::test]
+  async fn marked_skip_range_is_stored_for_the_series() {
+    let state = test_state_with_range(SkipKind::Credits, 1200.0, 1260.0);
+    state.write().current_item = Some(MediaItem {
+      id: "episode-1".to_string(),
+      name: "Pilot".to_string(),
+      item_type: "Episode".to_string(),
+      series_id: Some("series-1".to_string()),
+      series_name: None,
+      season_name: None,
+      index_number: Some(1),
+      parent_index_number: Some(1),
+      run_time_ticks: None,
+      overview: None,
+      status: None,
+    });
+    let (action_tx, mut action_rx) = mpsc::channel(2);
+
+    set_position(&state, 62.0);
+    marked_skip::handle_skip_mark_start(&state, &action_tx).await;
+    set_position(&state, 151.5);
+    assert!(marked_skip::handle_skip_mark_end(&state, &action_tx).await);
+
+    assert!(matches!(
+      action_rx.recv().await,
+      Some(MpvAction::ShowText { text, .. }) if text == "Skip start marked at 1:02"
+    ));
+    assert!(matches!(
+      action_rx.recv().await,
+      Some(MpvAction::ShowText { text, .. })
+        if text == "Skipping 1:02-2:31 in this series from now on"
+    ));
+    let s = state.read();
+    assert_eq!(
+      s.marked_skip_ranges.get("series-1"),
+      Some(&vec![MarkedSkipRange::new(62.0, 151.5).unwrap()])
+    );
+    assert_eq!(s.skip_mark_start, None);
+    let marked = s.playback.as_ref().unwrap().skip_ranges.last().unwrap();
+    assert_eq!(marked.kind, SkipKind::Custom);
+    assert!(marked.skipped);
+  }
+
+  #[tokio::test]
+  async fn skip_mark_end_without_start_stores_nothing() {
+    let state = test_state_with_intro_range();
+    state.write().current_item = Some(MediaItem {
+      id: "movie-1".to_string(),
+      name: "Movie".to_string(),
+      item_type: "Movie".to_string(),
+      series_id: None,
+      series_name: None,
+      season_name: None,
+      index_number: None,
+      parent_index_number: None,
+      run_time_ticks: None,
+      overview: None,
+      status: None,
+    });
+    let (action_tx, mut action_rx) = mpsc::channel(1);
+
+    set_position(&state, 90.0);
+    assert!(!marked_skip::handle_skip_mark_end(&state, &action_tx).await);
+
+    assert!(matches!(
+      action_rx.recv().await,
+      Some(MpvAction::ShowText { text, .. }) if text == "Mark the skip start first"
+    ));
+    assert!(state.read().marked_skip_ranges.is_empty());
+  }
+
+  #[tokio::test]
+  async fn credits_fallback_adds_tail_range_once_duration_is_known() {
+    let state = test_state_with_intro_range();
+    {
+      let mut s = state.write();
+      s.current_item = Some(MediaItem {
+        id: "episode-1".to_string(),
+        name: "Pilot".to_string(),
+        item_type: "Episode".to_string(),
+        series_id: Some("series-1".to_string()),
+        series_name: None,
+        season_name: None,
+        index_number: Some(1),
+        parent_index_number: Some(1),
+        run_time_ticks: None,
+        overview: None,
+        status: None,
+      });
+      s.playback.as_mut().unwrap().mpv_duration_ticks = Some(seconds_to_ticks(1400.0));
+    }
+    let config = RwLock::new(AppConfig {
+      credits_fallback: true,
+      credits_fallback_seconds: Some(60),
+      ..Default::default()
+    });
+    // No MPV process: the chapter list is unavailable, so the tail is used.
+    let mpv = MpvClient::new(None);
+
+    SessionManager::add_credits_fallback(&mpv, &state, &config).await;
+    SessionManager::add_credits_fallback(&mpv, &state, &config).await;
+
+    let s = state.read();
+    let credits: Vec<_> = s
+      .playback
+      .as_ref()
+      .unwrap()
+      .skip_ranges
+      .iter()
+      .filter(|range| range.kind == SkipKind::Credits)
+      .collect();
+    assert_eq!(credits.len(), 1);
+    assert_eq!(
+      (credits[0].start_seconds, credits[0].end_seconds),
+      (1340.0, 1400.0)
+    );
+  }
+
+  #[tokio::test]
+  async fn credits_start_auto_next_countdown_instead_of_seeking() {
+    let state = test_state_with_range(SkipKind::Credits, 1200.0, 1260.0);
+    let config = AppConfig {
+      auto_next_at_credits: true,
+      ..Default::default()
+    };
+    state.write().effective_intro_skipper_config = IntroSkipperRuntimeConfig::from(&config);
+    let (action_tx, mut action_rx) = mpsc::channel(1);
+    let event = crate::mpv::MpvEvent {
+      event: "property-change".to_string(),
+      id: Some(4),
+      name: Some("time-pos".to_string()),
+      data: Some(serde_json::json!(1200.5)),
+      reason: None,
+      args: None,
+    };
+
+    SessionManager::apply_skip_ranges(&state, &action_tx, &event).await;
+
+    assert!(action_rx.try_recv().is_err());
+    assert_eq!(
+      state.read().credits_countdown,
+      Some(CreditsCountdown::new(1200.5))
+    );
+  }
+
+  #[derive(Default)]
+  struct RecordingObserver {
+    calls: parking_lot::Mutex<Vec<String>>,
+    released: parking_lot::Mutex<Vec<String>>,
+    failing: Option<&'static str>,
+  }
+
+  impl PropertyObserver for RecordingObserver {
+    async fn observe_property(&self, property: &str) -> Result<(), String> {
+      self.calls.lock().push(property.to_string());
+      match self.failing {
+        Some(failing) if failing == property => Err("property unavailable".to_string()),
+        _ => Ok(()),
+      }
+    }
+
+    async fn unobserve_property(&self, property: &str) -> Result<(), String> {
+      self.released.lock().push(property.to_string());
+      Ok(())
+    }
+  }
+
+  #[tokio::test]
+  async fn every_mpv_connection_registers_all_observers_again() {
+    let state = test_state_with_intro_range();
+    let observer = RecordingObserver {
+      failing: Some("volume"),
+      ..Default::default()
+    };
+
+    MpvConnection::open(&observer, &state).await;
+    MpvConnection::open(&observer, &state).await;
+
+    let calls = observer.calls.lock();
+    let (first, second) = calls.split_at(OBSERVED_PROPERTIES.len());
+    assert_eq!(first, &OBSERVED_PROPERTIES[..]);
+    assert_eq!(second, &OBSERVED_PROPERTIES[..]);
+    assert_eq!(state.read().playback.as_ref().unwrap().skip_ranges.len(), 1);
+  }
+
+  #[tokio::test]
+  async fn closing_a_connection_releases_only_what_it_observed() {
+    let state = test_state_with_intro_range();
+    let observer = RecordingObserver {
+      failing: Some("volume"),
+      ..Default::default()
+    };
+
+    MpvConnection::open(&observer, &state)
+      .await
+      .close(&observer)
+      .await;
+
+    let released = observer.released.lock();
+    assert_eq!(released.len(), OBSERVED_PROPERTIES.len() - 1);
+    assert!(!released.iter().any(|property| property == "volume"));
+  }
+
+  #[tokio::test]
+  async fn new_mpv_connection_keeps_the_session_progress_throttle() {
+    let state = test_state_with_intro_range();
+    state.write().last_report_time = std::time::Instant::now();
+
+    let connection = MpvConnection::open(&RecordingObserver::default(), &state).await;
+
+    assert!(!connection.should_report(&state, PropertyReportDecision::ReportWhenThrottleElapsed));
+    assert!(connection.should_report(&state, PropertyReportDecision::ReportNow));
+    state.write().last_report_time -= PROGRESS_REPORT_INTERVAL;
+    assert!(connection.should_report(&state, PropertyReportDecision::ReportWhenThrottleElapsed));
+  }
+
   #[tokio::test]
   async fn disabled_intro_skipper_setting_blocks_credit_seek_action() {
-    let state = test_state_with_range(IntroSkipKind::Credits, 1200.0, 1260.0);
+    let state = test_state_with_range(SkipKind::Credits, 1200.0, 1260.0);
     let (action_tx, mut action_rx) = mpsc::channel(1);
     let config = AppConfig {
       intro_skipper_mode: IntroSkipperMode::Off,
@@ -2543,7 +6765,7 @@ mod tests {
       args: None,
     };
 
-    SessionManager::apply_intro_skipper(&state, &action_tx, &event).await;
+    SessionManager::apply_skip_ranges(&state, &action_tx, &event).await;
 
     assert!(action_rx.try_recv().is_err());
   }
@@ -2565,7 +6787,11 @@ mod regression_tests {
       args: None,
     };
 
-    SessionManager::update_state_from_property(&state, &event);
+    SessionManager::update_state_from_property(
+      &state,
+      &event,
+      &VolumeMapping::from_config(&AppConfig::default()),
+    );
 
     let position_ticks = state
       .read()
@@ -2628,28 +6854,6 @@ mod regression_tests {
     assert_eq!(parse_command_int(Some(&value)), Some(-1));
   }
 
-  #[test]
-  fn redact_url_removes_authenticated_stream_websocket_and_login_secrets() {
-    let input = concat!(
-      "http://media.test/Videos/1/stream.mkv?MediaSourceId=source-1",
-      "&api_key=stream-token",
-      "&AccessToken=access-token",
-      "&password=login-secret",
-      " ws://media.test/socket?api_key=socket-token&deviceId=device-1"
-    );
-
-    let redacted = redact_url(input);
-
-    assert!(!redacted.contains("stream-token"));
-    assert!(!redacted.contains("access-token"));
-    assert!(!redacted.contains("login-secret"));
-    assert!(!redacted.contains("socket-token"));
-    assert!(redacted.contains("api_key=[REDACTED]"));
-    assert!(redacted.contains("AccessToken=[REDACTED]"));
-    assert!(redacted.contains("password=[REDACTED]"));
-    assert!(redacted.contains("deviceId=device-1"));
-  }
-
   #[test]
   fn jellyfin_general_command_volume_from_string_updates_session_and_sends_action() {
     let state = RwLock::new(SessionState {
@@ -2657,7 +6861,7 @@ mod regression_tests {
         item_id: "item-1".to_string(),
         media_source_id: Some("source-1".to_string()),
         play_session_id: Some("play-1".to_string()),
-        intro_skipper_ranges: vec![],
+        skip_ranges: vec![],
         position_ticks: 0,
         is_paused: false,
         is_muted: false,
@@ -2665,13 +6869,14 @@ mod regression_tests {
         audio_stream_index: None,
         subtitle_stream_index: None,
         play_method: "DirectPlay".to_string(),
+        mpv_duration_ticks: None,
+        can_seek: true,
+        is_growing: false,
+        subtitles_burned_in: false,
+        live_stream_id: None,
       }),
-      last_report_time: std::time::Instant::now(),
-      effective_intro_skipper_config: IntroSkipperRuntimeConfig::from(&AppConfig::default()),
-      current_series_id: None,
-      current_item: None,
-      current_media_streams: Vec::new(),
-      series_preferences: HashMap::new(),
+      phase: SessionPhase::Playing,
+      ..SessionState::for_test()
     });
     let (action_tx, mut action_rx) = mpsc::channel(1);
 
@@ -2701,6 +6906,23 @@ mod regression_tests {
     });
   }
 
+  #[test]
+  fn shuffle_mode_argument_maps_to_playback_order() {
+    assert_eq!(parse_shuffle_mode("Shuffle"), Some(PlaybackOrder::Shuffle));
+    assert_eq!(parse_shuffle_mode("Sorted"), Some(PlaybackOrder::Default));
+    assert_eq!(parse_shuffle_mode("Random"), None);
+  }
+
+  #[test]
+  fn repeat_mode_argument_uses_jellyfin_names() {
+    let args = serde_json::json!({"RepeatMode": "RepeatOne"});
+    let repeat_mode = serde_json::from_value::<RepeatMode>(args["RepeatMode"].clone());
+    assert_eq!(repeat_mode.ok(), Some(RepeatMode::RepeatOne));
+
+    let invalid = serde_json::from_value::<RepeatMode>(serde_json::json!("RepeatSome"));
+    assert!(invalid.is_err());
+  }
+
   #[test]
   fn jellyfin_general_command_volume_from_number_still_works() {
     let args = serde_json::json!({"Volume": 75});
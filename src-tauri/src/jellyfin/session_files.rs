@@ -0,0 +1,338 @@
+//! Temporary files downloaded for the current playback.
+//!
+//! Sidecar files a playback needs on disk (external subtitles, fonts,
+//! screenshots waiting to be moved) go in a directory of their own under the
+//! user's cache dir, named after JellyPilot's process id. It is removed when
+//! the playback ends and when JellyPilot stops; directories a crashed run left
+//! behind are removed on the next start. The files of one playback are capped
+//! in size so a runaway download can't fill the disk.
+//!
+//! Since entries of that root are deleted at startup and downloads are
+//! written into it, it must be a private directory of this user. One that
+//! isn't (someone else's, open to others or a symlink) is neither listed nor
+//! written to.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use parking_lot::RwLock;
+
+use super::client::JellyfinClient;
+use super::session::{PlaybackGeneration, SessionState};
+use super::types::MediaSource;
+
+/// Most the files of one playback may take up on disk.
+pub const MAX_SESSION_FILES_BYTES: u64 = 256 * 1024 * 1024;
+
+/// Directory holding the per-playback directories of every JellyPilot run.
+fn files_root() -> PathBuf {
+  dirs::cache_dir()
+    .unwrap_or_else(std::env::temp_dir)
+    .join("jellypilot-session")
+}
+
+/// Create `root`, or check the existing one, before anything in it is
+/// written or removed.
+fn prepare_root(root: &Path) -> io::Result<()> {
+  if let Some(parent) = root.parent() {
+    std::fs::create_dir_all(parent)?;
+  }
+  create_private_dir(root)
+}
+
+/// Owner-only, owned by this user and not a symlink.
+#[cfg(not(windows))]
+fn create_private_dir(dir: &Path) -> io::Result<()> {
+  crate::mpv::prepare_private_dir(dir)
+}
+
+/// The cache dir of a Windows profile is already the user's alone.
+#[cfg(windows)]
+fn create_private_dir(dir: &Path) -> io::Result<()> {
+  std::fs::create_dir_all(dir)
+}
+
+/// Files of the current playback; the directory is created on first use.
+#[derive(Debug)]
+pub struct SessionFiles {
+  root: PathBuf,
+  dir: Option<PathBuf>,
+  /// Bytes reserved in `dir` so far.
+  bytes: u64,
+  /// Playbacks that had a directory, to give each a fresh one.
+  count: u32,
+}
+
+impl Default for SessionFiles {
+  fn default() -> Self {
+    Self::in_root(files_root())
+  }
+}
+
+impl SessionFiles {
+  fn in_root(root: PathBuf) -> Self {
+    Self {
+      root,
+      dir: None,
+      bytes: 0,
+      count: 0,
+    }
+  }
+
+  /// Directory of the current playback. Nothing is created here, so this is
+  /// cheap under a lock; see [`create_dir`].
+  pub fn dir(&mut self) -> PathBuf {
+    if let Some(dir) = &self.dir {
+      return dir.clone();
+    }
+    self.count += 1;
+    let dir = self
+      .root
+      .join(format!("{}-{}", std::process::id(), self.count));
+    self.dir = Some(dir.clone());
+    dir
+  }
+
+  /// Path of the file named `name` in the current playback's directory.
+  pub fn path(&mut self, name: &str) -> io::Result<PathBuf> {
+    let name = Path::new(name)
+      .file_name()
+      .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "no file name"))?;
+    Ok(self.dir().join(name))
+  }
+
+  /// Bytes the current playback may still write before reaching the cap.
+  pub fn remaining(&self) -> u64 {
+    MAX_SESSION_FILES_BYTES.saturating_sub(self.bytes)
+  }
+
+  /// Count `len` bytes written to the current playback's directory.
+  pub fn add(&mut self, len: u64) {
+    self.bytes = self.bytes.saturating_add(len);
+  }
+
+  /// Remove the current playback's files.
+  pub fn clear(&mut self) {
+    self.bytes = 0;
+    let Some(dir) = self.dir.take() else {
+      return;
+    };
+    match std::fs::remove_dir_all(&dir) {
+      Ok(()) => log::debug!("Removed playback files in {}", dir.display()),
+      Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+      Err(e) => log::warn!("Failed to remove {}: {}", dir.display(), e),
+    }
+  }
+}
+
+impl Drop for SessionFiles {
+  fn drop(&mut self) {
+    self.clear();
+  }
+}
+
+/// Create a playback directory from [`SessionFiles::dir`], and the root it
+/// is in, before files are written to it.
+pub fn create_dir(dir: &Path) -> io::Result<()> {
+  if let Some(root) = dir.parent() {
+    prepare_root(root)?;
+  }
+  std::fs::create_dir_all(dir)
+}
+
+/// Remove playback directories left by JellyPilot runs that are gone.
+pub fn remove_stale_session_files() {
+  remove_stale_in(&files_root(), crate::mpv::process_running);
+}
+
+fn remove_stale_in(root: &Path, running: impl Fn(u32) -> bool) {
+  if let Err(e) = prepare_root(root) {
+    log::warn!("Not cleaning up playback files: {}", e);
+    return;
+  }
+  let Ok(entries) = std::fs::read_dir(root) else {
+    return;
+  };
+  for entry in entries.flatten() {
+    let name = entry.file_name();
+    let owner = name
+      .to_str()
+      .and_then(|name| name.split_once('-'))
+      .and_then(|(pid, _)| pid.parse::<u32>().ok());
+    // Leave alone what JellyPilot didn't name and what a running copy uses.
+    let Some(owner) = owner else {
+      continue;
+    };
+    if owner == std::process::id() || running(owner) {
+      continue;
+    }
+    let path = entry.path();
+    match std::fs::remove_dir_all(&path) {
+      Ok(()) => log::info!("Removed stale playback files in {}", path.display()),
+      Err(e) => log::warn!("Failed to remove {}: {}", path.display(), e),
+    }
+  }
+}
+
+/// Download the embedded fonts `media_source`'s stream leaves out into the
+/// playback's files, so styled subtitles keep their look. Returns the folder
+/// MPV should take fonts from.
+pub(super) async fn fetch_subtitle_fonts(
+  client: &JellyfinClient,
+  state: &RwLock<SessionState>,
+  generation: PlaybackGeneration,
+  item_id: &str,
+  media_source: &MediaSource,
+) -> Option<std::path::PathBuf> {
+  let fonts = media_source.missing_subtitle_fonts();
+  if fonts.is_empty() {
+    return None;
+  }
+  let mut fonts_dir = None;
+  let mut fetched = 0;
+  for font in fonts {
+    let name = font.font_file_name();
+    let (path, remaining) = {
+      let mut s = state.write();
+      if s.playback_generation != generation {
+        return None;
+      }
+      (s.session_files.path(&name), s.session_files.remaining())
+    };
+    let path = match path {
+      Ok(path) => path,
+      Err(e) => {
+        log::warn!("Not saving font {}: {}", name, e);
+        continue;
+      }
+    };
+    let Some(dir) = path.parent().map(std::path::Path::to_path_buf) else {
+      continue;
+    };
+    // A reload of the same playback finds the fonts already there.
+    if path.exists() {
+      fonts_dir = Some(dir);
+      continue;
+    }
+    if remaining == 0 {
+      log::warn!(
+        "Not saving font {}: playback files are at their size cap",
+        name
+      );
+      break;
+    }
+    if let Err(e) = create_dir(&dir) {
+      log::warn!("Not saving fonts of {}: {}", item_id, e);
+      break;
+    }
+    let written = match client
+      .playback()
+      .download_attachment(item_id, &media_source.id, font.index, &path, remaining)
+      .await
+    {
+      Ok(written) => written,
+      Err(e) => {
+        log::warn!("Font {} of {} unavailable: {}", name, item_id, e);
+        continue;
+      }
+    };
+    {
+      let mut s = state.write();
+      if s.playback_generation != generation {
+        let _ = std::fs::remove_file(&path);
+        return None;
+      }
+      s.session_files.add(written);
+    }
+    fetched += 1;
+    fonts_dir = Some(dir);
+  }
+  if fetched > 0 {
+    log::info!("Downloaded {} subtitle fonts of {}", fetched, item_id);
+  }
+  fonts_dir
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn test_root(name: &str) -> PathBuf {
+    let root = std::env::temp_dir().join(format!(
+      "jellypilot-session-files-{}-{}",
+      name,
+      std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&root);
+    root
+  }
+
+  #[test]
+  fn clearing_removes_the_playback_directory() {
+    let root = test_root("clear");
+    let mut files = SessionFiles::in_root(root.clone());
+    let path = files.path("../font.ttf").unwrap();
+    assert_eq!(path.parent(), Some(files.dir().as_path()));
+    assert_eq!(path.file_name().unwrap(), "font.ttf");
+    create_dir(path.parent().unwrap()).unwrap();
+    std::fs::write(&path, b"font").unwrap();
+
+    files.clear();
+    assert!(!path.exists());
+    // The next playback gets a directory of its own.
+    assert_ne!(files.path("font.ttf").unwrap(), path);
+    drop(files);
+    let _ = std::fs::remove_dir_all(&root);
+  }
+
+  #[test]
+  fn written_bytes_count_against_the_cap() {
+    let mut files = SessionFiles::in_root(test_root("cap"));
+    assert_eq!(files.remaining(), MAX_SESSION_FILES_BYTES);
+    files.add(MAX_SESSION_FILES_BYTES - 1);
+    assert_eq!(files.remaining(), 1);
+    files.add(2);
+    assert_eq!(files.remaining(), 0);
+
+    files.clear();
+    assert_eq!(files.remaining(), MAX_SESSION_FILES_BYTES);
+  }
+
+  #[test]
+  fn only_directories_of_exited_runs_are_removed() {
+    let root = test_root("stale");
+    for name in ["100-1", "200-3", "notes"] {
+      std::fs::create_dir_all(root.join(name)).unwrap();
+    }
+    remove_stale_in(&root, |pid| pid == 200);
+    assert!(!root.join("100-1").exists());
+    assert!(root.join("200-3").exists());
+    assert!(root.join("notes").exists());
+    let _ = std::fs::remove_dir_all(&root);
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn a_root_that_is_not_private_is_left_alone() {
+    use std::os::unix::fs::PermissionsExt;
+
+    // A root planted as a symlink to somewhere else.
+    let target = test_root("symlink-target");
+    let link = test_root("symlink");
+    std::fs::create_dir_all(target.join("2023-photos")).unwrap();
+    std::os::unix::fs::symlink(&target, &link).unwrap();
+
+    remove_stale_in(&link, |_| false);
+    assert!(target.join("2023-photos").exists());
+    let dir = SessionFiles::in_root(link.clone()).dir();
+    assert!(create_dir(&dir).is_err());
+    std::fs::remove_file(&link).unwrap();
+
+    // A root open to other users is made private before it is used.
+    std::fs::set_permissions(&target, std::fs::Permissions::from_mode(0o777)).unwrap();
+    remove_stale_in(&target, |_| true);
+    let mode = std::fs::metadata(&target).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o700);
+    let _ = std::fs::remove_dir_all(&target);
+  }
+}
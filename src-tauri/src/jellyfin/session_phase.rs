@@ -0,0 +1,135 @@
+//! What the playback session is doing, as one explicit state.
+//!
+//! Whether a file is still loading, playing, paused or buffering used to be
+//! pieced together from MPV properties and the playback session. The phase
+//! names it, only moves along the transitions below, and lets commands that
+//! make no sense right now (a seek while MPV is still opening the stream) be
+//! turned away instead of reaching MPV.
+
+use serde::Serialize;
+use specta::Type;
+
+/// Phase of the playback session.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum SessionPhase {
+  /// No session is running.
+  #[default]
+  Disconnected,
+  /// Connected and waiting for something to play.
+  Idle,
+  /// MPV is opening a stream.
+  Loading,
+  Playing,
+  Paused,
+  /// Playback waits for the network to fill the cache.
+  Stalling,
+  /// MPV was told to stop and has not confirmed yet.
+  Stopping,
+}
+
+impl SessionPhase {
+  /// True when the session may move from this phase to `next`. Staying in
+  /// the same phase is always allowed.
+  pub fn can_become(self, next: Self) -> bool {
+    use SessionPhase::*;
+    if self == next || next == Disconnected {
+      return true;
+    }
+    match self {
+      Disconnected => next == Idle,
+      Idle => next == Loading,
+      Loading => matches!(next, Playing | Paused | Stopping | Idle),
+      Playing | Paused | Stalling => {
+        matches!(
+          next,
+          Playing | Paused | Stalling | Loading | Stopping | Idle
+        )
+      }
+      Stopping => matches!(next, Idle | Loading),
+    }
+  }
+
+  /// True while a file is open and its position can be changed.
+  pub fn can_seek(self) -> bool {
+    matches!(self, Self::Playing | Self::Paused | Self::Stalling)
+  }
+
+  /// Phase of an open file that is paused or not.
+  pub fn open(paused: bool) -> Self {
+    if paused {
+      Self::Paused
+    } else {
+      Self::Playing
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::SessionPhase::*;
+  use super::*;
+
+  #[test]
+  fn playback_follows_the_usual_path() {
+    let path = [
+      Disconnected,
+      Idle,
+      Loading,
+      Playing,
+      Stalling,
+      Playing,
+      Paused,
+      Playing,
+      Stopping,
+      Idle,
+      Disconnected,
+    ];
+    for pair in path.windows(2) {
+      assert!(
+        pair[0].can_become(pair[1]),
+        "{:?} -> {:?}",
+        pair[0],
+        pair[1]
+      );
+    }
+  }
+
+  #[test]
+  fn impossible_transitions_are_refused() {
+    assert!(!Disconnected.can_become(Playing));
+    assert!(!Disconnected.can_become(Loading));
+    assert!(!Idle.can_become(Playing));
+    assert!(!Idle.can_become(Stopping));
+    assert!(!Loading.can_become(Stalling));
+    assert!(!Stopping.can_become(Playing));
+    assert!(!Stopping.can_become(Paused));
+  }
+
+  #[test]
+  fn any_phase_can_disconnect_or_stay() {
+    for phase in [
+      Disconnected,
+      Idle,
+      Loading,
+      Playing,
+      Paused,
+      Stalling,
+      Stopping,
+    ] {
+      assert!(phase.can_become(Disconnected));
+      assert!(phase.can_become(phase));
+    }
+  }
+
+  #[test]
+  fn seeks_need_an_open_file() {
+    assert!(Playing.can_seek());
+    assert!(Paused.can_seek());
+    assert!(Stalling.can_seek());
+    assert!(!Loading.can_seek());
+    assert!(!Stopping.can_seek());
+    assert!(!Idle.can_seek());
+    assert!(!Disconnected.can_seek());
+  }
+}
@@ -0,0 +1,425 @@
+//! Skip decisions for labeled time ranges of the playing item.
+//!
+//! Ranges come from different sources (the Intro Skipper plugin, user marks,
+//! chapter names) but are all decided here: each kind has a policy, and every
+//! range is skipped or prompted at most once per playback session. Nothing
+//! in this module talks to MPV.
+
+const LOOKAHEAD_SECONDS: f64 = 1.0;
+
+/// What a skip range covers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(dead_code)] // Recap and commercial ranges have no source yet
+pub enum SkipKind {
+  Introduction,
+  Credits,
+  Recap,
+  Commercial,
+  /// A range marked by the user.
+  Custom,
+}
+
+/// What happens when playback reaches a range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkipPolicy {
+  /// Seek to the end of the range.
+  Skip,
+  /// Offer a manual skip.
+  Prompt,
+  /// Move on to the next item; for credits.
+  PlayNext,
+  Ignore,
+}
+
+/// Policy per range kind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SkipPolicies {
+  pub introduction: SkipPolicy,
+  pub credits: SkipPolicy,
+  pub recap: SkipPolicy,
+  pub commercial: SkipPolicy,
+  pub custom: SkipPolicy,
+}
+
+impl SkipPolicies {
+  /// The same policy for every kind.
+  pub fn uniform(policy: SkipPolicy) -> Self {
+    Self {
+      introduction: policy,
+      credits: policy,
+      recap: policy,
+      commercial: policy,
+      custom: policy,
+    }
+  }
+
+  pub fn policy(&self, kind: SkipKind) -> SkipPolicy {
+    match kind {
+      SkipKind::Introduction => self.introduction,
+      SkipKind::Credits => self.credits,
+      SkipKind::Recap => self.recap,
+      SkipKind::Commercial => self.commercial,
+      SkipKind::Custom => self.custom,
+    }
+  }
+}
+
+/// A skippable range in the current playback session.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkipRange {
+  pub kind: SkipKind,
+  pub start_seconds: f64,
+  pub end_seconds: f64,
+  /// A prompt was shown, or the range was skipped.
+  pub notified: bool,
+  pub skipped: bool,
+}
+
+impl SkipRange {
+  /// A fresh range, or None for reversed, negative or non-finite bounds.
+  pub fn new(kind: SkipKind, start_seconds: f64, end_seconds: f64) -> Option<Self> {
+    if !start_seconds.is_finite()
+      || !end_seconds.is_finite()
+      || start_seconds < 0.0
+      || end_seconds <= start_seconds
+    {
+      return None;
+    }
+
+    Some(Self {
+      kind,
+      start_seconds,
+      end_seconds,
+      notified: false,
+      skipped: false,
+    })
+  }
+
+  fn is_active(&self, position_seconds: f64) -> bool {
+    !self.skipped
+      && position_seconds >= self.start_seconds - LOOKAHEAD_SECONDS
+      && position_seconds < self.end_seconds
+  }
+
+  fn skip(&mut self) -> SkipDecision {
+    self.skipped = true;
+    self.notified = true;
+    SkipDecision {
+      kind: self.kind,
+      seek_target: self.end_seconds,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SkipDecision {
+  pub kind: SkipKind,
+  pub seek_target: f64,
+}
+
+/// Result of a position update.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SkipAction {
+  Seek(SkipDecision),
+  Prompt(SkipKind),
+  PlayNext(SkipKind),
+}
+
+/// Decide what to do at `position_seconds`.
+///
+/// A range is skipped or prompted only on first entry; seeking back into it
+/// does neither again.
+pub fn evaluate_skip_ranges(
+  position_seconds: f64,
+  ranges: &mut [SkipRange],
+  policies: &SkipPolicies,
+) -> Option<SkipAction> {
+  if !position_seconds.is_finite() {
+    return None;
+  }
+
+  for range in ranges
+    .iter_mut()
+    .filter(|range| range.is_active(position_seconds))
+  {
+    match policies.policy(range.kind) {
+      SkipPolicy::Skip => return Some(SkipAction::Seek(range.skip())),
+      SkipPolicy::PlayNext => {
+        range.skip();
+        return Some(SkipAction::PlayNext(range.kind));
+      }
+      SkipPolicy::Prompt if !range.notified => {
+        range.notified = true;
+        return Some(SkipAction::Prompt(range.kind));
+      }
+      SkipPolicy::Prompt | SkipPolicy::Ignore => {}
+    }
+  }
+  None
+}
+
+/// Skip the prompted range playback is in, whether or not the prompt was shown.
+pub fn evaluate_manual_skip(
+  position_seconds: f64,
+  ranges: &mut [SkipRange],
+  policies: &SkipPolicies,
+) -> Option<SkipDecision> {
+  if !position_seconds.is_finite() {
+    return None;
+  }
+
+  ranges
+    .iter_mut()
+    .find(|range| {
+      range.is_active(position_seconds) && policies.policy(range.kind) == SkipPolicy::Prompt
+    })
+    .map(SkipRange::skip)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const AUTOMATIC: SkipPolicies = SkipPolicies {
+    introduction: SkipPolicy::Skip,
+    credits: SkipPolicy::Skip,
+    recap: SkipPolicy::Skip,
+    commercial: SkipPolicy::Skip,
+    custom: SkipPolicy::Skip,
+  };
+
+  fn intro_range(start_seconds: f64, end_seconds: f64) -> SkipRange {
+    SkipRange::new(SkipKind::Introduction, start_seconds, end_seconds).unwrap()
+  }
+
+  fn credit_range(start_seconds: f64, end_seconds: f64) -> SkipRange {
+    SkipRange::new(SkipKind::Credits, start_seconds, end_seconds).unwrap()
+  }
+
+  fn evaluate_skip(position_seconds: f64, ranges: &mut [SkipRange]) -> Option<f64> {
+    match evaluate_skip_ranges(position_seconds, ranges, &AUTOMATIC) {
+      Some(SkipAction::Seek(decision)) => Some(decision.seek_target),
+      _ => None,
+    }
+  }
+
+  #[test]
+  fn invalid_bounds_are_rejected() {
+    assert!(SkipRange::new(SkipKind::Introduction, 90.0, 80.0).is_none());
+    assert!(SkipRange::new(SkipKind::Introduction, -1.0, 80.0).is_none());
+    assert!(SkipRange::new(SkipKind::Credits, 10.0, f64::NAN).is_none());
+    assert!(SkipRange::new(SkipKind::Custom, 10.0, 10.0).is_none());
+  }
+
+  #[test]
+  fn first_entry_into_unskipped_range_returns_seek_target_once() {
+    let mut ranges = vec![intro_range(10.0, 80.0)];
+
+    assert_eq!(evaluate_skip(10.0, &mut ranges), Some(80.0));
+    assert!(ranges[0].notified);
+    assert!(ranges[0].skipped);
+    assert_eq!(evaluate_skip(10.5, &mut ranges), None);
+  }
+
+  #[test]
+  fn resume_or_manual_seek_into_unskipped_range_still_skips() {
+    let mut ranges = vec![intro_range(10.0, 80.0)];
+
+    assert_eq!(evaluate_skip(42.0, &mut ranges), Some(80.0));
+  }
+
+  #[test]
+  fn manual_seek_back_into_already_skipped_range_does_not_skip_again() {
+    let mut ranges = vec![intro_range(10.0, 80.0)];
+
+    assert_eq!(evaluate_skip(10.0, &mut ranges), Some(80.0));
+    assert_eq!(evaluate_skip(20.0, &mut ranges), None);
+  }
+
+  #[test]
+  fn new_playback_range_set_resets_skipped_state() {
+    let mut first_session_ranges = vec![intro_range(10.0, 80.0)];
+    assert_eq!(evaluate_skip(10.0, &mut first_session_ranges), Some(80.0));
+
+    let mut next_session_ranges = vec![intro_range(10.0, 80.0)];
+
+    assert_eq!(evaluate_skip(10.0, &mut next_session_ranges), Some(80.0));
+  }
+
+  #[test]
+  fn returns_seek_target_inside_half_open_range() {
+    let mut at_start = vec![intro_range(10.0, 80.0)];
+    let mut before_end = vec![intro_range(10.0, 80.0)];
+    let mut at_end = vec![intro_range(10.0, 80.0)];
+
+    assert_eq!(evaluate_skip(10.0, &mut at_start), Some(80.0));
+    assert_eq!(evaluate_skip(79.99, &mut before_end), Some(80.0));
+    assert_eq!(evaluate_skip(80.0, &mut at_end), None);
+  }
+
+  #[test]
+  fn returns_seek_target_inside_one_second_lookahead_window() {
+    let mut inside_lookahead = vec![intro_range(10.0, 80.0)];
+    let mut outside_lookahead = vec![intro_range(10.0, 80.0)];
+
+    assert_eq!(evaluate_skip(9.0, &mut inside_lookahead), Some(80.0));
+    assert_eq!(evaluate_skip(8.99, &mut outside_lookahead), None);
+  }
+
+  #[test]
+  fn position_near_range_end_does_not_retrigger_after_skip() {
+    let mut ranges = vec![intro_range(10.0, 80.0)];
+
+    assert_eq!(evaluate_skip(79.5, &mut ranges), Some(80.0));
+    assert_eq!(evaluate_skip(79.75, &mut ranges), None);
+  }
+
+  #[test]
+  fn credit_range_uses_same_seek_rules() {
+    let mut lookahead_ranges = vec![credit_range(1200.0, 1260.0)];
+    let mut start_ranges = vec![credit_range(1200.0, 1260.0)];
+
+    assert_eq!(evaluate_skip(1199.0, &mut lookahead_ranges), Some(1260.0));
+    assert_eq!(evaluate_skip(1200.0, &mut start_ranges), Some(1260.0));
+  }
+
+  #[test]
+  fn non_finite_positions_decide_nothing() {
+    let mut ranges = vec![intro_range(10.0, 80.0)];
+
+    assert_eq!(evaluate_skip(f64::NAN, &mut ranges), None);
+    assert_eq!(
+      evaluate_manual_skip(f64::INFINITY, &mut ranges, &AUTOMATIC),
+      None
+    );
+    assert!(!ranges[0].notified);
+  }
+
+  #[test]
+  fn prompt_marks_notified_without_skipping() {
+    let policies = SkipPolicies::uniform(SkipPolicy::Prompt);
+    let mut ranges = vec![intro_range(10.0, 80.0)];
+
+    assert_eq!(
+      evaluate_skip_ranges(10.0, &mut ranges, &policies),
+      Some(SkipAction::Prompt(SkipKind::Introduction))
+    );
+    assert!(ranges[0].notified);
+    assert!(!ranges[0].skipped);
+    assert_eq!(evaluate_skip_ranges(10.5, &mut ranges, &policies), None);
+  }
+
+  #[test]
+  fn manual_skip_returns_kind_and_marks_range_skipped() {
+    let policies = SkipPolicies::uniform(SkipPolicy::Prompt);
+    let mut ranges = vec![credit_range(1200.0, 1260.0)];
+
+    assert_eq!(
+      evaluate_manual_skip(1200.0, &mut ranges, &policies),
+      Some(SkipDecision {
+        kind: SkipKind::Credits,
+        seek_target: 1260.0
+      })
+    );
+    assert!(ranges[0].notified);
+    assert!(ranges[0].skipped);
+    assert_eq!(evaluate_manual_skip(1201.0, &mut ranges, &policies), None);
+  }
+
+  #[test]
+  fn manual_skip_only_applies_to_prompted_kinds() {
+    let mut ranges = vec![intro_range(10.0, 80.0)];
+
+    assert_eq!(evaluate_manual_skip(20.0, &mut ranges, &AUTOMATIC), None);
+    assert_eq!(
+      evaluate_manual_skip(
+        20.0,
+        &mut ranges,
+        &SkipPolicies::uniform(SkipPolicy::Ignore)
+      ),
+      None
+    );
+    assert!(!ranges[0].skipped);
+  }
+
+  #[test]
+  fn ignored_kinds_are_passed_over_for_overlapping_ranges() {
+    let policies = SkipPolicies {
+      recap: SkipPolicy::Ignore,
+      ..AUTOMATIC
+    };
+    let mut ranges = vec![
+      SkipRange::new(SkipKind::Recap, 0.0, 60.0).unwrap(),
+      intro_range(30.0, 90.0),
+    ];
+
+    assert_eq!(evaluate_skip_ranges(10.0, &mut ranges, &policies), None);
+    assert_eq!(
+      evaluate_skip_ranges(30.0, &mut ranges, &policies),
+      Some(SkipAction::Seek(SkipDecision {
+        kind: SkipKind::Introduction,
+        seek_target: 90.0
+      }))
+    );
+    assert!(!ranges[0].notified);
+  }
+
+  #[test]
+  fn play_next_fires_once_without_seeking() {
+    let policies = SkipPolicies {
+      credits: SkipPolicy::PlayNext,
+      ..AUTOMATIC
+    };
+    let mut ranges = vec![credit_range(1200.0, 1260.0)];
+
+    assert_eq!(
+      evaluate_skip_ranges(1200.0, &mut ranges, &policies),
+      Some(SkipAction::PlayNext(SkipKind::Credits))
+    );
+    assert!(ranges[0].skipped);
+    assert_eq!(evaluate_skip_ranges(1210.0, &mut ranges, &policies), None);
+    assert_eq!(evaluate_manual_skip(1210.0, &mut ranges, &policies), None);
+  }
+
+  #[test]
+  fn each_kind_follows_its_own_policy() {
+    let policies = SkipPolicies {
+      introduction: SkipPolicy::Skip,
+      credits: SkipPolicy::Prompt,
+      recap: SkipPolicy::Ignore,
+      commercial: SkipPolicy::Skip,
+      custom: SkipPolicy::Prompt,
+    };
+    let mut ranges = vec![
+      SkipRange::new(SkipKind::Recap, 0.0, 20.0).unwrap(),
+      intro_range(20.0, 80.0),
+      SkipRange::new(SkipKind::Commercial, 600.0, 660.0).unwrap(),
+      SkipRange::new(SkipKind::Custom, 900.0, 930.0).unwrap(),
+      credit_range(1200.0, 1260.0),
+    ];
+
+    assert_eq!(evaluate_skip_ranges(5.0, &mut ranges, &policies), None);
+    assert_eq!(
+      evaluate_skip_ranges(20.0, &mut ranges, &policies),
+      Some(SkipAction::Seek(SkipDecision {
+        kind: SkipKind::Introduction,
+        seek_target: 80.0
+      }))
+    );
+    assert_eq!(
+      evaluate_skip_ranges(600.0, &mut ranges, &policies),
+      Some(SkipAction::Seek(SkipDecision {
+        kind: SkipKind::Commercial,
+        seek_target: 660.0
+      }))
+    );
+    assert_eq!(
+      evaluate_skip_ranges(900.0, &mut ranges, &policies),
+      Some(SkipAction::Prompt(SkipKind::Custom))
+    );
+    assert_eq!(
+      evaluate_skip_ranges(1200.0, &mut ranges, &policies),
+      Some(SkipAction::Prompt(SkipKind::Credits))
+    );
+  }
+}
@@ -0,0 +1,292 @@
+//! Buffering stalls reported by MPV's `paused-for-cache` property.
+//!
+//! A stall only counts once it outlasts the warning threshold, so the short
+//! waits after seeks stay quiet. Long stalls that keep coming back mean the
+//! connection can't sustain the stream, and the bitrate is stepped down.
+
+use std::time::{Duration, Instant};
+
+use parking_lot::RwLock;
+use tauri::AppHandle;
+use tokio::sync::mpsc;
+
+use super::bandwidth::BandwidthMeter;
+use super::client::JellyfinClient;
+use super::session::{MpvAction, SessionManager, SessionState};
+use super::session_phase::SessionPhase;
+use crate::command::AppNotification;
+use crate::config::AppConfig;
+use crate::i18n::tr;
+
+/// Long stalls in one playback before they count as persistent.
+const PERSISTENT_STALL_COUNT: u32 = 3;
+/// Share of the measured throughput a reduced stream may use.
+const THROUGHPUT_HEADROOM: f64 = 0.8;
+/// Each step down is at most this share of the previous cap.
+const BITRATE_STEP: f64 = 0.75;
+/// Lowest bitrate a reduced stream is requested at, in bits per second.
+const MIN_REDUCED_BITRATE: i64 = 1_000_000;
+
+/// What a buffering update means for the current playback.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StallStep {
+  Wait,
+  /// The current stall just outlasted the threshold; `persistent` once
+  /// several did in this playback.
+  Warn {
+    persistent: bool,
+  },
+}
+
+/// Buffering state of one playback.
+#[derive(Debug, Default)]
+pub struct StallTracker {
+  stalled_since: Option<Instant>,
+  warned: bool,
+  long_stalls: u32,
+  bitrate_cap: Option<i64>,
+}
+
+impl StallTracker {
+  /// Record a `paused-for-cache` change.
+  pub fn set_buffering(&mut self, buffering: bool, now: Instant) {
+    if !buffering {
+      self.stalled_since = None;
+      self.warned = false;
+    } else if self.stalled_since.is_none() {
+      self.stalled_since = Some(now);
+    }
+  }
+
+  /// Warn once per stall that lasts `threshold`.
+  pub fn check(&mut self, now: Instant, threshold: Duration) -> StallStep {
+    let Some(since) = self.stalled_since else {
+      return StallStep::Wait;
+    };
+    if self.warned || now.saturating_duration_since(since) < threshold {
+      return StallStep::Wait;
+    }
+    self.warned = true;
+    self.long_stalls += 1;
+    let persistent = self.long_stalls >= PERSISTENT_STALL_COUNT;
+    if persistent {
+      self.long_stalls = 0;
+    }
+    StallStep::Warn { persistent }
+  }
+
+  /// Bitrate requested from the server for this playback (None = server default).
+  pub fn bitrate_cap(&self) -> Option<i64> {
+    self.bitrate_cap
+  }
+
+  /// Step the bitrate cap down below the measured throughput. None when
+  /// there is no lower step: nothing was measured yet or the floor is reached.
+  pub fn reduce_bitrate(&mut self, throughput_bytes_per_second: f64) -> Option<i64> {
+    let measured = (throughput_bytes_per_second.is_finite() && throughput_bytes_per_second > 0.0)
+      .then(|| (throughput_bytes_per_second * 8.0 * THROUGHPUT_HEADROOM) as i64);
+    let stepped = self
+      .bitrate_cap
+      .map(|cap| (cap as f64 * BITRATE_STEP) as i64);
+    let target = match (measured, stepped) {
+      (Some(measured), Some(stepped)) => measured.min(stepped),
+      (Some(bitrate), None) | (None, Some(bitrate)) => bitrate,
+      (None, None) => return None,
+    }
+    .max(MIN_REDUCED_BITRATE);
+    if self.bitrate_cap.is_some_and(|cap| target >= cap) {
+      return None;
+    }
+    self.bitrate_cap = Some(target);
+    Some(target)
+  }
+}
+
+/// Network speed in Mbit/s with one decimal, for stall messages.
+fn format_mbit_per_second(bytes_per_second: f64) -> String {
+  format!("{:.1}", bytes_per_second * 8.0 / 1_000_000.0)
+}
+
+/// Warn when buffering outlasts the configured delay, and stream at a lower
+/// bitrate when long stalls keep coming back and the user allowed it.
+pub(super) async fn handle_buffering(
+  client: &JellyfinClient,
+  state: &RwLock<SessionState>,
+  action_tx: &mpsc::Sender<MpvAction>,
+  config: &RwLock<AppConfig>,
+  bandwidth: &RwLock<BandwidthMeter>,
+  app_handle: &AppHandle,
+  event: &crate::mpv::MpvEvent,
+) {
+  let (warning_seconds, lower_bitrate) = {
+    let config = config.read();
+    (config.stall_warning_seconds, config.lower_bitrate_on_stalls)
+  };
+  let now = Instant::now();
+  let (step, title) = {
+    let mut s = state.write();
+    if s.playback.is_none() {
+      return;
+    }
+    if event.name.as_deref() == Some("paused-for-cache") {
+      let buffering = event
+        .data
+        .as_ref()
+        .and_then(|data| data.as_bool())
+        .unwrap_or(false);
+      s.stall.set_buffering(buffering, now);
+      let phase = if buffering {
+        SessionPhase::Stalling
+      } else {
+        s.open_phase()
+      };
+      if matches!(
+        s.phase,
+        SessionPhase::Playing | SessionPhase::Paused | SessionPhase::Stalling
+      ) {
+        s.enter(phase);
+      }
+    }
+    let Some(warning_seconds) = warning_seconds else {
+      return;
+    };
+    let step = s
+      .stall
+      .check(now, Duration::from_secs(u64::from(warning_seconds)));
+    let title = s.current_item.as_ref().map(SessionManager::format_title);
+    (step, title.unwrap_or_default())
+  };
+  let StallStep::Warn { persistent } = step else {
+    return;
+  };
+
+  let throughput = bandwidth.read().stats(now).session_bytes_per_second();
+  let speed = format_mbit_per_second(throughput);
+  log::warn!(
+    "Playback stalled (persistent={}) at {} Mbit/s",
+    persistent,
+    speed
+  );
+  let _ = action_tx
+    .send(MpvAction::ShowText {
+      text: tr!("osd-buffering", speed = speed.clone()),
+      duration_ms: 3000,
+    })
+    .await;
+
+  let reduced = if persistent && lower_bitrate {
+    let mut s = state.write();
+    let subtitle_index = s
+      .playback
+      .as_ref()
+      .map(|playback| playback.subtitle_stream_index.unwrap_or(-1));
+    subtitle_index.zip(s.stall.reduce_bitrate(throughput))
+  } else {
+    None
+  };
+  let Some((subtitle_index, bitrate)) = reduced else {
+    AppNotification::warning(
+      app_handle,
+      tr!("notify-playback-stalled", title = title, speed = speed),
+    );
+    return;
+  };
+
+  log::info!(
+    "Reloading the stream at {} bit/s after repeated stalls",
+    bitrate
+  );
+  if SessionManager::reload_stream(client, state, action_tx, subtitle_index, true).await {
+    AppNotification::warning(
+      app_handle,
+      tr!(
+        "notify-bitrate-lowered",
+        title = title,
+        speed = format_mbit_per_second(bitrate as f64 / 8.0)
+      ),
+    );
+  } else {
+    log::warn!("Failed to reload the stream at a lower bitrate");
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const THRESHOLD: Duration = Duration::from_secs(5);
+
+  fn long_stall(tracker: &mut StallTracker, start: Instant) -> StallStep {
+    tracker.set_buffering(true, start);
+    let step = tracker.check(start + THRESHOLD, THRESHOLD);
+    tracker.set_buffering(false, start + THRESHOLD * 2);
+    step
+  }
+
+  #[test]
+  fn stalls_warn_once_after_the_threshold() {
+    let start = Instant::now();
+    let mut tracker = StallTracker::default();
+
+    tracker.set_buffering(true, start);
+    assert_eq!(
+      tracker.check(start + Duration::from_secs(4), THRESHOLD),
+      StallStep::Wait
+    );
+    assert_eq!(
+      tracker.check(start + THRESHOLD, THRESHOLD),
+      StallStep::Warn { persistent: false }
+    );
+    assert_eq!(
+      tracker.check(start + THRESHOLD * 2, THRESHOLD),
+      StallStep::Wait
+    );
+  }
+
+  #[test]
+  fn short_stalls_and_playing_never_warn() {
+    let start = Instant::now();
+    let mut tracker = StallTracker::default();
+
+    assert_eq!(tracker.check(start, THRESHOLD), StallStep::Wait);
+    tracker.set_buffering(true, start);
+    tracker.set_buffering(false, start + Duration::from_secs(2));
+    assert_eq!(
+      tracker.check(start + THRESHOLD * 2, THRESHOLD),
+      StallStep::Wait
+    );
+  }
+
+  #[test]
+  fn every_third_long_stall_is_persistent() {
+    let start = Instant::now();
+    let mut tracker = StallTracker::default();
+
+    let steps: Vec<StallStep> = (0..4)
+      .map(|i| long_stall(&mut tracker, start + THRESHOLD * (i * 4)))
+      .collect();
+
+    assert_eq!(
+      steps,
+      vec![
+        StallStep::Warn { persistent: false },
+        StallStep::Warn { persistent: false },
+        StallStep::Warn { persistent: true },
+        StallStep::Warn { persistent: false },
+      ]
+    );
+  }
+
+  #[test]
+  fn bitrate_steps_down_below_throughput_until_the_floor() {
+    let mut tracker = StallTracker::default();
+
+    assert_eq!(tracker.reduce_bitrate(0.0), None);
+    // 1 MB/s measured leaves 6.4 Mbit/s with headroom.
+    assert_eq!(tracker.reduce_bitrate(1_000_000.0), Some(6_400_000));
+    assert_eq!(tracker.reduce_bitrate(1_000_000.0), Some(4_800_000));
+    assert_eq!(tracker.reduce_bitrate(50_000.0), Some(MIN_REDUCED_BITRATE));
+    assert_eq!(tracker.reduce_bitrate(50_000.0), None);
+    assert_eq!(tracker.bitrate_cap(), Some(MIN_REDUCED_BITRATE));
+  }
+}
@@ -0,0 +1,186 @@
+//! MPV subtitle track ids for Jellyfin subtitle streams.
+//!
+//! MPV numbers the embedded subtitle tracks first, in file order, and then
+//! every file added with `sub-add` in the order it was added. External
+//! streams are loaded on first use and selected by their id afterwards, so
+//! switching back and forth never loads one twice. A second stream can be
+//! shown alongside through MPV's `secondary-sid`, using the same ids.
+
+use super::play_resolution::jellyfin_to_mpv_track_index;
+use super::types::MediaStream;
+
+/// How MPV shows a Jellyfin subtitle stream.
+#[derive(Debug, Clone, Copy)]
+pub enum SubtitleSelection<'a> {
+  Disable,
+  /// Select the MPV subtitle track with this id.
+  Select(i32),
+  /// Add the external stream with `sub-add`, which also selects it.
+  Load(&'a MediaStream),
+}
+
+/// External subtitle streams loaded into the current MPV file.
+#[derive(Debug, Default)]
+pub struct SubtitleTracks {
+  loaded_external: Vec<i32>,
+  /// Jellyfin index of the stream shown as the secondary subtitle.
+  secondary: Option<i32>,
+}
+
+impl SubtitleTracks {
+  /// Record an external stream added with `sub-add`.
+  pub fn loaded(&mut self, jellyfin_index: i32) {
+    if !self.loaded_external.contains(&jellyfin_index) {
+      self.loaded_external.push(jellyfin_index);
+    }
+  }
+
+  pub fn secondary(&self) -> Option<i32> {
+    self.secondary
+  }
+
+  pub fn set_secondary(&mut self, jellyfin_index: Option<i32>) {
+    self.secondary = jellyfin_index;
+  }
+
+  /// The secondary stream after the current one among `streams`, skipping
+  /// the `primary` one; None after the last, which turns it off.
+  pub fn next_secondary(&self, streams: &[MediaStream], primary: Option<i32>) -> Option<i32> {
+    let mut candidates = streams
+      .iter()
+      .filter(|stream| stream.stream_type == "Subtitle" && Some(stream.index) != primary)
+      .map(|stream| stream.index);
+    match self.secondary {
+      Some(current) => candidates.skip_while(|index| *index != current).nth(1),
+      None => candidates.next(),
+    }
+  }
+
+  /// How to show `jellyfin_index` (-1 = off) among `streams`.
+  pub fn select<'a>(
+    &self,
+    streams: &'a [MediaStream],
+    jellyfin_index: i32,
+  ) -> SubtitleSelection<'a> {
+    if jellyfin_index < 0 {
+      return SubtitleSelection::Disable;
+    }
+    let external = streams.iter().find(|stream| {
+      stream.stream_type == "Subtitle" && stream.index == jellyfin_index && stream.is_external
+    });
+    let Some(external) = external else {
+      return SubtitleSelection::Select(jellyfin_to_mpv_track_index(
+        streams,
+        "Subtitle",
+        jellyfin_index,
+      ));
+    };
+    match self
+      .loaded_external
+      .iter()
+      .position(|index| *index == external.index)
+    {
+      Some(position) => {
+        let embedded = streams
+          .iter()
+          .filter(|stream| stream.stream_type == "Subtitle" && !stream.is_external)
+          .count();
+        SubtitleSelection::Select((embedded + position + 1) as i32)
+      }
+      None => SubtitleSelection::Load(external),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn subtitle(index: i32, is_external: bool) -> MediaStream {
+    MediaStream {
+      index,
+      stream_type: "Subtitle".into(),
+      codec: Some("srt".into()),
+      language: None,
+      display_title: None,
+      is_default: false,
+      is_external,
+      height: None,
+    }
+  }
+
+  /// External streams can come before the file's own tracks.
+  fn mixed_streams() -> Vec<MediaStream> {
+    vec![
+      subtitle(0, true),
+      subtitle(1, true),
+      MediaStream {
+        stream_type: "Video".into(),
+        ..subtitle(2, false)
+      },
+      subtitle(3, false),
+      subtitle(4, false),
+    ]
+  }
+
+  fn selected_id(selection: SubtitleSelection<'_>) -> Option<i32> {
+    match selection {
+      SubtitleSelection::Select(id) => Some(id),
+      _ => None,
+    }
+  }
+
+  #[test]
+  fn embedded_streams_map_past_external_ones() {
+    let streams = mixed_streams();
+    let tracks = SubtitleTracks::default();
+
+    assert_eq!(selected_id(tracks.select(&streams, 3)), Some(1));
+    assert_eq!(selected_id(tracks.select(&streams, 4)), Some(2));
+    assert!(matches!(
+      tracks.select(&streams, -1),
+      SubtitleSelection::Disable
+    ));
+  }
+
+  #[test]
+  fn external_streams_load_once_then_select_in_load_order() {
+    let streams = mixed_streams();
+    let mut tracks = SubtitleTracks::default();
+
+    assert!(matches!(
+      tracks.select(&streams, 1),
+      SubtitleSelection::Load(stream) if stream.index == 1
+    ));
+    tracks.loaded(1);
+    assert!(matches!(
+      tracks.select(&streams, 0),
+      SubtitleSelection::Load(stream) if stream.index == 0
+    ));
+    tracks.loaded(0);
+    tracks.loaded(1);
+
+    // Two embedded tracks come first, then external 1 and external 0.
+    assert_eq!(selected_id(tracks.select(&streams, 1)), Some(3));
+    assert_eq!(selected_id(tracks.select(&streams, 0)), Some(4));
+    assert_eq!(selected_id(tracks.select(&streams, 4)), Some(2));
+  }
+
+  #[test]
+  fn secondary_subtitles_cycle_past_the_primary_then_off() {
+    let streams = mixed_streams();
+    let mut tracks = SubtitleTracks::default();
+    let mut shown = Vec::new();
+
+    loop {
+      let next = tracks.next_secondary(&streams, Some(3));
+      tracks.set_secondary(next);
+      shown.push(next);
+      if next.is_none() {
+        break;
+      }
+    }
+
+    assert_eq!(shown, [Some(0), Some(1), Some(4), None]);
+  }
+}
@@ -0,0 +1,236 @@
+//! TLS trust for servers with self-signed or privately issued certificates.
+//!
+//! By default the HTTP and WebSocket clients trust the usual public roots.
+//! A server profile can add CA certificates from a PEM file, or pin the
+//! SHA-256 fingerprint of the server certificate after the user accepted it
+//! once (trust on first use).
+
+use std::sync::Arc;
+
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::client::WebPkiServerVerifier;
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::{DigitallySignedStruct, RootCertStore, SignatureScheme};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use specta::Type;
+
+use super::error::JellyfinError;
+
+/// How certificates presented by a server are trusted.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct TlsTrust {
+  /// PEM file with CA certificates trusted in addition to the public roots.
+  #[serde(default)]
+  pub ca_certificate_path: Option<String>,
+  /// SHA-256 fingerprint of a server certificate accepted even when it does
+  /// not chain to a trusted CA, as hex with optional colons.
+  #[serde(default)]
+  pub pinned_fingerprint: Option<String>,
+}
+
+impl TlsTrust {
+  /// True when only the public roots are trusted.
+  pub fn is_default(&self) -> bool {
+    self.ca_certificate_path.is_none() && self.pinned_fingerprint.is_none()
+  }
+
+  /// Client TLS configuration for this trust, or `None` for the defaults.
+  pub fn client_config(&self) -> Result<Option<rustls::ClientConfig>, JellyfinError> {
+    if self.is_default() {
+      return Ok(None);
+    }
+
+    let provider = Arc::new(rustls::crypto::aws_lc_rs::default_provider());
+    let mut roots = RootCertStore::from_iter(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+    if let Some(path) = &self.ca_certificate_path {
+      let pem = std::fs::read(path)
+        .map_err(|err| JellyfinError::Tls(format!("Could not read CA file {path}: {err}")))?;
+      let mut added = 0;
+      for certificate in CertificateDer::pem_slice_iter(&pem) {
+        let certificate = certificate
+          .map_err(|err| JellyfinError::Tls(format!("Invalid certificate in {path}: {err}")))?;
+        roots
+          .add(certificate)
+          .map_err(|err| JellyfinError::Tls(format!("Invalid certificate in {path}: {err}")))?;
+        added += 1;
+      }
+      if added == 0 {
+        return Err(JellyfinError::Tls(format!(
+          "No certificates found in {path}"
+        )));
+      }
+    }
+
+    let verifier = WebPkiServerVerifier::builder_with_provider(Arc::new(roots), provider.clone())
+      .build()
+      .map_err(|err| JellyfinError::Tls(err.to_string()))?;
+    let builder = rustls::ClientConfig::builder_with_provider(provider)
+      .with_safe_default_protocol_versions()
+      .map_err(|err| JellyfinError::Tls(err.to_string()))?;
+
+    let config = match &self.pinned_fingerprint {
+      Some(fingerprint) => builder
+        .dangerous()
+        .with_custom_certificate_verifier(Arc::new(PinnedCertificateVerifier {
+          fingerprint: parse_fingerprint(fingerprint)?,
+          roots: verifier,
+        }))
+        .with_no_client_auth(),
+      None => builder.with_webpki_verifier(verifier).with_no_client_auth(),
+    };
+    Ok(Some(config))
+  }
+}
+
+/// SHA-256 fingerprint of a DER certificate, as colon-separated upper-case hex.
+pub fn certificate_fingerprint(der: &[u8]) -> String {
+  Sha256::digest(der)
+    .iter()
+    .map(|byte| format!("{byte:02X}"))
+    .collect::<Vec<_>>()
+    .join(":")
+}
+
+/// Parse a SHA-256 fingerprint, ignoring case, colons and whitespace.
+fn parse_fingerprint(fingerprint: &str) -> Result<[u8; 32], JellyfinError> {
+  let hex: String = fingerprint
+    .chars()
+    .filter(|c| *c != ':' && !c.is_whitespace())
+    .collect();
+  let invalid = || JellyfinError::Tls(format!("Invalid SHA-256 fingerprint: {fingerprint}"));
+  if hex.len() != 64 || !hex.is_ascii() {
+    return Err(invalid());
+  }
+
+  let mut bytes = [0u8; 32];
+  for (byte, pair) in bytes.iter_mut().zip(hex.as_bytes().chunks(2)) {
+    let pair = std::str::from_utf8(pair).map_err(|_| invalid())?;
+    *byte = u8::from_str_radix(pair, 16).map_err(|_| invalid())?;
+  }
+  Ok(bytes)
+}
+
+/// Fingerprint of the certificate a server presents, without checking it, so
+/// the user can decide whether to trust it.
+pub async fn fetch_certificate_fingerprint(server_url: &str) -> Result<String, JellyfinError> {
+  if !server_url.starts_with("https://") {
+    return Err(JellyfinError::Tls(
+      "The server does not use HTTPS".to_string(),
+    ));
+  }
+
+  let client = reqwest::Client::builder()
+    .timeout(std::time::Duration::from_secs(10))
+    .tls_danger_accept_invalid_certs(true)
+    .tls_info(true)
+    .build()?;
+  let response = client.get(server_url).send().await?;
+  response
+    .extensions()
+    .get::<reqwest::tls::TlsInfo>()
+    .and_then(|info| info.peer_certificate())
+    .map(certificate_fingerprint)
+    .ok_or_else(|| JellyfinError::Tls("The server presented no certificate".to_string()))
+}
+
+/// Accepts the pinned certificate and otherwise falls back to the roots.
+#[derive(Debug)]
+struct PinnedCertificateVerifier {
+  fingerprint: [u8; 32],
+  roots: Arc<WebPkiServerVerifier>,
+}
+
+impl ServerCertVerifier for PinnedCertificateVerifier {
+  fn verify_server_cert(
+    &self,
+    end_entity: &CertificateDer<'_>,
+    intermediates: &[CertificateDer<'_>],
+    server_name: &ServerName<'_>,
+    ocsp_response: &[u8],
+    now: UnixTime,
+  ) -> Result<ServerCertVerified, rustls::Error> {
+    if Sha256::digest(end_entity.as_ref()).as_slice() == self.fingerprint {
+      return Ok(ServerCertVerified::assertion());
+    }
+    self
+      .roots
+      .verify_server_cert(end_entity, intermediates, server_name, ocsp_response, now)
+  }
+
+  fn verify_tls12_signature(
+    &self,
+    message: &[u8],
+    cert: &CertificateDer<'_>,
+    dss: &DigitallySignedStruct,
+  ) -> Result<HandshakeSignatureValid, rustls::Error> {
+    self.roots.verify_tls12_signature(message, cert, dss)
+  }
+
+  fn verify_tls13_signature(
+    &self,
+    message: &[u8],
+    cert: &CertificateDer<'_>,
+    dss: &DigitallySignedStruct,
+  ) -> Result<HandshakeSignatureValid, rustls::Error> {
+    self.roots.verify_tls13_signature(message, cert, dss)
+  }
+
+  fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+    self.roots.supported_verify_schemes()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn fingerprints_parse_with_or_without_separators() {
+    let formatted = certificate_fingerprint(b"certificate");
+    assert_eq!(formatted.len(), 32 * 3 - 1);
+
+    let expected: [u8; 32] = Sha256::digest(b"certificate").into();
+    assert_eq!(parse_fingerprint(&formatted).unwrap(), expected);
+    assert_eq!(
+      parse_fingerprint(&formatted.replace(':', "").to_lowercase()).unwrap(),
+      expected
+    );
+    assert!(parse_fingerprint("AB:CD").is_err());
+    assert!(parse_fingerprint(&"ZZ".repeat(32)).is_err());
+  }
+
+  #[test]
+  fn default_trust_keeps_the_default_client() {
+    assert!(TlsTrust::default().client_config().unwrap().is_none());
+  }
+
+  #[test]
+  fn pinned_fingerprint_builds_a_config_and_bad_input_is_rejected() {
+    let pinned = TlsTrust {
+      ca_certificate_path: None,
+      pinned_fingerprint: Some(certificate_fingerprint(b"certificate")),
+    };
+    assert!(pinned.client_config().unwrap().is_some());
+
+    let bad_fingerprint = TlsTrust {
+      ca_certificate_path: None,
+      pinned_fingerprint: Some("not-a-fingerprint".to_string()),
+    };
+    assert!(matches!(
+      bad_fingerprint.client_config(),
+      Err(JellyfinError::Tls(_))
+    ));
+
+    let missing_ca = TlsTrust {
+      ca_certificate_path: Some("/nonexistent/ca.pem".to_string()),
+      pinned_fingerprint: None,
+    };
+    assert!(matches!(
+      missing_ca.client_config(),
+      Err(JellyfinError::Tls(_))
+    ));
+  }
+}
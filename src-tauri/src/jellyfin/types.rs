@@ -2,10 +2,13 @@
 //!
 //! These types mirror the Jellyfin API responses and requests.
 
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 use specta::Type;
 
-use super::intro_skipper::IntroSkipRange;
+use super::skip_range::SkipRange;
+use super::tls::TlsTrust;
 
 /// Authentication response from Jellyfin.
 #[derive(Debug, Clone, Deserialize)]
@@ -361,6 +364,8 @@ pub struct VideoUserDataUpdate {
   pub item_id: String,
   pub played: bool,
   pub favorite: bool,
+  /// Thumbs up (true) or down (false); None when not rated.
+  pub likes: Option<bool>,
 }
 
 /// Credentials for authentication.
@@ -372,6 +377,16 @@ pub struct Credentials {
   pub server_url: String,
   pub username: String,
   pub password: String,
+  /// Extra certificate trust for servers with self-signed certificates.
+  #[serde(default)]
+  pub tls: TlsTrust,
+  /// LAN address of the same server; whichever address answers faster is used.
+  #[serde(default)]
+  pub local_url: Option<String>,
+  /// Headers a reverse proxy in front of the server requires, sent with
+  /// every request and the WebSocket upgrade.
+  #[serde(default)]
+  pub custom_headers: BTreeMap<String, String>,
 }
 
 /// Quick Connect request created by the server.
@@ -413,6 +428,8 @@ pub struct PlayRequest {
   pub audio_stream_index: Option<i32>,
   #[serde(default)]
   pub subtitle_stream_index: Option<i32>,
+  #[serde(default)]
+  pub start_index: Option<i32>,
 }
 
 /// Playstate command from Jellyfin.
@@ -433,6 +450,109 @@ pub struct GeneralCommand {
   pub arguments: Option<serde_json::Value>,
 }
 
+/// Session entry from `/Sessions`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct SessionInfo {
+  pub id: String,
+  #[serde(default)]
+  pub user_id: Option<String>,
+  #[serde(default)]
+  pub user_name: Option<String>,
+  #[serde(default)]
+  pub device_id: Option<String>,
+  #[serde(default)]
+  pub device_name: Option<String>,
+  #[serde(default)]
+  pub client: Option<String>,
+  #[serde(default)]
+  pub now_playing_item: Option<SessionNowPlayingItem>,
+  #[serde(default)]
+  pub play_state: Option<SessionPlayState>,
+  #[serde(default)]
+  pub supports_remote_control: bool,
+  /// Address the server sees the session's client connect from.
+  #[serde(default)]
+  pub remote_end_point: Option<String>,
+}
+
+/// Item a session is playing.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct SessionNowPlayingItem {
+  pub id: String,
+  #[serde(default)]
+  pub name: Option<String>,
+}
+
+/// Playback state of a session.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct SessionPlayState {
+  #[serde(default)]
+  pub is_paused: bool,
+}
+
+/// Another active session on the server, e.g. the phone that cast to
+/// JellyPilot or a TV it can control.
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteSession {
+  pub id: String,
+  pub user_name: Option<String>,
+  pub device_name: Option<String>,
+  pub client: Option<String>,
+  pub now_playing_item_id: Option<String>,
+  pub now_playing_name: Option<String>,
+  pub is_paused: bool,
+}
+
+impl From<SessionInfo> for RemoteSession {
+  fn from(session: SessionInfo) -> Self {
+    let (now_playing_item_id, now_playing_name) = match session.now_playing_item {
+      Some(item) => (Some(item.id), item.name),
+      None => (None, None),
+    };
+    Self {
+      id: session.id,
+      user_name: session.user_name,
+      device_name: session.device_name,
+      client: session.client,
+      now_playing_item_id,
+      now_playing_name,
+      is_paused: session.play_state.is_some_and(|state| state.is_paused),
+    }
+  }
+}
+
+/// Playstate command sent to another session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum RemotePlaystateCommand {
+  Pause,
+  Unpause,
+  PlayPause,
+  Stop,
+  /// Seek to the position given with the command.
+  Seek,
+  NextTrack,
+  PreviousTrack,
+}
+
+impl RemotePlaystateCommand {
+  pub fn as_str(self) -> &'static str {
+    match self {
+      Self::Pause => "Pause",
+      Self::Unpause => "Unpause",
+      Self::PlayPause => "PlayPause",
+      Self::Stop => "Stop",
+      Self::Seek => "Seek",
+      Self::NextTrack => "NextTrack",
+      Self::PreviousTrack => "PreviousTrack",
+    }
+  }
+}
+
 /// Media item (movie, episode, etc.).
 #[derive(Debug, Clone, Deserialize, Serialize, Type)]
 #[serde(rename_all = "PascalCase")]
@@ -455,6 +575,16 @@ pub struct MediaItem {
   pub run_time_ticks: Option<i64>,
   #[serde(default)]
   pub overview: Option<String>,
+  /// DVR recording status, e.g. `InProgress` while the recording is still running.
+  #[serde(default)]
+  pub status: Option<String>,
+}
+
+impl MediaItem {
+  /// A DVR recording that is still being written; its file grows during playback.
+  pub fn is_recording_in_progress(&self) -> bool {
+    self.status.as_deref() == Some("InProgress")
+  }
 }
 
 /// Media source for playback.
@@ -483,6 +613,104 @@ pub struct MediaSource {
   pub add_api_key_to_direct_stream_url: Option<bool>,
   #[serde(default)]
   pub transcoding_url: Option<String>,
+  /// Live stream opened by the server for live TV and in-progress recordings.
+  #[serde(default)]
+  pub live_stream_id: Option<String>,
+  #[serde(default)]
+  pub is_infinite_stream: bool,
+  /// Version name shown when an item has several versions, e.g. "4K".
+  #[serde(default)]
+  pub name: Option<String>,
+  /// Total bitrate in bits per second.
+  #[serde(default)]
+  pub bitrate: Option<i32>,
+  /// Files embedded in the container, like the fonts of styled subtitles.
+  #[serde(default)]
+  pub media_attachments: Vec<MediaAttachment>,
+}
+
+impl MediaSource {
+  /// Whether the stream URL serves a server remux or transcode rather than the
+  /// original file. Mirrors the choice made when building the stream URL.
+  pub fn is_server_processed(&self) -> bool {
+    !self.supports_direct_play
+      && ((self.supports_direct_stream && self.direct_stream_url.is_some())
+        || (self.supports_transcoding && self.transcoding_url.is_some()))
+  }
+
+  /// Whether the server can transcode this source, e.g. to burn in subtitles.
+  pub fn can_burn_in_subtitles(&self) -> bool {
+    self.supports_transcoding && self.transcoding_url.is_some()
+  }
+
+  /// Height of the first video stream.
+  pub fn video_height(&self) -> Option<i32> {
+    self
+      .media_streams
+      .iter()
+      .find(|stream| stream.stream_type == "Video")
+      .and_then(|stream| stream.height)
+  }
+
+  /// Embedded fonts the stream MPV gets leaves out. Remuxed and transcoded
+  /// streams drop attachments, which ASS subtitles need to look as styled.
+  pub fn missing_subtitle_fonts(&self) -> Vec<&MediaAttachment> {
+    let styled_subtitles = self
+      .media_streams
+      .iter()
+      .any(MediaStream::is_styled_subtitle);
+    if !self.is_server_processed() || !styled_subtitles {
+      return Vec::new();
+    }
+    self
+      .media_attachments
+      .iter()
+      .filter(|attachment| attachment.is_font())
+      .collect()
+  }
+}
+
+/// File embedded in a media container.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct MediaAttachment {
+  pub index: i32,
+  #[serde(default)]
+  pub codec: Option<String>,
+  #[serde(default)]
+  pub file_name: Option<String>,
+  #[serde(default)]
+  pub mime_type: Option<String>,
+}
+
+impl MediaAttachment {
+  /// TrueType and OpenType fonts, by MIME type, codec or file extension.
+  pub fn is_font(&self) -> bool {
+    let mime_type = self.mime_type.as_deref().unwrap_or("").to_ascii_lowercase();
+    let codec = self.codec.as_deref().unwrap_or("").to_ascii_lowercase();
+    let extension = self
+      .file_name
+      .as_deref()
+      .and_then(|name| std::path::Path::new(name).extension())
+      .and_then(|extension| extension.to_str())
+      .unwrap_or("")
+      .to_ascii_lowercase();
+    mime_type.contains("font")
+      || mime_type == "application/vnd.ms-opentype"
+      || matches!(codec.as_str(), "ttf" | "otf")
+      || matches!(extension.as_str(), "ttf" | "otf" | "ttc")
+  }
+
+  /// Name to save the font under; the index keeps unnamed ones apart.
+  pub fn font_file_name(&self) -> String {
+    self
+      .file_name
+      .as_deref()
+      .and_then(|name| std::path::Path::new(name).file_name())
+      .and_then(|name| name.to_str())
+      .map(|name| format!("{}-{}", self.index, name))
+      .unwrap_or_else(|| format!("{}.ttf", self.index))
+  }
 }
 
 /// Individual stream (video, audio, subtitle).
@@ -503,6 +731,35 @@ pub struct MediaStream {
   pub is_default: bool,
   #[serde(default)]
   pub is_external: bool,
+  /// Video height in pixels.
+  #[serde(default)]
+  pub height: Option<i32>,
+}
+
+impl MediaStream {
+  /// Bitmap subtitles (PGS, VobSub, DVB). Remuxed and transcoded streams drop
+  /// them, so they have to be burned into the video instead.
+  pub fn is_image_subtitle(&self) -> bool {
+    let codec = self.codec.as_deref().unwrap_or("").to_ascii_lowercase();
+    self.stream_type == "Subtitle"
+      && matches!(
+        codec.as_str(),
+        "pgs"
+          | "pgssub"
+          | "hdmv_pgs_subtitle"
+          | "dvdsub"
+          | "dvd_subtitle"
+          | "vobsub"
+          | "dvbsub"
+          | "dvb_subtitle"
+      )
+  }
+
+  /// ASS/SSA subtitles, which bring their own fonts.
+  pub fn is_styled_subtitle(&self) -> bool {
+    let codec = self.codec.as_deref().unwrap_or("").to_ascii_lowercase();
+    self.stream_type == "Subtitle" && matches!(codec.as_str(), "ass" | "ssa")
+  }
 }
 
 /// Playback info request.
@@ -534,6 +791,73 @@ pub struct PlaybackInfoResponse {
   pub play_session_id: Option<String>,
 }
 
+/// Entry of the playback queue reported as `NowPlayingQueue`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct QueueItem {
+  pub id: String,
+  pub playlist_item_id: String,
+}
+
+/// Queue repeat behavior reported as `RepeatMode`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RepeatMode {
+  #[default]
+  RepeatNone,
+  RepeatAll,
+  RepeatOne,
+}
+
+/// Queue ordering reported as `PlaybackOrder`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PlaybackOrder {
+  #[default]
+  Default,
+  Shuffle,
+}
+
+/// Item metadata sent as `Item` in playback reports, which the server shows
+/// as the session's `NowPlayingItem` to other clients.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct NowPlayingItem {
+  pub id: String,
+  pub name: String,
+  #[serde(rename = "Type")]
+  pub item_type: String,
+  pub media_type: &'static str,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub series_id: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub series_name: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub season_name: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub index_number: Option<i32>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub parent_index_number: Option<i32>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub run_time_ticks: Option<i64>,
+}
+
+impl NowPlayingItem {
+  /// `run_time_ticks` replaces the item's runtime, e.g. for a growing recording.
+  pub fn new(item: &MediaItem, run_time_ticks: Option<i64>) -> Self {
+    Self {
+      id: item.id.clone(),
+      name: item.name.clone(),
+      item_type: item.item_type.clone(),
+      media_type: "Video",
+      series_id: item.series_id.clone(),
+      series_name: item.series_name.clone(),
+      season_name: item.season_name.clone(),
+      index_number: item.index_number,
+      parent_index_number: item.parent_index_number,
+      run_time_ticks,
+    }
+  }
+}
+
 /// Playback start info (sent to Jellyfin when playback starts).
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "PascalCase")]
@@ -554,6 +878,19 @@ pub struct PlaybackStartInfo {
   pub subtitle_stream_index: Option<i32>,
   pub play_method: String,
   pub can_seek: bool,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub live_stream_id: Option<String>,
+  /// Runtime of the current item, so remote controls can size their scrubber.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub run_time_ticks: Option<i64>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub item: Option<NowPlayingItem>,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub now_playing_queue: Vec<QueueItem>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub playlist_item_id: Option<String>,
+  pub repeat_mode: RepeatMode,
+  pub playback_order: PlaybackOrder,
 }
 
 /// Playback progress info (sent periodically to Jellyfin).
@@ -576,6 +913,19 @@ pub struct PlaybackProgressInfo {
   pub subtitle_stream_index: Option<i32>,
   pub play_method: String,
   pub can_seek: bool,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub live_stream_id: Option<String>,
+  /// Runtime of the current item, so remote controls can size their scrubber.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub run_time_ticks: Option<i64>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub item: Option<NowPlayingItem>,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub now_playing_queue: Vec<QueueItem>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub playlist_item_id: Option<String>,
+  pub repeat_mode: RepeatMode,
+  pub playback_order: PlaybackOrder,
 }
 
 /// Playback stop info (sent when playback ends).
@@ -597,7 +947,7 @@ pub struct PlaybackSession {
   pub item_id: String,
   pub media_source_id: Option<String>,
   pub play_session_id: Option<String>,
-  pub intro_skipper_ranges: Vec<IntroSkipRange>,
+  pub skip_ranges: Vec<SkipRange>,
   pub position_ticks: i64,
   pub is_paused: bool,
   pub is_muted: bool,
@@ -605,6 +955,25 @@ pub struct PlaybackSession {
   pub audio_stream_index: Option<i32>,
   pub subtitle_stream_index: Option<i32>,
   pub play_method: String,
+  /// Duration MPV reports for the loaded file, once known.
+  pub mpv_duration_ticks: Option<i64>,
+  /// Whether MPV can seek in the current file.
+  pub can_seek: bool,
+  /// The file is an in-progress recording or live stream that keeps growing.
+  pub is_growing: bool,
+  /// The selected image subtitle is burned into the transcoded video.
+  pub subtitles_burned_in: bool,
+  /// Live stream the server opened for this playback.
+  pub live_stream_id: Option<String>,
+}
+
+/// Jellyfin runtime and MPV-reported duration of the current item, for diagnostics.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DurationReport {
+  pub item_id: String,
+  pub jellyfin_runtime_ticks: Option<i64>,
+  pub mpv_duration_ticks: Option<i64>,
 }
 
 /// Ticks conversion helpers (1 tick = 100 nanoseconds).
@@ -632,9 +1001,19 @@ pub struct SavedSession {
   pub user_name: String,
   pub server_name: Option<String>,
   pub device_id: Option<String>,
+  #[serde(default)]
+  pub tls: TlsTrust,
+  #[serde(default)]
+  pub local_url: Option<String>,
+  #[serde(default)]
+  pub custom_headers: BTreeMap<String, String>,
+  /// ID the server reported at sign-in; another address is only used when
+  /// the server there reports the same ID.
+  #[serde(default)]
+  pub server_id: Option<String>,
 }
 
-/// Track preference for a series (audio/subtitle language).
+/// Remembered audio/subtitle choice for a series, library or all media.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct TrackPreference {
   /// Preferred audio language code (e.g., "jpn", "eng").
@@ -653,6 +1032,9 @@ pub struct TrackPreference {
   /// Whether subtitles should be enabled when a subtitle preference is set.
   #[serde(default)]
   pub is_subtitle_enabled: bool,
+  /// Series or library name the preference was saved for.
+  #[serde(default)]
+  pub label: Option<String>,
 }
 
 impl TrackPreference {
@@ -793,6 +1175,54 @@ mod tests {
     assert_eq!(credentials.provider, MediaServerProvider::Jellyfin);
   }
 
+  #[test]
+  fn in_progress_recording_deserializes_status_and_live_stream() {
+    let item: MediaItem = serde_json::from_value(serde_json::json!({
+      "Id": "recording-1",
+      "Name": "Evening News",
+      "Type": "Recording",
+      "Status": "InProgress"
+    }))
+    .unwrap();
+    let source: MediaSource = serde_json::from_value(serde_json::json!({
+      "Id": "source-1",
+      "Protocol": "File",
+      "LiveStreamId": "live-7",
+      "IsInfiniteStream": false
+    }))
+    .unwrap();
+
+    assert!(item.is_recording_in_progress());
+    assert_eq!(source.live_stream_id.as_deref(), Some("live-7"));
+    assert!(!source.is_infinite_stream);
+  }
+
+  #[test]
+  fn fonts_of_ass_subtitles_are_missing_from_a_remux() {
+    let remux = serde_json::json!({
+      "Id": "source-1",
+      "Protocol": "File",
+      "SupportsDirectPlay": false,
+      "SupportsTranscoding": true,
+      "TranscodingUrl": "/videos/item-1/master.m3u8",
+      "MediaStreams": [{ "Index": 2, "Type": "Subtitle", "Codec": "ass" }],
+      "MediaAttachments": [
+        { "Index": 3, "Codec": "ttf", "FileName": "Gandhi Sans.ttf", "MimeType": "font/ttf" },
+        { "Index": 4, "FileName": "cover.jpg", "MimeType": "image/jpeg" }
+      ]
+    });
+    let source: MediaSource = serde_json::from_value(remux.clone()).unwrap();
+    let fonts = source.missing_subtitle_fonts();
+    assert_eq!(fonts.len(), 1);
+    assert_eq!(fonts[0].font_file_name(), "3-Gandhi Sans.ttf");
+
+    // The original file carries its fonts to MPV.
+    let mut direct_play = remux;
+    direct_play["SupportsDirectPlay"] = serde_json::json!(true);
+    let source: MediaSource = serde_json::from_value(direct_play).unwrap();
+    assert!(source.missing_subtitle_fonts().is_empty());
+  }
+
   #[test]
   fn playback_progress_serializes_to_shared_server_payload_shape() {
     let progress = PlaybackProgressInfo {
@@ -807,6 +1237,13 @@ mod tests {
       subtitle_stream_index: Some(2),
       play_method: "DirectStream".to_string(),
       can_seek: true,
+      live_stream_id: None,
+      run_time_ticks: None,
+      item: None,
+      now_playing_queue: Vec::new(),
+      playlist_item_id: None,
+      repeat_mode: RepeatMode::RepeatAll,
+      playback_order: PlaybackOrder::Shuffle,
     };
 
     let payload = serde_json::to_value(progress).expect("progress should serialize");
@@ -824,7 +1261,62 @@ mod tests {
         "AudioStreamIndex": 1,
         "SubtitleStreamIndex": 2,
         "PlayMethod": "DirectStream",
-        "CanSeek": true
+        "CanSeek": true,
+        "RepeatMode": "RepeatAll",
+        "PlaybackOrder": "Shuffle"
+      })
+    );
+  }
+
+  #[test]
+  fn playback_progress_carries_now_playing_item_and_runtime() {
+    let item: MediaItem = serde_json::from_value(serde_json::json!({
+      "Id": "episode-1",
+      "Name": "Pilot",
+      "Type": "Episode",
+      "SeriesId": "series-1",
+      "SeriesName": "Show",
+      "IndexNumber": 1,
+      "ParentIndexNumber": 1,
+      "RunTimeTicks": 12_000_000_000i64
+    }))
+    .unwrap();
+    let progress = PlaybackProgressInfo {
+      item_id: item.id.clone(),
+      media_source_id: None,
+      play_session_id: None,
+      position_ticks: Some(600_000_000),
+      is_paused: false,
+      is_muted: false,
+      volume_level: 100,
+      audio_stream_index: None,
+      subtitle_stream_index: None,
+      play_method: "DirectPlay".to_string(),
+      can_seek: true,
+      live_stream_id: None,
+      run_time_ticks: item.run_time_ticks,
+      item: Some(NowPlayingItem::new(&item, item.run_time_ticks)),
+      now_playing_queue: Vec::new(),
+      playlist_item_id: None,
+      repeat_mode: RepeatMode::RepeatNone,
+      playback_order: PlaybackOrder::Default,
+    };
+
+    let payload = serde_json::to_value(progress).expect("progress should serialize");
+
+    assert_eq!(payload["RunTimeTicks"], 12_000_000_000i64);
+    assert_eq!(
+      payload["Item"],
+      serde_json::json!({
+        "Id": "episode-1",
+        "Name": "Pilot",
+        "Type": "Episode",
+        "MediaType": "Video",
+        "SeriesId": "series-1",
+        "SeriesName": "Show",
+        "IndexNumber": 1,
+        "ParentIndexNumber": 1,
+        "RunTimeTicks": 12_000_000_000i64
       })
     );
   }
@@ -860,6 +1352,7 @@ mod tests {
       display_title: None,
       is_default: false,
       is_external: false,
+      height: None,
     }
   }
 
@@ -1001,6 +1494,32 @@ mod tests {
   }
 }
 
+/// Chapter of a media item as the server lists it.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ChapterInfo {
+  #[serde(default)]
+  pub start_position_ticks: i64,
+  #[serde(default)]
+  pub name: Option<String>,
+}
+
+/// Item response, reduced to the item's chapters.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ItemChapters {
+  #[serde(default)]
+  pub chapters: Vec<ChapterInfo>,
+}
+
+/// Items query result.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ItemsResponse {
+  #[serde(default)]
+  pub items: Vec<MediaItem>,
+}
+
 /// Response from /Shows/{seriesId}/Episodes endpoint.
 #[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "PascalCase")]
@@ -0,0 +1,88 @@
+//! Mapping between the remote volume domain (0-100) and MPV volume.
+//!
+//! Jellyfin clients always speak 0-100. The configured cap is the MPV volume
+//! reached at remote 100, so a phone at full volume can't overdrive speakers,
+//! and the curve decides how the range below the cap is spread.
+
+use crate::config::{AppConfig, VolumeCurve};
+
+/// Remote volume change for one `VolumeUp` / `VolumeDown` command.
+pub const VOLUME_STEP: i32 = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VolumeMapping {
+  max_volume: f64,
+  curve: VolumeCurve,
+}
+
+impl VolumeMapping {
+  pub fn new(max_volume: u32, curve: VolumeCurve) -> Self {
+    Self {
+      max_volume: f64::from(max_volume.max(1)),
+      curve,
+    }
+  }
+
+  pub fn from_config(config: &AppConfig) -> Self {
+    Self::new(config.max_volume, config.volume_curve)
+  }
+
+  /// MPV volume for a remote volume.
+  pub fn to_mpv(&self, volume: i32) -> f64 {
+    let fraction = f64::from(volume.clamp(0, 100)) / 100.0;
+    let fraction = match self.curve {
+      VolumeCurve::Linear => fraction,
+      VolumeCurve::Soft => fraction * fraction,
+    };
+    fraction * self.max_volume
+  }
+
+  /// Remote volume for an MPV volume.
+  pub fn to_remote(&self, mpv_volume: f64) -> i32 {
+    let fraction = (mpv_volume / self.max_volume).clamp(0.0, 1.0);
+    let fraction = match self.curve {
+      VolumeCurve::Linear => fraction,
+      VolumeCurve::Soft => fraction.sqrt(),
+    };
+    (fraction * 100.0).round() as i32
+  }
+
+  /// True when MPV was raised past the cap, e.g. from its own keybindings.
+  pub fn exceeds_cap(&self, mpv_volume: f64) -> bool {
+    mpv_volume > self.max_volume + 0.5
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn linear_mapping_scales_to_the_cap() {
+    let mapping = VolumeMapping::new(70, VolumeCurve::Linear);
+
+    assert_eq!(mapping.to_mpv(100), 70.0);
+    assert_eq!(mapping.to_mpv(50), 35.0);
+    assert_eq!(mapping.to_mpv(150), 70.0);
+    assert_eq!(mapping.to_remote(35.0), 50);
+    assert_eq!(mapping.to_remote(100.0), 100);
+  }
+
+  #[test]
+  fn soft_curve_round_trips() {
+    let mapping = VolumeMapping::new(100, VolumeCurve::Soft);
+
+    assert_eq!(mapping.to_mpv(50), 25.0);
+    for volume in [0, 10, 33, 50, 99, 100] {
+      assert_eq!(mapping.to_remote(mapping.to_mpv(volume)), volume);
+    }
+  }
+
+  #[test]
+  fn cap_check_tolerates_rounding() {
+    let mapping = VolumeMapping::new(70, VolumeCurve::Linear);
+
+    assert!(!mapping.exceeds_cap(70.4));
+    assert!(mapping.exceeds_cap(100.0));
+  }
+}
@@ -4,16 +4,18 @@ use futures_util::{SinkExt, StreamExt};
 use parking_lot::RwLock;
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::{mpsc, oneshot};
+use tokio::sync::{mpsc, oneshot, Notify};
 use tokio::task::JoinHandle;
 use tokio_tungstenite::{
-  connect_async,
+  connect_async_tls_with_config,
   tungstenite::{client::IntoClientRequest, http::header, Message},
+  Connector,
 };
 use tokio_util::sync::CancellationToken;
 
 use super::error::JellyfinError;
 use super::types::*;
+use crate::redact::redact;
 
 /// Commands that can be received from Jellyfin.
 #[derive(Debug, Clone)]
@@ -24,6 +26,8 @@ pub enum JellyfinCommand {
   Playstate(PlaystateRequest),
   /// General command (volume, mute, etc.).
   GeneralCommand(GeneralCommand),
+  /// Watched state, favorites or resume positions changed on the server.
+  UserDataChanged,
 }
 
 /// Stream events emitted by the restartable Jellyfin WebSocket command stream.
@@ -32,11 +36,58 @@ pub enum JellyfinWebSocketEvent {
   /// Initial socket connection has been established.
   Connected,
   /// The active socket was lost. A reconnect may follow unless shutdown was requested.
-  ConnectionLost,
+  ConnectionLost(ReconnectAttempt),
   /// A lost socket has reconnected successfully.
   Reconnected,
   /// A Jellyfin command received from the active socket.
   Command(JellyfinCommand),
+  /// A message that could not be parsed and was dropped.
+  ParseFailed(ParseFailure),
+  /// The server refused to reconnect with the saved token, e.g. after a
+  /// password change. The stream stops instead of retrying.
+  AuthRejected,
+  /// A reconnect attempt failed; another follows after a delay.
+  ReconnectFailed(ReconnectAttempt),
+}
+
+/// The reconnect that follows a lost socket or a failed reconnect.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReconnectAttempt {
+  /// 1 for the first reconnect after the socket was lost.
+  pub attempt: u32,
+  /// Wait before the attempt.
+  pub delay: Duration,
+}
+
+/// Longest raw payload kept for a message that failed to parse.
+const MAX_FAILED_PAYLOAD_LEN: usize = 4096;
+
+/// A WebSocket message that failed to parse, kept for diagnostics.
+#[derive(Debug, Clone)]
+pub struct ParseFailure {
+  /// `MessageType` of the message, or `<invalid>` when it isn't readable.
+  pub message_type: String,
+  pub error: String,
+  /// Raw message text, truncated to `MAX_FAILED_PAYLOAD_LEN` bytes.
+  pub payload: String,
+}
+
+impl ParseFailure {
+  fn new(text: &str, error: &JellyfinError) -> Self {
+    let message_type = serde_json::from_str::<serde_json::Value>(text)
+      .ok()
+      .and_then(|value| value.get("MessageType")?.as_str().map(str::to_string))
+      .unwrap_or_else(|| "<invalid>".to_string());
+    let mut end = text.len().min(MAX_FAILED_PAYLOAD_LEN);
+    while !text.is_char_boundary(end) {
+      end -= 1;
+    }
+    Self {
+      message_type,
+      error: error.to_string(),
+      payload: text[..end].to_string(),
+    }
+  }
 }
 
 /// Internal state for the command stream receiver.
@@ -45,9 +96,27 @@ struct ChannelState {
   event_rx: Option<mpsc::Receiver<JellyfinWebSocketEvent>>,
 }
 
+/// Where and how the command stream connects.
+struct StreamTarget {
+  /// Shared so a server address switch applies to the next reconnect.
+  url: Arc<RwLock<String>>,
+  user_agent: Option<String>,
+  /// TLS configuration for self-signed servers; `None` trusts the public roots.
+  tls: Option<Arc<rustls::ClientConfig>>,
+  /// Headers a reverse proxy requires on the upgrade request.
+  headers: header::HeaderMap,
+}
+
+/// How long a re-validation ping may go unanswered before the socket is treated as lost.
+const REVALIDATE_TIMEOUT: Duration = Duration::from_secs(5);
+/// How long a self-check connection may take.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
 /// WebSocket connection to Jellyfin server.
 pub struct JellyfinWebSocket {
   channel: Arc<RwLock<ChannelState>>,
+  url: Arc<RwLock<String>>,
+  revalidate: Arc<Notify>,
   connected: Arc<RwLock<bool>>,
   cancel_token: Arc<RwLock<Option<CancellationToken>>>,
   task_handle: Arc<RwLock<Option<JoinHandle<()>>>>,
@@ -62,6 +131,8 @@ impl JellyfinWebSocket {
         event_tx: Some(event_tx),
         event_rx: Some(event_rx),
       })),
+      url: Arc::new(RwLock::new(String::new())),
+      revalidate: Arc::new(Notify::new()),
       connected: Arc::new(RwLock::new(false)),
       cancel_token: Arc::new(RwLock::new(None)),
       task_handle: Arc::new(RwLock::new(None)),
@@ -79,6 +150,20 @@ impl JellyfinWebSocket {
     &self,
     url: &str,
     user_agent: Option<&str>,
+  ) -> Result<(), JellyfinError> {
+    self
+      .connect_with_options(url, user_agent, None, header::HeaderMap::new())
+      .await
+  }
+
+  /// Connect with an optional User-Agent override, TLS configuration and
+  /// extra upgrade headers.
+  pub async fn connect_with_options(
+    &self,
+    url: &str,
+    user_agent: Option<&str>,
+    tls: Option<Arc<rustls::ClientConfig>>,
+    headers: header::HeaderMap,
   ) -> Result<(), JellyfinError> {
     self.stop_task(false).await;
 
@@ -90,16 +175,22 @@ impl JellyfinWebSocket {
     *self.cancel_token.write() = Some(cancel_token.clone());
 
     let connected = self.connected.clone();
-    let url = url.to_string();
-    let user_agent = user_agent.map(str::to_string);
+    let revalidate = self.revalidate.clone();
+    *self.url.write() = url.to_string();
+    let target = StreamTarget {
+      url: self.url.clone(),
+      user_agent: user_agent.map(str::to_string),
+      tls,
+      headers,
+    };
     let (initial_tx, initial_rx) = oneshot::channel();
 
     let handle = tokio::spawn(async move {
       Self::run_command_stream(
-        url,
-        user_agent,
+        target,
         event_tx,
         connected,
+        revalidate,
         cancel_token,
         Some(initial_tx),
       )
@@ -111,10 +202,10 @@ impl JellyfinWebSocket {
   }
 
   async fn run_command_stream(
-    url: String,
-    user_agent: Option<String>,
+    target: StreamTarget,
     event_tx: mpsc::Sender<JellyfinWebSocketEvent>,
     connected: Arc<RwLock<bool>>,
+    revalidate: Arc<Notify>,
     cancel_token: CancellationToken,
     mut initial_tx: Option<oneshot::Sender<Result<(), JellyfinError>>>,
   ) {
@@ -126,7 +217,9 @@ impl JellyfinWebSocket {
         break;
       }
 
-      let request = match Self::connection_request(&url, user_agent.as_deref()) {
+      let url = target.url.read().clone();
+      let user_agent = target.user_agent.as_deref();
+      let request = match Self::connection_request(&url, user_agent, &target.headers) {
         Ok(request) => request,
         Err(error) => {
           *connected.write() = false;
@@ -134,9 +227,11 @@ impl JellyfinWebSocket {
             let _ = initial_tx.send(Err(error));
             break;
           }
-          log::error!("WebSocket request build failed: {}", error);
-          let delay = reconnect_delay(reconnect_attempt);
-          reconnect_attempt = reconnect_attempt.saturating_add(1);
+          log::error!(
+            "WebSocket request build failed: {}",
+            redact(&error.to_string())
+          );
+          let delay = next_reconnect(&mut reconnect_attempt).delay;
           if wait_for_reconnect_delay(delay, &cancel_token).await {
             break;
           }
@@ -146,21 +241,56 @@ impl JellyfinWebSocket {
 
       let connection = tokio::select! {
         _ = cancel_token.cancelled() => break,
-        connection = connect_async(request) => connection,
+        connection = connect_async_tls_with_config(
+          request,
+          None,
+          false,
+          target.tls.clone().map(Connector::Rustls),
+        ) => connection,
       };
 
       let (ws_stream, _) = match connection {
         Ok(connection) => connection,
         Err(error) => {
           *connected.write() = false;
+          let auth_rejected = is_auth_rejection(&error);
           if let Some(initial_tx) = initial_tx.take() {
-            let _ = initial_tx.send(Err(error.into()));
+            let error = if auth_rejected {
+              JellyfinError::CredentialsExpired(error.to_string())
+            } else {
+              error.into()
+            };
+            let _ = initial_tx.send(Err(error));
             break;
           }
-          log::error!("WebSocket reconnection failed: {}", error);
-          let delay = reconnect_delay(reconnect_attempt);
-          reconnect_attempt = reconnect_attempt.saturating_add(1);
-          if wait_for_reconnect_delay(delay, &cancel_token).await {
+          if auth_rejected {
+            log::warn!(
+              "WebSocket reconnection rejected by server: {}",
+              redact(&error.to_string())
+            );
+            let _ = Self::send_event(
+              &event_tx,
+              JellyfinWebSocketEvent::AuthRejected,
+              &cancel_token,
+            )
+            .await;
+            break;
+          }
+          log::error!(
+            "WebSocket reconnection failed: {}",
+            redact(&error.to_string())
+          );
+          let next = next_reconnect(&mut reconnect_attempt);
+          if Self::send_event(
+            &event_tx,
+            JellyfinWebSocketEvent::ReconnectFailed(next),
+            &cancel_token,
+          )
+          .await
+          {
+            break;
+          }
+          if wait_for_reconnect_delay(next.delay, &cancel_token).await {
             break;
           }
           continue;
@@ -189,30 +319,29 @@ impl JellyfinWebSocket {
         }
       }
 
-      let lost = Self::run_socket(ws_stream, &event_tx, &cancel_token).await;
+      let lost = Self::run_socket(ws_stream, &event_tx, &revalidate, &cancel_token).await;
       *connected.write() = false;
 
       if !lost || cancel_token.is_cancelled() {
         break;
       }
 
+      let next = next_reconnect(&mut reconnect_attempt);
       if Self::send_event(
         &event_tx,
-        JellyfinWebSocketEvent::ConnectionLost,
+        JellyfinWebSocketEvent::ConnectionLost(next),
         &cancel_token,
       )
       .await
       {
         break;
       }
-      let delay = reconnect_delay(reconnect_attempt);
-      reconnect_attempt = reconnect_attempt.saturating_add(1);
       log::info!(
         "Attempting WebSocket reconnection in {} seconds (attempt {})",
-        delay.as_secs(),
-        reconnect_attempt
+        next.delay.as_secs(),
+        next.attempt
       );
-      if wait_for_reconnect_delay(delay, &cancel_token).await {
+      if wait_for_reconnect_delay(next.delay, &cancel_token).await {
         break;
       }
     }
@@ -220,11 +349,40 @@ impl JellyfinWebSocket {
     *connected.write() = false;
   }
 
+  /// Open a socket and close it again, to check that the server accepts
+  /// WebSocket connections without touching the command stream.
+  pub async fn probe(
+    url: &str,
+    user_agent: Option<&str>,
+    tls: Option<Arc<rustls::ClientConfig>>,
+    headers: header::HeaderMap,
+  ) -> Result<(), JellyfinError> {
+    let request = Self::connection_request(url, user_agent, &headers)?;
+    let connection = tokio::time::timeout(
+      PROBE_TIMEOUT,
+      connect_async_tls_with_config(request, None, false, tls.map(Connector::Rustls)),
+    )
+    .await
+    .map_err(|_| JellyfinError::HttpError("WebSocket connection timed out".to_string()))?;
+
+    let (mut ws_stream, _) = connection.map_err(|error| {
+      if is_auth_rejection(&error) {
+        JellyfinError::CredentialsExpired(error.to_string())
+      } else {
+        error.into()
+      }
+    })?;
+    let _ = ws_stream.close(None).await;
+    Ok(())
+  }
+
   fn connection_request(
     url: &str,
     user_agent: Option<&str>,
+    headers: &header::HeaderMap,
   ) -> Result<tokio_tungstenite::tungstenite::handshake::client::Request, JellyfinError> {
     let mut request = url.into_client_request()?;
+    request.headers_mut().extend(headers.clone());
     if let Some(user_agent) = user_agent {
       let value = header::HeaderValue::from_str(user_agent)
         .map_err(tokio_tungstenite::tungstenite::Error::from)?;
@@ -236,6 +394,7 @@ impl JellyfinWebSocket {
   async fn run_socket<S>(
     ws_stream: tokio_tungstenite::WebSocketStream<S>,
     event_tx: &mpsc::Sender<JellyfinWebSocketEvent>,
+    revalidate: &Notify,
     cancel_token: &CancellationToken,
   ) -> bool
   where
@@ -257,6 +416,7 @@ impl JellyfinWebSocket {
     }
 
     let mut keepalive_interval = tokio::time::interval(Duration::from_secs(30));
+    let mut pong_deadline: Option<tokio::time::Instant> = None;
 
     loop {
       tokio::select! {
@@ -272,6 +432,11 @@ impl JellyfinWebSocket {
                 log::error!("Failed to handle WebSocket message: {}", e);
               }
             }
+            Some(Ok(Message::Pong(_))) => {
+              if pong_deadline.take().is_some() {
+                log::info!("WebSocket re-validated");
+              }
+            }
             Some(Ok(Message::Close(_))) => {
               log::info!("WebSocket closed by server");
               return true;
@@ -287,6 +452,18 @@ impl JellyfinWebSocket {
             _ => {}
           }
         }
+        _ = revalidate.notified() => {
+          log::info!("Re-validating WebSocket connection");
+          if let Err(e) = write.send(Message::Ping(Vec::new().into())).await {
+            log::error!("Failed to send re-validation ping: {}", e);
+            return true;
+          }
+          pong_deadline = Some(tokio::time::Instant::now() + REVALIDATE_TIMEOUT);
+        }
+        _ = tokio::time::sleep_until(pong_deadline.unwrap_or_else(tokio::time::Instant::now)), if pong_deadline.is_some() => {
+          log::warn!("WebSocket did not answer re-validation ping");
+          return true;
+        }
         _ = keepalive_interval.tick() => {
           let keepalive = serde_json::json!({
             "MessageType": "KeepAlive"
@@ -321,13 +498,26 @@ impl JellyfinWebSocket {
     event_tx: &mpsc::Sender<JellyfinWebSocketEvent>,
     cancel_token: &CancellationToken,
   ) -> Result<(), JellyfinError> {
-    if let Some(command) = Self::parse_message(text)? {
-      let _ = Self::send_event(
-        event_tx,
-        JellyfinWebSocketEvent::Command(command),
-        cancel_token,
-      )
-      .await;
+    match Self::parse_message(text) {
+      Ok(Some(command)) => {
+        let _ = Self::send_event(
+          event_tx,
+          JellyfinWebSocketEvent::Command(command),
+          cancel_token,
+        )
+        .await;
+      }
+      Ok(None) => {}
+      Err(error) => {
+        let failure = ParseFailure::new(text, &error);
+        let _ = Self::send_event(
+          event_tx,
+          JellyfinWebSocketEvent::ParseFailed(failure),
+          cancel_token,
+        )
+        .await;
+        return Err(error);
+      }
     }
 
     Ok(())
@@ -379,6 +569,7 @@ impl JellyfinWebSocket {
           Ok(None)
         }
       }
+      "UserDataChanged" => Ok(Some(JellyfinCommand::UserDataChanged)),
       "ForceKeepAlive" | "KeepAlive" => Ok(None),
       _ => {
         log::debug!("Unhandled WebSocket message type: {}", msg.message_type);
@@ -409,8 +600,20 @@ impl JellyfinWebSocket {
     self.stop_task(true).await;
   }
 
+  /// Use `url` from the next reconnect on, e.g. after the server address changed.
+  pub fn set_url(&self, url: &str) {
+    *self.url.write() = url.to_string();
+  }
+
+  /// Ping the server and reconnect if the socket turns out to be dead.
+  /// No-op while disconnected; the reconnect loop already handles that.
+  pub fn revalidate(&self) {
+    if self.is_connected() {
+      self.revalidate.notify_one();
+    }
+  }
+
   /// Check if connected.
-  #[allow(dead_code)]
   pub fn is_connected(&self) -> bool {
     *self.connected.read()
   }
@@ -435,6 +638,27 @@ fn reconnect_delay(attempt: usize) -> Duration {
   Duration::from_secs(delays[attempt.min(delays.len() - 1)])
 }
 
+/// Count the next reconnect attempt and return when it happens.
+fn next_reconnect(reconnect_attempt: &mut usize) -> ReconnectAttempt {
+  let delay = reconnect_delay(*reconnect_attempt);
+  *reconnect_attempt = reconnect_attempt.saturating_add(1);
+  ReconnectAttempt {
+    attempt: u32::try_from(*reconnect_attempt).unwrap_or(u32::MAX),
+    delay,
+  }
+}
+
+/// True when the handshake failed because the server refused the token.
+/// A 403 is left to the retry loop since reverse proxies answer with it too.
+fn is_auth_rejection(error: &tokio_tungstenite::tungstenite::Error) -> bool {
+  match error {
+    tokio_tungstenite::tungstenite::Error::Http(response) => {
+      response.status() == tokio_tungstenite::tungstenite::http::StatusCode::UNAUTHORIZED
+    }
+    _ => false,
+  }
+}
+
 async fn wait_for_reconnect_delay(delay: Duration, cancel_token: &CancellationToken) -> bool {
   tokio::select! {
     _ = cancel_token.cancelled() => true,
@@ -566,6 +790,12 @@ mod tests {
     )
     .await
     .expect("general command handled");
+    JellyfinWebSocket::handle_message(
+      r#"{"MessageType":"UserDataChanged","Data":{"UserId":"user-1","UserDataList":[]}}"#,
+      &event_tx,
+    )
+    .await
+    .expect("user data change handled");
 
     match next_event(&mut event_rx).await {
       JellyfinWebSocketEvent::Command(JellyfinCommand::Play(request)) => {
@@ -586,9 +816,75 @@ mod tests {
       }
       event => panic!("unexpected event: {event:?}"),
     }
+    assert!(matches!(
+      next_event(&mut event_rx).await,
+      JellyfinWebSocketEvent::Command(JellyfinCommand::UserDataChanged)
+    ));
     assert!(event_rx.try_recv().is_err());
   }
 
+  #[tokio::test]
+  async fn failed_parses_are_reported_with_their_raw_payload() {
+    let (event_tx, mut event_rx) = mpsc::channel(8);
+    let cancel_token = CancellationToken::new();
+    let message = r#"{"MessageType":"Playstate","Data":{"Command":42}}"#;
+
+    assert!(
+      JellyfinWebSocket::handle_socket_message(message, &event_tx, &cancel_token)
+        .await
+        .is_err()
+    );
+
+    match next_event(&mut event_rx).await {
+      JellyfinWebSocketEvent::ParseFailed(failure) => {
+        assert_eq!(failure.message_type, "Playstate");
+        assert_eq!(failure.payload, message);
+        assert!(!failure.error.is_empty());
+      }
+      event => panic!("unexpected event: {event:?}"),
+    }
+  }
+
+  #[test]
+  fn failed_payloads_are_truncated_on_a_char_boundary() {
+    let text = format!("{}é", "x".repeat(MAX_FAILED_PAYLOAD_LEN - 1));
+    let error = JellyfinError::HttpError("bad".to_string());
+
+    let failure = ParseFailure::new(&text, &error);
+
+    assert_eq!(failure.message_type, "<invalid>");
+    assert_eq!(failure.payload.len(), MAX_FAILED_PAYLOAD_LEN - 1);
+  }
+
+  #[tokio::test]
+  async fn unknown_command_values_still_decode() {
+    let (event_tx, mut event_rx) = mpsc::channel(8);
+
+    JellyfinWebSocket::handle_message(
+      r#"{"MessageType":"Playstate","Data":{"Command":"FutureCommand"}}"#,
+      &event_tx,
+    )
+    .await
+    .expect("unknown playstate command decodes");
+    JellyfinWebSocket::handle_message(
+      r#"{"MessageType":"Play","Data":{"ItemIds":["item-1"],"PlayCommand":"PlayInstantMix","ExtraField":true}}"#,
+      &event_tx,
+    )
+    .await
+    .expect("unknown play command decodes");
+
+    assert!(matches!(
+      next_event(&mut event_rx).await,
+      JellyfinWebSocketEvent::Command(JellyfinCommand::Playstate(request))
+        if request.command == "FutureCommand"
+    ));
+    assert!(matches!(
+      next_event(&mut event_rx).await,
+      JellyfinWebSocketEvent::Command(JellyfinCommand::Play(request))
+        if request.play_command == "PlayInstantMix"
+    ));
+  }
+
   #[tokio::test]
   async fn emby_remote_control_commands_decode_to_provider_neutral_commands() {
     let (event_tx, mut event_rx) = mpsc::channel(8);
@@ -700,7 +996,7 @@ mod tests {
     ));
     assert!(matches!(
       next_event(&mut rx).await,
-      JellyfinWebSocketEvent::ConnectionLost
+      JellyfinWebSocketEvent::ConnectionLost(ReconnectAttempt { attempt: 1, .. })
     ));
     assert!(matches!(
       next_event(&mut rx).await,
@@ -720,6 +1016,55 @@ mod tests {
     assert!(!websocket.is_connected());
   }
 
+  #[tokio::test]
+  async fn rejected_reconnect_reports_auth_and_stops_retrying() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+    let url = format!("ws://{}", listener.local_addr().expect("addr"));
+
+    let server = tokio::spawn(async move {
+      let (first_socket, _) = listener.accept().await.expect("first accept");
+      let mut first = accept_async(first_socket).await.expect("first websocket");
+      expect_sessions_start(&mut first).await;
+      first.close(None).await.expect("close first");
+
+      let (mut second, _) = listener.accept().await.expect("second accept");
+      let mut request = [0u8; 1024];
+      let _ = second.read(&mut request).await;
+      second
+        .write_all(b"HTTP/1.1 401 Unauthorized\r\nContent-Length: 0\r\n\r\n")
+        .await
+        .expect("write 401");
+
+      tokio::time::timeout(Duration::from_millis(200), listener.accept())
+        .await
+        .is_err()
+    });
+
+    let websocket = JellyfinWebSocket::new();
+    let mut rx = websocket.take_event_receiver().expect("event receiver");
+    websocket.connect(&url).await.expect("initial connect");
+
+    assert!(matches!(
+      next_event(&mut rx).await,
+      JellyfinWebSocketEvent::Connected
+    ));
+    assert!(matches!(
+      next_event(&mut rx).await,
+      JellyfinWebSocketEvent::ConnectionLost(ReconnectAttempt { attempt: 1, .. })
+    ));
+    assert!(matches!(
+      next_event(&mut rx).await,
+      JellyfinWebSocketEvent::AuthRejected
+    ));
+
+    assert!(
+      server.await.expect("server done"),
+      "client retried after 401"
+    );
+    assert!(!websocket.is_connected());
+    websocket.disconnect().await;
+  }
+
   #[tokio::test]
   async fn explicit_shutdown_does_not_schedule_reconnect() {
     let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
@@ -1,25 +1,38 @@
-use std::path::PathBuf;
 use std::sync::Arc;
 
 mod auth_profiles;
 mod command;
 mod config;
+mod control_api;
+mod diagnostics;
+mod i18n;
 mod image_cache;
 mod image_ref;
 mod jellyfin;
+mod metrics;
 mod mpv;
 mod now_playing;
+mod os_notification;
 mod playback_control;
+mod power;
+mod redact;
+mod retention;
+mod self_check;
+mod settings_lock;
+mod telemetry;
 mod tray;
+mod update_check;
+mod warm_mpv;
+mod webhooks;
 
-use command::{ConfigState, JellyfinState, MpvState};
+use command::{ConfigState, JellyfinState, MpvState, SettingsLockState};
 pub use config::AppConfig;
 use image_cache::{ImageCache, ImageCacheState};
-use jellyfin::JellyfinClient;
+use jellyfin::{CastCapabilities, JellyfinClient};
 use mpv::MpvClient;
 use parking_lot::RwLock;
 use tauri::{Manager, WindowEvent};
-use tauri_plugin_log::{Target, TargetKind};
+use tauri_plugin_log::{RotationStrategy, Target, TargetKind};
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
@@ -61,20 +74,26 @@ pub fn run() {
       },
     )
     .manage(config_state)
+    .manage(SettingsLockState::default())
     .manage(image_cache_state)
     .manage(mpv_state)
     .manage(jellyfin_state)
     .invoke_handler(builder.invoke_handler())
     .plugin(tauri_plugin_store::Builder::new().build())
+    .plugin(tauri_plugin_notification::init())
     .setup(move |app| {
-      // Setup logging with webview target for in-app log viewing
+      // Setup logging with webview target for in-app log viewing and a
+      // rotating log file for diagnostics bundles
       app.handle().plugin(
         tauri_plugin_log::Builder::default()
           .level(log::LevelFilter::Info)
           .targets([
             Target::new(TargetKind::Stdout),
             Target::new(TargetKind::Webview),
+            Target::new(TargetKind::LogDir { file_name: None }),
           ])
+          .max_file_size(5 * 1024 * 1024)
+          .rotation_strategy(RotationStrategy::KeepSome(3))
           .build(),
       )?;
 
@@ -93,25 +112,82 @@ pub fn run() {
       }
 
       // Apply loaded config to MPV client
-      let mpv_path = loaded_config
-        .mpv_path
-        .as_ref()
-        .filter(|s| !s.is_empty())
-        .map(PathBuf::from);
-      mpv_for_setup.set_mpv_path(mpv_path);
+      mpv_for_setup.set_mpv_path(loaded_config.resolved_mpv_path());
       mpv_for_setup.set_extra_args(loaded_config.mpv_args.clone());
+      mpv_for_setup.set_managed_config(loaded_config.managed_mpv_config());
+      mpv_for_setup.set_fullscreen_options(loaded_config.fullscreen_options());
+      mpv_for_setup.set_display_server(loaded_config.mpv_display_server);
+      mpv_for_setup.set_command_timeouts(loaded_config.command_timeouts());
+      mpv_for_setup.set_adopt_orphans(loaded_config.adopt_orphaned_mpv);
+      mpv_for_setup.set_outlive_app(loaded_config.mpv_outlives_app);
+      mpv_for_setup.set_audit_retention(loaded_config.command_audit_retention as usize);
+      mpv::set_log_retention(loaded_config.mpv_log_retention as usize);
 
       // Apply loaded config to Jellyfin client
       jellyfin_for_setup.set_device_name(loaded_config.device_name.clone());
+      jellyfin_for_setup.set_cast_capabilities(CastCapabilities::from_config(&loaded_config));
+
+      i18n::set_language(loaded_config.language);
+      if let Err(e) = metrics::apply_port(loaded_config.metrics_port) {
+        log::warn!("Failed to serve metrics: {}", e);
+      }
+      webhooks::configure(loaded_config.webhooks.clone());
+      control_api::apply(
+        app.handle(),
+        loaded_config.control_api_port,
+        &loaded_config.control_api_token,
+      );
 
       // Store config in state
+      let bundled_mpv = loaded_config.bundled_mpv;
       *config_for_setup.write() = loaded_config;
 
+      // Detect the MPV version and keep the bundled MPV up to date; an
+      // offline start keeps the installed build.
+      let app_handle = app.handle().clone();
+      let mpv_for_version = mpv_for_setup.clone();
+      let config_for_version = config_for_setup.clone();
+      tauri::async_runtime::spawn(async move {
+        if bundled_mpv {
+          match command::update_bundled_mpv(&app_handle, &mpv_for_version, &config_for_version)
+            .await
+          {
+            Ok(()) => return,
+            Err(e) => log::warn!("{}", e.message),
+          }
+        }
+        command::refresh_mpv_version(&app_handle, &mpv_for_version).await;
+      });
+
+      // Quit or adopt an MPV a crashed JellyPilot left running.
+      let mpv_for_orphans = mpv_for_setup.clone();
+      tauri::async_runtime::spawn(async move {
+        mpv_for_orphans.handle_orphans().await;
+      });
+
+      // Playback files a crashed JellyPilot left in the temp dir.
+      tauri::async_runtime::spawn_blocking(jellyfin::remove_stale_session_files);
+
       // Setup system tray
       if let Err(e) = tray::setup_tray(app) {
         log::error!("Failed to setup system tray: {}", e);
       }
 
+      // Pause playback when the screen locks or the system sleeps
+      power::start_power_monitor(app.handle().clone());
+
+      update_check::start(app.handle().clone(), config_for_setup.clone());
+      telemetry::start(
+        app.handle().clone(),
+        config_for_setup.clone(),
+        mpv_for_setup.clone(),
+      );
+      warm_mpv::start(
+        app.handle().clone(),
+        config_for_setup.clone(),
+        mpv_for_setup.clone(),
+      );
+
       builder.mount_events(app);
       Ok(())
     })
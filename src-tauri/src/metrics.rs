@@ -0,0 +1,295 @@
+//! Optional Prometheus metrics endpoint.
+//!
+//! Counters are kept process-wide and always updated; they only leave the
+//! process when `metrics_port` is set, in which case `GET /metrics` on
+//! `127.0.0.1:<port>` serves them in the Prometheus text format.
+
+use std::fmt::Write as _;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::Duration;
+
+use axum::http::header;
+use axum::response::IntoResponse;
+use axum::routing::get;
+use axum::Router;
+use parking_lot::Mutex;
+use tauri::async_runtime::JoinHandle;
+
+/// Counters exposed as `jellypilot_<name>_total`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Counter {
+  /// Commands received from the server over the WebSocket.
+  CommandsHandled,
+  /// WebSocket reconnects after a lost connection.
+  Reconnects,
+  /// Progress reports the server did not accept.
+  ProgressReportFailures,
+  /// MPV processes spawned after the first one.
+  MpvRestarts,
+  /// Items that started playing in MPV.
+  PlaybacksStarted,
+  /// MPV exits while an item was playing.
+  MpvCrashes,
+  /// Transcodes the server ended before the item did.
+  InterruptedTranscodes,
+  /// MPV actions that outlasted their slow threshold.
+  SlowMpvActions,
+}
+
+impl Counter {
+  const ALL: [Counter; 8] = [
+    Counter::CommandsHandled,
+    Counter::Reconnects,
+    Counter::ProgressReportFailures,
+    Counter::MpvRestarts,
+    Counter::PlaybacksStarted,
+    Counter::MpvCrashes,
+    Counter::InterruptedTranscodes,
+    Counter::SlowMpvActions,
+  ];
+
+  fn name(self) -> &'static str {
+    match self {
+      Self::CommandsHandled => "commands_handled",
+      Self::Reconnects => "reconnects",
+      Self::ProgressReportFailures => "progress_report_failures",
+      Self::MpvRestarts => "mpv_restarts",
+      Self::PlaybacksStarted => "playbacks_started",
+      Self::MpvCrashes => "mpv_crashes",
+      Self::InterruptedTranscodes => "interrupted_transcodes",
+      Self::SlowMpvActions => "slow_mpv_actions",
+    }
+  }
+
+  fn help(self) -> &'static str {
+    match self {
+      Self::CommandsHandled => "Remote commands received from the server.",
+      Self::Reconnects => "WebSocket reconnects after a lost connection.",
+      Self::ProgressReportFailures => "Playback progress reports that failed.",
+      Self::MpvRestarts => "MPV processes started after the first one.",
+      Self::PlaybacksStarted => "Items that started playing in MPV.",
+      Self::MpvCrashes => "MPV exits during playback.",
+      Self::InterruptedTranscodes => "Transcodes the server ended before the item did.",
+      Self::SlowMpvActions => "MPV actions that took unusually long.",
+    }
+  }
+}
+
+/// Counter and gauge values; the process-wide set is [`METRICS`].
+struct Metrics {
+  counters: [AtomicU64; Counter::ALL.len()],
+  mpv_started: AtomicBool,
+  /// Milliseconds the latest MPV start took to connect.
+  mpv_connect_millis: AtomicU64,
+  playing: AtomicBool,
+  /// `f64` bits of the playback position in seconds.
+  position_bits: AtomicU64,
+}
+
+impl Metrics {
+  const fn new() -> Self {
+    Self {
+      counters: [const { AtomicU64::new(0) }; Counter::ALL.len()],
+      mpv_started: AtomicBool::new(false),
+      mpv_connect_millis: AtomicU64::new(0),
+      playing: AtomicBool::new(false),
+      position_bits: AtomicU64::new(0),
+    }
+  }
+
+  fn increment(&self, counter: Counter) {
+    self.counters[counter as usize].fetch_add(1, Ordering::Relaxed);
+  }
+
+  fn count(&self, counter: Counter) -> u64 {
+    self.counters[counter as usize].load(Ordering::Relaxed)
+  }
+
+  fn record_mpv_start(&self, connect_time: Duration) {
+    self
+      .mpv_connect_millis
+      .store(connect_time.as_millis() as u64, Ordering::Relaxed);
+    if self.mpv_started.swap(true, Ordering::Relaxed) {
+      self.increment(Counter::MpvRestarts);
+    }
+  }
+
+  fn set_position(&self, position_seconds: Option<f64>) {
+    self
+      .playing
+      .store(position_seconds.is_some(), Ordering::Relaxed);
+    self
+      .position_bits
+      .store(position_seconds.unwrap_or(0.0).to_bits(), Ordering::Relaxed);
+  }
+
+  /// The values in the Prometheus text exposition format.
+  fn render(&self) -> String {
+    let mut out = String::new();
+    for counter in Counter::ALL {
+      let name = format!("jellypilot_{}_total", counter.name());
+      let value = self.count(counter);
+      let _ = writeln!(out, "# HELP {} {}", name, counter.help());
+      let _ = writeln!(out, "# TYPE {} counter", name);
+      let _ = writeln!(out, "{} {}", name, value);
+    }
+
+    let playing = self.playing.load(Ordering::Relaxed);
+    let position = f64::from_bits(self.position_bits.load(Ordering::Relaxed));
+    let _ = writeln!(
+      out,
+      "# HELP jellypilot_playing Whether an item is loaded in MPV."
+    );
+    let _ = writeln!(out, "# TYPE jellypilot_playing gauge");
+    let _ = writeln!(out, "jellypilot_playing {}", u8::from(playing));
+    let _ = writeln!(
+      out,
+      "# HELP jellypilot_position_seconds Playback position of the current item."
+    );
+    let _ = writeln!(out, "# TYPE jellypilot_position_seconds gauge");
+    let _ = writeln!(out, "jellypilot_position_seconds {}", position);
+    let connect_seconds = self.mpv_connect_millis.load(Ordering::Relaxed) as f64 / 1000.0;
+    let _ = writeln!(
+      out,
+      "# HELP jellypilot_mpv_connect_seconds Time the latest MPV start took to accept IPC commands."
+    );
+    let _ = writeln!(out, "# TYPE jellypilot_mpv_connect_seconds gauge");
+    let _ = writeln!(out, "jellypilot_mpv_connect_seconds {}", connect_seconds);
+    out
+  }
+}
+
+static METRICS: Metrics = Metrics::new();
+
+static SERVER: Mutex<Option<(u16, JoinHandle<()>)>> = parking_lot::const_mutex(None);
+
+/// Add one to `counter`.
+pub fn increment(counter: Counter) {
+  METRICS.increment(counter);
+}
+
+/// Current value of `counter`.
+pub fn count(counter: Counter) -> u64 {
+  METRICS.count(counter)
+}
+
+/// Count an MPV start that connected after `connect_time`; every start
+/// after the first is a restart.
+pub fn record_mpv_start(connect_time: Duration) {
+  METRICS.record_mpv_start(connect_time);
+}
+
+/// Update the playback gauges; `None` when nothing is playing.
+pub fn set_position(position_seconds: Option<f64>) {
+  METRICS.set_position(position_seconds);
+}
+
+/// Serve metrics on `port`, or stop serving when `None`.
+///
+/// Restarts the listener only when the port changes. When the port can't be
+/// bound nothing is served, and the next call with that port tries again.
+pub fn apply_port(port: Option<u16>) -> std::io::Result<()> {
+  let mut server = SERVER.lock();
+  if server.as_ref().map(|(current, _)| *current) == port {
+    return Ok(());
+  }
+  if let Some((old_port, handle)) = server.take() {
+    log::info!("Stopping metrics endpoint on port {}", old_port);
+    handle.abort();
+  }
+  let Some(port) = port else {
+    return Ok(());
+  };
+  let listener = std::net::TcpListener::bind(("127.0.0.1", port))?;
+  listener.set_nonblocking(true)?;
+  *server = Some((port, tauri::async_runtime::spawn(serve(listener))));
+  Ok(())
+}
+
+async fn serve(listener: std::net::TcpListener) {
+  let listener = match tokio::net::TcpListener::from_std(listener) {
+    Ok(listener) => listener,
+    Err(e) => {
+      log::error!("Failed to start metrics endpoint: {}", e);
+      return;
+    }
+  };
+  if let Ok(address) = listener.local_addr() {
+    log::info!("Serving metrics on http://{}/metrics", address);
+  }
+  if let Err(e) = axum::serve(listener, router(&METRICS)).await {
+    log::error!("Metrics endpoint stopped: {}", e);
+  }
+}
+
+/// `GET /metrics` for `metrics`; anything else is answered by axum.
+fn router(metrics: &'static Metrics) -> Router {
+  Router::new().route(
+    "/metrics",
+    get(move || async move {
+      (
+        [(
+          header::CONTENT_TYPE,
+          "text/plain; version=0.0.4; charset=utf-8",
+        )],
+        metrics.render(),
+      )
+        .into_response()
+    }),
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn render_lists_counters_and_gauges() {
+    let metrics = Metrics::new();
+    metrics.increment(Counter::Reconnects);
+    metrics.set_position(Some(12.5));
+
+    let text = metrics.render();
+    assert!(text.contains("# TYPE jellypilot_reconnects_total counter\n"));
+    assert!(text.contains("jellypilot_reconnects_total 1\n"));
+    assert!(text.contains("jellypilot_commands_handled_total 0\n"));
+    assert!(text.contains("jellypilot_playing 1\n"));
+    assert!(text.contains("jellypilot_position_seconds 12.5\n"));
+    assert!(text.contains("# TYPE jellypilot_mpv_connect_seconds gauge\n"));
+  }
+
+  #[tokio::test]
+  async fn only_get_metrics_is_served() {
+    static SERVED: Metrics = Metrics::new();
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, router(&SERVED)).await });
+    let client = reqwest::Client::new();
+
+    let scrape = client.get(format!("{base}/metrics")).send().await.unwrap();
+    assert_eq!(scrape.status(), reqwest::StatusCode::OK);
+    assert!(scrape
+      .text()
+      .await
+      .unwrap()
+      .contains("jellypilot_playing 0\n"));
+    let other = client.get(format!("{base}/")).send().await.unwrap();
+    assert_eq!(other.status(), reqwest::StatusCode::NOT_FOUND);
+    let post = client.post(format!("{base}/metrics")).send().await.unwrap();
+    assert_eq!(post.status(), reqwest::StatusCode::METHOD_NOT_ALLOWED);
+  }
+
+  #[tokio::test]
+  async fn a_port_that_cannot_be_bound_is_not_marked_as_served() {
+    let taken = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = taken.local_addr().unwrap().port();
+
+    assert!(apply_port(Some(port)).is_err());
+    assert!(SERVER.lock().is_none());
+
+    drop(taken);
+    assert!(apply_port(Some(port)).is_ok());
+    assert!(SERVER.lock().is_some());
+    apply_port(None).unwrap();
+  }
+}
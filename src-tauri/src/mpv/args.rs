@@ -0,0 +1,198 @@
+//! Translation of extra MPV command-line args into runtime changes.
+
+/// Options that only take effect when MPV starts.
+const STARTUP_ONLY_OPTIONS: [&str; 14] = [
+  "config",
+  "config-dir",
+  "include",
+  "input-conf",
+  "input-ipc-server",
+  "idle",
+  "log-file",
+  "script",
+  "scripts",
+  "load-scripts",
+  "vo",
+  "gpu-api",
+  "gpu-context",
+  "player-operation-mode",
+];
+
+/// How an extra arg can be applied to a running MPV.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LiveArg {
+  /// `--name=value`, `--name` or `--no-name`, applied with `set_property`.
+  Property { name: String, value: String },
+  /// `--profile=name`, applied with `apply-profile`.
+  Profile(String),
+  /// Needs an MPV restart.
+  RestartRequired,
+}
+
+/// Split `--name=value`, `--name` or `--no-name` into name and value.
+fn split_option(arg: &str) -> Option<(&str, String)> {
+  let option = arg.trim().strip_prefix("--")?;
+  Some(match option.split_once('=') {
+    Some((name, value)) => (name, value.to_string()),
+    None => match option.strip_prefix("no-") {
+      Some(name) => (name, "no".to_string()),
+      None => (option, "yes".to_string()),
+    },
+  })
+}
+
+/// The option JellyPilot spawns MPV with that `arg` would override: the IPC
+/// server it controls MPV through, idling between casts, or the input
+/// bindings its shortcuts rely on.
+pub fn overridden_required_option(arg: &str) -> Option<&'static str> {
+  let (name, value) = split_option(arg)?;
+  match name {
+    "input-ipc-server" => Some("--input-ipc-server"),
+    "idle" if value != "yes" => Some("--idle"),
+    "input-default-bindings" if value != "yes" => Some("--input-default-bindings"),
+    _ => None,
+  }
+}
+
+/// Split extra args into those safe to pass and those overriding a required option.
+pub fn strip_conflicting_args(args: &[String]) -> (Vec<String>, Vec<String>) {
+  args
+    .iter()
+    .cloned()
+    .partition(|arg| overridden_required_option(arg).is_none())
+}
+
+/// Classify one extra arg.
+pub fn classify_extra_arg(arg: &str) -> LiveArg {
+  let Some((name, value)) = split_option(arg) else {
+    return LiveArg::RestartRequired;
+  };
+
+  if name.is_empty() || STARTUP_ONLY_OPTIONS.contains(&name) {
+    return LiveArg::RestartRequired;
+  }
+  if name == "profile" {
+    return LiveArg::Profile(value);
+  }
+  LiveArg::Property {
+    name: name.to_string(),
+    value,
+  }
+}
+
+/// Option an arg sets, so `--volume=50` and `--volume=70` or `--osc` and
+/// `--no-osc` count as the same one; anything else stands for itself.
+fn option_key(arg: &str) -> &str {
+  split_option(arg).map_or(arg.trim(), |(name, _)| name)
+}
+
+/// Split the change from extra args `old` to `new` into the args to apply,
+/// being new or changed, and the old args whose option is no longer set.
+/// MPV's defaults aren't known, so only a restart undoes those.
+pub fn diff_extra_args(old: &[String], new: &[String]) -> (Vec<String>, Vec<String>) {
+  let changed = new
+    .iter()
+    .filter(|arg| !old.contains(arg))
+    .cloned()
+    .collect();
+  let removed = old
+    .iter()
+    .filter(|arg| !new.iter().any(|new| option_key(new) == option_key(arg)))
+    .cloned()
+    .collect();
+  (changed, removed)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn property(name: &str, value: &str) -> LiveArg {
+    LiveArg::Property {
+      name: name.to_string(),
+      value: value.to_string(),
+    }
+  }
+
+  #[test]
+  fn simple_option_forms_map_to_properties() {
+    assert_eq!(
+      classify_extra_arg("--volume-max=150"),
+      property("volume-max", "150")
+    );
+    assert_eq!(
+      classify_extra_arg("--fullscreen"),
+      property("fullscreen", "yes")
+    );
+    assert_eq!(classify_extra_arg("--no-osc"), property("osc", "no"));
+  }
+
+  #[test]
+  fn profiles_are_applied_as_profiles() {
+    assert_eq!(
+      classify_extra_arg("--profile=gpu-hq"),
+      LiveArg::Profile("gpu-hq".to_string())
+    );
+  }
+
+  #[test]
+  fn startup_options_and_non_options_need_restart() {
+    assert_eq!(
+      classify_extra_arg("--vo=gpu-next"),
+      LiveArg::RestartRequired
+    );
+    assert_eq!(classify_extra_arg("--no-config"), LiveArg::RestartRequired);
+    assert_eq!(classify_extra_arg("file.mkv"), LiveArg::RestartRequired);
+  }
+
+  #[test]
+  fn args_that_break_ipc_or_idling_are_flagged() {
+    assert_eq!(
+      overridden_required_option("--input-ipc-server=/tmp/other"),
+      Some("--input-ipc-server")
+    );
+    assert_eq!(overridden_required_option("--no-idle"), Some("--idle"));
+    assert_eq!(overridden_required_option("--idle=once"), Some("--idle"));
+    assert_eq!(overridden_required_option("--idle"), None);
+    assert_eq!(overridden_required_option("--volume-max=150"), None);
+  }
+
+  #[test]
+  fn conflicting_args_are_stripped_and_the_rest_kept_in_order() {
+    let args: Vec<String> = [
+      "--volume-max=150",
+      "--input-ipc-server=/tmp/other",
+      "--no-input-default-bindings",
+      "--no-osc",
+    ]
+    .map(String::from)
+    .to_vec();
+
+    let (kept, removed) = strip_conflicting_args(&args);
+
+    assert_eq!(kept, vec!["--volume-max=150", "--no-osc"]);
+    assert_eq!(
+      removed,
+      vec![
+        "--input-ipc-server=/tmp/other",
+        "--no-input-default-bindings"
+      ]
+    );
+  }
+
+  #[test]
+  fn only_new_and_changed_args_are_applied_and_dropped_options_reported() {
+    let old: Vec<String> = ["--volume=50", "--osc", "--sub-auto=fuzzy", "--profile=fast"]
+      .map(String::from)
+      .to_vec();
+    let new: Vec<String> = ["--volume=70", "--no-osc", "--profile=fast", "--mute"]
+      .map(String::from)
+      .to_vec();
+
+    let (changed, removed) = diff_extra_args(&old, &new);
+
+    assert_eq!(changed, vec!["--volume=70", "--no-osc", "--mute"]);
+    assert_eq!(removed, vec!["--sub-auto=fuzzy"]);
+    assert_eq!(diff_extra_args(&new, &new), (Vec::new(), Vec::new()));
+  }
+}
@@ -0,0 +1,182 @@
+//! Bounded record of recent IPC commands sent to MPV, for diagnostics.
+//!
+//! Only the command name (and property name for property commands) is kept;
+//! arguments such as stream URLs carry the access token and are never stored.
+//! Remote-control messages that failed to parse are recorded alongside, with
+//! their raw payload, when WebSocket parse diagnostics are enabled.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+
+use super::protocol::MpvCommand;
+use crate::retention::{BoundedBuffer, BufferUsage};
+
+/// Number of commands kept in the audit log unless configured otherwise.
+pub const DEFAULT_AUDIT_RETENTION: usize = 200;
+
+/// One IPC command and how it ended.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntry {
+  /// Milliseconds since the Unix epoch when the command completed.
+  pub timestamp_ms: u64,
+  pub request_id: i64,
+  pub command: String,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub property: Option<String>,
+  /// `"success"` or the error MPV / the IPC layer reported.
+  pub outcome: String,
+  /// Raw payload of a rejected remote-control message.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub payload: Option<String>,
+}
+
+/// Ring buffer of the most recent commands.
+#[derive(Debug)]
+pub struct CommandAudit {
+  entries: BoundedBuffer<AuditEntry>,
+}
+
+impl Default for CommandAudit {
+  fn default() -> Self {
+    Self {
+      entries: BoundedBuffer::new(DEFAULT_AUDIT_RETENTION),
+    }
+  }
+}
+
+impl AuditEntry {
+  /// Estimated memory held by the entry.
+  fn size(&self) -> usize {
+    std::mem::size_of::<Self>()
+      + self.command.len()
+      + self.outcome.len()
+      + self.property.as_ref().map_or(0, String::len)
+      + self.payload.as_ref().map_or(0, String::len)
+  }
+}
+
+impl CommandAudit {
+  pub fn record(&mut self, cmd: &MpvCommand, outcome: impl Into<String>) {
+    self.push(AuditEntry {
+      timestamp_ms: now_ms(),
+      request_id: cmd.request_id,
+      command: command_name(cmd),
+      property: property_name(cmd),
+      outcome: outcome.into(),
+      payload: None,
+    });
+  }
+
+  /// Record a remote-control message that could not be parsed.
+  pub fn record_rejected_message(&mut self, message_type: &str, error: &str, payload: String) {
+    self.push(AuditEntry {
+      timestamp_ms: now_ms(),
+      request_id: 0,
+      command: format!("websocket:{}", message_type),
+      property: None,
+      outcome: error.to_string(),
+      payload: Some(payload),
+    });
+  }
+
+  fn push(&mut self, entry: AuditEntry) {
+    self.entries.push(entry);
+  }
+
+  /// Keep at most `retention` commands from now on.
+  pub fn set_retention(&mut self, retention: usize) {
+    self.entries.set_capacity(retention);
+  }
+
+  /// Entries oldest first.
+  pub fn entries(&self) -> Vec<AuditEntry> {
+    self.entries.iter().cloned().collect()
+  }
+
+  pub fn usage(&self) -> BufferUsage {
+    let bytes: usize = self.entries.iter().map(AuditEntry::size).sum();
+    BufferUsage::new(
+      "MPV command audit",
+      self.entries.len(),
+      self.entries.capacity(),
+      bytes as u64,
+    )
+  }
+}
+
+fn now_ms() -> u64 {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|d| d.as_millis() as u64)
+    .unwrap_or_default()
+}
+
+fn command_name(cmd: &MpvCommand) -> String {
+  cmd.name().unwrap_or("<unknown>").to_string()
+}
+
+fn property_name(cmd: &MpvCommand) -> Option<String> {
+  match cmd.name()? {
+    "set_property" | "get_property" | "observe_property" | "cycle" => cmd
+      .args()
+      .iter()
+      .find_map(|arg| arg.as_str())
+      .map(str::to_string),
+    _ => None,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn records_command_names_without_arguments() {
+    let mut audit = CommandAudit::default();
+
+    audit.record(
+      &MpvCommand::loadfile("https://server/Videos/1/stream?api_key=secret"),
+      "success",
+    );
+    audit.record(&MpvCommand::set_pause(true), "success");
+
+    let entries = audit.entries();
+    assert_eq!(entries[0].command, "loadfile");
+    assert_eq!(entries[0].property, None);
+    assert_eq!(entries[1].property.as_deref(), Some("pause"));
+    let json = serde_json::to_string(&entries).unwrap();
+    assert!(!json.contains("secret"));
+  }
+
+  #[test]
+  fn rejected_messages_keep_their_payload() {
+    let mut audit = CommandAudit::default();
+
+    audit.record_rejected_message(
+      "Playstate",
+      "invalid type: integer `42`",
+      r#"{"Command":42}"#.to_string(),
+    );
+
+    let entries = audit.entries();
+    assert_eq!(entries[0].command, "websocket:Playstate");
+    assert_eq!(entries[0].payload.as_deref(), Some(r#"{"Command":42}"#));
+  }
+
+  #[test]
+  fn keeps_only_the_most_recent_commands() {
+    let mut audit = CommandAudit::default();
+    let commands: Vec<MpvCommand> = (0..DEFAULT_AUDIT_RETENTION + 3)
+      .map(|_| MpvCommand::seek(1.0))
+      .collect();
+    for cmd in &commands {
+      audit.record(cmd, "success");
+    }
+
+    let entries = audit.entries();
+    assert_eq!(entries.len(), DEFAULT_AUDIT_RETENTION);
+    assert_eq!(entries[0].request_id, commands[3].request_id);
+  }
+}
@@ -0,0 +1,358 @@
+//! Optional JellyPilot-managed MPV build.
+//!
+//! For users without a system MPV: the build listed for this platform in the
+//! manifest published with JellyPilot releases is downloaded, checked against
+//! its SHA-256 and unpacked into the app data directory. The installed
+//! version is recorded next to it, so an update only downloads when the
+//! manifest lists a newer build.
+//!
+//! The release workflow signs the manifest with an Ed25519 key whose public
+//! half is compiled in (`JELLYPILOT_MPV_MANIFEST_KEY`), so the checksums it
+//! lists are trusted only when the signature checks out; the server hosting
+//! the files can't vouch for them on its own. Builds without the key can't
+//! install a bundled MPV.
+
+use std::io::Cursor;
+use std::path::{Component, Path, PathBuf};
+use std::time::Duration;
+
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use ring::signature::{UnparsedPublicKey, ED25519};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use specta::Type;
+use thiserror::Error;
+
+/// Known-good MPV builds, one per platform.
+const MANIFEST_URL: &str =
+  "https://github.com/hewel/jellypilot/releases/latest/download/mpv-builds.json";
+/// Base64 Ed25519 signature of the manifest's bytes.
+const SIGNATURE_URL: &str =
+  "https://github.com/hewel/jellypilot/releases/latest/download/mpv-builds.json.sig";
+/// Base64 of the raw 32-byte key that signs the manifest, set by release builds.
+const MANIFEST_PUBLIC_KEY: Option<&str> = option_env!("JELLYPILOT_MPV_MANIFEST_KEY");
+/// Generous, since builds are tens of megabytes.
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+const INSTALLED_FILE: &str = "installed.json";
+
+#[derive(Error, Debug)]
+pub enum BundledMpvError {
+  #[error("Network error: {0}")]
+  Network(#[from] reqwest::Error),
+  #[error("No MPV build is available for {0}")]
+  Unsupported(String),
+  #[error("This build of JellyPilot can't verify MPV downloads")]
+  NoPublicKey,
+  #[error("The MPV build list is not signed by JellyPilot")]
+  Signature,
+  #[error("Checksum mismatch: expected {expected}, got {actual}")]
+  Checksum { expected: String, actual: String },
+  #[error("Zip error: {0}")]
+  Zip(#[from] zip::result::ZipError),
+  #[error("The downloaded build has no {0}")]
+  MissingExecutable(String),
+  #[error("I/O error: {0}")]
+  Io(#[from] std::io::Error),
+  #[error("The app data directory is not available")]
+  NoDataDir,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BuildManifest {
+  builds: Vec<BundledBuild>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BundledBuild {
+  /// `<os>-<arch>` as in `std::env::consts`, e.g. `windows-x86_64`.
+  target: String,
+  version: String,
+  /// Zip archive of the build.
+  url: String,
+  /// SHA-256 of the archive as hex.
+  sha256: String,
+  /// Relative path of the MPV executable inside the archive.
+  executable: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct InstalledBuild {
+  version: String,
+  executable: String,
+}
+
+/// Bundled MPV state for the settings window.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct BundledMpvStatus {
+  /// Version of the installed build, or null when none is installed.
+  pub version: Option<String>,
+  /// Path of the installed MPV executable.
+  pub executable: Option<String>,
+}
+
+/// Directory holding the bundled MPV build.
+fn bundle_dir() -> Option<PathBuf> {
+  dirs::data_local_dir().map(|p| p.join("jellypilot").join("mpv-bundled"))
+}
+
+fn current_target() -> String {
+  format!("{}-{}", std::env::consts::OS, std::env::consts::ARCH)
+}
+
+/// Executable of the installed bundled MPV, if any.
+pub fn bundled_mpv_path() -> Option<PathBuf> {
+  installed_in(&bundle_dir()?).map(|(_, path)| path)
+}
+
+/// What is installed right now.
+pub fn bundled_mpv_status() -> BundledMpvStatus {
+  match bundle_dir().as_deref().and_then(installed_in) {
+    Some((installed, path)) => BundledMpvStatus {
+      version: Some(installed.version),
+      executable: Some(path.to_string_lossy().to_string()),
+    },
+    None => BundledMpvStatus {
+      version: None,
+      executable: None,
+    },
+  }
+}
+
+fn installed_in(dir: &Path) -> Option<(InstalledBuild, PathBuf)> {
+  let contents = std::fs::read(dir.join(INSTALLED_FILE)).ok()?;
+  let installed: InstalledBuild = serde_json::from_slice(&contents).ok()?;
+  let path = dir.join(&installed.executable);
+  path.is_file().then_some((installed, path))
+}
+
+/// Install the manifest's build for this platform, or update to it.
+///
+/// Does nothing when that version is already installed. Returns the path of
+/// the MPV executable.
+pub async fn install_bundled_mpv() -> Result<PathBuf, BundledMpvError> {
+  let dir = bundle_dir().ok_or(BundledMpvError::NoDataDir)?;
+  let client = reqwest::Client::builder()
+    .timeout(DOWNLOAD_TIMEOUT)
+    .build()?;
+
+  let public_key = MANIFEST_PUBLIC_KEY
+    .and_then(|key| STANDARD.decode(key.trim()).ok())
+    .ok_or(BundledMpvError::NoPublicKey)?;
+  let manifest = client
+    .get(MANIFEST_URL)
+    .send()
+    .await?
+    .error_for_status()?
+    .bytes()
+    .await?;
+  let signature = client
+    .get(SIGNATURE_URL)
+    .send()
+    .await?
+    .error_for_status()?
+    .text()
+    .await?;
+  verify_manifest(&manifest, &signature, &public_key)?;
+  let manifest: BuildManifest = serde_json::from_slice(&manifest).map_err(std::io::Error::other)?;
+  let target = current_target();
+  let build = manifest
+    .builds
+    .into_iter()
+    .find(|build| build.target == target)
+    .ok_or(BundledMpvError::Unsupported(target))?;
+
+  if let Some((installed, path)) = installed_in(&dir) {
+    if installed.version == build.version {
+      log::debug!("Bundled MPV {} is up to date", installed.version);
+      return Ok(path);
+    }
+  }
+
+  log::info!(
+    "Downloading bundled MPV {} from {}",
+    build.version,
+    build.url
+  );
+  let archive = client
+    .get(&build.url)
+    .send()
+    .await?
+    .error_for_status()?
+    .bytes()
+    .await?;
+  verify_checksum(&archive, &build.sha256)?;
+
+  tokio::task::spawn_blocking(move || unpack(&dir, &archive, &build))
+    .await
+    .map_err(std::io::Error::other)?
+}
+
+/// Check that `manifest` was signed with the key matching `public_key`.
+fn verify_manifest(
+  manifest: &[u8],
+  signature: &str,
+  public_key: &[u8],
+) -> Result<(), BundledMpvError> {
+  let signature = STANDARD
+    .decode(signature.trim())
+    .map_err(|_| BundledMpvError::Signature)?;
+  UnparsedPublicKey::new(&ED25519, public_key)
+    .verify(manifest, &signature)
+    .map_err(|_| BundledMpvError::Signature)
+}
+
+fn verify_checksum(data: &[u8], expected: &str) -> Result<(), BundledMpvError> {
+  let actual: String = Sha256::digest(data)
+    .iter()
+    .map(|byte| format!("{byte:02x}"))
+    .collect();
+  if actual.eq_ignore_ascii_case(expected.trim()) {
+    Ok(())
+  } else {
+    Err(BundledMpvError::Checksum {
+      expected: expected.to_string(),
+      actual,
+    })
+  }
+}
+
+/// Unpack into a staging directory and swap it in, so a failed update keeps
+/// the previous build usable.
+fn unpack(dir: &Path, archive: &[u8], build: &BundledBuild) -> Result<PathBuf, BundledMpvError> {
+  let executable = Path::new(&build.executable);
+  if !executable
+    .components()
+    .all(|component| matches!(component, Component::Normal(_)))
+  {
+    return Err(BundledMpvError::MissingExecutable(build.executable.clone()));
+  }
+
+  let staging = dir.with_extension("partial");
+  if staging.exists() {
+    std::fs::remove_dir_all(&staging)?;
+  }
+  std::fs::create_dir_all(&staging)?;
+
+  // Unix permissions stored in the archive are restored, keeping MPV executable.
+  zip::ZipArchive::new(Cursor::new(archive))?.extract(&staging)?;
+  if !staging.join(executable).is_file() {
+    let _ = std::fs::remove_dir_all(&staging);
+    return Err(BundledMpvError::MissingExecutable(build.executable.clone()));
+  }
+
+  let installed = InstalledBuild {
+    version: build.version.clone(),
+    executable: build.executable.clone(),
+  };
+  std::fs::write(
+    staging.join(INSTALLED_FILE),
+    serde_json::to_vec_pretty(&installed).map_err(std::io::Error::other)?,
+  )?;
+
+  if dir.exists() {
+    std::fs::remove_dir_all(dir)?;
+  }
+  std::fs::rename(&staging, dir)?;
+  log::info!("Bundled MPV {} installed to {:?}", build.version, dir);
+  Ok(dir.join(executable))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::io::Write;
+  use zip::write::SimpleFileOptions;
+
+  fn test_build(executable: &str) -> BundledBuild {
+    BundledBuild {
+      target: current_target(),
+      version: "0.39.0".to_string(),
+      url: "https://example.invalid/mpv.zip".to_string(),
+      sha256: String::new(),
+      executable: executable.to_string(),
+    }
+  }
+
+  fn test_archive() -> Vec<u8> {
+    let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    zip
+      .start_file("mpv/mpv.exe", SimpleFileOptions::default())
+      .unwrap();
+    zip.write_all(b"not really mpv").unwrap();
+    zip.finish().unwrap().into_inner()
+  }
+
+  #[test]
+  fn manifest_must_carry_a_valid_signature() {
+    use ring::signature::{Ed25519KeyPair, KeyPair};
+
+    let pkcs8 = Ed25519KeyPair::generate_pkcs8(&ring::rand::SystemRandom::new()).unwrap();
+    let key = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+    let manifest = br#"{"builds":[]}"#;
+    let signature = STANDARD.encode(key.sign(manifest));
+    let public_key = key.public_key().as_ref();
+
+    assert!(verify_manifest(manifest, &signature, public_key).is_ok());
+    assert!(matches!(
+      verify_manifest(br#"{"builds":[{}]}"#, &signature, public_key),
+      Err(BundledMpvError::Signature)
+    ));
+    assert!(matches!(
+      verify_manifest(manifest, "not base64", public_key),
+      Err(BundledMpvError::Signature)
+    ));
+    let other = Ed25519KeyPair::generate_pkcs8(&ring::rand::SystemRandom::new()).unwrap();
+    let other = Ed25519KeyPair::from_pkcs8(other.as_ref()).unwrap();
+    assert!(verify_manifest(manifest, &signature, other.public_key().as_ref()).is_err());
+  }
+
+  #[test]
+  fn checksum_must_match() {
+    let data = b"mpv build";
+    assert!(matches!(
+      verify_checksum(data, &"0".repeat(64)),
+      Err(BundledMpvError::Checksum { .. })
+    ));
+
+    let actual: String = Sha256::digest(data)
+      .iter()
+      .map(|byte| format!("{byte:02x}"))
+      .collect();
+    assert!(verify_checksum(data, &actual.to_uppercase()).is_ok());
+  }
+
+  #[test]
+  fn unpack_installs_and_records_the_version() {
+    let root =
+      std::env::temp_dir().join(format!("jellypilot-bundled-test-{}", uuid::Uuid::new_v4()));
+    let dir = root.join("mpv-bundled");
+
+    let path = unpack(&dir, &test_archive(), &test_build("mpv/mpv.exe")).unwrap();
+    assert_eq!(path, dir.join("mpv/mpv.exe"));
+    let (installed, installed_path) = installed_in(&dir).unwrap();
+    assert_eq!(installed.version, "0.39.0");
+    assert_eq!(installed_path, path);
+    assert!(!dir.with_extension("partial").exists());
+
+    std::fs::remove_dir_all(&root).unwrap();
+  }
+
+  #[test]
+  fn unpack_keeps_the_old_build_when_the_executable_is_missing() {
+    let root =
+      std::env::temp_dir().join(format!("jellypilot-bundled-test-{}", uuid::Uuid::new_v4()));
+    let dir = root.join("mpv-bundled");
+    unpack(&dir, &test_archive(), &test_build("mpv/mpv.exe")).unwrap();
+
+    let result = unpack(&dir, &test_archive(), &test_build("bin/mpv"));
+    assert!(matches!(result, Err(BundledMpvError::MissingExecutable(_))));
+    assert!(unpack(&dir, &test_archive(), &test_build("../mpv.exe")).is_err());
+    assert!(installed_in(&dir).is_some());
+
+    std::fs::remove_dir_all(&root).unwrap();
+  }
+}
@@ -1,17 +1,40 @@
 //! High-level MPV client with command methods.
 
-use std::path::PathBuf;
-use std::process::Child;
+use std::path::{Path, PathBuf};
+use std::process::{Child, ExitStatus};
 use std::sync::Arc;
 use std::time::Duration;
 
-use async_channel::Receiver;
 use parking_lot::Mutex;
+use serde::de::DeserializeOwned;
 use thiserror::Error;
 
-use super::ipc::{IpcError, MpvIpc};
-use super::process::{cleanup_ipc, ipc_path, spawn_mpv, ProcessError};
-use super::protocol::{MpvCommand, MpvEvent, MpvResponse, PropertyValue};
+use super::args::{classify_extra_arg, diff_extra_args, LiveArg};
+use super::audit::{AuditEntry, CommandAudit};
+use super::display_server::{display_server_args, DisplayServerMode};
+use super::event_queue::EventQueue;
+use super::ipc::{IpcError, MpvIpc, PipeServer};
+use super::key_capture::{
+  captured_key, write_capture_script, CANCEL_MESSAGE, CAPTURED_KEY_PROPERTY, CAPTURE_MESSAGE,
+  SCRIPT_LOADED_PROPERTY,
+};
+use super::logs::log_usage;
+use super::managed_config::ManagedMpvConfig;
+use super::observers::ObserverRegistry;
+use super::orphans::{find_orphans, remove_endpoint};
+use super::process::{
+  cleanup_ipc, find_mpv, ipc_path, spawn_mpv, FullscreenOptions, InputBinding, ProcessError,
+};
+use super::protocol::{
+  header_field_options, quote_option_value, MpvCommand, MpvResponse, PropertyValue,
+};
+use super::timeouts::CommandTimeouts;
+use super::version::{detect_version, MpvFeatures, MpvVersion, MIN_IPC_MPV_VERSION};
+use crate::redact::redact;
+use crate::retention::BufferUsage;
+
+/// How long a newly spawned MPV may take to open its IPC socket.
+const IPC_CONNECT_TIMEOUT: Duration = Duration::from_secs(8);
 
 #[derive(Error, Debug)]
 pub enum MpvError {
@@ -23,14 +46,44 @@ pub enum MpvError {
   CommandFailed(String),
   #[error("Not connected")]
   NotConnected,
+  #[error("Unexpected value of MPV property {0}: {1}")]
+  UnexpectedValue(String, String),
+  #[error("MPV {0} is too old; JellyPilot needs MPV {min} or newer", min = MIN_IPC_MPV_VERSION)]
+  TooOld(MpvVersion),
 }
 
+/// How often a running key capture is checked for a pressed key.
+const KEY_CAPTURE_POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// How long the key capture script may take to load.
+const KEY_CAPTURE_LOAD_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How long an MPV left by an earlier JellyPilot gets to accept a connection.
+const ORPHAN_CONNECT_TIMEOUT: Duration = Duration::from_secs(1);
+
 /// High-level MPV client.
 pub struct MpvClient {
   mpv_path: Arc<Mutex<Option<PathBuf>>>,
   extra_args: Arc<Mutex<Vec<String>>>,
+  managed_config: Arc<Mutex<Option<ManagedMpvConfig>>>,
+  fullscreen_options: Arc<Mutex<FullscreenOptions>>,
+  display_server: Arc<Mutex<DisplayServerMode>>,
   process: Arc<Mutex<Option<Child>>>,
   ipc: Arc<Mutex<Option<Arc<MpvIpc>>>>,
+  audit: Arc<Mutex<CommandAudit>>,
+  version: Arc<Mutex<Option<MpvVersion>>>,
+  removed_args: Arc<Mutex<Vec<String>>>,
+  /// Started ahead of a cast and not used for playback yet.
+  warm: Arc<Mutex<bool>>,
+  command_timeouts: Arc<Mutex<CommandTimeouts>>,
+  observers: Arc<Mutex<ObserverRegistry>>,
+  /// Let MPV keep running when JellyPilot crashes.
+  outlive_app: Arc<Mutex<bool>>,
+  /// Take over an MPV an earlier JellyPilot left running instead of quitting it.
+  adopt_orphans: Arc<Mutex<bool>>,
+  /// Endpoint of the running MPV when it was adopted rather than spawned.
+  adopted: Arc<Mutex<Option<String>>>,
+  /// Held while orphans are handled, so two starts don't adopt twice.
+  orphan_check: Arc<tokio::sync::Mutex<()>>,
 }
 
 impl MpvClient {
@@ -39,8 +92,21 @@ impl MpvClient {
     Self {
       mpv_path: Arc::new(Mutex::new(mpv_path)),
       extra_args: Arc::new(Mutex::new(Vec::new())),
+      managed_config: Arc::new(Mutex::new(None)),
+      fullscreen_options: Arc::new(Mutex::new(FullscreenOptions::default())),
+      display_server: Arc::new(Mutex::new(DisplayServerMode::default())),
       process: Arc::new(Mutex::new(None)),
       ipc: Arc::new(Mutex::new(None)),
+      audit: Arc::new(Mutex::new(CommandAudit::default())),
+      version: Arc::new(Mutex::new(None)),
+      removed_args: Arc::new(Mutex::new(Vec::new())),
+      warm: Arc::new(Mutex::new(false)),
+      command_timeouts: Arc::new(Mutex::new(CommandTimeouts::default())),
+      observers: Arc::new(Mutex::new(ObserverRegistry::default())),
+      outlive_app: Arc::new(Mutex::new(false)),
+      adopt_orphans: Arc::new(Mutex::new(false)),
+      adopted: Arc::new(Mutex::new(None)),
+      orphan_check: Arc::new(tokio::sync::Mutex::new(())),
     }
   }
 
@@ -54,40 +120,371 @@ impl MpvClient {
     *self.extra_args.lock() = args;
   }
 
-  /// Start MPV and connect to IPC.
+  /// Use a JellyPilot-managed mpv.conf instead of the user's config
+  /// (takes effect on next start). `None` leaves the user's config untouched.
+  pub fn set_managed_config(&self, config: Option<ManagedMpvConfig>) {
+    *self.managed_config.lock() = config;
+  }
+
+  /// Update fullscreen spawn options (takes effect on next start).
+  pub fn set_fullscreen_options(&self, options: FullscreenOptions) {
+    *self.fullscreen_options.lock() = options;
+  }
+
+  /// Set how long MPV gets to answer each IPC command (takes effect
+  /// immediately).
+  pub fn set_command_timeouts(&self, timeouts: CommandTimeouts) {
+    *self.command_timeouts.lock() = timeouts;
+  }
+
+  /// IPC response timeouts in use.
+  pub fn command_timeouts(&self) -> CommandTimeouts {
+    self.command_timeouts.lock().clone()
+  }
+
+  /// Let MPVs spawned from now on keep running when JellyPilot crashes.
+  pub fn set_outlive_app(&self, outlive: bool) {
+    *self.outlive_app.lock() = outlive;
+  }
+
+  /// Adopt rather than quit an MPV an earlier JellyPilot left running.
+  pub fn set_adopt_orphans(&self, adopt: bool) {
+    *self.adopt_orphans.lock() = adopt;
+  }
+
+  /// Set which display server options are used on next spawn.
+  pub fn set_display_server(&self, mode: DisplayServerMode) {
+    *self.display_server.lock() = mode;
+  }
+
+  /// Run `mpv --version` on the executable that would be spawned and keep
+  /// the result for feature gating. Returns None when it cannot be parsed.
+  pub async fn refresh_version(&self) -> Option<MpvVersion> {
+    let mpv_path = self.mpv_path.lock().clone().or_else(find_mpv);
+    let version = match mpv_path {
+      Some(path) => tokio::task::spawn_blocking(move || detect_version(&path))
+        .await
+        .ok()
+        .and_then(|detected| match detected {
+          Ok((version, first_line)) => {
+            log::info!("MPV version: {}", first_line);
+            version
+          }
+          Err(e) => {
+            log::warn!("Failed to run mpv --version: {}", e);
+            None
+          }
+        }),
+      None => None,
+    };
+    *self.version.lock() = version;
+    version
+  }
+
+  /// Version found by the last [`Self::refresh_version`].
+  pub fn version(&self) -> Option<MpvVersion> {
+    *self.version.lock()
+  }
+
+  /// Extra args the last start left out, each reported once.
+  pub fn take_removed_args(&self) -> Vec<String> {
+    std::mem::take(&mut *self.removed_args.lock())
+  }
+
+  fn features(&self) -> MpvFeatures {
+    MpvFeatures::for_version(self.version())
+  }
+
+  /// Best-effort apply of the change from extra args `previous` to `args`
+  /// to the running MPV; args that didn't change are left alone.
+  ///
+  /// Returns the args that could not be applied or were removed, which need
+  /// an MPV restart.
+  pub async fn apply_extra_args_live(&self, previous: &[String], args: &[String]) -> Vec<String> {
+    let (changed, removed) = diff_extra_args(previous, args);
+    let mut restart_required = Vec::new();
+    for arg in &changed {
+      let result = match classify_extra_arg(arg) {
+        LiveArg::Property { name, value } => self.set_property_string(&name, &value).await,
+        LiveArg::Profile(name) => self
+          .send(MpvCommand::apply_profile(&name))
+          .await
+          .map(|_| ()),
+        LiveArg::RestartRequired => {
+          restart_required.push(arg.clone());
+          continue;
+        }
+      };
+      match result {
+        Ok(()) => log::info!("Applied MPV arg live: {}", arg),
+        Err(e) => {
+          log::info!("MPV arg {} needs a restart: {}", arg, e);
+          restart_required.push(arg.clone());
+        }
+      }
+    }
+    restart_required.extend(removed);
+    restart_required
+  }
+
+  /// Bind `bindings` in the running MPV, so changed shortcuts work without
+  /// restarting it.
+  ///
+  /// Returns the keys that could not be bound and need an MPV restart.
+  pub async fn apply_input_bindings_live(&self, bindings: &[InputBinding]) -> Vec<String> {
+    if !self.features().keybind {
+      return bindings.iter().map(|binding| binding.key.clone()).collect();
+    }
+    let mut restart_required = Vec::new();
+    for binding in bindings {
+      match self
+        .send(MpvCommand::keybind(&binding.key, &binding.command))
+        .await
+      {
+        Ok(_) => log::info!("Bound {} in MPV: {}", binding.key, binding.command),
+        Err(e) => {
+          log::info!("Key {} needs a restart: {}", binding.key, e);
+          restart_required.push(binding.key.clone());
+        }
+      }
+    }
+    restart_required
+  }
+
+  /// Record the next key pressed in MPV, in input.conf syntax. None when
+  /// Escape was pressed or no key was pressed within `timeout`.
+  pub async fn capture_key(&self, timeout: Duration) -> Result<Option<String>, MpvError> {
+    self.load_key_capture_script().await?;
+    self.set_property_string(CAPTURED_KEY_PROPERTY, "").await?;
+    // From here on MPV's keys may be swallowed, so end the capture however
+    // this returns.
+    let _capture = KeyCapture(self.clone());
+    self
+      .send(MpvCommand::script_message(&[CAPTURE_MESSAGE]))
+      .await?;
+
+    let deadline = tokio::time::Instant::now() + timeout;
+    while tokio::time::Instant::now() < deadline {
+      tokio::time::sleep(KEY_CAPTURE_POLL_INTERVAL).await;
+      let value = self
+        .get_property_as::<Option<String>>(CAPTURED_KEY_PROPERTY)
+        .await?
+        .unwrap_or_default();
+      if let Some(key) = captured_key(&value) {
+        return Ok(key);
+      }
+    }
+    log::info!("No key pressed in MPV within {:?}", timeout);
+    Ok(None)
+  }
+
+  /// Whether the key capture script is loaded into this MPV.
+  async fn key_capture_loaded(&self) -> bool {
+    self
+      .get_property_as::<Option<bool>>(SCRIPT_LOADED_PROPERTY)
+      .await
+      .ok()
+      .flatten()
+      .unwrap_or(false)
+  }
+
+  /// Load the key capture script, unless this MPV already has it.
+  async fn load_key_capture_script(&self) -> Result<(), MpvError> {
+    if self.key_capture_loaded().await {
+      return Ok(());
+    }
+    let script = tokio::task::spawn_blocking(write_capture_script)
+      .await
+      .ok()
+      .flatten()
+      .ok_or_else(|| MpvError::CommandFailed("key capture script unavailable".to_string()))?;
+    self
+      .send(MpvCommand::load_script(&script.to_string_lossy()))
+      .await?;
+
+    let deadline = tokio::time::Instant::now() + KEY_CAPTURE_LOAD_TIMEOUT;
+    while !self.key_capture_loaded().await {
+      if tokio::time::Instant::now() >= deadline {
+        return Err(MpvError::CommandFailed(
+          "key capture script did not load".to_string(),
+        ));
+      }
+      tokio::time::sleep(KEY_CAPTURE_POLL_INTERVAL).await;
+    }
+    Ok(())
+  }
+
+  /// Quit the MPVs earlier JellyPilots left running, or adopt one of them as
+  /// the running MPV when set to. Returns true when one was adopted.
+  pub async fn handle_orphans(&self) -> bool {
+    let _check = self.orphan_check.lock().await;
+    let mut adopt = *self.adopt_orphans.lock() && !self.is_connected();
+    let mut adopted = false;
+    let current = self.adopted.lock().clone();
+    for orphan in find_orphans() {
+      if current.as_ref() == Some(&orphan.path) {
+        continue;
+      }
+      // Check the pipe's server before adopting it or telling it to quit.
+      let ipc = match MpvIpc::connect(
+        &orphan.path,
+        Some(PipeServer::OwnMpv),
+        ORPHAN_CONNECT_TIMEOUT,
+        || true,
+      )
+      .await
+      {
+        Ok(ipc) => ipc,
+        Err(e @ IpcError::ForeignServer { .. }) => {
+          log::warn!("Ignoring MPV endpoint {}: {}", orphan.path, e);
+          continue;
+        }
+        Err(e) => {
+          log::info!("Removing stale MPV endpoint {}: {}", orphan.path, e);
+          remove_endpoint(&orphan.path);
+          continue;
+        }
+      };
+      if adopt {
+        log::info!(
+          "Adopting the MPV left running by JellyPilot (pid {})",
+          orphan.owner_pid
+        );
+        self.observers.lock().reset();
+        *self.ipc.lock() = Some(Arc::new(ipc));
+        *self.adopted.lock() = Some(orphan.path);
+        adopt = false;
+        adopted = true;
+        continue;
+      }
+      log::info!(
+        "Quitting the MPV left running by JellyPilot (pid {})",
+        orphan.owner_pid
+      );
+      let quit = MpvCommand::quit();
+      let timeout = self.command_timeouts.lock().for_command(&quit);
+      if let Err(e) = ipc.send_command(quit, timeout).await {
+        log::warn!("Failed to quit the MPV at {}: {}", orphan.path, e);
+      }
+      ipc.close();
+      remove_endpoint(&orphan.path);
+    }
+    adopted
+  }
+
+  /// True while the running MPV was adopted from an earlier JellyPilot.
+  pub fn is_adopted(&self) -> bool {
+    self.adopted.lock().is_some()
+  }
+
+  /// Start MPV and connect to IPC, or adopt one an earlier JellyPilot left
+  /// running when set to.
   pub async fn start(&self) -> Result<(), MpvError> {
+    if let Some(version) = self.version().filter(|v| *v < MIN_IPC_MPV_VERSION) {
+      return Err(MpvError::TooOld(version));
+    }
+
+    if self.handle_orphans().await || (self.is_adopted() && self.is_connected()) {
+      return Ok(());
+    }
+
     // Cleanup any existing socket
     cleanup_ipc();
+    *self.adopted.lock() = None;
+    *self.warm.lock() = false;
+    self.observers.lock().reset();
 
     // Get current config
     let mpv_path = self.mpv_path.lock().clone();
     let extra_args = self.extra_args.lock().clone();
+    let managed_config = self.managed_config.lock().clone();
+    let fullscreen_options = self.fullscreen_options.lock().clone();
+    let display_server = self.display_server.lock().resolve();
+    let display_server_args =
+      display_server_args(display_server, self.features(), &fullscreen_options);
 
     // Spawn MPV process
-    let child = spawn_mpv(mpv_path.as_ref(), &extra_args)?;
+    let spawned = spawn_mpv(
+      mpv_path.as_ref(),
+      &extra_args,
+      managed_config.as_ref(),
+      &fullscreen_options,
+      &display_server_args,
+      *self.outlive_app.lock(),
+    )?;
+    let mpv_pid = spawned.child.id();
     {
       let mut process = self.process.lock();
-      *process = Some(child);
+      *process = Some(spawned.child);
     }
+    *self.removed_args.lock() = spawned.removed_args;
 
-    // Wait a bit for MPV to create the socket
-    tokio::time::sleep(Duration::from_millis(500)).await;
-
-    // Connect to IPC with retries
-    let ipc_conn = MpvIpc::connect(&ipc_path(), 10).await?;
+    // Connect as soon as MPV has created the socket
+    let spawned_at = std::time::Instant::now();
+    let ipc_conn = MpvIpc::connect(
+      &ipc_path(),
+      Some(PipeServer::Spawned(mpv_pid)),
+      IPC_CONNECT_TIMEOUT,
+      || self.exit_status().is_none(),
+    )
+    .await?;
     {
       let mut ipc = self.ipc.lock();
       *ipc = Some(Arc::new(ipc_conn));
     }
 
-    log::info!("MPV client connected");
+    let connect_time = spawned_at.elapsed();
+    log::info!("MPV client connected after {:?}", connect_time);
+    crate::metrics::record_mpv_start(connect_time);
     Ok(())
   }
 
+  /// Start an idle MPV ahead of the first cast, without a window or with
+  /// its window minimized.
+  pub async fn start_warm(&self, minimized: bool) -> Result<(), MpvError> {
+    self.start().await?;
+    // An adopted MPV may be playing; its window stays as it is.
+    if self.is_adopted() {
+      return Ok(());
+    }
+    if minimized {
+      self.set_property_string("window-minimized", "yes").await?;
+    } else {
+      self.set_force_window(false).await?;
+    }
+    *self.warm.lock() = true;
+    Ok(())
+  }
+
+  /// True while the running MPV was started ahead of a cast and has not
+  /// played anything yet.
+  pub fn is_warm(&self) -> bool {
+    *self.warm.lock()
+  }
+
+  /// Mark the running MPV as used for playback, returning whether it was
+  /// started ahead of the cast.
+  pub fn take_warm(&self) -> bool {
+    std::mem::take(&mut *self.warm.lock())
+  }
+
+  /// How the MPV process ended, once it has. None while it runs or after
+  /// [`Self::stop`].
+  pub fn exit_status(&self) -> Option<ExitStatus> {
+    let mut process = self.process.lock();
+    process.as_mut()?.try_wait().ok().flatten()
+  }
+
   /// Stop MPV and disconnect.
   /// This is async to avoid blocking on process kill/wait.
   pub async fn stop(&self) {
     log::info!("stop() called - closing IPC connection");
+    // Without a process handle, an adopted MPV has to be asked to quit.
+    let adopted = self.adopted.lock().take();
+    if let Some(path) = &adopted {
+      log::info!("Quitting adopted MPV at {}", path);
+      let _ = self.send(MpvCommand::quit()).await;
+    }
     // Close IPC first
     {
       let mut ipc = self.ipc.lock();
@@ -136,13 +533,19 @@ impl MpvClient {
       log::warn!("No MPV process handle to kill");
     }
 
+    *self.warm.lock() = false;
+    self.observers.lock().reset();
     cleanup_ipc();
+    if let Some(path) = adopted {
+      remove_endpoint(&path);
+    }
     log::info!("MPV client stopped");
   }
 
-  /// Check if connected.
+  /// Check if connected. A connection MPV closed, e.g. because its window
+  /// was closed, does not count.
   pub fn is_connected(&self) -> bool {
-    let connected = self.ipc.lock().is_some();
+    let connected = self.ipc.lock().as_ref().is_some_and(|ipc| !ipc.is_closed());
     let has_process = self.process.lock().is_some();
     log::debug!(
       "is_connected check: ipc={}, process={}",
@@ -161,7 +564,16 @@ impl MpvClient {
   /// Send a command to MPV.
   async fn send(&self, cmd: MpvCommand) -> Result<MpvResponse, MpvError> {
     let ipc = self.get_ipc()?;
-    let response = ipc.send_command(cmd).await?;
+    let audited = cmd.clone();
+    let timeout = self.command_timeouts.lock().for_command(&cmd);
+    let response = match ipc.send_command(cmd, timeout).await {
+      Ok(response) => response,
+      Err(e) => {
+        self.audit.lock().record(&audited, e.to_string());
+        return Err(e.into());
+      }
+    };
+    self.audit.lock().record(&audited, response.error.clone());
 
     if !response.is_success() {
       return Err(MpvError::CommandFailed(response.error));
@@ -170,21 +582,52 @@ impl MpvClient {
     Ok(response)
   }
 
+  /// Recent IPC commands (names only) and their outcomes, oldest first.
+  pub fn command_audit(&self) -> Vec<AuditEntry> {
+    self.audit.lock().entries()
+  }
+
+  /// Keep at most `retention` commands in the command audit.
+  pub fn set_audit_retention(&self, retention: usize) {
+    self.audit.lock().set_retention(retention);
+  }
+
+  /// Sizes of the command audit, the event queue and the session logs.
+  pub fn memory_usage(&self) -> Vec<BufferUsage> {
+    let mut usage = vec![self.audit.lock().usage()];
+    if let Some(events) = self.events() {
+      usage.push(events.usage());
+    }
+    usage.push(log_usage());
+    usage
+  }
+
+  /// Add a remote-control message that failed to parse to the command audit.
+  pub fn audit_rejected_message(&self, message_type: &str, error: &str, payload: String) {
+    self
+      .audit
+      .lock()
+      .record_rejected_message(message_type, error, payload);
+  }
+
   /// Load a file for playback.
   pub async fn loadfile(&self, url: &str) -> Result<(), MpvError> {
-    log::info!("Loading file: {}", url);
+    log::info!("Loading file: {}", redact(url));
     self.send(MpvCommand::loadfile(url)).await?;
     Ok(())
   }
 
   /// Load a file for playback with options.
   /// Options like start position, audio/subtitle track are applied atomically with the file load.
+  /// `http_headers` go with that file's requests only, after the user's own.
   pub async fn loadfile_with_options(
     &self,
     url: &str,
     start: Option<f64>,
     audio_index: Option<i64>,
     subtitle_index: Option<i64>,
+    fonts_dir: Option<&std::path::Path>,
+    http_headers: &[String],
   ) -> Result<(), MpvError> {
     let mut options = Vec::new();
 
@@ -209,15 +652,33 @@ impl MpvClient {
       None => {}
     }
 
-    if options.is_empty() {
-      log::info!("Loading file: {}", url);
+    // Per-file, so the fonts folder of one item doesn't stick to the next
+    if let Some(dir) = fonts_dir {
+      options.push(format!(
+        "sub-fonts-dir={}",
+        quote_option_value(&dir.to_string_lossy())
+      ));
+    }
+
+    if options.is_empty() && http_headers.is_empty() {
+      log::info!("Loading file: {}", redact(url));
       self.send(MpvCommand::loadfile(url)).await?;
     } else {
+      // Header values are secrets; only their number is logged.
+      log::info!(
+        "Loading file: {} with options: {} and {} HTTP headers",
+        redact(url),
+        options.join(","),
+        http_headers.len()
+      );
+      options.extend(header_field_options(http_headers));
       let options_str = options.join(",");
-      log::info!("Loading file: {} with options: {}", url, options_str);
-      self
-        .send(MpvCommand::loadfile_with_options(url, &options_str))
-        .await?;
+      let command = if self.features().loadfile_index {
+        MpvCommand::loadfile_with_options(url, &options_str)
+      } else {
+        MpvCommand::loadfile_with_options_legacy(url, &options_str)
+      };
+      self.send(command).await?;
     }
 
     Ok(())
@@ -259,6 +720,14 @@ impl MpvClient {
     Ok(())
   }
 
+  /// Set the secondary subtitle track by ID.
+  pub async fn set_secondary_subtitle_track(&self, id: i64) -> Result<(), MpvError> {
+    self
+      .send(MpvCommand::set_secondary_subtitle_track(id))
+      .await?;
+    Ok(())
+  }
+
   /// Get a property value.
   pub async fn get_property(&self, name: &str) -> Result<PropertyValue, MpvError> {
     let response = self.send(MpvCommand::get_property(name)).await?;
@@ -270,39 +739,102 @@ impl MpvClient {
     )
   }
 
+  /// Get a property decoded as `T`, e.g. `Option<f64>` or a
+  /// [`ChapterList`](super::ChapterList). A property without a value
+  /// decodes as `None` when `T` is an `Option`.
+  pub async fn get_property_as<T: DeserializeOwned>(&self, name: &str) -> Result<T, MpvError> {
+    let response = self.send(MpvCommand::get_property(name)).await?;
+    response
+      .data_as()
+      .map_err(|e| MpvError::UnexpectedValue(name.to_string(), e.to_string()))
+  }
+
   /// Get current time position in seconds.
   #[allow(dead_code)]
   pub async fn get_time_pos(&self) -> Result<f64, MpvError> {
-    match self.get_property("time-pos").await? {
-      PropertyValue::Number(n) => Ok(n),
-      _ => Ok(0.0),
-    }
+    Ok(
+      self
+        .get_property_as::<Option<f64>>("time-pos")
+        .await?
+        .unwrap_or(0.0),
+    )
   }
 
   /// Get current pause state.
   pub async fn get_pause(&self) -> Result<bool, MpvError> {
-    match self.get_property("pause").await? {
-      PropertyValue::Bool(b) => Ok(b),
-      _ => Ok(true),
-    }
+    Ok(
+      self
+        .get_property_as::<Option<bool>>("pause")
+        .await?
+        .unwrap_or(true),
+    )
   }
 
   /// Get current volume (0-100).
   #[allow(dead_code)]
   pub async fn get_volume(&self) -> Result<f64, MpvError> {
-    match self.get_property("volume").await? {
-      PropertyValue::Number(n) => Ok(n),
-      _ => Ok(100.0),
-    }
+    Ok(
+      self
+        .get_property_as::<Option<f64>>("volume")
+        .await?
+        .unwrap_or(100.0),
+    )
   }
 
   /// Get current mute state.
   #[allow(dead_code)]
   pub async fn get_mute(&self) -> Result<bool, MpvError> {
-    match self.get_property("mute").await? {
-      PropertyValue::Bool(b) => Ok(b),
-      _ => Ok(false),
+    Ok(
+      self
+        .get_property_as::<Option<bool>>("mute")
+        .await?
+        .unwrap_or(false),
+    )
+  }
+
+  /// Get current playback speed.
+  pub async fn get_speed(&self) -> Result<f64, MpvError> {
+    Ok(
+      self
+        .get_property_as::<Option<f64>>("speed")
+        .await?
+        .unwrap_or(1.0),
+    )
+  }
+
+  /// Set playback speed (1.0 = normal).
+  pub async fn set_speed(&self, speed: f64) -> Result<(), MpvError> {
+    self.send(MpvCommand::set_speed(speed)).await?;
+    Ok(())
+  }
+
+  /// Set the A-B loop points in seconds; `None` clears a point.
+  pub async fn set_ab_loop(&self, start: Option<f64>, end: Option<f64>) -> Result<(), MpvError> {
+    for (property, seconds) in [("ab-loop-a", start), ("ab-loop-b", end)] {
+      self
+        .send(MpvCommand::set_ab_loop_point(property, seconds))
+        .await?;
     }
+    Ok(())
+  }
+
+  /// Save a screenshot of the current frame to `path`.
+  pub async fn screenshot_to_file(&self, path: &Path, flags: &str) -> Result<(), MpvError> {
+    self
+      .send(MpvCommand::screenshot_to_file(
+        &path.to_string_lossy(),
+        flags,
+      ))
+      .await?;
+    Ok(())
+  }
+
+  /// Toggle the stats overlay of MPV's built-in stats script.
+  pub async fn toggle_stats(&self) -> Result<(), MpvError> {
+    self
+      .send(MpvCommand::script_binding("stats/display-stats-toggle"))
+      .await?;
+    Ok(())
   }
 
   /// Toggle mute state.
@@ -317,6 +849,12 @@ impl MpvClient {
     Ok(())
   }
 
+  /// Enter or leave fullscreen.
+  pub async fn set_fullscreen(&self, fullscreen: bool) -> Result<(), MpvError> {
+    self.send(MpvCommand::set_fullscreen(fullscreen)).await?;
+    Ok(())
+  }
+
   /// Set a string property (e.g., force-media-title).
   pub async fn set_property_string(&self, name: &str, value: &str) -> Result<(), MpvError> {
     self
@@ -333,14 +871,44 @@ impl MpvClient {
 
   /// Add an external subtitle file and optionally select it.
   ///
-  /// When `select` is true, the subtitle is immediately selected after loading.
+  /// When `select` is true, the subtitle is immediately selected after loading;
+  /// otherwise the current selection is kept.
   pub async fn sub_add(&self, url: &str, select: bool) -> Result<(), MpvError> {
-    log::info!("Adding external subtitle (select={}): {}", select, url);
-    let flags = if select { Some("select") } else { None };
-    self.send(MpvCommand::sub_add(url, flags)).await?;
+    log::info!(
+      "Adding external subtitle (select={}): {}",
+      select,
+      redact(url)
+    );
+    // Without a flag MPV selects the new track.
+    let flags = if select { "select" } else { "auto" };
+    self.send(MpvCommand::sub_add(url, Some(flags))).await?;
     Ok(())
   }
 
+  /// Stop playback but keep MPV and its IPC connection running, idle.
+  ///
+  /// `idle` is forced on first so extra args like `--idle=no` can't make
+  /// MPV exit. `keep_window` leaves the idle window open.
+  pub async fn stop_playback(&self, keep_window: bool) -> Result<(), MpvError> {
+    self.set_property_string("idle", "yes").await?;
+    self.set_force_window(keep_window).await?;
+    self.send(MpvCommand::stop()).await?;
+    Ok(())
+  }
+
+  /// Bring back a window hidden or minimized while MPV was idle.
+  pub async fn show_window(&self) -> Result<(), MpvError> {
+    self.set_force_window(true).await?;
+    self.set_property_string("window-minimized", "no").await
+  }
+
+  /// Keep a window open even without video (`--force-window`).
+  pub async fn set_force_window(&self, force: bool) -> Result<(), MpvError> {
+    self
+      .set_property_string("force-window", if force { "yes" } else { "no" })
+      .await
+  }
+
   /// Quit MPV gracefully.
   pub async fn quit(&self) -> Result<(), MpvError> {
     let _ = self.send(MpvCommand::quit()).await;
@@ -348,30 +916,150 @@ impl MpvClient {
     Ok(())
   }
 
-  /// Observe a property for changes.
-  /// Returns events via the events() receiver with event="property-change".
-  pub async fn observe_property(&self, observer_id: i64, property: &str) -> Result<(), MpvError> {
-    self
+  /// Observe a property for changes, unless it already is on this
+  /// connection. Returns events via the events() receiver with
+  /// event="property-change".
+  pub async fn observe_property(&self, property: &str) -> Result<(), MpvError> {
+    let Some(observer_id) = self.observers.lock().acquire(property) else {
+      return Ok(());
+    };
+    if let Err(e) = self
       .send(MpvCommand::observe_property(observer_id, property))
+      .await
+    {
+      self.observers.lock().forget(property);
+      return Err(e);
+    }
+    Ok(())
+  }
+
+  /// Give up one observation of a property; MPV stops observing it once
+  /// no one else does.
+  pub async fn unobserve_property(&self, property: &str) -> Result<(), MpvError> {
+    let Some(observer_id) = self.observers.lock().release(property) else {
+      return Ok(());
+    };
+    self
+      .send(MpvCommand::unobserve_property(observer_id))
       .await?;
     Ok(())
   }
 
   /// Get event receiver for property changes and other events.
-  pub fn events(&self) -> Option<Receiver<MpvEvent>> {
+  pub fn events(&self) -> Option<Arc<EventQueue>> {
     let guard = self.ipc.lock();
     guard.as_ref().map(|ipc| ipc.events())
   }
 }
 
+/// A key capture in progress; dropping it removes the capture's exclusive
+/// binding, which is harmless after the script already removed it itself.
+struct KeyCapture(MpvClient);
+
+impl Drop for KeyCapture {
+  fn drop(&mut self) {
+    let client = self.0.clone();
+    tauri::async_runtime::spawn(async move {
+      if let Err(e) = client
+        .send(MpvCommand::script_message(&[CANCEL_MESSAGE]))
+        .await
+      {
+        log::warn!("Failed to end the key capture in MPV: {}", e);
+      }
+    });
+  }
+}
+
 // Need to implement Clone manually because Child doesn't implement Clone
 impl Clone for MpvClient {
   fn clone(&self) -> Self {
     Self {
       mpv_path: self.mpv_path.clone(),
       extra_args: self.extra_args.clone(),
+      managed_config: self.managed_config.clone(),
+      fullscreen_options: self.fullscreen_options.clone(),
+      display_server: self.display_server.clone(),
       process: self.process.clone(),
       ipc: self.ipc.clone(),
+      audit: self.audit.clone(),
+      version: self.version.clone(),
+      removed_args: self.removed_args.clone(),
+      warm: self.warm.clone(),
+      command_timeouts: self.command_timeouts.clone(),
+      observers: self.observers.clone(),
+      outlive_app: self.outlive_app.clone(),
+      adopt_orphans: self.adopt_orphans.clone(),
+      adopted: self.adopted.clone(),
+      orphan_check: self.orphan_check.clone(),
+    }
+  }
+}
+
+#[cfg(all(test, not(windows)))]
+mod tests {
+  use super::*;
+  use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+  /// An MPV on a Unix socket that has the key capture script loaded, fails
+  /// reading the captured key, and records the commands it gets.
+  async fn fake_mpv() -> (MpvClient, Arc<Mutex<Vec<serde_json::Value>>>) {
+    use std::os::unix::fs::DirBuilderExt;
+
+    let dir = std::env::temp_dir().join(format!("jellypilot-client-{}", uuid::Uuid::new_v4()));
+    std::fs::DirBuilder::new().mode(0o700).create(&dir).unwrap();
+    let path = dir.join("mpv.sock");
+    let listener = tokio::net::UnixListener::bind(&path).unwrap();
+    let commands = Arc::new(Mutex::new(Vec::new()));
+    let received = commands.clone();
+    tokio::spawn(async move {
+      let (stream, _) = listener.accept().await.unwrap();
+      let (read, mut write) = stream.into_split();
+      let mut lines = BufReader::new(read).lines();
+      while let Ok(Some(line)) = lines.next_line().await {
+        let request: serde_json::Value = serde_json::from_str(&line).unwrap();
+        let command = request["command"].clone();
+        let mut response = match (command[0].as_str(), command[1].as_str()) {
+          (Some("get_property"), Some(SCRIPT_LOADED_PROPERTY)) => {
+            serde_json::json!({ "error": "success", "data": true })
+          }
+          (Some("get_property"), _) => serde_json::json!({ "error": "property unavailable" }),
+          _ => serde_json::json!({ "error": "success" }),
+        };
+        received.lock().push(command);
+        response["request_id"] = request["request_id"].clone();
+        let line = format!("{}\n", response);
+        if write.write_all(line.as_bytes()).await.is_err() {
+          break;
+        }
+      }
+      let _ = std::fs::remove_dir_all(&dir);
+    });
+
+    let ipc = MpvIpc::connect(path.to_str().unwrap(), None, Duration::from_secs(5), || {
+      true
+    })
+    .await
+    .unwrap();
+    let client = MpvClient::new(None);
+    *client.ipc.lock() = Some(Arc::new(ipc));
+    (client, commands)
+  }
+
+  #[tokio::test]
+  async fn a_failed_key_capture_still_ends_the_capture() {
+    let (client, commands) = fake_mpv().await;
+
+    let result = client.capture_key(Duration::from_secs(5)).await;
+
+    assert!(result.is_err());
+    let cancel = serde_json::json!(["script-message", CANCEL_MESSAGE]);
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(5);
+    while !commands.lock().contains(&cancel) {
+      assert!(
+        tokio::time::Instant::now() < deadline,
+        "capture was not cancelled"
+      );
+      tokio::time::sleep(Duration::from_millis(10)).await;
     }
   }
 }
@@ -0,0 +1,170 @@
+//! Wayland/X11-specific MPV window options on Linux.
+//!
+//! MPV is spawned with a fixed app id (Wayland) or window class (X11) so
+//! window managers can match rules against the MPV window, and with the GPU
+//! context of the running session so it does not end up on XWayland. The
+//! session type is detected from the environment unless the user picks one.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use super::process::FullscreenOptions;
+use super::version::MpvFeatures;
+
+/// App id / window class of the MPV window spawned by JellyPilot.
+pub const MPV_WINDOW_CLASS: &str = "jellypilot";
+
+/// Which display server options MPV is spawned with.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum DisplayServerMode {
+  /// Detect the session type; no options outside Linux.
+  #[default]
+  Auto,
+  Wayland,
+  X11,
+  /// Leave window options to MPV and the user's mpv.conf.
+  Off,
+}
+
+/// Display server of a Linux desktop session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisplayServer {
+  Wayland,
+  X11,
+}
+
+impl DisplayServerMode {
+  /// The display server to target, or None for no extra options.
+  pub fn resolve(self) -> Option<DisplayServer> {
+    match self {
+      Self::Auto => detect_display_server(),
+      Self::Wayland => Some(DisplayServer::Wayland),
+      Self::X11 => Some(DisplayServer::X11),
+      Self::Off => None,
+    }
+  }
+}
+
+/// Detect the session type from the environment.
+#[cfg(target_os = "linux")]
+pub fn detect_display_server() -> Option<DisplayServer> {
+  let var = |name: &str| std::env::var(name).ok().filter(|value| !value.is_empty());
+  display_server_from_env(
+    var("XDG_SESSION_TYPE").as_deref(),
+    var("WAYLAND_DISPLAY").is_some(),
+    var("DISPLAY").is_some(),
+  )
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn detect_display_server() -> Option<DisplayServer> {
+  None
+}
+
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn display_server_from_env(
+  session_type: Option<&str>,
+  wayland_display: bool,
+  x11_display: bool,
+) -> Option<DisplayServer> {
+  match session_type {
+    Some("wayland") => Some(DisplayServer::Wayland),
+    Some("x11") => Some(DisplayServer::X11),
+    // tty sessions and unset types: fall back to the sockets that are present.
+    _ if wayland_display => Some(DisplayServer::Wayland),
+    _ if x11_display => Some(DisplayServer::X11),
+    _ => None,
+  }
+}
+
+/// MPV options for `display_server`.
+pub fn display_server_args(
+  display_server: Option<DisplayServer>,
+  features: MpvFeatures,
+  fullscreen: &FullscreenOptions,
+) -> Vec<String> {
+  let mut args = Vec::new();
+  match display_server {
+    Some(DisplayServer::Wayland) => {
+      args.push("--gpu-context=wayland".to_string());
+      if features.wayland_app_id {
+        args.push(format!("--wayland-app-id={}", MPV_WINDOW_CLASS));
+      }
+      // Wayland clients cannot pick the output they go fullscreen on; the
+      // compositor uses the one the window opened on.
+      if let Some(screen) = fullscreen.screen {
+        args.push(format!("--screen={}", screen));
+      }
+    }
+    Some(DisplayServer::X11) => {
+      args.push("--gpu-context=x11egl".to_string());
+      args.push(format!("--x11-name={}", MPV_WINDOW_CLASS));
+    }
+    None => {}
+  }
+  args
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn session_type_wins_over_display_sockets() {
+    assert_eq!(
+      display_server_from_env(Some("x11"), true, true),
+      Some(DisplayServer::X11)
+    );
+    assert_eq!(
+      display_server_from_env(Some("tty"), true, true),
+      Some(DisplayServer::Wayland)
+    );
+    assert_eq!(
+      display_server_from_env(None, false, true),
+      Some(DisplayServer::X11)
+    );
+    assert_eq!(display_server_from_env(None, false, false), None);
+  }
+
+  #[test]
+  fn wayland_args_set_the_app_id_when_mpv_supports_it() {
+    let fullscreen = FullscreenOptions {
+      on_start: true,
+      screen: Some(1),
+    };
+    let features = MpvFeatures::for_version(None);
+
+    assert_eq!(
+      display_server_args(Some(DisplayServer::Wayland), features, &fullscreen),
+      vec![
+        "--gpu-context=wayland",
+        "--wayland-app-id=jellypilot",
+        "--screen=1"
+      ]
+    );
+
+    let old = MpvFeatures {
+      wayland_app_id: false,
+      ..features
+    };
+    assert!(
+      !display_server_args(Some(DisplayServer::Wayland), old, &fullscreen)
+        .iter()
+        .any(|arg| arg.starts_with("--wayland-app-id"))
+    );
+  }
+
+  #[test]
+  fn x11_args_set_the_window_class() {
+    assert_eq!(
+      display_server_args(
+        Some(DisplayServer::X11),
+        MpvFeatures::for_version(None),
+        &FullscreenOptions::default()
+      ),
+      vec!["--gpu-context=x11egl", "--x11-name=jellypilot"]
+    );
+    assert!(DisplayServerMode::Off.resolve().is_none());
+  }
+}
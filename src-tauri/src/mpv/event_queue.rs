@@ -0,0 +1,195 @@
+//! Queue of MPV events between the IPC reader and the session.
+//!
+//! MPV sends property changes far more often than the session may read them
+//! while it is busy (e.g. `time-pos` many times a second). A bounded channel
+//! dropped whatever arrived once it was full, which could be an `end-file`.
+//! Here a property change replaces the queued, unread change of the same
+//! property, events the session acts on are always kept, and only other
+//! events are dropped once the queue is full.
+
+use std::collections::VecDeque;
+
+use parking_lot::Mutex;
+use tokio::sync::Notify;
+
+use super::protocol::MpvEvent;
+use crate::retention::BufferUsage;
+
+/// Queued events beyond which droppable events are left out.
+const CAPACITY: usize = 100;
+
+/// Events that are never dropped.
+const GUARANTEED_EVENTS: &[&str] = &[
+  "client-message",
+  "end-file",
+  "file-loaded",
+  "playback-restart",
+  "start-file",
+];
+
+#[derive(Default)]
+struct Queue {
+  events: VecDeque<MpvEvent>,
+  closed: bool,
+}
+
+/// MPV events waiting to be handled, oldest first.
+#[derive(Default)]
+pub struct EventQueue {
+  queue: Mutex<Queue>,
+  ready: Notify,
+}
+
+impl EventQueue {
+  /// Queue `event`, coalescing property changes.
+  pub fn push(&self, event: MpvEvent) {
+    {
+      let mut queue = self.queue.lock();
+      if queue.closed {
+        return;
+      }
+      let guaranteed = GUARANTEED_EVENTS.contains(&event.event.as_str());
+      if event.event == "property-change" {
+        queue
+          .events
+          .retain(|queued| queued.event != "property-change" || queued.name != event.name);
+      } else if !guaranteed && queue.events.len() >= CAPACITY {
+        log::warn!("MPV event queue full, dropping {} event", event.event);
+        return;
+      }
+      queue.events.push_back(event);
+    }
+    self.ready.notify_one();
+  }
+
+  /// The next event, waiting for one to arrive. None once the connection
+  /// closed and every queued event was handled.
+  pub async fn recv(&self) -> Option<MpvEvent> {
+    loop {
+      {
+        let mut queue = self.queue.lock();
+        if let Some(event) = queue.events.pop_front() {
+          return Some(event);
+        }
+        if queue.closed {
+          return None;
+        }
+      }
+      self.ready.notified().await;
+    }
+  }
+
+  /// Events waiting to be handled. Guaranteed events may exceed the capacity.
+  pub fn usage(&self) -> BufferUsage {
+    let queue = self.queue.lock();
+    let bytes: usize = queue
+      .events
+      .iter()
+      .map(|event| {
+        std::mem::size_of::<MpvEvent>()
+          + event.data.as_ref().map_or(0, |data| data.to_string().len())
+      })
+      .sum();
+    BufferUsage::new(
+      "MPV event queue",
+      queue.events.len(),
+      CAPACITY,
+      bytes as u64,
+    )
+  }
+
+  /// Stop taking events; waiting receivers get the queued ones, then None.
+  pub fn close(&self) {
+    self.queue.lock().closed = true;
+    self.ready.notify_waiters();
+    // A receiver between its check and its wait gets the stored permit.
+    self.ready.notify_one();
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn event(name: &str) -> MpvEvent {
+    MpvEvent {
+      event: name.to_string(),
+      id: None,
+      name: None,
+      data: None,
+      reason: None,
+      args: None,
+    }
+  }
+
+  fn property(name: &str, value: f64) -> MpvEvent {
+    MpvEvent {
+      name: Some(name.to_string()),
+      data: Some(serde_json::json!(value)),
+      ..event("property-change")
+    }
+  }
+
+  async fn drain(queue: &EventQueue) -> Vec<MpvEvent> {
+    queue.close();
+    let mut events = Vec::new();
+    while let Some(event) = queue.recv().await {
+      events.push(event);
+    }
+    events
+  }
+
+  #[tokio::test]
+  async fn property_changes_keep_only_the_latest_value() {
+    let queue = EventQueue::default();
+    for second in 0..1000 {
+      queue.push(property("time-pos", f64::from(second)));
+      queue.push(property("volume", 50.0));
+    }
+    queue.push(event("end-file"));
+
+    let events = drain(&queue).await;
+
+    assert_eq!(events.len(), 3);
+    assert_eq!(events[0].name.as_deref(), Some("time-pos"));
+    assert_eq!(events[0].data, Some(serde_json::json!(999.0)));
+    assert_eq!(events[1].name.as_deref(), Some("volume"));
+    assert_eq!(events[2].event, "end-file");
+  }
+
+  #[tokio::test]
+  async fn end_file_survives_a_full_queue() {
+    let queue = EventQueue::default();
+    for _ in 0..CAPACITY + 50 {
+      queue.push(event("video-reconfig"));
+    }
+    queue.push(event("end-file"));
+    queue.push(event("client-message"));
+
+    let events = drain(&queue).await;
+
+    assert_eq!(events.len(), CAPACITY + 2);
+    assert_eq!(events[CAPACITY].event, "end-file");
+    assert_eq!(events[CAPACITY + 1].event, "client-message");
+  }
+
+  #[tokio::test]
+  async fn a_waiting_receiver_wakes_for_new_events_and_close() {
+    let queue = std::sync::Arc::new(EventQueue::default());
+    let receiver = {
+      let queue = queue.clone();
+      tokio::spawn(async move { (queue.recv().await, queue.recv().await) })
+    };
+    tokio::task::yield_now().await;
+
+    queue.push(event("start-file"));
+    queue.close();
+
+    let (first, second) = receiver.await.unwrap();
+    assert_eq!(
+      first.map(|event| event.event),
+      Some("start-file".to_string())
+    );
+    assert!(second.is_none());
+  }
+}
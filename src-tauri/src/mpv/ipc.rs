@@ -7,14 +7,17 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 
-use async_channel::{Receiver, Sender};
 use parking_lot::Mutex;
 use thiserror::Error;
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::sync::oneshot;
 use tokio::task::JoinHandle;
 
-use super::protocol::{MpvCommand, MpvEvent, MpvMessage, MpvResponse};
+use super::event_queue::EventQueue;
+#[cfg(windows)]
+use super::pipe_security;
+use super::protocol::{MpvCommand, MpvMessage, MpvResponse};
+use crate::redact::redact;
 
 #[derive(Error, Debug)]
 pub enum IpcError {
@@ -22,14 +25,70 @@ pub enum IpcError {
   ConnectionFailed(String),
   #[error("Write failed: {0}")]
   WriteFailed(#[from] std::io::Error),
-  #[error("Command timeout")]
-  Timeout,
+  #[error("Command {0} timed out")]
+  Timeout(String),
   #[error("Disconnected")]
   Disconnected,
+  #[error("Disconnected while waiting for {0}")]
+  DisconnectedDuring(String),
+  #[error("The MPV pipe is served by process {actual}, not by MPV ({expected})")]
+  UnexpectedServer { expected: u32, actual: u32 },
+  #[error("The MPV pipe is served by process {actual}, which is not an MPV of this user")]
+  ForeignServer { actual: u32 },
 }
 
+/// Process the socket or pipe must be served by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PipeServer {
+  /// The MPV spawned with this PID, or a process it started. Only pipes
+  /// are checked by PID; see `try_connect`.
+  Spawned(#[cfg_attr(not(windows), allow(dead_code))] u32),
+  /// Any MPV of the current user, such as one an earlier JellyPilot left
+  /// running.
+  OwnMpv,
+}
+
+/// First and longest wait between IPC connect attempts.
+const FIRST_CONNECT_DELAY: Duration = Duration::from_millis(20);
+const MAX_CONNECT_DELAY: Duration = Duration::from_millis(250);
+
+/// Windows error opening a pipe whose instances are all connected.
+#[cfg(windows)]
+const ERROR_PIPE_BUSY: i32 = 231;
+/// Wait for a pipe instance to free up, and how many times to do so.
+#[cfg(windows)]
+const PIPE_BUSY_DELAY: Duration = Duration::from_millis(50);
+#[cfg(windows)]
+const PIPE_BUSY_RETRIES: u32 = 20;
+
+/// Wait after the `attempt`th failed connect: doubling from 20ms up to 250ms.
+fn connect_delay(attempt: u32) -> Duration {
+  FIRST_CONNECT_DELAY
+    .saturating_mul(1 << attempt.saturating_sub(1).min(8))
+    .min(MAX_CONNECT_DELAY)
+}
+
+/// Characters of a command kept in error messages.
+const COMMAND_DESCRIPTION_LIMIT: usize = 120;
+
 /// Pending request waiting for response.
-type PendingRequest = oneshot::Sender<Result<MpvResponse, IpcError>>;
+struct PendingRequest {
+  tx: oneshot::Sender<Result<MpvResponse, IpcError>>,
+  /// The command as [`describe`] wrote it, to name it in errors.
+  command: String,
+}
+
+/// The command and its arguments for logs, with secrets in stream URLs
+/// masked and long arguments cut short.
+fn describe(cmd: &MpvCommand) -> String {
+  let json = redact(&serde_json::to_string(&cmd.command).unwrap_or_default());
+  if json.chars().count() <= COMMAND_DESCRIPTION_LIMIT {
+    return json;
+  }
+  let mut short: String = json.chars().take(COMMAND_DESCRIPTION_LIMIT).collect();
+  short.push('…');
+  short
+}
 
 /// IPC connection state shared between writer and reader.
 struct IpcState {
@@ -37,12 +96,18 @@ struct IpcState {
 }
 
 impl IpcState {
-  /// Drain all pending requests with Disconnected error.
+  /// Fail all pending requests, naming the command each was waiting on.
   fn drain_pending(&mut self) {
     let pending = std::mem::take(&mut self.pending);
-    for (request_id, tx) in pending {
-      log::debug!("Draining pending request {}", request_id);
-      let _ = tx.send(Err(IpcError::Disconnected));
+    for (request_id, request) in pending {
+      log::debug!(
+        "Draining pending request {}: {}",
+        request_id,
+        request.command
+      );
+      let _ = request
+        .tx
+        .send(Err(IpcError::DisconnectedDuring(request.command)));
     }
   }
 }
@@ -57,50 +122,110 @@ enum WriteMessage {
 pub struct MpvIpc {
   state: Arc<Mutex<IpcState>>,
   write_tx: async_channel::Sender<WriteMessage>,
-  event_rx: Receiver<MpvEvent>,
+  events: Arc<EventQueue>,
   closed: Arc<AtomicBool>,
   reader_handle: JoinHandle<()>,
   writer_handle: JoinHandle<()>,
 }
 
 impl MpvIpc {
-  /// Connect to MPV IPC socket/pipe.
-  pub async fn connect(path: &str, retry_count: u32) -> Result<Self, IpcError> {
-    let mut last_error = None;
-
-    for attempt in 0..retry_count {
-      if attempt > 0 {
-        tokio::time::sleep(Duration::from_millis(100 * (attempt as u64 + 1))).await;
-      }
-
-      match Self::try_connect(path).await {
+  /// Connect to MPV IPC socket/pipe as soon as MPV has created it.
+  ///
+  /// Polls quickly at first, since MPV usually opens the socket within tens
+  /// of milliseconds, then less often until `timeout`. Stops early once
+  /// `running` reports that MPV exited.
+  ///
+  /// With `server`, the socket or pipe must be served by that process: its
+  /// name is predictable, so another program could create it first and
+  /// receive the stream URLs meant for MPV.
+  pub async fn connect(
+    path: &str,
+    server: Option<PipeServer>,
+    timeout: Duration,
+    mut running: impl FnMut() -> bool,
+  ) -> Result<Self, IpcError> {
+    let deadline = tokio::time::Instant::now() + timeout;
+    let mut attempt = 0;
+    loop {
+      let error = match Self::try_connect(path, server).await {
         Ok(ipc) => return Ok(ipc),
-        Err(e) => {
-          log::debug!("IPC connect attempt {} failed: {}", attempt + 1, e);
-          last_error = Some(e);
+        Err(e @ (IpcError::UnexpectedServer { .. } | IpcError::ForeignServer { .. })) => {
+          return Err(e)
         }
+        Err(e) => e,
+      };
+      attempt += 1;
+      log::debug!("IPC connect attempt {} failed: {}", attempt, error);
+      if !running() {
+        return Err(IpcError::ConnectionFailed(
+          "MPV exited before opening its IPC socket".into(),
+        ));
       }
+      let delay = connect_delay(attempt);
+      if tokio::time::Instant::now() + delay > deadline {
+        return Err(error);
+      }
+      tokio::time::sleep(delay).await;
     }
-
-    Err(last_error.unwrap_or_else(|| IpcError::ConnectionFailed("Unknown error".into())))
   }
 
+  /// Open the pipe, waiting while all its instances are busy: MPV creates
+  /// the next instance only after a client took the previous one.
   #[cfg(windows)]
-  async fn try_connect(path: &str) -> Result<Self, IpcError> {
+  async fn try_connect(path: &str, server: Option<PipeServer>) -> Result<Self, IpcError> {
     use tokio::net::windows::named_pipe::ClientOptions;
 
-    let client = ClientOptions::new()
-      .open(path)
-      .map_err(|e| IpcError::ConnectionFailed(format!("Failed to open pipe: {}", e)))?;
+    let mut busy_retries = 0;
+    let client = loop {
+      let error = match ClientOptions::new().open(path) {
+        Ok(client) => break client,
+        Err(e) => e,
+      };
+      if error.raw_os_error() != Some(ERROR_PIPE_BUSY) {
+        return Err(IpcError::ConnectionFailed(format!(
+          "Failed to open pipe: {}",
+          error
+        )));
+      }
+      if busy_retries == PIPE_BUSY_RETRIES {
+        return Err(IpcError::ConnectionFailed(
+          "All MPV pipe instances are in use".into(),
+        ));
+      }
+      busy_retries += 1;
+      tokio::time::sleep(PIPE_BUSY_DELAY).await;
+    };
+
+    if let Some(server) = server {
+      let actual = pipe_security::server_process_id(&client)
+        .map_err(|e| IpcError::ConnectionFailed(format!("Failed to check pipe server: {}", e)))?;
+      match server {
+        PipeServer::Spawned(expected) => {
+          if !pipe_security::is_same_or_descendant(actual, expected) {
+            return Err(IpcError::UnexpectedServer { expected, actual });
+          }
+        }
+        PipeServer::OwnMpv => {
+          if !pipe_security::is_own_mpv(actual) {
+            return Err(IpcError::ForeignServer { actual });
+          }
+        }
+      }
+    }
 
     let (reader, writer) = tokio::io::split(client);
     Self::setup(reader, writer).await
   }
 
+  /// MPV may run under a wrapper such as flatpak with a PID of its own, so
+  /// sockets rely on the permissions of their directory instead: only the
+  /// current user can create one there.
   #[cfg(not(windows))]
-  async fn try_connect(path: &str) -> Result<Self, IpcError> {
+  async fn try_connect(path: &str, _server: Option<PipeServer>) -> Result<Self, IpcError> {
     use tokio::net::UnixStream;
 
+    super::socket_dir::verify_socket(std::path::Path::new(path))
+      .map_err(|e| IpcError::ConnectionFailed(e.to_string()))?;
     let stream = UnixStream::connect(path)
       .await
       .map_err(|e| IpcError::ConnectionFailed(e.to_string()))?;
@@ -120,14 +245,15 @@ impl MpvIpc {
 
     let closed = Arc::new(AtomicBool::new(false));
 
-    let (event_tx, event_rx) = async_channel::bounded(100); // Bounded to prevent memory bloat
+    let events = Arc::new(EventQueue::default());
     let (write_tx, write_rx) = async_channel::bounded::<WriteMessage>(100); // Bounded to prevent OOM
 
     // Spawn reader task
     let reader_state = state.clone();
     let reader_closed = closed.clone();
+    let reader_events = events.clone();
     let reader_handle = tokio::spawn(async move {
-      Self::reader_loop(reader, reader_state, event_tx, reader_closed).await;
+      Self::reader_loop(reader, reader_state, reader_events, reader_closed).await;
     });
 
     // Spawn writer task - pass state and closed for error handling
@@ -140,7 +266,7 @@ impl MpvIpc {
     Ok(Self {
       state,
       write_tx,
-      event_rx,
+      events,
       closed,
       reader_handle,
       writer_handle,
@@ -150,7 +276,7 @@ impl MpvIpc {
   async fn reader_loop<R: tokio::io::AsyncRead + Unpin>(
     reader: R,
     state: Arc<Mutex<IpcState>>,
-    event_tx: Sender<MpvEvent>,
+    events: Arc<EventQueue>,
     closed: Arc<AtomicBool>,
   ) {
     log::info!("MPV IPC reader loop started");
@@ -183,16 +309,13 @@ impl MpvIpc {
                 response.request_id
               );
               let mut state = state.lock();
-              if let Some(tx) = state.pending.remove(&response.request_id) {
-                let _ = tx.send(Ok(response));
+              if let Some(request) = state.pending.remove(&response.request_id) {
+                let _ = request.tx.send(Ok(response));
               }
             }
             Ok(MpvMessage::Event(event)) => {
               log::debug!("MPV event: {} (reason={:?})", event.event, event.reason);
-              // Use try_send to avoid blocking if channel is full
-              if event_tx.try_send(event).is_err() {
-                log::warn!("Event channel full, dropping event");
-              }
+              events.push(event);
             }
             Err(e) => {
               log::warn!("Failed to parse MPV message: {} - {}", e, trimmed);
@@ -213,7 +336,8 @@ impl MpvIpc {
     log::info!("MPV IPC reader exiting, draining pending requests");
     state.lock().drain_pending();
 
-    // Close event channel by dropping sender (happens automatically when task ends)
+    // Let the session see the end of the event stream
+    events.close();
     log::info!("MPV IPC reader loop ended");
   }
 
@@ -250,7 +374,7 @@ impl MpvIpc {
     }
 
     // On any exit (IO error or close), mark closed and drain pending
-    // so callers get immediate Disconnected instead of 5s timeout
+    // so callers get immediate Disconnected instead of a timeout
     log::info!("MPV IPC writer exiting, marking closed and draining pending");
     closed.store(true, Ordering::Release);
     state.lock().drain_pending();
@@ -263,14 +387,19 @@ impl MpvIpc {
     self.closed.load(Ordering::Acquire)
   }
 
-  /// Send a command to MPV and wait for response.
-  pub async fn send_command(&self, cmd: MpvCommand) -> Result<MpvResponse, IpcError> {
+  /// Send a command to MPV and wait up to `timeout` for its response.
+  pub async fn send_command(
+    &self,
+    cmd: MpvCommand,
+    timeout: Duration,
+  ) -> Result<MpvResponse, IpcError> {
     // Early check for closed connection
     if self.is_closed() {
       return Err(IpcError::Disconnected);
     }
 
     let request_id = cmd.request_id;
+    let command = describe(&cmd);
 
     // Create response channel
     let (tx, rx) = oneshot::channel();
@@ -278,16 +407,22 @@ impl MpvIpc {
     // Register pending request
     {
       let mut state = self.state.lock();
-      state.pending.insert(request_id, tx);
+      state.pending.insert(
+        request_id,
+        PendingRequest {
+          tx,
+          command: command.clone(),
+        },
+      );
     }
 
     // Re-check closed after inserting to handle race with close()/drain_pending()
     // If closed was set between our first check and insert, drain_pending() already ran
-    // and won't drain our newly inserted pending - we'd timeout after 5s instead of
+    // and won't drain our newly inserted pending - we'd time out instead of
     // getting immediate Disconnected
     if self.is_closed() {
-      if let Some(tx) = self.state.lock().pending.remove(&request_id) {
-        let _ = tx.send(Err(IpcError::Disconnected));
+      if let Some(request) = self.state.lock().pending.remove(&request_id) {
+        let _ = request.tx.send(Err(IpcError::Disconnected));
       }
       return Err(IpcError::Disconnected);
     }
@@ -313,8 +448,8 @@ impl MpvIpc {
       .await
       .is_err()
     {
-      if let Some(tx) = self.state.lock().pending.remove(&request_id) {
-        let _ = tx.send(Err(IpcError::Disconnected));
+      if let Some(request) = self.state.lock().pending.remove(&request_id) {
+        let _ = request.tx.send(Err(IpcError::Disconnected));
       }
       return Err(IpcError::Disconnected);
     }
@@ -322,31 +457,33 @@ impl MpvIpc {
     log::trace!("MPV command queued, waiting for response...");
 
     // Wait for response with timeout
-    match tokio::time::timeout(Duration::from_secs(5), rx).await {
+    match tokio::time::timeout(timeout, rx).await {
       Ok(Ok(result)) => {
         log::trace!("MPV response received: {:?}", result);
         result
       }
       Ok(Err(_)) => {
         // Channel was closed (sender dropped) - connection died
-        log::error!("MPV IPC channel closed unexpectedly");
-        Err(IpcError::Disconnected)
+        log::error!("MPV IPC channel closed unexpectedly during {}", command);
+        Err(IpcError::DisconnectedDuring(command))
       }
       Err(_) => {
         // Timeout - remove pending request
         log::error!(
-          "MPV command timeout after 5 seconds, request_id={}",
-          request_id
+          "MPV command timeout after {:?}, request_id={}: {}",
+          timeout,
+          request_id,
+          command
         );
         self.state.lock().pending.remove(&request_id);
-        Err(IpcError::Timeout)
+        Err(IpcError::Timeout(command))
       }
     }
   }
 
   /// Get the event receiver for property changes and other events.
-  pub fn events(&self) -> Receiver<MpvEvent> {
-    self.event_rx.clone()
+  pub fn events(&self) -> Arc<EventQueue> {
+    self.events.clone()
   }
 
   /// Close the connection gracefully.
@@ -376,6 +513,9 @@ impl Drop for MpvIpc {
     // Signal closed with Release ordering
     self.closed.store(true, Ordering::Release);
 
+    // The aborted reader can't end the event stream itself
+    self.events.close();
+
     // Close write channel
     self.write_tx.close();
 
@@ -387,3 +527,169 @@ impl Drop for MpvIpc {
     self.state.lock().drain_pending();
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn drained_requests_name_their_command_without_secrets() {
+    let mut state = IpcState {
+      pending: HashMap::new(),
+    };
+    let cmd = MpvCommand::loadfile("https://jellyfin.example/Videos/1/stream?api_key=secret");
+    let (tx, rx) = oneshot::channel();
+    state.pending.insert(
+      cmd.request_id,
+      PendingRequest {
+        tx,
+        command: describe(&cmd),
+      },
+    );
+
+    state.drain_pending();
+
+    let error = rx.blocking_recv().unwrap().unwrap_err().to_string();
+    assert!(error.contains("loadfile"));
+    assert!(!error.contains("secret"));
+  }
+
+  #[test]
+  fn long_commands_are_cut_short() {
+    let cmd = MpvCommand::show_text(&"x".repeat(500), 1000);
+
+    let description = describe(&cmd);
+
+    assert!(description.starts_with(r#"["show-text","xxx"#));
+    assert_eq!(description.chars().count(), COMMAND_DESCRIPTION_LIMIT + 1);
+  }
+
+  #[test]
+  fn connect_polls_fast_then_backs_off() {
+    let delays: Vec<u128> = (1..=7)
+      .map(|attempt| connect_delay(attempt).as_millis())
+      .collect();
+    assert_eq!(delays, [20, 40, 80, 160, 250, 250, 250]);
+    assert_eq!(connect_delay(u32::MAX), MAX_CONNECT_DELAY);
+  }
+
+  #[cfg(not(windows))]
+  #[tokio::test]
+  async fn connect_waits_for_the_socket() {
+    use std::os::unix::fs::DirBuilderExt;
+
+    let dir = std::env::temp_dir().join(format!("jellypilot-ipc-{}", uuid::Uuid::new_v4()));
+    std::fs::DirBuilder::new().mode(0o700).create(&dir).unwrap();
+    let path = dir.join("mpv.sock");
+    let listen_path = path.clone();
+    let listener = tokio::spawn(async move {
+      tokio::time::sleep(Duration::from_millis(60)).await;
+      let listener = tokio::net::UnixListener::bind(&listen_path).unwrap();
+      let (stream, _) = listener.accept().await.unwrap();
+      stream
+    });
+
+    let ipc = MpvIpc::connect(path.to_str().unwrap(), None, Duration::from_secs(5), || {
+      true
+    })
+    .await;
+
+    assert!(ipc.is_ok());
+    drop(listener.await);
+    let _ = std::fs::remove_dir_all(&dir);
+  }
+
+  #[tokio::test]
+  async fn connect_stops_once_mpv_exited() {
+    let path = std::env::temp_dir().join(format!("jellypilot-ipc-{}", uuid::Uuid::new_v4()));
+    let started = std::time::Instant::now();
+
+    let ipc = MpvIpc::connect(path.to_str().unwrap(), None, Duration::from_secs(5), || {
+      false
+    })
+    .await;
+
+    assert!(matches!(ipc, Err(IpcError::ConnectionFailed(_))));
+    assert!(started.elapsed() < Duration::from_secs(1));
+  }
+
+  #[cfg(windows)]
+  fn pipe_name() -> String {
+    format!(r"\\.\pipe\jellypilot-test-{}", uuid::Uuid::new_v4())
+  }
+
+  #[cfg(windows)]
+  #[tokio::test]
+  async fn busy_pipes_are_retried_until_an_instance_frees_up() {
+    use tokio::net::windows::named_pipe::ServerOptions;
+
+    let name = pipe_name();
+    let first = ServerOptions::new()
+      .first_pipe_instance(true)
+      .create(&name)
+      .unwrap();
+    // The only instance is taken, like MPV's right after its last client.
+    let taken = tokio::net::windows::named_pipe::ClientOptions::new()
+      .open(&name)
+      .unwrap();
+    let next_name = name.clone();
+    let next = tokio::spawn(async move {
+      tokio::time::sleep(Duration::from_millis(150)).await;
+      let server = ServerOptions::new().create(&next_name).unwrap();
+      server.connect().await.unwrap();
+      server
+    });
+
+    let ipc = MpvIpc::try_connect(&name, Some(PipeServer::Spawned(std::process::id()))).await;
+
+    assert!(ipc.is_ok());
+    drop((first, taken, next.await));
+  }
+
+  #[cfg(windows)]
+  #[tokio::test]
+  async fn pipes_served_by_another_process_are_refused() {
+    use tokio::net::windows::named_pipe::ServerOptions;
+
+    let name = pipe_name();
+    let _server = ServerOptions::new()
+      .first_pipe_instance(true)
+      .create(&name)
+      .unwrap();
+    let started = std::time::Instant::now();
+
+    let ipc = MpvIpc::connect(
+      &name,
+      Some(PipeServer::Spawned(u32::MAX)),
+      Duration::from_secs(5),
+      || true,
+    )
+    .await;
+
+    assert!(matches!(ipc, Err(IpcError::UnexpectedServer { .. })));
+    assert!(started.elapsed() < Duration::from_secs(1));
+  }
+
+  #[cfg(windows)]
+  #[tokio::test]
+  async fn orphan_pipes_must_be_served_by_an_mpv() {
+    use tokio::net::windows::named_pipe::ServerOptions;
+
+    let name = pipe_name();
+    // Served by the test binary, which is no MPV.
+    let _server = ServerOptions::new()
+      .first_pipe_instance(true)
+      .create(&name)
+      .unwrap();
+
+    let ipc = MpvIpc::connect(
+      &name,
+      Some(PipeServer::OwnMpv),
+      Duration::from_secs(5),
+      || true,
+    )
+    .await;
+
+    assert!(matches!(ipc, Err(IpcError::ForeignServer { .. })));
+  }
+}
@@ -0,0 +1,99 @@
+//! Recording the next key pressed in MPV, in input.conf syntax.
+//!
+//! MPV has no IPC command that reports a key press, so a small script is
+//! loaded into it. On request the script puts a binding for `UNMAPPED` in an
+//! exclusive section: every key then falls through to it instead of running
+//! its usual command, and the script stores the key's name in a `user-data`
+//! property the client polls. Escape cancels.
+
+use std::path::PathBuf;
+
+use super::keys::is_valid_key;
+
+/// Property the script stores the captured key in; empty while waiting.
+pub const CAPTURED_KEY_PROPERTY: &str = "user-data/jellypilot/captured-key";
+/// Property set once the script is loaded into the running MPV.
+pub const SCRIPT_LOADED_PROPERTY: &str = "user-data/jellypilot/key-capture-loaded";
+/// Script message that starts a capture.
+pub const CAPTURE_MESSAGE: &str = "jellypilot-capture-key";
+/// Script message that ends a capture nobody pressed a key for.
+pub const CANCEL_MESSAGE: &str = "jellypilot-cancel-key-capture";
+
+/// Value stored when the capture was cancelled with Escape.
+const CANCELLED: &str = "ESC";
+
+const SCRIPT: &str = r#"-- Written by JellyPilot: records the next key pressed in MPV.
+local section = "input_forced_" .. mp.get_script_name()
+
+local function on_key(e)
+  if e.event ~= "down" and e.event ~= "press" then
+    return
+  end
+  local name = e.key_name or ""
+  if name == "" or name:find("MOUSE_MOVE") or name:find("MOUSE_ENTER") or name:find("MOUSE_LEAVE") then
+    return
+  end
+  mp.remove_key_binding("jellypilot-capture-key")
+  mp.set_property_native("user-data/jellypilot/captured-key", name)
+end
+
+mp.register_script_message("jellypilot-capture-key", function()
+  mp.add_forced_key_binding("UNMAPPED", "jellypilot-capture-key", on_key, { complex = true })
+  mp.commandv("enable-section", section, "exclusive")
+end)
+
+mp.register_script_message("jellypilot-cancel-key-capture", function()
+  mp.remove_key_binding("jellypilot-capture-key")
+end)
+
+mp.set_property_native("user-data/jellypilot/key-capture-loaded", true)
+"#;
+
+/// Write the capture script next to JellyPilot's input.conf.
+pub fn write_capture_script() -> Option<PathBuf> {
+  let path = super::process::jellypilot_input_conf_path()?.with_file_name("capture-key.lua");
+  if let Some(parent) = path.parent() {
+    if let Err(e) = std::fs::create_dir_all(parent) {
+      log::warn!("Failed to create JellyPilot config directory: {}", e);
+      return None;
+    }
+  }
+  if let Err(e) = std::fs::write(&path, SCRIPT) {
+    log::warn!("Failed to write the key capture script: {}", e);
+    return None;
+  }
+  Some(path)
+}
+
+/// The captured key from the script's property: None while waiting, Some(None)
+/// when cancelled or the key can't go in input.conf.
+pub fn captured_key(value: &str) -> Option<Option<String>> {
+  let key = value.trim();
+  if key.is_empty() {
+    return None;
+  }
+  if key.eq_ignore_ascii_case(CANCELLED) || !is_valid_key(key) {
+    return Some(None);
+  }
+  Some(Some(key.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn captured_keys_are_returned_as_mpv_names() {
+    assert_eq!(captured_key(""), None);
+    assert_eq!(captured_key("Alt+g"), Some(Some("Alt+g".to_string())));
+    assert_eq!(
+      captured_key("Ctrl+RIGHT"),
+      Some(Some("Ctrl+RIGHT".to_string()))
+    );
+  }
+
+  #[test]
+  fn escape_cancels_the_capture() {
+    assert_eq!(captured_key("ESC"), Some(None));
+  }
+}
@@ -0,0 +1,160 @@
+//! MPV key names as accepted in input.conf.
+//!
+//! A key is any single character or a named key, optionally prefixed with
+//! `Shift+`, `Ctrl+`, `Alt+` or `Meta+`. MPV matches names case-insensitively.
+
+const MODIFIERS: [&str; 4] = ["shift", "ctrl", "alt", "meta"];
+
+/// Named keys without a numbered range.
+const NAMED_KEYS: &[&str] = &[
+  "space",
+  "ideographic_space",
+  "sharp",
+  "enter",
+  "tab",
+  "bs",
+  "del",
+  "ins",
+  "home",
+  "end",
+  "pgup",
+  "pgdwn",
+  "esc",
+  "print",
+  "right",
+  "left",
+  "down",
+  "up",
+  "kp_dec",
+  "kp_del",
+  "kp_ins",
+  "kp_home",
+  "kp_end",
+  "kp_pgup",
+  "kp_pgdwn",
+  "kp_right",
+  "kp_left",
+  "kp_down",
+  "kp_up",
+  "kp_begin",
+  "kp_enter",
+  "kp_add",
+  "kp_subtract",
+  "kp_multiply",
+  "kp_divide",
+  "mbtn_left",
+  "mbtn_mid",
+  "mbtn_right",
+  "mbtn_back",
+  "mbtn_forward",
+  "mbtn_left_dbl",
+  "mbtn_mid_dbl",
+  "mbtn_right_dbl",
+  "wheel_up",
+  "wheel_down",
+  "wheel_left",
+  "wheel_right",
+  "power",
+  "menu",
+  "play",
+  "pause",
+  "playpause",
+  "playonly",
+  "pauseonly",
+  "stop",
+  "forward",
+  "rewind",
+  "next",
+  "prev",
+  "volume_up",
+  "volume_down",
+  "mute",
+  "homepage",
+  "www",
+  "mail",
+  "favorites",
+  "search",
+  "sleep",
+  "cancel",
+  "record",
+  "channel_up",
+  "channel_down",
+  "close_win",
+  "mouse_move",
+  "mouse_leave",
+  "mouse_enter",
+  "any_unicode",
+  "unmapped",
+];
+
+/// Whether MPV accepts `key` as a key name in input.conf.
+pub fn is_valid_key(key: &str) -> bool {
+  let key = key.trim();
+  // `+` is a key of its own as well as the modifier separator.
+  let (modifiers, base) = if key == "+" {
+    ("", "+")
+  } else if let Some(modifiers) = key.strip_suffix("++") {
+    (modifiers, "+")
+  } else {
+    key.rsplit_once('+').unwrap_or(("", key))
+  };
+  let modifiers_valid = modifiers.is_empty()
+    || modifiers
+      .split('+')
+      .all(|modifier| MODIFIERS.contains(&modifier.to_ascii_lowercase().as_str()));
+  modifiers_valid && is_valid_base_key(base)
+}
+
+fn is_valid_base_key(base: &str) -> bool {
+  let mut chars = base.chars();
+  if let (Some(c), None) = (chars.next(), chars.next()) {
+    return !c.is_whitespace();
+  }
+  let name = base.to_ascii_lowercase();
+  if NAMED_KEYS.contains(&name.as_str()) {
+    return true;
+  }
+  let numbered = |prefix: &str, range: std::ops::RangeInclusive<u32>| {
+    name
+      .strip_prefix(prefix)
+      .and_then(|number| number.parse::<u32>().ok())
+      .is_some_and(|number| range.contains(&number))
+  };
+  numbered("f", 1..=24)
+    || numbered("kp", 0..=9)
+    || numbered("mbtn", 9..=19)
+    || name
+      .strip_prefix("0x")
+      .is_some_and(|hex| !hex.is_empty() && hex.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn characters_and_named_keys_with_modifiers_are_valid() {
+    for key in [
+      "g",
+      "Shift+>",
+      "Ctrl+w",
+      "ctrl+alt+DEL",
+      "+",
+      "Ctrl++",
+      "F12",
+      "KP5",
+      "MBTN_LEFT_DBL",
+      "ä",
+      "0x1b",
+    ] {
+      assert!(is_valid_key(key), "{key} should be valid");
+    }
+  }
+
+  #[test]
+  fn unknown_names_modifiers_and_blanks_are_rejected() {
+    for key in ["", " ", "Enterr", "Hyper+g", "F25", "Ctrl+", "a b", "0x"] {
+      assert!(!is_valid_key(key), "{key:?} should be rejected");
+    }
+  }
+}
@@ -0,0 +1,271 @@
+//! Tie the spawned MPV to JellyPilot's lifetime.
+//!
+//! When JellyPilot crashes, an MPV it spawned keeps playing with nobody
+//! reporting progress or listening to the remote. On Windows MPV is put in a
+//! job object that is killed when JellyPilot's handle to it closes, which
+//! happens however the process ends. On Linux MPV gets SIGTERM when its parent
+//! dies. macOS has no such signal, so a small watcher process forked next to
+//! MPV waits on a kqueue for JellyPilot to exit and sends MPV SIGTERM then.
+//! On other Unix systems MPV outlives a crash and is left to the orphan check
+//! of the next start (see `orphans.rs`).
+
+use std::process::{Child, Command};
+
+/// Prepare `cmd` so the MPV it spawns ends with JellyPilot.
+#[cfg(target_os = "linux")]
+pub fn bind_command(cmd: &mut Command) {
+  use std::os::unix::process::CommandExt;
+
+  const PR_SET_PDEATHSIG: i32 = 1;
+  const SIGTERM: u64 = 15;
+  const ESRCH: i32 = 3;
+
+  extern "C" {
+    fn prctl(option: i32, ...) -> i32;
+    fn getppid() -> i32;
+  }
+
+  // The signal follows the spawning thread rather than the process, so this
+  // relies on MPV being spawned from a runtime worker, which lives as long
+  // as JellyPilot.
+  let parent = std::process::id() as i32;
+  // SAFETY: only async-signal-safe calls run between fork and exec.
+  unsafe {
+    cmd.pre_exec(move || {
+      if prctl(PR_SET_PDEATHSIG, SIGTERM) != 0 {
+        return Err(std::io::Error::last_os_error());
+      }
+      // JellyPilot died before the death signal was set up.
+      if getppid() != parent {
+        return Err(std::io::Error::from_raw_os_error(ESRCH));
+      }
+      Ok(())
+    });
+  }
+}
+
+/// Prepare `cmd` so the MPV it spawns ends with JellyPilot.
+#[cfg(target_os = "macos")]
+pub fn bind_command(cmd: &mut Command) {
+  use std::os::unix::process::CommandExt;
+
+  extern "C" {
+    fn sysconf(name: i32) -> i64;
+  }
+  const SC_OPEN_MAX: i32 = 5;
+
+  let parent = std::process::id() as i32;
+  // Read here: sysconf isn't safe to call between fork and exec.
+  // SAFETY: plain call without pointers.
+  let open_max = unsafe { sysconf(SC_OPEN_MAX) }.clamp(256, 65536) as i32;
+  // SAFETY: only async-signal-safe calls run between fork and exec.
+  unsafe {
+    cmd.pre_exec(move || {
+      macos::fork_watcher(parent, open_max);
+      Ok(())
+    });
+  }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub fn bind_command(_cmd: &mut Command) {}
+
+/// Put the spawned MPV in the job object killed when JellyPilot exits.
+#[cfg(windows)]
+pub fn bind_child(child: &Child) {
+  if let Err(e) = windows::assign(child) {
+    log::warn!("Failed to tie MPV to JellyPilot's lifetime: {}", e);
+  }
+}
+
+#[cfg(not(windows))]
+pub fn bind_child(_child: &Child) {}
+
+#[cfg(target_os = "macos")]
+mod macos {
+  use std::ffi::c_void;
+
+  const EVFILT_PROC: i16 = -5;
+  const EV_ADD: u16 = 0x0001;
+  const NOTE_EXIT: u32 = 0x8000_0000;
+  const SIGTERM: i32 = 15;
+  const EINTR: i32 = 4;
+
+  /// `struct kevent` from sys/event.h.
+  #[repr(C)]
+  struct KEvent {
+    ident: usize,
+    filter: i16,
+    flags: u16,
+    fflags: u32,
+    data: isize,
+    udata: *mut c_void,
+  }
+
+  extern "C" {
+    fn fork() -> i32;
+    fn getpid() -> i32;
+    fn close(fd: i32) -> i32;
+    fn kill(pid: i32, signal: i32) -> i32;
+    fn kqueue() -> i32;
+    fn kevent(
+      kq: i32,
+      changes: *const KEvent,
+      change_count: i32,
+      events: *mut KEvent,
+      event_count: i32,
+      timeout: *const c_void,
+    ) -> i32;
+    fn __error() -> *mut i32;
+    fn _exit(status: i32) -> !;
+  }
+
+  fn exit_of(pid: i32) -> KEvent {
+    KEvent {
+      ident: pid as usize,
+      filter: EVFILT_PROC,
+      flags: EV_ADD,
+      fflags: NOTE_EXIT,
+      data: 0,
+      udata: std::ptr::null_mut(),
+    }
+  }
+
+  /// Fork, in the child about to become MPV, a watcher that sends MPV
+  /// SIGTERM once `parent` exits and goes away with MPV. Without a watcher
+  /// MPV still starts; it is just not bound.
+  ///
+  /// # Safety
+  ///
+  /// Only to be called between fork and exec: it uses nothing but
+  /// async-signal-safe system calls.
+  pub unsafe fn fork_watcher(parent: i32, open_max: i32) {
+    let mpv = getpid();
+    if fork() != 0 {
+      return;
+    }
+    // Inherited descriptors would keep the spawn's exec-status pipe and
+    // MPV's log and sockets open after MPV exits.
+    for fd in 0..open_max {
+      close(fd);
+    }
+    let kq = kqueue();
+    if kq < 0 {
+      _exit(1);
+    }
+    let watch = |pid: i32| {
+      let change = exit_of(pid);
+      kevent(kq, &change, 1, std::ptr::null_mut(), 0, std::ptr::null()) == 0
+    };
+    // JellyPilot died before the watch was set up.
+    if !watch(parent) {
+      kill(mpv, SIGTERM);
+      _exit(0);
+    }
+    if !watch(mpv) {
+      _exit(0);
+    }
+    let mut event = exit_of(0);
+    loop {
+      let count = kevent(kq, std::ptr::null(), 0, &mut event, 1, std::ptr::null());
+      if count < 0 && *__error() == EINTR {
+        continue;
+      }
+      if count > 0 && event.ident == parent as usize {
+        kill(mpv, SIGTERM);
+      }
+      _exit(0);
+    }
+  }
+}
+
+#[cfg(windows)]
+mod windows {
+  use std::ffi::c_void;
+  use std::os::windows::io::AsRawHandle;
+  use std::process::Child;
+  use std::sync::OnceLock;
+
+  const JOB_OBJECT_EXTENDED_LIMIT_INFORMATION: i32 = 9;
+  const JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE: u32 = 0x2000;
+
+  /// `JOBOBJECT_BASIC_LIMIT_INFORMATION` from winnt.h.
+  #[repr(C)]
+  #[derive(Default)]
+  struct BasicLimits {
+    per_process_user_time_limit: i64,
+    per_job_user_time_limit: i64,
+    limit_flags: u32,
+    minimum_working_set_size: usize,
+    maximum_working_set_size: usize,
+    active_process_limit: u32,
+    affinity: usize,
+    priority_class: u32,
+    scheduling_class: u32,
+  }
+
+  /// `JOBOBJECT_EXTENDED_LIMIT_INFORMATION` from winnt.h.
+  #[repr(C)]
+  #[derive(Default)]
+  struct ExtendedLimits {
+    basic: BasicLimits,
+    io_counters: [u64; 6],
+    process_memory_limit: usize,
+    job_memory_limit: usize,
+    peak_process_memory_used: usize,
+    peak_job_memory_used: usize,
+  }
+
+  #[link(name = "kernel32")]
+  extern "system" {
+    fn CreateJobObjectW(attributes: *mut c_void, name: *const u16) -> *mut c_void;
+    fn SetInformationJobObject(job: *mut c_void, class: i32, info: *mut c_void, len: u32) -> i32;
+    fn AssignProcessToJobObject(job: *mut c_void, process: *mut c_void) -> i32;
+    fn CloseHandle(handle: *mut c_void) -> i32;
+  }
+
+  /// The job, kept open until JellyPilot exits. Stored as an address so it
+  /// can live in a static.
+  static JOB: OnceLock<Result<usize, String>> = OnceLock::new();
+
+  pub fn assign(child: &Child) -> Result<(), String> {
+    let job = JOB.get_or_init(create_job).clone()? as *mut c_void;
+    // SAFETY: `job` stays open for the life of the process and the child's
+    // handle is open while `child` is borrowed.
+    if unsafe { AssignProcessToJobObject(job, child.as_raw_handle()) } == 0 {
+      return Err(std::io::Error::last_os_error().to_string());
+    }
+    Ok(())
+  }
+
+  fn create_job() -> Result<usize, String> {
+    // SAFETY: plain call; the returned handle is checked below.
+    let job = unsafe { CreateJobObjectW(std::ptr::null_mut(), std::ptr::null()) };
+    if job.is_null() {
+      return Err(std::io::Error::last_os_error().to_string());
+    }
+    let mut limits = ExtendedLimits {
+      basic: BasicLimits {
+        limit_flags: JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE,
+        ..Default::default()
+      },
+      ..Default::default()
+    };
+    // SAFETY: `limits` matches the layout of the requested information class.
+    let ok = unsafe {
+      SetInformationJobObject(
+        job,
+        JOB_OBJECT_EXTENDED_LIMIT_INFORMATION,
+        &mut limits as *mut ExtendedLimits as *mut c_void,
+        std::mem::size_of::<ExtendedLimits>() as u32,
+      )
+    };
+    if ok == 0 {
+      let error = std::io::Error::last_os_error().to_string();
+      // SAFETY: `job` is open and not used afterwards.
+      unsafe { CloseHandle(job) };
+      return Err(error);
+    }
+    Ok(job as usize)
+  }
+}
@@ -0,0 +1,231 @@
+//! Per-session MPV log files.
+//!
+//! Each MPV launch writes its stdout/stderr to its own file so a crash or
+//! decode error can be attached to a bug report. Only the newest few sessions
+//! are kept, and a session log that grows past a size cap (MPV left running
+//! for days, or stuck repeating an error) is cut back to its start.
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::retention::BufferUsage;
+
+/// Number of MPV session logs kept on disk, including the current one,
+/// unless configured otherwise.
+pub const DEFAULT_MPV_LOG_RETENTION: usize = 5;
+
+static RETENTION: AtomicUsize = AtomicUsize::new(DEFAULT_MPV_LOG_RETENTION);
+
+/// Size past which a session log is cut back.
+const MAX_SESSION_LOG_BYTES: u64 = 10 * MIB;
+/// Start of a session log kept when it is cut back, with MPV's startup output.
+const KEPT_LOG_HEAD_BYTES: u64 = MIB;
+const MIB: u64 = 1024 * 1024;
+/// How often the current session log's size is checked.
+const SIZE_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Advanced for every new session log; a size watcher stops once it changes.
+static LOG_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+const LOG_PREFIX: &str = "mpv-";
+const LOG_EXTENSION: &str = "log";
+
+/// Keep at most `retention` session logs, starting with the next MPV launch.
+/// At least the current session's log is always kept.
+pub fn set_log_retention(retention: usize) {
+  RETENTION.store(retention.max(1), Ordering::Relaxed);
+}
+
+/// Session logs on disk and their total size.
+pub fn log_usage() -> BufferUsage {
+  let logs = session_logs();
+  let bytes = logs
+    .iter()
+    .filter_map(|path| std::fs::metadata(path).ok())
+    .map(|metadata| metadata.len())
+    .sum();
+  BufferUsage::new(
+    "MPV session logs",
+    logs.len(),
+    RETENTION.load(Ordering::Relaxed),
+    bytes,
+  )
+}
+
+/// Directory holding MPV session logs.
+pub fn log_dir() -> Option<PathBuf> {
+  dirs::data_local_dir().map(|p| p.join("jellypilot").join("mpv-logs"))
+}
+
+/// Create a log file for a new MPV session, pruning old sessions first.
+pub fn open_session_log() -> Option<(PathBuf, File)> {
+  let dir = log_dir()?;
+  if let Err(e) = std::fs::create_dir_all(&dir) {
+    log::warn!("Failed to create MPV log directory: {}", e);
+    return None;
+  }
+
+  // Leave room for the file about to be created.
+  let existing = session_logs_in(&dir);
+  let keep = RETENTION.load(Ordering::Relaxed).saturating_sub(1);
+  for stale in logs_to_prune(&existing, keep) {
+    if let Err(e) = std::fs::remove_file(stale) {
+      log::warn!("Failed to remove old MPV log {:?}: {}", stale, e);
+    }
+  }
+
+  let path = dir.join(session_log_name(
+    SystemTime::now()
+      .duration_since(UNIX_EPOCH)
+      .map(|d| d.as_secs())
+      .unwrap_or_default(),
+    std::process::id(),
+  ));
+  // Appending, MPV keeps writing at the end after the log is cut back.
+  match OpenOptions::new().create(true).append(true).open(&path) {
+    Ok(file) => {
+      watch_size(path.clone());
+      Some((path, file))
+    }
+    Err(e) => {
+      log::warn!("Failed to create MPV log {:?}: {}", path, e);
+      None
+    }
+  }
+}
+
+/// Keep the session log at `path` under the size cap until a newer session
+/// starts or the log is removed.
+fn watch_size(path: PathBuf) {
+  let generation = LOG_GENERATION.fetch_add(1, Ordering::Relaxed) + 1;
+  let watcher = std::thread::Builder::new()
+    .name("mpv-log-size".to_string())
+    .spawn(move || {
+      while LOG_GENERATION.load(Ordering::Relaxed) == generation {
+        std::thread::sleep(SIZE_CHECK_INTERVAL);
+        match cap_log(&path, MAX_SESSION_LOG_BYTES, KEPT_LOG_HEAD_BYTES) {
+          Ok(true) => log::info!(
+            "Cut back MPV log {:?} past {} MiB",
+            path,
+            MAX_SESSION_LOG_BYTES / MIB
+          ),
+          Ok(false) => {}
+          Err(e) if e.kind() == io::ErrorKind::NotFound => return,
+          Err(e) => log::warn!("Failed to cap MPV log {:?}: {}", path, e),
+        }
+      }
+    });
+  if let Err(e) = watcher {
+    log::warn!("Failed to watch the MPV log size: {}", e);
+  }
+}
+
+/// Cut the log at `path` back to its first `keep` bytes and a note once it
+/// is larger than `max`. Returns whether it was cut.
+fn cap_log(path: &Path, max: u64, keep: u64) -> io::Result<bool> {
+  let mut file = OpenOptions::new().write(true).open(path)?;
+  let len = file.metadata()?.len();
+  if len <= max {
+    return Ok(false);
+  }
+  file.set_len(keep)?;
+  file.seek(SeekFrom::End(0))?;
+  writeln!(
+    file,
+    "\n[JellyPilot] Cut {} bytes of MPV output here to keep this log under {} bytes.",
+    len - keep,
+    max
+  )?;
+  Ok(true)
+}
+
+/// MPV session logs on disk, oldest first.
+pub fn session_logs() -> Vec<PathBuf> {
+  log_dir()
+    .map(|dir| session_logs_in(&dir))
+    .unwrap_or_default()
+}
+
+fn session_logs_in(dir: &Path) -> Vec<PathBuf> {
+  let Ok(entries) = std::fs::read_dir(dir) else {
+    return Vec::new();
+  };
+  let mut logs: Vec<PathBuf> = entries
+    .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+    .filter(|path| is_session_log(path))
+    .collect();
+  // Names start with a zero-padded timestamp, so lexical order is age order.
+  logs.sort();
+  logs
+}
+
+fn session_log_name(unix_secs: u64, pid: u32) -> String {
+  format!("{}{:012}-{}.{}", LOG_PREFIX, unix_secs, pid, LOG_EXTENSION)
+}
+
+fn is_session_log(path: &Path) -> bool {
+  path.extension().is_some_and(|ext| ext == LOG_EXTENSION)
+    && path
+      .file_name()
+      .and_then(|name| name.to_str())
+      .is_some_and(|name| name.starts_with(LOG_PREFIX))
+}
+
+/// Oldest logs beyond `keep`, given logs sorted oldest first.
+fn logs_to_prune(logs: &[PathBuf], keep: usize) -> &[PathBuf] {
+  &logs[..logs.len().saturating_sub(keep)]
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn session_log_names_sort_by_start_time() {
+    let earlier = session_log_name(999, 42);
+    let later = session_log_name(1_000, 7);
+
+    assert_eq!(earlier, "mpv-000000000999-42.log");
+    assert!(earlier < later);
+  }
+
+  #[test]
+  fn logs_to_prune_drops_oldest_beyond_limit() {
+    let logs: Vec<PathBuf> = (1..=6)
+      .map(|n| PathBuf::from(session_log_name(n, 1)))
+      .collect();
+
+    let pruned = logs_to_prune(&logs, 4);
+
+    assert_eq!(pruned, &logs[..2]);
+    assert!(logs_to_prune(&logs[..3], 4).is_empty());
+  }
+
+  #[test]
+  fn only_mpv_log_files_count_as_session_logs() {
+    assert!(is_session_log(Path::new("/tmp/mpv-000000000001-1.log")));
+    assert!(!is_session_log(Path::new("/tmp/jellypilot.log")));
+    assert!(!is_session_log(Path::new("/tmp/mpv-000000000001-1.txt")));
+  }
+
+  #[test]
+  fn oversized_logs_are_cut_back_to_their_start() {
+    let path = std::env::temp_dir().join(format!("jellypilot-mpv-log-{}.log", std::process::id()));
+    std::fs::write(&path, "startup\n".repeat(20)).unwrap();
+    // MPV's handle, appending like the one it is spawned with.
+    let mut mpv = OpenOptions::new().append(true).open(&path).unwrap();
+
+    assert!(!cap_log(&path, 1024, 16).unwrap());
+    assert!(cap_log(&path, 100, 16).unwrap());
+    write!(mpv, "latest\n").unwrap();
+
+    let text = std::fs::read_to_string(&path).unwrap();
+    assert!(text.starts_with("startup\nstartup\n\n[JellyPilot] Cut 144 bytes"));
+    assert!(text.ends_with("under 100 bytes.\nlatest\n"));
+    drop(mpv);
+    let _ = std::fs::remove_file(&path);
+  }
+}
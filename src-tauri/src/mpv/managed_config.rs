@@ -0,0 +1,100 @@
+//! mpv.conf generated from JellyPilot settings.
+//!
+//! When enabled, MPV is started with `--config-dir` pointing at a directory
+//! JellyPilot owns, so the user's own mpv config is not loaded. When disabled,
+//! MPV reads the user's config untouched.
+
+use std::path::PathBuf;
+
+/// Structured MPV settings written to the managed mpv.conf.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManagedMpvConfig {
+  /// Demuxer cache size in MiB (None = MPV default).
+  pub cache_size_mib: Option<u32>,
+  /// Screen the window opens on (None = MPV default).
+  pub screen: Option<u32>,
+  /// Open the window fullscreen.
+  pub fullscreen: bool,
+  /// Show MPV's on-screen controller.
+  pub osc: bool,
+}
+
+/// Directory JellyPilot uses as MPV's config dir in managed mode.
+pub fn managed_config_dir() -> Option<PathBuf> {
+  dirs::config_dir().map(|p| p.join("jellypilot").join("mpv"))
+}
+
+/// Write the managed mpv.conf and return the directory holding it.
+pub fn write_managed_mpv_conf(config: &ManagedMpvConfig) -> Option<PathBuf> {
+  let dir = managed_config_dir()?;
+  if let Err(e) = std::fs::create_dir_all(&dir) {
+    log::warn!("Failed to create managed MPV config directory: {}", e);
+    return None;
+  }
+
+  let path = dir.join("mpv.conf");
+  if let Err(e) = std::fs::write(&path, render_mpv_conf(config)) {
+    log::warn!("Failed to write managed mpv.conf: {}", e);
+    return None;
+  }
+  log::info!("Wrote managed mpv.conf at {:?}", path);
+
+  Some(dir)
+}
+
+fn render_mpv_conf(config: &ManagedMpvConfig) -> String {
+  let mut conf = String::from(
+    "# Generated by JellyPilot from its MPV settings. Changes here are overwritten.\n",
+  );
+  if let Some(cache_size_mib) = config.cache_size_mib {
+    conf.push_str("cache=yes\n");
+    conf.push_str(&format!("demuxer-max-bytes={}MiB\n", cache_size_mib));
+  }
+  if let Some(screen) = config.screen {
+    conf.push_str(&format!("screen={}\n", screen));
+  }
+  conf.push_str(&format!("fullscreen={}\n", yes_no(config.fullscreen)));
+  conf.push_str(&format!("osc={}\n", yes_no(config.osc)));
+  conf
+}
+
+fn yes_no(value: bool) -> &'static str {
+  if value {
+    "yes"
+  } else {
+    "no"
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn render_writes_only_configured_optional_settings() {
+    let conf = render_mpv_conf(&ManagedMpvConfig {
+      cache_size_mib: None,
+      screen: None,
+      fullscreen: false,
+      osc: true,
+    });
+
+    assert!(!conf.contains("demuxer-max-bytes"));
+    assert!(!conf.contains("screen="));
+    assert!(conf.ends_with("fullscreen=no\nosc=yes\n"));
+  }
+
+  #[test]
+  fn render_writes_cache_and_screen_settings() {
+    let conf = render_mpv_conf(&ManagedMpvConfig {
+      cache_size_mib: Some(512),
+      screen: Some(1),
+      fullscreen: true,
+      osc: false,
+    });
+
+    assert!(conf.contains("cache=yes\ndemuxer-max-bytes=512MiB\n"));
+    assert!(conf.contains("screen=1\n"));
+    assert!(conf.contains("fullscreen=yes\nosc=no\n"));
+  }
+}
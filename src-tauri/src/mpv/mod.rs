@@ -4,13 +4,66 @@
 //! - `process.rs` - MPV binary detection and process spawning
 //! - `ipc.rs` - Async IPC connection (Named Pipes on Windows, Unix Sockets on Linux/macOS)
 //! - `protocol.rs` - JSON command/response types and serialization
+//! - `properties.rs` - Typed values of structured properties like `track-list`
 //! - `client.rs` - High-level MPV client with command methods
+//! - `args.rs` - Runtime translation of extra command-line args
+//! - `keys.rs` - Key names accepted in input.conf
+//! - `key_capture.rs` - Recording the next key pressed in MPV
+//! - `managed_config.rs` - mpv.conf generated from JellyPilot settings
+//! - `logs.rs` - Per-session MPV log files
+//! - `audit.rs` - Bounded record of recent IPC commands for diagnostics
+//! - `version.rs` - `mpv --version` parsing and the minimum supported version
+//! - `bundled.rs` - Optional downloaded MPV build for systems without one
+//! - `display_server.rs` - Wayland/X11 window options on Linux
+//! - `event_queue.rs` - MPV events waiting for the session, with property changes coalesced
+//! - `observers.rs` - Property observations shared by the listeners of one connection
+//! - `lifetime.rs` - End the spawned MPV when JellyPilot exits
+//! - `orphans.rs` - MPVs left running by an earlier JellyPilot
+//! - `timeouts.rs` - How long each IPC command may take to answer
+//! - `pipe_security.rs` - Check that MPV's named pipe is served by MPV (Windows)
+//! - `socket_dir.rs` - Private directory for the IPC socket (Unix)
 
+mod args;
+mod audit;
+mod bundled;
 mod client;
+mod display_server;
+mod event_queue;
 mod ipc;
+mod key_capture;
+mod keys;
+mod lifetime;
+mod logs;
+mod managed_config;
+mod observers;
+mod orphans;
+#[cfg(windows)]
+mod pipe_security;
 mod process;
+mod properties;
 mod protocol;
+#[cfg(not(windows))]
+mod socket_dir;
+mod timeouts;
+mod version;
 
+pub use args::overridden_required_option;
+pub use audit::{AuditEntry, DEFAULT_AUDIT_RETENTION};
+pub use bundled::{bundled_mpv_path, bundled_mpv_status, install_bundled_mpv, BundledMpvStatus};
 pub use client::MpvClient;
-pub use process::{find_mpv, write_input_conf};
+pub use display_server::DisplayServerMode;
+pub use event_queue::EventQueue;
+pub use keys::is_valid_key;
+pub use logs::{session_logs, set_log_retention, DEFAULT_MPV_LOG_RETENTION};
+pub use managed_config::ManagedMpvConfig;
+pub use orphans::process_running;
+pub use process::{
+  builtin_input_bindings, changed_bindings, find_mpv, ipc_path, write_input_conf,
+  FullscreenOptions, InputBinding, ShortcutKeys,
+};
+pub use properties::{Chapter, ChapterList};
 pub use protocol::{MpvEvent, PropertyValue};
+#[cfg(not(windows))]
+pub use socket_dir::{prepare_ipc_dir, prepare_private_dir};
+pub use timeouts::{default_command_timeouts, CommandTimeouts, DEFAULT_COMMAND_TIMEOUT_SECONDS};
+pub use version::{detect_version, MpvVersion, MIN_IPC_MPV_VERSION, MIN_MPV_VERSION};
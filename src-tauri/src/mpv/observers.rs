@@ -0,0 +1,101 @@
+//! Property observations registered on the current MPV connection.
+//!
+//! Observer ids used to be fixed per property and never released, so every
+//! listener that started on a connection registered the same properties
+//! again. The registry hands out one id per property for as long as any
+//! listener wants it; MPV is only told to stop observing when the last one
+//! lets go. A new MPV connection starts with an empty registry.
+
+use std::collections::BTreeMap;
+
+/// Observer ids and their users by property name.
+#[derive(Debug, Default)]
+pub struct ObserverRegistry {
+  next_id: i64,
+  observed: BTreeMap<String, Observation>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Observation {
+  id: i64,
+  users: u32,
+}
+
+impl ObserverRegistry {
+  /// Add a user of `property`. Returns the observer id when MPV still has to
+  /// be told to observe it.
+  pub fn acquire(&mut self, property: &str) -> Option<i64> {
+    if let Some(observation) = self.observed.get_mut(property) {
+      observation.users += 1;
+      return None;
+    }
+    self.next_id += 1;
+    let id = self.next_id;
+    self
+      .observed
+      .insert(property.to_string(), Observation { id, users: 1 });
+    Some(id)
+  }
+
+  /// Drop a user of `property`. Returns the observer id when it was the last
+  /// one and MPV should stop observing it.
+  pub fn release(&mut self, property: &str) -> Option<i64> {
+    let observation = self.observed.get_mut(property)?;
+    observation.users -= 1;
+    if observation.users > 0 {
+      return None;
+    }
+    self
+      .observed
+      .remove(property)
+      .map(|observation| observation.id)
+  }
+
+  /// Forget `property` whatever its users, e.g. after MPV refused to observe it.
+  pub fn forget(&mut self, property: &str) {
+    self.observed.remove(property);
+  }
+
+  /// Start over for a new connection; ids are not reused.
+  pub fn reset(&mut self) {
+    self.observed.clear();
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn each_property_is_registered_once() {
+    let mut registry = ObserverRegistry::default();
+
+    let pause = registry.acquire("pause");
+    assert!(pause.is_some());
+    assert_eq!(registry.acquire("pause"), None);
+    assert_ne!(registry.acquire("volume"), pause);
+  }
+
+  #[test]
+  fn the_last_user_unobserves() {
+    let mut registry = ObserverRegistry::default();
+    let id = registry.acquire("time-pos");
+    registry.acquire("time-pos");
+
+    assert_eq!(registry.release("time-pos"), None);
+    assert_eq!(registry.release("time-pos"), id);
+    assert_eq!(registry.release("time-pos"), None);
+    assert!(registry.acquire("time-pos").is_some());
+  }
+
+  #[test]
+  fn ids_are_not_reused_after_a_reset() {
+    let mut registry = ObserverRegistry::default();
+    let first = registry.acquire("pause");
+    registry.reset();
+
+    let second = registry.acquire("pause");
+    assert!(second.is_some());
+    assert_ne!(first, second);
+  }
+}
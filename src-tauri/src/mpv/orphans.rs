@@ -0,0 +1,115 @@
+//! MPV instances an earlier JellyPilot left running.
+//!
+//! Every JellyPilot names its IPC socket or pipe after its own PID. After a
+//! crash, its MPV keeps running and keeps the endpoint open, with nobody
+//! reporting progress for what it plays. Endpoints whose JellyPilot is gone
+//! are found here, so the client can quit or adopt their MPV before it
+//! spawns another one.
+
+use std::path::PathBuf;
+
+/// File name prefix of MPV endpoints created for JellyPilot.
+const ENDPOINT_PREFIX: &str = "jellypilot-mpv-";
+
+/// IPC endpoint of an MPV whose JellyPilot is no longer running.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrphanEndpoint {
+  pub path: String,
+  /// PID of the JellyPilot that spawned the MPV.
+  pub owner_pid: u32,
+}
+
+/// Endpoints of MPVs spawned by JellyPilots that have exited.
+pub fn find_orphans() -> Vec<OrphanEndpoint> {
+  let Ok(entries) = std::fs::read_dir(endpoint_dir()) else {
+    return Vec::new();
+  };
+  let own_pid = std::process::id();
+  entries
+    .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+    .filter_map(|name| {
+      let owner_pid = owner_pid(&name)?;
+      Some(OrphanEndpoint {
+        path: endpoint_path(&name),
+        owner_pid,
+      })
+    })
+    .filter(|endpoint| endpoint.owner_pid != own_pid && !process_running(endpoint.owner_pid))
+    .collect()
+}
+
+/// Remove the socket of an MPV that is gone.
+#[cfg(not(windows))]
+pub fn remove_endpoint(path: &str) {
+  let _ = std::fs::remove_file(path);
+}
+
+/// Named pipes vanish with the MPV that created them.
+#[cfg(windows)]
+pub fn remove_endpoint(_path: &str) {}
+
+/// PID of the JellyPilot an endpoint was named after.
+fn owner_pid(name: &str) -> Option<u32> {
+  let pid = name.strip_prefix(ENDPOINT_PREFIX)?;
+  pid.strip_suffix(".sock").unwrap_or(pid).parse().ok()
+}
+
+#[cfg(windows)]
+fn endpoint_dir() -> PathBuf {
+  PathBuf::from(r"\\.\pipe\")
+}
+
+#[cfg(windows)]
+fn endpoint_path(name: &str) -> String {
+  format!(r"\\.\pipe\{}", name)
+}
+
+#[cfg(not(windows))]
+fn endpoint_dir() -> PathBuf {
+  super::socket_dir::ipc_dir()
+}
+
+#[cfg(not(windows))]
+fn endpoint_path(name: &str) -> String {
+  endpoint_dir().join(name).to_string_lossy().into_owned()
+}
+
+/// Whether a process with id `pid` is running.
+#[cfg(windows)]
+pub fn process_running(pid: u32) -> bool {
+  super::pipe_security::process_exists(pid)
+}
+
+#[cfg(target_os = "linux")]
+pub fn process_running(pid: u32) -> bool {
+  std::path::Path::new("/proc").join(pid.to_string()).exists()
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+pub fn process_running(pid: u32) -> bool {
+  std::process::Command::new("kill")
+    .args(["-0", &pid.to_string()])
+    .stdout(std::process::Stdio::null())
+    .stderr(std::process::Stdio::null())
+    .status()
+    .is_ok_and(|status| status.success())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn owner_pids_come_from_endpoint_names() {
+    assert_eq!(owner_pid("jellypilot-mpv-4242.sock"), Some(4242));
+    assert_eq!(owner_pid("jellypilot-mpv-4242"), Some(4242));
+    assert_eq!(owner_pid("jellypilot-mpv-x.sock"), None);
+    assert_eq!(owner_pid("mpv-4242.sock"), None);
+    assert_eq!(owner_pid(".owner-check-4242"), None);
+  }
+
+  #[test]
+  fn this_process_counts_as_running() {
+    assert!(process_running(std::process::id()));
+  }
+}
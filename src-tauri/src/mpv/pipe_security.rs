@@ -0,0 +1,291 @@
+//! Checks on the process serving MPV's named pipe (Windows only).
+//!
+//! The pipe name is predictable, so another program could create it before
+//! MPV does and receive the stream URLs, access token included, that are
+//! meant for MPV. Before talking to a pipe, its server must be the MPV we
+//! spawned or a process it started: launchers such as Scoop's shims run the
+//! real `mpv.exe` as their child. A pipe left by an earlier JellyPilot must be
+//! served by an MPV running as the current user.
+
+use std::ffi::c_void;
+use std::os::windows::io::AsRawHandle;
+use std::path::Path;
+
+use tokio::net::windows::named_pipe::NamedPipeClient;
+
+/// Wrapper levels between the spawned process and MPV that are accepted.
+const MAX_WRAPPER_DEPTH: usize = 4;
+
+const TH32CS_SNAPPROCESS: u32 = 0x2;
+const INVALID_HANDLE_VALUE: isize = -1;
+const MAX_PATH: usize = 260;
+const PROCESS_QUERY_LIMITED_INFORMATION: u32 = 0x1000;
+const TOKEN_QUERY: u32 = 0x0008;
+/// `TokenUser` from the `TOKEN_INFORMATION_CLASS` enum.
+const TOKEN_USER_CLASS: u32 = 1;
+
+/// `PROCESSENTRY32W` from tlhelp32.h.
+#[repr(C)]
+struct ProcessEntry {
+  size: u32,
+  usage: u32,
+  process_id: u32,
+  default_heap_id: usize,
+  module_id: u32,
+  threads: u32,
+  parent_process_id: u32,
+  priority_class_base: i32,
+  flags: u32,
+  exe_file: [u16; MAX_PATH],
+}
+
+#[link(name = "kernel32")]
+extern "system" {
+  fn GetNamedPipeServerProcessId(pipe: *mut c_void, server_pid: *mut u32) -> i32;
+  fn CreateToolhelp32Snapshot(flags: u32, process_id: u32) -> *mut c_void;
+  fn Process32FirstW(snapshot: *mut c_void, entry: *mut ProcessEntry) -> i32;
+  fn Process32NextW(snapshot: *mut c_void, entry: *mut ProcessEntry) -> i32;
+  fn CloseHandle(handle: *mut c_void) -> i32;
+  fn OpenProcess(access: u32, inherit_handle: i32, process_id: u32) -> *mut c_void;
+  fn GetCurrentProcess() -> *mut c_void;
+}
+
+#[link(name = "advapi32")]
+extern "system" {
+  fn OpenProcessToken(process: *mut c_void, access: u32, token: *mut *mut c_void) -> i32;
+  fn GetTokenInformation(
+    token: *mut c_void,
+    class: u32,
+    info: *mut c_void,
+    length: u32,
+    returned: *mut u32,
+  ) -> i32;
+  fn EqualSid(a: *mut c_void, b: *mut c_void) -> i32;
+}
+
+/// `TOKEN_USER` from winnt.h; the SID it points to follows in the same buffer.
+#[repr(C)]
+struct TokenUser {
+  sid: *mut c_void,
+  attributes: u32,
+}
+
+/// Process that created the pipe `client` is connected to.
+pub fn server_process_id(client: &NamedPipeClient) -> std::io::Result<u32> {
+  let mut pid = 0;
+  // SAFETY: the handle stays open while `client` is borrowed, and `pid`
+  // outlives the call.
+  let ok = unsafe { GetNamedPipeServerProcessId(client.as_raw_handle(), &mut pid) };
+  if ok == 0 {
+    return Err(std::io::Error::last_os_error());
+  }
+  Ok(pid)
+}
+
+/// Whether `pid` is `ancestor` or was started by it, a few levels deep.
+pub fn is_same_or_descendant(pid: u32, ancestor: u32) -> bool {
+  if pid == ancestor {
+    return true;
+  }
+  let parents = match parent_processes() {
+    Ok(parents) => parents,
+    Err(e) => {
+      log::warn!("Failed to list processes: {}", e);
+      return false;
+    }
+  };
+  let mut current = pid;
+  for _ in 0..MAX_WRAPPER_DEPTH {
+    let Some(&parent) = parents.get(&current) else {
+      return false;
+    };
+    if parent == ancestor {
+      return true;
+    }
+    current = parent;
+  }
+  false
+}
+
+/// Whether `pid` is an MPV running as the current user.
+pub fn is_own_mpv(pid: u32) -> bool {
+  let executable = match process_executable(pid) {
+    Ok(Some(executable)) => executable,
+    Ok(None) => return false,
+    Err(e) => {
+      log::warn!("Failed to list processes: {}", e);
+      return false;
+    }
+  };
+  if !is_mpv_executable(&executable) {
+    log::warn!("Process {} serving the MPV pipe is {}", pid, executable);
+    return false;
+  }
+  match runs_as_current_user(pid) {
+    Ok(own) => own,
+    Err(e) => {
+      log::warn!("Failed to check the owner of process {}: {}", pid, e);
+      false
+    }
+  }
+}
+
+fn is_mpv_executable(name: &str) -> bool {
+  Path::new(name)
+    .file_stem()
+    .is_some_and(|stem| stem.eq_ignore_ascii_case("mpv"))
+}
+
+/// Whether a process with this PID is running.
+pub fn process_exists(pid: u32) -> bool {
+  parent_processes().is_ok_and(|parents| parents.contains_key(&pid))
+}
+
+/// Parent of every running process, by PID.
+fn parent_processes() -> std::io::Result<std::collections::HashMap<u32, u32>> {
+  let mut parents = std::collections::HashMap::new();
+  for_each_process(|entry| {
+    parents.insert(entry.process_id, entry.parent_process_id);
+  })?;
+  Ok(parents)
+}
+
+/// Executable file name of the process with this PID.
+fn process_executable(pid: u32) -> std::io::Result<Option<String>> {
+  let mut executable = None;
+  for_each_process(|entry| {
+    if entry.process_id == pid {
+      let len = entry
+        .exe_file
+        .iter()
+        .position(|c| *c == 0)
+        .unwrap_or(MAX_PATH);
+      executable = Some(String::from_utf16_lossy(&entry.exe_file[..len]));
+    }
+  })?;
+  Ok(executable)
+}
+
+fn for_each_process(mut visit: impl FnMut(&ProcessEntry)) -> std::io::Result<()> {
+  // SAFETY: plain call; the returned handle is checked and closed below.
+  let snapshot = unsafe { CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0) };
+  if snapshot as isize == INVALID_HANDLE_VALUE {
+    return Err(std::io::Error::last_os_error());
+  }
+
+  let mut entry = ProcessEntry {
+    size: std::mem::size_of::<ProcessEntry>() as u32,
+    usage: 0,
+    process_id: 0,
+    default_heap_id: 0,
+    module_id: 0,
+    threads: 0,
+    parent_process_id: 0,
+    priority_class_base: 0,
+    flags: 0,
+    exe_file: [0; MAX_PATH],
+  };
+  // SAFETY: `snapshot` is a valid process snapshot and `entry` has its size
+  // set as the API requires.
+  let mut more = unsafe { Process32FirstW(snapshot, &mut entry) } != 0;
+  while more {
+    visit(&entry);
+    // SAFETY: as above.
+    more = unsafe { Process32NextW(snapshot, &mut entry) } != 0;
+  }
+  // SAFETY: `snapshot` is open and not used afterwards.
+  unsafe { CloseHandle(snapshot) };
+  Ok(())
+}
+
+/// Whether the process with this PID runs as the same user as JellyPilot.
+fn runs_as_current_user(pid: u32) -> std::io::Result<bool> {
+  // SAFETY: plain call; the returned handle is checked and closed below.
+  let process = unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid) };
+  if process.is_null() {
+    return Err(std::io::Error::last_os_error());
+  }
+  let theirs = token_user(process);
+  // SAFETY: `process` is open and not used afterwards.
+  unsafe { CloseHandle(process) };
+  // SAFETY: the pseudo handle of the current process needs no closing.
+  let mut ours = token_user(unsafe { GetCurrentProcess() })?;
+  let mut theirs = theirs?;
+  // SAFETY: both buffers start with a `TOKEN_USER` whose SID points into
+  // the buffer, and both outlive the call.
+  Ok(unsafe { EqualSid(user_sid(&mut theirs), user_sid(&mut ours)) } != 0)
+}
+
+/// `TOKEN_USER` of a process, in a buffer of `usize`s so it is aligned.
+fn token_user(process: *mut c_void) -> std::io::Result<Vec<usize>> {
+  let mut token = std::ptr::null_mut();
+  // SAFETY: `process` is a valid process handle and `token` outlives the call.
+  if unsafe { OpenProcessToken(process, TOKEN_QUERY, &mut token) } == 0 {
+    return Err(std::io::Error::last_os_error());
+  }
+  let mut needed = 0;
+  // SAFETY: asks for the size only; `needed` outlives the call.
+  unsafe {
+    GetTokenInformation(
+      token,
+      TOKEN_USER_CLASS,
+      std::ptr::null_mut(),
+      0,
+      &mut needed,
+    )
+  };
+  let mut buffer = vec![0usize; (needed as usize).div_ceil(std::mem::size_of::<usize>())];
+  // SAFETY: `buffer` holds at least `needed` bytes.
+  let ok = unsafe {
+    GetTokenInformation(
+      token,
+      TOKEN_USER_CLASS,
+      buffer.as_mut_ptr().cast(),
+      (buffer.len() * std::mem::size_of::<usize>()) as u32,
+      &mut needed,
+    )
+  };
+  let error = std::io::Error::last_os_error();
+  // SAFETY: `token` is open and not used afterwards.
+  unsafe { CloseHandle(token) };
+  if ok == 0 {
+    return Err(error);
+  }
+  Ok(buffer)
+}
+
+fn user_sid(token_user: &mut [usize]) -> *mut c_void {
+  // SAFETY: the buffer was filled by `GetTokenInformation(TokenUser)`.
+  unsafe { (*token_user.as_ptr().cast::<TokenUser>()).sid }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn a_child_process_descends_from_its_parent() {
+    let mut child = std::process::Command::new("cmd")
+      .args(["/C", "ping -n 3 127.0.0.1 > NUL"])
+      .spawn()
+      .unwrap();
+
+    assert!(is_same_or_descendant(child.id(), std::process::id()));
+    assert!(is_same_or_descendant(
+      std::process::id(),
+      std::process::id()
+    ));
+    assert!(!is_same_or_descendant(std::process::id(), child.id()));
+    let _ = child.kill();
+  }
+
+  #[test]
+  fn only_an_mpv_of_this_user_counts_as_own() {
+    assert!(runs_as_current_user(std::process::id()).unwrap());
+    // The test binary runs as this user but is no MPV.
+    assert!(!is_own_mpv(std::process::id()));
+    assert!(is_mpv_executable("mpv.exe"));
+    assert!(is_mpv_executable("MPV.COM"));
+    assert!(!is_mpv_executable("mpv-helper.exe"));
+  }
+}
@@ -4,19 +4,35 @@ use std::path::PathBuf;
 use std::process::{Child, Command, Stdio};
 use thiserror::Error;
 
+use super::args::strip_conflicting_args;
+use super::lifetime::{bind_child, bind_command};
+use super::logs::open_session_log;
+use super::managed_config::{write_managed_mpv_conf, ManagedMpvConfig};
+use crate::redact::redact;
+
 #[derive(Error, Debug)]
 pub enum ProcessError {
   #[error("MPV executable not found")]
   NotFound,
   #[error("Failed to spawn MPV: {0}")]
   SpawnFailed(#[from] std::io::Error),
+  #[error("Unsafe MPV socket directory: {0}")]
+  SocketDir(std::io::Error),
+}
+
+/// Fullscreen behavior applied when MPV is spawned.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FullscreenOptions {
+  /// Start fullscreen (`--fs`).
+  pub on_start: bool,
+  /// Monitor used for fullscreen (`--fs-screen`), None = MPV default.
+  pub screen: Option<u32>,
 }
 
 /// Get the IPC socket/pipe path for MPV.
 /// Uses PID suffix to prevent collisions when multiple JellyPilot instances run.
 ///
-/// On Linux, respects `XDG_RUNTIME_DIR` for AppImage/Flatpak compatibility
-/// where `/tmp` may be inaccessible inside sandboxes.
+/// On Unix the socket is in a private directory, see `socket_dir.rs`.
 pub fn ipc_path() -> String {
   let pid = std::process::id();
   #[cfg(windows)]
@@ -25,8 +41,10 @@ pub fn ipc_path() -> String {
   }
   #[cfg(not(windows))]
   {
-    let base_dir = std::env::var("XDG_RUNTIME_DIR").unwrap_or_else(|_| "/tmp".to_string());
-    format!("{}/jellypilot-mpv-{}.sock", base_dir, pid)
+    super::socket_dir::ipc_dir()
+      .join(format!("jellypilot-mpv-{}.sock", pid))
+      .to_string_lossy()
+      .into_owned()
   }
 }
 
@@ -59,13 +77,164 @@ fn migrated_legacy_keybindings(input: &str) -> (String, String, String) {
   )
 }
 
-/// Write JellyPilot's input.conf with the specified keybindings.
-/// Always overwrites the file with the provided keybindings.
-pub fn write_input_conf(
-  keybind_next: &str,
-  keybind_prev: &str,
-  keybind_intro_skip: &str,
-) -> Option<PathBuf> {
+const MANAGED_BLOCK_START: &str = "# >>> JellyPilot managed bindings >>>";
+const MANAGED_BLOCK_END: &str = "# <<< JellyPilot managed bindings <<<";
+/// First line of the input.conf written before the managed block existed.
+const LEGACY_GENERATED_HEADER: &str = "# JellyPilot MPV Keybindings";
+
+/// A key bound to an MPV input command inside JellyPilot's managed block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputBinding {
+  pub key: String,
+  pub command: String,
+}
+
+impl InputBinding {
+  pub fn new(key: impl Into<String>, command: impl Into<String>) -> Self {
+    Self {
+      key: key.into(),
+      command: command.into(),
+    }
+  }
+}
+
+/// Keys for JellyPilot's own MPV shortcuts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShortcutKeys {
+  pub next: String,
+  pub prev: String,
+  pub intro_skip: String,
+  pub toggle_intro_skip: String,
+  pub toggle_watched: String,
+  pub toggle_favorite: String,
+  pub speed_up: String,
+  pub speed_down: String,
+  pub stats: String,
+  pub stop_idle: String,
+  pub secondary_subtitle: String,
+  pub ab_loop: String,
+  pub screenshot: String,
+}
+
+impl Default for ShortcutKeys {
+  fn default() -> Self {
+    Self {
+      next: "Shift+>".to_string(),
+      prev: "Shift+<".to_string(),
+      intro_skip: "g".to_string(),
+      toggle_intro_skip: "Alt+g".to_string(),
+      toggle_watched: "Alt+w".to_string(),
+      toggle_favorite: "Alt+f".to_string(),
+      speed_up: "Alt+]".to_string(),
+      speed_down: "Alt+[".to_string(),
+      stats: "Alt+i".to_string(),
+      stop_idle: "Alt+q".to_string(),
+      secondary_subtitle: "Alt+j".to_string(),
+      ab_loop: "Alt+l".to_string(),
+      screenshot: "Alt+s".to_string(),
+    }
+  }
+}
+
+/// JellyPilot's own shortcut bindings, each sending a script-message.
+pub fn builtin_input_bindings(keys: &ShortcutKeys) -> Vec<InputBinding> {
+  [
+    (&keys.next, "jellypilot-next"),
+    (&keys.prev, "jellypilot-prev"),
+    (&keys.intro_skip, "jellypilot-skip-intro"),
+    (&keys.toggle_intro_skip, "jellypilot-toggle-intro-skip"),
+    (&keys.toggle_watched, "jellypilot-toggle-watched"),
+    (&keys.toggle_favorite, "jellypilot-toggle-favorite"),
+    (&keys.speed_up, "jellypilot-speed-up"),
+    (&keys.speed_down, "jellypilot-speed-down"),
+    (&keys.stats, "jellypilot-toggle-stats"),
+    (&keys.stop_idle, "jellypilot-stop-idle"),
+    (
+      &keys.secondary_subtitle,
+      "jellypilot-cycle-secondary-subtitle",
+    ),
+    (&keys.ab_loop, "jellypilot-ab-loop"),
+    (&keys.screenshot, "jellypilot-screenshot"),
+  ]
+  .into_iter()
+  .map(|(key, message)| InputBinding::new(key.as_str(), format!("script-message {}", message)))
+  .collect()
+}
+
+/// Bindings that bring a running MPV from `previous` to `current`: new or
+/// changed ones, and keys only `previous` bound set to `ignore`.
+///
+/// An ignored key shadows whatever the user's own input.conf binds it to
+/// until MPV restarts.
+pub fn changed_bindings(previous: &[InputBinding], current: &[InputBinding]) -> Vec<InputBinding> {
+  let mut changed: Vec<InputBinding> = current
+    .iter()
+    .filter(|binding| !previous.contains(binding))
+    .cloned()
+    .collect();
+  changed.extend(
+    previous
+      .iter()
+      .filter(|old| !current.iter().any(|binding| binding.key == old.key))
+      .map(|old| InputBinding::new(old.key.as_str(), "ignore")),
+  );
+  changed
+}
+
+fn render_managed_block(bindings: &[InputBinding]) -> String {
+  let mut block = format!(
+    "{}\n# Rewritten from JellyPilot Settings. Add your own bindings outside this block.\n",
+    MANAGED_BLOCK_START
+  );
+  for binding in bindings {
+    block.push_str(&format!("{} {}\n", binding.key, binding.command));
+  }
+  block.push_str(MANAGED_BLOCK_END);
+  block.push('\n');
+  block
+}
+
+/// Replace the managed block in `existing`, keeping every line outside it.
+///
+/// Files without a block get one appended, except files generated entirely by
+/// older JellyPilot versions, which are replaced.
+fn merge_managed_block(existing: &str, block: &str) -> String {
+  let lines: Vec<&str> = existing.lines().collect();
+  let start = lines
+    .iter()
+    .position(|line| line.trim() == MANAGED_BLOCK_START);
+  let end = start.and_then(|start| {
+    lines[start..]
+      .iter()
+      .position(|line| line.trim() == MANAGED_BLOCK_END)
+      .map(|offset| start + offset)
+  });
+
+  let (before, after) = match (start, end) {
+    (Some(start), Some(end)) => (&lines[..start], &lines[end + 1..]),
+    _ if existing.trim_start().starts_with(LEGACY_GENERATED_HEADER) => (&[][..], &[][..]),
+    _ => (&lines[..], &[][..]),
+  };
+
+  let mut merged = String::new();
+  for line in before {
+    merged.push_str(line);
+    merged.push('\n');
+  }
+  if start.is_none() && before.last().is_some_and(|line| !line.trim().is_empty()) {
+    merged.push('\n');
+  }
+  merged.push_str(block);
+  for line in after {
+    merged.push_str(line);
+    merged.push('\n');
+  }
+  merged
+}
+
+/// Write JellyPilot's bindings into the managed block of its input.conf.
+/// Lines the user added outside the block are preserved.
+pub fn write_input_conf(bindings: &[InputBinding]) -> Option<PathBuf> {
   let path = jellypilot_input_conf_path()?;
 
   // Create parent directory if needed
@@ -78,19 +247,18 @@ pub fn write_input_conf(
     }
   }
 
-  let bindings = format!(
-    r#"# JellyPilot MPV Keybindings
-# These keybindings are used by JellyPilot to control episode navigation.
-# You can customize these bindings in JellyPilot Settings.
-
-{} script-message jellypilot-next    # Play next episode
-{} script-message jellypilot-prev    # Play previous episode
-{} script-message jellypilot-skip-intro    # Skip active Intro Skipper segment
-"#,
-    keybind_next, keybind_prev, keybind_intro_skip
-  );
+  let existing = match std::fs::read_to_string(&path) {
+    Ok(existing) => existing,
+    Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+    Err(e) => {
+      // Never clobber a file we could not read.
+      log::warn!("Failed to read JellyPilot input.conf: {}", e);
+      return None;
+    }
+  };
+  let merged = merge_managed_block(&existing, &render_managed_block(bindings));
 
-  if let Err(e) = std::fs::write(&path, bindings) {
+  if let Err(e) = std::fs::write(&path, merged) {
     log::warn!("Failed to write JellyPilot input.conf: {}", e);
     return None;
   }
@@ -110,9 +278,14 @@ fn ensure_input_conf() -> Option<PathBuf> {
       .and_then(|legacy_path| std::fs::read_to_string(legacy_path).ok())
       .map(|legacy| migrated_legacy_keybindings(&legacy))
     {
-      return write_input_conf(&next, &prev, &intro);
+      return write_input_conf(&builtin_input_bindings(&ShortcutKeys {
+        next,
+        prev,
+        intro_skip: intro,
+        ..ShortcutKeys::default()
+      }));
     }
-    return write_input_conf("Shift+>", "Shift+<", "g");
+    return write_input_conf(&builtin_input_bindings(&ShortcutKeys::default()));
   }
 
   Some(path)
@@ -206,18 +379,41 @@ pub fn find_mpv() -> Option<PathBuf> {
   None
 }
 
+/// A spawned MPV process.
+pub struct SpawnedMpv {
+  pub child: Child,
+  /// Extra args left out because they would break control of MPV.
+  pub removed_args: Vec<String>,
+}
+
 /// Spawn MPV process with IPC server enabled.
-pub fn spawn_mpv(mpv_path: Option<&PathBuf>, extra_args: &[String]) -> Result<Child, ProcessError> {
+pub fn spawn_mpv(
+  mpv_path: Option<&PathBuf>,
+  extra_args: &[String],
+  managed_config: Option<&ManagedMpvConfig>,
+  fullscreen: &FullscreenOptions,
+  display_server_args: &[String],
+  outlive_app: bool,
+) -> Result<SpawnedMpv, ProcessError> {
   let mpv_exe = mpv_path
     .cloned()
     .or_else(find_mpv)
     .ok_or(ProcessError::NotFound)?;
 
+  #[cfg(not(windows))]
+  super::socket_dir::prepare_ipc_dir().map_err(ProcessError::SocketDir)?;
   let ipc = ipc_path();
 
   log::info!("Spawning MPV: {:?} with IPC: {}", mpv_exe, ipc);
+  let (extra_args, removed_args) = strip_conflicting_args(extra_args);
+  for arg in &removed_args {
+    log::warn!(
+      "Leaving out MPV arg {}: it overrides an option JellyPilot needs",
+      redact(arg)
+    );
+  }
   if !extra_args.is_empty() {
-    log::info!("Extra MPV args: {:?}", extra_args);
+    log::info!("Extra MPV args: {}", redact(&format!("{:?}", extra_args)));
   }
 
   let mut cmd = Command::new(&mpv_exe);
@@ -225,9 +421,42 @@ pub fn spawn_mpv(mpv_path: Option<&PathBuf>, extra_args: &[String]) -> Result<Ch
     .arg(format!("--input-ipc-server={}", ipc))
     .arg("--idle")
     .arg("--force-window")
-    .arg("--keep-open=no")
-    .arg("--no-terminal")
-    .arg("--osc");
+    .arg("--keep-open=no");
+
+  // The managed mpv.conf decides the OSC; a command-line `--osc` would override it.
+  match managed_config.and_then(write_managed_mpv_conf) {
+    Some(config_dir) => {
+      cmd.arg(format!("--config-dir={}", config_dir.display()));
+      log::info!("Using managed MPV config dir: {:?}", config_dir);
+    }
+    None => {
+      cmd.arg("--osc");
+    }
+  }
+
+  // Capture MPV's terminal output in a per-session log. `--no-terminal` would
+  // silence it entirely, so only keyboard input from the terminal is disabled.
+  let session_log = open_session_log();
+  match &session_log {
+    Some((path, _)) => {
+      cmd.arg("--no-input-terminal").arg("--msg-color=no");
+      log::info!("Writing MPV output to {:?}", path);
+    }
+    None => {
+      cmd.arg("--no-terminal");
+    }
+  }
+
+  if let Some(screen) = fullscreen.screen {
+    cmd.arg(format!("--fs-screen={}", screen));
+  }
+  if fullscreen.on_start {
+    cmd.arg("--fs");
+  }
+  if !display_server_args.is_empty() {
+    log::info!("Display server MPV args: {:?}", display_server_args);
+    cmd.args(display_server_args);
+  }
 
   // Add JellyPilot keybindings via input.conf
   // Using --input-conf appends to (not replaces) the user's input.conf
@@ -237,17 +466,34 @@ pub fn spawn_mpv(mpv_path: Option<&PathBuf>, extra_args: &[String]) -> Result<Ch
   }
 
   // Add user-specified extra arguments
-  for arg in extra_args {
+  for arg in &extra_args {
     cmd.arg(arg);
   }
 
+  let (stdout, stderr) = match session_log.and_then(|(_, file)| {
+    let stderr = file.try_clone().ok()?;
+    Some((Stdio::from(file), Stdio::from(stderr)))
+  }) {
+    Some(log_stdio) => log_stdio,
+    None => (Stdio::null(), Stdio::null()),
+  };
+
+  if !outlive_app {
+    bind_command(&mut cmd);
+  }
   let child = cmd
     .stdin(Stdio::null())
-    .stdout(Stdio::null())
-    .stderr(Stdio::null())
+    .stdout(stdout)
+    .stderr(stderr)
     .spawn()?;
+  if !outlive_app {
+    bind_child(&child);
+  }
 
-  Ok(child)
+  Ok(SpawnedMpv {
+    child,
+    removed_args,
+  })
 }
 
 /// Kill MPV process and cleanup socket.
@@ -262,7 +508,31 @@ pub fn cleanup_ipc() {
 
 #[cfg(test)]
 mod tests {
-  use super::migrated_legacy_keybindings;
+  use super::*;
+
+  #[test]
+  fn changed_bindings_rebind_new_keys_and_ignore_dropped_ones() {
+    let previous = [
+      InputBinding::new("g", "script-message jellypilot-skip-intro"),
+      InputBinding::new("Alt+w", "script-message jellypilot-toggle-watched"),
+      InputBinding::new("Ctrl+x", "quit"),
+    ];
+    let current = [
+      InputBinding::new("s", "script-message jellypilot-skip-intro"),
+      InputBinding::new("Alt+w", "script-message jellypilot-toggle-watched"),
+      InputBinding::new("Ctrl+x", "stop"),
+    ];
+
+    assert_eq!(
+      changed_bindings(&previous, &current),
+      [
+        InputBinding::new("s", "script-message jellypilot-skip-intro"),
+        InputBinding::new("Ctrl+x", "stop"),
+        InputBinding::new("g", "ignore"),
+      ]
+    );
+    assert!(changed_bindings(&current, &current).is_empty());
+  }
 
   #[test]
   fn migrated_legacy_keybindings_maps_old_script_messages_to_new_writer_keys() {
@@ -277,4 +547,63 @@ i script-message jmsr-skip-intro
       ("Alt+n".to_string(), "Alt+p".to_string(), "i".to_string())
     );
   }
+
+  #[test]
+  fn builtin_bindings_send_one_script_message_per_shortcut() {
+    let keys = ShortcutKeys {
+      speed_up: "Ctrl+Up".to_string(),
+      ..ShortcutKeys::default()
+    };
+
+    let bindings = builtin_input_bindings(&keys);
+
+    assert_eq!(bindings.len(), 13);
+    assert!(bindings.contains(&InputBinding::new(
+      "Ctrl+Up",
+      "script-message jellypilot-speed-up"
+    )));
+    assert!(bindings
+      .iter()
+      .all(|binding| binding.command.starts_with("script-message jellypilot-")));
+  }
+
+  #[test]
+  fn managed_block_is_appended_after_user_bindings() {
+    let block = render_managed_block(&[InputBinding::new(
+      "g",
+      "script-message jellypilot-skip-intro",
+    )]);
+
+    let merged = merge_managed_block("a cycle audio\n", &block);
+
+    assert_eq!(merged, format!("a cycle audio\n\n{}", block));
+  }
+
+  #[test]
+  fn managed_block_is_replaced_in_place_and_user_lines_survive() {
+    let old_block =
+      render_managed_block(&[InputBinding::new("n", "script-message jellypilot-next")]);
+    let existing = format!("a cycle audio\n{}b cycle sub\n", old_block);
+    let new_block = render_managed_block(&[
+      InputBinding::new("N", "script-message jellypilot-next"),
+      InputBinding::new("Ctrl+w", "quit"),
+    ]);
+
+    let merged = merge_managed_block(&existing, &new_block);
+
+    assert_eq!(merged, format!("a cycle audio\n{}b cycle sub\n", new_block));
+    assert_eq!(merge_managed_block(&merged, &new_block), merged);
+  }
+
+  #[test]
+  fn file_generated_by_older_version_is_replaced_by_managed_block() {
+    let legacy = r#"# JellyPilot MPV Keybindings
+# These keybindings are used by JellyPilot to control episode navigation.
+
+Shift+> script-message jellypilot-next    # Play next episode
+"#;
+    let block = render_managed_block(&builtin_input_bindings(&ShortcutKeys::default()));
+
+    assert_eq!(merge_managed_block(legacy, &block), block);
+  }
 }
@@ -0,0 +1,107 @@
+//! Typed values of structured MPV properties.
+//!
+//! Read them with `MpvClient::get_property_as`; fields MPV leaves out for
+//! some files (an untitled chapter, a track without a language) are optional.
+
+use serde::Deserialize;
+
+/// Entry of MPV's `chapter-list` property.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Chapter {
+  #[serde(default)]
+  pub title: Option<String>,
+  pub time: f64,
+}
+
+/// MPV's `chapter-list` property.
+pub type ChapterList = Vec<Chapter>;
+
+/// Entry of MPV's `track-list` property.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+#[allow(dead_code)]
+pub struct Track {
+  /// ID to select the track with, e.g. as `sid`; unique per type only.
+  pub id: i64,
+  /// `video`, `audio` or `sub`.
+  #[serde(rename = "type")]
+  pub kind: String,
+  #[serde(default)]
+  pub title: Option<String>,
+  #[serde(default)]
+  pub lang: Option<String>,
+  #[serde(default)]
+  pub codec: Option<String>,
+  #[serde(default)]
+  pub selected: bool,
+  #[serde(default)]
+  pub external: bool,
+}
+
+/// MPV's `track-list` property.
+#[allow(dead_code)]
+pub type TrackList = Vec<Track>;
+
+/// MPV's `video-params` property, the decoded video before filters.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[allow(dead_code)]
+pub struct VideoParams {
+  pub w: u32,
+  pub h: u32,
+  #[serde(default)]
+  pub aspect: Option<f64>,
+  #[serde(default)]
+  pub pixelformat: Option<String>,
+  #[serde(default)]
+  pub primaries: Option<String>,
+  #[serde(default)]
+  pub gamma: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn track_list_decodes_mpv_field_names() {
+    let tracks: TrackList = serde_json::from_value(serde_json::json!([
+      {"id": 1, "type": "video", "codec": "hevc", "selected": true, "src-id": 0},
+      {"id": 1, "type": "sub", "title": "Signs", "lang": "eng", "external": true},
+    ]))
+    .unwrap();
+
+    assert_eq!(tracks[0].kind, "video");
+    assert!(tracks[0].selected);
+    assert_eq!(tracks[1].lang.as_deref(), Some("eng"));
+    assert!(tracks[1].external);
+    assert!(!tracks[1].selected);
+  }
+
+  #[test]
+  fn video_params_keep_what_mpv_reports() {
+    let params: VideoParams = serde_json::from_value(serde_json::json!({
+      "pixelformat": "yuv420p10",
+      "w": 3840,
+      "h": 2160,
+      "aspect": 1.777778,
+      "primaries": "bt.2020",
+      "gamma": "pq",
+      "rotate": 0,
+    }))
+    .unwrap();
+
+    assert_eq!((params.w, params.h), (3840, 2160));
+    assert_eq!(params.gamma.as_deref(), Some("pq"));
+  }
+
+  #[test]
+  fn untitled_chapters_decode() {
+    let chapters: ChapterList = serde_json::from_value(
+      serde_json::json!([{"time": 0.0}, {"title": "Credits", "time": 1300.5}]),
+    )
+    .unwrap();
+
+    assert_eq!(chapters[0].title, None);
+    assert_eq!(chapters[1].time, 1300.5);
+  }
+}
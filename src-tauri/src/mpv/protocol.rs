@@ -2,6 +2,7 @@
 //!
 //! Reference: https://mpv.io/manual/master/#json-ipc
 
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::sync::atomic::{AtomicI64, Ordering};
 
@@ -13,10 +14,40 @@ pub fn next_request_id() -> i64 {
   REQUEST_ID.fetch_add(1, Ordering::SeqCst)
 }
 
+/// Quote `value` for a `key=value` option list like loadfile's, so commas
+/// and equals signs in it don't end it: `%<bytes>%<value>`.
+pub fn quote_option_value(value: &str) -> String {
+  format!("%{}%{}", value.len(), value)
+}
+
+/// Loadfile options adding `headers` (`Name: value`) to the user's own
+/// `http-header-fields` for that one file, so they go only to the file's
+/// server and are dropped when it ends.
+pub fn header_field_options(headers: &[String]) -> Vec<String> {
+  headers
+    .iter()
+    .map(|header| format!("http-header-fields-append={}", quote_option_value(header)))
+    .collect()
+}
+
+/// Arguments of an MPV command.
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum CommandArgs {
+  /// `["name", arg1, arg2, ...]`
+  Positional(Vec<serde_json::Value>),
+  /// `{"name": "name", "arg": value, ...}`, leaving out optional arguments.
+  Named(serde_json::Map<String, serde_json::Value>),
+}
+
 /// Command sent to MPV via IPC.
 #[derive(Debug, Clone, Serialize)]
 pub struct MpvCommand {
-  pub command: Vec<serde_json::Value>,
+  pub command: CommandArgs,
+  /// Run the command without blocking MPV's playback loop; the response
+  /// still arrives once it finished.
+  #[serde(rename = "async", skip_serializing_if = "std::ops::Not::not")]
+  pub is_async: bool,
   pub request_id: i64,
 }
 
@@ -24,11 +55,49 @@ impl MpvCommand {
   /// Create a new command with auto-generated request ID.
   pub fn new(args: Vec<serde_json::Value>) -> Self {
     Self {
-      command: args,
+      command: CommandArgs::Positional(args),
+      is_async: false,
+      request_id: next_request_id(),
+    }
+  }
+
+  /// Create a command with named arguments, e.g. `url` and `flags` of `sub-add`.
+  pub fn named(name: &str, args: Vec<(&str, serde_json::Value)>) -> Self {
+    let mut map = serde_json::Map::new();
+    map.insert("name".to_string(), name.into());
+    for (key, value) in args {
+      map.insert(key.to_string(), value);
+    }
+    Self {
+      command: CommandArgs::Named(map),
+      is_async: false,
       request_id: next_request_id(),
     }
   }
 
+  /// Run this command asynchronously. Only some commands support it
+  /// (screenshots, `sub-add` and the like); MPV rejects it elsewhere.
+  pub fn asynchronous(mut self) -> Self {
+    self.is_async = true;
+    self
+  }
+
+  /// The command name, e.g. `loadfile`.
+  pub fn name(&self) -> Option<&str> {
+    match &self.command {
+      CommandArgs::Positional(args) => args.first()?.as_str(),
+      CommandArgs::Named(map) => map.get("name")?.as_str(),
+    }
+  }
+
+  /// Positional arguments after the name; empty for named commands.
+  pub fn args(&self) -> &[serde_json::Value] {
+    match &self.command {
+      CommandArgs::Positional(args) => args.get(1..).unwrap_or_default(),
+      CommandArgs::Named(_) => &[],
+    }
+  }
+
   /// Load a file for playback.
   pub fn loadfile(url: &str) -> Self {
     Self::new(vec!["loadfile".into(), url.into()])
@@ -48,6 +117,16 @@ impl MpvCommand {
     ])
   }
 
+  /// `loadfile` with options for MPV before 0.38.0, which has no index argument.
+  pub fn loadfile_with_options_legacy(url: &str, options: &str) -> Self {
+    Self::new(vec![
+      "loadfile".into(),
+      url.into(),
+      "replace".into(),
+      options.into(),
+    ])
+  }
+
   /// Seek to absolute position in seconds.
   pub fn seek(time: f64) -> Self {
     Self::new(vec!["seek".into(), time.into(), "absolute".into()])
@@ -63,6 +142,15 @@ impl MpvCommand {
     Self::new(vec!["set_property".into(), "pause".into(), paused.into()])
   }
 
+  /// Set fullscreen state.
+  pub fn set_fullscreen(fullscreen: bool) -> Self {
+    Self::new(vec![
+      "set_property".into(),
+      "fullscreen".into(),
+      fullscreen.into(),
+    ])
+  }
+
   /// Set volume (0-100).
   pub fn set_volume(volume: f64) -> Self {
     Self::new(vec!["set_property".into(), "volume".into(), volume.into()])
@@ -78,11 +166,25 @@ impl MpvCommand {
     Self::new(vec!["set_property".into(), "sid".into(), id.into()])
   }
 
+  /// Show a second subtitle track alongside the selected one.
+  pub fn set_secondary_subtitle_track(id: i64) -> Self {
+    Self::new(vec![
+      "set_property".into(),
+      "secondary-sid".into(),
+      id.into(),
+    ])
+  }
+
   /// Get a property value.
   pub fn get_property(name: &str) -> Self {
     Self::new(vec!["get_property".into(), name.into()])
   }
 
+  /// Stop playback; with `--idle` MPV stays running.
+  pub fn stop() -> Self {
+    Self::new(vec!["stop".into()])
+  }
+
   /// Quit MPV.
   pub fn quit() -> Self {
     Self::new(vec!["quit".into()])
@@ -93,11 +195,85 @@ impl MpvCommand {
     Self::new(vec!["cycle".into(), property.into()])
   }
 
+  /// Bind `key` to an input command, replacing what it was bound to.
+  pub fn keybind(key: &str, command: &str) -> Self {
+    Self::new(vec!["keybind".into(), key.into(), command.into()])
+  }
+
   /// Set a string property.
   pub fn set_property_string(name: &str, value: &str) -> Self {
     Self::new(vec!["set_property".into(), name.into(), value.into()])
   }
 
+  /// Set an A-B loop point in seconds, or clear it with `None`.
+  pub fn set_ab_loop_point(property: &str, seconds: Option<f64>) -> Self {
+    let value = seconds.map_or_else(|| "no".into(), serde_json::Value::from);
+    Self::new(vec!["set_property".into(), property.into(), value])
+  }
+
+  /// Set playback speed (1.0 = normal).
+  pub fn set_speed(speed: f64) -> Self {
+    Self::new(vec!["set_property".into(), "speed".into(), speed.into()])
+  }
+
+  /// Save a screenshot to `path`; `flags` is `subtitles`, `video` or `window`.
+  /// Encoding runs in the background so playback doesn't stutter.
+  pub fn screenshot_to_file(path: &str, flags: &str) -> Self {
+    Self::named(
+      "screenshot-to-file",
+      vec![("filename", path.into()), ("flags", flags.into())],
+    )
+    .asynchronous()
+  }
+
+  /// Cycle the A-B loop: set A, then B, then clear both.
+  #[allow(dead_code)]
+  pub fn ab_loop() -> Self {
+    Self::new(vec!["ab-loop".into()])
+  }
+
+  /// Play the next entry of MPV's own playlist.
+  #[allow(dead_code)]
+  pub fn playlist_next() -> Self {
+    Self::new(vec!["playlist-next".into()])
+  }
+
+  /// Play the previous entry of MPV's own playlist.
+  #[allow(dead_code)]
+  pub fn playlist_prev() -> Self {
+    Self::new(vec!["playlist-prev".into()])
+  }
+
+  /// Send a message to one script, e.g. `script-message-to uosc set ...`.
+  #[allow(dead_code)]
+  pub fn script_message_to(target: &str, args: &[&str]) -> Self {
+    let mut command: Vec<serde_json::Value> = vec!["script-message-to".into(), target.into()];
+    command.extend(args.iter().map(|&arg| arg.into()));
+    Self::new(command)
+  }
+
+  /// Send a message to every script, e.g. `script-message jellypilot-capture-key`.
+  pub fn script_message(args: &[&str]) -> Self {
+    let mut command: Vec<serde_json::Value> = vec!["script-message".into()];
+    command.extend(args.iter().map(|&arg| arg.into()));
+    Self::new(command)
+  }
+
+  /// Load a Lua or JavaScript script into the running MPV.
+  pub fn load_script(path: &str) -> Self {
+    Self::new(vec!["load-script".into(), path.into()])
+  }
+
+  /// Run a key binding a script registered, e.g. `stats/display-stats-toggle`.
+  pub fn script_binding(name: &str) -> Self {
+    Self::new(vec!["script-binding".into(), name.into()])
+  }
+
+  /// Apply a profile from the user's mpv.conf.
+  pub fn apply_profile(name: &str) -> Self {
+    Self::new(vec!["apply-profile".into(), name.into()])
+  }
+
   /// Disable a track (set property to "no").
   pub fn disable_track(property: &str) -> Self {
     Self::new(vec!["set_property".into(), property.into(), "no".into()])
@@ -114,7 +290,6 @@ impl MpvCommand {
   }
 
   /// Stop observing a property.
-  #[allow(dead_code)]
   pub fn unobserve_property(observer_id: i64) -> Self {
     Self::new(vec!["unobserve_property".into(), observer_id.into()])
   }
@@ -123,12 +298,15 @@ impl MpvCommand {
   ///
   /// MPV sub-add format: `sub-add <url> [<flags> [<title> [<lang>]]]`
   /// Flags: "select" (select immediately), "auto" (don't select), "cached" (cache only)
+  ///
+  /// The subtitle is downloaded asynchronously, so a slow server doesn't
+  /// freeze the video meanwhile.
   pub fn sub_add(url: &str, flags: Option<&str>) -> Self {
-    let mut args: Vec<serde_json::Value> = vec!["sub-add".into(), url.into()];
+    let mut args = vec![("url", url.into())];
     if let Some(f) = flags {
-      args.push(f.into());
+      args.push(("flags", f.into()));
     }
-    Self::new(args)
+    Self::named("sub-add", args).asynchronous()
   }
 }
 
@@ -148,6 +326,11 @@ impl MpvResponse {
   pub fn is_success(&self) -> bool {
     self.error == "success"
   }
+
+  /// Decode the response data; a missing value decodes as JSON `null`.
+  pub fn data_as<T: DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
+    serde_json::from_value(self.data.clone().unwrap_or_default())
+  }
 }
 
 /// Event sent by MPV (property changes, playback events, etc.).
@@ -231,6 +414,55 @@ mod tests {
     assert!(json.contains("http://example.com/video.mp4"));
   }
 
+  #[test]
+  fn option_values_are_quoted_by_byte_length() {
+    assert_eq!(quote_option_value("/tmp/fonts, ä"), "%14%/tmp/fonts, ä");
+  }
+
+  #[test]
+  fn headers_are_appended_for_one_file() {
+    let options = header_field_options(&[
+      "CF-Access-Client-Id: a,b".to_string(),
+      "X-Emby-Token: token-1".to_string(),
+    ]);
+
+    assert_eq!(
+      options,
+      [
+        "http-header-fields-append=%24%CF-Access-Client-Id: a,b",
+        "http-header-fields-append=%21%X-Emby-Token: token-1",
+      ]
+    );
+  }
+
+  #[test]
+  fn named_commands_leave_out_missing_arguments() {
+    let cmd = MpvCommand::sub_add("https://jellyfin.example/sub.srt", None);
+    let json = serde_json::to_value(&cmd).unwrap();
+
+    assert_eq!(
+      json["command"],
+      serde_json::json!({"name": "sub-add", "url": "https://jellyfin.example/sub.srt"})
+    );
+    assert_eq!(json["async"], serde_json::json!(true));
+    assert_eq!(cmd.name(), Some("sub-add"));
+    assert!(cmd.args().is_empty());
+  }
+
+  #[test]
+  fn positional_commands_are_not_async() {
+    let cmd = MpvCommand::script_message_to("uosc", &["set", "chapters"]);
+    let json = serde_json::to_value(&cmd).unwrap();
+
+    assert_eq!(
+      json["command"],
+      serde_json::json!(["script-message-to", "uosc", "set", "chapters"])
+    );
+    assert!(json.get("async").is_none());
+    assert_eq!(cmd.name(), Some("script-message-to"));
+    assert_eq!(cmd.args().len(), 3);
+  }
+
   #[test]
   fn test_response_parsing() {
     let json = r#"{"error":"success","data":null,"request_id":1}"#;
@@ -244,6 +476,22 @@ mod tests {
     }
   }
 
+  #[test]
+  fn response_data_decodes_as_typed_values() {
+    let json = r#"{"error":"success","data":12.5,"request_id":1}"#;
+    let MpvMessage::Response(response) = MpvMessage::parse(json).unwrap() else {
+      panic!("Expected response");
+    };
+    assert_eq!(response.data_as::<f64>().unwrap(), 12.5);
+    assert!(response.data_as::<bool>().is_err());
+
+    let json = r#"{"error":"success","request_id":2}"#;
+    let MpvMessage::Response(response) = MpvMessage::parse(json).unwrap() else {
+      panic!("Expected response");
+    };
+    assert_eq!(response.data_as::<Option<f64>>().unwrap(), None);
+  }
+
   #[test]
   fn test_event_parsing() {
     let json = r#"{"event":"property-change","id":1,"name":"pause","data":false}"#;
@@ -0,0 +1,164 @@
+//! Private directory for MPV's IPC socket (Unix only).
+//!
+//! Anyone who can open the socket controls MPV and can read the stream URLs,
+//! access token included, that JellyPilot sends it. The socket lives in a
+//! `jellypilot` directory only its owner may enter: under `XDG_RUNTIME_DIR`
+//! when set, else under the temp dir with the user name in it. A directory
+//! someone else created there, or one reached through a symlink, is refused
+//! rather than used.
+
+use std::io;
+use std::os::unix::fs::{DirBuilderExt, FileTypeExt, MetadataExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+
+/// Permission bits of the socket directory: owner only.
+const PRIVATE_MODE: u32 = 0o700;
+
+/// Directory holding MPV's IPC socket.
+pub fn ipc_dir() -> PathBuf {
+  match std::env::var_os("XDG_RUNTIME_DIR").filter(|dir| !dir.is_empty()) {
+    Some(runtime_dir) => PathBuf::from(runtime_dir).join("jellypilot"),
+    None => {
+      let user = std::env::var("USER")
+        .or_else(|_| std::env::var("LOGNAME"))
+        .unwrap_or_else(|_| "user".to_string());
+      std::env::temp_dir().join(format!("jellypilot-{}", user))
+    }
+  }
+}
+
+/// Create the socket directory, or make sure the existing one is private.
+pub fn prepare_ipc_dir() -> io::Result<PathBuf> {
+  let dir = ipc_dir();
+  prepare_private_dir(&dir)?;
+  Ok(dir)
+}
+
+/// Create `dir` for this user only, or make sure the existing one is a
+/// private directory of this user. Its parent must exist.
+pub fn prepare_private_dir(dir: &Path) -> io::Result<()> {
+  match std::fs::DirBuilder::new().mode(PRIVATE_MODE).create(dir) {
+    Ok(()) => {}
+    Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {}
+    Err(e) => return Err(e),
+  }
+  let owner = owned_dir_owner(dir)?;
+  let metadata = std::fs::symlink_metadata(dir)?;
+  if metadata.mode() & 0o077 != 0 {
+    log::warn!(
+      "Directory {} was accessible to others, restricting it",
+      dir.display()
+    );
+    std::fs::set_permissions(dir, std::fs::Permissions::from_mode(PRIVATE_MODE))?;
+  }
+  log::debug!("Using private directory {} (uid {})", dir.display(), owner);
+  Ok(())
+}
+
+/// Check that `socket` is a socket in a private directory, both owned by
+/// this user, before connecting to it.
+pub fn verify_socket(socket: &Path) -> io::Result<()> {
+  let dir = socket
+    .parent()
+    .ok_or_else(|| refused(socket, "has no parent directory"))?;
+  let owner = owned_dir_owner(dir)?;
+  if std::fs::symlink_metadata(dir)?.mode() & 0o077 != 0 {
+    return Err(refused(dir, "is accessible to other users"));
+  }
+  let metadata = std::fs::symlink_metadata(socket)?;
+  if !metadata.file_type().is_socket() {
+    return Err(refused(socket, "is not a socket"));
+  }
+  if metadata.uid() != owner {
+    return Err(refused(socket, "belongs to another user"));
+  }
+  Ok(())
+}
+
+/// Owner of `dir` after checking that it is a real directory of this user,
+/// found by creating a file in it.
+fn owned_dir_owner(dir: &Path) -> io::Result<u32> {
+  let metadata = std::fs::symlink_metadata(dir)?;
+  if !metadata.file_type().is_dir() {
+    return Err(refused(dir, "is not a directory"));
+  }
+  let probe = dir.join(format!(".owner-check-{}", uuid::Uuid::new_v4()));
+  let own_uid = std::fs::File::create_new(&probe)
+    .and_then(|file| file.metadata())
+    .map(|probe| probe.uid());
+  let _ = std::fs::remove_file(&probe);
+  if own_uid? != metadata.uid() {
+    return Err(refused(dir, "belongs to another user"));
+  }
+  Ok(metadata.uid())
+}
+
+fn refused(path: &Path, reason: &str) -> io::Error {
+  io::Error::new(
+    io::ErrorKind::PermissionDenied,
+    format!("{} {}", path.display(), reason),
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn scratch_dir() -> PathBuf {
+    std::env::temp_dir().join(format!("jellypilot-socket-test-{}", uuid::Uuid::new_v4()))
+  }
+
+  #[test]
+  fn the_directory_is_created_private() {
+    let dir = scratch_dir();
+
+    prepare_private_dir(&dir).unwrap();
+
+    let mode = std::fs::metadata(&dir).unwrap().mode();
+    assert_eq!(mode & 0o777, PRIVATE_MODE);
+    std::fs::remove_dir_all(&dir).unwrap();
+  }
+
+  #[test]
+  fn an_existing_open_directory_is_restricted() {
+    let dir = scratch_dir();
+    std::fs::create_dir(&dir).unwrap();
+    std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o777)).unwrap();
+
+    prepare_private_dir(&dir).unwrap();
+
+    let mode = std::fs::metadata(&dir).unwrap().mode();
+    assert_eq!(mode & 0o777, PRIVATE_MODE);
+    std::fs::remove_dir_all(&dir).unwrap();
+  }
+
+  #[test]
+  fn symlinked_directories_are_refused() {
+    let target = scratch_dir();
+    let link = scratch_dir();
+    std::fs::create_dir(&target).unwrap();
+    std::os::unix::fs::symlink(&target, &link).unwrap();
+
+    let result = prepare_private_dir(&link);
+
+    assert_eq!(result.unwrap_err().kind(), io::ErrorKind::PermissionDenied);
+    std::fs::remove_file(&link).unwrap();
+    std::fs::remove_dir_all(&target).unwrap();
+  }
+
+  #[tokio::test]
+  async fn only_sockets_in_a_private_directory_pass() {
+    let dir = scratch_dir();
+    prepare_private_dir(&dir).unwrap();
+    let socket = dir.join("mpv.sock");
+    let not_a_socket = dir.join("mpv.txt");
+    std::fs::write(&not_a_socket, "").unwrap();
+    let _listener = tokio::net::UnixListener::bind(&socket).unwrap();
+
+    assert!(verify_socket(&socket).is_ok());
+    assert!(verify_socket(&not_a_socket).is_err());
+    std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o755)).unwrap();
+    assert!(verify_socket(&socket).is_err());
+    std::fs::remove_dir_all(&dir).unwrap();
+  }
+}
@@ -0,0 +1,123 @@
+//! How long MPV gets to answer an IPC command.
+//!
+//! One limit does not fit every command: `loadfile` of a remote stream can
+//! take a while over a slow link, while a property read that has not been
+//! answered within two seconds will not be. Commands without an override use
+//! the default.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use super::protocol::MpvCommand;
+
+pub const DEFAULT_COMMAND_TIMEOUT_SECONDS: u32 = 5;
+
+/// Overrides used until the settings say otherwise, in seconds by command name.
+pub fn default_command_timeouts() -> BTreeMap<String, u32> {
+  BTreeMap::from([
+    ("get_property".to_string(), 2),
+    ("loadfile".to_string(), 30),
+  ])
+}
+
+/// Response timeouts for IPC commands.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandTimeouts {
+  default: Duration,
+  overrides: BTreeMap<String, Duration>,
+}
+
+impl Default for CommandTimeouts {
+  fn default() -> Self {
+    Self::from_seconds(DEFAULT_COMMAND_TIMEOUT_SECONDS, &default_command_timeouts())
+  }
+}
+
+impl CommandTimeouts {
+  /// Timeouts from a default and per-command overrides, all in seconds.
+  pub fn from_seconds(default: u32, overrides: &BTreeMap<String, u32>) -> Self {
+    Self {
+      default: Duration::from_secs(default.into()),
+      overrides: overrides
+        .iter()
+        .map(|(name, seconds)| {
+          (
+            name.trim().to_string(),
+            Duration::from_secs((*seconds).into()),
+          )
+        })
+        .collect(),
+    }
+  }
+
+  /// How long to wait for MPV's response to `cmd`.
+  pub fn for_command(&self, cmd: &MpvCommand) -> Duration {
+    cmd
+      .name()
+      .and_then(|name| self.overrides.get(name))
+      .copied()
+      .unwrap_or(self.default)
+  }
+
+  /// The longest any command may wait.
+  pub fn longest(&self) -> Duration {
+    self.longest_except("")
+  }
+
+  /// The longest any command other than `name` may wait.
+  pub fn longest_except(&self, name: &str) -> Duration {
+    self
+      .overrides
+      .iter()
+      .filter(|(command, _)| command.as_str() != name)
+      .map(|(_, timeout)| *timeout)
+      .fold(self.default, Duration::max)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn overrides_apply_by_command_name() {
+    let timeouts = CommandTimeouts::default();
+
+    assert_eq!(
+      timeouts.for_command(&MpvCommand::loadfile("https://jellyfin.example/video")),
+      Duration::from_secs(30)
+    );
+    assert_eq!(
+      timeouts.for_command(&MpvCommand::get_property("time-pos")),
+      Duration::from_secs(2)
+    );
+    assert_eq!(
+      timeouts.for_command(&MpvCommand::quit()),
+      Duration::from_secs(5)
+    );
+  }
+
+  #[test]
+  fn the_default_is_configurable() {
+    let timeouts = CommandTimeouts::from_seconds(10, &BTreeMap::new());
+
+    assert_eq!(
+      timeouts.for_command(&MpvCommand::loadfile("https://jellyfin.example/video")),
+      Duration::from_secs(10)
+    );
+  }
+
+  #[test]
+  fn the_longest_timeout_counts_the_default() {
+    assert_eq!(
+      CommandTimeouts::default().longest(),
+      Duration::from_secs(30)
+    );
+    let timeouts = CommandTimeouts::from_seconds(40, &default_command_timeouts());
+    assert_eq!(timeouts.longest(), Duration::from_secs(40));
+    assert_eq!(
+      CommandTimeouts::default().longest_except("loadfile"),
+      Duration::from_secs(5)
+    );
+  }
+}
@@ -0,0 +1,168 @@
+//! MPV version detection from `mpv --version`.
+
+use std::path::Path;
+use std::process::Command;
+
+use serde::Serialize;
+use specta::Type;
+
+/// Oldest MPV with every feature JellyPilot uses. Older builds still play,
+/// with the features listed in [`MpvFeatures`] turned off.
+pub const MIN_MPV_VERSION: MpvVersion = LOADFILE_INDEX_VERSION;
+
+/// Oldest MPV JellyPilot can drive at all: `--input-ipc-server` replaced
+/// `--input-unix-socket` in 0.17.0.
+pub const MIN_IPC_MPV_VERSION: MpvVersion = MpvVersion {
+  major: 0,
+  minor: 17,
+  patch: 0,
+};
+
+/// `--wayland-app-id` was added in 0.36.0.
+const WAYLAND_APP_ID_VERSION: MpvVersion = MpvVersion {
+  major: 0,
+  minor: 36,
+  patch: 0,
+};
+
+/// The `keybind` command was added in 0.37.0.
+const KEYBIND_VERSION: MpvVersion = MpvVersion {
+  major: 0,
+  minor: 37,
+  patch: 0,
+};
+
+/// `loadfile` gained its playlist index argument in 0.38.0.
+const LOADFILE_INDEX_VERSION: MpvVersion = MpvVersion {
+  major: 0,
+  minor: 38,
+  patch: 0,
+};
+
+/// Release version of an MPV build.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct MpvVersion {
+  pub major: u32,
+  pub minor: u32,
+  pub patch: u32,
+}
+
+impl std::fmt::Display for MpvVersion {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+  }
+}
+
+/// MPV features that depend on the installed version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MpvFeatures {
+  /// `loadfile <url> <flags> <index> <options>`; older builds take the
+  /// options as the third argument.
+  pub loadfile_index: bool,
+  /// `--wayland-app-id`, so window rules can match the MPV window.
+  pub wayland_app_id: bool,
+  /// `keybind`, to change key bindings of a running MPV.
+  pub keybind: bool,
+}
+
+impl MpvFeatures {
+  /// Features of `version`; an unknown version is assumed to be current.
+  pub fn for_version(version: Option<MpvVersion>) -> Self {
+    let at_least = |minimum: MpvVersion| version.is_none_or(|version| version >= minimum);
+    Self {
+      loadfile_index: at_least(LOADFILE_INDEX_VERSION),
+      wayland_app_id: at_least(WAYLAND_APP_ID_VERSION),
+      keybind: at_least(KEYBIND_VERSION),
+    }
+  }
+}
+
+/// Parse the first line of `mpv --version`, e.g. `mpv 0.38.0 Copyright ...`
+/// or `mpv v0.37.0-429-g5a1b2c3 Copyright ...` for git builds.
+pub fn parse_version_output(output: &str) -> Option<MpvVersion> {
+  let first_line = output.lines().next()?;
+  let mut words = first_line.split_whitespace();
+  if words.next()? != "mpv" {
+    return None;
+  }
+  let version = words.next()?.trim_start_matches('v');
+  let mut parts = version
+    .split(|c: char| !c.is_ascii_digit())
+    .take(3)
+    .map(|part| part.parse::<u32>().ok());
+  Some(MpvVersion {
+    major: parts.next()??,
+    minor: parts.next()??,
+    patch: parts.next().flatten().unwrap_or(0),
+  })
+}
+
+/// Run `mpv --version` and return the parsed version with the first output
+/// line. Blocking; call from a blocking task.
+pub fn detect_version(mpv_path: &Path) -> Result<(Option<MpvVersion>, String), std::io::Error> {
+  let mut command = Command::new(mpv_path);
+  command.arg("--version");
+  #[cfg(windows)]
+  {
+    use std::os::windows::process::CommandExt;
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+    command.creation_flags(CREATE_NO_WINDOW);
+  }
+
+  let output = command.output()?;
+  let stdout = String::from_utf8_lossy(&output.stdout);
+  let first_line = stdout.lines().next().unwrap_or_default().to_string();
+  Ok((parse_version_output(&stdout), first_line))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn release_and_git_builds_parse() {
+    assert_eq!(
+      parse_version_output("mpv 0.38.0 Copyright © 2000-2024 mpv/MPlayer/mplayer2 projects\n"),
+      Some(MpvVersion {
+        major: 0,
+        minor: 38,
+        patch: 0
+      })
+    );
+    assert_eq!(
+      parse_version_output("mpv v0.37.0-429-g5a1b2c3 Copyright © 2000-2023"),
+      Some(MpvVersion {
+        major: 0,
+        minor: 37,
+        patch: 0
+      })
+    );
+  }
+
+  #[test]
+  fn unexpected_output_is_not_a_version() {
+    assert_eq!(parse_version_output(""), None);
+    assert_eq!(parse_version_output("mplayer 1.5"), None);
+    assert_eq!(parse_version_output("mpv git-master"), None);
+  }
+
+  #[test]
+  fn features_follow_the_version() {
+    let old = parse_version_output("mpv 0.36.0");
+    let new = parse_version_output("mpv 0.38.0");
+    assert!(!MpvFeatures::for_version(old).loadfile_index);
+    assert!(MpvFeatures::for_version(new).loadfile_index);
+    assert!(MpvFeatures::for_version(old).wayland_app_id);
+    assert!(!MpvFeatures::for_version(old).keybind);
+    assert!(MpvFeatures::for_version(None).loadfile_index);
+  }
+
+  #[test]
+  fn versions_compare_against_the_minimum() {
+    let old = parse_version_output("mpv 0.35.1").unwrap();
+    let new = parse_version_output("mpv 0.39.0").unwrap();
+    assert!(old < MIN_MPV_VERSION);
+    assert!(new >= MIN_MPV_VERSION);
+  }
+}
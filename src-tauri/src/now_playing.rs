@@ -1,15 +1,17 @@
 //! Now Playing read model shared by direct queries and session event emission.
 
 use crate::command::{
-  AdjacentEpisodeUnavailableReason, NowPlayingMedia, NowPlayingState, NowPlayingStatus, PlayerState,
+  AdjacentEpisodeUnavailableReason, NowPlayingMedia, NowPlayingState, NowPlayingStatus,
+  NowPlayingUserData, PlayerState,
 };
 use crate::jellyfin::MediaItem;
-use crate::mpv::{MpvClient, PropertyValue};
+use crate::mpv::MpvClient;
 
 /// Playback context used to derive user-facing adjacent episode availability.
 pub struct PlaybackContext<'a> {
   pub has_active_session: bool,
   pub current_item: Option<&'a MediaItem>,
+  pub user_data: Option<NowPlayingUserData>,
 }
 
 /// Collect the current MPV player state used by the Now Playing read model.
@@ -19,16 +21,16 @@ pub async fn collect_player_state(mpv: &MpvClient) -> PlayerState {
   }
 
   let (paused_res, time_pos_res, duration_res, volume_res, muted_res) = tokio::join!(
-    mpv.get_property("pause"),
-    mpv.get_property("time-pos"),
-    mpv.get_property("duration"),
-    mpv.get_property("volume"),
-    mpv.get_property("mute"),
+    mpv.get_property_as::<Option<bool>>("pause"),
+    mpv.get_property_as::<Option<f64>>("time-pos"),
+    mpv.get_property_as::<Option<f64>>("duration"),
+    mpv.get_property_as::<Option<f64>>("volume"),
+    mpv.get_property_as::<Option<bool>>("mute"),
   );
 
   let paused = match paused_res {
-    Ok(PropertyValue::Bool(b)) => b,
-    Ok(_) => true,
+    Ok(Some(b)) => b,
+    Ok(None) => true,
     Err(e) => {
       log::warn!("Failed to get pause property: {}", e);
       true
@@ -36,7 +38,7 @@ pub async fn collect_player_state(mpv: &MpvClient) -> PlayerState {
   };
 
   let time_pos = match time_pos_res {
-    Ok(PropertyValue::Number(n)) if n.is_finite() => n,
+    Ok(Some(n)) if n.is_finite() => n,
     Ok(_) => 0.0,
     Err(e) => {
       log::warn!("Failed to get time-pos property: {}", e);
@@ -45,7 +47,7 @@ pub async fn collect_player_state(mpv: &MpvClient) -> PlayerState {
   };
 
   let duration = match duration_res {
-    Ok(PropertyValue::Number(n)) if n.is_finite() => n,
+    Ok(Some(n)) if n.is_finite() => n,
     Ok(_) => 0.0,
     Err(e) => {
       log::warn!("Failed to get duration property: {}", e);
@@ -54,7 +56,7 @@ pub async fn collect_player_state(mpv: &MpvClient) -> PlayerState {
   };
 
   let volume = match volume_res {
-    Ok(PropertyValue::Number(n)) if n.is_finite() => n.clamp(0.0, 100.0),
+    Ok(Some(n)) if n.is_finite() => n.clamp(0.0, 100.0),
     Ok(_) => 100.0,
     Err(e) => {
       log::warn!("Failed to get volume property: {}", e);
@@ -63,8 +65,8 @@ pub async fn collect_player_state(mpv: &MpvClient) -> PlayerState {
   };
 
   let muted = match muted_res {
-    Ok(PropertyValue::Bool(b)) => b,
-    Ok(_) => false,
+    Ok(Some(b)) => b,
+    Ok(None) => false,
     Err(e) => {
       log::warn!("Failed to get mute property: {}", e);
       false
@@ -93,6 +95,7 @@ pub fn build_now_playing_state(
     series_name: item.series_name.clone(),
     season_number: item.parent_index_number,
     episode_number: item.index_number,
+    user_data: context.user_data,
   });
 
   let unavailable_reason = if !context.has_active_session {
@@ -156,6 +159,7 @@ mod tests {
       parent_index_number: Some(1),
       run_time_ticks: Some(1_000),
       overview: None,
+      status: None,
     }
   }
 
@@ -169,6 +173,7 @@ mod tests {
       PlaybackContext {
         has_active_session,
         current_item,
+        user_data: None,
       },
     )
   }
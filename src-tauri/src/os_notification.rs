@@ -0,0 +1,125 @@
+//! Optional OS notifications for key playback and connection events.
+//!
+//! `AppNotification` only reaches the webview, which is usually hidden in
+//! the tray while casting. These go to the desktop's notification center
+//! instead, each kind behind its own setting.
+
+use std::path::PathBuf;
+
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::command::ConfigState;
+use crate::config::AppConfig;
+use crate::i18n::tr;
+use crate::jellyfin::JellyfinClient;
+
+/// Artwork is scaled down by the server; notification icons are small.
+const ARTWORK_MAX_WIDTH: u32 = 256;
+
+/// Events that can raise an OS notification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OsNotificationKind {
+  PlaybackStarted,
+  /// The next episode or queue item started after the previous one ended.
+  AutoNext,
+  ConnectionLost,
+  ConnectionRestored,
+  MpvCrashed,
+}
+
+impl OsNotificationKind {
+  fn enabled(self, config: &AppConfig) -> bool {
+    match self {
+      Self::PlaybackStarted => config.os_notify_playback_started,
+      Self::AutoNext => config.os_notify_auto_next,
+      Self::ConnectionLost | Self::ConnectionRestored => config.os_notify_connection,
+      Self::MpvCrashed => config.os_notify_mpv_crash,
+    }
+  }
+
+  fn title(self) -> String {
+    match self {
+      Self::PlaybackStarted => tr!("os-playback-started"),
+      Self::AutoNext => tr!("os-auto-next"),
+      Self::ConnectionLost => tr!("os-connection-lost"),
+      Self::ConnectionRestored => tr!("os-connection-restored"),
+      Self::MpvCrashed => tr!("os-mpv-crashed"),
+    }
+  }
+}
+
+fn is_enabled(app: &AppHandle, kind: OsNotificationKind) -> bool {
+  app
+    .try_state::<ConfigState>()
+    .is_some_and(|config| kind.enabled(&config.0.read()))
+}
+
+/// Show a notification for `kind` when it is enabled.
+pub fn notify(app: &AppHandle, kind: OsNotificationKind, body: &str) {
+  if is_enabled(app, kind) {
+    show(app, kind, body, None);
+  }
+}
+
+/// Show a playback notification for `item_id` with its artwork, when enabled.
+pub async fn notify_playback(
+  app: &AppHandle,
+  client: &JellyfinClient,
+  kind: OsNotificationKind,
+  item_id: &str,
+  title: &str,
+) {
+  if !is_enabled(app, kind) {
+    return;
+  }
+  let artwork = save_artwork(app, client, item_id).await;
+  show(app, kind, title, artwork);
+}
+
+fn show(app: &AppHandle, kind: OsNotificationKind, body: &str, icon: Option<PathBuf>) {
+  let mut notification = app.notification().builder().title(kind.title()).body(body);
+  if let Some(icon) = icon {
+    notification = notification.icon(icon.to_string_lossy());
+  }
+  if let Err(e) = notification.show() {
+    log::warn!("Failed to show OS notification: {}", e);
+  }
+}
+
+/// Download the item's primary image to a file the notification can show.
+async fn save_artwork(app: &AppHandle, client: &JellyfinClient, item_id: &str) -> Option<PathBuf> {
+  let server_url = client.connection_state().server_url?;
+  let url = format!(
+    "{}/Items/{}/Images/Primary?maxWidth={}",
+    server_url, item_id, ARTWORK_MAX_WIDTH
+  );
+  let image = match client.download_image(&url).await {
+    Ok(image) => image,
+    Err(e) => {
+      log::debug!("No artwork for notification of {}: {}", item_id, e);
+      return None;
+    }
+  };
+
+  let extension = match image.content_type.as_deref() {
+    Some("image/png") => "png",
+    Some("image/webp") => "webp",
+    _ => "jpg",
+  };
+  let path = app
+    .path()
+    .app_cache_dir()
+    .ok()?
+    .join(format!("notification-artwork.{}", extension));
+  if let Some(dir) = path.parent() {
+    tokio::fs::create_dir_all(dir).await.ok()?;
+  }
+  match tokio::fs::write(&path, &image.bytes).await {
+    Ok(()) => Some(path),
+    Err(e) => {
+      log::warn!("Failed to save notification artwork: {}", e);
+      None
+    }
+  }
+}
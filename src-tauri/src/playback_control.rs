@@ -2,7 +2,10 @@
 
 use tauri_specta::Event;
 
-use crate::command::{CommandError, JellyfinState, NowPlayingChanged, NowPlayingState};
+use crate::command::{
+  CommandError, JellyfinState, NowPlayingChanged, NowPlayingState, NowPlayingUserData,
+};
+use crate::jellyfin::SeekTarget;
 use crate::mpv::MpvClient;
 use crate::now_playing::{build_now_playing_state, collect_player_state, PlaybackContext};
 
@@ -25,20 +28,24 @@ pub async fn collect_now_playing_state(state: &JellyfinState) -> NowPlayingState
   let player = collect_player_state(&state.mpv).await;
   let session = state.session.read().clone();
   let current_item = session.as_ref().and_then(|session| session.current_item());
+  let user_data = session
+    .as_ref()
+    .and_then(|session| session.current_user_data());
 
   build_now_playing_state(
     player,
     PlaybackContext {
       has_active_session: session.is_some(),
       current_item: current_item.as_ref(),
+      user_data,
     },
   )
 }
 
 pub async fn emit_now_playing_changed(app: &tauri::AppHandle, state: &JellyfinState) {
-  let event = NowPlayingChanged {
-    state: collect_now_playing_state(state).await,
-  };
+  let now_playing = collect_now_playing_state(state).await;
+  crate::control_api::publish(&now_playing);
+  let event = NowPlayingChanged { state: now_playing };
   if let Err(e) = event.emit(app) {
     log::error!("Failed to emit now playing state: {}", e);
   }
@@ -50,24 +57,69 @@ pub async fn set_pause(
   jellyfin_state: &JellyfinState,
   paused: bool,
 ) -> Result<(), CommandError> {
-  mpv
-    .set_pause(paused)
-    .await
-    .map_err(|e| CommandError::internal(e.to_string()))?;
+  apply_pause(app, mpv, jellyfin_state, Some(paused)).await
+}
+
+pub async fn toggle_pause(
+  app: &tauri::AppHandle,
+  mpv: &MpvClient,
+  jellyfin_state: &JellyfinState,
+) -> Result<(), CommandError> {
+  apply_pause(app, mpv, jellyfin_state, None).await
+}
+
+/// Pause through the active session, which owns the pause state and reports
+/// it; without one only MPV needs to change.
+async fn apply_pause(
+  app: &tauri::AppHandle,
+  mpv: &MpvClient,
+  jellyfin_state: &JellyfinState,
+  paused: Option<bool>,
+) -> Result<(), CommandError> {
+  let session = jellyfin_state.session.read().clone();
+  match session {
+    Some(session) => {
+      session
+        .set_pause(paused)
+        .await
+        .map_err(CommandError::internal)?;
+    }
+    None => {
+      let paused = match paused {
+        Some(paused) => paused,
+        None => !mpv
+          .get_pause()
+          .await
+          .map_err(|e| CommandError::internal(e.to_string()))?,
+      };
+      mpv
+        .set_pause(paused)
+        .await
+        .map_err(|e| CommandError::internal(e.to_string()))?;
+    }
+  }
   emit_now_playing_changed(app, jellyfin_state).await;
   Ok(())
 }
 
-pub async fn toggle_pause(
+/// Set the MPV volume (0-100).
+pub async fn set_volume(
   app: &tauri::AppHandle,
   mpv: &MpvClient,
   jellyfin_state: &JellyfinState,
+  volume: f64,
 ) -> Result<(), CommandError> {
-  let is_paused = mpv
-    .get_pause()
+  if !(0.0..=100.0).contains(&volume) {
+    return Err(CommandError::invalid_input(
+      "Volume must be between 0 and 100",
+    ));
+  }
+  mpv
+    .set_volume(volume)
     .await
     .map_err(|e| CommandError::internal(e.to_string()))?;
-  set_pause(app, mpv, jellyfin_state, !is_paused).await
+  emit_now_playing_changed(app, jellyfin_state).await;
+  Ok(())
 }
 
 pub async fn toggle_mute(
@@ -83,6 +135,86 @@ pub async fn toggle_mute(
   Ok(())
 }
 
+/// Seek through the active session so its state and server reports stay in sync.
+pub async fn seek(
+  app: &tauri::AppHandle,
+  state: &JellyfinState,
+  target: SeekTarget,
+) -> Result<(), CommandError> {
+  let session = state
+    .session
+    .read()
+    .clone()
+    .ok_or_else(|| CommandError::invalid_input("Seeking is available during playback"))?;
+
+  session
+    .seek(target)
+    .await
+    .map_err(CommandError::invalid_input)?;
+  emit_now_playing_changed(app, state).await;
+  Ok(())
+}
+
+/// Mark the playing item watched or unwatched through the active session,
+/// which confirms it on MPV's OSD. Returns whether it is watched afterwards.
+pub async fn toggle_watched(
+  app: &tauri::AppHandle,
+  state: &JellyfinState,
+) -> Result<bool, CommandError> {
+  let session = state
+    .session
+    .read()
+    .clone()
+    .ok_or_else(|| CommandError::invalid_input("Watched state can be changed during playback"))?;
+
+  let watched = session
+    .toggle_watched()
+    .await
+    .map_err(CommandError::invalid_input)?;
+  emit_now_playing_changed(app, state).await;
+  Ok(watched)
+}
+
+/// Add the playing item to the favorites or remove it through the active
+/// session. Returns whether it is a favorite afterwards.
+pub async fn toggle_favorite(
+  app: &tauri::AppHandle,
+  state: &JellyfinState,
+) -> Result<bool, CommandError> {
+  let session = state
+    .session
+    .read()
+    .clone()
+    .ok_or_else(|| CommandError::invalid_input("Favorites can be changed during playback"))?;
+
+  let favorite = session
+    .toggle_favorite()
+    .await
+    .map_err(CommandError::invalid_input)?;
+  emit_now_playing_changed(app, state).await;
+  Ok(favorite)
+}
+
+/// Rate the playing item thumbs up or down through the active session.
+pub async fn set_user_rating(
+  app: &tauri::AppHandle,
+  state: &JellyfinState,
+  likes: bool,
+) -> Result<NowPlayingUserData, CommandError> {
+  let session = state
+    .session
+    .read()
+    .clone()
+    .ok_or_else(|| CommandError::invalid_input("Rating is available during playback"))?;
+
+  let user_data = session
+    .set_user_rating(likes)
+    .await
+    .map_err(CommandError::invalid_input)?;
+  emit_now_playing_changed(app, state).await;
+  Ok(user_data)
+}
+
 pub async fn play_adjacent_episode(
   app: &tauri::AppHandle,
   state: &JellyfinState,
@@ -0,0 +1,280 @@
+//! OS sleep and screen-lock integration.
+//!
+//! On Linux, playback is paused and progress reported as soon as the system
+//! suspends or our login session locks, so the server keeps the real position
+//! instead of whatever MPV reaches unattended. Resuming playback is left to
+//! the user. After a wake-up the WebSocket is re-validated because sleeping
+//! usually kills it silently.
+//!
+//! Sources:
+//! - Linux: logind `PrepareForSleep` and the `Lock` signal of our own session
+//!   (`XDG_SESSION_ID`, or the session logind assigns our PID) via
+//!   `gdbus monitor`; locks of other users' sessions are ignored
+//! - All platforms: wall-clock jumps, which reveal a wake-up after the fact
+//!
+//! Windows and macOS get only the wake-up detection: playback is paused and
+//! the connection re-validated after a sleep, but not before it, and a locked
+//! screen doesn't pause playback there.
+
+use std::time::{Duration, SystemTime};
+
+use tauri::{AppHandle, Manager};
+
+use crate::command::JellyfinState;
+
+/// How often the wall clock is sampled for sleep detection.
+const SLEEP_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+/// Extra wall-clock time between samples that counts as a sleep.
+const SLEEP_GAP_THRESHOLD: Duration = Duration::from_secs(30);
+
+/// A power or session change that affects playback.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PowerEvent {
+  /// The system is about to suspend.
+  Sleeping,
+  /// The screen was locked.
+  Locked,
+  /// The system woke up from suspend.
+  Resumed,
+}
+
+/// Detects a suspend by comparing wall-clock time between periodic samples.
+#[derive(Debug)]
+struct SleepDetector {
+  last_sample: SystemTime,
+}
+
+impl SleepDetector {
+  fn new(now: SystemTime) -> Self {
+    Self { last_sample: now }
+  }
+
+  /// Record a sample; true when far more time passed than the sampling interval.
+  fn observe(&mut self, now: SystemTime) -> bool {
+    let elapsed = now.duration_since(self.last_sample).unwrap_or_default();
+    self.last_sample = now;
+    elapsed > SLEEP_CHECK_INTERVAL + SLEEP_GAP_THRESHOLD
+  }
+}
+
+/// Start listening for OS power events.
+pub fn start_power_monitor(app: AppHandle) {
+  let sleep_app = app.clone();
+  tauri::async_runtime::spawn(async move {
+    let mut detector = SleepDetector::new(SystemTime::now());
+    let mut interval = tokio::time::interval(SLEEP_CHECK_INTERVAL);
+    loop {
+      interval.tick().await;
+      if detector.observe(SystemTime::now()) {
+        handle_power_event(&sleep_app, PowerEvent::Resumed).await;
+      }
+    }
+  });
+
+  #[cfg(target_os = "linux")]
+  tauri::async_runtime::spawn(logind::monitor(app));
+  #[cfg(not(target_os = "linux"))]
+  let _ = app;
+}
+
+async fn handle_power_event(app: &AppHandle, event: PowerEvent) {
+  log::info!("Power event: {:?}", event);
+  let Some(session) = app
+    .try_state::<JellyfinState>()
+    .and_then(|state| state.session.read().clone())
+  else {
+    return;
+  };
+
+  session.pause_for_system_event().await;
+  if event == PowerEvent::Resumed {
+    session.revalidate_connection();
+  }
+}
+
+#[cfg(target_os = "linux")]
+mod logind {
+  use tauri::AppHandle;
+  use tokio::io::{AsyncBufReadExt, BufReader};
+  use tokio::process::Command;
+
+  use super::{handle_power_event, parse_logind_signal, session_object_path};
+
+  /// Follow logind signals until `gdbus` exits.
+  pub async fn monitor(app: AppHandle) {
+    let session = own_session().await;
+    if session.is_none() {
+      log::info!("No logind session found; screen locks won't pause playback");
+    }
+    let child = Command::new("gdbus")
+      .args(["monitor", "--system", "--dest", "org.freedesktop.login1"])
+      .stdout(std::process::Stdio::piped())
+      .stderr(std::process::Stdio::null())
+      .kill_on_drop(true)
+      .spawn();
+    let mut child = match child {
+      Ok(child) => child,
+      Err(e) => {
+        log::info!(
+          "logind monitor unavailable ({}); relying on wake-up detection",
+          e
+        );
+        return;
+      }
+    };
+    let Some(stdout) = child.stdout.take() else {
+      return;
+    };
+
+    let mut lines = BufReader::new(stdout).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+      if let Some(event) = parse_logind_signal(&line, session.as_deref()) {
+        handle_power_event(&app, event).await;
+      }
+    }
+    log::warn!("logind monitor stopped");
+  }
+
+  /// Object path of the logind session JellyPilot runs in.
+  async fn own_session() -> Option<String> {
+    if let Some(id) = std::env::var("XDG_SESSION_ID")
+      .ok()
+      .filter(|id| !id.is_empty())
+    {
+      return Some(session_object_path(&id));
+    }
+    // Started outside a login shell, e.g. by a service manager.
+    let output = Command::new("gdbus")
+      .args([
+        "call",
+        "--system",
+        "--dest",
+        "org.freedesktop.login1",
+        "--object-path",
+        "/org/freedesktop/login1",
+        "--method",
+        "org.freedesktop.login1.Manager.GetSessionByPID",
+        &std::process::id().to_string(),
+      ])
+      .stderr(std::process::Stdio::null())
+      .output()
+      .await
+      .ok()?;
+    // `(objectpath '/org/freedesktop/login1/session/_32',)`
+    let reply = String::from_utf8_lossy(&output.stdout);
+    let path = reply.split('\'').nth(1)?;
+    path
+      .starts_with("/org/freedesktop/login1/session/")
+      .then(|| path.to_string())
+  }
+}
+
+/// logind's object path for a session ID: bytes other than ASCII letters
+/// and digits, and a leading digit, are escaped as `_xx`.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn session_object_path(id: &str) -> String {
+  let mut path = String::from("/org/freedesktop/login1/session/");
+  for (i, byte) in id.bytes().enumerate() {
+    if byte.is_ascii_alphabetic() || (i > 0 && byte.is_ascii_digit()) {
+      path.push(byte as char);
+    } else {
+      path.push_str(&format!("_{byte:02x}"));
+    }
+  }
+  path
+}
+
+/// Parse one line of `gdbus monitor` output for logind. Only `session`, our
+/// own session's object path, can lock playback; other users share logind.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_logind_signal(line: &str, session: Option<&str>) -> Option<PowerEvent> {
+  if line.contains("org.freedesktop.login1.Manager.PrepareForSleep") {
+    // `(true,)` before suspending, `(false,)` after waking up.
+    return Some(if line.contains("(true,)") {
+      PowerEvent::Sleeping
+    } else {
+      PowerEvent::Resumed
+    });
+  }
+  if line.contains("org.freedesktop.login1.Session.Lock ") {
+    let (sender, _) = line.split_once(": ")?;
+    return (Some(sender) == session).then_some(PowerEvent::Locked);
+  }
+  None
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn sleep_detector_flags_only_large_wall_clock_gaps() {
+    let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+    let mut detector = SleepDetector::new(start);
+
+    assert!(!detector.observe(start + Duration::from_secs(11)));
+    assert!(detector.observe(start + Duration::from_secs(11 + 600)));
+    // Clock moved backwards (NTP correction): not a sleep.
+    assert!(!detector.observe(start));
+  }
+
+  #[test]
+  fn parses_logind_sleep_and_lock_signals() {
+    let session = Some("/org/freedesktop/login1/session/_32");
+    assert_eq!(
+      parse_logind_signal(
+        "/org/freedesktop/login1: org.freedesktop.login1.Manager.PrepareForSleep (true,)",
+        session
+      ),
+      Some(PowerEvent::Sleeping)
+    );
+    assert_eq!(
+      parse_logind_signal(
+        "/org/freedesktop/login1: org.freedesktop.login1.Manager.PrepareForSleep (false,)",
+        session
+      ),
+      Some(PowerEvent::Resumed)
+    );
+    assert_eq!(
+      parse_logind_signal(
+        "/org/freedesktop/login1/session/_32: org.freedesktop.login1.Session.Lock ()",
+        session
+      ),
+      Some(PowerEvent::Locked)
+    );
+    assert_eq!(
+      parse_logind_signal(
+        "/org/freedesktop/login1/session/_32: org.freedesktop.login1.Session.Unlock ()",
+        session
+      ),
+      None
+    );
+  }
+
+  #[test]
+  fn locks_of_other_sessions_are_ignored() {
+    let lock = "/org/freedesktop/login1/session/_35: org.freedesktop.login1.Session.Lock ()";
+
+    assert_eq!(
+      parse_logind_signal(lock, Some("/org/freedesktop/login1/session/_32")),
+      None
+    );
+    assert_eq!(parse_logind_signal(lock, None), None);
+  }
+
+  #[test]
+  fn session_ids_are_escaped_like_logind_does() {
+    assert_eq!(
+      session_object_path("2"),
+      "/org/freedesktop/login1/session/_32"
+    );
+    assert_eq!(
+      session_object_path("c12"),
+      "/org/freedesktop/login1/session/c12"
+    );
+    assert_eq!(
+      session_object_path("a-1"),
+      "/org/freedesktop/login1/session/a_2d1"
+    );
+  }
+}
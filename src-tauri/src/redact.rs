@@ -0,0 +1,197 @@
+//! Redaction of access tokens and passwords in log text.
+//!
+//! Used for everything that may end up in a log or diagnostics bundle: stream
+//! and WebSocket URLs, authorization headers, and Debug or JSON dumps of
+//! requests and sessions. Matching is by key name, so a secret is masked
+//! whether it appears as `api_key=...`, `Token="..."`, `X-Emby-Token: ...`,
+//! `"AccessToken":"..."` or `access_token: "..."`.
+
+/// Replacement for redacted values.
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Keys whose value is a secret, compared case-insensitively.
+const SENSITIVE_KEYS: &[&str] = &[
+  "api_key",
+  "apikey",
+  "access_token",
+  "accesstoken",
+  "token",
+  "x-emby-token",
+  "x-mediabrowser-token",
+  "password",
+  "pw",
+];
+
+/// Mask secret values in `text`, leaving everything else as is.
+pub fn redact(text: &str) -> String {
+  let mut output = String::with_capacity(text.len());
+  let mut cursor = 0;
+
+  while cursor < text.len() {
+    let Some(value_start) = find_sensitive_value(&text[cursor..]) else {
+      output.push_str(&text[cursor..]);
+      break;
+    };
+
+    let value_start = cursor + value_start;
+    let quote = text[value_start..]
+      .chars()
+      .next()
+      .filter(|ch| matches!(ch, '"' | '\''));
+    let value_start = value_start + quote.map(char::len_utf8).unwrap_or(0);
+    let value_end = find_value_end(text, value_start, quote);
+
+    output.push_str(&text[cursor..value_start]);
+    output.push_str(REDACTED);
+    if let Some(quote) = quote {
+      if value_end < text.len() && text[value_end..].starts_with(quote) {
+        output.push(quote);
+        cursor = value_end + quote.len_utf8();
+        continue;
+      }
+    }
+    cursor = value_end;
+  }
+
+  output
+}
+
+/// Offset of the first value assigned to a sensitive key, after its `=` or
+/// `:` separator and any spaces.
+fn find_sensitive_value(text: &str) -> Option<usize> {
+  let bytes = text.as_bytes();
+  let mut index = 0;
+
+  while index < bytes.len() {
+    if !is_key_boundary(bytes, index) {
+      index += 1;
+      continue;
+    }
+
+    let key_start = index + boundary_len(bytes, index);
+    let mut key_end = key_start;
+    while key_end < bytes.len() && is_key_byte(bytes[key_end]) {
+      key_end += 1;
+    }
+    let is_sensitive = SENSITIVE_KEYS
+      .iter()
+      .any(|key| text[key_start..key_end].eq_ignore_ascii_case(key));
+
+    // A JSON key is followed by its closing quote before the separator.
+    let mut separator = key_end;
+    if separator < bytes.len() && bytes[separator] == b'"' {
+      separator += 1;
+    }
+    if is_sensitive && separator < bytes.len() && matches!(bytes[separator], b'=' | b':') {
+      let mut value_start = separator + 1;
+      while value_start < bytes.len() && bytes[value_start] == b' ' {
+        value_start += 1;
+      }
+      return Some(value_start);
+    }
+
+    index = key_end.max(index + 1);
+  }
+
+  None
+}
+
+fn is_key_boundary(bytes: &[u8], index: usize) -> bool {
+  index == 0
+    || matches!(
+      bytes[index],
+      b'?' | b'&' | b',' | b' ' | b'\t' | b'\n' | b'"' | b'{' | b'(' | b';' | b'=' | b':'
+    )
+}
+
+fn boundary_len(bytes: &[u8], index: usize) -> usize {
+  if index == 0 && is_key_byte(bytes[0]) {
+    0
+  } else {
+    1
+  }
+}
+
+fn is_key_byte(byte: u8) -> bool {
+  byte.is_ascii_alphanumeric() || matches!(byte, b'_' | b'-')
+}
+
+fn find_value_end(text: &str, value_start: usize, quote: Option<char>) -> usize {
+  if let Some(quote) = quote {
+    text[value_start..]
+      .find(quote)
+      .map(|offset| value_start + offset)
+      .unwrap_or(text.len())
+  } else {
+    text[value_start..]
+      .find(['&', ',', ' ', '\t', '\n', '\r', '"', '\'', ')', '}'])
+      .map(|offset| value_start + offset)
+      .unwrap_or(text.len())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn stream_websocket_and_login_secrets_are_masked() {
+    let input = concat!(
+      "http://media.test/Videos/1/stream.mkv?MediaSourceId=source-1",
+      "&api_key=stream-token",
+      "&AccessToken=access-token",
+      "&password=login-secret",
+      " ws://media.test/socket?api_key=socket-token&deviceId=device-1"
+    );
+
+    let redacted = redact(input);
+
+    assert!(!redacted.contains("stream-token"));
+    assert!(!redacted.contains("access-token"));
+    assert!(!redacted.contains("login-secret"));
+    assert!(!redacted.contains("socket-token"));
+    assert!(redacted.contains("api_key=[REDACTED]"));
+    assert!(redacted.contains("AccessToken=[REDACTED]"));
+    assert!(redacted.contains("password=[REDACTED]"));
+    assert!(redacted.contains("deviceId=device-1"));
+  }
+
+  #[test]
+  fn header_values_are_masked() {
+    let redacted = redact(concat!(
+      "Authorization: MediaBrowser Client=\"JellyPilot\", Token=\"tok-456\"\n",
+      "X-Emby-Token: header-token\n",
+      "http-header-fields=X-Emby-Token: field-token"
+    ));
+
+    assert_eq!(
+      redacted,
+      concat!(
+        "Authorization: MediaBrowser Client=\"JellyPilot\", Token=\"[REDACTED]\"\n",
+        "X-Emby-Token: [REDACTED]\n",
+        "http-header-fields=X-Emby-Token: [REDACTED]"
+      )
+    );
+  }
+
+  #[test]
+  fn json_and_debug_dumps_are_masked() {
+    let json = redact(r#"{"AccessToken":"json-token","ServerId":"server-1"}"#);
+    assert_eq!(
+      json,
+      r#"{"AccessToken":"[REDACTED]","ServerId":"server-1"}"#
+    );
+
+    let debug = redact(r#"SavedSession { access_token: "debug-token", user_id: "user-1" }"#);
+    assert_eq!(
+      debug,
+      r#"SavedSession { access_token: "[REDACTED]", user_id: "user-1" }"#
+    );
+  }
+
+  #[test]
+  fn text_without_secrets_is_unchanged() {
+    let text = "GET http://[fd00::10]:8096/Items?Static=true&deviceId=device-1 tokens: 5";
+    assert_eq!(redact(text), text);
+  }
+}
@@ -0,0 +1,116 @@
+//! Bounded records kept while JellyPilot runs, and what they currently hold.
+//!
+//! JellyPilot sits in the tray for days. Anything it records for diagnostics
+//! keeps only the newest entries, up to a limit that can be set in the
+//! config (`command_audit_retention`, `mpv_log_retention`), so memory and
+//! disk use stay flat. `diagnostics_memory_report` lists every such record
+//! with its size.
+
+use std::collections::VecDeque;
+
+use serde::Serialize;
+use specta::Type;
+
+/// Ring buffer keeping the newest `capacity` entries.
+#[derive(Debug)]
+pub struct BoundedBuffer<T> {
+  entries: VecDeque<T>,
+  capacity: usize,
+}
+
+impl<T> BoundedBuffer<T> {
+  pub fn new(capacity: usize) -> Self {
+    Self {
+      entries: VecDeque::new(),
+      capacity,
+    }
+  }
+
+  /// Add `entry`, dropping the oldest ones beyond the capacity.
+  pub fn push(&mut self, entry: T) {
+    if self.capacity == 0 {
+      return;
+    }
+    while self.entries.len() >= self.capacity {
+      self.entries.pop_front();
+    }
+    self.entries.push_back(entry);
+  }
+
+  /// Change the capacity; the oldest entries beyond a smaller one are dropped.
+  pub fn set_capacity(&mut self, capacity: usize) {
+    self.capacity = capacity;
+    let excess = self.entries.len().saturating_sub(capacity);
+    self.entries.drain(..excess);
+    self.entries.shrink_to(capacity);
+  }
+
+  pub fn capacity(&self) -> usize {
+    self.capacity
+  }
+
+  pub fn len(&self) -> usize {
+    self.entries.len()
+  }
+
+  /// Entries oldest first.
+  pub fn iter(&self) -> impl Iterator<Item = &T> {
+    self.entries.iter()
+  }
+}
+
+/// What one bounded record holds, for `diagnostics_memory_report`.
+#[derive(Debug, Clone, PartialEq, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct BufferUsage {
+  pub name: String,
+  pub entries: u32,
+  /// Entries kept at most.
+  pub capacity: u32,
+  /// Estimated size of the entries, in memory or on disk.
+  pub bytes: f64,
+}
+
+impl BufferUsage {
+  pub fn new(name: &str, entries: usize, capacity: usize, bytes: u64) -> Self {
+    Self {
+      name: name.to_string(),
+      entries: u32::try_from(entries).unwrap_or(u32::MAX),
+      capacity: u32::try_from(capacity).unwrap_or(u32::MAX),
+      bytes: bytes as f64,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn the_oldest_entries_are_dropped_beyond_the_capacity() {
+    let mut buffer = BoundedBuffer::new(3);
+    for n in 1..=5 {
+      buffer.push(n);
+    }
+    assert_eq!(buffer.iter().copied().collect::<Vec<_>>(), [3, 4, 5]);
+  }
+
+  #[test]
+  fn shrinking_keeps_the_newest_entries() {
+    let mut buffer = BoundedBuffer::new(5);
+    for n in 1..=5 {
+      buffer.push(n);
+    }
+    buffer.set_capacity(2);
+    assert_eq!(buffer.iter().copied().collect::<Vec<_>>(), [4, 5]);
+    buffer.push(6);
+    assert_eq!(buffer.iter().copied().collect::<Vec<_>>(), [5, 6]);
+  }
+
+  #[test]
+  fn a_zero_capacity_keeps_nothing() {
+    let mut buffer = BoundedBuffer::new(0);
+    buffer.push(1);
+    assert_eq!(buffer.len(), 0);
+  }
+}
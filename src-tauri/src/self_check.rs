@@ -0,0 +1,352 @@
+//! Startup self-check for the settings window.
+//!
+//! Runs the checks behind most "nothing happens when I cast" reports: MPV
+//! installed and recent enough, a writable IPC socket location, and a server
+//! that is reachable, accepts the token and the WebSocket, and lists this
+//! device as a cast target. Every failed check carries a hint on how to fix it.
+
+use std::path::PathBuf;
+
+use serde::Serialize;
+use specta::Type;
+
+use crate::config::AppConfig;
+use crate::jellyfin::{JellyfinClient, JellyfinError, SessionManager};
+use crate::mpv::{detect_version, find_mpv, MIN_IPC_MPV_VERSION, MIN_MPV_VERSION};
+use crate::redact::redact;
+
+/// What a self-check looked at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum SelfCheckKind {
+  MpvBinary,
+  MpvVersion,
+  IpcPath,
+  ServerReachable,
+  TokenValid,
+  WebSocket,
+  Capabilities,
+}
+
+/// Outcome of a self-check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum SelfCheckStatus {
+  Passed,
+  /// Works, but something is likely to cause trouble.
+  Warning,
+  Failed,
+  /// Not run because an earlier check failed or does not apply.
+  Skipped,
+}
+
+/// One row of the self-check list.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SelfCheck {
+  pub kind: SelfCheckKind,
+  pub status: SelfCheckStatus,
+  /// What was found, e.g. the MPV path or the server's error.
+  pub detail: String,
+  /// How to fix a warning or failure.
+  pub hint: Option<String>,
+}
+
+impl SelfCheck {
+  fn passed(kind: SelfCheckKind, detail: impl Into<String>) -> Self {
+    Self {
+      kind,
+      status: SelfCheckStatus::Passed,
+      detail: detail.into(),
+      hint: None,
+    }
+  }
+
+  fn skipped(kind: SelfCheckKind, detail: impl Into<String>) -> Self {
+    Self {
+      kind,
+      status: SelfCheckStatus::Skipped,
+      detail: detail.into(),
+      hint: None,
+    }
+  }
+
+  fn problem(
+    kind: SelfCheckKind,
+    status: SelfCheckStatus,
+    detail: impl Into<String>,
+    hint: impl Into<String>,
+  ) -> Self {
+    Self {
+      kind,
+      status,
+      detail: detail.into(),
+      hint: Some(hint.into()),
+    }
+  }
+}
+
+/// Run all checks in order.
+pub async fn run_checks(
+  config: &AppConfig,
+  client: &JellyfinClient,
+  session: Option<&SessionManager>,
+) -> Vec<SelfCheck> {
+  let mpv_path = config.resolved_mpv_path().or_else(find_mpv);
+
+  let mut checks = vec![check_mpv_binary(mpv_path.as_ref())];
+  checks.push(check_mpv_version(mpv_path).await);
+  checks.push(check_ipc_path());
+  checks.extend(check_server(client, session).await);
+  checks
+}
+
+fn check_mpv_binary(path: Option<&PathBuf>) -> SelfCheck {
+  match path {
+    Some(path) if path.exists() => {
+      SelfCheck::passed(SelfCheckKind::MpvBinary, path.display().to_string())
+    }
+    Some(path) => SelfCheck::problem(
+      SelfCheckKind::MpvBinary,
+      SelfCheckStatus::Failed,
+      format!("{} does not exist", path.display()),
+      "Fix the MPV path in Settings or clear it to detect MPV automatically.",
+    ),
+    None => SelfCheck::problem(
+      SelfCheckKind::MpvBinary,
+      SelfCheckStatus::Failed,
+      "MPV was not found",
+      "Install MPV or set the path to the MPV executable in Settings.",
+    ),
+  }
+}
+
+async fn check_mpv_version(path: Option<PathBuf>) -> SelfCheck {
+  let Some(path) = path.filter(|path| path.exists()) else {
+    return SelfCheck::skipped(SelfCheckKind::MpvVersion, "MPV was not found");
+  };
+
+  let detected = tauri::async_runtime::spawn_blocking(move || detect_version(&path)).await;
+  match detected {
+    Ok(Ok((Some(version), _))) if version >= MIN_MPV_VERSION => {
+      SelfCheck::passed(SelfCheckKind::MpvVersion, format!("MPV {}", version))
+    }
+    Ok(Ok((Some(version), _))) if version >= MIN_IPC_MPV_VERSION => SelfCheck::problem(
+      SelfCheckKind::MpvVersion,
+      SelfCheckStatus::Warning,
+      format!(
+        "MPV {} is older than {}; some features are turned off",
+        version, MIN_MPV_VERSION
+      ),
+      format!("Update MPV to {} or newer.", MIN_MPV_VERSION),
+    ),
+    Ok(Ok((Some(version), _))) => SelfCheck::problem(
+      SelfCheckKind::MpvVersion,
+      SelfCheckStatus::Failed,
+      format!("MPV {} is too old to be controlled", version),
+      format!("Update MPV to {} or newer.", MIN_MPV_VERSION),
+    ),
+    Ok(Ok((None, first_line))) => SelfCheck::problem(
+      SelfCheckKind::MpvVersion,
+      SelfCheckStatus::Warning,
+      format!("Unrecognized version output: {}", first_line),
+      format!(
+        "Make sure the configured executable is MPV {} or newer.",
+        MIN_MPV_VERSION
+      ),
+    ),
+    Ok(Err(e)) => SelfCheck::problem(
+      SelfCheckKind::MpvVersion,
+      SelfCheckStatus::Failed,
+      format!("Could not run MPV: {}", e),
+      "Check that the MPV executable can be run by your user.",
+    ),
+    Err(e) => SelfCheck::problem(
+      SelfCheckKind::MpvVersion,
+      SelfCheckStatus::Warning,
+      format!("Version check did not finish: {}", e),
+      "Run the checks again.",
+    ),
+  }
+}
+
+#[cfg(windows)]
+fn check_ipc_path() -> SelfCheck {
+  SelfCheck::passed(SelfCheckKind::IpcPath, crate::mpv::ipc_path())
+}
+
+#[cfg(not(windows))]
+fn check_ipc_path() -> SelfCheck {
+  let socket = crate::mpv::ipc_path();
+  match crate::mpv::prepare_ipc_dir() {
+    Ok(_) => SelfCheck::passed(SelfCheckKind::IpcPath, socket),
+    Err(e) => SelfCheck::problem(
+      SelfCheckKind::IpcPath,
+      SelfCheckStatus::Failed,
+      format!("Cannot use the socket directory: {}", e),
+      "Set XDG_RUNTIME_DIR to a writable directory only you can access, or allow the sandbox to access it.",
+    ),
+  }
+}
+
+async fn check_server(client: &JellyfinClient, session: Option<&SessionManager>) -> Vec<SelfCheck> {
+  const SERVER_CHECKS: [SelfCheckKind; 4] = [
+    SelfCheckKind::ServerReachable,
+    SelfCheckKind::TokenValid,
+    SelfCheckKind::WebSocket,
+    SelfCheckKind::Capabilities,
+  ];
+  let skip_all = |detail: &str| -> Vec<SelfCheck> {
+    SERVER_CHECKS
+      .iter()
+      .map(|kind| SelfCheck::skipped(*kind, detail))
+      .collect()
+  };
+
+  if !client.is_connected() {
+    return skip_all("Not signed in to a server");
+  }
+  let playback = client.playback();
+
+  if let Err(e) = playback.probe_server().await {
+    let mut checks = skip_all("The server is not reachable");
+    checks[0] = SelfCheck::problem(
+      SelfCheckKind::ServerReachable,
+      SelfCheckStatus::Failed,
+      redact(&e.to_string()),
+      "Check the server address and that this device can reach it on the network.",
+    );
+    return checks;
+  }
+  let mut checks = vec![SelfCheck::passed(
+    SelfCheckKind::ServerReachable,
+    client.connection_state().server_url.unwrap_or_default(),
+  )];
+
+  let token = match playback.validate_session().await {
+    Ok(()) => SelfCheck::passed(SelfCheckKind::TokenValid, "The server accepts the session"),
+    Err(e) if e.is_credentials_expired() => SelfCheck::problem(
+      SelfCheckKind::TokenValid,
+      SelfCheckStatus::Failed,
+      redact(&e.to_string()),
+      "Sign in again.",
+    ),
+    Err(e) => SelfCheck::problem(
+      SelfCheckKind::TokenValid,
+      SelfCheckStatus::Warning,
+      redact(&e.to_string()),
+      "Run the checks again; sign in again if this keeps failing.",
+    ),
+  };
+  let token_failed = token.status == SelfCheckStatus::Failed;
+  checks.push(token);
+  if token_failed {
+    checks.push(SelfCheck::skipped(
+      SelfCheckKind::WebSocket,
+      "The session is not valid",
+    ));
+    checks.push(SelfCheck::skipped(
+      SelfCheckKind::Capabilities,
+      "The session is not valid",
+    ));
+    return checks;
+  }
+
+  if !client.supports_remote_control() {
+    checks.push(SelfCheck::skipped(
+      SelfCheckKind::WebSocket,
+      "Remote control is not available on this server",
+    ));
+    checks.push(SelfCheck::skipped(
+      SelfCheckKind::Capabilities,
+      "Remote control is not available on this server",
+    ));
+    return checks;
+  }
+
+  checks.push(check_websocket(client, session).await);
+  checks.push(check_capabilities(client).await);
+  checks
+}
+
+async fn check_websocket(client: &JellyfinClient, session: Option<&SessionManager>) -> SelfCheck {
+  if session.is_some_and(SessionManager::is_websocket_connected) {
+    return SelfCheck::passed(SelfCheckKind::WebSocket, "Remote control is connected");
+  }
+
+  match client.playback().probe_websocket().await {
+    Ok(()) => SelfCheck::problem(
+      SelfCheckKind::WebSocket,
+      SelfCheckStatus::Warning,
+      "The server accepts WebSocket connections, but remote control is not connected",
+      "Reconnect to the server to restart remote control.",
+    ),
+    Err(e) => SelfCheck::problem(
+      SelfCheckKind::WebSocket,
+      SelfCheckStatus::Failed,
+      redact(&e.to_string()),
+      websocket_hint(&e),
+    ),
+  }
+}
+
+fn websocket_hint(error: &JellyfinError) -> &'static str {
+  if error.is_credentials_expired() {
+    "Sign in again."
+  } else {
+    "If the server is behind a reverse proxy, enable WebSocket forwarding for /socket."
+  }
+}
+
+async fn check_capabilities(client: &JellyfinClient) -> SelfCheck {
+  match client.playback().own_session().await {
+    Ok(Some(session)) if session.supports_remote_control => SelfCheck::passed(
+      SelfCheckKind::Capabilities,
+      "JellyPilot is listed as a cast target",
+    ),
+    Ok(Some(_)) => SelfCheck::problem(
+      SelfCheckKind::Capabilities,
+      SelfCheckStatus::Failed,
+      "The server does not list JellyPilot as controllable",
+      "Reconnect to the server so JellyPilot registers as a cast target again.",
+    ),
+    Ok(None) => SelfCheck::problem(
+      SelfCheckKind::Capabilities,
+      SelfCheckStatus::Failed,
+      "The server has no session for this device",
+      "Reconnect to the server so JellyPilot registers as a cast target again.",
+    ),
+    Err(e) => SelfCheck::problem(
+      SelfCheckKind::Capabilities,
+      SelfCheckStatus::Warning,
+      redact(&e.to_string()),
+      "Run the checks again.",
+    ),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn missing_mpv_fails_with_a_hint() {
+    let check = check_mpv_binary(Some(&PathBuf::from("/nonexistent/mpv")));
+    assert_eq!(check.status, SelfCheckStatus::Failed);
+    assert!(check.hint.is_some());
+
+    let check = check_mpv_binary(None);
+    assert_eq!(check.kind, SelfCheckKind::MpvBinary);
+    assert_eq!(check.status, SelfCheckStatus::Failed);
+  }
+
+  #[tokio::test]
+  async fn server_checks_are_skipped_when_signed_out() {
+    let checks = check_server(&JellyfinClient::new(), None).await;
+
+    assert_eq!(checks.len(), 4);
+    assert!(checks
+      .iter()
+      .all(|check| check.status == SelfCheckStatus::Skipped));
+  }
+}
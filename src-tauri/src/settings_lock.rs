@@ -0,0 +1,277 @@
+//! Optional PIN gate for settings and destructive actions.
+//!
+//! Meant for shared HTPC setups: the PIN keeps kids out of the settings
+//! window, logout and preference deletion. Only a salted, stretched SHA-256
+//! hash of the PIN is stored in the config. A correct PIN unlocks for a few
+//! minutes; the commands that change settings check the lock themselves, so
+//! hiding the settings window in the frontend is not the only protection.
+//! After a few wrong PINs each further try has to wait longer, so the short
+//! PIN can't be guessed by trying them all. Hashing takes a moment, so callers
+//! run [`SettingsPin::new`] and [`SettingsPin::verify`] off the async runtime.
+
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use specta::Type;
+
+/// How long a correct PIN keeps settings unlocked.
+const UNLOCK_DURATION: Duration = Duration::from_secs(10 * 60);
+/// Hash rounds, so that a copied config does not give the PIN away instantly.
+const HASH_ROUNDS: u32 = 100_000;
+const MIN_PIN_DIGITS: usize = 4;
+const MAX_PIN_DIGITS: usize = 12;
+/// Wrong PINs accepted before tries are throttled.
+const FREE_ATTEMPTS: u32 = 3;
+/// Wait after the first throttled failure; doubles with each further one.
+const FIRST_BACKOFF: Duration = Duration::from_secs(5);
+const MAX_BACKOFF: Duration = Duration::from_secs(15 * 60);
+
+/// Salted hash of the settings PIN.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsPin {
+  /// Random salt as hex.
+  pub salt: String,
+  /// Stretched SHA-256 of salt and PIN as hex.
+  pub hash: String,
+}
+
+impl SettingsPin {
+  /// Hash a new PIN of 4 to 12 digits with a fresh salt.
+  pub fn new(pin: &str) -> Result<Self, String> {
+    if pin.len() < MIN_PIN_DIGITS
+      || pin.len() > MAX_PIN_DIGITS
+      || !pin.chars().all(|c| c.is_ascii_digit())
+    {
+      return Err(format!(
+        "PIN must be {} to {} digits",
+        MIN_PIN_DIGITS, MAX_PIN_DIGITS
+      ));
+    }
+    let salt = to_hex(uuid::Uuid::new_v4().as_bytes());
+    Ok(Self {
+      hash: hash_pin(&salt, pin),
+      salt,
+    })
+  }
+
+  /// True when `pin` matches.
+  pub fn verify(&self, pin: &str) -> bool {
+    let hash = hash_pin(&self.salt, pin);
+    // Compare without stopping at the first difference.
+    hash.len() == self.hash.len()
+      && hash
+        .bytes()
+        .zip(self.hash.bytes())
+        .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+        == 0
+  }
+}
+
+/// Whether settings are locked, exposed to the frontend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsLockStatus {
+  /// A PIN is configured.
+  pub pin_set: bool,
+  /// Settings can be changed right now.
+  pub unlocked: bool,
+}
+
+/// Why an unlock was refused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnlockError {
+  WrongPin,
+  /// Too many wrong PINs; the next try is accepted after `retry_in`.
+  Throttled {
+    retry_in: Duration,
+  },
+}
+
+/// Unlock state for the running app; never persisted.
+#[derive(Debug, Default)]
+pub struct SettingsLock {
+  unlocked_until: Option<Instant>,
+  /// Wrong PINs since the last successful unlock.
+  failed_attempts: u32,
+  /// No PIN is checked before this.
+  retry_at: Option<Instant>,
+}
+
+impl SettingsLock {
+  /// True when there is no PIN or it was entered recently.
+  pub fn is_unlocked(&self, pin: Option<&SettingsPin>, now: Instant) -> bool {
+    pin.is_none() || self.unlocked_until.is_some_and(|until| now < until)
+  }
+
+  pub fn status(&self, pin: Option<&SettingsPin>, now: Instant) -> SettingsLockStatus {
+    SettingsLockStatus {
+      pin_set: pin.is_some(),
+      unlocked: self.is_unlocked(pin, now),
+    }
+  }
+
+  /// Start an unlock attempt; while throttled the PIN mustn't be checked at
+  /// all. The attempt counts as wrong until [`Self::unlocked`] is called, so
+  /// tries made while another one is being checked are throttled too.
+  pub fn begin_unlock(&mut self, now: Instant) -> Result<(), UnlockError> {
+    if let Some(retry_at) = self.retry_at.filter(|retry_at| now < *retry_at) {
+      return Err(UnlockError::Throttled {
+        retry_in: retry_at - now,
+      });
+    }
+    self.failed_attempts += 1;
+    self.retry_at = backoff(self.failed_attempts).map(|wait| now + wait);
+    Ok(())
+  }
+
+  /// The PIN of the attempt started last was right.
+  pub fn unlocked(&mut self, now: Instant) {
+    self.failed_attempts = 0;
+    self.retry_at = None;
+    self.unlocked_until = Some(now + UNLOCK_DURATION);
+  }
+
+  pub fn lock(&mut self) {
+    self.unlocked_until = None;
+  }
+}
+
+/// Wait after `failed_attempts` wrong PINs in a row.
+fn backoff(failed_attempts: u32) -> Option<Duration> {
+  let throttled = failed_attempts.checked_sub(FREE_ATTEMPTS)?;
+  let factor = 1u32.checked_shl(throttled).unwrap_or(u32::MAX);
+  Some(
+    FIRST_BACKOFF
+      .checked_mul(factor)
+      .map_or(MAX_BACKOFF, |wait| wait.min(MAX_BACKOFF)),
+  )
+}
+
+fn hash_pin(salt: &str, pin: &str) -> String {
+  let mut digest: [u8; 32] = Sha256::new()
+    .chain_update(salt.as_bytes())
+    .chain_update(pin.as_bytes())
+    .finalize()
+    .into();
+  for _ in 1..HASH_ROUNDS {
+    digest = Sha256::new()
+      .chain_update(digest)
+      .chain_update(salt.as_bytes())
+      .finalize()
+      .into();
+  }
+  to_hex(&digest)
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+  bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn unlock(
+    lock: &mut SettingsLock,
+    pin: &SettingsPin,
+    entered: &str,
+    now: Instant,
+  ) -> Result<(), UnlockError> {
+    lock.begin_unlock(now)?;
+    if !pin.verify(entered) {
+      return Err(UnlockError::WrongPin);
+    }
+    lock.unlocked(now);
+    Ok(())
+  }
+
+  #[test]
+  fn pins_are_salted_and_verified() {
+    let first = SettingsPin::new("1234").unwrap();
+    let second = SettingsPin::new("1234").unwrap();
+
+    assert_ne!(first.salt, second.salt);
+    assert_ne!(first.hash, second.hash);
+    assert!(first.verify("1234"));
+    assert!(!first.verify("4321"));
+  }
+
+  #[test]
+  fn pins_must_be_short_digit_strings() {
+    assert!(SettingsPin::new("123").is_err());
+    assert!(SettingsPin::new("12a4").is_err());
+    assert!(SettingsPin::new(&"1".repeat(13)).is_err());
+  }
+
+  #[test]
+  fn unlock_expires_and_needs_the_right_pin() {
+    let pin = SettingsPin::new("2468").unwrap();
+    let now = Instant::now();
+    let mut lock = SettingsLock::default();
+
+    assert!(lock.is_unlocked(None, now));
+    assert!(!lock.is_unlocked(Some(&pin), now));
+    assert_eq!(
+      unlock(&mut lock, &pin, "0000", now),
+      Err(UnlockError::WrongPin)
+    );
+    assert_eq!(unlock(&mut lock, &pin, "2468", now), Ok(()));
+    assert!(lock.is_unlocked(Some(&pin), now + Duration::from_secs(60)));
+    assert!(!lock.is_unlocked(Some(&pin), now + UNLOCK_DURATION));
+
+    lock.lock();
+    assert!(!lock.is_unlocked(Some(&pin), now));
+  }
+
+  #[test]
+  fn repeated_wrong_pins_are_throttled() {
+    let pin = SettingsPin::new("2468").unwrap();
+    let now = Instant::now();
+    let mut lock = SettingsLock::default();
+
+    for _ in 0..FREE_ATTEMPTS {
+      assert_eq!(
+        unlock(&mut lock, &pin, "0000", now),
+        Err(UnlockError::WrongPin)
+      );
+    }
+    // The right PIN is not even checked while throttled.
+    assert_eq!(
+      unlock(&mut lock, &pin, "2468", now),
+      Err(UnlockError::Throttled {
+        retry_in: FIRST_BACKOFF
+      })
+    );
+
+    // Each further wrong PIN doubles the wait.
+    let later = now + FIRST_BACKOFF;
+    assert_eq!(
+      unlock(&mut lock, &pin, "0000", later),
+      Err(UnlockError::WrongPin)
+    );
+    assert_eq!(
+      unlock(&mut lock, &pin, "2468", later + FIRST_BACKOFF),
+      Err(UnlockError::Throttled {
+        retry_in: FIRST_BACKOFF
+      })
+    );
+
+    let after_wait = later + FIRST_BACKOFF * 2;
+    assert_eq!(unlock(&mut lock, &pin, "2468", after_wait), Ok(()));
+    // A successful unlock starts counting from zero again.
+    assert_eq!(
+      unlock(&mut lock, &pin, "0000", after_wait),
+      Err(UnlockError::WrongPin)
+    );
+    assert_eq!(lock.retry_at, None);
+  }
+
+  #[test]
+  fn the_backoff_is_capped() {
+    assert_eq!(backoff(FREE_ATTEMPTS - 1), None);
+    assert_eq!(backoff(FREE_ATTEMPTS), Some(FIRST_BACKOFF));
+    assert_eq!(backoff(40), Some(MAX_BACKOFF));
+  }
+}
@@ -0,0 +1,306 @@
+//! Opt-in anonymous usage statistics.
+//!
+//! Nothing leaves the machine unless `telemetry_consent` is on. A report
+//! only holds aggregate counters since the previous report and the platform:
+//! playbacks started, MPV crashes, the OS, the app version and the MPV
+//! version. No server, user, item or device name is included. In dry-run
+//! mode the report is logged instead of sent, and `telemetry_preview` shows
+//! the next report at any time.
+//!
+//! The unreported counts and the time of the last report are saved in the
+//! app data dir every few minutes, so a report covers earlier runs too and
+//! goes out at the first start after a day has passed.
+
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+use crate::config::AppConfig;
+use crate::metrics::{self, Counter};
+use crate::mpv::MpvClient;
+
+const REPORT_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+/// How often the counts are saved and a due report is sent.
+const SAVE_INTERVAL: Duration = Duration::from_secs(10 * 60);
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+const TELEMETRY_STORE_FILE: &str = "telemetry.json";
+const TELEMETRY_STORE_KEY: &str = "telemetry";
+
+/// Anonymous usage counters sent to the telemetry endpoint.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct TelemetryReport {
+  pub app_version: String,
+  /// Operating system family, e.g. `linux` or `windows`.
+  pub os: String,
+  pub arch: String,
+  /// None when MPV's version was not detected.
+  pub mpv_version: Option<String>,
+  /// Playbacks started since the previous report.
+  pub playbacks: u32,
+  /// MPV exits during playback since the previous report.
+  pub mpv_crashes: u32,
+}
+
+/// What is kept on disk between runs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SavedLedger {
+  playbacks: u64,
+  mpv_crashes: u64,
+  /// Unix seconds of the last report.
+  last_report: Option<u64>,
+}
+
+/// Counts not reported yet, as (playbacks, crashes) pairs.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Ledger {
+  /// Unreported counts of earlier runs.
+  carried: (u64, u64),
+  /// Counter totals of this run the previous report covered.
+  reported: (u64, u64),
+  /// Unix seconds of the last report.
+  last_report: Option<u64>,
+}
+
+impl Ledger {
+  /// Continue from a saved ledger; the first run starts the interval now.
+  fn restore(saved: SavedLedger, now: u64) -> Self {
+    Self {
+      carried: (saved.playbacks, saved.mpv_crashes),
+      reported: (0, 0),
+      last_report: Some(saved.last_report.unwrap_or(now)),
+    }
+  }
+
+  /// Counts not reported yet, given this run's counter `totals`.
+  fn pending(&self, totals: (u64, u64)) -> (u64, u64) {
+    (
+      self.carried.0 + totals.0.saturating_sub(self.reported.0),
+      self.carried.1 + totals.1.saturating_sub(self.reported.1),
+    )
+  }
+
+  fn is_due(&self, now: u64) -> bool {
+    self
+      .last_report
+      .is_none_or(|last| now.saturating_sub(last) >= REPORT_INTERVAL.as_secs())
+  }
+
+  /// Count everything up to `totals` as delivered.
+  fn mark_reported(&mut self, totals: (u64, u64), now: u64) {
+    self.carried = (0, 0);
+    self.reported = totals;
+    self.last_report = Some(now);
+  }
+
+  fn saved(&self, totals: (u64, u64)) -> SavedLedger {
+    let (playbacks, mpv_crashes) = self.pending(totals);
+    SavedLedger {
+      playbacks,
+      mpv_crashes,
+      last_report: self.last_report,
+    }
+  }
+}
+
+static LEDGER: Mutex<Ledger> = parking_lot::const_mutex(Ledger {
+  carried: (0, 0),
+  reported: (0, 0),
+  last_report: None,
+});
+
+fn totals() -> (u64, u64) {
+  (
+    metrics::count(Counter::PlaybacksStarted),
+    metrics::count(Counter::MpvCrashes),
+  )
+}
+
+fn unix_now() -> u64 {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+/// The report that would be sent now.
+pub fn report(mpv: &MpvClient) -> TelemetryReport {
+  build_report(
+    mpv.version().map(|version| version.to_string()),
+    LEDGER.lock().pending(totals()),
+  )
+}
+
+/// A report of the `pending` counts.
+fn build_report(mpv_version: Option<String>, pending: (u64, u64)) -> TelemetryReport {
+  TelemetryReport {
+    app_version: env!("CARGO_PKG_VERSION").to_string(),
+    os: std::env::consts::OS.to_string(),
+    arch: std::env::consts::ARCH.to_string(),
+    mpv_version,
+    playbacks: saturate(pending.0),
+    mpv_crashes: saturate(pending.1),
+  }
+}
+
+fn saturate(count: u64) -> u32 {
+  u32::try_from(count).unwrap_or(u32::MAX)
+}
+
+fn load(app: &AppHandle) -> SavedLedger {
+  let stored = match app.store(TELEMETRY_STORE_FILE) {
+    Ok(store) => store.get(TELEMETRY_STORE_KEY),
+    Err(e) => {
+      log::warn!("Failed to open telemetry store: {}", e);
+      return SavedLedger::default();
+    }
+  };
+  stored
+    .and_then(|value| {
+      serde_json::from_value(value)
+        .inspect_err(|e| log::warn!("Failed to parse stored telemetry counts: {}", e))
+        .ok()
+    })
+    .unwrap_or_default()
+}
+
+fn save(app: &AppHandle) {
+  let saved = LEDGER.lock().saved(totals());
+  let value = match serde_json::to_value(saved) {
+    Ok(value) => value,
+    Err(e) => {
+      log::error!("Failed to serialize telemetry counts: {}", e);
+      return;
+    }
+  };
+  let result = app.store(TELEMETRY_STORE_FILE).and_then(|store| {
+    store.set(TELEMETRY_STORE_KEY.to_string(), value);
+    store.save()
+  });
+  if let Err(e) = result {
+    log::warn!("Failed to save telemetry counts: {}", e);
+  }
+}
+
+async fn send(endpoint: &str, report: &TelemetryReport) -> Result<(), reqwest::Error> {
+  reqwest::Client::builder()
+    .timeout(DELIVERY_TIMEOUT)
+    .build()?
+    .post(endpoint)
+    .json(report)
+    .send()
+    .await?
+    .error_for_status()?;
+  Ok(())
+}
+
+/// Send a report when one is due and the user consents to it.
+async fn report_if_due(config: &RwLock<AppConfig>, mpv: &MpvClient) {
+  let (consent, dry_run, endpoint) = {
+    let config = config.read();
+    (
+      config.telemetry_consent,
+      config.telemetry_dry_run,
+      config.telemetry_endpoint.trim().to_string(),
+    )
+  };
+  if !consent || !LEDGER.lock().is_due(unix_now()) {
+    return;
+  }
+
+  let totals = totals();
+  let report = build_report(
+    mpv.version().map(|version| version.to_string()),
+    LEDGER.lock().pending(totals),
+  );
+  if dry_run {
+    match serde_json::to_string(&report) {
+      Ok(json) => log::info!("Telemetry dry run, not sent: {}", json),
+      Err(e) => log::error!("Failed to serialize telemetry report: {}", e),
+    }
+  } else if endpoint.is_empty() {
+    log::warn!("Telemetry is on but no endpoint is set");
+    return;
+  } else if let Err(e) = send(&endpoint, &report).await {
+    log::warn!("Failed to send telemetry: {}", e);
+    return;
+  }
+  LEDGER.lock().mark_reported(totals, unix_now());
+}
+
+/// Restore the saved counts, then report once a day while the user consents
+/// to it, starting right away when a day passed since the last report.
+pub fn start(app: AppHandle, config: Arc<RwLock<AppConfig>>, mpv: Arc<MpvClient>) {
+  tauri::async_runtime::spawn(async move {
+    *LEDGER.lock() = Ledger::restore(load(&app), unix_now());
+    loop {
+      report_if_due(&config, &mpv).await;
+      save(&app);
+      tokio::time::sleep(SAVE_INTERVAL).await;
+    }
+  });
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn reports_count_since_the_previous_one_without_identifying_data() {
+    let ledger = Ledger {
+      carried: (0, 0),
+      reported: (4, 2),
+      last_report: None,
+    };
+    let report = build_report(Some("0.38.0".to_string()), ledger.pending((7, 2)));
+
+    assert_eq!(report.playbacks, 3);
+    assert_eq!(report.mpv_crashes, 0);
+    assert_eq!(report.app_version, env!("CARGO_PKG_VERSION"));
+    assert_eq!(report.os, std::env::consts::OS);
+    let json = serde_json::to_value(&report).unwrap();
+    let mut fields: Vec<&String> = json.as_object().unwrap().keys().collect();
+    fields.sort();
+    assert_eq!(
+      fields,
+      [
+        "appVersion",
+        "arch",
+        "mpvCrashes",
+        "mpvVersion",
+        "os",
+        "playbacks"
+      ]
+    );
+  }
+
+  #[test]
+  fn unreported_counts_and_the_last_report_survive_a_restart() {
+    let day = REPORT_INTERVAL.as_secs();
+    let mut ledger = Ledger::restore(SavedLedger::default(), 1_000);
+    assert!(!ledger.is_due(1_000 + day - 1));
+    assert!(ledger.is_due(1_000 + day));
+
+    ledger.mark_reported((2, 1), 1_000 + day);
+    let saved = ledger.saved((5, 1));
+    assert_eq!(
+      saved,
+      SavedLedger {
+        playbacks: 3,
+        mpv_crashes: 0,
+        last_report: Some(1_000 + day),
+      }
+    );
+
+    // The next run starts its counters at zero and carries the saved ones.
+    let restarted = Ledger::restore(saved, 1_000 + day + 60);
+    assert_eq!(restarted.pending((1, 1)), (4, 1));
+    assert!(!restarted.is_due(1_000 + day + 60));
+    assert!(restarted.is_due(1_000 + 2 * day));
+  }
+}
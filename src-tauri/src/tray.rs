@@ -5,26 +5,63 @@
 //! - Next: Play next episode
 //! - Previous: Play previous episode
 //! - Mute: Toggle mute
+//! - Mark Watched/Unwatched: Toggle the played state of the current item
+//! - Favorite: Add the current item to the favorites or remove it
+//! - Continue Watching: Resume recent items from the connected server
 //! - Show Operations Console: Opens/focuses the main window
 //! - Quit: Exits the application
+//!
+//! While something plays, the tooltip shows the item and position, and on
+//! Windows the taskbar button of the main window shows the progress.
+
+use std::time::Duration;
 
+use parking_lot::Mutex;
 use tauri::{
-  menu::{Menu, MenuItem, PredefinedMenuItem},
+  menu::{Menu, MenuItem, PredefinedMenuItem, Submenu},
   tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
-  Manager,
+  Manager, Wry,
 };
 
-use crate::command::{JellyfinState, MpvState};
+use crate::command::{
+  AppNotification, JellyfinState, MpvState, NowPlayingState, NowPlayingStatus, PlaybackError,
+};
+use crate::i18n::tr;
+use crate::jellyfin::{VideoHomeItem, VideoLibraryPlayMode, VideoLibraryPlayRequest};
 use crate::playback_control::{self, AdjacentDirection};
 
+const TRAY_ID: &str = "main";
+/// Tooltip while nothing is playing.
+const TRAY_TOOLTIP: &str = "JellyPilot";
+/// Windows cuts tray tooltips off at 127 characters.
+const TOOLTIP_MAX_CHARS: usize = 127;
+
 /// Menu item IDs
 const MENU_PLAY_PAUSE: &str = "play_pause";
 const MENU_NEXT: &str = "next";
 const MENU_PREVIOUS: &str = "previous";
 const MENU_MUTE: &str = "mute";
+const MENU_TOGGLE_WATCHED: &str = "toggle_watched";
+const MENU_TOGGLE_FAVORITE: &str = "toggle_favorite";
+const MENU_CONTINUE_WATCHING: &str = "continue_watching";
+const MENU_CONTINUE_WATCHING_EMPTY: &str = "continue_watching_empty";
+const MENU_CONTINUE_WATCHING_ITEM_PREFIX: &str = "continue_watching:";
 const MENU_SHOW: &str = "show_console";
 const MENU_QUIT: &str = "quit";
 
+/// Maximum number of resume items listed in the Continue Watching submenu.
+const CONTINUE_WATCHING_LIMIT: usize = 8;
+/// Background refresh period for the Continue Watching submenu.
+const CONTINUE_WATCHING_REFRESH_INTERVAL: Duration = Duration::from_secs(300);
+
+/// Tray handles that are updated after the tray has been built.
+pub struct TrayState {
+  continue_watching: Submenu<Wry>,
+  continue_watching_items: Mutex<Vec<VideoHomeItem>>,
+  /// Fixed menu items with their message ids, relabeled when the language changes.
+  labeled_items: Vec<(MenuItem<Wry>, &'static str)>,
+}
+
 /// Sets up the system tray icon with menu.
 ///
 /// # Menu Items
@@ -32,6 +69,9 @@ const MENU_QUIT: &str = "quit";
 /// - **Next**: Play next episode
 /// - **Previous**: Play previous episode
 /// - **Mute**: Toggle mute
+/// - **Mark Watched/Unwatched**: Toggle the played state of the current item
+/// - **Favorite**: Add the current item to the favorites or remove it
+/// - **Continue Watching**: Resume items, refreshed when the menu opens and on a timer
 /// - **Show Operations Console**: Shows and focuses the main window
 /// - **Quit**: Exits the application
 ///
@@ -39,19 +79,44 @@ const MENU_QUIT: &str = "quit";
 /// - Left-click: Shows and focuses the main window
 pub fn setup_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
   // Create menu items
-  let play_pause_item = MenuItem::with_id(app, MENU_PLAY_PAUSE, "Play/Pause", true, None::<&str>)?;
-  let next_item = MenuItem::with_id(app, MENU_NEXT, "Next", true, None::<&str>)?;
-  let previous_item = MenuItem::with_id(app, MENU_PREVIOUS, "Previous", true, None::<&str>)?;
-  let mute_item = MenuItem::with_id(app, MENU_MUTE, "Mute", true, None::<&str>)?;
-  let separator = PredefinedMenuItem::separator(app)?;
-  let show_item = MenuItem::with_id(
+  let play_pause_item = MenuItem::with_id(
+    app,
+    MENU_PLAY_PAUSE,
+    tr!("tray-play-pause"),
+    true,
+    None::<&str>,
+  )?;
+  let next_item = MenuItem::with_id(app, MENU_NEXT, tr!("tray-next"), true, None::<&str>)?;
+  let previous_item =
+    MenuItem::with_id(app, MENU_PREVIOUS, tr!("tray-previous"), true, None::<&str>)?;
+  let mute_item = MenuItem::with_id(app, MENU_MUTE, tr!("tray-mute"), true, None::<&str>)?;
+  let toggle_watched_item = MenuItem::with_id(
     app,
-    MENU_SHOW,
-    "Show Operations Console",
+    MENU_TOGGLE_WATCHED,
+    tr!("tray-toggle-watched"),
     true,
     None::<&str>,
   )?;
-  let quit_item = MenuItem::with_id(app, MENU_QUIT, "Quit", true, None::<&str>)?;
+  let toggle_favorite_item = MenuItem::with_id(
+    app,
+    MENU_TOGGLE_FAVORITE,
+    tr!("tray-toggle-favorite"),
+    true,
+    None::<&str>,
+  )?;
+  let continue_watching = Submenu::with_id(
+    app,
+    MENU_CONTINUE_WATCHING,
+    tr!("tray-continue-watching"),
+    true,
+  )?;
+  continue_watching.append(&continue_watching_placeholder(
+    app.handle(),
+    &tr!("tray-not-connected"),
+  )?)?;
+  let separator = PredefinedMenuItem::separator(app)?;
+  let show_item = MenuItem::with_id(app, MENU_SHOW, tr!("tray-show-console"), true, None::<&str>)?;
+  let quit_item = MenuItem::with_id(app, MENU_QUIT, tr!("tray-quit"), true, None::<&str>)?;
 
   // Build the menu
   let menu = Menu::with_items(
@@ -61,6 +126,9 @@ pub fn setup_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
       &next_item,
       &previous_item,
       &mute_item,
+      &toggle_watched_item,
+      &toggle_favorite_item,
+      &continue_watching,
       &separator,
       &show_item,
       &quit_item,
@@ -68,10 +136,10 @@ pub fn setup_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
   )?;
 
   // Create tray icon
-  let _tray = TrayIconBuilder::new()
+  let _tray = TrayIconBuilder::with_id(TRAY_ID)
     .icon(app.default_window_icon().unwrap().clone())
     .menu(&menu)
-    .tooltip("JellyPilot")
+    .tooltip(TRAY_TOOLTIP)
     .show_menu_on_left_click(false) // Left-click shows window, right-click shows menu
     .on_menu_event(|app, event| match event.id.as_ref() {
       MENU_PLAY_PAUSE => {
@@ -124,6 +192,24 @@ pub fn setup_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
           }
         });
       }
+      MENU_TOGGLE_WATCHED => {
+        let app_handle = (*app).clone();
+        tauri::async_runtime::spawn(async move {
+          let jellyfin_state = app_handle.state::<JellyfinState>();
+          if let Err(e) = playback_control::toggle_watched(&app_handle, &jellyfin_state).await {
+            log::warn!("Failed to toggle watched state: {}", e);
+          }
+        });
+      }
+      MENU_TOGGLE_FAVORITE => {
+        let app_handle = (*app).clone();
+        tauri::async_runtime::spawn(async move {
+          let jellyfin_state = app_handle.state::<JellyfinState>();
+          if let Err(e) = playback_control::toggle_favorite(&app_handle, &jellyfin_state).await {
+            log::warn!("Failed to toggle favorite: {}", e);
+          }
+        });
+      }
       MENU_SHOW => {
         if let Some(window) = app.get_webview_window("main") {
           let _ = window.show();
@@ -133,24 +219,415 @@ pub fn setup_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
       MENU_QUIT => {
         app.exit(0);
       }
-      _ => {}
+      id => {
+        if let Some(item_id) = id.strip_prefix(MENU_CONTINUE_WATCHING_ITEM_PREFIX) {
+          play_continue_watching_item(app, item_id.to_string());
+        }
+      }
     })
-    .on_tray_icon_event(|tray, event| {
+    .on_tray_icon_event(|tray, event| match event {
       // Left-click on tray icon shows/focuses the window
-      if let TrayIconEvent::Click {
+      TrayIconEvent::Click {
         button: MouseButton::Left,
         button_state: MouseButtonState::Up,
         ..
-      } = event
-      {
+      } => {
         let app = tray.app_handle();
         if let Some(window) = app.get_webview_window("main") {
           let _ = window.show();
           let _ = window.set_focus();
         }
       }
+      // Right-click opens the menu, so refresh resume items as it opens
+      TrayIconEvent::Click {
+        button: MouseButton::Right,
+        button_state: MouseButtonState::Down,
+        ..
+      } => {
+        let app_handle = tray.app_handle().clone();
+        tauri::async_runtime::spawn(async move {
+          refresh_continue_watching(&app_handle).await;
+        });
+      }
+      _ => {}
     })
     .build(app)?;
 
+  app.manage(TrayState {
+    continue_watching,
+    continue_watching_items: Mutex::new(Vec::new()),
+    labeled_items: vec![
+      (play_pause_item, "tray-play-pause"),
+      (next_item, "tray-next"),
+      (previous_item, "tray-previous"),
+      (mute_item, "tray-mute"),
+      (toggle_watched_item, "tray-toggle-watched"),
+      (toggle_favorite_item, "tray-toggle-favorite"),
+      (show_item, "tray-show-console"),
+      (quit_item, "tray-quit"),
+    ],
+  });
+
+  let app_handle = app.handle().clone();
+  tauri::async_runtime::spawn(async move {
+    let mut interval = tokio::time::interval(CONTINUE_WATCHING_REFRESH_INTERVAL);
+    loop {
+      interval.tick().await;
+      refresh_continue_watching(&app_handle).await;
+    }
+  });
+
   Ok(())
 }
+
+/// Reload Continue Watching items from the connected server into the tray submenu.
+pub async fn refresh_continue_watching(app: &tauri::AppHandle) {
+  let jellyfin_state = app.state::<JellyfinState>();
+  let items = if jellyfin_state.client.is_connected() {
+    match jellyfin_state.client.library().continue_watching().await {
+      Ok(items) => Some(
+        items
+          .into_iter()
+          .take(CONTINUE_WATCHING_LIMIT)
+          .collect::<Vec<_>>(),
+      ),
+      Err(e) => {
+        log::warn!("Failed to refresh tray Continue Watching items: {}", e);
+        return;
+      }
+    }
+  } else {
+    None
+  };
+
+  if let Err(e) = rebuild_continue_watching_menu(app, items) {
+    log::warn!("Failed to rebuild tray Continue Watching menu: {}", e);
+  }
+}
+
+/// Show the playing item and position in the tray tooltip and the Windows
+/// taskbar. Called with every Now Playing update from the MPV event listener.
+pub fn show_playback_progress(app: &tauri::AppHandle, now_playing: &NowPlayingState) {
+  if let Some(tray) = app.tray_by_id(TRAY_ID) {
+    let tooltip = playback_tooltip(now_playing);
+    if let Err(e) = tray.set_tooltip(Some(tooltip.as_deref().unwrap_or(TRAY_TOOLTIP))) {
+      log::warn!("Failed to update tray tooltip: {}", e);
+    }
+  }
+
+  #[cfg(windows)]
+  set_taskbar_progress(app, now_playing);
+}
+
+#[cfg(windows)]
+fn set_taskbar_progress(app: &tauri::AppHandle, now_playing: &NowPlayingState) {
+  use tauri::window::{ProgressBarState, ProgressBarStatus};
+
+  let Some(window) = app.get_webview_window("main") else {
+    return;
+  };
+  let player = &now_playing.player;
+  let status = match now_playing.status {
+    _ if player.duration <= 0.0 => ProgressBarStatus::None,
+    NowPlayingStatus::Playing => ProgressBarStatus::Normal,
+    NowPlayingStatus::Paused => ProgressBarStatus::Paused,
+    _ => ProgressBarStatus::None,
+  };
+  let progress = (player.time_pos / player.duration * 100.0).clamp(0.0, 100.0) as u64;
+  if let Err(e) = window.set_progress_bar(ProgressBarState {
+    status: Some(status),
+    progress: Some(progress),
+  }) {
+    log::warn!("Failed to update taskbar progress: {}", e);
+  }
+}
+
+/// Tooltip like `Show S02E05 — 23:14 / 42:00`, or None when nothing plays.
+fn playback_tooltip(now_playing: &NowPlayingState) -> Option<String> {
+  if !matches!(
+    now_playing.status,
+    NowPlayingStatus::Playing | NowPlayingStatus::Paused
+  ) {
+    return None;
+  }
+  let media = now_playing.media.as_ref()?;
+  let player = &now_playing.player;
+
+  let title = match (media.season_number, media.episode_number) {
+    (Some(season), Some(episode)) if media.item_type == "Episode" => {
+      let code = format!("S{:02}E{:02}", season, episode);
+      match &media.series_name {
+        Some(series) => format!("{} {}", series, code),
+        None => code,
+      }
+    }
+    _ => media.name.clone(),
+  };
+  let position = if player.duration > 0.0 {
+    format!(
+      "{} / {}",
+      format_clock(player.time_pos),
+      format_clock(player.duration)
+    )
+  } else {
+    format_clock(player.time_pos)
+  };
+
+  // Shorten the title rather than the position.
+  let title_budget = TOOLTIP_MAX_CHARS.saturating_sub(position.chars().count() + 3);
+  let title = if title.chars().count() > title_budget {
+    let mut shortened: String = title.chars().take(title_budget.saturating_sub(1)).collect();
+    shortened.push('…');
+    shortened
+  } else {
+    title
+  };
+  Some(format!("{} — {}", title, position))
+}
+
+/// `m:ss`, or `h:mm:ss` from one hour on.
+pub(crate) fn format_clock(seconds: f64) -> String {
+  let total = seconds.max(0.0) as u64;
+  let (hours, minutes, seconds) = (total / 3600, total / 60 % 60, total % 60);
+  if hours > 0 {
+    format!("{}:{:02}:{:02}", hours, minutes, seconds)
+  } else {
+    format!("{}:{:02}", minutes, seconds)
+  }
+}
+
+/// Relabel the tray menu after the language changed. Continue Watching
+/// placeholders follow on the next refresh.
+pub fn refresh_labels(app: &tauri::AppHandle) {
+  let Some(tray_state) = app.try_state::<TrayState>() else {
+    return;
+  };
+  let result = tray_state
+    .labeled_items
+    .iter()
+    .try_for_each(|(item, message_id)| item.set_text(tr!(message_id)))
+    .and_then(|()| {
+      tray_state
+        .continue_watching
+        .set_text(tr!("tray-continue-watching"))
+    });
+  if let Err(e) = result {
+    log::warn!("Failed to relabel tray menu: {}", e);
+  }
+}
+
+fn rebuild_continue_watching_menu(
+  app: &tauri::AppHandle,
+  items: Option<Vec<VideoHomeItem>>,
+) -> tauri::Result<()> {
+  let Some(tray_state) = app.try_state::<TrayState>() else {
+    return Ok(());
+  };
+  let submenu = &tray_state.continue_watching;
+
+  for existing in submenu.items()? {
+    submenu.remove(&existing)?;
+  }
+
+  match items.as_deref() {
+    None => submenu.append(&continue_watching_placeholder(
+      app,
+      &tr!("tray-not-connected"),
+    )?)?,
+    Some([]) => submenu.append(&continue_watching_placeholder(
+      app,
+      &tr!("tray-nothing-to-resume"),
+    )?)?,
+    Some(items) => {
+      for item in items {
+        let menu_item = MenuItem::with_id(
+          app,
+          format!("{}{}", MENU_CONTINUE_WATCHING_ITEM_PREFIX, item.id),
+          continue_watching_label(item),
+          true,
+          None::<&str>,
+        )?;
+        submenu.append(&menu_item)?;
+      }
+    }
+  }
+
+  *tray_state.continue_watching_items.lock() = items.unwrap_or_default();
+  Ok(())
+}
+
+fn continue_watching_placeholder(
+  app: &tauri::AppHandle,
+  text: &str,
+) -> tauri::Result<MenuItem<Wry>> {
+  MenuItem::with_id(app, MENU_CONTINUE_WATCHING_EMPTY, text, false, None::<&str>)
+}
+
+fn play_continue_watching_item(app: &tauri::AppHandle, item_id: String) {
+  let Some(tray_state) = app.try_state::<TrayState>() else {
+    return;
+  };
+  let request = tray_state
+    .continue_watching_items
+    .lock()
+    .iter()
+    .find(|item| item.id == item_id)
+    .map(continue_watching_play_request);
+  let Some(request) = request else {
+    log::warn!(
+      "Tray Continue Watching item {} is no longer listed",
+      item_id
+    );
+    return;
+  };
+
+  let app_handle = app.clone();
+  tauri::async_runtime::spawn(async move {
+    let jellyfin_state = app_handle.state::<JellyfinState>();
+    let session = jellyfin_state.session.read().clone();
+    let Some(session) = session else {
+      AppNotification::warning(&app_handle, tr!("notify-library-needs-session"));
+      return;
+    };
+
+    if let Err(e) = session.play_library(request).await {
+      log::warn!("Failed to play Continue Watching item: {}", e);
+      match e.playback_stage() {
+        Some(stage) => PlaybackError::report(&app_handle, stage, e.to_string()),
+        None => AppNotification::error(
+          &app_handle,
+          tr!("notify-resume-failed", error = e.to_string()),
+        ),
+      }
+      return;
+    }
+    playback_control::emit_now_playing_changed(&app_handle, &jellyfin_state).await;
+  });
+}
+
+/// Build the Library playback request for a Continue Watching entry.
+fn continue_watching_play_request(item: &VideoHomeItem) -> VideoLibraryPlayRequest {
+  let resume_position = item
+    .resume_position_seconds
+    .filter(|seconds| *seconds > 0.0);
+
+  VideoLibraryPlayRequest {
+    item_id: item.id.clone(),
+    mode: if resume_position.is_some() {
+      VideoLibraryPlayMode::Resume
+    } else {
+      VideoLibraryPlayMode::Start
+    },
+    start_position_seconds: resume_position,
+    audio_stream_index: None,
+    subtitle_stream_index: None,
+  }
+}
+
+/// Menu label for a Continue Watching entry.
+fn continue_watching_label(item: &VideoHomeItem) -> String {
+  match (item.item_type.as_str(), item.series_name.as_deref()) {
+    ("Episode", Some(series)) => format!(
+      "{} - S{:02}E{:02} - {}",
+      series,
+      item.season_number.unwrap_or(1),
+      item.episode_number.unwrap_or(1),
+      item.name
+    ),
+    _ => item.name.clone(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::command::{NowPlayingMedia, PlayerState};
+
+  fn home_item(item_type: &str, resume_position_seconds: Option<f64>) -> VideoHomeItem {
+    VideoHomeItem {
+      id: "item-1".to_string(),
+      name: "Pilot".to_string(),
+      item_type: item_type.to_string(),
+      series_id: Some("series-1".to_string()),
+      series_name: Some("Show".to_string()),
+      season_number: Some(2),
+      episode_number: Some(5),
+      production_year: None,
+      runtime_seconds: Some(1800.0),
+      resume_position_seconds,
+      played_percentage: None,
+      played: false,
+      favorite: false,
+      artwork_image_id: None,
+    }
+  }
+
+  #[test]
+  fn continue_watching_label_formats_episodes_and_movies() {
+    assert_eq!(
+      continue_watching_label(&home_item("Episode", None)),
+      "Show - S02E05 - Pilot"
+    );
+    assert_eq!(continue_watching_label(&home_item("Movie", None)), "Pilot");
+  }
+
+  #[test]
+  fn tooltip_shows_the_episode_and_position_while_playing() {
+    let mut now_playing = NowPlayingState {
+      status: NowPlayingStatus::Playing,
+      player: PlayerState {
+        connected: true,
+        paused: false,
+        muted: false,
+        time_pos: 1394.0,
+        duration: 2520.0,
+        volume: 100.0,
+      },
+      media: Some(NowPlayingMedia {
+        item_id: "item-1".to_string(),
+        name: "Pilot".to_string(),
+        item_type: "Episode".to_string(),
+        series_name: Some("Show".to_string()),
+        season_number: Some(2),
+        episode_number: Some(5),
+        user_data: None,
+      }),
+      can_play_next: true,
+      can_play_previous: true,
+      next_unavailable_reason: None,
+      previous_unavailable_reason: None,
+    };
+
+    assert_eq!(
+      playback_tooltip(&now_playing).as_deref(),
+      Some("Show S02E05 — 23:14 / 42:00")
+    );
+
+    now_playing.player.duration = 4000.0;
+    now_playing.media.as_mut().unwrap().item_type = "Movie".to_string();
+    assert_eq!(
+      playback_tooltip(&now_playing).as_deref(),
+      Some("Pilot — 23:14 / 1:06:40")
+    );
+
+    now_playing.status = NowPlayingStatus::Idle;
+    assert_eq!(playback_tooltip(&now_playing), None);
+  }
+
+  #[test]
+  fn continue_watching_request_resumes_from_saved_position() {
+    let request = continue_watching_play_request(&home_item("Episode", Some(42.0)));
+
+    assert_eq!(request.item_id, "item-1");
+    assert!(matches!(request.mode, VideoLibraryPlayMode::Resume));
+    assert_eq!(request.start_position_seconds, Some(42.0));
+  }
+
+  #[test]
+  fn continue_watching_request_without_position_starts_from_beginning() {
+    let request = continue_watching_play_request(&home_item("Movie", Some(0.0)));
+
+    assert!(matches!(request.mode, VideoLibraryPlayMode::Start));
+    assert_eq!(request.start_position_seconds, None);
+  }
+}
@@ -0,0 +1,241 @@
+//! Checks GitHub for newer JellyPilot releases.
+//!
+//! The latest release is looked up shortly after start and once a day after
+//! that. When it is newer than the running build, `UpdateAvailable` is
+//! emitted once per version with the release notes and the release page to
+//! download from. Distribution packages turn the check off with
+//! `check_for_updates`; updates then come from the package manager.
+
+use std::cmp::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+
+use parking_lot::RwLock;
+use serde::Deserialize;
+use tauri_specta::Event;
+
+use crate::command::UpdateAvailable;
+use crate::config::AppConfig;
+
+const LATEST_RELEASE_URL: &str = "https://api.github.com/repos/hewel/jellypilot/releases/latest";
+/// Leave the startup to connecting the session first.
+const FIRST_CHECK_DELAY: Duration = Duration::from_secs(60);
+const CHECK_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The parts of a GitHub release the check uses.
+#[derive(Debug, Deserialize)]
+struct Release {
+  tag_name: String,
+  html_url: String,
+  #[serde(default)]
+  body: Option<String>,
+  #[serde(default)]
+  draft: bool,
+  #[serde(default)]
+  prerelease: bool,
+}
+
+/// A semantic version; build metadata is ignored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Version {
+  major: u64,
+  minor: u64,
+  patch: u64,
+  pre_release: Option<String>,
+}
+
+impl Version {
+  /// Parse `1.4.1`, `v1.5.0-beta.2` or `1.5.0+build.7`.
+  fn parse(text: &str) -> Option<Self> {
+    let text = text.trim();
+    let text = text.strip_prefix(['v', 'V']).unwrap_or(text);
+    let text = text.split_once('+').map_or(text, |(version, _)| version);
+    let (core, pre_release) = match text.split_once('-') {
+      Some((core, pre)) if !pre.is_empty() => (core, Some(pre.to_string())),
+      Some(_) => return None,
+      None => (text, None),
+    };
+    let mut parts = core.split('.').map(|part| part.parse::<u64>().ok());
+    let (Some(Some(major)), Some(Some(minor)), Some(Some(patch)), None) =
+      (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+      return None;
+    };
+    Some(Self {
+      major,
+      minor,
+      patch,
+      pre_release,
+    })
+  }
+}
+
+impl Ord for Version {
+  fn cmp(&self, other: &Self) -> Ordering {
+    (self.major, self.minor, self.patch)
+      .cmp(&(other.major, other.minor, other.patch))
+      .then_with(|| match (&self.pre_release, &other.pre_release) {
+        (None, None) => Ordering::Equal,
+        // A pre-release comes before its release.
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (Some(ours), Some(theirs)) => compare_pre_releases(ours, theirs),
+      })
+  }
+}
+
+impl PartialOrd for Version {
+  fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+/// Numeric identifiers compare as numbers and sort before alphanumeric ones.
+fn compare_pre_releases(ours: &str, theirs: &str) -> Ordering {
+  let mut ours = ours.split('.');
+  let mut theirs = theirs.split('.');
+  loop {
+    let ordering = match (ours.next(), theirs.next()) {
+      (None, None) => return Ordering::Equal,
+      (None, Some(_)) => return Ordering::Less,
+      (Some(_), None) => return Ordering::Greater,
+      (Some(a), Some(b)) => match (a.parse::<u64>(), b.parse::<u64>()) {
+        (Ok(a), Ok(b)) => a.cmp(&b),
+        (Ok(_), Err(_)) => Ordering::Less,
+        (Err(_), Ok(_)) => Ordering::Greater,
+        (Err(_), Err(_)) => a.cmp(b),
+      },
+    };
+    if ordering != Ordering::Equal {
+      return ordering;
+    }
+  }
+}
+
+/// The update `release` offers over the `current` version, if any.
+fn newer_release(release: Release, current: &str) -> Option<UpdateAvailable> {
+  if release.draft || release.prerelease {
+    return None;
+  }
+  let latest = Version::parse(&release.tag_name)?;
+  let current = Version::parse(current)?;
+  (latest > current).then(|| UpdateAvailable {
+    version: release
+      .tag_name
+      .trim()
+      .trim_start_matches(['v', 'V'])
+      .to_string(),
+    release_notes: release.body.unwrap_or_default(),
+    download_url: release.html_url,
+  })
+}
+
+async fn fetch_latest_release() -> Result<Release, reqwest::Error> {
+  reqwest::Client::builder()
+    .timeout(REQUEST_TIMEOUT)
+    .build()?
+    .get(LATEST_RELEASE_URL)
+    // GitHub rejects API requests without a User-Agent.
+    .header(
+      reqwest::header::USER_AGENT,
+      concat!("JellyPilot/", env!("CARGO_PKG_VERSION")),
+    )
+    .header(reqwest::header::ACCEPT, "application/vnd.github+json")
+    .send()
+    .await?
+    .error_for_status()?
+    .json()
+    .await
+}
+
+/// Check for updates in the background while `check_for_updates` is on.
+pub fn start(app: tauri::AppHandle, config: Arc<RwLock<AppConfig>>) {
+  tauri::async_runtime::spawn(async move {
+    tokio::time::sleep(FIRST_CHECK_DELAY).await;
+    let mut announced: Option<String> = None;
+    loop {
+      if config.read().check_for_updates {
+        match fetch_latest_release().await {
+          Ok(release) => {
+            if let Some(update) = newer_release(release, env!("CARGO_PKG_VERSION")) {
+              if announced.as_deref() != Some(update.version.as_str()) {
+                log::info!("JellyPilot {} is available", update.version);
+                announced = Some(update.version.clone());
+                if let Err(e) = update.emit(&app) {
+                  log::error!("Failed to emit update notice: {}", e);
+                }
+              }
+            }
+          }
+          Err(e) => log::warn!("Update check failed: {}", e),
+        }
+      }
+      tokio::time::sleep(CHECK_INTERVAL).await;
+    }
+  });
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn release(tag_name: &str) -> Release {
+    Release {
+      tag_name: tag_name.to_string(),
+      html_url: format!("https://github.com/hewel/jellypilot/releases/tag/{tag_name}"),
+      body: Some("Fixes".to_string()),
+      draft: false,
+      prerelease: false,
+    }
+  }
+
+  #[test]
+  fn versions_order_by_semver_precedence() {
+    let ordered = [
+      "1.4.1",
+      "1.5.0-alpha",
+      "1.5.0-alpha.2",
+      "1.5.0-alpha.10",
+      "1.5.0-beta",
+      "v1.5.0",
+      "1.5.1+build.3",
+      "1.10.0",
+    ];
+    for pair in ordered.windows(2) {
+      let lower = Version::parse(pair[0]).unwrap();
+      let higher = Version::parse(pair[1]).unwrap();
+      assert!(
+        lower < higher,
+        "{} should be older than {}",
+        pair[0],
+        pair[1]
+      );
+    }
+    for invalid in ["", "1.5", "1.5.0.1", "1.x.0", "1.5.0-"] {
+      assert_eq!(Version::parse(invalid), None, "{invalid:?}");
+    }
+  }
+
+  #[test]
+  fn only_newer_published_releases_are_offered() {
+    let update = newer_release(release("v1.5.0"), "1.4.1").expect("1.5.0 is newer");
+    assert_eq!(update.version, "1.5.0");
+    assert_eq!(update.release_notes, "Fixes");
+    assert_eq!(
+      update.download_url,
+      "https://github.com/hewel/jellypilot/releases/tag/v1.5.0"
+    );
+
+    assert!(newer_release(release("v1.4.1"), "1.4.1").is_none());
+    assert!(newer_release(release("v1.4.0"), "1.4.1").is_none());
+    assert!(newer_release(
+      Release {
+        prerelease: true,
+        ..release("v2.0.0-rc.1")
+      },
+      "1.4.1"
+    )
+    .is_none());
+  }
+}
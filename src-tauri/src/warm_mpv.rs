@@ -0,0 +1,137 @@
+//! Optional idle MPV kept ready for the first cast.
+//!
+//! Spawning MPV and connecting to its IPC socket delays the first cast. With
+//! `mpv_warm_start` on, an MPV without a window (or with a minimized one) is
+//! started at launch and again whenever none runs between playbacks, so a
+//! cast only has to load the file. An MPV the user closed, or one that
+//! failed to start, is left alone until the next playback.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use parking_lot::RwLock;
+use tauri::{AppHandle, Manager};
+
+use crate::command::JellyfinState;
+use crate::config::{AppConfig, MpvWarmStart};
+use crate::jellyfin::SessionPhase;
+use crate::metrics::{self, Counter};
+use crate::mpv::MpvClient;
+
+/// Time for the MPV version check and bundled MPV update at launch.
+const FIRST_CHECK_DELAY: Duration = Duration::from_secs(3);
+const CHECK_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Whether a warm MPV should be started now.
+fn should_start(mode: MpvWarmStart, running: bool, phase: SessionPhase, held: bool) -> bool {
+  mode != MpvWarmStart::Off
+    && !running
+    && !held
+    && matches!(phase, SessionPhase::Disconnected | SessionPhase::Idle)
+}
+
+/// Keep an idle MPV running while `mpv_warm_start` is on.
+pub fn start(app: AppHandle, config: Arc<RwLock<AppConfig>>, mpv: Arc<MpvClient>) {
+  tauri::async_runtime::spawn(async move {
+    tokio::time::sleep(FIRST_CHECK_DELAY).await;
+    // Playbacks started when warm starts were held; a newer one lifts the hold.
+    let mut held_at: Option<u64> = None;
+    loop {
+      let mode = config.read().mpv_warm_start;
+      let playbacks = metrics::count(Counter::PlaybacksStarted);
+      if held_at.is_some_and(|held_at| playbacks > held_at) {
+        held_at = None;
+      }
+
+      if mode == MpvWarmStart::Off {
+        if mpv.is_warm() && mpv.is_connected() {
+          log::info!("Warm start turned off, quitting the idle MPV");
+          let _ = mpv.quit().await;
+        }
+      } else if !mpv.is_connected() {
+        if let Some(status) = mpv.exit_status() {
+          // A clean exit of an idle MPV means its window was closed.
+          if status.success() {
+            log::info!("The idle MPV was closed; not restarting it before the next playback");
+            held_at = Some(playbacks);
+          } else {
+            log::warn!("MPV exited with {} while idle", status);
+          }
+          mpv.stop().await;
+        }
+      }
+
+      let phase = app
+        .state::<JellyfinState>()
+        .session
+        .read()
+        .as_ref()
+        .map_or(SessionPhase::Disconnected, |session| session.phase());
+      if should_start(mode, mpv.is_connected(), phase, held_at.is_some()) {
+        log::info!("Starting an idle MPV ({:?})", mode);
+        if let Err(e) = mpv.start_warm(mode == MpvWarmStart::Minimized).await {
+          log::warn!("Failed to start an idle MPV: {}", e);
+          mpv.stop().await;
+          held_at = Some(playbacks);
+        }
+      }
+
+      tokio::time::sleep(CHECK_INTERVAL).await;
+    }
+  });
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn mpv_is_started_only_when_on_and_idle() {
+    assert!(should_start(
+      MpvWarmStart::Hidden,
+      false,
+      SessionPhase::Disconnected,
+      false
+    ));
+    assert!(should_start(
+      MpvWarmStart::Minimized,
+      false,
+      SessionPhase::Idle,
+      false
+    ));
+    assert!(!should_start(
+      MpvWarmStart::Off,
+      false,
+      SessionPhase::Idle,
+      false
+    ));
+    assert!(!should_start(
+      MpvWarmStart::Hidden,
+      true,
+      SessionPhase::Idle,
+      false
+    ));
+  }
+
+  #[test]
+  fn held_or_busy_sessions_wait() {
+    assert!(!should_start(
+      MpvWarmStart::Hidden,
+      false,
+      SessionPhase::Idle,
+      true
+    ));
+    assert!(!should_start(
+      MpvWarmStart::Hidden,
+      false,
+      SessionPhase::Loading,
+      false
+    ));
+    assert!(!should_start(
+      MpvWarmStart::Hidden,
+      false,
+      SessionPhase::Stopping,
+      false
+    ));
+  }
+}
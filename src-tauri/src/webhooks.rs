@@ -0,0 +1,225 @@
+//! Outgoing webhooks for playback lifecycle events.
+//!
+//! Every configured URL receives a JSON POST when playback starts, pauses,
+//! resumes, stops or moves on to the next item by itself. With a secret set,
+//! the body is signed with HMAC-SHA256 in the `X-JellyPilot-Signature`
+//! header (`sha256=<hex>`), so receivers like Home Assistant can reject
+//! forged calls. Delivery is best effort: failures are logged, not retried.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use hmac::{Hmac, Mac};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use specta::Type;
+
+use crate::jellyfin::{ticks_to_seconds, MediaItem};
+
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+const EVENT_HEADER: &str = "X-JellyPilot-Event";
+const SIGNATURE_HEADER: &str = "X-JellyPilot-Signature";
+
+/// A webhook receiver.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct Webhook {
+  /// http(s) URL the events are posted to.
+  pub url: String,
+  /// Key for the HMAC-SHA256 body signature; empty for unsigned requests.
+  #[serde(default)]
+  pub secret: String,
+}
+
+impl Webhook {
+  pub fn validate(&self) -> Result<(), String> {
+    let url = self.url.trim();
+    if !(url.starts_with("http://") || url.starts_with("https://")) {
+      return Err(format!(
+        "Webhook URL \"{}\" must start with http:// or https://",
+        url
+      ));
+    }
+    Ok(())
+  }
+}
+
+/// Playback lifecycle events sent to webhooks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WebhookEvent {
+  PlaybackStarted,
+  Paused,
+  Resumed,
+  Stopped,
+  /// The next episode or queue item started after the previous one ended.
+  AutoNext,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct WebhookPayload<'a> {
+  event: WebhookEvent,
+  /// Unix time in seconds.
+  timestamp: u64,
+  item: WebhookItem<'a>,
+  position_seconds: f64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct WebhookItem<'a> {
+  id: &'a str,
+  name: &'a str,
+  #[serde(rename = "type")]
+  item_type: &'a str,
+  series_name: Option<&'a str>,
+  season_number: Option<i32>,
+  episode_number: Option<i32>,
+  run_time_seconds: Option<f64>,
+}
+
+impl<'a> From<&'a MediaItem> for WebhookItem<'a> {
+  fn from(item: &'a MediaItem) -> Self {
+    Self {
+      id: &item.id,
+      name: &item.name,
+      item_type: &item.item_type,
+      series_name: item.series_name.as_deref(),
+      season_number: item.parent_index_number,
+      episode_number: item.index_number,
+      run_time_seconds: item.run_time_ticks.map(ticks_to_seconds),
+    }
+  }
+}
+
+static WEBHOOKS: RwLock<Vec<Webhook>> = parking_lot::const_rwlock(Vec::new());
+
+/// Replace the configured receivers.
+pub fn configure(webhooks: Vec<Webhook>) {
+  *WEBHOOKS.write() = webhooks;
+}
+
+/// Send `event` for `item` at `position_ticks` to every receiver.
+pub fn fire(event: WebhookEvent, item: &MediaItem, position_ticks: i64) {
+  let webhooks = WEBHOOKS.read().clone();
+  if webhooks.is_empty() {
+    return;
+  }
+
+  let payload = WebhookPayload {
+    event,
+    timestamp: SystemTime::now()
+      .duration_since(UNIX_EPOCH)
+      .map(|elapsed| elapsed.as_secs())
+      .unwrap_or_default(),
+    item: WebhookItem::from(item),
+    position_seconds: ticks_to_seconds(position_ticks),
+  };
+  let body = match serde_json::to_vec(&payload) {
+    Ok(body) => body,
+    Err(e) => {
+      log::error!("Failed to serialize webhook payload: {}", e);
+      return;
+    }
+  };
+
+  tauri::async_runtime::spawn(async move {
+    let client = match reqwest::Client::builder().timeout(DELIVERY_TIMEOUT).build() {
+      Ok(client) => client,
+      Err(e) => {
+        log::error!("Failed to create webhook client: {}", e);
+        return;
+      }
+    };
+    for webhook in webhooks {
+      deliver(&client, &webhook, event, &body).await;
+    }
+  });
+}
+
+async fn deliver(client: &reqwest::Client, webhook: &Webhook, event: WebhookEvent, body: &[u8]) {
+  let mut request = client
+    .post(webhook.url.trim())
+    .header(reqwest::header::CONTENT_TYPE, "application/json")
+    .header(EVENT_HEADER, event_name(event))
+    .body(body.to_vec());
+  if !webhook.secret.is_empty() {
+    request = request.header(SIGNATURE_HEADER, signature(&webhook.secret, body));
+  }
+
+  match request
+    .send()
+    .await
+    .and_then(|response| response.error_for_status())
+  {
+    Ok(_) => log::debug!("Webhook {:?} delivered", event),
+    Err(e) => log::warn!("Webhook {:?} delivery failed: {}", event, e),
+  }
+}
+
+fn event_name(event: WebhookEvent) -> &'static str {
+  match event {
+    WebhookEvent::PlaybackStarted => "playbackStarted",
+    WebhookEvent::Paused => "paused",
+    WebhookEvent::Resumed => "resumed",
+    WebhookEvent::Stopped => "stopped",
+    WebhookEvent::AutoNext => "autoNext",
+  }
+}
+
+/// `sha256=<hex>` HMAC of `body` keyed with `secret`.
+fn signature(secret: &str, body: &[u8]) -> String {
+  let mut mac =
+    Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+  mac.update(body);
+  let hex: String = mac
+    .finalize()
+    .into_bytes()
+    .iter()
+    .map(|byte| format!("{byte:02x}"))
+    .collect();
+  format!("sha256={}", hex)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn signature_matches_the_rfc_4231_vector() {
+    assert_eq!(
+      signature("Jefe", b"what do ya want for nothing?"),
+      "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+    );
+  }
+
+  #[test]
+  fn event_names_match_the_payload() {
+    for event in [
+      WebhookEvent::PlaybackStarted,
+      WebhookEvent::Paused,
+      WebhookEvent::Resumed,
+      WebhookEvent::Stopped,
+      WebhookEvent::AutoNext,
+    ] {
+      assert_eq!(
+        serde_json::to_value(event).unwrap(),
+        serde_json::json!(event_name(event))
+      );
+    }
+  }
+
+  #[test]
+  fn only_http_urls_are_valid() {
+    let webhook = |url: &str| Webhook {
+      url: url.to_string(),
+      secret: String::new(),
+    };
+    assert!(webhook("https://ha.local/api/webhook/abc")
+      .validate()
+      .is_ok());
+    assert!(webhook("ftp://ha.local").validate().is_err());
+    assert!(webhook("").validate().is_err());
+  }
+}
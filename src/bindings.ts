@@ -13,12 +13,49 @@ export const commands = {
 	mpvLoadfile: (url: string) => typedError<null, CommandError>(__TAURI_INVOKE("mpv_loadfile", { url })),
 	/**  Seek to absolute position in seconds. */
 	mpvSeek: (time: number | null) => typedError<null, CommandError>(__TAURI_INVOKE("mpv_seek", { time })),
+	/**  Seek the current item to a percentage (0-100) of its runtime. */
+	playbackSeekPercent: (percent: number | null) => typedError<null, CommandError>(__TAURI_INVOKE("playback_seek_percent", { percent })),
+	/**  Seek the current item to an absolute position in seconds. */
+	playbackSeekAbsolute: (seconds: number | null) => typedError<null, CommandError>(__TAURI_INVOKE("playback_seek_absolute", { seconds })),
+	/**  Rate the current item thumbs up (`likes`) or thumbs down. */
+	playbackSetUserRating: (likes: boolean) => typedError<NowPlayingUserData, CommandError>(__TAURI_INVOKE("playback_set_user_rating", { likes })),
 	/**  Set pause state. */
 	mpvSetPause: (paused: boolean) => typedError<null, CommandError>(__TAURI_INVOKE("mpv_set_pause", { paused })),
 	/**  Set volume (0-100). */
 	mpvSetVolume: (volume: number | null) => typedError<null, CommandError>(__TAURI_INVOKE("mpv_set_volume", { volume })),
 	/**  Toggle mute state. */
 	mpvToggleMute: () => typedError<null, CommandError>(__TAURI_INVOKE("mpv_toggle_mute")),
+	/**  Enter or leave fullscreen in the running player. */
+	playbackSetFullscreen: (fullscreen: boolean) => typedError<null, CommandError>(__TAURI_INVOKE("playback_set_fullscreen", { fullscreen })),
+	/**  List the versions of the current item. */
+	playbackListVersions: () => typedError<MediaVersion[], CommandError>(__TAURI_INVOKE("playback_list_versions")),
+	/**  Reload the current item from another version at the current position. */
+	playbackSwitchVersion: (mediaSourceId: string) => typedError<null, CommandError>(__TAURI_INVOKE("playback_switch_version", { mediaSourceId })),
+	/**
+	 *  Show a second subtitle stream (Jellyfin index) under the selected one,
+	 *  or hide it with `None`.
+	 */
+	playbackSetSecondarySubtitle: (index: number | null) => typedError<null, CommandError>(__TAURI_INVOKE("playback_set_secondary_subtitle", { index })),
+	/**  Loop the current item between `start` and `end` seconds. */
+	playbackSetAbLoop: (start: number, end: number) => typedError<null, CommandError>(__TAURI_INVOKE("playback_set_ab_loop", { start, end })),
+	/**  Stop looping part of the current item. */
+	playbackClearAbLoop: () => typedError<null, CommandError>(__TAURI_INVOKE("playback_clear_ab_loop")),
+	/**
+	 *  Save a screenshot named after the playing item to the screenshot folder.
+	 *  Returns the file's path.
+	 */
+	playbackScreenshot: (mode: ScreenshotMode) => typedError<string, CommandError>(__TAURI_INVOKE("playback_screenshot", { mode })),
+	/**
+	 *  Chapters of the playing item, titled from the server even when the
+	 *  stream leaves them unnamed.
+	 */
+	playbackGetChapters: () => typedError<PlaybackChapter[], CommandError>(__TAURI_INVOKE("playback_get_chapters")),
+	/**  Jump to a chapter by its index in `playback_get_chapters`. */
+	playbackGotoChapter: (index: number) => typedError<null, CommandError>(__TAURI_INVOKE("playback_goto_chapter", { index })),
+	/**  Play the item that ended early again from where it stopped. */
+	playbackRetry: () => typedError<null, CommandError>(__TAURI_INVOKE("playback_retry")),
+	/**  What the playback session is doing, e.g. loading, playing or stalling. */
+	sessionGetState: () => __TAURI_INVOKE<SessionPhase>("session_get_state"),
 	/**  Set audio track by ID. */
 	mpvSetAudioTrack: (id: number) => typedError<null, CommandError>(__TAURI_INVOKE("mpv_set_audio_track", { id })),
 	/**  Set subtitle track by ID, or disable subtitles with a negative ID. */
@@ -29,6 +66,15 @@ export const commands = {
 	mpvGetState: () => typedError<PlayerState, CommandError>(__TAURI_INVOKE("mpv_get_state")),
 	/**  Check if MPV is connected. */
 	mpvIsConnected: () => __TAURI_INVOKE<boolean>("mpv_is_connected"),
+	/**  Version of the configured MPV, or null when it could not be detected. */
+	mpvGetVersion: () => __TAURI_INVOKE<MpvVersion | null>("mpv_get_version"),
+	/**  Installed bundled MPV build. */
+	mpvBundledStatus: () => __TAURI_INVOKE<BundledMpvStatus>("mpv_bundled_status"),
+	/**
+	 *  Download the bundled MPV build for this platform, or update it, and use it
+	 *  when no MPV path is configured.
+	 */
+	mpvBundledInstall: () => typedError<BundledMpvStatus, CommandError>(__TAURI_INVOKE("mpv_bundled_install")),
 	/**  Get current user-facing Now Playing state. */
 	nowPlayingGetState: () => typedError<NowPlayingState, CommandError>(__TAURI_INVOKE("now_playing_get_state")),
 	/**  Load the Library Browser Video Home dashboard data. */
@@ -66,6 +112,9 @@ export const commands = {
 	userName: string,
 	serverName: string | null,
 	deviceId: string | null,
+	tls?: TlsTrust,
+	localUrl?: string | null,
+	customHeaders?: Partial<{ [key in string]: string }>,
 } | null>("jellyfin_get_session"),
 	/**  Restore a session from saved data. */
 	jellyfinRestoreSession: (session: SavedSession) => typedError<null, CommandError>(__TAURI_INVOKE("jellyfin_restore_session", { session })),
@@ -76,6 +125,13 @@ export const commands = {
 	 *  by the profile-store commands.
 	 */
 	jellyfinClearSession: () => typedError<null, CommandError>(__TAURI_INVOKE("jellyfin_clear_session")),
+	/**  Register as a cast target again, retrying until the server lists this device. */
+	jellyfinRefreshCastRegistration: () => typedError<null, CommandError>(__TAURI_INVOKE("jellyfin_refresh_cast_registration")),
+	/**
+	 *  Give this install a new device ID when another one uses the same, then
+	 *  register as a cast target under it.
+	 */
+	jellyfinRegenerateDeviceId: () => typedError<null, CommandError>(__TAURI_INVOKE("jellyfin_regenerate_device_id")),
 	/**  Play the next episode from the active Jellyfin session. */
 	jellyfinPlayNextEpisode: () => typedError<null, CommandError>(__TAURI_INVOKE("jellyfin_play_next_episode")),
 	/**  Play the previous episode from the active Jellyfin session. */
@@ -103,11 +159,51 @@ export const commands = {
 	userName: string,
 	serverName: string | null,
 	deviceId: string | null,
+	tls?: TlsTrust,
+	localUrl?: string | null,
+	customHeaders?: Partial<{ [key in string]: string }>,
 } | null>("server_get_session"),
 	/**  Restore a media server session from saved data. */
 	serverRestoreSession: (session: SavedSession) => typedError<null, CommandError>(__TAURI_INVOKE("server_restore_session", { session })),
 	/**  Clear/logout from the current media server session. */
 	serverClearSession: () => typedError<null, CommandError>(__TAURI_INVOKE("server_clear_session")),
+	/**
+	 *  Set the certificate trust used for the next connection, e.g. before
+	 *  starting Quick Connect with a self-signed server.
+	 */
+	serverSetTlsTrust: (trust: TlsTrust) => typedError<null, CommandError>(__TAURI_INVOKE("server_set_tls_trust", { trust })),
+	/**
+	 *  Set the headers a reverse proxy requires for the next connection, e.g.
+	 *  before starting Quick Connect behind Cloudflare Access.
+	 */
+	serverSetCustomHeaders: (headers: Partial<{ [key in string]: string }>) => typedError<null, CommandError>(__TAURI_INVOKE("server_set_custom_headers", { headers })),
+	/**
+	 *  SHA-256 fingerprint of the certificate a server presents, for the user to
+	 *  accept before it is pinned.
+	 */
+	serverCertificateFingerprint: (serverUrl: string) => typedError<string, CommandError>(__TAURI_INVOKE("server_certificate_fingerprint", { serverUrl })),
+	/**
+	 *  Other active sessions of the signed-in user, e.g. a phone or TV that may
+	 *  be playing what gets cast here.
+	 */
+	serverListOtherSessions: () => typedError<RemoteSession[], CommandError>(__TAURI_INVOKE("server_list_other_sessions")),
+	/**
+	 *  Sessions on the server that the signed-in user may control, e.g. TVs and
+	 *  other clients.
+	 */
+	serverListSessions: () => typedError<RemoteSession[], CommandError>(__TAURI_INVOKE("server_list_sessions")),
+	/**  Start playing items on another session. */
+	serverRemotePlay: (sessionId: string, itemIds: string[], startPositionSeconds: number | null) => typedError<null, CommandError>(__TAURI_INVOKE("server_remote_play", { sessionId, itemIds, startPositionSeconds })),
+	/**
+	 *  Pause, resume, stop, seek or skip on another session. `seek_position_seconds`
+	 *  is required for `seek`.
+	 */
+	serverRemotePlaystate: (sessionId: string, command: RemotePlaystateCommand, seekPositionSeconds: number | null) => typedError<null, CommandError>(__TAURI_INVOKE("server_remote_playstate", { sessionId, command, seekPositionSeconds })),
+	/**
+	 *  Send a general command such as `VolumeUp`, `ToggleMute` or `SetVolume`
+	 *  (with a `Volume` argument) to another session.
+	 */
+	serverRemoteCommand: (sessionId: string, name: string, args: Partial<{ [key in string]: string }>) => typedError<null, CommandError>(__TAURI_INVOKE("server_remote_command", { sessionId, name, args })),
 	/**  List saved media server profiles. */
 	serverProfilesGet: () => typedError<SavedServiceProfiles, CommandError>(__TAURI_INVOKE("server_profiles_get")),
 	/**  Import a legacy single saved session into the saved service profile store. */
@@ -126,12 +222,64 @@ export const commands = {
 	configDefault: () => __TAURI_INVOKE<AppConfig>("config_default"),
 	/**  Detect MPV path automatically. */
 	configDetectMpv: () => __TAURI_INVOKE<string | null>("config_detect_mpv"),
+	/**
+	 *  Record the next key pressed in the MPV window, in MPV's key syntax for
+	 *  shortcut settings. MPV is started for it when none runs, and quit again
+	 *  afterwards. Returns null when Escape was pressed or no key came.
+	 */
+	configCaptureKeybind: () => typedError<string | null, CommandError>(__TAURI_INVOKE("config_capture_keybind")),
+	/**  Whether a settings PIN is set and whether settings are currently unlocked. */
+	settingsLockStatus: () => __TAURI_INVOKE<SettingsLockStatus>("settings_lock_status"),
+	/**  Unlock settings and destructive actions for a few minutes. */
+	settingsUnlock: (pin: string) => typedError<null, CommandError>(__TAURI_INVOKE("settings_unlock", { pin })),
+	/**  Lock settings again before the unlock expires. */
+	settingsLock: () => __TAURI_INVOKE<null>("settings_lock"),
+	/**
+	 *  Set a new settings PIN, or remove the lock with `None`. Requires settings
+	 *  to be unlocked when a PIN is already set.
+	 */
+	settingsSetPin: (pin: string | null) => typedError<null, CommandError>(__TAURI_INVOKE("settings_set_pin", { pin })),
+	/**  List saved track preferences, naming series that were saved without a name. */
+	preferencesList: () => typedError<SavedTrackPreference[], CommandError>(__TAURI_INVOKE("preferences_list")),
+	/**  Replace the audio and subtitle choice of a saved track preference. */
+	preferencesUpdate: (preference: SavedTrackPreference) => typedError<null, CommandError>(__TAURI_INVOKE("preferences_update", { preference })),
+	/**  Delete a saved track preference by key. */
+	preferencesDelete: (key: string) => typedError<null, CommandError>(__TAURI_INVOKE("preferences_delete", { key })),
+	/**
+	 *  Bundle app logs, MPV logs, redacted config and recent MPV commands into a zip.
+	 * 
+	 *  Writes to `destination` when given, otherwise to the downloads folder.
+	 *  Returns the path of the written file.
+	 */
+	diagnosticsExport: (destination: string | null) => typedError<string, CommandError>(__TAURI_INVOKE("diagnostics_export", { destination })),
+	/**
+	 *  Check MPV, the IPC socket location and the server connection, returning
+	 *  a checklist with a fix-it hint for every problem.
+	 */
+	diagnosticsRunChecks: () => typedError<SelfCheck[], CommandError>(__TAURI_INVOKE("diagnostics_run_checks")),
+	/**
+	 *  Entries and estimated size of every bounded record JellyPilot keeps
+	 *  while running, such as the MPV command audit and session logs.
+	 */
+	diagnosticsMemoryReport: () => __TAURI_INVOKE<BufferUsage[]>("diagnostics_memory_report"),
+	/**  Bytes downloaded in the current or last playback session and since startup. */
+	statsGetBandwidth: () => __TAURI_INVOKE<BandwidthStats>("stats_get_bandwidth"),
+	/**
+	 *  The anonymous usage statistics the next report would send, so users can
+	 *  see them before opting in.
+	 */
+	telemetryPreview: () => __TAURI_INVOKE<TelemetryReport>("telemetry_preview"),
 };
 
 /** Events */
 export const events = {
 	appNotification: makeEvent<AppNotification>("app-notification"),
+	bandwidthUpdated: makeEvent<BandwidthUpdated>("bandwidth-updated"),
+	connectionEvent: makeEvent<ConnectionEvent>("connection-event"),
 	nowPlayingChanged: makeEvent<NowPlayingChanged>("now-playing-changed"),
+	playbackError: makeEvent<PlaybackError>("playback-error"),
+	sessionExpired: makeEvent<SessionExpired>("session-expired"),
+	updateAvailable: makeEvent<UpdateAvailable>("update-available"),
 };
 
 /* Types */
@@ -144,14 +292,28 @@ export type AppConfig = {
 	mpvPath?: string | null,
 	/**  Additional MPV command-line arguments. */
 	mpvArgs?: string[],
+	/**  Download and use a JellyPilot-managed MPV when no MPV path is set. */
+	bundledMpv?: boolean,
 	/**  Device name shown in Jellyfin cast menu. */
 	deviceName?: string,
+	/**  Language of notifications, MPV on-screen messages and the tray menu. */
+	language?: Language,
 	/**  Progress reporting interval in seconds. */
 	progressInterval?: number,
 	/**  Start minimized to system tray. */
 	startMinimized?: boolean,
 	/**  Intro Skipper plugin behavior mode. */
 	introSkipperMode?: IntroSkipperMode,
+	/**
+	 *  Treat a "Credits"/"Ending" chapter as the credits of episodes that have
+	 *  no Intro Skipper credits range.
+	 */
+	creditsFallback?: boolean,
+	/**
+	 *  With `credits_fallback`, treat this many final seconds as credits when
+	 *  no chapter is named like them (None = chapters only).
+	 */
+	creditsFallbackSeconds?: number | null,
 	/**  Ordered subtitle language codes to prefer when Jellyfin does not request a track. */
 	preferredSubtitleLanguages?: string[],
 	/**  Cache Library Browser images on disk for faster repeat browsing. */
@@ -162,6 +324,144 @@ export type AppConfig = {
 	keybindPrev?: string,
 	/**  Keybinding for manual Intro Skipper seek in MPV. */
 	keybindIntroSkip?: string,
+	/**  Keybinding that turns automatic intro skipping on or off for this MPV session. */
+	keybindToggleIntroSkip?: string,
+	/**  Keybinding that marks the playing item watched or unwatched. */
+	keybindToggleWatched?: string,
+	/**  Keybinding that adds the playing item to the favorites or removes it. */
+	keybindToggleFavorite?: string,
+	/**  Keybinding that raises the playback speed. */
+	keybindSpeedUp?: string,
+	/**  Keybinding that lowers the playback speed. */
+	keybindSpeedDown?: string,
+	/**  Keybinding that toggles MPV's stats overlay. */
+	keybindStats?: string,
+	/**  Keybinding that stops playback and leaves MPV idle. */
+	keybindStopIdle?: string,
+	/**  Keybinding that cycles the secondary subtitle through the item's streams. */
+	keybindSecondarySubtitle?: string,
+	/**  Keybinding that sets the A-B loop start, then its end, then clears it. */
+	keybindAbLoop?: string,
+	/**  Keybinding that saves a screenshot named after the playing item. */
+	keybindScreenshot?: string,
+	/**
+	 *  Extra MPV keybindings, mapping a key to the MPV input command it runs
+	 *  (e.g. `"Ctrl+n": "script-message jellypilot-next"`).
+	 */
+	customBindings?: Partial<{ [key in string]: string }>,
+	/**  Start MPV with a JellyPilot-generated mpv.conf instead of the user's own config. */
+	managedMpvConfig?: boolean,
+	/**  Demuxer cache size in MiB for the managed mpv.conf (None = MPV default). */
+	mpvCacheSizeMib?: number | null,
+	/**  Screen index MPV opens on for the managed mpv.conf (None = MPV default). */
+	mpvScreen?: number | null,
+	/**  Open MPV fullscreen with the managed mpv.conf. */
+	mpvFullscreen?: boolean,
+	/**  Show MPV's on-screen controller with the managed mpv.conf. */
+	mpvOsc?: boolean,
+	/**  Switch MPV to fullscreen whenever new playback starts. */
+	fullscreenOnPlayback?: boolean,
+	/**  Monitor index used for fullscreen (None = MPV default). */
+	fullscreenScreen?: number | null,
+	/**  Switch to fullscreen when playback is cast from another device, such as a phone. */
+	fullscreenRemoteCasts?: boolean,
+	/**  Wayland/X11 window options for MPV on Linux (auto-detected by default). */
+	mpvDisplayServer?: DisplayServerMode,
+	/**  What happens to MPV when playback is stopped from Jellyfin. */
+	mpvStopBehavior?: MpvStopBehavior,
+	/**  Start MPV at launch and again whenever none runs between playbacks. */
+	mpvWarmStart?: MpvWarmStart,
+	/**  Seconds MPV gets to answer an IPC command. */
+	mpvCommandTimeoutSeconds?: number,
+	/**
+	 *  Timeouts in seconds for single IPC commands by name (e.g. `loadfile`),
+	 *  replacing the default for them.
+	 */
+	mpvCommandTimeouts?: Partial<{ [key in string]: number }>,
+	/**  Take over an MPV a crashed JellyPilot left running instead of quitting it. */
+	adoptOrphanedMpv?: boolean,
+	/**  Let MPV keep playing when JellyPilot crashes instead of ending with it. */
+	mpvOutlivesApp?: boolean,
+	/**  Recent MPV commands kept for diagnostics bundles. */
+	commandAuditRetention?: number,
+	/**  MPV session logs kept on disk, including the current one. */
+	mpvLogRetention?: number,
+	/**  Step over specials (season 0) when moving to the next or previous episode. */
+	skipSpecials?: boolean,
+	/**  Where automatic playback of the next episode may continue. */
+	autoNextScope?: AutoNextScope,
+	/**  Let automatic playback continue into specials (season 0). */
+	autoNextSpecials?: boolean,
+	/**  Start the next item when the credits begin instead of at the end of the file. */
+	autoNextAtCredits?: boolean,
+	/**  Seconds of "Up next" countdown shown over the credits first (None = no countdown). */
+	autoNextCountdownSeconds?: number | null,
+	/**  Record the raw payload of WebSocket messages that fail to parse and warn about them. */
+	websocketParseDiagnostics?: boolean,
+	/**  OS notification when playback starts, with the item's artwork. */
+	osNotifyPlaybackStarted?: boolean,
+	/**  OS notification when the next episode or queue item starts on its own. */
+	osNotifyAutoNext?: boolean,
+	/**  OS notifications when the server connection is lost and restored. */
+	osNotifyConnection?: boolean,
+	/**  OS notification when MPV exits unexpectedly during playback. */
+	osNotifyMpvCrash?: boolean,
+	/**  Serve Prometheus metrics on 127.0.0.1 at this port (None = off). */
+	metricsPort?: number | null,
+	/**  Receivers of playback start/pause/resume/stop/auto-next events. */
+	webhooks?: Webhook[],
+	/**  Serve the local control API on 127.0.0.1 at this port (None = off). */
+	controlApiPort?: number | null,
+	/**  Bearer token the control API requires; generated when the API is enabled. */
+	controlApiToken?: string,
+	/**  MPV volume reached at remote volume 100; MPV is never allowed above it. */
+	maxVolume?: number,
+	/**  How remote volume maps onto MPV volume below the cap. */
+	volumeCurve?: VolumeCurve,
+	/**  Which items share track changes made during playback. */
+	trackPreferenceScope?: TrackPreferenceScope,
+	/**  Which version plays when a Play request doesn't name one. */
+	mediaVersionPreference?: MediaVersionPreference,
+	/**  Video height in pixels used by the preferred-resolution version choice. */
+	preferredVideoHeight?: number,
+	/**  Seconds MPV may wait for the network before a stall warning (None = no warnings). */
+	stallWarningSeconds?: number | null,
+	/**  Reload as a lower-bitrate transcode when stall warnings keep recurring. */
+	lowerBitrateOnStalls?: boolean,
+	/**  Pause or stop the user's other session that was playing an item cast here. */
+	castTakeover?: CastTakeover,
+	/**
+	 *  PIN guarding settings and destructive actions (None = no lock). Only
+	 *  changed through `settings_set_pin`; `config_set` keeps the stored value.
+	 */
+	settingsPin?: SettingsPin | null,
+	/**
+	 *  Look for newer releases on GitHub. Off for distribution packages,
+	 *  which update through the package manager.
+	 */
+	checkForUpdates?: boolean,
+	/**  The user agreed to send anonymous usage statistics. */
+	telemetryConsent?: boolean,
+	/**  http(s) URL the usage statistics are posted to. */
+	telemetryEndpoint?: string,
+	/**  Log the usage statistics instead of sending them. */
+	telemetryDryRun?: boolean,
+	/**
+	 *  Seconds to go back when resuming a saved position or after a long pause
+	 *  (None = resume where playback stopped).
+	 */
+	resumeRewindSeconds?: number | null,
+	/**  Pauses at least this many seconds long rewind on resume. */
+	resumeRewindAfterPauseSeconds?: number,
+	/**
+	 *  Folder screenshots are saved to (None = `JellyPilot` in the pictures
+	 *  folder).
+	 */
+	screenshotDir?: string | null,
+	/**  Media types the server may cast to JellyPilot. */
+	castMediaTypes?: CastMediaType[],
+	/**  Remote controls offered to other clients while JellyPilot plays. */
+	castCommands?: RemoteCommand[],
 };
 
 /**  App notification event emitted to frontend. */
@@ -170,10 +470,60 @@ export type AppNotification = {
 	message: string,
 };
 
+/**  How far playback continues on its own when an episode ends. */
+export type AutoNextScope = "off" | "sameSeason" | "sameSeries";
+
+/**  Download totals for users on metered connections. */
+export type BandwidthStats = {
+	/**  Item of the current or last playback session. */
+	itemId: string | null,
+	/**  Bytes downloaded while playing that item. */
+	sessionBytes: number,
+	/**  Seconds since that session started. */
+	sessionSeconds: number,
+	/**  Bytes downloaded since JellyPilot started. */
+	totalBytes: number,
+	/**  Current download speed in bytes per second. */
+	bytesPerSecond: number,
+};
+
+/**  Download totals emitted periodically while playing. */
+export type BandwidthUpdated = {
+	stats: BandwidthStats,
+};
+
+/**  What one bounded record holds, for `diagnostics_memory_report`. */
+export type BufferUsage = {
+	name: string,
+	entries: number,
+	/**  Entries kept at most. */
+	capacity: number,
+	/**  Estimated size of the entries, in memory or on disk. */
+	bytes: number,
+};
+
+/**  Bundled MPV state for the settings window. */
+export type BundledMpvStatus = {
+	/**  Version of the installed build, or null when none is installed. */
+	version: string | null,
+	/**  Path of the installed MPV executable. */
+	executable: string | null,
+};
+
+/**  Media a cast to JellyPilot may play. */
+export type CastMediaType = "video" | "audio";
+
+/**  What happens on the device that was playing an item when it is cast to JellyPilot. */
+export type CastTakeover = 
+/**  Leave the other device alone. */
+"off" | "pause" | "stop";
+
 /**  Typed command error for better frontend error handling. */
 export type CommandError = {
 	code: CommandErrorCode,
 	message: string,
+	/**  Settings that failed validation, for `config_set`. */
+	fieldErrors?: ConfigFieldError[] | null,
 };
 
 /**  Error codes for frontend to distinguish error types. */
@@ -192,6 +542,25 @@ export type CommandErrorCode =
 "internal";
 
 /**  Connection state exposed to frontend. */
+/**  A setting that failed validation. */
+export type ConfigFieldError = {
+	/**  The setting's name as serialized, e.g. `deviceName`. */
+	field: string,
+	message: string,
+};
+
+/**
+ *  Server connection changes, for a status banner that replaces toasts
+ *  about every lost and restored connection.
+ */
+export type ConnectionEvent = { status: "connecting" } | { status: "connected", 
+/**  Server name, or its address when the name is unknown. */
+server: string } | 
+/**  The connection was lost; attempt `attempt` follows in `delay_seconds`. */
+{ status: "reconnecting", attempt: number, delaySeconds: number } | 
+/**  Not connected and not retrying, e.g. after signing out. */
+{ status: "disconnected", reason: string };
+
 export type ConnectionState = {
 	provider: MediaServerProvider,
 	capabilities: ProviderCapabilities,
@@ -208,14 +577,80 @@ export type Credentials = {
 	serverUrl: string,
 	username: string,
 	password: string,
+	/**  Extra certificate trust for servers with self-signed certificates. */
+	tls?: TlsTrust,
+	/**  LAN address of the same server; whichever address answers faster is used. */
+	localUrl?: string | null,
+	/**
+	 *  Headers a reverse proxy in front of the server requires, sent with
+	 *  every request and the WebSocket upgrade.
+	 */
+	customHeaders?: Partial<{ [key in string]: string }>,
 };
 
+/**  Which display server options MPV is spawned with. */
+export type DisplayServerMode = 
+/**  Detect the session type; no options outside Linux. */
+"auto" | "wayland" | "x11" | 
+/**  Leave window options to MPV and the user's mpv.conf. */
+"off";
+
 /**  Intro Skipper behavior mode. */
 export type IntroSkipperMode = "automatic" | "manual" | "off";
 
+/**  Language of backend-originated strings. */
+export type Language = "english" | "german";
+
 /**  Media server provider selected for a connection or saved service profile. */
 export type MediaServerProvider = "jellyfin" | "emby";
 
+/**  Version (media source) of the current item. */
+export type MediaVersion = {
+	/**  Id passed to `playback_switch_version`. */
+	id: string,
+	/**  Version name from the server, or the resolution when it has none. */
+	name: string,
+	container: string | null,
+	/**  Video height in pixels. */
+	height: number | null,
+	/**  Total bitrate in bits per second. */
+	bitrate: number | null,
+	/**  Whether this version is playing. */
+	isCurrent: boolean,
+};
+
+/**  Which version to play when an item has several media sources. */
+/**  What happens to MPV when playback is stopped from Jellyfin. */
+export type MpvStopBehavior = 
+/**  Quit MPV; the next cast starts a new one. */
+"quit" | 
+/**  Stop playback and leave the idle MPV window open, so the next cast starts faster. */
+"keepIdle" | 
+/**  Stop playback and close the window while MPV keeps running in the background. */
+"hideWindow";
+
+export type MediaVersionPreference = 
+/**  The first version the server lists. */
+"serverDefault" | "highestBitrate" | "lowestBitrate" | 
+/**  The version closest to the preferred video height without exceeding it. */
+"preferredResolution";
+
+/**  Release version of an MPV build. */
+export type MpvVersion = {
+	major: number,
+	minor: number,
+	patch: number,
+};
+
+/**  Whether an idle MPV is kept ready for the first cast. */
+export type MpvWarmStart = 
+/**  Start MPV on the first cast. */
+"off" | 
+/**  Start MPV at launch without a window. */
+"hidden" | 
+/**  Start MPV at launch with its window minimized. */
+"minimized";
+
 /**  Notification level for UI display. */
 export type NotificationLevel = "error" | "warning" | "info" | "success";
 
@@ -232,6 +667,8 @@ export type NowPlayingMedia = {
 	seriesName: string | null,
 	seasonNumber: number | null,
 	episodeNumber: number | null,
+	/**  None until the server has sent it. */
+	userData: NowPlayingUserData | null,
 };
 
 /**  User-facing playback state for the Operations Console. */
@@ -248,6 +685,49 @@ export type NowPlayingState = {
 /**  User-facing Now Playing status. */
 export type NowPlayingStatus = "offline" | "idle" | "playing" | "paused" | "unknown";
 
+/**  The signed-in user's watched, favorite and rating state of the current media. */
+export type NowPlayingUserData = {
+	played: boolean,
+	favorite: boolean,
+	/**  Thumbs up (true) or down (false); None when not rated. */
+	likes: boolean | null,
+};
+
+/**  Chapter of the playing item. */
+export type PlaybackChapter = {
+	/**  Position in the list, as `playback_goto_chapter` takes it. */
+	index: number,
+	/**  None when neither the stream nor the server names the chapter. */
+	title: string | null,
+	startSeconds: number,
+};
+
+/**  Playback failure event emitted to frontend. */
+export type PlaybackError = {
+	stage: PlaybackErrorStage,
+	/**  What the user can do about the failure. */
+	message: string,
+	/**  The underlying error, for details and bug reports. */
+	detail: string,
+};
+
+/**  Stage of starting or running playback that failed. */
+export type PlaybackErrorStage = 
+/**  The server rejected the session token. */
+"auth" | 
+/**  Item metadata or playback info could not be fetched. */
+"playback-info" | 
+/**  No stream URL could be built for the media source. */
+"stream-url" | 
+/**  MPV could not be started. */
+"mpv-spawn" | 
+/**  MPV failed to open the stream. */
+"mpv-load" | 
+/**  The IPC connection to MPV dropped during playback. */
+"ipc-lost" | 
+/**  The file ended well before the end of the item. */
+"ended-early";
+
 /**  Player transport state returned to frontend. */
 export type PlayerState = {
 	connected: boolean,
@@ -281,6 +761,28 @@ export type QuickConnectRequest = {
 /**  Quick Connect request status exposed to the frontend. */
 export type QuickConnectStatus = "waiting" | "approved";
 
+/**  Remote control command JellyPilot can handle. */
+export type RemoteCommand = "play" | "playstate" | "setVolume" | "volumeUp" | "volumeDown" | "toggleMute" | "toggleFullscreen" | "setAudioStreamIndex" | "setSubtitleStreamIndex" | "setRepeatMode" | "setShuffleQueue";
+
+/**  Playstate command sent to another session. */
+export type RemotePlaystateCommand = "pause" | "unpause" | "playPause" | "stop" | 
+/**  Seek to the position given with the command. */
+"seek" | "nextTrack" | "previousTrack";
+
+/**
+ *  Another active session on the server, e.g. the phone that cast to
+ *  JellyPilot or a TV it can control.
+ */
+export type RemoteSession = {
+	id: string,
+	userName: string | null,
+	deviceName: string | null,
+	client: string | null,
+	nowPlayingItemId: string | null,
+	nowPlayingName: string | null,
+	isPaused: boolean,
+};
+
 export type SavedServiceProfileSummary = {
 	key: string,
 	provider: MediaServerProvider,
@@ -305,6 +807,148 @@ export type SavedSession = {
 	userName: string,
 	serverName: string | null,
 	deviceId: string | null,
+	tls?: TlsTrust,
+	localUrl?: string | null,
+	customHeaders?: Partial<{ [key in string]: string }>,
+	/**
+	 *  ID the server reported at sign-in; another address is only used when
+	 *  the server there reports the same ID.
+	 */
+	serverId?: string | null,
+};
+
+/**
+ *  Audio and subtitle choice remembered from track changes during playback.
+ * 
+ *  Sent back unchanged apart from the track fields to edit an entry; `scope`
+ *  and `label` are ignored then.
+ */
+export type SavedTrackPreference = {
+	/**  Key passed to `preferences_delete`. */
+	key: string,
+	scope: TrackPreferenceScope,
+	/**  Series or library name, when known. */
+	label: string | null,
+	audioLanguage: string | null,
+	audioTitle: string | null,
+	subtitleLanguage: string | null,
+	subtitleTitle: string | null,
+	/**  Whether subtitles are turned on; None when no subtitle choice was saved. */
+	subtitlesEnabled: boolean | null,
+};
+
+/**  What a screenshot shows. */
+export type ScreenshotMode = 
+/**  The video with subtitles, at the video's resolution. */
+"subtitles" | 
+/**  The video alone. */
+"video" | 
+/**  The window as shown, with the OSD and at the window's size. */
+"window";
+
+/**  One row of the self-check list. */
+export type SelfCheck = {
+	kind: SelfCheckKind,
+	status: SelfCheckStatus,
+	/**  What was found, e.g. the MPV path or the server's error. */
+	detail: string,
+	/**  How to fix a warning or failure. */
+	hint: string | null,
+};
+
+/**  What a self-check looked at. */
+export type SelfCheckKind = "mpvBinary" | "mpvVersion" | "ipcPath" | "serverReachable" | "tokenValid" | "webSocket" | "capabilities";
+
+/**  Outcome of a self-check. */
+export type SelfCheckStatus = "passed" | 
+/**  Works, but something is likely to cause trouble. */
+"warning" | "failed" | 
+/**  Not run because an earlier check failed or does not apply. */
+"skipped";
+
+/**
+ *  Event emitted when the server stops accepting the saved session, e.g.
+ *  after a password change. The session stays idle until the user signs in
+ *  again.
+ */
+export type SessionExpired = {
+	/**  What the user can do about it. */
+	message: string,
+	/**  The server's rejection, for details and bug reports. */
+	detail: string,
+};
+
+/**  Phase of the playback session. */
+export type SessionPhase = 
+/**  No session is running. */
+"disconnected" | 
+/**  Connected and waiting for something to play. */
+"idle" | 
+/**  MPV is opening a stream. */
+"loading" | "playing" | "paused" | 
+/**  Playback waits for the network to fill the cache. */
+"stalling" | 
+/**  MPV was told to stop and has not confirmed yet. */
+"stopping";
+
+/**  Whether settings are locked, exposed to the frontend. */
+export type SettingsLockStatus = {
+	/**  A PIN is configured. */
+	pinSet: boolean,
+	/**  Settings can be changed right now. */
+	unlocked: boolean,
+};
+
+/**  Salted hash of the settings PIN. */
+export type SettingsPin = {
+	/**  Random salt as hex. */
+	salt: string,
+	/**  Stretched SHA-256 of salt and PIN as hex. */
+	hash: string,
+};
+
+/**  Anonymous usage counters sent to the telemetry endpoint. */
+export type TelemetryReport = {
+	appVersion: string,
+	/**  Operating system family, e.g. `linux` or `windows`. */
+	os: string,
+	arch: string,
+	/**  None when MPV's version was not detected. */
+	mpvVersion: string | null,
+	/**  Playbacks started since the previous report. */
+	playbacks: number,
+	/**  MPV exits during playback since the previous report. */
+	mpvCrashes: number,
+};
+
+/**  How certificates presented by a server are trusted. */
+export type TlsTrust = {
+	/**  PEM file with CA certificates trusted in addition to the public roots. */
+	caCertificatePath?: string | null,
+	/**
+	 *  SHA-256 fingerprint of a server certificate accepted even when it does
+	 *  not chain to a trusted CA, as hex with optional colons.
+	 */
+	pinnedFingerprint?: string | null,
+};
+
+/**  Which items share remembered audio and subtitle choices. */
+export type TrackPreferenceScope = 
+/**  Each series keeps its own choices. */
+"series" | 
+/**  All items in the same library share choices. */
+"library" | 
+/**  One set of choices for everything. */
+"global";
+
+/**  Event emitted when a newer JellyPilot release is published. */
+export type UpdateAvailable = {
+	/**  The release's version, e.g. `1.5.0`. */
+	version: string,
+	/**  Release notes in Markdown. */
+	releaseNotes: string,
+	/**  The release page with the downloads. */
+	downloadUrl: string,
 };
 
 /**  Library Browser landing data exposed to the frontend. */
@@ -510,6 +1154,8 @@ export type VideoUserDataUpdate = {
 	itemId: string,
 	played: boolean,
 	favorite: boolean,
+	/**  Thumbs up (true) or down (false); None when not rated. */
+	likes: boolean | null,
 };
 
 /**  User-scoped Jellyfin user data mutation request. */
@@ -518,6 +1164,21 @@ export type VideoUserDataUpdateRequest = {
 	action: VideoUserDataAction,
 };
 
+/**  How remote volume (0-100) is spread over MPV's volume range. */
+export type VolumeCurve = 
+/**  Volume scales proportionally. */
+"linear" | 
+/**  Quadratic: finer steps at low volume, closer to perceived loudness. */
+"soft";
+
+/**  A webhook receiver. */
+export type Webhook = {
+	/**  http(s) URL the events are posted to. */
+	url: string,
+	/**  Key for the HMAC-SHA256 body signature; empty for unsigned requests. */
+	secret?: string,
+};
+
 /* Tauri Specta runtime */
 async function typedError<T, E>(result: Promise<T>): Promise<{ status: "ok"; data: T } | { status: "error"; error: E }> {
     try {
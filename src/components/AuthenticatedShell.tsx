@@ -1,11 +1,14 @@
 import { createQuery } from '@tanstack/solid-query';
-import { Outlet } from '@tanstack/solid-router';
+import { Outlet, useNavigate } from '@tanstack/solid-router';
 import { Exit } from 'effect';
+import { onCleanup, onMount } from 'solid-js';
 
-import { fetchConnectionState } from '../effects/connection';
+import { fetchConnectionState, listenSessionExpired } from '../effects/connection';
 import { queryKeys, runExit } from '../effects/query';
+import ConnectionBanner from './ConnectionBanner';
 import NowPlayingDrawer from './NowPlayingDrawer';
 import SettingsModal from './SettingsModal';
+import { useToast } from './ToastProvider';
 import { ConsoleShell } from './ui';
 
 export default function AuthenticatedShell() {
@@ -17,9 +20,34 @@ export default function AuthenticatedShell() {
     connectionQuery.data && Exit.isSuccess(connectionQuery.data)
       ? connectionQuery.data.value.connected
       : false;
+  const navigate = useNavigate();
+  const { showToast } = useToast();
+
+  // A rejected session can't recover on its own, so send the user to sign in.
+  onMount(() => {
+    let disposed = false;
+    let cleanup: (() => void) | undefined;
+    listenSessionExpired((event) => {
+      showToast('error', event.message);
+      void connectionQuery.refetch();
+      void navigate({ to: '/login', search: { expired: true } });
+    }).then((unlisten) => {
+      if (disposed) {
+        unlisten();
+      } else {
+        cleanup = unlisten;
+      }
+    });
+
+    onCleanup(() => {
+      disposed = true;
+      cleanup?.();
+    });
+  });
 
   return (
     <ConsoleShell>
+      <ConnectionBanner />
       {/*
         Bottom padding reserves space so the fixed bottom-right floating cluster
         (Now Playing + Open Settings) never covers the last Library Browser items.
@@ -0,0 +1,76 @@
+import type { ConnectionEvent } from '@bindings';
+import { LoaderCircle, WifiOff } from 'lucide-solid';
+import { createSignal, Match, onCleanup, onMount, Show, Switch } from 'solid-js';
+
+import { listenConnectionEvents } from '../effects/connection';
+
+/** Persistent server connection status; hidden while connected. */
+export default function ConnectionBanner() {
+  const [event, setEvent] = createSignal<ConnectionEvent | null>(null);
+
+  onMount(() => {
+    let disposed = false;
+    let cleanup: (() => void) | undefined;
+    listenConnectionEvents(setEvent).then((unlisten) => {
+      if (disposed) {
+        unlisten();
+      } else {
+        cleanup = unlisten;
+      }
+    });
+
+    onCleanup(() => {
+      disposed = true;
+      cleanup?.();
+    });
+  });
+
+  const visible = () => {
+    const current = event();
+    return current !== null && current.status !== 'connected';
+  };
+  const reconnecting = () => {
+    const current = event();
+    return current?.status === 'reconnecting' ? current : undefined;
+  };
+  const disconnected = () => {
+    const current = event();
+    return current?.status === 'disconnected' ? current : undefined;
+  };
+
+  return (
+    <Show when={visible()}>
+      <div
+        role="status"
+        aria-live="polite"
+        class="bg-warning-container/85 text-on-warning-container border-warning/25 sticky top-0 z-90 flex items-center justify-center gap-2 border-b px-4 py-2 text-[14px] leading-[20px] backdrop-blur-md"
+      >
+        <Switch>
+          <Match when={reconnecting()}>
+            {(current) => (
+              <>
+                <LoaderCircle class="h-4 w-4 animate-spin" />
+                <span>
+                  Connection lost. Reconnecting in {Math.round(current().delaySeconds)} s (attempt{' '}
+                  {current().attempt})…
+                </span>
+              </>
+            )}
+          </Match>
+          <Match when={disconnected()}>
+            {(current) => (
+              <>
+                <WifiOff class="h-4 w-4" />
+                <span>{current().reason}</span>
+              </>
+            )}
+          </Match>
+          <Match when={event()?.status === 'connecting'}>
+            <LoaderCircle class="h-4 w-4 animate-spin" />
+            <span>Connecting to the server…</span>
+          </Match>
+        </Switch>
+      </div>
+    </Show>
+  );
+}
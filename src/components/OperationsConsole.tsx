@@ -6,18 +6,33 @@ import { X } from 'lucide-solid';
 import { Show, createEffect, createSignal } from 'solid-js';
 import { Portal } from 'solid-js/web';
 
-import type { AppConfig, IntroSkipperMode } from '../bindings';
+import type { AppConfig, CastMediaType, IntroSkipperMode, RemoteCommand } from '../bindings';
 import { commandFailureMessage } from '../effects/commands';
 import { detectMpv, fetchConfig, saveConfig } from '../effects/config';
-import { disconnectJellyfin, fetchConnectionState } from '../effects/connection';
+import {
+  disconnectJellyfin,
+  fetchConnectionState,
+  refreshCastRegistration,
+  regenerateDeviceId,
+} from '../effects/connection';
 import {
   activateSavedServiceProfile,
   fetchSavedServiceProfiles,
   removeSavedServiceProfile,
 } from '../effects/profiles';
 import { queryKeys, runExit } from '../effects/query';
+import {
+  fetchSettingsLockStatus,
+  lockSettings,
+  setSettingsPin,
+  unlockSettings,
+} from '../effects/settingsLock';
 import { restoreSavedSession } from '../sessionAccess';
 import LoginPage from './LoginPage';
+import CastTargetCard, {
+  DEFAULT_CAST_COMMANDS,
+  DEFAULT_CAST_MEDIA_TYPES,
+} from './OperationsConsole/CastTargetCard';
 import ConnectionCard from './OperationsConsole/ConnectionCard';
 import DiagnosticsCard from './OperationsConsole/DiagnosticsCard';
 import IntroSkipCard from './OperationsConsole/IntroSkipCard';
@@ -25,12 +40,14 @@ import LibrarySettingsCard from './OperationsConsole/LibrarySettingsCard';
 import PlayerBridgeSettingsCard from './OperationsConsole/PlayerBridgeSettingsCard';
 import SavedServicesCard from './OperationsConsole/SavedServicesCard';
 import SessionCard from './OperationsConsole/SessionCard';
+import SettingsLockCard from './OperationsConsole/SettingsLockCard';
 import ShortcutKeysCard from './OperationsConsole/ShortcutKeysCard';
 import { createOperationsConsoleStore } from './OperationsConsole/store';
 import {
   normalizePreferredSubtitleLanguages,
   parseSubtitleLanguageInput,
 } from './OperationsConsole/subtitleLanguages';
+import type { ShortcutKeyField } from './OperationsConsole/types';
 import { useToast } from './ToastProvider';
 import { Button, ConsoleContainer, ConsoleGrid, PageFooter } from './ui';
 import type { JellyPilotSelectItem } from './ui';
@@ -47,6 +64,10 @@ export default function OperationsConsole(props: OperationsConsoleProps) {
   const [activatingProfileKey, setActivatingProfileKey] = createSignal<string | null>(null);
   const [removingProfileKey, setRemovingProfileKey] = createSignal<string | null>(null);
   const [imageCacheEnabledDraft, setImageCacheEnabledDraft] = createSignal<boolean | null>(null);
+  const [castTargetDraft, setCastTargetDraft] = createSignal<Pick<
+    AppConfig,
+    'castMediaTypes' | 'castCommands'
+  > | null>(null);
 
   let configHydrated = false;
   interface PendingSave {
@@ -85,12 +106,31 @@ export default function OperationsConsole(props: OperationsConsoleProps) {
     queryKey: queryKeys.appConfig,
     queryFn: () => runExit(fetchConfig()),
   }));
+  const settingsLockQuery = createQuery(() => ({
+    queryKey: queryKeys.settingsLockStatus,
+    queryFn: () => runExit(fetchSettingsLockStatus()),
+  }));
+  const unlockSettingsMutation = createMutation(() => ({
+    mutationFn: (pin: string) => runExit(unlockSettings(pin)),
+  }));
+  const lockSettingsMutation = createMutation(() => ({
+    mutationFn: () => runExit(lockSettings()),
+  }));
+  const settingsPinMutation = createMutation(() => ({
+    mutationFn: (pin: string | null) => runExit(setSettingsPin(pin)),
+  }));
   const saveConfigMutation = createMutation(() => ({
     mutationFn: (config: AppConfig) => runExit(saveConfig(config)),
   }));
   const disconnectMutation = createMutation(() => ({
     mutationFn: () => runExit(disconnectJellyfin()),
   }));
+  const castRegistrationMutation = createMutation(() => ({
+    mutationFn: () => runExit(refreshCastRegistration()),
+  }));
+  const deviceIdMutation = createMutation(() => ({
+    mutationFn: () => runExit(regenerateDeviceId()),
+  }));
   const detectMpvMutation = createMutation(() => ({
     mutationFn: () => runExit(detectMpv()),
   }));
@@ -120,9 +160,19 @@ export default function OperationsConsole(props: OperationsConsoleProps) {
     defaultValues: {
       deviceName: 'JellyPilot',
       introSkipperMode: 'automatic' as IntroSkipperMode,
+      keybindAbLoop: 'Alt+l',
       keybindIntroSkip: 'g',
       keybindNext: 'Shift+>',
       keybindPrev: 'Shift+<',
+      keybindScreenshot: 'Alt+s',
+      keybindSecondarySubtitle: 'Alt+j',
+      keybindSpeedDown: 'Alt+[',
+      keybindSpeedUp: 'Alt+]',
+      keybindStats: 'Alt+i',
+      keybindStopIdle: 'Alt+q',
+      keybindToggleFavorite: 'Alt+f',
+      keybindToggleIntroSkip: 'Alt+g',
+      keybindToggleWatched: 'Alt+w',
       mpvArgs: '',
       mpvPath: '',
     },
@@ -138,6 +188,16 @@ export default function OperationsConsole(props: OperationsConsoleProps) {
       form.setFieldValue('keybindNext', cfg.keybindNext ?? 'Shift+>');
       form.setFieldValue('keybindPrev', cfg.keybindPrev ?? 'Shift+<');
       form.setFieldValue('keybindIntroSkip', cfg.keybindIntroSkip ?? 'g');
+      form.setFieldValue('keybindToggleIntroSkip', cfg.keybindToggleIntroSkip ?? 'Alt+g');
+      form.setFieldValue('keybindToggleWatched', cfg.keybindToggleWatched ?? 'Alt+w');
+      form.setFieldValue('keybindToggleFavorite', cfg.keybindToggleFavorite ?? 'Alt+f');
+      form.setFieldValue('keybindSpeedUp', cfg.keybindSpeedUp ?? 'Alt+]');
+      form.setFieldValue('keybindSpeedDown', cfg.keybindSpeedDown ?? 'Alt+[');
+      form.setFieldValue('keybindStats', cfg.keybindStats ?? 'Alt+i');
+      form.setFieldValue('keybindStopIdle', cfg.keybindStopIdle ?? 'Alt+q');
+      form.setFieldValue('keybindSecondarySubtitle', cfg.keybindSecondarySubtitle ?? 'Alt+j');
+      form.setFieldValue('keybindAbLoop', cfg.keybindAbLoop ?? 'Alt+l');
+      form.setFieldValue('keybindScreenshot', cfg.keybindScreenshot ?? 'Alt+s');
       actions.hydrateFromConfig({
         introSkipperMode: cfg.introSkipperMode ?? 'automatic',
         mpvArgs: cfg.mpvArgs,
@@ -158,6 +218,10 @@ export default function OperationsConsole(props: OperationsConsoleProps) {
   const profiles = () =>
     profilesQuery.data && Exit.isSuccess(profilesQuery.data) ? profilesQuery.data.value : null;
   const capabilities = () => state()?.capabilities;
+  const settingsLockStatus = () =>
+    settingsLockQuery.data && Exit.isSuccess(settingsLockQuery.data)
+      ? settingsLockQuery.data.value
+      : null;
   const config = () =>
     configQuery.data && Exit.isSuccess(configQuery.data) ? configQuery.data.value : null;
   const introSkipperMode = () => ui.introSkipperDraft ?? config()?.introSkipperMode ?? 'automatic';
@@ -167,6 +231,11 @@ export default function OperationsConsole(props: OperationsConsoleProps) {
     config()?.imageDiskCacheEnabled ??
     true;
 
+  const castMediaTypes = (): CastMediaType[] =>
+    castTargetDraft()?.castMediaTypes ?? config()?.castMediaTypes ?? DEFAULT_CAST_MEDIA_TYPES;
+  const castCommands = (): RemoteCommand[] =>
+    castTargetDraft()?.castCommands ?? config()?.castCommands ?? DEFAULT_CAST_COMMANDS;
+
   const showPlayerBridgeStatus = (type: 'saving' | 'saved' | 'error', text: string) => {
     if (clearPlayerBridgeStatusTimer) {
       clearTimeout(clearPlayerBridgeStatusTimer);
@@ -216,6 +285,8 @@ export default function OperationsConsole(props: OperationsConsoleProps) {
           showPlayerBridgeStatus('saved', 'Saved');
         } else {
           const message = commandFailureMessage(exit.cause, 'Could not save configuration');
+          // The unlock may have run out; show the PIN field again.
+          void settingsLockQuery.refetch();
           nextSave.onError?.(message);
           showPlayerBridgeStatus('error', message);
           showToast('error', message);
@@ -246,13 +317,7 @@ export default function OperationsConsole(props: OperationsConsoleProps) {
   };
 
   const saveTextSetting = (
-    field:
-      | 'deviceName'
-      | 'mpvPath'
-      | 'mpvArgs'
-      | 'keybindNext'
-      | 'keybindPrev'
-      | 'keybindIntroSkip',
+    field: 'deviceName' | 'mpvPath' | 'mpvArgs' | ShortcutKeyField,
     value: string,
   ) => {
     const saved = lastSavedConfig ?? config();
@@ -264,13 +329,7 @@ export default function OperationsConsole(props: OperationsConsoleProps) {
     if (field === 'deviceName' && value.trim().length === 0) {
       return;
     }
-    if (field === 'keybindNext' && value.trim().length === 0) {
-      return;
-    }
-    if (field === 'keybindPrev' && value.trim().length === 0) {
-      return;
-    }
-    if (field === 'keybindIntroSkip' && value.trim().length === 0) {
+    if (field.startsWith('keybind') && value.trim().length === 0) {
       return;
     }
 
@@ -350,6 +409,21 @@ export default function OperationsConsole(props: OperationsConsoleProps) {
     });
   };
 
+  const saveCastTarget = (
+    overrides: Pick<AppConfig, 'castMediaTypes'> | Pick<AppConfig, 'castCommands'>,
+  ) => {
+    const next = { castMediaTypes: castMediaTypes(), castCommands: castCommands(), ...overrides };
+    setCastTargetDraft(next);
+    queueConfigSave(buildConfigSnapshot(next), {
+      onError: () => {
+        setCastTargetDraft(null);
+      },
+      onSuccess: () => {
+        setCastTargetDraft(null);
+      },
+    });
+  };
+
   const addPreferredSubtitleLanguageCodes = (languages: string[]) => {
     if (languages.length === 0) {
       return;
@@ -439,6 +513,30 @@ export default function OperationsConsole(props: OperationsConsoleProps) {
     actions.finishDisconnect();
   };
 
+  const handleRefreshCastRegistration = async () => {
+    actions.beginCastRegistrationRefresh();
+    const exit = await castRegistrationMutation.mutateAsync();
+    if (Exit.isSuccess(exit)) {
+      showToast('success', 'This device is registered as a cast target');
+    } else {
+      showToast('error', commandFailureMessage(exit.cause, 'Cast registration failed'));
+    }
+    void connectionQuery.refetch();
+    actions.finishCastRegistrationRefresh();
+  };
+
+  const handleRegenerateDeviceId = async () => {
+    actions.beginDeviceIdRegeneration();
+    const exit = await deviceIdMutation.mutateAsync();
+    if (Exit.isSuccess(exit)) {
+      showToast('success', 'This device now has a new ID');
+    } else {
+      showToast('error', commandFailureMessage(exit.cause, 'Could not regenerate the device ID'));
+    }
+    void connectionQuery.refetch();
+    actions.finishDeviceIdRegeneration();
+  };
+
   const handleSignOut = async () => {
     const activeProfileKey = profiles()?.activeProfileKey;
     if (!activeProfileKey) {
@@ -539,6 +637,33 @@ export default function OperationsConsole(props: OperationsConsoleProps) {
     actions.finishMpvDetection();
   };
 
+  const handleUnlockSettings = async (pin: string) => {
+    const exit = await unlockSettingsMutation.mutateAsync(pin);
+    void settingsLockQuery.refetch();
+    if (Exit.isSuccess(exit)) {
+      showToast('success', 'Settings unlocked');
+      return true;
+    }
+    showToast('error', commandFailureMessage(exit.cause, 'Could not unlock settings'));
+    return false;
+  };
+
+  const handleLockSettings = async () => {
+    await lockSettingsMutation.mutateAsync();
+    void settingsLockQuery.refetch();
+  };
+
+  const handleSetSettingsPin = async (pin: string | null) => {
+    const exit = await settingsPinMutation.mutateAsync(pin);
+    void settingsLockQuery.refetch();
+    if (Exit.isSuccess(exit)) {
+      showToast('success', pin === null ? 'Settings PIN removed' : 'Settings PIN saved');
+      return true;
+    }
+    showToast('error', commandFailureMessage(exit.cause, 'Could not save the settings PIN'));
+    return false;
+  };
+
   const handleIntroSkipperModeChange = (mode: IntroSkipperMode) => {
     form.setFieldValue('introSkipperMode', mode);
     saveIntroSkipperSetting(mode);
@@ -549,6 +674,18 @@ export default function OperationsConsole(props: OperationsConsoleProps) {
       <ConsoleContainer>
         <ConsoleGrid>
           <div class="space-y-6">
+            <SettingsLockCard
+              status={settingsLockStatus()}
+              busy={
+                unlockSettingsMutation.isPending ||
+                lockSettingsMutation.isPending ||
+                settingsPinMutation.isPending
+              }
+              onUnlock={handleUnlockSettings}
+              onLock={() => void handleLockSettings()}
+              onSetPin={handleSetSettingsPin}
+            />
+
             <SavedServicesCard
               profiles={profiles()}
               activatingProfileKey={activatingProfileKey()}
@@ -564,6 +701,8 @@ export default function OperationsConsole(props: OperationsConsoleProps) {
               onDisconnect={handleDisconnect}
               onReconnect={handleReconnect}
               onRefresh={handleRefresh}
+              onRefreshCastRegistration={handleRefreshCastRegistration}
+              onRegenerateDeviceId={handleRegenerateDeviceId}
             />
 
             <form class="space-y-6">
@@ -593,6 +732,13 @@ export default function OperationsConsole(props: OperationsConsoleProps) {
               onImageDiskCacheEnabledChange={saveImageDiskCacheEnabled}
             />
 
+            <CastTargetCard
+              mediaTypes={castMediaTypes()}
+              commands={castCommands()}
+              onMediaTypesChange={(mediaTypes) => saveCastTarget({ castMediaTypes: mediaTypes })}
+              onCommandsChange={(commands) => saveCastTarget({ castCommands: commands })}
+            />
+
             <Show when={capabilities()?.introSkipper ?? true}>
               <IntroSkipCard
                 currentMode={introSkipperMode()}
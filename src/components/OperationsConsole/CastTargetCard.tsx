@@ -0,0 +1,132 @@
+import { Cast, Check } from 'lucide-solid';
+import { For, Show } from 'solid-js';
+
+import type { CastMediaType, RemoteCommand } from '../../bindings';
+import { SectionCard } from '../ui';
+
+const MEDIA_TYPES: { value: CastMediaType; label: string; description: string }[] = [
+  { value: 'video', label: 'Video', description: 'Movies, episodes and music videos.' },
+  { value: 'audio', label: 'Audio', description: 'Music and audiobooks.' },
+];
+
+const COMMANDS: { value: RemoteCommand; label: string }[] = [
+  { value: 'play', label: 'Play items' },
+  { value: 'playstate', label: 'Pause, seek and stop' },
+  { value: 'setVolume', label: 'Set volume' },
+  { value: 'volumeUp', label: 'Volume up' },
+  { value: 'volumeDown', label: 'Volume down' },
+  { value: 'toggleMute', label: 'Mute' },
+  { value: 'toggleFullscreen', label: 'Fullscreen' },
+  { value: 'setAudioStreamIndex', label: 'Audio track' },
+  { value: 'setSubtitleStreamIndex', label: 'Subtitle track' },
+  { value: 'setRepeatMode', label: 'Repeat mode' },
+  { value: 'setShuffleQueue', label: 'Shuffle' },
+];
+
+/** What JellyPilot advertises until the settings say otherwise. */
+export const DEFAULT_CAST_MEDIA_TYPES = MEDIA_TYPES.map((mediaType) => mediaType.value);
+export const DEFAULT_CAST_COMMANDS = COMMANDS.map((command) => command.value);
+
+interface CastTargetCardProps {
+  mediaTypes: CastMediaType[];
+  commands: RemoteCommand[];
+  onMediaTypesChange: (mediaTypes: CastMediaType[]) => void;
+  onCommandsChange: (commands: RemoteCommand[]) => void;
+}
+
+function toggled<T>(values: T[], value: T): T[] {
+  return values.includes(value) ? values.filter((item) => item !== value) : [...values, value];
+}
+
+function CheckboxMark(props: { checked: boolean }) {
+  return (
+    <span
+      aria-hidden="true"
+      class={`border-outline bg-surface-container-high text-on-primary hover:border-primary/60 mt-0.5 inline-flex h-5.5 w-5.5 shrink-0 items-center justify-center rounded-lg border text-[11px] leading-none transition-[background-color,border-color,box-shadow] duration-200 ${
+        props.checked ? 'border-primary from-primary to-primary-gradient-end bg-gradient-to-br' : ''
+      }`}
+    >
+      <Show when={props.checked}>
+        <Check class="h-3.5 w-3.5" stroke-width={3} />
+      </Show>
+    </span>
+  );
+}
+
+export default function CastTargetCard(props: CastTargetCardProps) {
+  return (
+    <SectionCard
+      icon={<Cast class="text-primary h-5 w-5 drop-shadow-[0_0_8px_rgba(79,70,229,0.4)]" />}
+      title="Cast target"
+    >
+      <div class="space-y-5">
+        <section>
+          <h3 class="text-on-surface-variant mb-1.5 block text-[12px] leading-[16px] font-bold tracking-[0.05em] uppercase">
+            Accept casts of
+          </h3>
+          <div class="grid grid-cols-1 gap-2.5 sm:grid-cols-2">
+            <For each={MEDIA_TYPES}>
+              {(mediaType) => {
+                const checked = () => props.mediaTypes.includes(mediaType.value);
+                // The last media type stays: without one nothing could be cast.
+                const locked = () => checked() && props.mediaTypes.length === 1;
+                return (
+                  <button
+                    type="button"
+                    role="checkbox"
+                    aria-label={mediaType.label}
+                    aria-checked={checked()}
+                    disabled={locked()}
+                    onClick={() =>
+                      props.onMediaTypesChange(toggled(props.mediaTypes, mediaType.value))
+                    }
+                    class="bg-surface-container-high/30 border-outline-variant/60 focus-visible:outline-primary flex cursor-pointer items-start gap-3 rounded-2xl border p-4 text-left shadow-inner backdrop-blur-sm focus-visible:outline-2 focus-visible:outline-offset-2 disabled:cursor-not-allowed"
+                  >
+                    <CheckboxMark checked={checked()} />
+                    <div class="min-w-0">
+                      <span class="text-on-surface block text-[14px] leading-5 font-semibold">
+                        {mediaType.label}
+                      </span>
+                      <p class="text-on-surface-variant/80 mt-1 text-[12px] leading-4">
+                        {mediaType.description}
+                      </p>
+                    </div>
+                  </button>
+                );
+              }}
+            </For>
+          </div>
+        </section>
+
+        <section>
+          <h3 class="text-on-surface-variant mb-1.5 block text-[12px] leading-[16px] font-bold tracking-[0.05em] uppercase">
+            Remote controls
+          </h3>
+          <p class="text-on-surface-variant/80 mb-2.5 text-[12px] leading-4">
+            Controls other Jellyfin clients may use while JellyPilot plays.
+          </p>
+          <div class="grid grid-cols-1 gap-2 sm:grid-cols-2">
+            <For each={COMMANDS}>
+              {(command) => {
+                const checked = () => props.commands.includes(command.value);
+                return (
+                  <button
+                    type="button"
+                    role="checkbox"
+                    aria-label={command.label}
+                    aria-checked={checked()}
+                    onClick={() => props.onCommandsChange(toggled(props.commands, command.value))}
+                    class="focus-visible:outline-primary flex cursor-pointer items-center gap-3 rounded-xl px-2 py-1.5 text-left focus-visible:outline-2 focus-visible:outline-offset-2"
+                  >
+                    <CheckboxMark checked={checked()} />
+                    <span class="text-on-surface text-[14px] leading-5">{command.label}</span>
+                  </button>
+                );
+              }}
+            </For>
+          </div>
+        </section>
+      </div>
+    </SectionCard>
+  );
+}
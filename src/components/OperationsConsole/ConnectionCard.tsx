@@ -1,4 +1,14 @@
-import { Activity, AlertTriangle, Link, Power, RefreshCw, Server, User } from 'lucide-solid';
+import {
+  Activity,
+  AlertTriangle,
+  Cast,
+  Fingerprint,
+  Link,
+  Power,
+  RefreshCw,
+  Server,
+  User,
+} from 'lucide-solid';
 import { Show } from 'solid-js';
 
 import type { ConnectionState } from '../../bindings';
@@ -11,6 +21,8 @@ interface ConnectionCardProps {
   onDisconnect: () => void;
   onReconnect: () => void;
   onRefresh: () => void;
+  onRefreshCastRegistration: () => void;
+  onRegenerateDeviceId: () => void;
 }
 
 export default function ConnectionCard(props: ConnectionCardProps) {
@@ -112,6 +124,28 @@ export default function ConnectionCard(props: ConnectionCardProps) {
             {ui.reconnecting ? 'Reconnecting...' : 'Reconnect'}
           </Button>
         </Show>
+        <Show when={props.state?.connected && capabilities()?.remoteControl}>
+          <Button
+            type="button"
+            variant="outlined"
+            class="text-on-surface-variant hover:border-primary/50 hover:text-on-surface"
+            disabled={ui.refreshingCastRegistration}
+            onClick={props.onRefreshCastRegistration}
+            leadingIcon={<Cast class="h-4.5 w-4.5" />}
+          >
+            {ui.refreshingCastRegistration ? 'Registering...' : 'Refresh cast registration'}
+          </Button>
+          <Button
+            type="button"
+            variant="outlined"
+            class="text-on-surface-variant hover:border-primary/50 hover:text-on-surface"
+            disabled={ui.regeneratingDeviceId}
+            onClick={props.onRegenerateDeviceId}
+            leadingIcon={<Fingerprint class="h-4.5 w-4.5" />}
+          >
+            {ui.regeneratingDeviceId ? 'Regenerating...' : 'Regenerate device ID'}
+          </Button>
+        </Show>
         <Button
           type="button"
           variant="icon"
@@ -125,7 +159,8 @@ export default function ConnectionCard(props: ConnectionCardProps) {
       </div>
       <p class="text-on-surface-variant/80 mt-4 text-[12px] leading-[16px]">
         Disconnect ends the active media server connection but keeps saved services available for
-        Reconnect.
+        Reconnect. Refresh cast registration when this device is missing from the cast menu, and
+        regenerate the device ID when another JellyPilot copied this one's settings.
       </p>
     </SectionCard>
   );
@@ -0,0 +1,128 @@
+import { Lock, LockOpen } from 'lucide-solid';
+import { Show, createSignal } from 'solid-js';
+
+import type { SettingsLockStatus } from '../../bindings';
+import { Button, SectionCard, TextField } from '../ui';
+
+interface SettingsLockCardProps {
+  status: SettingsLockStatus | null;
+  busy: boolean;
+  /** Resolves true when the PIN was accepted. */
+  onUnlock: (pin: string) => Promise<boolean>;
+  onLock: () => void;
+  /** Resolves true when the PIN was saved; `null` removes the lock. */
+  onSetPin: (pin: string | null) => Promise<boolean>;
+}
+
+export default function SettingsLockCard(props: SettingsLockCardProps) {
+  const [pin, setPin] = createSignal('');
+  const [newPin, setNewPin] = createSignal('');
+
+  const locked = () => Boolean(props.status?.pinSet && !props.status.unlocked);
+
+  const handleUnlock = async (event: SubmitEvent) => {
+    event.preventDefault();
+    if (await props.onUnlock(pin())) {
+      setPin('');
+    }
+  };
+
+  const handleSetPin = async (event: SubmitEvent) => {
+    event.preventDefault();
+    if (await props.onSetPin(newPin())) {
+      setNewPin('');
+    }
+  };
+
+  return (
+    <Show when={props.status}>
+      <SectionCard
+        icon={
+          locked() ? (
+            <Lock class="text-primary h-5 w-5" />
+          ) : (
+            <LockOpen class="text-primary h-5 w-5" />
+          )
+        }
+        title="Settings lock"
+        trailing={
+          <Show when={props.status?.pinSet && !locked()}>
+            <Button
+              type="button"
+              variant="text"
+              class="min-w-0 px-3"
+              onClick={props.onLock}
+              disabled={props.busy}
+            >
+              Lock now
+            </Button>
+          </Show>
+        }
+      >
+        <Show
+          when={!locked()}
+          fallback={
+            <form class="space-y-4" onSubmit={handleUnlock}>
+              <p class="text-on-surface-variant/80 text-[12px] leading-4">
+                Settings are locked. Enter the PIN to change them for the next few minutes.
+              </p>
+              <TextField
+                name="settingsPin"
+                label="PIN"
+                type="password"
+                value={pin()}
+                onInput={setPin}
+                disabled={props.busy}
+              />
+              <Button
+                type="submit"
+                variant="primary"
+                class="w-full"
+                disabled={props.busy || pin().length === 0}
+              >
+                Unlock
+              </Button>
+            </form>
+          }
+        >
+          <form class="space-y-4" onSubmit={handleSetPin}>
+            <p class="text-on-surface-variant/80 text-[12px] leading-4">
+              {props.status?.pinSet
+                ? 'A PIN protects settings, sign out and saved preferences.'
+                : 'Set a PIN of 4 to 12 digits to keep others out of settings.'}
+            </p>
+            <TextField
+              name="settingsNewPin"
+              label={props.status?.pinSet ? 'New PIN' : 'PIN'}
+              type="password"
+              value={newPin()}
+              onInput={setNewPin}
+              disabled={props.busy}
+            />
+            <div class="flex gap-3">
+              <Button
+                type="submit"
+                variant="primary"
+                class="flex-1"
+                disabled={props.busy || newPin().length === 0}
+              >
+                {props.status?.pinSet ? 'Change PIN' : 'Set PIN'}
+              </Button>
+              <Show when={props.status?.pinSet}>
+                <Button
+                  type="button"
+                  variant="outlined"
+                  class="flex-1"
+                  onClick={() => void props.onSetPin(null)}
+                  disabled={props.busy}
+                >
+                  Remove PIN
+                </Button>
+              </Show>
+            </div>
+          </form>
+        </Show>
+      </SectionCard>
+    </Show>
+  );
+}
@@ -1,17 +1,71 @@
 import { Field as ArkField } from '@ark-ui/solid/field';
 import { Keyboard } from 'lucide-solid';
-import { Show } from 'solid-js';
+import { For, Show } from 'solid-js';
 
 import { FieldControl, SectionCard } from '../ui';
-import type { OperationsConsoleForm } from './types';
+import type { OperationsConsoleForm, ShortcutKeyField } from './types';
 
 interface ShortcutKeysCardProps {
   form: OperationsConsoleForm;
   showIntroSkipKey: boolean;
-  onSaveTextSetting: (
-    field: 'keybindNext' | 'keybindPrev' | 'keybindIntroSkip',
-    value: string,
-  ) => void;
+  onSaveTextSetting: (field: ShortcutKeyField, value: string) => void;
+}
+
+interface PlayerShortcut {
+  field: ShortcutKeyField;
+  label: string;
+  placeholder: string;
+}
+
+const PLAYER_SHORTCUTS: PlayerShortcut[] = [
+  { field: 'keybindToggleWatched', label: 'Watched toggle key', placeholder: 'Alt+w' },
+  { field: 'keybindToggleFavorite', label: 'Favorite toggle key', placeholder: 'Alt+f' },
+  { field: 'keybindSpeedUp', label: 'Speed up key', placeholder: 'Alt+]' },
+  { field: 'keybindSpeedDown', label: 'Slow down key', placeholder: 'Alt+[' },
+  { field: 'keybindStats', label: 'Stats overlay key', placeholder: 'Alt+i' },
+  { field: 'keybindStopIdle', label: 'Stop and go idle key', placeholder: 'Alt+q' },
+  { field: 'keybindSecondarySubtitle', label: 'Secondary subtitle key', placeholder: 'Alt+j' },
+  { field: 'keybindAbLoop', label: 'A-B loop key', placeholder: 'Alt+l' },
+  { field: 'keybindScreenshot', label: 'Screenshot key', placeholder: 'Alt+s' },
+];
+
+function ShortcutKeyInput(
+  props: PlayerShortcut & Pick<ShortcutKeysCardProps, 'form' | 'onSaveTextSetting'>,
+) {
+  return (
+    <props.form.Field
+      name={props.field}
+      validators={{
+        onBlur: ({ value }) => (!value.trim() ? 'Keybinding is required' : undefined),
+      }}
+    >
+      {(field) => (
+        <ArkField.Root class="block" invalid={field().state.meta.errors.length > 0}>
+          <ArkField.Label class="text-on-surface-variant mb-1.5 block text-[12px] leading-[16px] font-bold tracking-[0.05em] uppercase">
+            {props.label}
+          </ArkField.Label>
+          <ArkField.Input
+            asChild={(fieldProps) => (
+              <FieldControl
+                {...fieldProps()}
+                variant="filled"
+                name={field().name}
+                type="text"
+                value={field().state.value}
+                onInput={(event) => field().handleChange(event.currentTarget.value)}
+                onBlur={(event) => {
+                  field().handleBlur();
+                  props.onSaveTextSetting(props.field, event.currentTarget.value);
+                }}
+                class="text-secondary w-full font-mono font-semibold"
+                placeholder={props.placeholder}
+              />
+            )}
+          />
+        </ArkField.Root>
+      )}
+    </props.form.Field>
+  );
 }
 
 export default function ShortcutKeysCard(props: ShortcutKeysCardProps) {
@@ -23,8 +77,8 @@ export default function ShortcutKeysCard(props: ShortcutKeysCardProps) {
       <div class="space-y-4">
         <p class="text-on-surface-variant/80 text-[12px] leading-[16px]">
           {props.showIntroSkipKey
-            ? 'MPV input bindings for episode navigation and manual intro skipping.'
-            : 'MPV input bindings for episode navigation.'}
+            ? 'MPV input bindings for episode navigation, intro skipping and playback controls.'
+            : 'MPV input bindings for episode navigation and playback controls.'}
         </p>
 
         <props.form.Field
@@ -126,7 +180,25 @@ export default function ShortcutKeysCard(props: ShortcutKeysCardProps) {
               </ArkField.Root>
             )}
           </props.form.Field>
+
+          <ShortcutKeyInput
+            form={props.form}
+            field="keybindToggleIntroSkip"
+            label="Automatic intro skip toggle key"
+            placeholder="Alt+g"
+            onSaveTextSetting={props.onSaveTextSetting}
+          />
         </Show>
+
+        <For each={PLAYER_SHORTCUTS}>
+          {(shortcut) => (
+            <ShortcutKeyInput
+              form={props.form}
+              {...shortcut}
+              onSaveTextSetting={props.onSaveTextSetting}
+            />
+          )}
+        </For>
       </div>
     </SectionCard>
   );
@@ -17,6 +17,8 @@ export interface PlayerBridgeSaveStatus {
 export interface OperationsConsoleState {
   disconnecting: boolean;
   reconnecting: boolean;
+  refreshingCastRegistration: boolean;
+  regeneratingDeviceId: boolean;
   signingOut: boolean;
   confirmSignOut: boolean;
   detectingMpv: boolean;
@@ -61,6 +63,12 @@ export interface OperationsConsoleActions {
   beginDisconnect(): void;
   finishDisconnect(): void;
 
+  beginCastRegistrationRefresh(): void;
+  finishCastRegistrationRefresh(): void;
+
+  beginDeviceIdRegeneration(): void;
+  finishDeviceIdRegeneration(): void;
+
   beginSignOut(): void;
   finishSignOut(): void;
   setSignOutDialogOpen(open: boolean): void;
@@ -102,6 +110,8 @@ export function getInitialState(): OperationsConsoleState {
     introSkipperSaving: false,
     playerBridgeSaveStatus: null,
     reconnecting: false,
+    refreshingCastRegistration: false,
+    regeneratingDeviceId: false,
     selectedSubtitleLanguages: [],
     signingOut: false,
     subtitleLanguageInput: '',
@@ -110,6 +120,14 @@ export function getInitialState(): OperationsConsoleState {
 
 function createActions(set: SetStoreFunction<OperationsConsoleState>): OperationsConsoleActions {
   return {
+    beginCastRegistrationRefresh() {
+      set('refreshingCastRegistration', true);
+    },
+
+    beginDeviceIdRegeneration() {
+      set('regeneratingDeviceId', true);
+    },
+
     beginDisconnect() {
       set('disconnecting', true);
     },
@@ -142,6 +160,14 @@ function createActions(set: SetStoreFunction<OperationsConsoleState>): Operation
       set('introSkipperError', message);
     },
 
+    finishCastRegistrationRefresh() {
+      set('refreshingCastRegistration', false);
+    },
+
+    finishDeviceIdRegeneration() {
+      set('regeneratingDeviceId', false);
+    },
+
     finishDisconnect() {
       set('disconnecting', false);
     },
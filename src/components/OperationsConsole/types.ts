@@ -2,6 +2,22 @@ import { createForm } from '@tanstack/solid-form';
 
 import type { IntroSkipperMode } from '../../bindings';
 
+/** Config fields holding the key of a JellyPilot MPV shortcut. */
+export type ShortcutKeyField =
+  | 'keybindNext'
+  | 'keybindPrev'
+  | 'keybindIntroSkip'
+  | 'keybindToggleIntroSkip'
+  | 'keybindToggleWatched'
+  | 'keybindToggleFavorite'
+  | 'keybindSpeedUp'
+  | 'keybindSpeedDown'
+  | 'keybindStats'
+  | 'keybindStopIdle'
+  | 'keybindSecondarySubtitle'
+  | 'keybindAbLoop'
+  | 'keybindScreenshot';
+
 export interface FormDefaultValues {
   deviceName: string;
   mpvPath: string;
@@ -9,6 +25,16 @@ export interface FormDefaultValues {
   keybindNext: string;
   keybindPrev: string;
   keybindIntroSkip: string;
+  keybindToggleIntroSkip: string;
+  keybindToggleWatched: string;
+  keybindToggleFavorite: string;
+  keybindSpeedUp: string;
+  keybindSpeedDown: string;
+  keybindStats: string;
+  keybindStopIdle: string;
+  keybindSecondarySubtitle: string;
+  keybindAbLoop: string;
+  keybindScreenshot: string;
   introSkipperMode: IntroSkipperMode;
 }
 
@@ -22,9 +48,19 @@ function inferFormType() {
     defaultValues: {
       deviceName: '',
       introSkipperMode: 'automatic' as IntroSkipperMode,
+      keybindAbLoop: '',
       keybindIntroSkip: '',
       keybindNext: '',
       keybindPrev: '',
+      keybindScreenshot: '',
+      keybindSecondarySubtitle: '',
+      keybindSpeedDown: '',
+      keybindSpeedUp: '',
+      keybindStats: '',
+      keybindStopIdle: '',
+      keybindToggleFavorite: '',
+      keybindToggleIntroSkip: '',
+      keybindToggleWatched: '',
       mpvArgs: '',
       mpvPath: '',
     },
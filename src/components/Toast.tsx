@@ -1,16 +1,23 @@
 import { Match } from 'effect';
 import { Check, Info, TriangleAlert, X } from 'lucide-solid';
-import { onCleanup, onMount } from 'solid-js';
+import { onCleanup, onMount, Show } from 'solid-js';
 import type { JSX } from 'solid-js';
 
 import type { NotificationLevel } from '../bindings';
 
 export type { NotificationLevel };
 
+/** Button shown next to the message, e.g. to retry what failed. */
+export interface ToastAction {
+  label: string;
+  run: () => void;
+}
+
 interface ToastProps {
   id: string;
   level: NotificationLevel;
   message: string;
+  action?: ToastAction;
   exiting: boolean;
   onDismiss: (id: string) => void;
 }
@@ -73,6 +80,20 @@ export default function Toast(props: ToastProps) {
       <div class="text-on-surface-variant ml-3 flex-1 text-[14px] leading-[20px] font-normal break-words">
         {props.message}
       </div>
+      <Show when={props.action}>
+        {(action) => (
+          <button
+            type="button"
+            class="hover:bg-on-surface/10 ml-2 rounded-lg px-3 py-1.5 text-[13px] font-bold transition-colors"
+            onClick={() => {
+              action().run();
+              props.onDismiss(props.id);
+            }}
+          >
+            {action().label}
+          </button>
+        )}
+      </Show>
       <button
         type="button"
         class="hover:bg-on-surface/10 -mx-1.5 -my-1.5 ml-auto inline-flex h-10 w-10 items-center justify-center rounded-full p-1.5 transition-colors"
@@ -1,16 +1,21 @@
 import { listen } from '@tauri-apps/api/event';
 import type { UnlistenFn } from '@tauri-apps/api/event';
+import { Effect, Exit } from 'effect';
 import { createContext, createSignal, For, onCleanup, onMount, useContext } from 'solid-js';
 import type { ParentProps } from 'solid-js';
 
+import type { PlaybackError } from '../bindings';
+import { commandFailureMessage } from '../effects/commands';
+import { retryPlayback } from '../effects/nowPlaying';
 import Toast from './Toast';
-import type { NotificationLevel } from './Toast';
+import type { NotificationLevel, ToastAction } from './Toast';
 
 const TOAST_EXIT_MS = 200;
 export interface ToastMessage {
   id: string;
   level: NotificationLevel;
   message: string;
+  action?: ToastAction;
 }
 
 /** Payload from backend AppNotification event */
@@ -20,7 +25,7 @@ interface AppNotificationPayload {
 }
 
 interface ToastContextValue {
-  showToast: (level: NotificationLevel, message: string) => void;
+  showToast: (level: NotificationLevel, message: string, action?: ToastAction) => void;
   removeToast: (id: string) => void;
 }
 
@@ -29,12 +34,20 @@ const ToastContext = createContext<ToastContextValue>();
 export function ToastProvider(props: ParentProps) {
   const [toasts, setToasts] = createSignal<ToastMessage[]>([]);
   let unlisten: UnlistenFn | undefined;
+  let unlistenPlaybackError: UnlistenFn | undefined;
   const [exitingToastIds, setExitingToastIds] = createSignal<Set<string>>(new Set());
   const dismissTimers = new Map<string, ReturnType<typeof setTimeout>>();
 
-  const showToast = (level: NotificationLevel, message: string) => {
+  const showToast = (level: NotificationLevel, message: string, action?: ToastAction) => {
     const id = Math.random().toString(36).slice(2, 9);
-    setToasts((prev) => [...prev, { id, level, message }]);
+    setToasts((prev) => [...prev, { action, id, level, message }]);
+  };
+
+  const retry = async () => {
+    const exit = await Effect.runPromiseExit(retryPlayback());
+    if (Exit.isFailure(exit)) {
+      showToast('error', commandFailureMessage(exit.cause, 'Could not retry playback'));
+    }
   };
 
   const removeToast = (id: string) => {
@@ -68,8 +81,24 @@ export function ToastProvider(props: ParentProps) {
     }
   });
 
+  // Playback failures carry a message telling the user how to fix them
+  onMount(async () => {
+    try {
+      unlistenPlaybackError = await listen<PlaybackError>('playback-error', (event) => {
+        const action =
+          event.payload.stage === 'ended-early'
+            ? { label: 'Retry', run: () => void retry() }
+            : undefined;
+        showToast('error', event.payload.message, action);
+      });
+    } catch (error) {
+      console.error('Failed to listen for playback errors:', error);
+    }
+  });
+
   onCleanup(() => {
     unlisten?.();
+    unlistenPlaybackError?.();
     dismissTimers.forEach((timer) => clearTimeout(timer));
     dismissTimers.clear();
   });
@@ -84,6 +113,7 @@ export function ToastProvider(props: ParentProps) {
               id={toast.id}
               level={toast.level}
               message={toast.message}
+              action={toast.action}
               exiting={exitingToastIds().has(toast.id)}
               onDismiss={removeToast}
             />
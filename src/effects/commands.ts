@@ -22,6 +22,7 @@ export function runTauriCommand<T>(
       if (result.status === 'error') {
         throw new CommandErrorTag({
           code: result.error.code,
+          fieldErrors: result.error.fieldErrors ?? undefined,
           message: result.error.message,
         });
       }
@@ -1,5 +1,5 @@
-import { commands } from '@bindings';
-import type { ConnectionState, Credentials } from '@bindings';
+import { commands, events } from '@bindings';
+import type { ConnectionEvent, ConnectionState, Credentials, SessionExpired } from '@bindings';
 import { Effect } from 'effect';
 
 import { runTauriCommand, runTauriCommandRaw } from './commands';
@@ -18,6 +18,26 @@ export function disconnectJellyfin(): Effect.Effect<void, CommandError> {
   return runTauriCommand(() => commands.serverDisconnect()).pipe(Effect.asVoid);
 }
 
+export function refreshCastRegistration(): Effect.Effect<void, CommandError> {
+  return runTauriCommand(() => commands.jellyfinRefreshCastRegistration()).pipe(Effect.asVoid);
+}
+
+export function regenerateDeviceId(): Effect.Effect<void, CommandError> {
+  return runTauriCommand(() => commands.jellyfinRegenerateDeviceId()).pipe(Effect.asVoid);
+}
+
 export function clearJellyfinSession(): Effect.Effect<void, CommandError> {
   return runTauriCommand(() => commands.serverClearSession()).pipe(Effect.asVoid);
 }
+
+export function listenConnectionEvents(
+  onEvent: (event: ConnectionEvent) => void,
+): Promise<() => void> {
+  return events.connectionEvent.listen((event) => onEvent(event.payload));
+}
+
+export function listenSessionExpired(
+  onExpired: (event: SessionExpired) => void,
+): Promise<() => void> {
+  return events.sessionExpired.listen((event) => onExpired(event.payload));
+}
@@ -1,6 +1,6 @@
 import { Data } from 'effect';
 
-import type { CommandErrorCode } from '../bindings';
+import type { CommandErrorCode, ConfigFieldError } from '../bindings';
 
 export class InvalidServerUrl extends Data.TaggedError('InvalidServerUrl')<{
   readonly message: string;
@@ -14,4 +14,5 @@ export class StorageParseError extends Data.TaggedError('StorageParseError')<{
 export class CommandError extends Data.TaggedError('CommandError')<{
   readonly code?: CommandErrorCode;
   readonly message: string;
+  readonly fieldErrors?: readonly ConfigFieldError[];
 }> {}
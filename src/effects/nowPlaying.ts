@@ -112,6 +112,10 @@ export function playNextEpisode(): NowPlayingEffect<void> {
   return runTauriCommand(() => commands.jellyfinPlayNextEpisode()).pipe(Effect.asVoid);
 }
 
+export function retryPlayback(): NowPlayingEffect<void> {
+  return runTauriCommand(() => commands.playbackRetry()).pipe(Effect.asVoid);
+}
+
 export function startMpv(): NowPlayingEffect<void> {
   return runTauriCommand(() => commands.mpvStart()).pipe(Effect.asVoid);
 }
@@ -120,6 +124,10 @@ export function toggleMute(): NowPlayingEffect<void> {
   return runTauriCommand(() => commands.mpvToggleMute()).pipe(Effect.asVoid);
 }
 
+export function setUserRating(likes: boolean): NowPlayingEffect<void> {
+  return runTauriCommand(() => commands.playbackSetUserRating(likes)).pipe(Effect.asVoid);
+}
+
 export function listenNowPlayingChanged(
   onState: (state: NowPlayingState) => void,
 ): Promise<() => void> {
@@ -54,6 +54,7 @@ export function isLibrarySessionKeyConnected(sessionKey: LibrarySessionKey) {
 export const queryKeys = {
   appVersion: ['app', 'version'] as const,
   appConfig: ['config', 'app'] as const,
+  settingsLockStatus: ['config', 'settingsLock'] as const,
   connectionState: ['connection', 'state'] as const,
   savedServiceProfiles: ['connection', 'profiles'] as const,
   nowPlayingState: ['nowPlaying', 'state'] as const,
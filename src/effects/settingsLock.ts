@@ -0,0 +1,23 @@
+import { commands } from '@bindings';
+import type { SettingsLockStatus } from '@bindings';
+import { Effect } from 'effect';
+
+import { runTauriCommand, runTauriCommandRaw } from './commands';
+import type { CommandError } from './errors';
+
+export function fetchSettingsLockStatus(): Effect.Effect<SettingsLockStatus, CommandError> {
+  return runTauriCommandRaw(() => commands.settingsLockStatus());
+}
+
+export function unlockSettings(pin: string): Effect.Effect<void, CommandError> {
+  return runTauriCommand(() => commands.settingsUnlock(pin)).pipe(Effect.asVoid);
+}
+
+export function lockSettings(): Effect.Effect<void, CommandError> {
+  return runTauriCommandRaw(() => commands.settingsLock()).pipe(Effect.asVoid);
+}
+
+/** Set a new settings PIN, or remove the lock with `null`. */
+export function setSettingsPin(pin: string | null): Effect.Effect<void, CommandError> {
+  return runTauriCommand(() => commands.settingsSetPin(pin)).pipe(Effect.asVoid);
+}
@@ -4,7 +4,19 @@ import { canAccessConsole, checkAuthWithRestore } from './sessionAccess';
 
 export const AUTHENTICATED_HOME_ROUTE = '/library';
 
-export async function redirectLoggedInUsersToLibrary() {
+export interface LoginSearch {
+  /** Set when the server rejected the session, which has to be signed in again. */
+  expired?: boolean;
+}
+
+export function validateLoginSearch(search: Record<string, unknown>): LoginSearch {
+  return search.expired === true || search.expired === 'true' ? { expired: true } : {};
+}
+
+export async function redirectLoggedInUsersToLibrary({ search }: { search?: LoginSearch } = {}) {
+  if (search?.expired) {
+    return;
+  }
   if (await canAccessConsole()) {
     throw redirect({ to: AUTHENTICATED_HOME_ROUTE });
   }
@@ -9,6 +9,7 @@ import { commands, events } from '../src/bindings';
 import type {
   AppConfig,
   NowPlayingState,
+  SessionExpired,
   VideoHome,
   VideoItemDetail,
   VideoLibraryPage,
@@ -1610,6 +1611,33 @@ test('Close Settings and standard dismissal close the Settings modal back to the
   cleanup();
 });
 
+test('an expired session sends the user to sign in again', async () => {
+  mockShellCommands();
+  let expire: ((event: { payload: SessionExpired }) => void) | undefined;
+  rstest.spyOn(events.sessionExpired, 'listen').mockImplementation((handler) => {
+    expire = handler as typeof expire;
+    return Promise.resolve(() => {});
+  });
+
+  const cleanup = renderShell('/library');
+  await screen.findByRole('navigation', { name: 'Library navigation' });
+  await waitFor(() => expect(expire).toBeDefined());
+
+  expire?.({
+    payload: {
+      detail: 'HTTP 401',
+      message: 'The server no longer accepts this session. Sign in again.',
+    },
+  });
+
+  expect(await screen.findByRole('tab', { name: 'Quick Connect' })).toBeVisible();
+  expect(
+    screen.getByText('The server no longer accepts this session. Sign in again.'),
+  ).toBeVisible();
+
+  cleanup();
+});
+
 test('Settings modal keeps Disconnect and Sign out as distinct session controls', async () => {
   mockShellCommands();
   localStorage.setItem(
@@ -95,6 +95,9 @@ function mockCommon(
   });
   rstest.spyOn(commands, 'mpvIsConnected').mockResolvedValue(false);
   rstest.spyOn(commands, 'configGet').mockResolvedValue(appConfig);
+  rstest
+    .spyOn(commands, 'settingsLockStatus')
+    .mockResolvedValue({ pinSet: false, unlocked: true });
 }
 
 function renderConsole(
@@ -627,6 +630,24 @@ test('disconnect rejected commands stay on console and unlock the action', async
   cleanup();
 });
 
+test('refresh cast registration re-registers the device and reports the result', async () => {
+  const refresh = rstest
+    .spyOn(commands, 'jellyfinRefreshCastRegistration')
+    .mockResolvedValue({ data: null, status: 'ok' });
+  const cleanup = renderConsole();
+
+  const button = await screen.findByRole('button', { name: 'Refresh cast registration' });
+  fireEvent.click(button);
+
+  await waitFor(() => expect(refresh).toHaveBeenCalledTimes(1));
+  await waitFor(() =>
+    expect(screen.getByText('This device is registered as a cast target')).toBeVisible(),
+  );
+  expect(screen.getByRole('button', { name: 'Refresh cast registration' })).not.toBeDisabled();
+
+  cleanup();
+});
+
 test('reconnect activates the active saved service profile', async () => {
   const activate = rstest.spyOn(commands, 'serverProfilesActivate').mockResolvedValue({
     data: validSavedProfiles,
@@ -912,6 +933,56 @@ test('settings and session actions keep shared visual semantics', async () => {
   cleanup();
 });
 
+test('locked settings ask for the PIN and unlock with it', async () => {
+  mockCommon();
+  const lockStatus = rstest
+    .spyOn(commands, 'settingsLockStatus')
+    .mockResolvedValue({ pinSet: true, unlocked: false });
+  const unlock = rstest.spyOn(commands, 'settingsUnlock').mockImplementation(async () => {
+    lockStatus.mockResolvedValue({ pinSet: true, unlocked: true });
+    return { data: null, status: 'ok' as const };
+  });
+  const root = document.createElement('div');
+  document.body.append(root);
+  const dispose = render(
+    () => (
+      <TestQueryProvider>
+        <ToastProvider>
+          <OperationsConsole onSignedOut={() => {}} />
+        </ToastProvider>
+      </TestQueryProvider>
+    ),
+    root,
+  );
+
+  await screen.findByRole('heading', { name: 'Settings lock' });
+  fireEvent.input(await screen.findByLabelText('PIN'), { target: { value: '2468' } });
+  fireEvent.click(screen.getByRole('button', { name: 'Unlock' }));
+
+  await waitFor(() => expect(unlock).toHaveBeenCalledWith('2468'));
+  expect(await screen.findByRole('button', { name: 'Lock now' })).toBeVisible();
+  expect(screen.getByRole('button', { name: 'Change PIN' })).toBeVisible();
+
+  dispose();
+  root.remove();
+});
+
+test('settings without a PIN offer to set one', async () => {
+  const setPin = rstest.spyOn(commands, 'settingsSetPin').mockResolvedValue({
+    data: null,
+    status: 'ok',
+  });
+  const cleanup = renderConsole();
+
+  await screen.findByRole('heading', { name: 'Settings lock' });
+  fireEvent.input(screen.getByLabelText('PIN'), { target: { value: '1357' } });
+  fireEvent.click(screen.getByRole('button', { name: 'Set PIN' }));
+
+  await waitFor(() => expect(setPin).toHaveBeenCalledWith('1357'));
+
+  cleanup();
+});
+
 test('library image disk cache toggle persists in config', async () => {
   const configSet = rstest.spyOn(commands, 'configSet').mockResolvedValue({
     data: null,
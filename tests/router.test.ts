@@ -45,6 +45,13 @@ test('login guard redirects authenticated users to Library', async () => {
   await expectRedirect(redirectLoggedInUsersToLibrary, '/library');
 });
 
+test('login guard lets users whose session expired sign in again', async () => {
+  const connected = rstest.spyOn(commands, 'serverIsConnected').mockResolvedValue(true);
+
+  await redirectLoggedInUsersToLibrary({ search: { expired: true } });
+  expect(connected).not.toHaveBeenCalled();
+});
+
 test('root guard restores the active saved service profile into Library', async () => {
   rstest.spyOn(commands, 'serverIsConnected').mockResolvedValue(false);
   rstest.spyOn(commands, 'serverProfilesGet').mockResolvedValue({